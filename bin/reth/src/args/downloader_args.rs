@@ -0,0 +1,123 @@
+//! clap [Args](clap::Args) for tuning the headers and bodies downloaders
+
+use clap::Args;
+use reth_staged_sync::config::StageConfig;
+use std::time::Duration;
+
+/// Parameters for tuning the headers and bodies downloaders via CLI.
+///
+/// Each argument overrides the corresponding value of the config file.
+#[derive(Debug, Args, PartialEq, Default)]
+#[command(next_help_heading = "Downloader")]
+pub struct DownloaderArgs {
+    /// The timeout of a single headers request in seconds. default: 30
+    #[arg(long = "downloader.headers.request-timeout", value_name = "SECONDS")]
+    pub headers_request_timeout: Option<u64>,
+
+    /// How many times a failed headers request is retried. default: 10
+    #[arg(long = "downloader.headers.max-retries")]
+    pub headers_max_retries: Option<usize>,
+
+    /// The maximum number of headers responses to buffer. default: 750
+    #[arg(long = "downloader.headers.max-buffered-responses")]
+    pub headers_max_buffered_responses: Option<usize>,
+
+    /// The maximum number of concurrent headers requests. default: 150
+    #[arg(long = "downloader.headers.max-concurrent-requests")]
+    pub headers_max_concurrent_requests: Option<usize>,
+
+    /// The timeout of a single bodies request in seconds. default: 30
+    #[arg(long = "downloader.bodies.request-timeout", value_name = "SECONDS")]
+    pub bodies_request_timeout: Option<u64>,
+
+    /// How many times a failed bodies request is retried before it is restarted with high
+    /// priority. default: 10
+    #[arg(long = "downloader.bodies.max-retries")]
+    pub bodies_max_retries: Option<usize>,
+
+    /// The maximum number of bodies responses to buffer. default: 1000
+    #[arg(long = "downloader.bodies.max-buffered-responses")]
+    pub bodies_max_buffered_responses: Option<usize>,
+
+    /// The maximum number of concurrent bodies requests. default: 100
+    #[arg(long = "downloader.bodies.max-concurrent-requests")]
+    pub bodies_max_concurrent_requests: Option<usize>,
+}
+
+impl DownloaderArgs {
+    /// Applies the downloader settings to the [StageConfig], overriding the values of the config
+    /// file.
+    pub fn apply(&self, stages: &mut StageConfig) {
+        let headers = &mut stages.headers;
+        if let Some(secs) = self.headers_request_timeout {
+            headers.downloader_request_timeout = Duration::from_secs(secs);
+        }
+        if let Some(max_retries) = self.headers_max_retries {
+            headers.downloader_max_retries = max_retries;
+        }
+        if let Some(max_buffered_responses) = self.headers_max_buffered_responses {
+            headers.downloader_max_buffered_responses = max_buffered_responses;
+        }
+        if let Some(max_concurrent_requests) = self.headers_max_concurrent_requests {
+            headers.downloader_max_concurrent_requests = max_concurrent_requests;
+        }
+
+        let bodies = &mut stages.bodies;
+        if let Some(secs) = self.bodies_request_timeout {
+            bodies.downloader_request_timeout = Duration::from_secs(secs);
+        }
+        if let Some(max_retries) = self.bodies_max_retries {
+            bodies.downloader_max_retries = max_retries;
+        }
+        if let Some(max_buffered_responses) = self.bodies_max_buffered_responses {
+            bodies.downloader_max_buffered_responses = max_buffered_responses;
+        }
+        if let Some(max_concurrent_requests) = self.bodies_max_concurrent_requests {
+            bodies.downloader_max_concurrent_requests = max_concurrent_requests;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn parse_downloader_args() {
+        let args = CommandParser::<DownloaderArgs>::parse_from([
+            "reth",
+            "--downloader.headers.request-timeout",
+            "60",
+            "--downloader.headers.max-concurrent-requests",
+            "10",
+            "--downloader.bodies.max-retries",
+            "3",
+            "--downloader.bodies.max-buffered-responses",
+            "100",
+        ])
+        .args;
+
+        let mut stages = StageConfig::default();
+        args.apply(&mut stages);
+        assert_eq!(stages.headers.downloader_request_timeout, Duration::from_secs(60));
+        assert_eq!(stages.headers.downloader_max_concurrent_requests, 10);
+        assert_eq!(stages.bodies.downloader_max_retries, 3);
+        assert_eq!(stages.bodies.downloader_max_buffered_responses, 100);
+
+        // unset arguments keep the config values
+        let defaults = StageConfig::default();
+        assert_eq!(stages.headers.downloader_max_retries, defaults.headers.downloader_max_retries);
+        assert_eq!(
+            stages.bodies.downloader_request_timeout,
+            defaults.bodies.downloader_request_timeout
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! clap [Args](clap::Args) for overriding fork activations of the chain spec
+
+use clap::Args;
+use reth_primitives::{ChainSpec, ForkCondition, Hardfork, U256};
+use std::sync::Arc;
+
+/// Parameters for shifting the activation of forks, e.g. for shadow forks
+#[derive(Debug, Args, PartialEq, Default)]
+#[command(next_help_heading = "Fork overrides")]
+pub struct ForkOverrideArgs {
+    /// Overrides the block at which Homestead is activated.
+    #[arg(long = "override.homestead", value_name = "BLOCK")]
+    pub homestead: Option<u64>,
+
+    /// Overrides the block at which the DAO fork is activated.
+    #[arg(long = "override.dao", value_name = "BLOCK")]
+    pub dao: Option<u64>,
+
+    /// Overrides the block at which Tangerine is activated.
+    #[arg(long = "override.tangerine", value_name = "BLOCK")]
+    pub tangerine: Option<u64>,
+
+    /// Overrides the block at which Spurious Dragon is activated.
+    #[arg(long = "override.spuriousdragon", value_name = "BLOCK")]
+    pub spurious_dragon: Option<u64>,
+
+    /// Overrides the block at which Byzantium is activated.
+    #[arg(long = "override.byzantium", value_name = "BLOCK")]
+    pub byzantium: Option<u64>,
+
+    /// Overrides the block at which Constantinople is activated.
+    #[arg(long = "override.constantinople", value_name = "BLOCK")]
+    pub constantinople: Option<u64>,
+
+    /// Overrides the block at which Petersburg is activated.
+    #[arg(long = "override.petersburg", value_name = "BLOCK")]
+    pub petersburg: Option<u64>,
+
+    /// Overrides the block at which Istanbul is activated.
+    #[arg(long = "override.istanbul", value_name = "BLOCK")]
+    pub istanbul: Option<u64>,
+
+    /// Overrides the block at which Muir Glacier is activated.
+    #[arg(long = "override.muirglacier", value_name = "BLOCK")]
+    pub muir_glacier: Option<u64>,
+
+    /// Overrides the block at which Berlin is activated.
+    #[arg(long = "override.berlin", value_name = "BLOCK")]
+    pub berlin: Option<u64>,
+
+    /// Overrides the block at which London is activated.
+    #[arg(long = "override.london", value_name = "BLOCK")]
+    pub london: Option<u64>,
+
+    /// Overrides the block at which Arrow Glacier is activated.
+    #[arg(long = "override.arrowglacier", value_name = "BLOCK")]
+    pub arrow_glacier: Option<u64>,
+
+    /// Overrides the block at which Gray Glacier is activated.
+    #[arg(long = "override.grayglacier", value_name = "BLOCK")]
+    pub gray_glacier: Option<u64>,
+
+    /// Overrides the timestamp at which Shanghai is activated.
+    #[arg(long = "override.shanghai", value_name = "TIMESTAMP")]
+    pub shanghai: Option<u64>,
+
+    /// Overrides the terminal total difficulty at which Paris is activated.
+    #[arg(long = "override.terminaltotaldifficulty", value_name = "TTD")]
+    pub terminal_total_difficulty: Option<U256>,
+}
+
+impl ForkOverrideArgs {
+    /// Returns the overridden forks and their new activation conditions for the given chain spec.
+    pub fn overrides(&self, chain: &ChainSpec) -> Vec<(Hardfork, ForkCondition)> {
+        let mut overrides = [
+            (Hardfork::Homestead, self.homestead),
+            (Hardfork::Dao, self.dao),
+            (Hardfork::Tangerine, self.tangerine),
+            (Hardfork::SpuriousDragon, self.spurious_dragon),
+            (Hardfork::Byzantium, self.byzantium),
+            (Hardfork::Constantinople, self.constantinople),
+            (Hardfork::Petersburg, self.petersburg),
+            (Hardfork::Istanbul, self.istanbul),
+            (Hardfork::MuirGlacier, self.muir_glacier),
+            (Hardfork::Berlin, self.berlin),
+            (Hardfork::London, self.london),
+            (Hardfork::ArrowGlacier, self.arrow_glacier),
+            (Hardfork::GrayGlacier, self.gray_glacier),
+        ]
+        .into_iter()
+        .filter_map(|(fork, block)| Some((fork, ForkCondition::Block(block?))))
+        .collect::<Vec<_>>();
+        if let Some(total_difficulty) = self.terminal_total_difficulty {
+            // keep the advertised fork block, if any
+            let fork_block = match chain.fork(Hardfork::Paris) {
+                ForkCondition::TTD { fork_block, .. } => fork_block,
+                _ => None,
+            };
+            overrides.push((Hardfork::Paris, ForkCondition::TTD { fork_block, total_difficulty }));
+        }
+        if let Some(timestamp) = self.shanghai {
+            overrides.push((Hardfork::Shanghai, ForkCondition::Timestamp(timestamp)));
+        }
+        overrides
+    }
+
+    /// Applies the configured overrides to the chain spec.
+    ///
+    /// Returns the overridden forks, the chain spec is left untouched if there are none.
+    pub fn apply(&self, chain: &mut Arc<ChainSpec>) -> Vec<(Hardfork, ForkCondition)> {
+        let overrides = self.overrides(chain);
+        if !overrides.is_empty() {
+            Arc::make_mut(chain).hardforks.extend(overrides.iter().copied());
+        }
+        overrides
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Args, Parser};
+    use reth_primitives::MAINNET;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn override_shanghai() {
+        let args = CommandParser::<ForkOverrideArgs>::parse_from([
+            "reth",
+            "--override.shanghai",
+            "1700000000",
+            "--override.terminaltotaldifficulty",
+            "100",
+        ])
+        .args;
+
+        let mut chain = Arc::new(MAINNET.clone());
+        let overrides = args.apply(&mut chain);
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(chain.fork(Hardfork::Shanghai), ForkCondition::Timestamp(1700000000));
+        assert_eq!(
+            chain.fork(Hardfork::Paris),
+            ForkCondition::TTD { fork_block: None, total_difficulty: U256::from(100) }
+        );
+        // the genesis is unchanged
+        assert_eq!(chain.genesis_hash(), MAINNET.genesis_hash());
+    }
+
+    #[test]
+    fn override_block_forks() {
+        let args = CommandParser::<ForkOverrideArgs>::parse_from([
+            "reth",
+            "--override.london",
+            "100",
+            "--override.grayglacier",
+            "200",
+        ])
+        .args;
+
+        let mut chain = Arc::new(MAINNET.clone());
+        let overrides = args.apply(&mut chain);
+        assert_eq!(
+            overrides,
+            vec![
+                (Hardfork::London, ForkCondition::Block(100)),
+                (Hardfork::GrayGlacier, ForkCondition::Block(200))
+            ]
+        );
+        assert_eq!(chain.fork(Hardfork::London), ForkCondition::Block(100));
+        assert_eq!(chain.fork(Hardfork::GrayGlacier), ForkCondition::Block(200));
+        assert_eq!(chain.fork(Hardfork::Berlin), MAINNET.fork(Hardfork::Berlin));
+    }
+
+    #[test]
+    fn no_overrides() {
+        let args = CommandParser::<ForkOverrideArgs>::parse_from(["reth"]).args;
+        let mut chain = Arc::new(MAINNET.clone());
+        assert!(args.apply(&mut chain).is_empty());
+        assert_eq!(chain.hardforks, MAINNET.hardforks);
+    }
+}
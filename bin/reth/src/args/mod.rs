@@ -13,12 +13,22 @@ mod debug_args;
 pub use debug_args::DebugArgs;
 
 mod secret_key;
-pub use secret_key::{get_secret_key, SecretKeyError};
+pub use secret_key::{
+    get_secret_key, read_secret_key, secret_key_peer_id, write_secret_key, SecretKeyError,
+};
 
 /// MinerArgs struct for configuring the miner
 mod payload_build_args;
 pub use payload_build_args::PayloadBuilderArgs;
 
+/// ForkOverrideArgs struct for shifting fork activations
+mod fork_override_args;
+pub use fork_override_args::ForkOverrideArgs;
+
+/// DownloaderArgs struct for tuning the headers and bodies downloaders
+mod downloader_args;
+pub use downloader_args::DownloaderArgs;
+
 /// Stage related arguments
 mod stage_args;
 pub use stage_args::StageEnum;
@@ -1,12 +1,25 @@
 //! clap [Args](clap::Args) for network related arguments.
 
+use crate::version::p2p_client_version;
 use clap::Args;
+use eyre::WrapErr;
+use reth_discv4::DEFAULT_DISCOVERY_PORT;
+use reth_discv5::{Discv5Config, Enr, DEFAULT_DISCOVERY_V5_PORT};
+use reth_dns_discovery::{tree::LinkEntry, DnsDiscoveryConfig};
+use reth_net_common::ban_list::IpNet;
 use reth_net_nat::NatResolver;
-use reth_network::NetworkConfigBuilder;
-use reth_primitives::{mainnet_nodes, ChainSpec, NodeRecord};
+use reth_network::{BackoffCurve, HelloMessage, NetworkConfigBuilder, PeersConfig, SessionsConfig};
+use reth_primitives::{ChainSpec, NodeRecord};
 use reth_staged_sync::Config;
 use secp256k1::SecretKey;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tracing::info;
 
 /// Parameters for configuring the network more granularity via CLI
 #[derive(Debug, Args)]
@@ -16,23 +29,59 @@ pub struct NetworkArgs {
     #[command(flatten)]
     pub discovery: DiscoveryArgs,
 
-    /// Target trusted peer enodes
-    /// --trusted-peers enode://abcd@192.168.0.1:30303
-    #[arg(long)]
+    /// Comma separated enodes of trusted peers, e.g.
+    /// `--trusted-peers enode://abcd@192.168.0.1:30303`.
+    ///
+    /// Trusted peers are never slashed and are connected to even if all peer slots are taken.
+    #[arg(long, value_delimiter = ',')]
     pub trusted_peers: Vec<NodeRecord>,
 
+    /// A file with enodes of trusted peers, one per line. Empty lines and lines starting with `#`
+    /// are ignored.
+    #[arg(long, value_name = "FILE")]
+    pub trusted_peers_file: Option<PathBuf>,
+
+    /// Comma separated enodes of static peers.
+    ///
+    /// Static peers are redialed with a backoff whenever the session to them is dropped.
+    #[arg(long, value_delimiter = ',')]
+    pub static_peers: Vec<NodeRecord>,
+
+    /// A file with enodes of static peers, one per line. Empty lines and lines starting with `#`
+    /// are ignored.
+    #[arg(long, value_name = "FILE")]
+    pub static_peers_file: Option<PathBuf>,
+
     /// Connect only to trusted peers
     #[arg(long)]
     pub trusted_only: bool,
 
+    /// Comma separated IPs or CIDR networks, e.g. `--deny-networks 1.2.3.4,10.0.0.0/8`.
+    ///
+    /// Peers of denied networks are never dialed or accepted, and are neither accepted from nor
+    /// handed out via discovery.
+    #[arg(long, value_delimiter = ',', value_name = "CIDR", value_parser = parse_network)]
+    pub deny_networks: Vec<IpNet>,
+
+    /// A file with IPs or CIDR networks to deny, one per line. Empty lines and lines starting with
+    /// `#` are ignored.
+    #[arg(long, value_name = "FILE")]
+    pub deny_networks_file: Option<PathBuf>,
+
     /// Bootnodes to connect to initially.
     ///
-    /// Will fall back to a network-specific default if not specified.
+    /// Replaces the bootnodes of the chain spec, or the network-specific default if the chain is a
+    /// known network.
     #[arg(long, value_delimiter = ',')]
     pub bootnodes: Option<Vec<NodeRecord>>,
 
-    /// The path to the known peers file. Connected peers are dumped to this file on nodes
-    /// shutdown, and read on startup. Cannot be used with `--no-persist-peers`.
+    /// Additional bootnodes to connect to initially, on top of the default bootnodes.
+    #[arg(long, value_delimiter = ',')]
+    pub extra_bootnodes: Vec<NodeRecord>,
+
+    /// The path to the known peers file. Peers that a session was established with are dumped to
+    /// this file with their reputation periodically and on shutdown, and read on startup. Cannot
+    /// be used with `--no-persist-peers`.
     #[arg(long, value_name = "FILE", verbatim_doc_comment, conflicts_with = "no_persist_peers")]
     pub peers_file: Option<PathBuf>,
 
@@ -40,13 +89,110 @@ pub struct NetworkArgs {
     #[arg(long, verbatim_doc_comment)]
     pub no_persist_peers: bool,
 
-    /// NAT resolution method.
-    #[arg(long, default_value = "any")]
+    /// NAT resolution method: any, none, upnp, pmp, publicip or extip:<IP>.
+    ///
+    /// With `upnp` and `pmp`, the RLPx and discovery ports are also mapped on the router and the
+    /// mappings are kept refreshed.
+    #[arg(long, default_value = "any", verbatim_doc_comment)]
     pub nat: NatResolver,
 
+    /// Custom node identity that is appended to the client version advertised to peers in the
+    /// RLPx `Hello` message and returned by `web3_clientVersion`, e.g.
+    /// `reth/v0.1.0/linux-6fc95a5/<IDENTITY>`.
+    ///
+    /// Must not be empty and may only contain printable ASCII characters without whitespace.
+    #[arg(long, value_name = "IDENTITY", value_parser = parse_identity, verbatim_doc_comment)]
+    pub identity: Option<String>,
+
+    /// Network listening address.
+    #[arg(long = "addr", value_name = "ADDR", default_value_t = Ipv4Addr::UNSPECIFIED.into())]
+    pub addr: IpAddr,
+
     /// Network listening port. default: 30303
     #[arg(long = "port", value_name = "PORT")]
     pub port: Option<u16>,
+
+    /// IPv6 network listening address, to listen on both address families next to an IPv4
+    /// `--addr`, e.g. `::`.
+    #[arg(long = "addr.ipv6", name = "addr.ipv6", value_name = "ADDR")]
+    pub addr_ipv6: Option<Ipv6Addr>,
+
+    /// IPv6 network listening port. default: same as `--port`
+    #[arg(long = "port.ipv6", name = "port.ipv6", value_name = "PORT", requires = "addr.ipv6")]
+    pub port_ipv6: Option<u16>,
+
+    /// The interval between two p2p pings of a responsive peer, in seconds. default: 60
+    #[arg(
+        long = "p2p.ping-interval",
+        name = "p2p.ping-interval",
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub ping_interval: Option<u64>,
+
+    /// The time to wait for the pong of a p2p ping, in seconds. default: 15
+    #[arg(
+        long = "p2p.ping-timeout",
+        name = "p2p.ping-timeout",
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub ping_timeout: Option<u64>,
+
+    /// The number of consecutive p2p pings a peer may miss before it is disconnected. default: 1
+    #[arg(
+        long = "p2p.max-missed-pings",
+        name = "p2p.max-missed-pings",
+        value_name = "NUM",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub max_missed_pings: Option<u32>,
+
+    /// The initial time to wait for the response to a request to a peer, in seconds. default: 20
+    #[arg(long = "p2p.request-timeout", name = "p2p.request-timeout", value_name = "SECONDS")]
+    pub request_timeout: Option<u64>,
+
+    /// The maximum number of outbound dials that are in progress at the same time. default: 30
+    #[arg(
+        long = "p2p.max-concurrent-dials",
+        name = "p2p.max-concurrent-dials",
+        value_name = "NUM",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub max_concurrent_dials: Option<u64>,
+
+    /// The time to wait for the tcp connection to a dialed peer, in seconds. default: 15
+    #[arg(long = "p2p.dial-timeout", name = "p2p.dial-timeout", value_name = "SECONDS")]
+    pub dial_timeout: Option<u64>,
+
+    /// How the redial backoff of unreachable peers grows: linear or exponential. default: linear
+    #[arg(
+        long = "p2p.backoff-curve",
+        name = "p2p.backoff-curve",
+        value_name = "CURVE",
+        value_parser = parse_backoff_curve
+    )]
+    pub backoff_curve: Option<BackoffCurve>,
+
+    /// The maximum download bandwidth across all peers, in bytes per second with an optional
+    /// K, M or G suffix, e.g. `10M`. default: unlimited
+    #[arg(
+        long = "network.max-download-rate",
+        name = "network.max-download-rate",
+        value_name = "BYTES_PER_SEC",
+        value_parser = parse_byte_rate
+    )]
+    pub max_download_rate: Option<u64>,
+
+    /// The maximum upload bandwidth across all peers, in bytes per second with an optional K, M or
+    /// G suffix, e.g. `2M`. default: unlimited
+    #[arg(
+        long = "network.max-upload-rate",
+        name = "network.max-upload-rate",
+        value_name = "BYTES_PER_SEC",
+        value_parser = parse_byte_rate
+    )]
+    pub max_upload_rate: Option<u64>,
 }
 
 impl NetworkArgs {
@@ -55,29 +201,50 @@ impl NetworkArgs {
     ///
     /// The `default_peers_file` will be used as the default location to store the persistent peers
     /// file if `no_persist_peers` is false, and there is no provided `peers_file`.
+    ///
+    /// Returns an error if the peers file exists but can't be loaded.
     pub fn network_config(
         &self,
         config: &Config,
         chain_spec: Arc<ChainSpec>,
         secret_key: SecretKey,
         default_peers_file: PathBuf,
-    ) -> NetworkConfigBuilder {
-        let chain_bootnodes = chain_spec.chain.bootnodes().unwrap_or_else(mainnet_nodes);
-
+    ) -> eyre::Result<NetworkConfigBuilder> {
         let peers_file = self.peers_file.clone().unwrap_or(default_peers_file);
 
-        let network_config_builder = config
+        let mut network_config_builder = config
             .network_config(self.nat, self.persistent_peers_file(peers_file), secret_key)
-            .boot_nodes(self.bootnodes.clone().unwrap_or(chain_bootnodes))
+            .wrap_err("failed to load the peers file")?
+            .boot_nodes(self.bootnodes(&chain_spec))
             .chain_spec(chain_spec);
 
-        self.discovery.apply_to_builder(network_config_builder)
+        // the client version of the `Hello` message is also returned by `web3_clientVersion`
+        let hello_message = HelloMessage::builder(network_config_builder.get_peer_id())
+            .client_version(p2p_client_version(self.identity.as_deref()))
+            .port(self.port.unwrap_or(DEFAULT_DISCOVERY_PORT))
+            .build();
+        network_config_builder = network_config_builder.hello_message(hello_message);
+
+        Ok(self.discovery.apply_to_builder(network_config_builder))
     }
 }
 
 // === impl NetworkArgs ===
 
 impl NetworkArgs {
+    /// Returns the bootnodes to use for the given chain.
+    ///
+    /// Chains without any known bootnodes, e.g. private networks, don't use any default
+    /// bootnodes.
+    pub fn bootnodes(&self, chain_spec: &ChainSpec) -> Vec<NodeRecord> {
+        let mut bootnodes = match &self.bootnodes {
+            Some(bootnodes) => bootnodes.clone(),
+            None => chain_spec.bootnodes().unwrap_or_default(),
+        };
+        bootnodes.extend(self.extra_bootnodes.iter().copied());
+        bootnodes
+    }
+
     /// If `no_persist_peers` is true then this returns the path to the persistent peers file path.
     pub fn persistent_peers_file(&self, peers_file: PathBuf) -> Option<PathBuf> {
         if self.no_persist_peers {
@@ -86,6 +253,193 @@ impl NetworkArgs {
 
         Some(peers_file)
     }
+
+    /// Sets the RLPx listener and discovery addresses.
+    ///
+    /// If an IPv6 address is configured next to an IPv4 address, the node runs dual-stack.
+    pub fn apply_addrs(
+        &self,
+        mut builder: NetworkConfigBuilder,
+    ) -> eyre::Result<NetworkConfigBuilder> {
+        let port = self.port.unwrap_or(DEFAULT_DISCOVERY_PORT);
+        builder = builder.listener_addr(SocketAddr::new(self.addr, port));
+        if let Some(addr_ipv6) = self.addr_ipv6 {
+            if self.addr.is_ipv6() {
+                eyre::bail!("--addr.ipv6 requires an IPv4 --addr, got {}", self.addr)
+            }
+            let port = self.port_ipv6.unwrap_or(port);
+            builder = builder.listener_addr_ipv6(SocketAddr::new(addr_ipv6.into(), port));
+        }
+
+        let discovery = &self.discovery;
+        let port = discovery.port.unwrap_or(DEFAULT_DISCOVERY_PORT);
+        builder = builder.discovery_addr(SocketAddr::new(discovery.addr, port));
+        if let Some(addr_ipv6) = discovery.addr_ipv6 {
+            if discovery.addr.is_ipv6() {
+                eyre::bail!(
+                    "--discovery.addr.ipv6 requires an IPv4 --discovery.addr, got {}",
+                    discovery.addr
+                )
+            }
+            let port = discovery.port_ipv6.unwrap_or(port);
+            builder = builder.discovery_addr_ipv6(SocketAddr::new(addr_ipv6.into(), port));
+        }
+        Ok(builder)
+    }
+
+    /// Adds the trusted and static peers to the [PeersConfig] and sets whether only trusted peers
+    /// should be connected to.
+    pub fn apply_peers(&self, peers: &mut PeersConfig) -> eyre::Result<()> {
+        peers.connect_trusted_nodes_only = self.trusted_only;
+
+        peers.trusted_nodes.extend(self.trusted_peers.iter().copied());
+        if let Some(path) = &self.trusted_peers_file {
+            peers.trusted_nodes.extend(read_enodes(path)?);
+        }
+        peers.static_nodes.extend(self.static_peers.iter().copied());
+        if let Some(path) = &self.static_peers_file {
+            peers.static_nodes.extend(read_enodes(path)?);
+        }
+
+        let mut denied_networks = self.deny_networks.clone();
+        if let Some(path) = &self.deny_networks_file {
+            let content = std::fs::read_to_string(path)
+                .wrap_err_with(|| format!("Could not read deny networks file {path:?}"))?;
+            denied_networks.extend(
+                parse_lines(&content, "IP or CIDR network", parse_network)
+                    .wrap_err_with(|| format!("Invalid deny networks file {path:?}"))?,
+            );
+        }
+        if !denied_networks.is_empty() {
+            info!(target: "reth::cli", networks = denied_networks.len(), "Denying networks");
+            for network in denied_networks {
+                peers.ban_list.deny_network(network);
+            }
+        }
+
+        if let Some(max_concurrent_dials) = self.max_concurrent_dials {
+            *peers = std::mem::take(peers).with_max_concurrent_dials(max_concurrent_dials as usize);
+        }
+        if let Some(curve) = self.backoff_curve {
+            peers.backoff_durations.curve = curve;
+        }
+
+        if !peers.trusted_nodes.is_empty() || !peers.static_nodes.is_empty() {
+            info!(
+                target: "reth::cli",
+                trusted = peers.trusted_nodes.len(),
+                r#static = peers.static_nodes.len(),
+                "Adding trusted and static nodes"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl NetworkArgs {
+    /// Applies the keepalive, request and dial timeout settings to the [SessionsConfig], overriding
+    /// the values of the config file.
+    pub fn apply_sessions(&self, sessions: &mut SessionsConfig) {
+        if let Some(secs) = self.ping_interval {
+            sessions.ping.interval = Duration::from_secs(secs);
+        }
+        if let Some(secs) = self.ping_timeout {
+            sessions.ping.timeout = Duration::from_secs(secs);
+        }
+        if let Some(max_missed_pings) = self.max_missed_pings {
+            sessions.ping.max_missed_pings = max_missed_pings;
+        }
+        if let Some(secs) = self.request_timeout {
+            sessions.initial_internal_request_timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = self.dial_timeout {
+            sessions.dial_timeout = Duration::from_secs(secs);
+        }
+        if let Some(rate) = self.max_download_rate {
+            sessions.max_download_rate = Some(rate);
+        }
+        if let Some(rate) = self.max_upload_rate {
+            sessions.max_upload_rate = Some(rate);
+        }
+    }
+}
+
+/// Reads the enodes of a peers file.
+fn read_enodes(path: &Path) -> eyre::Result<Vec<NodeRecord>> {
+    let content = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("Could not read peers file {path:?}"))?;
+    parse_enodes(&content).wrap_err_with(|| format!("Invalid peers file {path:?}"))
+}
+
+/// Parses enodes, one per line. Empty lines and lines starting with `#` are ignored.
+fn parse_enodes(input: &str) -> eyre::Result<Vec<NodeRecord>> {
+    parse_lines(input, "enode", str::parse)
+}
+
+/// Parses one entry per line with the given parser. Empty lines and lines starting with `#` are
+/// ignored.
+fn parse_lines<T, E: Display>(
+    input: &str,
+    kind: &str,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> eyre::Result<Vec<T>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_num, line)| {
+            parse(line).map_err(|err| eyre::eyre!("line {line_num}: invalid {kind}: {err}"))
+        })
+        .collect()
+}
+
+/// Parses the identity suffix of the client version.
+fn parse_identity(s: &str) -> Result<String, String> {
+    if s.is_empty() {
+        return Err("identity must not be empty".to_string())
+    }
+    if !s.chars().all(|c| c.is_ascii_graphic()) {
+        return Err(format!("invalid identity `{s}`, only printable ASCII without whitespace"))
+    }
+    Ok(s.to_string())
+}
+
+/// Parses a number of bytes per second with an optional binary K, M or G suffix.
+fn parse_byte_rate(s: &str) -> Result<u64, String> {
+    let (num, multiplier) = match s.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some('K') => (&s[..s.len() - 1], 1024),
+        Some('M') => (&s[..s.len() - 1], 1024 * 1024),
+        Some('G') => (&s[..s.len() - 1], 1024 * 1024 * 1024),
+        _ => (s, 1),
+    };
+    let rate = num
+        .parse::<u64>()
+        .ok()
+        .and_then(|num| num.checked_mul(multiplier))
+        .ok_or_else(|| format!("invalid rate `{s}`, expected bytes per second, e.g. `10M`"))?;
+    if rate == 0 {
+        return Err("rate must not be zero".to_string())
+    }
+    Ok(rate)
+}
+
+fn parse_backoff_curve(s: &str) -> Result<BackoffCurve, String> {
+    match s {
+        "linear" => Ok(BackoffCurve::Linear),
+        "exponential" => Ok(BackoffCurve::Exponential),
+        _ => Err(format!("invalid backoff curve `{s}`, expected `linear` or `exponential`")),
+    }
+}
+
+/// Parses an IP or a CIDR network, a single IP is treated as network of one address.
+fn parse_network(s: &str) -> Result<IpNet, String> {
+    if let Ok(network) = s.parse::<IpNet>() {
+        return Ok(network)
+    }
+    let ip = s.parse::<IpAddr>().map_err(|_| format!("not an IP or CIDR network: {s}"))?;
+    let prefix_len = if ip.is_ipv4() { 32 } else { 128 };
+    IpNet::new(ip, prefix_len).map_err(|err| err.to_string())
 }
 
 /// Arguments to setup discovery
@@ -99,13 +453,128 @@ pub struct DiscoveryArgs {
     #[arg(long, conflicts_with = "disable_discovery")]
     pub disable_dns_discovery: bool,
 
+    /// Comma separated EIP-1459 links of the DNS node lists to sync, e.g.
+    /// `enrtree://AKA3AM6LPBYEUDMVNU3BSVQJ5AD45Y7YPOHJLEF6W26QOE4VTUDPE@all.mainnet.ethdisco.net`.
+    ///
+    /// Replaces the default node list of the chain.
+    #[arg(
+        long = "discovery.dns",
+        name = "discovery.dns",
+        value_name = "ENRTREE",
+        value_delimiter = ',',
+        verbatim_doc_comment,
+        conflicts_with_all = ["disable_discovery", "disable_dns_discovery"]
+    )]
+    pub dns_networks: Vec<LinkEntry>,
+
     /// Disable Discv4 discovery.
     #[arg(long, conflicts_with = "disable_discovery")]
     pub disable_discv4_discovery: bool,
 
+    /// Only dial discovered peers once their fork id is known from their ENR (EIP-868).
+    ///
+    /// Peers of other networks are dropped before a connection is attempted. Peers that don't
+    /// advertise a fork id are never dialed.
+    #[arg(long, conflicts_with = "disable_discovery")]
+    pub enforce_enr_fork_id: bool,
+
+    /// The UDP address to use for P2P discovery/networking.
+    #[arg(
+        long = "discovery.addr",
+        name = "discovery.addr",
+        value_name = "DISCOVERY_ADDR",
+        default_value_t = Ipv4Addr::UNSPECIFIED.into()
+    )]
+    pub addr: IpAddr,
+
     /// The UDP port to use for P2P discovery/networking. default: 30303
     #[arg(long = "discovery.port", name = "discovery.port", value_name = "DISCOVERY_PORT")]
     pub port: Option<u16>,
+
+    /// The IPv6 UDP address to use for discovery next to an IPv4 `--discovery.addr`, e.g. `::`.
+    ///
+    /// Both address families are advertised in the local ENR.
+    #[arg(
+        long = "discovery.addr.ipv6",
+        name = "discovery.addr.ipv6",
+        value_name = "DISCOVERY_ADDR",
+        conflicts_with = "disable_discovery"
+    )]
+    pub addr_ipv6: Option<Ipv6Addr>,
+
+    /// The IPv6 UDP port to use for discovery. default: same as `--discovery.port`
+    #[arg(
+        long = "discovery.port.ipv6",
+        name = "discovery.port.ipv6",
+        value_name = "DISCOVERY_PORT",
+        requires = "discovery.addr.ipv6"
+    )]
+    pub port_ipv6: Option<u16>,
+
+    /// The maximum number of nodes per bucket of the discv4 routing table. default: 16
+    #[arg(
+        long = "discovery.v4.bucket-size",
+        name = "discovery.v4.bucket-size",
+        value_name = "SIZE",
+        value_parser = clap::value_parser!(u16).range(1..),
+        conflicts_with = "disable_discovery"
+    )]
+    pub discv4_bucket_size: Option<u16>,
+
+    /// The number of concurrent `FindNode` requests of a discv4 lookup. default: 3
+    #[arg(
+        long = "discovery.v4.lookup-concurrency",
+        name = "discovery.v4.lookup-concurrency",
+        value_name = "NUM",
+        value_parser = clap::value_parser!(u16).range(1..),
+        conflicts_with = "disable_discovery"
+    )]
+    pub discv4_lookup_concurrency: Option<u16>,
+
+    /// The interval between discv4 self lookups, in seconds. default: 20
+    #[arg(
+        long = "discovery.v4.lookup-interval",
+        name = "discovery.v4.lookup-interval",
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with = "disable_discovery"
+    )]
+    pub discv4_lookup_interval: Option<u64>,
+
+    /// The duration after which a discv4 ping is considered timed out, in seconds. default: 20
+    #[arg(
+        long = "discovery.v4.ping-expiration",
+        name = "discovery.v4.ping-expiration",
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with = "disable_discovery"
+    )]
+    pub discv4_ping_expiration: Option<u64>,
+
+    /// Enable discv5 discovery, next to discv4.
+    ///
+    /// Execution layer nodes are found via the `eth` entry of their ENR.
+    #[arg(long, conflicts_with = "disable_discovery")]
+    pub enable_discv5_discovery: bool,
+
+    /// The UDP port to use for discv5 discovery. default: 9000
+    #[arg(
+        long = "discovery.v5.port",
+        name = "discovery.v5.port",
+        value_name = "DISCOVERY_V5_PORT",
+        requires = "enable_discv5_discovery"
+    )]
+    pub discv5_port: Option<u16>,
+
+    /// Comma separated ENRs of the discv5 bootnodes.
+    #[arg(
+        long = "discovery.v5.bootnodes",
+        name = "discovery.v5.bootnodes",
+        value_name = "ENRS",
+        value_delimiter = ',',
+        requires = "enable_discv5_discovery"
+    )]
+    pub discv5_bootnodes: Vec<Enr>,
 }
 
 impl DiscoveryArgs {
@@ -116,11 +585,361 @@ impl DiscoveryArgs {
     ) -> NetworkConfigBuilder {
         if self.disable_discovery || self.disable_dns_discovery {
             network_config_builder = network_config_builder.disable_dns_discovery();
+        } else if !self.dns_networks.is_empty() {
+            network_config_builder = network_config_builder.dns_discovery(DnsDiscoveryConfig {
+                bootstrap_dns_networks: Some(self.dns_networks.iter().cloned().collect()),
+                ..Default::default()
+            });
         }
 
         if self.disable_discovery || self.disable_discv4_discovery {
             network_config_builder = network_config_builder.disable_discv4_discovery();
         }
+
+        network_config_builder = network_config_builder.configure_discovery(|discv4| {
+            if let Some(size) = self.discv4_bucket_size {
+                discv4.max_nodes_per_bucket(size as usize);
+            }
+            if let Some(concurrency) = self.discv4_lookup_concurrency {
+                discv4.lookup_concurrency(concurrency as usize);
+            }
+            if let Some(secs) = self.discv4_lookup_interval {
+                discv4.lookup_interval(Duration::from_secs(secs));
+            }
+            if let Some(secs) = self.discv4_ping_expiration {
+                discv4.ping_expiration(Duration::from_secs(secs));
+            }
+        });
+
+        network_config_builder =
+            network_config_builder.enforce_enr_fork_id(self.enforce_enr_fork_id);
+
+        if self.enable_discv5_discovery {
+            let port = self.discv5_port.unwrap_or(DEFAULT_DISCOVERY_V5_PORT);
+            network_config_builder = network_config_builder.discovery_v5(Discv5Config {
+                discovery_addr: SocketAddr::new(self.addr, port),
+                bootstrap_nodes: self.discv5_bootnodes.clone(),
+                ..Default::default()
+            });
+        }
         network_config_builder
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Args, Parser};
+    use reth_network::PeerBackoffDurations;
+    use reth_primitives::{mainnet_nodes, ChainSpecBuilder, Genesis, MAINNET};
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    const ENODE: &str = "enode://6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0@10.3.58.6:30303";
+
+    const ENRTREE: &str =
+        "enrtree://AKA3AM6LPBYEUDMVNU3BSVQJ5AD45Y7YPOHJLEF6W26QOE4VTUDPE@all.mainnet.ethdisco.net";
+
+    #[test]
+    fn default_bootnodes() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.bootnodes(&MAINNET), mainnet_nodes());
+
+        // custom chains don't fall back to the mainnet bootnodes
+        let custom = ChainSpecBuilder::default().chain_id(1337).genesis(Genesis::default()).build();
+        assert!(args.bootnodes(&custom).is_empty());
+
+        let custom = ChainSpecBuilder::from(&custom).bootnodes([ENODE.parse().unwrap()]).build();
+        assert_eq!(args.bootnodes(&custom), vec![ENODE.parse().unwrap()]);
+    }
+
+    #[test]
+    fn replace_and_extend_bootnodes() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth", "--bootnodes", ENODE]).args;
+        assert_eq!(args.bootnodes(&MAINNET), vec![ENODE.parse().unwrap()]);
+
+        let args =
+            CommandParser::<NetworkArgs>::parse_from(["reth", "--extra-bootnodes", ENODE]).args;
+        let bootnodes = args.bootnodes(&MAINNET);
+        assert_eq!(bootnodes.len(), mainnet_nodes().len() + 1);
+        assert_eq!(bootnodes.last(), Some(&ENODE.parse().unwrap()));
+    }
+
+    #[test]
+    fn parse_dns_networks() {
+        let args =
+            CommandParser::<NetworkArgs>::parse_from(["reth", "--discovery.dns", ENRTREE]).args;
+        assert_eq!(args.discovery.dns_networks, vec![ENRTREE.parse().unwrap()]);
+
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--discovery.dns",
+            ENRTREE,
+            "--disable-dns-discovery"
+        ])
+        .is_err());
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--discovery.dns",
+            "enrtree://invalid"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_nat_port_mapping() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth", "--nat", "pmp"]).args;
+        assert_eq!(args.nat, NatResolver::NatPmp);
+        assert!(args.nat.supports_port_mapping());
+
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth"]).args;
+        assert!(!args.nat.supports_port_mapping());
+    }
+
+    #[test]
+    fn parse_discv4_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--discovery.v4.bucket-size",
+            "32",
+            "--discovery.v4.lookup-concurrency",
+            "5",
+            "--discovery.v4.lookup-interval",
+            "60",
+            "--discovery.v4.ping-expiration",
+            "10",
+        ])
+        .args;
+        assert_eq!(args.discovery.discv4_bucket_size, Some(32));
+        assert_eq!(args.discovery.discv4_lookup_concurrency, Some(5));
+        assert_eq!(args.discovery.discv4_lookup_interval, Some(60));
+        assert_eq!(args.discovery.discv4_ping_expiration, Some(10));
+
+        for arg in [
+            "--discovery.v4.bucket-size",
+            "--discovery.v4.lookup-concurrency",
+            "--discovery.v4.lookup-interval",
+            "--discovery.v4.ping-expiration",
+        ] {
+            assert!(CommandParser::<NetworkArgs>::try_parse_from(["reth", arg, "0"]).is_err());
+        }
+    }
+
+    #[test]
+    fn parse_trusted_and_static_peers() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--trusted-peers",
+            ENODE,
+            "--static-peers",
+            &format!("{ENODE},{ENODE}"),
+        ])
+        .args;
+        assert_eq!(args.trusted_peers, vec![ENODE.parse().unwrap()]);
+        assert_eq!(args.static_peers.len(), 2);
+
+        let mut peers = PeersConfig::default();
+        args.apply_peers(&mut peers).unwrap();
+        assert!(peers.trusted_nodes.contains(&ENODE.parse().unwrap()));
+        assert_eq!(peers.static_nodes.len(), 1);
+    }
+
+    #[test]
+    fn parse_peers_file() {
+        let nodes = parse_enodes(&format!("# static peers\n\n{ENODE}\n  {ENODE}  \n")).unwrap();
+        assert_eq!(nodes, vec![ENODE.parse().unwrap(); 2]);
+
+        let err = parse_enodes(&format!("{ENODE}\nenode://invalid")).unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn parse_deny_networks() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--deny-networks",
+            "1.2.3.4,10.0.0.0/8,2001:db8::/32",
+        ])
+        .args;
+        assert_eq!(args.deny_networks.len(), 3);
+        assert_eq!(args.deny_networks[0], "1.2.3.4/32".parse().unwrap());
+
+        let mut peers = PeersConfig::default();
+        args.apply_peers(&mut peers).unwrap();
+        assert!(peers.ban_list.is_banned_ip(&"10.1.2.3".parse().unwrap()));
+        assert!(!peers.ban_list.is_banned_ip(&"1.2.3.5".parse().unwrap()));
+
+        assert!(CommandParser::<NetworkArgs>::try_parse_from(["reth", "--deny-networks", "1.2.3"])
+            .is_err());
+
+        let networks =
+            parse_lines("# sanctioned\n1.2.3.0/24\n\n5.6.7.8\n", "network", parse_network).unwrap();
+        assert_eq!(networks.len(), 2);
+    }
+
+    #[test]
+    fn parse_dial_scheduling() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--p2p.max-concurrent-dials",
+            "8",
+            "--p2p.backoff-curve",
+            "exponential",
+        ])
+        .args;
+
+        let mut peers = PeersConfig::default();
+        args.apply_peers(&mut peers).unwrap();
+        assert_eq!(
+            peers,
+            PeersConfig::default().with_max_concurrent_dials(8).with_backoff_durations(
+                PeerBackoffDurations { curve: BackoffCurve::Exponential, ..Default::default() }
+            )
+        );
+
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--p2p.backoff-curve",
+            "quadratic"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_dual_stack_addrs() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--addr.ipv6",
+            "::",
+            "--discovery.addr",
+            "127.0.0.1",
+            "--discovery.addr.ipv6",
+            "::1",
+            "--discovery.port.ipv6",
+            "30304",
+        ])
+        .args;
+        let config = args
+            .apply_addrs(NetworkConfigBuilder::new(SecretKey::from_slice(&[1; 32]).unwrap()))
+            .unwrap()
+            .build(());
+        assert_eq!(config.listener_addr, "0.0.0.0:30303".parse().unwrap());
+        assert_eq!(config.listener_addr_ipv6, Some("[::]:30303".parse().unwrap()));
+        assert_eq!(config.discovery_addr, "127.0.0.1:30303".parse().unwrap());
+        assert_eq!(config.discovery_addr_ipv6, Some("[::1]:30304".parse().unwrap()));
+
+        // a second address needs an IPv4 address next to it
+        let args =
+            CommandParser::<NetworkArgs>::parse_from(["reth", "--addr", "::", "--addr.ipv6", "::"])
+                .args;
+        assert!(args
+            .apply_addrs(NetworkConfigBuilder::new(SecretKey::from_slice(&[1; 32]).unwrap()))
+            .is_err());
+
+        assert!(
+            CommandParser::<NetworkArgs>::try_parse_from(["reth", "--port.ipv6", "30304"]).is_err()
+        );
+    }
+
+    #[test]
+    fn parse_identity_suffix() {
+        let args =
+            CommandParser::<NetworkArgs>::parse_from(["reth", "--identity", "my-pool-node-3"]).args;
+        assert_eq!(args.identity.as_deref(), Some("my-pool-node-3"));
+
+        assert!(CommandParser::<NetworkArgs>::try_parse_from(["reth", "--identity", ""]).is_err());
+        assert!(CommandParser::<NetworkArgs>::try_parse_from(["reth", "--identity", "my node"])
+            .is_err());
+    }
+
+    #[test]
+    fn parse_session_timeouts() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--p2p.ping-interval",
+            "120",
+            "--p2p.ping-timeout",
+            "30",
+            "--p2p.max-missed-pings",
+            "3",
+            "--p2p.request-timeout",
+            "40",
+            "--p2p.dial-timeout",
+            "5",
+        ])
+        .args;
+
+        let mut sessions = SessionsConfig::default();
+        args.apply_sessions(&mut sessions);
+        assert_eq!(sessions.dial_timeout, Duration::from_secs(5));
+        assert_eq!(sessions.ping.interval, Duration::from_secs(120));
+        assert_eq!(sessions.ping.timeout, Duration::from_secs(30));
+        assert_eq!(sessions.ping.max_missed_pings, 3);
+        assert_eq!(sessions.initial_internal_request_timeout, Duration::from_secs(40));
+
+        for arg in ["--p2p.ping-interval", "--p2p.ping-timeout", "--p2p.max-missed-pings"] {
+            assert!(CommandParser::<NetworkArgs>::try_parse_from(["reth", arg, "0"]).is_err());
+        }
+    }
+
+    #[test]
+    fn parse_bandwidth_limits() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--network.max-download-rate",
+            "10M",
+            "--network.max-upload-rate",
+            "512k",
+        ])
+        .args;
+
+        let mut sessions = SessionsConfig::default();
+        args.apply_sessions(&mut sessions);
+        assert_eq!(sessions.max_download_rate, Some(10 * 1024 * 1024));
+        assert_eq!(sessions.max_upload_rate, Some(512 * 1024));
+
+        for rate in ["0", "10X", "M", "-1"] {
+            assert!(CommandParser::<NetworkArgs>::try_parse_from([
+                "reth",
+                "--network.max-download-rate",
+                rate
+            ])
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn parse_discv5_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth"]).args;
+        assert!(!args.discovery.enable_discv5_discovery);
+
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--enable-discv5-discovery",
+            "--discovery.v5.port",
+            "9001",
+        ])
+        .args;
+        assert!(args.discovery.enable_discv5_discovery);
+        assert_eq!(args.discovery.discv5_port, Some(9001));
+
+        // discv5 options require discv5
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--discovery.v5.port",
+            "9001"
+        ])
+        .is_err());
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--enable-discv5-discovery",
+            "--disable-discovery"
+        ])
+        .is_err());
+    }
+}
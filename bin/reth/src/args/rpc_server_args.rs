@@ -1,26 +1,37 @@
 //! clap [Args](clap::Args) for RPC related arguments.
 
 use clap::Args;
+use eyre::WrapErr;
 use futures::FutureExt;
 use reth_network_api::{NetworkInfo, Peers};
 use reth_provider::{
-    BlockProvider, CanonStateSubscriptions, EvmEnvProvider, HeaderProvider, StateProviderFactory,
+    AccountHistoryProvider, BlockProvider, CanonStateSubscriptions, EvmEnvProvider, HeaderProvider,
+    LogIndexProvider, PruneProvider, StageCheckpointProvider, StateProviderFactory,
+};
+use reth_rpc::{
+    eth::{
+        cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, DevSigner, EthFilterConfig,
+    },
+    BlockingTaskPoolConfig, JwtError, JwtSecret, RejectionPolicy,
 };
-use reth_rpc::{JwtError, JwtSecret};
 use reth_rpc_builder::{
     auth::{AuthServerConfig, AuthServerHandle},
     constants,
     error::RpcError,
-    IpcServerBuilder, RethRpcModule, RpcModuleBuilder, RpcModuleSelection, RpcServerConfig,
-    RpcServerHandle, ServerBuilder, TransportRpcModuleConfig,
+    EthConfig, IpAllowlist, IpcServerBuilder, RethRpcModule, RpcModuleBuilder, RpcModuleConfig,
+    RpcModuleSelection, RpcServerConfig, RpcServerHandle, RpcTlsConfig, ServerBuilder,
+    TransportRpcModuleConfig,
 };
 use reth_rpc_engine_api::{EngineApi, EngineApiServer};
+use reth_rpc_types::SyncEvent;
 use reth_tasks::TaskSpawner;
 use reth_transaction_pool::TransactionPool;
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::{Path, PathBuf},
+    time::Duration,
 };
+use tokio::sync::broadcast;
 use tracing::info;
 
 /// Parameters for configuring the rpc more granularity via CLI
@@ -47,6 +58,11 @@ pub struct RpcServerArgs {
     #[arg(long = "http.corsdomain")]
     pub http_corsdomain: Option<String>,
 
+    /// Comma separated list of IP addresses or CIDR ranges allowed to connect to the HTTP
+    /// server, e.g. 127.0.0.1,10.0.0.0/8
+    #[arg(long = "http.allowlist", value_name = "CIDRS")]
+    pub http_allowlist: Option<IpAllowlist>,
+
     /// Enable the WS-RPC server
     #[arg(long)]
     pub ws: bool,
@@ -67,6 +83,89 @@ pub struct RpcServerArgs {
     #[arg(long = "ws.api")]
     pub ws_api: Option<RpcModuleSelection>,
 
+    /// Comma separated list of IP addresses or CIDR ranges allowed to connect to the WS server
+    #[arg(long = "ws.allowlist", value_name = "CIDRS")]
+    pub ws_allowlist: Option<IpAllowlist>,
+
+    /// Maximum number of logs returned by a single `eth_getLogs` request, 0 means no limit
+    #[arg(long = "rpc.max-logs-per-response", value_name = "COUNT")]
+    pub rpc_max_logs_per_response: Option<usize>,
+
+    /// Maximum number of blocks a single `eth_getLogs` request can span, 0 means no limit
+    #[arg(long = "rpc.max-blocks-per-filter", value_name = "COUNT")]
+    pub rpc_max_blocks_per_filter: Option<u64>,
+
+    /// Seconds after which a polling filter that isn't polled is removed
+    #[arg(
+        long = "rpc.stale-filter-ttl",
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub rpc_stale_filter_ttl: Option<u64>,
+
+    /// Maximum number of bytes of blocks kept in the cache shared by the RPC handlers
+    #[arg(long = "rpc.cache.max-block-bytes", value_name = "BYTES")]
+    pub rpc_cache_max_block_bytes: Option<usize>,
+
+    /// Maximum number of bytes of receipts kept in the cache shared by the RPC handlers
+    #[arg(long = "rpc.cache.max-receipt-bytes", value_name = "BYTES")]
+    pub rpc_cache_max_receipt_bytes: Option<usize>,
+
+    /// Maximum number of bytes of EVM environments kept in the cache shared by the RPC handlers
+    #[arg(long = "rpc.cache.max-env-bytes", value_name = "BYTES")]
+    pub rpc_cache_max_env_bytes: Option<usize>,
+
+    /// Number of threads for expensive calls like tracing and log queries, 0 uses all cores
+    #[arg(long = "rpc.blocking-threads", value_name = "COUNT")]
+    pub rpc_blocking_threads: Option<usize>,
+
+    /// Maximum number of expensive calls that are queued or executing, 0 means no limit
+    #[arg(long = "rpc.max-blocking-tasks", value_name = "COUNT")]
+    pub rpc_max_blocking_tasks: Option<usize>,
+
+    /// Wait for a free slot instead of rejecting expensive calls if the queue is full
+    #[arg(long = "rpc.wait-for-blocking-tasks")]
+    pub rpc_wait_for_blocking_tasks: bool,
+
+    /// Enable a local signer for `eth_accounts`, `eth_sign` and `eth_sendTransaction`.
+    ///
+    /// The signer keeps the unlocked keys in memory and signs for anyone who can reach the RPC
+    /// servers, only use it for local development. It's refused unless the HTTP and WS servers
+    /// listen on a loopback address. On the dev chain it unlocks the prefunded dev accounts. The
+    /// `personal` API can only be enabled together with the signer on the dev chain.
+    #[arg(long = "rpc.unsafe-signer")]
+    pub rpc_unsafe_signer: bool,
+
+    /// Directory of encrypted keystore files whose accounts are unlocked by the local signer
+    #[arg(
+        long = "rpc.keystore",
+        value_name = "DIR",
+        requires_all = ["rpc_unsafe_signer", "rpc_keystore_password_file"]
+    )]
+    pub rpc_keystore: Option<PathBuf>,
+
+    /// File with the password the keystore files are decrypted with
+    #[arg(long = "rpc.keystore-password-file", value_name = "PATH", requires = "rpc_keystore")]
+    pub rpc_keystore_password_file: Option<PathBuf>,
+
+    /// Settings of the gas price oracle, these are read from the `[gpo]` section of the config
+    /// file
+    #[arg(skip)]
+    pub gas_price_oracle: GasPriceOracleConfig,
+
+    /// Path to a PEM encoded certificate chain, enables TLS for the HTTP and WS servers
+    #[arg(long = "rpc.tls-cert", value_name = "PATH", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// Path to the PEM encoded private key of the TLS certificate
+    #[arg(long = "rpc.tls-key", value_name = "PATH", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Path to PEM encoded CA certificates, if set clients must authenticate with a certificate
+    /// signed by one of them
+    #[arg(long = "rpc.tls-client-ca", value_name = "PATH", requires = "tls_cert")]
+    pub tls_client_ca: Option<PathBuf>,
+
     /// Disable the IPC-RPC  server
     #[arg(long)]
     pub ipcdisable: bool,
@@ -83,6 +182,22 @@ pub struct RpcServerArgs {
     #[arg(long = "authrpc.port")]
     pub auth_port: Option<u16>,
 
+    /// Comma separated list of IP addresses or CIDR ranges allowed to connect to the auth
+    /// server, in addition to the JWT authentication
+    #[arg(long = "authrpc.allowlist", value_name = "CIDRS")]
+    pub auth_allowlist: Option<IpAllowlist>,
+
+    /// Serve the auth server on the given unix socket instead of TCP.
+    ///
+    /// Access is restricted by the socket's filesystem permissions, so connections over the socket
+    /// are not JWT authenticated.
+    #[arg(
+        long = "authrpc.ipcpath",
+        value_name = "PATH",
+        conflicts_with_all = ["auth_addr", "auth_port", "auth_allowlist"]
+    )]
+    pub auth_ipcpath: Option<String>,
+
     /// Path to a JWT secret to use for authenticated RPC endpoints
     #[arg(long = "authrpc.jwtsecret", value_name = "PATH", global = true, required = false)]
     auth_jwtsecret: Option<PathBuf>,
@@ -117,6 +232,61 @@ impl RpcServerArgs {
         }
     }
 
+    /// Returns the path of the JWT secret, if one was configured.
+    pub(crate) fn auth_jwtsecret_path(&self) -> Option<&Path> {
+        self.auth_jwtsecret.as_deref()
+    }
+
+    /// Returns the signers of the local signer enabled with `--rpc.unsafe-signer`.
+    ///
+    /// These unlock the accounts of the `--rpc.keystore` directory and, if `dev_chain` is set, the
+    /// prefunded dev accounts.
+    ///
+    /// Fails if the `personal` API is selected on any other chain than the dev chain or without
+    /// the signer, and if the signer is enabled while the HTTP or WS server listens on an address
+    /// other than a loopback address.
+    pub(crate) fn signers(&self, dev_chain: bool) -> eyre::Result<Vec<DevSigner>> {
+        let personal_selected = [&self.http_api, &self.ws_api]
+            .into_iter()
+            .flatten()
+            .any(|selection| selection.iter_selection().any(|m| m == RethRpcModule::Personal));
+        if personal_selected && !dev_chain {
+            eyre::bail!("the personal API is only available on the dev chain")
+        }
+
+        let mut signers = Vec::new();
+        if !self.rpc_unsafe_signer {
+            if personal_selected {
+                eyre::bail!("the personal API requires --rpc.unsafe-signer")
+            }
+            return Ok(signers)
+        }
+
+        // like geth's `--allow-insecure-unlock`, unlocked accounts must not be reachable remotely
+        let exposed = [(self.http, self.http_addr), (self.ws, self.ws_addr)]
+            .into_iter()
+            .any(|(enabled, addr)| enabled && !addr.map_or(false, |addr| addr.is_loopback()));
+        if exposed {
+            eyre::bail!(
+                "--rpc.unsafe-signer requires the http and ws servers to listen on a loopback address, set --http.addr and --ws.addr to 127.0.0.1"
+            )
+        }
+
+        if dev_chain {
+            signers.push(DevSigner::dev_accounts());
+        }
+        if let (Some(dir), Some(password_file)) =
+            (&self.rpc_keystore, &self.rpc_keystore_password_file)
+        {
+            let password = std::fs::read_to_string(password_file).wrap_err_with(|| {
+                format!("failed to read keystore password file {}", password_file.display())
+            })?;
+            let password = password.trim_end_matches(['\r', '\n']);
+            signers.push(DevSigner::from_keystore_dir(dir, password)?);
+        }
+        Ok(signers)
+    }
+
     /// Configures and launches _all_ servers.
     ///
     /// Returns the handles for the launched regular RPC server(s) (if any) and the server handle
@@ -132,10 +302,16 @@ impl RpcServerArgs {
         events: Events,
         engine_api: Engine,
         jwt_secret: JwtSecret,
+        sync_events: broadcast::Sender<SyncEvent>,
+        signers: Vec<DevSigner>,
     ) -> Result<(RpcServerHandle, AuthServerHandle), RpcError>
     where
         Client: BlockProvider
             + HeaderProvider
+            + StageCheckpointProvider
+            + LogIndexProvider
+            + AccountHistoryProvider
+            + PruneProvider
             + StateProviderFactory
             + EvmEnvProvider
             + Clone
@@ -147,17 +323,22 @@ impl RpcServerArgs {
         Events: CanonStateSubscriptions + Clone + 'static,
         Engine: EngineApiServer,
     {
-        let auth_config = self.auth_server_config(jwt_secret)?;
+        let auth_config = self.auth_server_config(jwt_secret, Box::new(executor.clone()))?;
 
-        let (rpc_modules, auth_module) = RpcModuleBuilder::default()
+        let mut builder = RpcModuleBuilder::default()
             .with_client(client)
             .with_pool(pool)
             .with_network(network)
             .with_events(events)
-            .with_executor(executor)
-            .build_with_auth_server(self.transport_rpc_module_config(), engine_api);
+            .with_executor(executor.clone())
+            .with_sync_events(sync_events);
+        for signer in signers {
+            builder = builder.with_signer(signer);
+        }
+        let (rpc_modules, auth_module) =
+            builder.build_with_auth_server(self.transport_rpc_module_config(), engine_api);
 
-        let server_config = self.rpc_server_config();
+        let server_config = self.rpc_server_config().with_executor(Box::new(executor));
         let has_server = server_config.has_server();
         let launch_rpc = rpc_modules.start_server(server_config).inspect(|_| {
             if has_server {
@@ -185,6 +366,10 @@ impl RpcServerArgs {
     where
         Client: BlockProvider
             + HeaderProvider
+            + StageCheckpointProvider
+            + LogIndexProvider
+            + AccountHistoryProvider
+            + PruneProvider
             + StateProviderFactory
             + EvmEnvProvider
             + Clone
@@ -220,6 +405,9 @@ impl RpcServerArgs {
     where
         Client: BlockProvider
             + HeaderProvider
+            + StageCheckpointProvider
+            + LogIndexProvider
+            + AccountHistoryProvider
             + StateProviderFactory
             + EvmEnvProvider
             + Clone
@@ -259,7 +447,41 @@ impl RpcServerArgs {
             config = config.with_ws(self.ws_api.as_ref().unwrap_or(&rpc_modules).clone());
         }
 
-        config
+        let mut filter = EthFilterConfig::default();
+        if let Some(max_logs) = self.rpc_max_logs_per_response {
+            filter.max_logs_per_response = max_logs;
+        }
+        if let Some(max_blocks) = self.rpc_max_blocks_per_filter {
+            filter.max_blocks_per_filter = max_blocks;
+        }
+        if let Some(ttl) = self.rpc_stale_filter_ttl {
+            filter.stale_filter_ttl = Duration::from_secs(ttl);
+        }
+        let mut cache = EthStateCacheConfig::default();
+        if let Some(max_block_bytes) = self.rpc_cache_max_block_bytes {
+            cache.max_block_bytes = max_block_bytes;
+        }
+        if let Some(max_receipt_bytes) = self.rpc_cache_max_receipt_bytes {
+            cache.max_receipt_bytes = max_receipt_bytes;
+        }
+        if let Some(max_env_bytes) = self.rpc_cache_max_env_bytes {
+            cache.max_env_bytes = max_env_bytes;
+        }
+        let eth =
+            EthConfig { cache, filter, gas_oracle: self.gas_price_oracle, ..Default::default() };
+
+        let mut blocking_pool = BlockingTaskPoolConfig::default();
+        if let Some(num_threads) = self.rpc_blocking_threads {
+            blocking_pool.num_threads = num_threads;
+        }
+        if let Some(max_queued_tasks) = self.rpc_max_blocking_tasks {
+            blocking_pool.max_queued_tasks = max_queued_tasks;
+        }
+        if self.rpc_wait_for_blocking_tasks {
+            blocking_pool.rejection_policy = RejectionPolicy::Wait;
+        }
+
+        config.with_config(RpcModuleConfig::builder().eth(eth).blocking_pool(blocking_pool).build())
     }
 
     /// Creates the [RpcServerConfig] from cli args.
@@ -276,6 +498,9 @@ impl RpcServerArgs {
                 .with_http(ServerBuilder::new())
                 .with_http_cors(self.http_corsdomain.clone())
                 .with_ws_cors(self.ws_allowed_origins.clone());
+            if let Some(allowlist) = &self.http_allowlist {
+                config = config.with_http_allowlist(allowlist.clone());
+            }
         }
 
         if self.ws {
@@ -284,6 +509,17 @@ impl RpcServerArgs {
                 self.ws_port.unwrap_or(constants::DEFAULT_WS_RPC_PORT),
             );
             config = config.with_ws_address(socket_address).with_ws(ServerBuilder::new());
+            if let Some(allowlist) = &self.ws_allowlist {
+                config = config.with_ws_allowlist(allowlist.clone());
+            }
+        }
+
+        if let Some((cert, key)) = self.tls_cert.as_ref().zip(self.tls_key.as_ref()) {
+            let mut tls = RpcTlsConfig::new(cert, key);
+            if let Some(client_ca) = &self.tls_client_ca {
+                tls = tls.with_client_ca(client_ca);
+            }
+            config = config.with_tls(tls);
         }
 
         if !self.ipcdisable {
@@ -297,13 +533,25 @@ impl RpcServerArgs {
     }
 
     /// Creates the [AuthServerConfig] from cli args.
-    fn auth_server_config(&self, jwt_secret: JwtSecret) -> Result<AuthServerConfig, RpcError> {
+    fn auth_server_config(
+        &self,
+        jwt_secret: JwtSecret,
+        executor: Box<dyn TaskSpawner>,
+    ) -> Result<AuthServerConfig, RpcError> {
         let address = SocketAddr::new(
             self.auth_addr.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
             self.auth_port.unwrap_or(constants::DEFAULT_AUTH_PORT),
         );
 
-        Ok(AuthServerConfig::builder(jwt_secret).socket_addr(address).build())
+        let mut builder =
+            AuthServerConfig::builder(jwt_secret).socket_addr(address).executor(executor);
+        if let Some(allowlist) = &self.auth_allowlist {
+            builder = builder.allowlist(allowlist.clone());
+        }
+        if let Some(path) = &self.auth_ipcpath {
+            builder = builder.ipc_endpoint(path);
+        }
+        Ok(builder.build())
     }
 }
 
@@ -379,4 +627,206 @@ mod tests {
         );
         assert_eq!(config.ipc_endpoint().unwrap().path(), constants::DEFAULT_IPC_ENDPOINT);
     }
+
+    #[test]
+    fn test_rpc_tls_args() {
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--http",
+            "--rpc.tls-cert",
+            "cert.pem",
+        ]);
+        assert!(args.is_err(), "the private key is required");
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--http",
+            "--rpc.tls-cert",
+            "cert.pem",
+            "--rpc.tls-key",
+            "key.pem",
+            "--rpc.tls-client-ca",
+            "ca.pem",
+        ])
+        .args;
+        assert_eq!(args.tls_client_ca, Some(PathBuf::from("ca.pem")));
+    }
+
+    #[test]
+    fn test_rpc_signer_args() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert!(args.signers(true).unwrap().is_empty());
+
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.unsafe-signer"]).args;
+        assert_eq!(args.signers(true).unwrap().len(), 1);
+        assert!(args.signers(false).unwrap().is_empty());
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.unsafe-signer",
+            "--rpc.keystore",
+            "keystore",
+        ]);
+        assert!(args.is_err(), "the password file is required");
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.keystore",
+            "keystore",
+            "--rpc.keystore-password-file",
+            "password.txt",
+        ]);
+        assert!(args.is_err(), "the signer must be enabled");
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--http",
+            "--http.api",
+            "personal",
+        ])
+        .args;
+        assert!(args.signers(true).is_err(), "the personal API requires the signer");
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--http",
+            "--http.addr",
+            "127.0.0.1",
+            "--http.api",
+            "personal",
+            "--rpc.unsafe-signer",
+        ])
+        .args;
+        assert!(args.signers(true).is_ok());
+        assert!(
+            args.signers(false).is_err(),
+            "the personal API is only available on the dev chain"
+        );
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--http", "--rpc.unsafe-signer"])
+                .args;
+        assert!(args.signers(true).is_err(), "the http server listens on all interfaces");
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--http",
+            "--http.addr",
+            "::1",
+            "--ws",
+            "--ws.addr",
+            "192.168.0.1",
+            "--rpc.unsafe-signer",
+        ])
+        .args;
+        assert!(args.signers(true).is_err(), "the ws server listens on a remote address");
+    }
+
+    #[test]
+    fn test_rpc_allowlist_args() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--http",
+            "--http.allowlist",
+            "127.0.0.1,10.0.0.0/8",
+            "--authrpc.allowlist",
+            "172.16.0.0/12",
+        ])
+        .args;
+        let allowlist = args.http_allowlist.unwrap();
+        assert!(allowlist.contains(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))));
+        assert!(!allowlist.contains(&IpAddr::V4(Ipv4Addr::new(172, 16, 0, 1))));
+        assert!(args.auth_allowlist.unwrap().contains(&IpAddr::V4(Ipv4Addr::new(172, 16, 0, 1))));
+        assert!(args.ws_allowlist.is_none());
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--http.allowlist",
+            "10.0.0.0/33",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_auth_ipc_args() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--authrpc.ipcpath",
+            "/tmp/reth_engine_api.ipc",
+        ])
+        .args;
+        assert_eq!(args.auth_ipcpath.as_deref(), Some("/tmp/reth_engine_api.ipc"));
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--authrpc.ipcpath",
+            "/tmp/reth_engine_api.ipc",
+            "--authrpc.port",
+            "8551",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_log_limits() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--http",
+            "--rpc.max-blocks-per-filter",
+            "10000",
+            "--rpc.stale-filter-ttl",
+            "60",
+        ])
+        .args;
+        let config = args.transport_rpc_module_config();
+        let filter = config.config().unwrap().eth().filter;
+        assert_eq!(filter.max_blocks_per_filter, 10_000);
+        assert_eq!(filter.stale_filter_ttl, Duration::from_secs(60));
+        assert_eq!(filter.max_logs_per_response, EthFilterConfig::default().max_logs_per_response);
+    }
+
+    #[test]
+    fn test_rpc_cache_args() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--http",
+            "--rpc.cache.max-block-bytes",
+            "1000000",
+            "--rpc.cache.max-env-bytes",
+            "1000",
+        ])
+        .args;
+        let config = args.transport_rpc_module_config();
+        let cache = config.config().unwrap().eth().cache.clone();
+        assert_eq!(
+            cache,
+            EthStateCacheConfig {
+                max_block_bytes: 1_000_000,
+                max_env_bytes: 1000,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_rpc_blocking_pool_args() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--http",
+            "--rpc.max-blocking-tasks",
+            "32",
+            "--rpc.wait-for-blocking-tasks",
+        ])
+        .args;
+        let config = args.transport_rpc_module_config();
+        let blocking_pool = *config.config().unwrap().blocking_pool();
+        assert_eq!(
+            blocking_pool,
+            BlockingTaskPoolConfig {
+                max_queued_tasks: 32,
+                rejection_policy: RejectionPolicy::Wait,
+                ..Default::default()
+            }
+        );
+    }
 }
@@ -1,6 +1,7 @@
 use hex::encode as hex_encode;
 use reth_network::config::rng_secret_key;
-use secp256k1::{Error as SecretKeyBaseError, SecretKey};
+use reth_primitives::PeerId;
+use secp256k1::{Error as SecretKeyBaseError, SecretKey, SECP256K1};
 use std::{fs::read_to_string, path::Path};
 use thiserror::Error;
 
@@ -21,21 +22,60 @@ pub fn get_secret_key(secret_key_path: &Path) -> Result<SecretKey, SecretKeyErro
     let exists = secret_key_path.try_exists();
 
     match exists {
-        Ok(true) => {
-            let contents = read_to_string(secret_key_path)?;
-            (contents.as_str().parse::<SecretKey>()).map_err(SecretKeyError::SecretKeyDecodeError)
-        }
+        Ok(true) => read_secret_key(secret_key_path),
         Ok(false) => {
-            if let Some(dir) = secret_key_path.parent() {
-                // Create parent directory
-                std::fs::create_dir_all(dir)?;
-            }
-
             let secret = rng_secret_key();
-            let hex = hex_encode(secret.as_ref());
-            std::fs::write(secret_key_path, hex)?;
+            write_secret_key(secret_key_path, &secret)?;
             Ok(secret)
         }
         Err(e) => Err(SecretKeyError::IOError(e)),
     }
 }
+
+/// Reads a hex encoded [`SecretKey`] from the given file.
+///
+/// Surrounding whitespace and an optional `0x` prefix are ignored, so this also accepts geth
+/// `nodekey` files.
+pub fn read_secret_key(secret_key_path: &Path) -> Result<SecretKey, SecretKeyError> {
+    let contents = read_to_string(secret_key_path)?;
+    let contents = contents.trim();
+    let contents = contents.strip_prefix("0x").unwrap_or(contents);
+    contents.parse::<SecretKey>().map_err(SecretKeyError::SecretKeyDecodeError)
+}
+
+/// Writes the hex encoded [`SecretKey`] to the given file, creating the parent directory if
+/// necessary.
+pub fn write_secret_key(secret_key_path: &Path, secret: &SecretKey) -> Result<(), SecretKeyError> {
+    if let Some(dir) = secret_key_path.parent() {
+        // Create parent directory
+        std::fs::create_dir_all(dir)?;
+    }
+
+    std::fs::write(secret_key_path, hex_encode(secret.as_ref()))?;
+    Ok(())
+}
+
+/// Returns the [`PeerId`] derived from the [`SecretKey`].
+pub fn secret_key_peer_id(secret: &SecretKey) -> PeerId {
+    let pk = secp256k1::PublicKey::from_secret_key(SECP256K1, secret);
+    PeerId::from_slice(&pk.serialize_uncompressed()[1..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_geth_nodekey() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nodekey");
+        let secret = rng_secret_key();
+        std::fs::write(&path, format!("0x{}\n", hex_encode(secret.as_ref()))).unwrap();
+        assert_eq!(read_secret_key(&path).unwrap(), secret);
+
+        // round trips through the reth format
+        let path = dir.path().join("nested").join("secret");
+        write_secret_key(&path, &secret).unwrap();
+        assert_eq!(get_secret_key(&path).unwrap(), secret);
+    }
+}
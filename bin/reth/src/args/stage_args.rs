@@ -12,4 +12,5 @@ pub enum StageEnum {
     Merkle,
     TxLookup,
     History,
+    LogIndex,
 }
@@ -0,0 +1,143 @@
+//! Standalone bootnode command that only runs the discovery services.
+use crate::args::get_secret_key;
+use clap::Parser;
+use futures::StreamExt;
+use reth_discv4::{DiscoveryUpdate, Discv4, Discv4Config};
+use reth_discv5::{Discv5, Discv5Config, DEFAULT_DISCOVERY_V5_PORT};
+use reth_net_nat::NatResolver;
+use reth_primitives::NodeRecord;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+};
+use tracing::{debug, info, warn};
+
+/// The default discv4 port of the bootnode.
+const DEFAULT_BOOTNODE_PORT: u16 = 30301;
+
+/// `reth bootnode` command
+///
+/// Runs the discovery services without syncing, RPC or a database, so other nodes can use this
+/// node to bootstrap their routing tables.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Secret key to use for this node.
+    ///
+    /// This determines the identity of the bootnode. If the file doesn't exist, a new key is
+    /// generated and written to it.
+    #[arg(long, value_name = "PATH")]
+    p2p_secret_key: PathBuf,
+
+    /// The IP address to listen on.
+    #[arg(long, value_name = "ADDR", default_value_t = IpAddr::V4(Ipv4Addr::UNSPECIFIED))]
+    addr: IpAddr,
+
+    /// The UDP port of discv4.
+    #[arg(long, value_name = "PORT", default_value_t = DEFAULT_BOOTNODE_PORT)]
+    port: u16,
+
+    /// NAT resolution method, used to determine the advertised IP address.
+    #[arg(long, default_value = "any")]
+    nat: NatResolver,
+
+    /// Also run discv5.
+    #[arg(long)]
+    enable_discv5: bool,
+
+    /// The UDP port of discv5.
+    #[arg(
+        long = "discovery.v5.port",
+        value_name = "PORT",
+        default_value_t = DEFAULT_DISCOVERY_V5_PORT,
+        requires = "enable_discv5"
+    )]
+    discv5_port: u16,
+
+    /// Comma separated enodes of other bootnodes to connect to.
+    #[arg(long, value_delimiter = ',')]
+    bootnodes: Vec<NodeRecord>,
+}
+
+impl Command {
+    /// Execute `bootnode` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let secret_key = get_secret_key(&self.p2p_secret_key)?;
+
+        let discovery_addr = SocketAddr::new(self.addr, self.port);
+        let mut local_enr = NodeRecord::from_secret_key(discovery_addr, &secret_key);
+        let external_ip = self.nat.external_addr().await;
+        match external_ip {
+            Some(ip) => local_enr.address = ip,
+            None if self.addr.is_unspecified() => {
+                warn!(target: "reth::cli", "Failed to resolve external IP, use --nat extip:<IP>")
+            }
+            None => {}
+        }
+
+        let config = Discv4Config::builder()
+            .external_ip_resolver(Some(self.nat))
+            .add_boot_nodes(self.bootnodes.clone())
+            .build();
+        let (_discv4, mut discv4_service) =
+            Discv4::bind(discovery_addr, local_enr, secret_key, config).await?;
+        let mut updates = discv4_service.update_stream();
+        let _discv4_service = discv4_service.spawn();
+        info!(target: "reth::cli", enode = %local_enr, "Started discv4");
+
+        let _discv5 = if self.enable_discv5 {
+            let config = Discv5Config {
+                discovery_addr: SocketAddr::new(self.addr, self.discv5_port),
+                external_ip,
+                ..Default::default()
+            };
+            let (discv5, discv5_service) = Discv5::bind(local_enr, secret_key, config).await?;
+            let _ = discv5_service.spawn();
+            info!(target: "reth::cli", enr = %discv5.local_enr().to_base64(), "Started discv5");
+            Some(discv5)
+        } else {
+            None
+        };
+
+        while let Some(update) = updates.next().await {
+            match update {
+                DiscoveryUpdate::Added(record) => {
+                    debug!(target: "reth::cli", ?record, "Node added")
+                }
+                DiscoveryUpdate::Removed(peer_id) => {
+                    debug!(target: "reth::cli", ?peer_id, "Node removed")
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bootnode_args() {
+        let cmd = Command::try_parse_from(["reth", "--p2p-secret-key", "key"]).unwrap();
+        assert_eq!(cmd.port, DEFAULT_BOOTNODE_PORT);
+        assert!(!cmd.enable_discv5);
+
+        let cmd = Command::try_parse_from([
+            "reth",
+            "--p2p-secret-key",
+            "key",
+            "--nat",
+            "extip:1.2.3.4",
+            "--enable-discv5",
+            "--discovery.v5.port",
+            "9001",
+        ])
+        .unwrap();
+        assert_eq!(cmd.nat, NatResolver::ExternalIp("1.2.3.4".parse().unwrap()));
+        assert_eq!(cmd.discv5_port, 9001);
+
+        // the key is required so the identity of the bootnode is stable
+        assert!(Command::try_parse_from(["reth"]).is_err());
+    }
+}
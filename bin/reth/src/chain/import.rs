@@ -1,6 +1,7 @@
 use crate::{
     dirs::{DataDirPath, MaybePlatformPath},
     node::events::{handle_events, NodeEvent},
+    utils::load_header_accumulator,
 };
 use clap::{crate_version, Parser};
 use eyre::Context;
@@ -55,6 +56,8 @@ pub struct ImportCommand {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - dev
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
@@ -70,6 +73,13 @@ pub struct ImportCommand {
     /// remaining stages are executed.
     #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
     path: PathBuf,
+
+    /// Path to the SSZ encoded accumulator of pre-merge headers.
+    ///
+    /// If set, the pre-merge headers are verified against the accumulator epoch by epoch. On
+    /// mainnet the root of the accumulator must match the known root.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    header_accumulator: Option<PathBuf>,
 }
 
 impl ImportCommand {
@@ -150,6 +160,14 @@ impl ImportCommand {
         let (tip_tx, tip_rx) = watch::channel(H256::zero());
         let factory = reth_revm::Factory::new(self.chain.clone());
 
+        let mut total_difficulty_stage = TotalDifficultyStage::new(consensus.clone())
+            .with_commit_threshold(config.stages.total_difficulty.commit_threshold);
+        if let Some(path) = &self.header_accumulator {
+            let accumulator = load_header_accumulator(path, &self.chain)?;
+            total_difficulty_stage =
+                total_difficulty_stage.with_header_accumulator(Arc::new(accumulator));
+        }
+
         let mut pipeline = Pipeline::builder()
             .with_tip_sender(tip_tx)
             // we want to sync all blocks the file client provides or 0 if empty
@@ -164,10 +182,7 @@ impl ImportCommand {
                     NoopStatusUpdater::default(),
                     factory.clone(),
                 )
-                .set(
-                    TotalDifficultyStage::new(consensus.clone())
-                        .with_commit_threshold(config.stages.total_difficulty.commit_threshold),
-                )
+                .set(total_difficulty_stage)
                 .set(SenderRecoveryStage {
                     commit_threshold: config.stages.sender_recovery.commit_threshold,
                 })
@@ -193,7 +208,7 @@ mod tests {
 
     #[test]
     fn parse_common_import_command_chain_args() {
-        for chain in ["mainnet", "sepolia", "goerli"] {
+        for chain in ["mainnet", "sepolia", "goerli", "holesky", "dev"] {
             let args: ImportCommand = ImportCommand::parse_from(["reth", "--chain", chain, "."]);
             assert_eq!(args.chain.chain, chain.parse().unwrap());
         }
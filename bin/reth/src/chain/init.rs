@@ -34,6 +34,8 @@ pub struct InitCommand {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - dev
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
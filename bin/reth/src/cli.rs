@@ -1,10 +1,10 @@
 //! CLI definition and entrypoint to executable
 use crate::{
-    chain, config, db,
+    bootnode, chain, config, db,
     dirs::{LogsDir, PlatformPath},
-    drop_stage, dump_stage, merkle_debug, node, p2p,
+    dns_tree, doctor, drop_stage, dump_stage, export, merkle_debug, node, p2p,
     runner::CliRunner,
-    stage, test_eth_chain, test_vectors,
+    sentry, snapshot, stage, test_eth_chain, test_vectors,
 };
 use clap::{ArgAction, Args, Parser, Subcommand};
 use reth_tracing::{
@@ -38,9 +38,16 @@ pub fn run() -> eyre::Result<()> {
             runner.run_until_ctrl_c(command.execute())
         }
         Commands::DropStage(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+        Commands::Export(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+        Commands::Snapshot(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+        Commands::DownloadSnapshot(command) => runner.run_blocking_until_ctrl_c(command.execute()),
         Commands::P2P(command) => runner.run_until_ctrl_c(command.execute()),
         Commands::TestVectors(command) => runner.run_until_ctrl_c(command.execute()),
         Commands::TestEthChain(command) => runner.run_until_ctrl_c(command.execute()),
+        Commands::Doctor(command) => runner.run_until_ctrl_c(command.execute()),
+        Commands::DnsTree(command) => runner.run_until_ctrl_c(command.execute()),
+        Commands::Bootnode(command) => runner.run_until_ctrl_c(command.execute()),
+        Commands::Sentry(command) => runner.run_until_ctrl_c(command.execute()),
         Commands::Config(command) => runner.run_until_ctrl_c(command.execute()),
         Commands::MerkleDebug(command) => runner.run_until_ctrl_c(command.execute()),
     }
@@ -75,6 +82,21 @@ pub enum Commands {
     /// Drops a stage's tables from the database.
     #[command(name = "drop-stage")]
     DropStage(drop_stage::Command),
+    /// Exports chain data into analytics-friendly files.
+    #[command(name = "export")]
+    Export(export::Command),
+    /// Creates and serves snapshots that other nodes can bootstrap from.
+    #[command(name = "snapshot")]
+    Snapshot(snapshot::Command),
+    /// Downloads a snapshot and imports it into a new database.
+    #[command(name = "download-snapshot")]
+    DownloadSnapshot(snapshot::DownloadCommand),
+    /// Runs only the discovery services, for bootnode deployments
+    #[command(name = "bootnode")]
+    Bootnode(bootnode::Command),
+    /// Runs only the p2p network, for nodes that download blocks through it
+    #[command(name = "sentry")]
+    Sentry(sentry::Command),
     /// P2P Debugging utilities
     #[command(name = "p2p")]
     P2P(p2p::Command),
@@ -84,6 +106,12 @@ pub enum Commands {
     /// Generate Test Vectors
     #[command(name = "test-vectors")]
     TestVectors(test_vectors::Command),
+    /// Checks the data dir and the configuration for common problems
+    #[command(name = "doctor")]
+    Doctor(doctor::Command),
+    /// Builds and signs EIP-1459 node lists for DNS discovery
+    #[command(name = "dns-tree")]
+    DnsTree(dns_tree::Command),
     /// Write config to stdout
     #[command(name = "config")]
     Config(config::Command),
@@ -0,0 +1,361 @@
+use clap::Parser;
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    models::{storage_sharded_key::StorageShardedKey, BlockNumberAddress},
+    tables,
+    transaction::DbTx,
+};
+use reth_primitives::{Account, Address, BlockNumber, H256, U256};
+use reth_stages::stages::{EXECUTION, INDEX_ACCOUNT_HISTORY, INDEX_STORAGE_HISTORY};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+use tracing::info;
+
+/// The arguments for the `reth db dump-state` command
+#[derive(Parser, Debug)]
+pub struct DumpStateArgs {
+    /// The block at which the state is dumped.
+    #[arg(long, value_name = "BLOCK")]
+    block: BlockNumber,
+
+    /// The file the state is written to.
+    ///
+    /// The first line contains the block number and state root, followed by one JSON object per
+    /// account, ordered by address.
+    #[arg(long, short, value_name = "FILE", verbatim_doc_comment)]
+    output: PathBuf,
+}
+
+impl DumpStateArgs {
+    /// Reconstructs the state at the configured block and writes it to the output file.
+    ///
+    /// The value of an account or storage slot at the block is the value before its first change
+    /// after the block, which is recorded in the changeset of that change, or the latest plain
+    /// state if it didn't change since. The plain state and the history indices are walked in
+    /// address order, so only the changes of the blocks the history indices don't cover yet are
+    /// held in memory.
+    pub(crate) fn execute<DB: Database>(&self, db: &DB) -> eyre::Result<()> {
+        let tx = db.tx()?;
+
+        let executed = EXECUTION.get_progress(&tx)?.unwrap_or_default();
+        eyre::ensure!(
+            self.block <= executed,
+            "block {} is not executed yet, the execution checkpoint is {executed}",
+            self.block
+        );
+        let Some(hash) = tx.get::<tables::CanonicalHeaders>(self.block)? else {
+            eyre::bail!("block {} not found", self.block)
+        };
+        let Some(header) = tx.get::<tables::Headers>(self.block)? else {
+            eyre::bail!("header of block {} not found", self.block)
+        };
+
+        // the changes of the blocks after the index checkpoints are only in the changesets, the
+        // first changeset entry after the block holds the value at the block
+        let account_index = INDEX_ACCOUNT_HISTORY.get_progress(&tx)?.unwrap_or_default();
+        let mut unindexed_accounts = BTreeMap::<Address, Option<Account>>::new();
+        let from = self.block.max(account_index) + 1;
+        for entry in tx.cursor_read::<tables::AccountChangeSet>()?.walk(Some(from))? {
+            let (_, before) = entry?;
+            unindexed_accounts.entry(before.address).or_insert(before.info);
+        }
+        let storage_index = INDEX_STORAGE_HISTORY.get_progress(&tx)?.unwrap_or_default();
+        let mut unindexed_storage = BTreeMap::<Address, BTreeMap<H256, U256>>::new();
+        let from = BlockNumberAddress((self.block.max(storage_index) + 1, Address::zero()));
+        for entry in tx.cursor_read::<tables::StorageChangeSet>()?.walk(Some(from))? {
+            let (BlockNumberAddress((_, address)), before) = entry?;
+            unindexed_storage.entry(address).or_default().entry(before.key).or_insert(before.value);
+        }
+        info!(
+            target: "reth::cli",
+            accounts = unindexed_accounts.len(),
+            storages = unindexed_storage.len(),
+            "Collected unindexed changes after block {}",
+            self.block
+        );
+
+        let mut out = BufWriter::new(File::create(&self.output)?);
+        serde_json::to_writer(
+            &mut out,
+            &DumpHeader { block: self.block, hash, root: header.state_root },
+        )?;
+        writeln!(out)?;
+
+        let mut plain_accounts = tx.cursor_read::<tables::PlainAccountState>()?;
+        let mut account_history = tx.cursor_read::<tables::AccountHistory>()?;
+        let mut account_changesets = tx.cursor_dup_read::<tables::AccountChangeSet>()?;
+        let mut plain_storage = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        let mut storage_history = tx.cursor_read::<tables::StorageHistory>()?;
+        let mut storage_changesets = tx.cursor_dup_read::<tables::StorageChangeSet>()?;
+
+        let mut next_plain = plain_accounts.first()?;
+        let mut next_history = account_history.first()?;
+        let mut next_storage_history = storage_history.first()?;
+        let mut unindexed_accounts = unindexed_accounts.into_iter().peekable();
+
+        // merge the accounts of the latest state with the accounts that changed, all are ordered
+        // by address
+        let mut accounts = 0usize;
+        loop {
+            let Some(address) = [
+                next_plain.as_ref().map(|(address, _)| *address),
+                next_history.as_ref().map(|(key, _)| key.key),
+                unindexed_accounts.peek().map(|(address, _)| *address),
+            ]
+            .into_iter()
+            .flatten()
+            .min() else {
+                break
+            };
+
+            let mut account = None;
+            if next_plain.as_ref().map_or(false, |(plain, _)| *plain == address) {
+                account = next_plain.take().map(|(_, latest)| latest);
+                next_plain = plain_accounts.next()?;
+            }
+
+            // the first indexed change after the block
+            let mut changed_at = None;
+            while let Some((key, list)) = next_history.take() {
+                if key.key != address {
+                    next_history = Some((key, list));
+                    break
+                }
+                if changed_at.is_none() && key.highest_block_number > self.block {
+                    changed_at = list.0.enable_rank().successor(self.block as usize + 1);
+                }
+                next_history = account_history.next()?;
+            }
+            let unindexed = unindexed_accounts
+                .next_if(|(changed, _)| *changed == address)
+                .map(|(_, info)| info);
+
+            if let Some(block) = changed_at {
+                let block = block as BlockNumber;
+                account = account_changesets
+                    .seek_by_key_subkey(block, address)?
+                    .filter(|before| before.address == address)
+                    .ok_or_else(|| {
+                        eyre::eyre!("account changeset of {address:?} in block {block} not found")
+                    })?
+                    .info;
+            } else if let Some(before) = unindexed {
+                account = before;
+            }
+
+            // skip the storage history of accounts without any other changes
+            if next_storage_history.as_ref().map_or(false, |(key, _)| key.address < address) {
+                next_storage_history =
+                    storage_history.seek(StorageShardedKey::new(address, H256::zero(), 0))?;
+            }
+
+            let mut storage = BTreeMap::new();
+            let mut walker = plain_storage.walk_dup(Some(address), None)?;
+            while let Some((key, entry)) = walker.next().transpose()? {
+                if key != address {
+                    break
+                }
+                storage.insert(entry.key, entry.value);
+            }
+
+            // the storage slots whose value at the block was found in the history index
+            let mut indexed_slots = HashSet::new();
+            while let Some((key, list)) = next_storage_history.take() {
+                if key.address != address {
+                    next_storage_history = Some((key, list));
+                    break
+                }
+                let slot = key.sharded_key.key;
+                if key.sharded_key.highest_block_number > self.block &&
+                    !indexed_slots.contains(&slot)
+                {
+                    if let Some(block) = list.0.enable_rank().successor(self.block as usize + 1) {
+                        let block = block as BlockNumber;
+                        let before = storage_changesets
+                            .seek_by_key_subkey((block, address).into(), slot)?
+                            .filter(|entry| entry.key == slot)
+                            .ok_or_else(|| {
+                                eyre::eyre!(
+                                    "storage changeset of {slot:?} in block {block} not found"
+                                )
+                            })?;
+                        indexed_slots.insert(slot);
+                        set_storage(&mut storage, slot, before.value);
+                    }
+                }
+                next_storage_history = storage_history.next()?;
+            }
+            for (slot, value) in unindexed_storage.remove(&address).unwrap_or_default() {
+                if !indexed_slots.contains(&slot) {
+                    set_storage(&mut storage, slot, value);
+                }
+            }
+
+            // the account did not exist at the block
+            let Some(account) = account else { continue };
+
+            let dump = DumpAccount {
+                address,
+                nonce: account.nonce,
+                balance: account.balance,
+                code_hash: account.bytecode_hash,
+                storage,
+            };
+            serde_json::to_writer(&mut out, &dump)?;
+            writeln!(out)?;
+            accounts += 1;
+        }
+        out.flush()?;
+
+        info!(target: "reth::cli", accounts, path = %self.output.display(), "State dumped");
+        Ok(())
+    }
+}
+
+/// Sets the value of the storage slot, zero values are removed.
+fn set_storage(storage: &mut BTreeMap<H256, U256>, slot: H256, value: U256) {
+    if value == U256::ZERO {
+        storage.remove(&slot);
+    } else {
+        storage.insert(slot, value);
+    }
+}
+
+/// The first line of the state dump.
+#[derive(Debug, Serialize)]
+struct DumpHeader {
+    block: BlockNumber,
+    hash: H256,
+    root: H256,
+}
+
+/// An account of the state dump.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DumpAccount {
+    address: Address,
+    nonce: u64,
+    balance: U256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code_hash: Option<H256>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    storage: BTreeMap<H256, U256>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{
+        mdbx::{test_utils::create_test_db, Env, EnvKind, WriteMap},
+        models::{AccountBeforeTx, ShardedKey},
+        transaction::DbTxMut,
+        BlockNumberList,
+    };
+    use reth_primitives::{Header, StorageEntry};
+    use std::sync::Arc;
+
+    const EXISTING: Address = Address::repeat_byte(1);
+    const CREATED: Address = Address::repeat_byte(2);
+    const SLOT: H256 = H256::repeat_byte(1);
+
+    /// Creates a database in which `EXISTING` was updated and `CREATED` was created in block 2.
+    fn test_db(indexed: bool) -> Arc<Env<WriteMap>> {
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let account = |balance: u64| Account { balance: U256::from(balance), ..Default::default() };
+
+        db.update(|tx| {
+            for number in 0..=2 {
+                tx.put::<tables::CanonicalHeaders>(number, H256::from_low_u64_be(number)).unwrap();
+                tx.put::<tables::Headers>(number, Header { number, ..Default::default() }).unwrap();
+            }
+            EXECUTION.save_progress(tx, 2).unwrap();
+            tx.put::<tables::PlainAccountState>(EXISTING, account(2)).unwrap();
+            tx.put::<tables::PlainAccountState>(CREATED, account(3)).unwrap();
+            tx.put::<tables::PlainStorageState>(
+                EXISTING,
+                StorageEntry { key: SLOT, value: U256::from(2) },
+            )
+            .unwrap();
+
+            tx.put::<tables::AccountChangeSet>(
+                2,
+                AccountBeforeTx { address: EXISTING, info: Some(account(1)) },
+            )
+            .unwrap();
+            tx.put::<tables::AccountChangeSet>(2, AccountBeforeTx { address: CREATED, info: None })
+                .unwrap();
+            tx.put::<tables::StorageChangeSet>(
+                BlockNumberAddress((2, EXISTING)),
+                StorageEntry { key: SLOT, value: U256::from(1) },
+            )
+            .unwrap();
+
+            if indexed {
+                for address in [EXISTING, CREATED] {
+                    tx.put::<tables::AccountHistory>(
+                        ShardedKey::new(address, u64::MAX),
+                        BlockNumberList::new([2]).unwrap(),
+                    )
+                    .unwrap();
+                }
+                tx.put::<tables::StorageHistory>(
+                    StorageShardedKey::new(EXISTING, SLOT, u64::MAX),
+                    BlockNumberList::new([2]).unwrap(),
+                )
+                .unwrap();
+                INDEX_ACCOUNT_HISTORY.save_progress(tx, 2).unwrap();
+                INDEX_STORAGE_HISTORY.save_progress(tx, 2).unwrap();
+            }
+        })
+        .unwrap();
+        db
+    }
+
+    fn dump_state(db: &Env<WriteMap>, block: BlockNumber) -> eyre::Result<Vec<serde_json::Value>> {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("state.jsonl");
+        DumpStateArgs { block, output: output.clone() }.execute(db)?;
+        let dump = std::fs::read_to_string(output).unwrap();
+        Ok(dump.lines().map(|line| serde_json::from_str(line).unwrap()).collect())
+    }
+
+    #[test]
+    fn dump_state_at_historical_block() {
+        for indexed in [false, true] {
+            let lines = dump_state(&test_db(indexed), 1).unwrap();
+            assert_eq!(lines.len(), 2, "indexed: {indexed}");
+            let account = &lines[1];
+            assert_eq!(account["address"], serde_json::json!(EXISTING));
+            assert_eq!(account["balance"], serde_json::json!(U256::from(1)));
+            assert_eq!(account["storage"][format!("{SLOT:?}")], serde_json::json!(U256::from(1)));
+        }
+    }
+
+    #[test]
+    fn dump_latest_state() {
+        for indexed in [false, true] {
+            let lines = dump_state(&test_db(indexed), 2).unwrap();
+            assert_eq!(lines.len(), 3, "indexed: {indexed}");
+            assert_eq!(lines[1]["storage"][format!("{SLOT:?}")], serde_json::json!(U256::from(2)));
+            assert_eq!(lines[2]["address"], serde_json::json!(CREATED));
+        }
+    }
+
+    #[test]
+    fn reject_unexecuted_block() {
+        let db = test_db(false);
+        db.update(|tx| {
+            tx.put::<tables::CanonicalHeaders>(3, H256::from_low_u64_be(3)).unwrap();
+            tx.put::<tables::Headers>(3, Header { number: 3, ..Default::default() }).unwrap();
+        })
+        .unwrap();
+        let err = dump_state(&db, 3).unwrap_err();
+        assert!(err.to_string().contains("not executed"), "{err}");
+    }
+}
@@ -16,6 +16,9 @@ use tracing::error;
 /// DB List TUI
 mod tui;
 
+mod dump_state;
+pub use dump_state::DumpStateArgs;
+
 /// `reth db` command
 #[derive(Debug, Parser)]
 pub struct Command {
@@ -42,6 +45,8 @@ pub struct Command {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - dev
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
@@ -72,6 +77,8 @@ pub enum Subcommands {
     },
     /// Deletes all database entries
     Drop,
+    /// Writes the full plain state at a historical block to a file
+    DumpState(DumpStateArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -215,12 +222,18 @@ impl Command {
                     StoragesTrie,
                     TxSenders,
                     SyncStage,
-                    SyncStageProgress
+                    SyncStageProgress,
+                    LogAddressIndex,
+                    LogTopicIndex,
+                    PruneCheckpoints
                 ]);
             }
             Subcommands::Drop => {
                 tool.drop(db_path)?;
             }
+            Subcommands::DumpState(args) => {
+                args.execute(tool.db)?;
+            }
         }
 
         Ok(())
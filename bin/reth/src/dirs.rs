@@ -6,7 +6,27 @@ use std::{
     fmt::{Debug, Display, Formatter},
     path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
 };
+use tracing::warn;
+
+/// The data dir value that selects an ephemeral data directory.
+///
+/// This does not keep the database in memory: all files, including the database, are written to a
+/// new temporary directory on disk that is removed once the node exits. Only `reth node` accepts
+/// it, see [MaybeEphemeralPath], the data dirs of all other commands reject it when parsed.
+pub const EPHEMERAL_DATA_DIR: &str = ":memory:";
+
+/// Errors when parsing a data dir.
+#[derive(Debug, thiserror::Error)]
+pub enum DataDirParseError {
+    /// An environment variable in the path is not set.
+    #[error(transparent)]
+    Lookup(#[from] shellexpand::LookupError<VarError>),
+    /// The ephemeral data dir was given to a command that doesn't support it.
+    #[error("the ephemeral data dir `{EPHEMERAL_DATA_DIR}` is only supported by `reth node`")]
+    Ephemeral,
+}
 
 /// Constructs a string to be used as a path for configuration and db paths.
 pub fn config_path_prefix(chain: Chain) -> String {
@@ -16,6 +36,10 @@ pub fn config_path_prefix(chain: Chain) -> String {
         "goerli".to_string()
     } else if chain == Chain::sepolia() {
         "sepolia".to_string()
+    } else if chain == Chain::holesky() {
+        "holesky".to_string()
+    } else if chain == Chain::dev() {
+        "dev".to_string()
     } else {
         chain.id().to_string()
     }
@@ -187,6 +211,7 @@ impl<D: XdgPath> MaybePlatformPath<D> {
         ChainPath(
             self.0.clone().unwrap_or_else(|| PlatformPath::default().with_chain(chain).0),
             chain,
+            None,
         )
     }
 
@@ -216,7 +241,7 @@ impl<D> Default for MaybePlatformPath<D> {
 }
 
 impl<D> FromStr for MaybePlatformPath<D> {
-    type Err = shellexpand::LookupError<VarError>;
+    type Err = DataDirParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let p = match s {
@@ -225,28 +250,110 @@ impl<D> FromStr for MaybePlatformPath<D> {
                 // computes the default value via `Default -> Display -> FromStr`
                 None
             }
+            EPHEMERAL_DATA_DIR => return Err(DataDirParseError::Ephemeral),
             _ => Some(PlatformPath::from_str(s)?),
         };
         Ok(Self(p))
     }
 }
 
+/// A [MaybePlatformPath] that also accepts the [EPHEMERAL_DATA_DIR].
+#[derive(Clone, Debug, PartialEq)]
+pub enum MaybeEphemeralPath<D> {
+    /// A new temporary directory.
+    Ephemeral,
+    /// The given path or the default path.
+    Path(MaybePlatformPath<D>),
+}
+
+// === impl MaybeEphemeralPath ===
+
+impl<D: XdgPath> MaybeEphemeralPath<D> {
+    /// Returns the path for the given chain like [MaybePlatformPath::unwrap_or_chain_default].
+    ///
+    /// If this is the [EPHEMERAL_DATA_DIR] a new temporary directory is created instead, which is
+    /// removed once the returned [ChainPath] and all its clones are dropped.
+    pub fn ephemeral_or_chain_default(&self, chain: Chain) -> std::io::Result<ChainPath<D>> {
+        match self {
+            Self::Ephemeral => {
+                let dir = tempfile::Builder::new().prefix("reth-").tempdir()?.into_path();
+                let path = PlatformPath(dir.clone(), std::marker::PhantomData);
+                Ok(ChainPath(path, chain, Some(Arc::new(EphemeralDir(dir)))))
+            }
+            Self::Path(path) => Ok(path.unwrap_or_chain_default(chain)),
+        }
+    }
+
+    /// Returns true if this is the [EPHEMERAL_DATA_DIR].
+    pub fn is_ephemeral(&self) -> bool {
+        matches!(self, Self::Ephemeral)
+    }
+}
+
+impl<D: XdgPath> Display for MaybeEphemeralPath<D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ephemeral => f.write_str(EPHEMERAL_DATA_DIR),
+            Self::Path(path) => path.fmt(f),
+        }
+    }
+}
+
+impl<D> Default for MaybeEphemeralPath<D> {
+    fn default() -> Self {
+        Self::Path(MaybePlatformPath::default())
+    }
+}
+
+impl<D> FromStr for MaybeEphemeralPath<D> {
+    type Err = DataDirParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == EPHEMERAL_DATA_DIR {
+            return Ok(Self::Ephemeral)
+        }
+        Ok(Self::Path(MaybePlatformPath::from_str(s)?))
+    }
+}
+
+/// A temporary data dir that is removed when dropped, which logs if the directory can't be
+/// removed.
+#[derive(Debug)]
+struct EphemeralDir(PathBuf);
+
+impl Drop for EphemeralDir {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_dir_all(&self.0) {
+            warn!(target: "reth::cli", path = ?self.0, %err, "Failed to remove the ephemeral data dir");
+        }
+    }
+}
+
 /// Wrapper type around PlatformPath that includes a `Chain`, used for separating reth data for
 /// different networks.
 ///
-/// If the chain is either mainnet, goerli, or sepolia, then the path will be:
+/// If the chain is either mainnet, goerli, sepolia, holesky or dev, then the path will be:
 ///  * mainnet: `<DIR>/mainnet`
 ///  * goerli: `<DIR>/goerli`
 ///  * sepolia: `<DIR>/sepolia`
+///  * holesky: `<DIR>/holesky`
+///  * dev: `<DIR>/dev`
 /// Otherwise, the path will be dependent on the chain ID:
 ///  * `<DIR>/<CHAIN_ID>`
-#[derive(Clone, Debug, PartialEq)]
-pub struct ChainPath<D>(PlatformPath<D>, Chain);
+///
+/// An ephemeral `ChainPath` keeps its temporary directory alive until all clones are dropped.
+#[derive(Clone, Debug)]
+pub struct ChainPath<D>(PlatformPath<D>, Chain, Option<Arc<EphemeralDir>>);
 
 impl<D> ChainPath<D> {
     /// Returns a new `ChainPath` given a `PlatformPath` and a `Chain`.
     pub fn new(path: PlatformPath<D>, chain: Chain) -> Self {
-        Self(path, chain)
+        Self(path, chain, None)
+    }
+
+    /// Returns true if this path points to a temporary directory that is removed on drop.
+    pub fn is_ephemeral(&self) -> bool {
+        self.2.is_some()
     }
 
     /// Returns the path to the db directory for this chain.
@@ -273,6 +380,28 @@ impl<D> ChainPath<D> {
     pub fn jwt_path(&self) -> PathBuf {
         self.0.join("jwt.hex").into()
     }
+
+    /// Returns the path to the jwtsecret file of the sentry service for this chain.
+    pub fn sentry_jwt_path(&self) -> PathBuf {
+        self.0.join("sentry-jwt.hex").into()
+    }
+
+    /// Returns the path to the directory the blob sidecars of mined transactions are stored in
+    /// for this chain.
+    pub fn blobstore_path(&self) -> PathBuf {
+        self.0.join("blobstore").into()
+    }
+
+    /// Returns the path to the directory downloaded snapshots are stored in for this chain.
+    pub fn snapshot_path(&self) -> PathBuf {
+        self.0.join("snapshot").into()
+    }
+}
+
+impl<D: PartialEq> PartialEq for ChainPath<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 && self.1 == other.1
+    }
 }
 
 impl<D> AsRef<Path> for ChainPath<D> {
@@ -310,4 +439,25 @@ mod tests {
         let path = path.unwrap_or_chain_default(Chain::mainnet());
         assert!(path.as_ref().ends_with("my/path/to/datadir"), "{:?}", path);
     }
+
+    #[test]
+    fn test_ephemeral_data_dir_path() {
+        // only accepted where the data dir can be ephemeral
+        assert!(matches!(
+            MaybePlatformPath::<DataDirPath>::from_str(EPHEMERAL_DATA_DIR),
+            Err(DataDirParseError::Ephemeral)
+        ));
+
+        let path = MaybeEphemeralPath::<DataDirPath>::from_str(EPHEMERAL_DATA_DIR).unwrap();
+        assert!(path.is_ephemeral());
+        assert_eq!(path.to_string(), EPHEMERAL_DATA_DIR);
+
+        let data_dir = path.ephemeral_or_chain_default(Chain::mainnet()).unwrap();
+        assert!(data_dir.is_ephemeral());
+        let dir = data_dir.as_ref().to_path_buf();
+        assert!(dir.exists());
+
+        drop(data_dir);
+        assert!(!dir.exists());
+    }
 }
@@ -0,0 +1,213 @@
+//! Command for building and signing [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) node lists
+//! that can be published via DNS.
+use crate::args::read_secret_key;
+use clap::{Parser, Subcommand, ValueEnum};
+use reth_dns_discovery::{
+    tree::{LinkEntry, NodeEntry},
+    DnsTree,
+};
+use secp256k1::SecretKey;
+use std::{io::Write, path::PathBuf};
+use tracing::info;
+
+/// The maximum length of a single string of a TXT record.
+const MAX_TXT_STRING_LEN: usize = 255;
+
+/// `reth dns-tree` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth dns-tree` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands {
+    /// Builds and signs the tree of a node list and prints its TXT records.
+    Publish(PublishCommand),
+}
+
+impl Command {
+    /// Execute `dns-tree` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::Publish(command) => command.execute(),
+        }
+    }
+}
+
+/// The format of the emitted TXT records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum RecordFormat {
+    /// Lines of a DNS zone file.
+    #[default]
+    Zone,
+    /// A JSON object of the record names and their content.
+    Json,
+}
+
+/// Builds the merkle tree of a node list, signs its root and emits the TXT records to publish.
+///
+/// The records are meant to be uploaded to the DNS provider of the domain, clients sync the list
+/// from the link that is logged after the records were written.
+#[derive(Debug, Parser)]
+pub struct PublishCommand {
+    /// The file with the ENRs of the nodes, one per line.
+    ///
+    /// Empty lines and lines starting with `#` are ignored. Nodes must be given as ENRs, because
+    /// the records are signed by the nodes themselves, enodes can't be published.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    nodes: PathBuf,
+
+    /// The file with the hex encoded secret key that signs the tree.
+    #[arg(long, value_name = "FILE")]
+    key: PathBuf,
+
+    /// The domain the tree is published under, e.g. `nodes.example.org`.
+    #[arg(long, value_name = "DOMAIN")]
+    domain: String,
+
+    /// The sequence number of the tree.
+    ///
+    /// Must be higher than the sequence number of the currently published tree, otherwise clients
+    /// ignore the update.
+    #[arg(long, value_name = "SEQ")]
+    seq: u64,
+
+    /// Comma separated links to other trees, e.g. `enrtree://<key>@nodes.example.org`.
+    #[arg(long, value_name = "ENRTREE", value_delimiter = ',')]
+    links: Vec<LinkEntry>,
+
+    /// The format of the records.
+    #[arg(long, value_enum, default_value_t)]
+    format: RecordFormat,
+
+    /// The TTL of the records in the zone format, in seconds.
+    #[arg(long, value_name = "SECONDS", default_value_t = 1800)]
+    ttl: u32,
+
+    /// The file to write the records to. Defaults to stdout.
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+impl PublishCommand {
+    /// Execute `dns-tree publish` command
+    pub fn execute(self) -> eyre::Result<()> {
+        let key = read_secret_key(&self.key)?;
+        let nodes = parse_nodes(&std::fs::read_to_string(&self.nodes)?)?;
+        let domain = self.domain.trim_end_matches('.');
+        eyre::ensure!(!domain.is_empty(), "domain must not be empty");
+
+        let num_nodes = nodes.len();
+        let tree = build_tree(nodes, self.links.clone(), self.seq, &key);
+        let records = tree.txt_records(domain);
+        let out = match self.format {
+            RecordFormat::Zone => zone_records(&records, self.ttl),
+            RecordFormat::Json => {
+                let records = records.into_iter().collect::<serde_json::Map<_, _>>();
+                serde_json::to_string_pretty(&records)? + "\n"
+            }
+        };
+
+        match &self.output {
+            Some(path) => std::fs::write(path, out)?,
+            None => std::io::stdout().write_all(out.as_bytes())?,
+        }
+
+        let link = tree.link(domain);
+        info!(target: "reth::cli", nodes = num_nodes, seq = self.seq, %link, "Signed DNS tree");
+        Ok(())
+    }
+}
+
+/// Parses the ENRs of the node list, one per line.
+fn parse_nodes(input: &str) -> eyre::Result<Vec<NodeEntry<SecretKey>>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_num, line)| {
+            eyre::ensure!(
+                !line.starts_with("enode://"),
+                "line {line_num}: enodes can't be published, the ENR of the node is required"
+            );
+            line.parse().map_err(|err| eyre::eyre!("line {line_num}: invalid ENR: {err}"))
+        })
+        .collect()
+}
+
+fn build_tree(
+    nodes: Vec<NodeEntry<SecretKey>>,
+    links: Vec<LinkEntry>,
+    seq: u64,
+    key: &SecretKey,
+) -> DnsTree {
+    DnsTree::new(nodes.into_iter().map(|node| node.enr).collect(), links, seq, key)
+}
+
+/// Formats the records as lines of a zone file.
+///
+/// Contents longer than a single TXT string are split into multiple strings.
+fn zone_records(records: &[(String, String)], ttl: u32) -> String {
+    records
+        .iter()
+        .map(|(name, content)| {
+            let strings = content
+                .as_bytes()
+                .chunks(MAX_TXT_STRING_LEN)
+                .map(|chunk| format!("\"{}\"", String::from_utf8_lossy(chunk)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("{name}. {ttl} IN TXT {strings}\n")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_dns_discovery::tree::{DnsEntry, TreeRootEntry};
+    use reth_network::config::rng_secret_key;
+
+    /// Reads the records of a zone file written by [zone_records].
+    fn parse_zone_records(zone: &str) -> Vec<(String, String)> {
+        zone.lines()
+            .map(|line| {
+                let (name, rest) = line.split_once(' ').unwrap();
+                let (_, strings) = rest.split_once("TXT ").unwrap();
+                let content = strings.split("\" \"").collect::<String>().replace('"', "");
+                (name.trim_end_matches('.').to_string(), content)
+            })
+            .collect()
+    }
+
+    const ENR: &str = "enr:-HW4QES8QIeXTYlDzbfr1WEzE-XKY4f8gJFJzjJL-9D7TC9lJb4Z3JPRRz1lP4pL_N_QpT6rGQjAU9Apnc-C1iMP36OAgmlkgnY0iXNlY3AyNTZrMaED5IdwfMxdmR8W37HqSFdQLjDkIwBd4Q_MjxgZifgKSdM";
+
+    #[test]
+    fn parse_node_list() {
+        let nodes = parse_nodes(&format!("# bootnodes\n\n{ENR}\n  {ENR}  \n")).unwrap();
+        assert_eq!(nodes.len(), 2);
+
+        let err = parse_nodes("enode://6f8a80d1@10.3.58.6:30303").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+        assert!(parse_nodes("enr:invalid").is_err());
+    }
+
+    #[test]
+    fn zone_roundtrip() {
+        let key = rng_secret_key();
+        let tree = build_tree(parse_nodes(ENR).unwrap(), vec![], 3, &key);
+        let records = tree.txt_records("nodes.example.org");
+
+        let zone = zone_records(&records, 60);
+        assert!(zone.lines().all(|line| line.contains(". 60 IN TXT \"")));
+        assert_eq!(parse_zone_records(&zone), records);
+
+        let root: TreeRootEntry = records[0].1.parse().unwrap();
+        assert!(root.verify::<SecretKey>(&tree.link("nodes.example.org").pubkey));
+        let node = records.iter().find(|(_, content)| content.starts_with("enr:")).unwrap();
+        assert!(matches!(node.1.parse::<DnsEntry<SecretKey>>().unwrap(), DnsEntry::Node(_)));
+    }
+}
@@ -0,0 +1,633 @@
+//! Self-diagnostics of the node's data dir and configuration.
+use crate::{
+    args::{NetworkArgs, RpcServerArgs},
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use clap::Parser;
+use hyper::{Body, Client, Uri};
+use hyper_rustls::HttpsConnectorBuilder;
+use reth_db::{
+    cursor::DbCursorRO,
+    mdbx::{Env, EnvKind, WriteMap},
+    tables,
+    transaction::DbTx,
+};
+use reth_discv4::DEFAULT_DISCOVERY_PORT;
+use reth_primitives::{BlockNumber, ChainSpec};
+use reth_rpc::JwtSecret;
+use reth_rpc_builder::constants;
+use reth_staged_sync::utils::chainspec::genesis_value_parser;
+use reth_stages::{
+    stages::{
+        ACCOUNT_HASHING, BODIES, EXECUTION, FINISH, HEADERS, INDEX_ACCOUNT_HISTORY,
+        INDEX_STORAGE_HISTORY, MERKLE_EXECUTION, SENDER_RECOVERY, STORAGE_HASHING,
+        TOTAL_DIFFICULTY, TRANSACTION_LOOKUP,
+    },
+    StageId,
+};
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// The stages whose checkpoint can't be ahead of the checkpoint of the stage they depend on.
+const STAGE_DEPENDENCIES: [(StageId, StageId); 11] = [
+    (TOTAL_DIFFICULTY, HEADERS),
+    (BODIES, TOTAL_DIFFICULTY),
+    (SENDER_RECOVERY, BODIES),
+    (EXECUTION, SENDER_RECOVERY),
+    (ACCOUNT_HASHING, EXECUTION),
+    (STORAGE_HASHING, EXECUTION),
+    (MERKLE_EXECUTION, EXECUTION),
+    (TRANSACTION_LOOKUP, BODIES),
+    (INDEX_STORAGE_HISTORY, EXECUTION),
+    (INDEX_ACCOUNT_HISTORY, EXECUTION),
+    (FINISH, EXECUTION),
+];
+
+/// The number of blocks produced per day with 12 second slots.
+const BLOCKS_PER_DAY: u64 = 7_200;
+
+/// The clock skew above which the consensus client may miss its duties.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(2);
+
+/// The timeout of the requests to external services.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `reth doctor` command
+///
+/// Checks the data dir and the node's configuration for common problems. Accepts the same
+/// network and RPC arguments as `reth node`, so the configuration of the node can be checked
+/// before it is started.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    /// - holesky
+    /// - dev
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// The number of days of database growth the free disk space must be sufficient for.
+    #[arg(long, value_name = "DAYS", default_value_t = 30)]
+    growth_days: u64,
+
+    /// URL of an external service that checks whether a TCP port of this host is reachable.
+    ///
+    /// `{ip}` and `{port}` are replaced with the public IP and the P2P port of the node. The port
+    /// is considered reachable if the service responds with a success status. The check is
+    /// skipped if no URL is set.
+    #[arg(long, value_name = "URL", verbatim_doc_comment)]
+    probe_url: Option<String>,
+
+    /// URL of a server whose `Date` header the local clock is compared against, e.g.
+    /// `https://cloudflare.com`.
+    ///
+    /// The check is skipped if no URL is set.
+    #[arg(long, value_name = "URL", verbatim_doc_comment)]
+    time_url: Option<String>,
+
+    #[clap(flatten)]
+    network: NetworkArgs,
+
+    #[clap(flatten)]
+    rpc: RpcServerArgs,
+}
+
+impl Command {
+    /// Execute `doctor` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+
+        let mut findings = Vec::new();
+        self.check_database(&data_dir.db_path(), &mut findings);
+        self.check_p2p_port(&mut findings).await;
+        self.check_clock(&mut findings).await;
+        self.check_auth_rpc(&data_dir.jwt_path(), &mut findings);
+
+        for finding in &findings {
+            println!("{finding}");
+        }
+
+        let errors = findings.iter().filter(|f| f.severity == Severity::Error).count();
+        eyre::ensure!(errors == 0, "found {errors} problem(s)");
+        Ok(())
+    }
+
+    /// Checks the schema, the stage checkpoints and the free disk space of the database.
+    fn check_database(&self, db_path: &Path, findings: &mut Vec<Finding>) {
+        if !db_path.join("mdbx.dat").exists() {
+            findings.push(Finding::info(
+                "database",
+                format!("no database at {}, it is created when the node starts", db_path.display()),
+            ));
+            return
+        }
+
+        let db = match Env::<WriteMap>::open(db_path, EnvKind::RO) {
+            Ok(db) => db,
+            Err(err) => {
+                findings.push(
+                    Finding::error("database", format!("failed to open database: {err}"))
+                        .with_fix("stop any other process that has the database open"),
+                );
+                return
+            }
+        };
+        let tx = match db.tx() {
+            Ok(tx) => tx,
+            Err(err) => {
+                findings
+                    .push(Finding::error("database", format!("failed to read database: {err}")));
+                return
+            }
+        };
+
+        // tables that exist in this version but not in the database
+        let missing = tables::TABLES
+            .iter()
+            .map(|(_, name)| *name)
+            .filter(|name| tx.inner.open_db(Some(name)).is_err())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            findings.push(
+                Finding::error("schema", format!("missing tables: {}", missing.join(", ")))
+                    .with_fix("resync or run the reth version that created the database"),
+            );
+            return
+        }
+        match tx.get::<tables::CanonicalHeaders>(0) {
+            Ok(Some(genesis)) if genesis != self.chain.genesis_hash() => findings.push(
+                Finding::error(
+                    "schema",
+                    format!(
+                        "database has genesis {genesis:?}, expected {:?}",
+                        self.chain.genesis_hash()
+                    ),
+                )
+                .with_fix("use the --chain the database was created for or another --datadir"),
+            ),
+            Ok(_) => findings.push(Finding::ok("schema", "all tables exist")),
+            Err(err) => {
+                findings.push(Finding::error("schema", format!("failed to read genesis: {err}")))
+            }
+        }
+
+        let checkpoint = |stage: StageId| stage.get_progress(&tx).ok().flatten();
+        findings.extend(stage_checkpoint_findings(checkpoint));
+
+        // the headers stage must not be ahead of the stored headers
+        if let Some(headers) = checkpoint(HEADERS) {
+            let last_header = tx
+                .cursor_read::<tables::CanonicalHeaders>()
+                .and_then(|mut cursor| cursor.last())
+                .ok()
+                .flatten()
+                .map(|(number, _)| number)
+                .unwrap_or_default();
+            if headers > last_header {
+                findings.push(
+                    Finding::error(
+                        "stage checkpoints",
+                        format!("{HEADERS} checkpoint {headers} is ahead of header {last_header}"),
+                    )
+                    .with_fix("the header tables are corrupted, resync"),
+                );
+            }
+        }
+
+        let last_block = checkpoint(HEADERS).unwrap_or_default();
+        findings.push(self.disk_space_finding(db_path, last_block));
+    }
+
+    /// Checks whether the free disk space suffices for the projected growth of the database.
+    fn disk_space_finding(&self, db_path: &Path, last_block: BlockNumber) -> Finding {
+        let available = match available_space(db_path) {
+            Ok(available) => available,
+            Err(err) => {
+                return Finding::warning(
+                    "disk space",
+                    format!("failed to read free disk space: {err}"),
+                )
+            }
+        };
+        if last_block == 0 {
+            return Finding::info(
+                "disk space",
+                format!(
+                    "{} free, growth can't be projected before syncing",
+                    format_bytes(available)
+                ),
+            )
+        }
+
+        let db_size = std::fs::read_dir(db_path)
+            .map(|entries| {
+                entries.filter_map(|entry| entry.ok()?.metadata().ok()).map(|m| m.len()).sum()
+            })
+            .unwrap_or(0u64);
+        let projected = db_size / last_block * BLOCKS_PER_DAY * self.growth_days;
+        if available < projected {
+            Finding::error(
+                "disk space",
+                format!(
+                    "{} free, but the database is projected to grow by {} in {} days",
+                    format_bytes(available),
+                    format_bytes(projected),
+                    self.growth_days
+                ),
+            )
+            .with_fix("free disk space or move the data dir to a larger disk")
+        } else {
+            Finding::ok(
+                "disk space",
+                format!(
+                    "{} free, projected growth in {} days is {}",
+                    format_bytes(available),
+                    self.growth_days,
+                    format_bytes(projected)
+                ),
+            )
+        }
+    }
+
+    /// Checks whether the P2P port is reachable from the internet.
+    async fn check_p2p_port(&self, findings: &mut Vec<Finding>) {
+        let Some(probe_url) = &self.probe_url else {
+            findings.push(Finding::info("p2p port", "skipped, no --probe-url set"));
+            return
+        };
+        let Some(ip) = self.network.nat.external_addr().await else {
+            findings.push(
+                Finding::warning("p2p port", "failed to determine the public IP")
+                    .with_fix("set the public IP with --nat extip:<IP>"),
+            );
+            return
+        };
+        let port = self.network.port.unwrap_or(DEFAULT_DISCOVERY_PORT);
+
+        let url = probe_url.replace("{ip}", &ip.to_string()).replace("{port}", &port.to_string());
+        let finding = match http_get(&url).await {
+            Ok(res) if res.status().is_success() => {
+                Finding::ok("p2p port", format!("{ip}:{port} is reachable"))
+            }
+            Ok(res) => Finding::warning(
+                "p2p port",
+                format!("{ip}:{port} is not reachable ({})", res.status()),
+            )
+            .with_fix("forward the port and allow it in the firewall to accept inbound peers"),
+            Err(err) => Finding::warning("p2p port", format!("probe failed: {err}")),
+        };
+        findings.push(finding);
+    }
+
+    /// Compares the local clock with the `Date` header of a remote server.
+    async fn check_clock(&self, findings: &mut Vec<Finding>) {
+        let Some(time_url) = &self.time_url else {
+            findings.push(Finding::info("clock", "skipped, no --time-url set"));
+            return
+        };
+        let start = Instant::now();
+        let requested_at = SystemTime::now();
+        let res = match http_get(time_url).await {
+            Ok(res) => res,
+            Err(err) => {
+                findings
+                    .push(Finding::warning("clock", format!("failed to fetch the time: {err}")));
+                return
+            }
+        };
+        // assume the server generated the response halfway through the round trip
+        let local = requested_at + start.elapsed() / 2;
+
+        let Some(remote) = res
+            .headers()
+            .get(hyper::header::DATE)
+            .and_then(|date| httpdate::parse_http_date(date.to_str().ok()?).ok())
+        else {
+            findings.push(Finding::warning("clock", "response has no valid Date header"));
+            return
+        };
+        let skew = local.duration_since(remote).unwrap_or_else(|err| err.duration());
+        findings.push(clock_skew_finding(skew));
+    }
+
+    /// Checks the JWT secret and the address of the authenticated RPC server.
+    fn check_auth_rpc(&self, default_jwt_path: &Path, findings: &mut Vec<Finding>) {
+        let jwt_path = self.rpc.auth_jwtsecret_path().unwrap_or(default_jwt_path);
+        if !jwt_path.exists() {
+            if self.rpc.auth_jwtsecret_path().is_some() {
+                findings.push(
+                    Finding::error("jwt secret", format!("{} does not exist", jwt_path.display()))
+                        .with_fix(
+                            "point --authrpc.jwtsecret to the secret used by the consensus client",
+                        ),
+                );
+            } else {
+                findings.push(
+                    Finding::info(
+                        "jwt secret",
+                        format!("{} is created when the node starts", jwt_path.display()),
+                    )
+                    .with_fix("configure the consensus client to use the same file"),
+                );
+            }
+        } else if let Err(err) = JwtSecret::from_file(jwt_path) {
+            findings.push(
+                Finding::error("jwt secret", format!("{} is invalid: {err}", jwt_path.display()))
+                    .with_fix("the file must contain a hex encoded 32 byte secret"),
+            );
+        } else if is_world_readable(jwt_path) {
+            findings.push(
+                Finding::warning(
+                    "jwt secret",
+                    format!("{} is readable by all users", jwt_path.display()),
+                )
+                .with_fix(format!("chmod 600 {}", jwt_path.display())),
+            );
+        } else {
+            findings.push(Finding::ok("jwt secret", format!("{} is valid", jwt_path.display())));
+        }
+
+        let auth_addr = SocketAddr::new(
+            self.rpc.auth_addr.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+            self.rpc.auth_port.unwrap_or(constants::DEFAULT_AUTH_PORT),
+        );
+        let mut servers = Vec::new();
+        if self.rpc.http {
+            servers.push((
+                "http",
+                SocketAddr::new(
+                    self.rpc.http_addr.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+                    self.rpc.http_port.unwrap_or(constants::DEFAULT_HTTP_RPC_PORT),
+                ),
+            ));
+        }
+        if self.rpc.ws {
+            servers.push((
+                "ws",
+                SocketAddr::new(
+                    self.rpc.ws_addr.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+                    self.rpc.ws_port.unwrap_or(constants::DEFAULT_WS_RPC_PORT),
+                ),
+            ));
+        }
+        findings.push(auth_rpc_finding(auth_addr, self.rpc.auth_allowlist.is_some(), &servers));
+    }
+}
+
+/// Returns the findings for stages whose checkpoint is ahead of the stage they depend on.
+fn stage_checkpoint_findings(checkpoint: impl Fn(StageId) -> Option<BlockNumber>) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (stage, dependency) in STAGE_DEPENDENCIES {
+        let Some(progress) = checkpoint(stage) else { continue };
+        let dependency_progress = checkpoint(dependency).unwrap_or_default();
+        if progress > dependency_progress {
+            findings.push(
+                Finding::error(
+                    "stage checkpoints",
+                    format!(
+                        "{stage} at {progress} is ahead of {dependency} at {dependency_progress}"
+                    ),
+                )
+                .with_fix(format!(
+                    "drop the {stage} stage with `reth drop-stage` if supported, or resync"
+                )),
+            );
+        }
+    }
+    if findings.is_empty() {
+        findings.push(Finding::ok("stage checkpoints", "checkpoints are consistent"));
+    }
+    findings
+}
+
+/// Returns the finding for the given difference between the local and the remote clock.
+fn clock_skew_finding(skew: Duration) -> Finding {
+    if skew > MAX_CLOCK_SKEW {
+        Finding::warning("clock", format!("local clock is off by {}ms", skew.as_millis()))
+            .with_fix("synchronize the clock with NTP, e.g. enable systemd-timesyncd or chrony")
+    } else {
+        Finding::ok("clock", format!("local clock is off by {}ms", skew.as_millis()))
+    }
+}
+
+/// Returns the finding for the address of the authenticated RPC server.
+fn auth_rpc_finding(
+    auth_addr: SocketAddr,
+    has_allowlist: bool,
+    servers: &[(&str, SocketAddr)],
+) -> Finding {
+    let conflicts = |addr: &SocketAddr| {
+        addr.port() == auth_addr.port() &&
+            (addr.ip() == auth_addr.ip() ||
+                addr.ip().is_unspecified() ||
+                auth_addr.ip().is_unspecified())
+    };
+    if let Some((server, addr)) = servers.iter().find(|(_, addr)| conflicts(addr)) {
+        return Finding::error(
+            "auth rpc",
+            format!(
+                "{server} server on {addr} uses the same port as the auth server on {auth_addr}"
+            ),
+        )
+        .with_fix("change the port with --authrpc.port")
+    }
+    if !auth_addr.ip().is_loopback() && !has_allowlist {
+        return Finding::warning(
+            "auth rpc",
+            format!("engine API on {auth_addr} accepts connections from any host"),
+        )
+        .with_fix("restrict it with --authrpc.addr or --authrpc.allowlist")
+    }
+    Finding::ok("auth rpc", format!("engine API listens on {auth_addr}"))
+}
+
+/// Sends a GET request with a timeout.
+async fn http_get(url: &str) -> eyre::Result<hyper::Response<Body>> {
+    let uri: Uri = url.parse()?;
+    let client = Client::builder().build::<_, Body>(
+        HttpsConnectorBuilder::new().with_native_roots().https_or_http().enable_http1().build(),
+    );
+    Ok(tokio::time::timeout(REQUEST_TIMEOUT, client.get(uri)).await??)
+}
+
+/// Returns the space available to unprivileged users on the file system of the path.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn available_space(path: &Path) -> std::io::Result<u64> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: the path is a valid C string and `stat` is valid for writes
+    if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(std::io::Error::last_os_error())
+    }
+    // SAFETY: `statvfs` succeeded, so `stat` is initialized
+    let stat = unsafe { stat.assume_init() };
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn available_space(_path: &Path) -> std::io::Result<u64> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "not supported on this platform"))
+}
+
+#[cfg(unix)]
+fn is_world_readable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).map(|m| m.permissions().mode() & 0o004 != 0).unwrap_or_default()
+}
+
+#[cfg(not(unix))]
+fn is_world_readable(_path: &Path) -> bool {
+    false
+}
+
+fn format_bytes(bytes: u64) -> String {
+    human_bytes::human_bytes(bytes as f64)
+}
+
+/// The severity of a [Finding].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    Ok,
+    Info,
+    Warning,
+    Error,
+}
+
+/// The result of a single check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Finding {
+    /// The name of the check.
+    check: &'static str,
+    severity: Severity,
+    message: String,
+    /// What the user can do about the finding.
+    fix: Option<String>,
+}
+
+impl Finding {
+    fn new(check: &'static str, severity: Severity, message: impl Into<String>) -> Self {
+        Self { check, severity, message: message.into(), fix: None }
+    }
+
+    fn ok(check: &'static str, message: impl Into<String>) -> Self {
+        Self::new(check, Severity::Ok, message)
+    }
+
+    fn info(check: &'static str, message: impl Into<String>) -> Self {
+        Self::new(check, Severity::Info, message)
+    }
+
+    fn warning(check: &'static str, message: impl Into<String>) -> Self {
+        Self::new(check, Severity::Warning, message)
+    }
+
+    fn error(check: &'static str, message: impl Into<String>) -> Self {
+        Self::new(check, Severity::Error, message)
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Ok => "ok",
+            Severity::Info => "info",
+            Severity::Warning => "warn",
+            Severity::Error => "error",
+        };
+        write!(f, "[{severity:>5}] {}: {}", self.check, self.message)?;
+        if let Some(fix) = &self.fix {
+            write!(f, "\n        -> {fix}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn parse_doctor_command() {
+        let cmd = Command::parse_from([
+            "reth",
+            "--probe-url",
+            "https://probe.example.com/{ip}/{port}",
+            "--authrpc.port",
+            "8552",
+        ]);
+        assert_eq!(cmd.rpc.auth_port, Some(8552));
+        assert_eq!(cmd.growth_days, 30);
+        // no outbound requests unless configured
+        assert_eq!(cmd.time_url, None);
+    }
+
+    #[test]
+    fn detects_inconsistent_checkpoints() {
+        let checkpoints = HashMap::from([(HEADERS, 100), (TOTAL_DIFFICULTY, 100), (BODIES, 90)]);
+        let findings = stage_checkpoint_findings(|stage| checkpoints.get(&stage).copied());
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Ok);
+
+        // stages without a checkpoint are not checked, a missing dependency counts as 0
+        let checkpoints = HashMap::from([(HEADERS, 100), (BODIES, 90), (SENDER_RECOVERY, 95)]);
+        let findings = stage_checkpoint_findings(|stage| checkpoints.get(&stage).copied());
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().all(|f| f.severity == Severity::Error));
+    }
+
+    #[test]
+    fn detects_auth_rpc_misconfiguration() {
+        let auth = SocketAddr::from(([127, 0, 0, 1], 8551));
+        assert_eq!(auth_rpc_finding(auth, false, &[]).severity, Severity::Ok);
+
+        let http = SocketAddr::from(([0, 0, 0, 0], 8551));
+        assert_eq!(auth_rpc_finding(auth, false, &[("http", http)]).severity, Severity::Error);
+
+        let public = SocketAddr::from(([0, 0, 0, 0], 8551));
+        assert_eq!(auth_rpc_finding(public, false, &[]).severity, Severity::Warning);
+        assert_eq!(auth_rpc_finding(public, true, &[]).severity, Severity::Ok);
+    }
+
+    #[test]
+    fn warns_about_clock_skew() {
+        assert_eq!(clock_skew_finding(Duration::from_millis(500)).severity, Severity::Ok);
+        assert_eq!(clock_skew_finding(Duration::from_secs(5)).severity, Severity::Warning);
+    }
+}
@@ -14,8 +14,8 @@ use reth_db::{
 use reth_primitives::ChainSpec;
 use reth_staged_sync::utils::{chainspec::genesis_value_parser, init::insert_genesis_state};
 use reth_stages::stages::{
-    ACCOUNT_HASHING, EXECUTION, INDEX_ACCOUNT_HISTORY, INDEX_STORAGE_HISTORY, MERKLE_EXECUTION,
-    MERKLE_UNWIND, STORAGE_HASHING,
+    ACCOUNT_HASHING, EXECUTION, INDEX_ACCOUNT_HISTORY, INDEX_LOGS, INDEX_STORAGE_HISTORY,
+    MERKLE_EXECUTION, MERKLE_UNWIND, STORAGE_HASHING,
 };
 use std::{path::PathBuf, sync::Arc};
 use tracing::info;
@@ -46,6 +46,8 @@ pub struct Command {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - dev
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
@@ -120,6 +122,15 @@ impl Command {
                     Ok::<_, eyre::Error>(())
                 })??;
             }
+            StageEnum::LogIndex => {
+                tool.db.update(|tx| {
+                    tx.clear::<tables::LogAddressIndex>()?;
+                    tx.clear::<tables::LogTopicIndex>()?;
+                    // the index is disabled until the stage runs again
+                    tx.delete::<tables::SyncStage>(INDEX_LOGS.0.to_string(), None)?;
+                    Ok::<_, eyre::Error>(())
+                })??;
+            }
             _ => {
                 info!("Nothing to do for stage {:?}", self.stage);
             }
@@ -50,6 +50,8 @@ pub struct Command {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - dev
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
@@ -0,0 +1,189 @@
+use arrow::{
+    array::{ArrayRef, BooleanArray, StringArray, UInt64Array},
+    datatypes::{DataType, Field, Schema},
+    record_batch::RecordBatch,
+};
+use parquet::arrow::ArrowWriter;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// The file format of exported data.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// Comma separated values with a header row.
+    Csv,
+    /// Apache Parquet.
+    Parquet,
+}
+
+impl ExportFormat {
+    /// Returns the file name for the given data set in this format.
+    pub fn file_name(&self, name: &str) -> String {
+        match self {
+            ExportFormat::Csv => format!("{name}.csv"),
+            ExportFormat::Parquet => format!("{name}.parquet"),
+        }
+    }
+
+    /// Creates the file at `path` and returns a writer for this format.
+    pub(crate) fn writer(&self, path: PathBuf) -> eyre::Result<ExportWriter> {
+        let file = File::create(&path)?;
+        Ok(match self {
+            ExportFormat::Csv => ExportWriter::Csv { out: BufWriter::new(file), has_header: false },
+            ExportFormat::Parquet => ExportWriter::Parquet { file: Some(file), writer: None },
+        })
+    }
+}
+
+/// A column of exported values.
+#[derive(Debug)]
+pub(crate) enum Column {
+    UInt64(Vec<u64>),
+    Boolean(Vec<bool>),
+    Utf8(Vec<Option<String>>),
+}
+
+impl Column {
+    fn len(&self) -> usize {
+        match self {
+            Column::UInt64(values) => values.len(),
+            Column::Boolean(values) => values.len(),
+            Column::Utf8(values) => values.len(),
+        }
+    }
+
+    /// Formats the value at the given row for a CSV file, `None` values are left empty.
+    fn csv_value(&self, row: usize) -> String {
+        match self {
+            Column::UInt64(values) => values[row].to_string(),
+            Column::Boolean(values) => values[row].to_string(),
+            Column::Utf8(values) => values[row].clone().unwrap_or_default(),
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            Column::UInt64(_) => DataType::UInt64,
+            Column::Boolean(_) => DataType::Boolean,
+            Column::Utf8(_) => DataType::Utf8,
+        }
+    }
+
+    fn into_array(self) -> ArrayRef {
+        match self {
+            Column::UInt64(values) => Arc::new(UInt64Array::from(values)),
+            Column::Boolean(values) => Arc::new(BooleanArray::from(values)),
+            Column::Utf8(values) => Arc::new(StringArray::from(values)),
+        }
+    }
+}
+
+/// Writes batches of columns into a file.
+pub(crate) enum ExportWriter {
+    Csv {
+        out: BufWriter<File>,
+        /// Whether the header row was already written.
+        has_header: bool,
+    },
+    Parquet {
+        /// The file to write to, until the schema is known from the first batch.
+        file: Option<File>,
+        writer: Option<ArrowWriter<File>>,
+    },
+}
+
+impl ExportWriter {
+    /// Appends a batch of columns, all columns must have the same length.
+    pub(crate) fn write(&mut self, batch: Vec<(&'static str, Column)>) -> eyre::Result<()> {
+        match self {
+            ExportWriter::Csv { out, has_header } => {
+                if !*has_header {
+                    let header = batch.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+                    writeln!(out, "{}", header.join(","))?;
+                    *has_header = true;
+                }
+                let rows = batch.first().map(|(_, column)| column.len()).unwrap_or_default();
+                for row in 0..rows {
+                    let values =
+                        batch.iter().map(|(_, column)| column.csv_value(row)).collect::<Vec<_>>();
+                    writeln!(out, "{}", values.join(","))?;
+                }
+            }
+            ExportWriter::Parquet { file, writer } => {
+                let schema = Arc::new(Schema::new(
+                    batch
+                        .iter()
+                        .map(|(name, column)| {
+                            let nullable = matches!(column, Column::Utf8(_));
+                            Field::new(*name, column.data_type(), nullable)
+                        })
+                        .collect::<Vec<_>>(),
+                ));
+                if writer.is_none() {
+                    let file = file.take().expect("file is set until the writer is created");
+                    *writer = Some(ArrowWriter::try_new(file, schema.clone(), None)?);
+                }
+                let columns = batch.into_iter().map(|(_, column)| column.into_array()).collect();
+                let record_batch = RecordBatch::try_new(schema, columns)?;
+                writer.as_mut().expect("writer was created").write(&record_batch)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes all written batches and finalizes the file.
+    pub(crate) fn finish(self) -> eyre::Result<()> {
+        match self {
+            ExportWriter::Csv { mut out, .. } => out.flush()?,
+            ExportWriter::Parquet { writer, .. } => {
+                if let Some(writer) = writer {
+                    writer.close()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for ExportWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportWriter::Csv { .. } => f.debug_struct("ExportWriter::Csv").finish_non_exhaustive(),
+            ExportWriter::Parquet { .. } => {
+                f.debug_struct("ExportWriter::Parquet").finish_non_exhaustive()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(ExportFormat::Csv.file_name("receipts"));
+        let mut writer = ExportFormat::Csv.writer(path.clone()).unwrap();
+
+        let batch = || {
+            vec![
+                ("block_number", Column::UInt64(vec![1, 2])),
+                ("success", Column::Boolean(vec![true, false])),
+                ("topic0", Column::Utf8(vec![Some("0x01".to_string()), None])),
+            ]
+        };
+        writer.write(batch()).unwrap();
+        writer.write(batch()).unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "block_number,success,topic0\n1,true,0x01\n2,false,\n1,true,0x01\n2,false,\n"
+        );
+    }
+}
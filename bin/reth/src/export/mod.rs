@@ -0,0 +1,80 @@
+//! Command for exporting chain data into analytics-friendly files.
+use crate::dirs::{DataDirPath, MaybePlatformPath};
+use clap::{Parser, Subcommand};
+use reth_db::mdbx::{Env, EnvKind, WriteMap};
+use reth_primitives::ChainSpec;
+use reth_provider::ShareableDatabase;
+use reth_staged_sync::utils::chainspec::genesis_value_parser;
+use std::{path::PathBuf, sync::Arc};
+
+mod format;
+mod receipts;
+
+pub use format::ExportFormat;
+pub use receipts::ReceiptsCommand;
+
+/// `reth export` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The path to the database folder. If not specified, it will be set in the data dir for the
+    /// chain being used.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    db: Option<PathBuf>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    /// - holesky
+    /// - dev
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth export` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands {
+    /// Exports receipts and their logs for a range of blocks
+    Receipts(ReceiptsCommand),
+}
+
+impl Command {
+    /// Execute `export` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+
+        // use the overridden db path if specified
+        let db_path = self.db.clone().unwrap_or(data_dir.db_path());
+
+        let db = Env::<WriteMap>::open(db_path.as_ref(), EnvKind::RO)?;
+        let client = ShareableDatabase::new(db, self.chain);
+
+        match self.command {
+            Subcommands::Receipts(command) => command.execute(client),
+        }
+    }
+}
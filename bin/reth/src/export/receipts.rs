@@ -0,0 +1,199 @@
+use super::format::{Column, ExportFormat};
+use clap::Parser;
+use reth_primitives::{BlockNumber, Receipt, TransactionSigned};
+use reth_provider::{BlockIdProvider, ReceiptProvider, TransactionsProvider};
+use std::path::PathBuf;
+use tracing::info;
+
+/// Exports receipts and their logs for a range of blocks.
+///
+/// This writes a `receipts` file with one row per transaction receipt and a `logs` file with one
+/// row per emitted log into the output directory. Blocks are read and written in batches, so the
+/// memory usage is bounded by the batch size regardless of the length of the range.
+#[derive(Debug, Parser)]
+pub struct ReceiptsCommand {
+    /// The first block to export.
+    #[arg(long, value_name = "BLOCK", default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block to export (inclusive). Defaults to the latest block.
+    #[arg(long, value_name = "BLOCK")]
+    to: Option<BlockNumber>,
+
+    /// The format of the exported files.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Parquet)]
+    format: ExportFormat,
+
+    /// The directory the exported files are written to.
+    #[arg(long, short, value_name = "DIR")]
+    output: PathBuf,
+
+    /// The number of blocks that are buffered before they're written to the files.
+    #[arg(long, value_name = "BLOCKS", default_value_t = 1_000)]
+    batch_size: u64,
+}
+
+impl ReceiptsCommand {
+    /// Execute `export receipts` command
+    pub fn execute<Client>(self, client: Client) -> eyre::Result<()>
+    where
+        Client: BlockIdProvider + TransactionsProvider + ReceiptProvider,
+    {
+        let to = match self.to {
+            Some(to) => to,
+            None => client.best_block_number()?,
+        };
+        eyre::ensure!(self.from <= to, "invalid block range {}..={to}", self.from);
+        eyre::ensure!(self.batch_size > 0, "batch size must be positive");
+
+        std::fs::create_dir_all(&self.output)?;
+        let mut receipts_writer =
+            self.format.writer(self.output.join(self.format.file_name("receipts")))?;
+        let mut logs_writer =
+            self.format.writer(self.output.join(self.format.file_name("logs")))?;
+
+        let mut receipts = ReceiptColumns::default();
+        let mut logs = LogColumns::default();
+        info!(target: "reth::cli", from = self.from, to, format = ?self.format, "Exporting receipts");
+
+        for number in self.from..=to {
+            let Some(transactions) = client.transactions_by_block(number.into())? else {
+                eyre::bail!("block {number} not found")
+            };
+            let Some(block_receipts) = client.receipts_by_block(number.into())? else {
+                eyre::bail!("receipts of block {number} are not available")
+            };
+            eyre::ensure!(
+                transactions.len() == block_receipts.len(),
+                "block {number} has {} transactions but {} receipts",
+                transactions.len(),
+                block_receipts.len()
+            );
+
+            let mut log_index = 0;
+            for (tx_index, (tx, receipt)) in transactions.iter().zip(block_receipts).enumerate() {
+                logs.push_receipt(number, tx_index as u64, &mut log_index, tx, &receipt);
+                receipts.push(number, tx_index as u64, tx, receipt);
+            }
+
+            if (number - self.from + 1) % self.batch_size == 0 {
+                receipts_writer.write(receipts.take())?;
+                logs_writer.write(logs.take())?;
+                info!(target: "reth::cli", block = number, to, "Exported receipts");
+            }
+        }
+
+        // always write the last batch, even if it's empty, so the files carry their schema
+        receipts_writer.write(receipts.take())?;
+        logs_writer.write(logs.take())?;
+        receipts_writer.finish()?;
+        logs_writer.finish()?;
+
+        info!(target: "reth::cli", output = %self.output.display(), "Export finished");
+        Ok(())
+    }
+}
+
+/// Formats a value as `0x` prefixed hex string.
+fn hex_string(value: impl AsRef<[u8]>) -> String {
+    format!("0x{}", hex::encode(value))
+}
+
+/// The buffered rows of the receipts file.
+#[derive(Debug, Default)]
+struct ReceiptColumns {
+    block_number: Vec<u64>,
+    transaction_index: Vec<u64>,
+    transaction_hash: Vec<Option<String>>,
+    transaction_type: Vec<u64>,
+    success: Vec<bool>,
+    cumulative_gas_used: Vec<u64>,
+    log_count: Vec<u64>,
+}
+
+impl ReceiptColumns {
+    fn push(
+        &mut self,
+        number: BlockNumber,
+        tx_index: u64,
+        tx: &TransactionSigned,
+        receipt: Receipt,
+    ) {
+        self.block_number.push(number);
+        self.transaction_index.push(tx_index);
+        self.transaction_hash.push(Some(hex_string(tx.hash.0)));
+        self.transaction_type.push(u8::from(receipt.tx_type) as u64);
+        self.success.push(receipt.success);
+        self.cumulative_gas_used.push(receipt.cumulative_gas_used);
+        self.log_count.push(receipt.logs.len() as u64);
+    }
+
+    /// Returns all buffered columns and clears the buffer.
+    fn take(&mut self) -> Vec<(&'static str, Column)> {
+        let this = std::mem::take(self);
+        vec![
+            ("block_number", Column::UInt64(this.block_number)),
+            ("transaction_index", Column::UInt64(this.transaction_index)),
+            ("transaction_hash", Column::Utf8(this.transaction_hash)),
+            ("transaction_type", Column::UInt64(this.transaction_type)),
+            ("success", Column::Boolean(this.success)),
+            ("cumulative_gas_used", Column::UInt64(this.cumulative_gas_used)),
+            ("log_count", Column::UInt64(this.log_count)),
+        ]
+    }
+}
+
+/// The buffered rows of the logs file.
+#[derive(Debug, Default)]
+struct LogColumns {
+    block_number: Vec<u64>,
+    transaction_index: Vec<u64>,
+    log_index: Vec<u64>,
+    transaction_hash: Vec<Option<String>>,
+    address: Vec<Option<String>>,
+    topics: [Vec<Option<String>>; 4],
+    data: Vec<Option<String>>,
+}
+
+impl LogColumns {
+    /// Adds all logs of the receipt, `log_index` is the index of the next log within the block.
+    fn push_receipt(
+        &mut self,
+        number: BlockNumber,
+        tx_index: u64,
+        log_index: &mut u64,
+        tx: &TransactionSigned,
+        receipt: &Receipt,
+    ) {
+        for log in &receipt.logs {
+            self.block_number.push(number);
+            self.transaction_index.push(tx_index);
+            self.log_index.push(*log_index);
+            self.transaction_hash.push(Some(hex_string(tx.hash.0)));
+            self.address.push(Some(hex_string(log.address.0)));
+            for (idx, topics) in self.topics.iter_mut().enumerate() {
+                topics.push(log.topics.get(idx).map(|topic| hex_string(topic.0)));
+            }
+            self.data.push(Some(hex_string(&log.data)));
+            *log_index += 1;
+        }
+    }
+
+    /// Returns all buffered columns and clears the buffer.
+    fn take(&mut self) -> Vec<(&'static str, Column)> {
+        let this = std::mem::take(self);
+        let [topic0, topic1, topic2, topic3] = this.topics;
+        vec![
+            ("block_number", Column::UInt64(this.block_number)),
+            ("transaction_index", Column::UInt64(this.transaction_index)),
+            ("log_index", Column::UInt64(this.log_index)),
+            ("transaction_hash", Column::Utf8(this.transaction_hash)),
+            ("address", Column::Utf8(this.address)),
+            ("topic0", Column::Utf8(topic0)),
+            ("topic1", Column::Utf8(topic1)),
+            ("topic2", Column::Utf8(topic2)),
+            ("topic3", Column::Utf8(topic3)),
+            ("data", Column::Utf8(this.data)),
+        ]
+    }
+}
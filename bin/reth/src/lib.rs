@@ -8,18 +8,24 @@
 //! Rust Ethereum (reth) binary executable.
 
 pub mod args;
+pub mod bootnode;
 pub mod chain;
 pub mod cli;
 pub mod config;
 pub mod db;
 pub mod dirs;
+pub mod dns_tree;
+pub mod doctor;
 pub mod drop_stage;
 pub mod dump_stage;
+pub mod export;
 pub mod merkle_debug;
 pub mod node;
 pub mod p2p;
 pub mod prometheus_exporter;
 pub mod runner;
+pub mod sentry;
+pub mod snapshot;
 pub mod stage;
 pub mod test_eth_chain;
 pub mod test_vectors;
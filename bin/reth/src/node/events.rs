@@ -1,10 +1,11 @@
 //! Support for handling events emitted by node components.
 
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use reth_beacon_consensus::BeaconConsensusEngineEvent;
 use reth_network::{NetworkEvent, NetworkHandle};
 use reth_network_api::PeersInfo;
-use reth_primitives::BlockNumber;
+use reth_primitives::{BlockNumber, U64};
+use reth_rpc_types::SyncEvent;
 use reth_stages::{PipelineEvent, StageId};
 use std::{
     future::Future,
@@ -12,7 +13,7 @@ use std::{
     task::{Context, Poll},
     time::Duration,
 };
-use tokio::time::Interval;
+use tokio::{sync::broadcast, time::Interval};
 use tracing::{info, warn};
 
 /// The current high-level state of the node.
@@ -132,6 +133,46 @@ pub async fn handle_events(
     handler.await
 }
 
+/// Sends the events emitted by the pipeline to the `reth_subscribeSyncEvents` subscribers.
+pub async fn forward_sync_events(
+    mut events: impl Stream<Item = PipelineEvent> + Unpin,
+    sync_events: broadcast::Sender<SyncEvent>,
+) {
+    while let Some(event) = events.next().await {
+        // there may be no subscribers
+        let _ = sync_events.send(to_sync_event(event));
+    }
+}
+
+/// Converts a [PipelineEvent] into the [SyncEvent] sent to subscribers.
+fn to_sync_event(event: PipelineEvent) -> SyncEvent {
+    match event {
+        PipelineEvent::Running { stage_id, stage_progress } => SyncEvent::StageStarted {
+            stage: stage_id.to_string(),
+            checkpoint: stage_progress.map(U64::from),
+        },
+        PipelineEvent::Ran { stage_id, result } => SyncEvent::StageFinished {
+            stage: stage_id.to_string(),
+            checkpoint: U64::from(result.stage_progress),
+            done: result.done,
+        },
+        PipelineEvent::Unwinding { stage_id, input } => SyncEvent::UnwindStarted {
+            stage: stage_id.to_string(),
+            checkpoint: U64::from(input.stage_progress),
+            unwind_to: U64::from(input.unwind_to),
+            bad_block: input.bad_block.map(U64::from),
+        },
+        PipelineEvent::Unwound { stage_id, result } => SyncEvent::UnwindFinished {
+            stage: stage_id.to_string(),
+            checkpoint: U64::from(result.stage_progress),
+        },
+        PipelineEvent::Error { stage_id } => SyncEvent::StageFailed { stage: stage_id.to_string() },
+        PipelineEvent::Skipped { stage_id } => {
+            SyncEvent::StageSkipped { stage: stage_id.to_string() }
+        }
+    }
+}
+
 /// Handles events emitted by the node and logs them accordingly.
 #[pin_project::pin_project]
 struct EventHandler<St> {
@@ -2,11 +2,14 @@
 //!
 //! Starts the client
 use crate::{
-    args::{get_secret_key, DebugArgs, NetworkArgs, RpcServerArgs},
+    args::{
+        get_secret_key, DebugArgs, DownloaderArgs, ForkOverrideArgs, NetworkArgs, RpcServerArgs,
+    },
     dirs::DataDirPath,
     prometheus_exporter,
     runner::CliContext,
-    utils::get_single_header,
+    utils::{get_single_header, load_header_accumulator},
+    version::client_version,
 };
 use clap::{crate_version, Parser};
 use eyre::Context;
@@ -22,9 +25,8 @@ use reth_db::{
     database::Database,
     mdbx::{Env, WriteMap},
     tables,
-    transaction::DbTx,
+    transaction::{DbTx, DbTxMut},
 };
-use reth_discv4::DEFAULT_DISCOVERY_PORT;
 use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
@@ -37,13 +39,21 @@ use reth_interfaces::{
     },
     sync::SyncStateUpdater,
 };
-use reth_network::{error::NetworkError, NetworkConfig, NetworkHandle, NetworkManager};
+use reth_net_nat::{maintain_port_mappings, PortMapping};
+use reth_network::{
+    error::NetworkError, NetworkConfig, NetworkHandle, NetworkManager, PeersConfig, PersistedPeers,
+};
 use reth_network_api::NetworkInfo;
-use reth_primitives::{BlockHashOrNumber, ChainSpec, Head, Header, SealedHeader, H256};
+use reth_primitives::{
+    stage, BlockHashOrNumber, ChainSpec, FromRecoveredTransaction, Head, Header,
+    IntoRecoveredTransaction, SealedHeader, DEV, H256,
+};
 use reth_provider::{BlockProvider, CanonStateSubscriptions, HeaderProvider, ShareableDatabase};
 use reth_revm::Factory;
 use reth_revm_inspectors::stack::Hook;
+use reth_rpc::{JwtSecret, SYNC_EVENTS_CHANNEL_CAPACITY};
 use reth_rpc_engine_api::EngineApi;
+use reth_sentry::{SentryClient, SentryStatusUpdater};
 use reth_staged_sync::{
     utils::{
         chainspec::genesis_value_parser,
@@ -54,20 +64,27 @@ use reth_staged_sync::{
 };
 use reth_stages::{
     prelude::*,
-    stages::{ExecutionStage, HeaderSyncMode, SenderRecoveryStage, TotalDifficultyStage, FINISH},
+    stages::{
+        ExecutionStage, HeaderSyncMode, IndexLogsStage, SenderRecoveryStage, TotalDifficultyStage,
+        FINISH,
+    },
 };
 use reth_tasks::TaskExecutor;
-use reth_transaction_pool::{EthTransactionValidator, TransactionPool};
+use reth_transaction_pool::{
+    BlobStore, EthTransactionValidator, TransactionPool, DEFAULT_BLOB_RETENTION_BLOCKS,
+};
 use secp256k1::SecretKey;
 use std::{
-    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
-    path::PathBuf,
+    io::Write,
+    net::{Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
-use tokio::sync::{mpsc::unbounded_channel, oneshot, watch};
+use tokio::sync::{broadcast, mpsc::unbounded_channel, oneshot, watch};
 use tracing::*;
 
-use crate::dirs::MaybePlatformPath;
+use crate::dirs::MaybeEphemeralPath;
 use reth_interfaces::p2p::headers::client::HeadersClient;
 use reth_payload_builder::PayloadBuilderService;
 use reth_provider::providers::BlockchainProvider;
@@ -75,6 +92,12 @@ use reth_stages::stages::{MERKLE_EXECUTION, MERKLE_UNWIND};
 
 pub mod events;
 
+/// The interval at which known peers are written to the peers file while the node is running.
+const PERSIST_PEERS_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// The interval at which the peer count of the sentries is refreshed.
+const SENTRY_PEER_COUNT_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Start the node
 #[derive(Debug, Parser)]
 pub struct Command {
@@ -85,8 +108,12 @@ pub struct Command {
     /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
     /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
     /// - macOS: `$HOME/Library/Application Support/reth/`
+    ///
+    /// Use `:memory:` to run with an ephemeral data dir. Note that this is not an in-memory
+    /// database: the data is written to a temporary directory on disk that is removed on exit.
+    /// Only `reth node` accepts `:memory:`.
     #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
-    datadir: MaybePlatformPath<DataDirPath>,
+    datadir: MaybeEphemeralPath<DataDirPath>,
 
     /// The path to the configuration file to use.
     #[arg(long, value_name = "FILE", verbatim_doc_comment)]
@@ -112,6 +139,8 @@ pub struct Command {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - dev
     #[arg(
     long,
     value_name = "CHAIN_OR_PATH",
@@ -133,25 +162,64 @@ pub struct Command {
     #[clap(flatten)]
     rpc: RpcServerArgs,
 
+    #[clap(flatten)]
+    downloader: DownloaderArgs,
+
     #[clap(flatten)]
     debug: DebugArgs,
 
+    #[clap(flatten)]
+    overrides: ForkOverrideArgs,
+
     /// Automatically mine blocks for new transactions
     #[arg(long)]
     auto_mine: bool,
+
+    /// The URLs of the sentries to download blocks through, see `reth sentry`.
+    ///
+    /// If set, headers and bodies are requested from the peers of the sentries, transactions are
+    /// exchanged with the peers of the sentries, and the sentries announce the head of this node.
+    /// The node's own p2p network then neither discovers nor accepts peers and only listens on
+    /// localhost.
+    #[arg(long = "sentry", value_name = "URL", conflicts_with = "auto_mine")]
+    sentries: Vec<String>,
+
+    /// Path to the JWT secret shared with the sentries.
+    ///
+    /// If not specified, the secret is read from the data dir for the chain being used, where
+    /// `reth sentry` creates it.
+    #[arg(long = "sentry.jwtsecret", value_name = "PATH", requires = "sentries")]
+    sentry_jwtsecret: Option<PathBuf>,
+
+    /// Path to the SSZ encoded accumulator of pre-merge headers.
+    ///
+    /// If set, the pre-merge headers are verified against the accumulator epoch by epoch. On
+    /// mainnet the root of the accumulator must match the known root.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    header_accumulator: Option<PathBuf>,
 }
 
 impl Command {
     /// Execute `node` command
-    pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
+    pub async fn execute(mut self, ctx: CliContext) -> eyre::Result<()> {
         info!(target: "reth::cli", "reth {} starting", crate_version!());
 
+        for (fork, condition) in self.overrides.apply(&mut self.chain) {
+            info!(target: "reth::cli", ?fork, ?condition, "Overriding fork activation");
+        }
+
         // Raise the fd limit of the process.
         // Does not do anything on windows.
         raise_fd_limit();
 
         // add network name to data dir
-        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let data_dir = self
+            .datadir
+            .ephemeral_or_chain_default(self.chain.chain)
+            .wrap_err("Could not create ephemeral data dir")?;
+        if data_dir.is_ephemeral() {
+            info!(target: "reth::cli", path = %data_dir, "Using temporary ephemeral data dir");
+        }
         let config_path = self.config.clone().unwrap_or(data_dir.config_path());
 
         let mut config: Config = self.load_config(config_path.clone())?;
@@ -172,6 +240,10 @@ impl Command {
 
         let genesis_hash = init_genesis(db.clone(), self.chain.clone())?;
 
+        if !config.stages.index_logs.enabled {
+            drop_log_index(db.as_ref())?;
+        }
+
         let consensus: Arc<dyn Consensus> = if self.auto_mine {
             debug!(target: "reth::cli", "Using auto seal");
             Arc::new(AutoSealConsensus::new(Arc::clone(&self.chain)))
@@ -179,7 +251,10 @@ impl Command {
             Arc::new(BeaconConsensus::new(Arc::clone(&self.chain)))
         };
 
-        self.init_trusted_nodes(&mut config);
+        self.network.apply_peers(&mut config.peers)?;
+        self.network.apply_sessions(&mut config.sessions);
+        self.downloader.apply(&mut config.stages);
+        self.rpc.gas_price_oracle = config.gpo;
 
         // configure blockchain tree
         let tree_externals = TreeExternals::new(
@@ -203,9 +278,12 @@ impl Command {
         let shareable_db = ShareableDatabase::new(Arc::clone(&db), Arc::clone(&self.chain));
         let blockchain_db = BlockchainProvider::new(shareable_db, blockchain_tree.clone());
 
-        let transaction_pool = reth_transaction_pool::Pool::eth_pool(
+        let blob_store = BlobStore::open(data_dir.blobstore_path(), DEFAULT_BLOB_RETENTION_BLOCKS)
+            .wrap_err("Failed to open the blob store")?;
+        let transaction_pool = reth_transaction_pool::Pool::eth_pool_with_blob_store(
             EthTransactionValidator::new(blockchain_db.clone(), Arc::clone(&self.chain)),
             Default::default(),
+            blob_store,
         );
         info!(target: "reth::cli", "Transaction pool initialized");
 
@@ -248,7 +326,7 @@ impl Command {
             ctx.task_executor.clone(),
             secret_key,
             default_peers_path.clone(),
-        );
+        )?;
         let network = self
             .start_network(
                 network_config,
@@ -316,6 +394,7 @@ impl Command {
                     &mut config,
                     network.clone(),
                     client,
+                    network.clone(),
                     Arc::clone(&consensus),
                     db.clone(),
                     &ctx.task_executor,
@@ -328,12 +407,38 @@ impl Command {
             ctx.task_executor.spawn(Box::pin(task));
 
             pipeline
+        } else if !self.sentries.is_empty() {
+            info!(target: "reth::cli", sentries = ?self.sentries, "Downloading blocks through sentries");
+            let secret_path =
+                self.sentry_jwtsecret.clone().unwrap_or_else(|| data_dir.sentry_jwt_path());
+            let secret = JwtSecret::from_file(&secret_path).wrap_err_with(|| {
+                format!("Could not load the sentry JWT secret {secret_path:?}")
+            })?;
+            let client = SentryClient::new(&self.sentries, secret)?;
+            ctx.task_executor
+                .spawn(Box::pin(client.clone().peer_count_updates(SENTRY_PEER_COUNT_INTERVAL)));
+            ctx.task_executor.spawn(Box::pin(exchange_transactions_with_sentries(
+                client.clone(),
+                transaction_pool.clone(),
+            )));
+            let status_updater = SentryStatusUpdater::new(client.clone(), network.clone());
+            self.build_networked_pipeline(
+                &mut config,
+                network.clone(),
+                client,
+                status_updater,
+                Arc::clone(&consensus),
+                db.clone(),
+                &ctx.task_executor,
+            )
+            .await?
         } else {
             let client = network.fetch_client().await?;
             self.build_networked_pipeline(
                 &mut config,
                 network.clone(),
                 client,
+                network.clone(),
                 Arc::clone(&consensus),
                 db.clone(),
                 &ctx.task_executor,
@@ -356,6 +461,12 @@ impl Command {
         ctx.task_executor.spawn_critical("payload builder service", payload_service);
 
         let pipeline_events = pipeline.events();
+
+        // stream the pipeline events to `reth_subscribeSyncEvents` subscribers
+        let (sync_events, _) = broadcast::channel(SYNC_EVENTS_CHANNEL_CAPACITY);
+        ctx.task_executor
+            .spawn(Box::pin(events::forward_sync_events(pipeline.events(), sync_events.clone())));
+
         let (beacon_consensus_engine, beacon_engine_handle) = BeaconConsensusEngine::with_channel(
             Arc::clone(&db),
             ctx.task_executor.clone(),
@@ -384,6 +495,8 @@ impl Command {
             self.chain.clone(),
             beacon_engine_handle,
             payload_builder.into(),
+            transaction_pool.blob_store().clone(),
+            client_version(),
         );
         info!(target: "reth::cli", "Engine API handler initialized");
 
@@ -391,6 +504,12 @@ impl Command {
         let default_jwt_path = data_dir.jwt_path();
         let jwt_secret = self.rpc.jwt_secret(default_jwt_path)?;
 
+        // the prefunded dev accounts are only unlocked on the dev chain
+        let signers = self.rpc.signers(self.chain.genesis_hash() == DEV.genesis_hash())?;
+        if self.rpc.rpc_unsafe_signer {
+            warn!(target: "reth::cli", "The unsafe local signer is enabled, only use it for local development");
+        }
+
         // Start RPC servers
         let (_rpc_server, _auth_server) = self
             .rpc
@@ -402,6 +521,8 @@ impl Command {
                 blockchain_tree,
                 engine_api,
                 jwt_secret,
+                sync_events,
+                signers,
             )
             .await?;
 
@@ -427,17 +548,20 @@ impl Command {
     }
 
     /// Constructs a [Pipeline] that's wired to the network
-    async fn build_networked_pipeline<Client>(
+    #[allow(clippy::too_many_arguments)]
+    async fn build_networked_pipeline<Client, S>(
         &self,
         config: &mut Config,
         network: NetworkHandle,
         client: Client,
+        status_updater: S,
         consensus: Arc<dyn Consensus>,
         db: Arc<Env<WriteMap>>,
         task_executor: &TaskExecutor,
     ) -> eyre::Result<Pipeline<Env<WriteMap>, NetworkHandle>>
     where
         Client: HeadersClient + BodiesClient + Clone + 'static,
+        S: StatusUpdater + 'static,
     {
         let max_block = if let Some(block) = self.debug.max_block {
             Some(block)
@@ -462,6 +586,7 @@ impl Command {
                 header_downloader,
                 body_downloader,
                 network.clone(),
+                status_updater,
                 consensus,
                 max_block,
                 self.debug.continuous,
@@ -477,17 +602,6 @@ impl Command {
             .wrap_err_with(|| format!("Could not load config file {:?}", config_path))
     }
 
-    fn init_trusted_nodes(&self, config: &mut Config) {
-        config.peers.connect_trusted_nodes_only = self.network.trusted_only;
-
-        if !self.network.trusted_peers.is_empty() {
-            info!(target: "reth::cli", "Adding trusted nodes");
-            self.network.trusted_peers.iter().for_each(|peer| {
-                config.peers.trusted_nodes.insert(*peer);
-            });
-        }
-    }
-
     async fn start_metrics_endpoint(&self, db: Arc<Env<WriteMap>>) -> eyre::Result<()> {
         if let Some(listen_addr) = self.metrics {
             info!(target: "reth::cli", addr = %listen_addr, "Starting metrics endpoint");
@@ -512,13 +626,19 @@ impl Command {
         Pool: TransactionPool + Unpin + 'static,
     {
         let client = config.client.clone();
+        let discv5_port = config.discovery_v5_config.as_ref().map(|c| c.discovery_addr.port());
         let (handle, network, txpool, eth) = NetworkManager::builder(config)
             .await?
             .transactions(pool)
             .request_handler(client)
             .split_with_handle();
 
-        let known_peers_file = self.network.persistent_peers_file(default_peers_path);
+        // the network has no peers if the node uses sentries, keep the known peers of the file
+        let known_peers_file = if self.sentries.is_empty() {
+            self.network.persistent_peers_file(default_peers_path)
+        } else {
+            None
+        };
         task_executor.spawn_critical_with_signal("p2p network task", |shutdown| {
             run_network_until_shutdown(shutdown, network, known_peers_file)
         });
@@ -526,6 +646,17 @@ impl Command {
         task_executor.spawn_critical("p2p eth request handler", eth);
         task_executor.spawn_critical("p2p txpool request handler", txpool);
 
+        if self.sentries.is_empty() && self.network.nat.supports_port_mapping() {
+            let mut mappings = vec![PortMapping::tcp(handle.local_addr().port())];
+            if let Some(discv4) = handle.discv4() {
+                mappings.push(PortMapping::udp(discv4.local_addr().port()));
+            }
+            if let Some(port) = discv5_port {
+                mappings.push(PortMapping::udp(port));
+            }
+            task_executor.spawn(Box::pin(maintain_port_mappings(self.network.nat, mappings)));
+        }
+
         Ok(handle)
     }
 
@@ -615,31 +746,36 @@ impl Command {
         executor: TaskExecutor,
         secret_key: SecretKey,
         default_peers_path: PathBuf,
-    ) -> NetworkConfig<ShareableDatabase<Arc<Env<WriteMap>>>> {
+    ) -> eyre::Result<NetworkConfig<ShareableDatabase<Arc<Env<WriteMap>>>>> {
         let head = self.lookup_head(Arc::clone(&db)).expect("the head block is missing");
 
-        self.network
-            .network_config(config, self.chain.clone(), secret_key, default_peers_path)
+        let builder = self
+            .network
+            .network_config(config, self.chain.clone(), secret_key, default_peers_path)?
             .with_task_executor(Box::new(executor))
-            .set_head(head)
-            .listener_addr(SocketAddr::V4(SocketAddrV4::new(
-                Ipv4Addr::UNSPECIFIED,
-                self.network.port.unwrap_or(DEFAULT_DISCOVERY_PORT),
-            )))
-            .discovery_addr(SocketAddr::V4(SocketAddrV4::new(
-                Ipv4Addr::UNSPECIFIED,
-                self.network.discovery.port.unwrap_or(DEFAULT_DISCOVERY_PORT),
-            )))
-            .build(ShareableDatabase::new(db, self.chain.clone()))
+            .set_head(head);
+        let builder = if self.sentries.is_empty() {
+            self.network.apply_addrs(builder)?
+        } else {
+            // the sentries are the node's only link to the p2p network, so its own network
+            // neither discovers nor accepts peers and isn't reachable from outside
+            builder
+                .boot_nodes(Vec::new())
+                .disable_discovery()
+                .peer_config(PeersConfig::default().with_max_inbound(0).with_max_outbound(0))
+                .listener_addr(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0))
+        };
+        Ok(builder.build(ShareableDatabase::new(db, self.chain.clone())))
     }
 
     #[allow(clippy::too_many_arguments)]
-    async fn build_pipeline<H, B, U>(
+    async fn build_pipeline<H, B, U, S>(
         &self,
         config: &Config,
         header_downloader: H,
         body_downloader: B,
         updater: U,
+        status_updater: S,
         consensus: Arc<dyn Consensus>,
         max_block: Option<u64>,
         continuous: bool,
@@ -647,7 +783,8 @@ impl Command {
     where
         H: HeaderDownloader + 'static,
         B: BodyDownloader + 'static,
-        U: SyncStateUpdater + StatusUpdater + Clone + 'static,
+        U: SyncStateUpdater + 'static,
+        S: StatusUpdater + 'static,
     {
         let stage_conf = &config.stages;
 
@@ -677,31 +814,40 @@ impl Command {
 
         let factory = factory.with_stack_config(stack_config);
 
+        let mut total_difficulty_stage = TotalDifficultyStage::new(consensus.clone())
+            .with_commit_threshold(stage_conf.total_difficulty.commit_threshold);
+        if let Some(path) = &self.header_accumulator {
+            let accumulator = load_header_accumulator(path, &self.chain)?;
+            total_difficulty_stage =
+                total_difficulty_stage.with_header_accumulator(Arc::new(accumulator));
+        }
+
         let header_mode =
             if continuous { HeaderSyncMode::Continuous } else { HeaderSyncMode::Tip(tip_rx) };
+        let mut stages = DefaultStages::new(
+            header_mode,
+            Arc::clone(&consensus),
+            header_downloader,
+            body_downloader,
+            status_updater,
+            factory.clone(),
+        )
+        .set(total_difficulty_stage)
+        .set(SenderRecoveryStage { commit_threshold: stage_conf.sender_recovery.commit_threshold })
+        .set(ExecutionStage::new(factory, stage_conf.execution.commit_threshold))
+        .disable_if(MERKLE_UNWIND, || self.auto_mine)
+        .disable_if(MERKLE_EXECUTION, || self.auto_mine);
+        if stage_conf.index_logs.enabled {
+            stages = stages.add_before(
+                IndexLogsStage { commit_threshold: stage_conf.index_logs.commit_threshold },
+                FINISH,
+            );
+        }
+
         let pipeline = builder
-            .with_sync_state_updater(updater.clone())
+            .with_sync_state_updater(updater)
             .with_tip_sender(tip_tx)
-            .add_stages(
-                DefaultStages::new(
-                    header_mode,
-                    Arc::clone(&consensus),
-                    header_downloader,
-                    body_downloader,
-                    updater,
-                    factory.clone(),
-                )
-                .set(
-                    TotalDifficultyStage::new(consensus)
-                        .with_commit_threshold(stage_conf.total_difficulty.commit_threshold),
-                )
-                .set(SenderRecoveryStage {
-                    commit_threshold: stage_conf.sender_recovery.commit_threshold,
-                })
-                .set(ExecutionStage::new(factory, stage_conf.execution.commit_threshold))
-                .disable_if(MERKLE_UNWIND, || self.auto_mine)
-                .disable_if(MERKLE_EXECUTION, || self.auto_mine),
-            )
+            .add_stages(stages)
             .build();
 
         Ok(pipeline)
@@ -709,7 +855,67 @@ impl Command {
 }
 
 /// Drives the [NetworkManager] future until a [Shutdown](reth_tasks::shutdown::Shutdown) signal is
-/// received. If configured, this writes known peers to `persistent_peers_file` afterwards.
+/// received. If configured, this writes known peers to `persistent_peers_file` periodically and
+/// afterwards.
+/// Drops the log index, if any.
+///
+/// The index isn't unwound while the stage that maintains it is disabled, so it would miss the logs
+/// of the blocks after a reorg.
+fn drop_log_index<DB: Database>(db: &DB) -> Result<(), reth_interfaces::db::Error> {
+    db.update(|tx| {
+        if tx.get::<tables::SyncStage>(stage::INDEX_LOGS.to_string())?.is_none() {
+            return Ok(())
+        }
+        info!(target: "reth::cli", "Log indexing is disabled, dropping the log index");
+        tx.clear::<tables::LogAddressIndex>()?;
+        tx.clear::<tables::LogTopicIndex>()?;
+        tx.delete::<tables::SyncStage>(stage::INDEX_LOGS.to_string(), None)?;
+        Ok(())
+    })?
+}
+
+/// Exchanges the transactions of the pool with the peers of the sentries.
+///
+/// New pending transactions of the pool are sent to the sentries, which propagate them to their
+/// peers, and the transactions the peers of the sentries broadcast are imported into the pool.
+async fn exchange_transactions_with_sentries<Pool>(client: SentryClient, pool: Pool)
+where
+    Pool: TransactionPool + 'static,
+{
+    let mut pending = pool.pending_transactions_listener();
+    let mut incoming = client.transactions();
+    loop {
+        tokio::select! {
+            hash = pending.recv() => {
+                let Some(hash) = hash else { return };
+                let mut hashes = vec![hash];
+                while let Ok(hash) = pending.try_recv() {
+                    hashes.push(hash);
+                }
+                let transactions = pool
+                    .get_all(hashes)
+                    .into_iter()
+                    .filter(|tx| tx.propagate)
+                    .map(|tx| tx.transaction.to_recovered_transaction().into_signed())
+                    .collect::<Vec<_>>();
+                if !transactions.is_empty() {
+                    client.send_transactions(&transactions);
+                }
+            }
+            Some(transactions) = incoming.next() => {
+                let transactions = transactions
+                    .into_iter()
+                    .filter_map(|tx| tx.into_ecrecovered())
+                    .map(Pool::Transaction::from_recovered_transaction)
+                    .collect();
+                if let Err(err) = pool.add_external_transactions(transactions).await {
+                    debug!(target: "reth::cli", ?err, "Failed to import transactions of the sentries");
+                }
+            }
+        }
+    }
+}
+
 async fn run_network_until_shutdown<C>(
     shutdown: reth_tasks::shutdown::Shutdown,
     network: NetworkManager<C>,
@@ -719,32 +925,104 @@ async fn run_network_until_shutdown<C>(
 {
     pin_mut!(network, shutdown);
 
-    tokio::select! {
-        _ = &mut network => {},
-        _ = shutdown => {},
+    let mut persist_interval = tokio::time::interval_at(
+        tokio::time::Instant::now() + PERSIST_PEERS_INTERVAL,
+        PERSIST_PEERS_INTERVAL,
+    );
+    loop {
+        tokio::select! {
+            _ = &mut network => break,
+            _ = &mut shutdown => break,
+            _ = persist_interval.tick() => {
+                if let Some(file_path) = &persistent_peers_file {
+                    write_peers_to_file(&network, file_path);
+                }
+            }
+        }
     }
 
     if let Some(file_path) = persistent_peers_file {
-        let known_peers = network.all_peers().collect::<Vec<_>>();
-        if let Ok(known_peers) = serde_json::to_string_pretty(&known_peers) {
-            trace!(target : "reth::cli", peers_file =?file_path, num_peers=%known_peers.len(), "Saving current peers");
-            let parent_dir = file_path.parent().map(std::fs::create_dir_all).transpose();
-            match parent_dir.and_then(|_| std::fs::write(&file_path, known_peers)) {
-                Ok(_) => {
-                    info!(target: "reth::cli", peers_file=?file_path, "Wrote network peers to file");
-                }
-                Err(err) => {
-                    warn!(target: "reth::cli", ?err, peers_file=?file_path, "Failed to write network peers to file");
-                }
+        write_peers_to_file(&network, &file_path);
+    }
+}
+
+/// Writes the peers worth remembering across restarts and the banned peers to the file.
+fn write_peers_to_file<C>(network: &NetworkManager<C>, file_path: &Path)
+where
+    C: BlockProvider,
+{
+    let known_peers = PersistedPeers {
+        peers: network.persisted_peers().collect(),
+        bans: network.persisted_bans().collect(),
+        ip_bans: network.persisted_ip_bans().collect(),
+    };
+    let num_peers = known_peers.peers.len();
+    let num_bans = known_peers.bans.len() + known_peers.ip_bans.len();
+    if let Ok(known_peers) = serde_json::to_string_pretty(&known_peers) {
+        trace!(target : "reth::cli", peers_file =?file_path, num_peers, num_bans, "Saving current peers");
+        let parent_dir = file_path.parent().map(std::fs::create_dir_all).transpose();
+        match parent_dir.and_then(|_| write_file_atomic(file_path, known_peers.as_bytes())) {
+            Ok(_) => {
+                info!(target: "reth::cli", peers_file=?file_path, "Wrote network peers to file");
+            }
+            Err(err) => {
+                warn!(target: "reth::cli", ?err, peers_file=?file_path, "Failed to write network peers to file");
             }
         }
     }
 }
 
+/// Writes the contents to a temporary file next to the file and renames it afterwards, so the file
+/// is never left partially written if the node crashes mid-write.
+fn write_file_atomic(file_path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut tmp_path = file_path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let mut file = std::fs::File::create(&tmp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(tmp_path, file_path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::{net::IpAddr, path::Path};
+    use reth_db::{models::ShardedKey, BlockNumberList};
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        path::Path,
+    };
+
+    #[test]
+    fn drops_log_index() {
+        let db = reth_db::mdbx::test_utils::create_test_rw_db();
+        let key = ShardedKey::new(H256::random(), u64::MAX);
+        db.update(|tx| {
+            tx.put::<tables::LogTopicIndex>(key.clone(), BlockNumberList::new([1]).unwrap())
+                .unwrap();
+            tx.put::<tables::SyncStage>(stage::INDEX_LOGS.to_string(), 1).unwrap();
+        })
+        .unwrap();
+
+        drop_log_index(db.as_ref()).unwrap();
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.get::<tables::LogTopicIndex>(key).unwrap(), None);
+        assert_eq!(tx.get::<tables::SyncStage>(stage::INDEX_LOGS.to_string()).unwrap(), None);
+    }
+
+    #[test]
+    fn writes_peers_file_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("known-peers.json");
+
+        write_file_atomic(&file_path, b"old").unwrap();
+        write_file_atomic(&file_path, b"new").unwrap();
+        assert_eq!(std::fs::read(&file_path).unwrap(), b"new");
+
+        // the temporary file is renamed
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
 
     #[test]
     fn parse_help_node_command() {
@@ -754,7 +1032,7 @@ mod tests {
 
     #[test]
     fn parse_common_node_command_chain_args() {
-        for chain in ["mainnet", "sepolia", "goerli"] {
+        for chain in ["mainnet", "sepolia", "goerli", "holesky", "dev"] {
             let args: Command = Command::parse_from(["reth", "--chain", chain]);
             assert_eq!(args.chain.chain, chain.parse().unwrap());
         }
@@ -790,14 +1068,14 @@ mod tests {
     fn parse_config_path() {
         let cmd = Command::try_parse_from(["reth", "--config", "my/path/to/reth.toml"]).unwrap();
         // always store reth.toml in the data dir, not the chain specific data dir
-        let data_dir = cmd.datadir.unwrap_or_chain_default(cmd.chain.chain);
+        let data_dir = cmd.datadir.ephemeral_or_chain_default(cmd.chain.chain).unwrap();
         let config_path = cmd.config.unwrap_or(data_dir.config_path());
         assert_eq!(config_path, Path::new("my/path/to/reth.toml"));
 
         let cmd = Command::try_parse_from(["reth"]).unwrap();
 
         // always store reth.toml in the data dir, not the chain specific data dir
-        let data_dir = cmd.datadir.unwrap_or_chain_default(cmd.chain.chain);
+        let data_dir = cmd.datadir.ephemeral_or_chain_default(cmd.chain.chain).unwrap();
         let config_path = cmd.config.clone().unwrap_or(data_dir.config_path());
         assert!(config_path.ends_with("reth/mainnet/reth.toml"), "{:?}", cmd.config);
     }
@@ -805,13 +1083,21 @@ mod tests {
     #[test]
     fn parse_db_path() {
         let cmd = Command::try_parse_from(["reth", "--db", "my/path/to/db"]).unwrap();
-        let data_dir = cmd.datadir.unwrap_or_chain_default(cmd.chain.chain);
+        let data_dir = cmd.datadir.ephemeral_or_chain_default(cmd.chain.chain).unwrap();
         let db_path = cmd.db.unwrap_or(data_dir.db_path());
         assert_eq!(db_path, Path::new("my/path/to/db"));
 
         let cmd = Command::try_parse_from(["reth"]).unwrap();
-        let data_dir = cmd.datadir.unwrap_or_chain_default(cmd.chain.chain);
+        let data_dir = cmd.datadir.ephemeral_or_chain_default(cmd.chain.chain).unwrap();
         let db_path = cmd.db.unwrap_or(data_dir.db_path());
         assert!(db_path.ends_with("reth/mainnet/db"), "{:?}", cmd.config);
     }
+
+    #[test]
+    fn parse_ephemeral_datadir() {
+        let cmd = Command::try_parse_from(["reth", "--datadir", ":memory:"]).unwrap();
+        let data_dir = cmd.datadir.ephemeral_or_chain_default(cmd.chain.chain).unwrap();
+        assert!(data_dir.is_ephemeral());
+        assert!(data_dir.db_path().starts_with(std::env::temp_dir()), "{:?}", data_dir);
+    }
 }
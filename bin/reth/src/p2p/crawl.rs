@@ -0,0 +1,275 @@
+//! Command that crawls the discv4 network.
+use clap::{Parser, ValueEnum};
+use futures::{stream::FuturesUnordered, StreamExt};
+use reth_discv4::{DiscoveryUpdate, Discv4};
+use reth_network::{NetworkEvent, NetworkHandle};
+use reth_primitives::{ChainSpec, ForkFilter, ForkId, Head, NodeRecord, PeerId};
+use serde::Serialize;
+use std::{collections::BTreeMap, io::Write, path::PathBuf, time::Duration};
+use tracing::{debug, info};
+
+/// The format of the crawled node list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum NodeListFormat {
+    /// A JSON array of the nodes.
+    #[default]
+    Json,
+    /// Comma separated values with a header row.
+    Csv,
+}
+
+/// Crawls the network with repeated discv4 lookups and writes all discovered nodes to a file.
+///
+/// The client version of a node is known if the network managed to establish a session with the
+/// node while crawling.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// How long to crawl, in seconds.
+    #[arg(long, value_name = "SECONDS", default_value_t = 300)]
+    duration: u64,
+
+    /// The number of lookups that run concurrently.
+    #[arg(long, value_name = "NUM", default_value_t = 4)]
+    concurrency: usize,
+
+    /// Only include nodes whose fork id is compatible with the chain.
+    ///
+    /// Nodes whose fork id is unknown are excluded as well.
+    #[arg(long)]
+    filter_fork_id: bool,
+
+    /// The format of the node list.
+    #[arg(long, value_enum, default_value_t)]
+    format: NodeListFormat,
+
+    /// The file to write the node list to. Defaults to stdout.
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+impl Command {
+    /// Execute `p2p crawl` command
+    pub async fn execute(&self, network: &NetworkHandle, chain: &ChainSpec) -> eyre::Result<()> {
+        let discv4 = network
+            .discv4()
+            .cloned()
+            .ok_or_else(|| eyre::eyre!("Crawling requires discv4, which is disabled"))?;
+        let mut updates = discv4.update_stream().await?;
+        let mut events = network.event_listener();
+
+        let mut lookups = (0..self.concurrency.max(1))
+            .map(|_| random_lookup(discv4.clone()))
+            .collect::<FuturesUnordered<_>>();
+        let deadline = tokio::time::sleep(Duration::from_secs(self.duration));
+        tokio::pin!(deadline);
+
+        info!(target: "reth::cli", duration = self.duration, "Crawling the network");
+        let mut crawl = Crawl::default();
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                Some(res) = lookups.next() => {
+                    match res {
+                        Ok(records) => records.into_iter().for_each(|r| crawl.on_record(r)),
+                        Err(err) => debug!(target: "reth::cli", %err, "Lookup failed"),
+                    }
+                    lookups.push(random_lookup(discv4.clone()));
+                }
+                Some(update) = updates.next() => crawl.on_discovery_update(update),
+                Some(event) = events.next() => crawl.on_network_event(event),
+            }
+        }
+
+        let fork_filter = self.filter_fork_id.then(|| genesis_fork_filter(chain));
+        let nodes = crawl.into_nodes(fork_filter.as_ref());
+        let out = match self.format {
+            NodeListFormat::Json => serde_json::to_string_pretty(&nodes)? + "\n",
+            NodeListFormat::Csv => csv_records(&nodes),
+        };
+        match &self.output {
+            Some(path) => std::fs::write(path, out)?,
+            None => std::io::stdout().write_all(out.as_bytes())?,
+        }
+
+        info!(target: "reth::cli", nodes = nodes.len(), "Finished crawling");
+        Ok(())
+    }
+}
+
+/// Looks up a random target.
+async fn random_lookup(discv4: Discv4) -> eyre::Result<Vec<NodeRecord>> {
+    Ok(discv4.lookup(PeerId::random()).await?)
+}
+
+/// Returns the fork filter of the chain at genesis.
+///
+/// The filter accepts all fork ids of the chain, including the ids of future forks.
+fn genesis_fork_filter(chain: &ChainSpec) -> ForkFilter {
+    chain.fork_filter(Head {
+        hash: chain.genesis_hash(),
+        number: 0,
+        timestamp: chain.genesis.timestamp,
+        difficulty: chain.genesis.difficulty,
+        total_difficulty: chain.genesis.difficulty,
+    })
+}
+
+/// A node found while crawling.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct CrawledNode {
+    /// The enode URL of the node.
+    enode: NodeRecord,
+    /// The fork hash of the node, from its ENR or its `Status` message.
+    fork_hash: Option<String>,
+    /// The next fork of the node, from its ENR or its `Status` message.
+    fork_next: Option<u64>,
+    /// The client version of the node, from its `Hello` message.
+    client_version: Option<String>,
+}
+
+impl CrawledNode {
+    fn new(enode: NodeRecord) -> Self {
+        Self { enode, fork_hash: None, fork_next: None, client_version: None }
+    }
+
+    fn set_fork_id(&mut self, fork_id: ForkId) {
+        self.fork_hash = Some(format!("0x{}", hex::encode(fork_id.hash.0)));
+        self.fork_next = Some(fork_id.next);
+    }
+}
+
+/// The state of a crawl.
+#[derive(Debug, Default)]
+struct Crawl {
+    /// All discovered nodes.
+    nodes: BTreeMap<PeerId, CrawledNode>,
+    /// The fork ids of the discovered nodes.
+    fork_ids: BTreeMap<PeerId, ForkId>,
+    /// The client versions of nodes that a session was established with, also includes nodes
+    /// that weren't discovered via discv4.
+    client_versions: BTreeMap<PeerId, String>,
+}
+
+impl Crawl {
+    fn on_record(&mut self, record: NodeRecord) {
+        self.nodes.entry(record.id).or_insert_with(|| CrawledNode::new(record));
+    }
+
+    fn on_discovery_update(&mut self, update: DiscoveryUpdate) {
+        match update {
+            DiscoveryUpdate::Added(record) | DiscoveryUpdate::DiscoveredAtCapacity(record) => {
+                self.on_record(record)
+            }
+            DiscoveryUpdate::EnrForkId(record, fork_id) => {
+                self.on_record(record);
+                self.fork_ids.insert(record.id, fork_id);
+            }
+            DiscoveryUpdate::Batch(updates) => {
+                updates.into_iter().for_each(|update| self.on_discovery_update(update))
+            }
+            DiscoveryUpdate::Removed(_) => {}
+        }
+    }
+
+    fn on_network_event(&mut self, event: NetworkEvent) {
+        if let NetworkEvent::SessionEstablished { peer_id, client_version, status, .. } = event {
+            self.client_versions.insert(peer_id, client_version.to_string());
+            // the ENR fork id takes precedence
+            self.fork_ids.entry(peer_id).or_insert(status.forkid);
+        }
+    }
+
+    /// Returns the discovered nodes, optionally only those with a compatible fork id.
+    fn into_nodes(mut self, fork_filter: Option<&ForkFilter>) -> Vec<CrawledNode> {
+        self.nodes
+            .into_values()
+            .filter_map(|mut node| {
+                let fork_id = self.fork_ids.get(&node.enode.id).copied();
+                if let Some(filter) = fork_filter {
+                    if !fork_id.map_or(false, |fork_id| filter.validate(fork_id).is_ok()) {
+                        return None
+                    }
+                }
+                if let Some(fork_id) = fork_id {
+                    node.set_fork_id(fork_id);
+                }
+                node.client_version = self.client_versions.remove(&node.enode.id);
+                Some(node)
+            })
+            .collect()
+    }
+}
+
+/// Formats the nodes as CSV.
+fn csv_records(nodes: &[CrawledNode]) -> String {
+    let mut out = String::from("enode,fork_hash,fork_next,client_version\n");
+    for node in nodes {
+        let fork_next = node.fork_next.map(|next| next.to_string());
+        let fields = [
+            Some(node.enode.to_string()),
+            node.fork_hash.clone(),
+            fork_next,
+            node.client_version.clone(),
+        ];
+        let line = fields.iter().map(|field| csv_field(field.as_deref())).collect::<Vec<_>>();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quotes the field if it contains characters that have a meaning in CSV.
+fn csv_field(field: Option<&str>) -> String {
+    match field {
+        Some(field) if field.contains([',', '"', '\n']) => {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        }
+        Some(field) => field.to_string(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{hex_literal::hex, ForkHash, MAINNET};
+
+    const ENODE: &str = "enode://6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0@10.3.58.6:30303";
+
+    #[test]
+    fn filters_by_fork_id() {
+        let mut crawl = Crawl::default();
+        let record: NodeRecord = ENODE.parse().unwrap();
+        let other = NodeRecord { id: PeerId::random(), ..record };
+        let unknown = NodeRecord { id: PeerId::random(), ..record };
+
+        // mainnet fork id of Shanghai
+        let fork_id = ForkId { hash: ForkHash(hex!("dce96c2d")), next: 0 };
+        let other_fork_id = ForkId { hash: ForkHash(hex!("deadbeef")), next: 0 };
+        crawl.on_discovery_update(DiscoveryUpdate::Batch(vec![
+            DiscoveryUpdate::EnrForkId(record, fork_id),
+            DiscoveryUpdate::EnrForkId(other, other_fork_id),
+            DiscoveryUpdate::Added(unknown),
+        ]));
+        assert_eq!(crawl.nodes.len(), 3);
+
+        let filter = genesis_fork_filter(&MAINNET);
+        let nodes = crawl.into_nodes(Some(&filter));
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].enode, record);
+        assert_eq!(nodes[0].fork_hash.as_deref(), Some("0xdce96c2d"));
+    }
+
+    #[test]
+    fn formats_csv() {
+        let mut node = CrawledNode::new(ENODE.parse().unwrap());
+        node.client_version = Some("Geth/v1.11.5,\"stable\"".to_string());
+        let csv = csv_records(&[node]);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("enode,fork_hash,fork_next,client_version"));
+        assert_eq!(
+            lines.next(),
+            Some(format!("{ENODE},,,\"Geth/v1.11.5,\"\"stable\"\"\"").as_str())
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! Commands for managing the node's discovery and RLPx secret key
+use crate::args::{read_secret_key, secret_key_peer_id, write_secret_key};
+use clap::{Parser, Subcommand};
+use hex::encode as hex_encode;
+use reth_network::config::rng_secret_key;
+use secp256k1::SecretKey;
+use std::path::{Path, PathBuf};
+
+/// `reth p2p key` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth p2p key` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands {
+    /// Generate a new secret key
+    Generate {
+        /// Overwrite an existing secret key.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Import an existing hex encoded secret key, e.g. a geth `nodekey` file
+    Import {
+        /// The file to import the secret key from.
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Overwrite an existing secret key.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Export the secret key
+    Export {
+        /// The file to export the secret key to, printed to stdout if not specified.
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+impl Command {
+    /// Execute `p2p key` command for the secret key stored at the given path
+    pub fn execute(&self, secret_key_path: &Path) -> eyre::Result<()> {
+        match &self.command {
+            Subcommands::Generate { force } => {
+                store_secret_key(secret_key_path, rng_secret_key(), *force)?;
+            }
+            Subcommands::Import { file, force } => {
+                let secret = read_secret_key(file)
+                    .map_err(|err| eyre::eyre!("Failed to read {}: {err}", file.display()))?;
+                store_secret_key(secret_key_path, secret, *force)?;
+            }
+            Subcommands::Export { output } => {
+                let secret = read_secret_key(secret_key_path).map_err(|err| {
+                    eyre::eyre!("Failed to read {}: {err}", secret_key_path.display())
+                })?;
+                match output {
+                    Some(output) => {
+                        write_secret_key(output, &secret)?;
+                        println!("Secret key exported to {}", output.display());
+                    }
+                    None => println!("{}", hex_encode(secret.as_ref())),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the secret key to the path unless there already is one and `force` is not set.
+fn store_secret_key(path: &Path, secret: SecretKey, force: bool) -> eyre::Result<()> {
+    if !force && path.try_exists()? {
+        eyre::bail!("Secret key already exists at {}, use --force to overwrite it", path.display())
+    }
+    write_secret_key(path, &secret)?;
+    println!("Secret key written to {}", path.display());
+    println!("Peer ID: {:?}", secret_key_peer_id(&secret));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_does_not_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let nodekey = dir.path().join("nodekey");
+        let secret = rng_secret_key();
+        std::fs::write(&nodekey, hex_encode(secret.as_ref())).unwrap();
+
+        let path = dir.path().join("discovery-secret");
+        let import = Command::parse_from(["key", "import", nodekey.to_str().unwrap()]);
+        import.execute(&path).unwrap();
+        assert_eq!(read_secret_key(&path).unwrap(), secret);
+
+        let generate = Command::parse_from(["key", "generate"]);
+        assert!(generate.execute(&path).is_err());
+        assert_eq!(read_secret_key(&path).unwrap(), secret);
+
+        let generate = Command::parse_from(["key", "generate", "--force"]);
+        generate.execute(&path).unwrap();
+        assert_ne!(read_secret_key(&path).unwrap(), secret);
+    }
+}
@@ -17,6 +17,10 @@ use reth_staged_sync::{
 };
 use std::{path::PathBuf, sync::Arc};
 
+mod crawl;
+mod key;
+mod rlpx_ping;
+
 /// `reth p2p` command
 #[derive(Debug, Parser)]
 pub struct Command {
@@ -32,6 +36,8 @@ pub struct Command {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - dev
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
@@ -95,15 +101,33 @@ pub enum Subcommands {
         #[arg(value_parser = hash_or_num_value_parser)]
         id: BlockHashOrNumber,
     },
+    /// Manage the secret key used for discovery and RLPx
+    Key(key::Command),
+    /// Crawl the network and write the discovered nodes to a file
+    Crawl(crawl::Command),
+    /// Perform the RLPx handshakes with a peer and print its client version and capabilities
+    RlpxPing(rlpx_ping::Command),
 }
 impl Command {
     /// Execute `p2p` command
     pub async fn execute(&self) -> eyre::Result<()> {
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let default_secret_key_path = data_dir.p2p_secret_path();
+        let secret_key_path = self.p2p_secret_key.clone().unwrap_or(default_secret_key_path);
+
+        if let Subcommands::Key(command) = &self.command {
+            return command.execute(&secret_key_path)
+        }
+
+        if let Subcommands::RlpxPing(command) = &self.command {
+            let p2p_secret_key = get_secret_key(&secret_key_path)?;
+            return command.execute(p2p_secret_key, &self.chain).await
+        }
+
         let tempdir = tempfile::TempDir::new()?;
         let noop_db = Arc::new(Env::<WriteMap>::open(&tempdir.into_path(), EnvKind::RW)?);
 
-        // add network name to data dir
-        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
         let config_path = self.config.clone().unwrap_or(data_dir.config_path());
 
         let mut config: Config = confy::load_path(&config_path).unwrap_or_default();
@@ -118,12 +142,10 @@ impl Command {
 
         config.peers.connect_trusted_nodes_only = self.trusted_only;
 
-        let default_secret_key_path = data_dir.p2p_secret_path();
-        let secret_key_path = self.p2p_secret_key.clone().unwrap_or(default_secret_key_path);
         let p2p_secret_key = get_secret_key(&secret_key_path)?;
 
         let mut network_config_builder =
-            config.network_config(self.nat, None, p2p_secret_key).chain_spec(self.chain.clone());
+            config.network_config(self.nat, None, p2p_secret_key)?.chain_spec(self.chain.clone());
 
         network_config_builder = self.discovery.apply_to_builder(network_config_builder);
 
@@ -132,6 +154,10 @@ impl Command {
             .start_network()
             .await?;
 
+        if let Subcommands::Crawl(command) = &self.command {
+            return command.execute(&network, &self.chain).await
+        }
+
         let fetch_client = network.fetch_client().await?;
         let retries = self.retries.max(1);
         let backoff = ConstantBuilder::default().with_max_times(retries);
@@ -176,6 +202,9 @@ impl Command {
                 let body = result.into_iter().next().unwrap();
                 println!("Successfully downloaded body: {body:?}")
             }
+            Subcommands::Key(_) => unreachable!("key commands don't use the network"),
+            Subcommands::Crawl(_) => unreachable!("crawls don't use the fetch client"),
+            Subcommands::RlpxPing(_) => unreachable!("rlpx pings don't use the network"),
         }
 
         Ok(())
@@ -0,0 +1,135 @@
+//! Command that performs the RLPx handshakes with a single peer.
+use crate::{args::secret_key_peer_id, version::p2p_client_version};
+use clap::Parser;
+use eyre::WrapErr;
+use futures::{SinkExt, StreamExt};
+use reth_ecies::stream::ECIESStream;
+use reth_eth_wire::{
+    types::message::RequestPair, CanDisconnect, DisconnectReason, EthMessage, GetBlockHeaders,
+    HelloMessage, Status, UnauthedEthStream, UnauthedP2PStream,
+};
+use reth_primitives::{ChainSpec, Head, HeadersDirection, NodeRecord};
+use secp256k1::SecretKey;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+
+/// Connects to a peer, performs the ECIES, `Hello` and `Status` handshakes and prints what the
+/// peer announced.
+///
+/// Every step is reported as it completes, so a failing step shows why a peer won't connect.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The enode of the peer to connect to.
+    enode: NodeRecord,
+
+    /// Request the header of the peer's head block after the handshakes.
+    #[arg(long)]
+    probe: bool,
+
+    /// How long to wait for the whole exchange, in seconds.
+    #[arg(long, value_name = "SECONDS", default_value_t = 10)]
+    timeout: u64,
+}
+
+impl Command {
+    /// Execute `p2p rlpx-ping` command
+    pub async fn execute(&self, secret_key: SecretKey, chain: &ChainSpec) -> eyre::Result<()> {
+        tokio::time::timeout(Duration::from_secs(self.timeout), self.ping(secret_key, chain))
+            .await
+            .map_err(|_| eyre::eyre!("Timed out after {}s", self.timeout))?
+    }
+
+    async fn ping(&self, secret_key: SecretKey, chain: &ChainSpec) -> eyre::Result<()> {
+        let addr = self.enode.tcp_addr();
+        let started = Instant::now();
+        let tcp = TcpStream::connect(addr)
+            .await
+            .wrap_err_with(|| format!("Failed to connect to {addr}"))?;
+        println!("TCP connection to {addr} established in {:?}", started.elapsed());
+
+        let ecies = ECIESStream::connect(tcp, secret_key, self.enode.id)
+            .await
+            .wrap_err("ECIES handshake failed")?;
+        println!("ECIES handshake completed in {:?}", started.elapsed());
+
+        let hello = HelloMessage::builder(secret_key_peer_id(&secret_key))
+            .client_version(p2p_client_version(None))
+            // we're not listening for incoming connections
+            .port(0)
+            .build();
+        let (p2p_stream, their_hello) = UnauthedP2PStream::new(ecies)
+            .handshake(hello)
+            .await
+            .wrap_err("Hello handshake failed")?;
+        let capabilities = their_hello
+            .capabilities
+            .iter()
+            .map(|cap| format!("{}/{}", cap.name, cap.version))
+            .collect::<Vec<_>>();
+        let version = p2p_stream.shared_capability().version();
+        println!("Hello handshake completed in {:?}", started.elapsed());
+        println!("Client version: {}", their_hello.client_version);
+        println!("Capabilities: {}", capabilities.join(", "));
+        println!("Negotiated eth version: eth/{version}");
+
+        let head = Head {
+            hash: chain.genesis_hash(),
+            number: 0,
+            timestamp: chain.genesis.timestamp,
+            difficulty: chain.genesis.difficulty,
+            total_difficulty: chain.genesis.difficulty,
+        };
+        let status = Status { version, ..Status::spec_builder(chain, &head).build() };
+        let (mut eth_stream, their_status) = UnauthedEthStream::new(p2p_stream)
+            .handshake(status, chain.fork_filter(head))
+            .await
+            .wrap_err("Status handshake failed")?;
+        println!("Status handshake completed in {:?}", started.elapsed());
+        println!("Status: {their_status}");
+
+        if self.probe {
+            let request_id = 1;
+            let request = GetBlockHeaders {
+                start_block: their_status.blockhash.into(),
+                limit: 1,
+                skip: 0,
+                direction: HeadersDirection::Rising,
+            };
+            let sent = Instant::now();
+            eth_stream
+                .send(EthMessage::GetBlockHeaders(RequestPair { request_id, message: request }))
+                .await
+                .wrap_err("Failed to send headers probe")?;
+
+            loop {
+                match eth_stream.next().await {
+                    Some(Ok(EthMessage::BlockHeaders(response)))
+                        if response.request_id == request_id =>
+                    {
+                        match response.message.0.first() {
+                            Some(header) => println!(
+                                "Received head block header #{} in {:?}",
+                                header.number,
+                                sent.elapsed()
+                            ),
+                            None => println!(
+                                "Peer returned no header for its head block in {:?}",
+                                sent.elapsed()
+                            ),
+                        }
+                        break
+                    }
+                    // skip broadcasts the peer sends in the meantime
+                    Some(Ok(_)) => continue,
+                    Some(Err(err)) => return Err(err).wrap_err("Headers probe failed"),
+                    None => eyre::bail!("Peer disconnected before answering the headers probe"),
+                }
+            }
+        }
+
+        // best effort, the results are already printed
+        let _ = eth_stream.disconnect(DisconnectReason::ClientQuitting).await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+//! Standalone sentry command that only runs the p2p network.
+use crate::{
+    args::{get_secret_key, NetworkArgs},
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use clap::Parser;
+use eyre::Context;
+use reth_db::mdbx::{Env, EnvKind, WriteMap};
+use reth_network::NetworkManager;
+use reth_network_api::NetworkInfo;
+use reth_primitives::ChainSpec;
+use reth_provider::ShareableDatabase;
+use reth_rpc::JwtSecret;
+use reth_sentry::{SentryServer, SentryTransactions, DEFAULT_SENTRY_PORT};
+use reth_staged_sync::{utils::chainspec::genesis_value_parser, Config};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+};
+use tokio::sync::mpsc;
+use tracing::info;
+
+/// `reth sentry` command
+///
+/// Runs the p2p network without syncing or a database. Nodes download blocks from the peers of
+/// the sentry and exchange transactions with them with `reth node --sentry <URL>`.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The path to the configuration file to use.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    config: Option<PathBuf>,
+
+    /// Secret key to use for this sentry.
+    ///
+    /// This will also deterministically set the peer ID. If not specified, it will be set in the
+    /// data dir for the chain being used.
+    #[arg(long, value_name = "PATH")]
+    p2p_secret_key: Option<PathBuf>,
+
+    /// The chain this sentry is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    /// - holesky
+    /// - dev
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    network: NetworkArgs,
+
+    /// The address of the sentry gRPC server.
+    #[arg(long = "sentry.addr", value_name = "ADDR", default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    sentry_addr: IpAddr,
+
+    /// The port of the sentry gRPC server.
+    #[arg(long = "sentry.port", value_name = "PORT", default_value_t = DEFAULT_SENTRY_PORT)]
+    sentry_port: u16,
+
+    /// Path to the JWT secret the nodes authenticate with.
+    ///
+    /// If not specified, the secret is read from, or created in, the data dir for the chain being
+    /// used. The nodes that use the sentry need the same secret.
+    #[arg(long = "sentry.jwtsecret", value_name = "PATH")]
+    sentry_jwtsecret: Option<PathBuf>,
+}
+
+impl Command {
+    /// Execute `sentry` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let config_path = self.config.clone().unwrap_or(data_dir.config_path());
+        let mut config: Config = confy::load_path(&config_path)
+            .wrap_err_with(|| format!("Could not load config file {:?}", config_path))?;
+        self.network.apply_peers(&mut config.peers)?;
+        self.network.apply_sessions(&mut config.sessions);
+
+        let secret_key_path = self.p2p_secret_key.clone().unwrap_or(data_dir.p2p_secret_path());
+        let secret_key = get_secret_key(&secret_key_path)?;
+
+        // the sentry doesn't store any blocks, so it serves no block requests of its peers
+        let tempdir = tempfile::TempDir::new()?;
+        let db = Arc::new(Env::<WriteMap>::open(tempdir.path(), EnvKind::RW)?);
+
+        let builder = self.network.network_config(
+            &config,
+            self.chain.clone(),
+            secret_key,
+            data_dir.known_peers_path(),
+        )?;
+        let provider = ShareableDatabase::new(db, self.chain.clone());
+        let config = self.network.apply_addrs(builder)?.build(provider.clone());
+        let (network, mut manager, _, eth) =
+            NetworkManager::builder(config).await?.request_handler(provider).split_with_handle();
+        let (tx, rx) = mpsc::unbounded_channel();
+        manager.set_transactions(tx);
+        let (transactions, transactions_task) = SentryTransactions::new(network.clone(), rx);
+        tokio::spawn(manager);
+        tokio::spawn(eth);
+        tokio::spawn(transactions_task.run());
+        info!(target: "reth::cli", peer_id = %network.peer_id(), local_addr = %network.local_addr(), "Connected to P2P network");
+
+        let secret = match &self.sentry_jwtsecret {
+            Some(path) => JwtSecret::from_file(path)?,
+            None => {
+                let path = data_dir.sentry_jwt_path();
+                if path.exists() {
+                    JwtSecret::from_file(&path)?
+                } else {
+                    JwtSecret::try_create(&path)?
+                }
+            }
+        };
+
+        let client = network.fetch_client().await?;
+        let addr = SocketAddr::new(self.sentry_addr, self.sentry_port);
+        let (addr, _handle) =
+            SentryServer::new(client, network, transactions, secret).start(addr).await?;
+        info!(target: "reth::cli", %addr, "Sentry gRPC server started");
+
+        futures::future::pending().await
+    }
+}
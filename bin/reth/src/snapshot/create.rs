@@ -0,0 +1,262 @@
+use super::segment::{
+    SegmentInfo, SegmentKind, SegmentWriter, SnapshotManifest, StateEntry, StorageSlot,
+    SNAPSHOT_VERSION,
+};
+use clap::Parser;
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    tables,
+    transaction::DbTx,
+};
+use reth_primitives::{
+    Address, BlockBody, BlockHash, BlockNumber, ChainSpec, Header, ReceiptWithBloom,
+    TransactionSigned,
+};
+use reth_stages::stages::EXECUTION;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// The number of blocks or accounts that are read per database transaction.
+///
+/// Short read transactions keep a running node from growing its database, which can't reuse the
+/// pages that a reader may still see.
+const READ_BATCH_SIZE: usize = 10_000;
+
+/// Creates a snapshot of the canonical chain and the latest state.
+///
+/// The snapshot consists of gzip compressed segment files and a `manifest.json` that lists the
+/// checksums of all segments.
+///
+/// The data is read in batches of short database transactions. Every batch checks that the
+/// snapshot block is still canonical and every batch of the state that no new block was executed
+/// in the meantime, so creating a snapshot of a running node fails if the node executes a new
+/// block while the state is exported.
+#[derive(Debug, Parser)]
+pub struct CreateCommand {
+    /// The directory the segments and the manifest are written to.
+    #[arg(long, short, value_name = "DIR")]
+    output: PathBuf,
+
+    /// The number of blocks per headers, bodies and receipts segment.
+    #[arg(long, value_name = "BLOCKS", default_value_t = 500_000)]
+    blocks_per_segment: u64,
+}
+
+impl CreateCommand {
+    /// Execute `snapshot create` command
+    pub fn execute<DB: Database>(self, db: &DB, chain: &ChainSpec) -> eyre::Result<()> {
+        eyre::ensure!(self.blocks_per_segment > 0, "blocks per segment must be positive");
+
+        let tx = db.tx()?;
+
+        // the plain state is the state at the block the execution stage reached
+        let Some(block) = EXECUTION.get_progress(&tx)? else {
+            eyre::bail!("no blocks have been executed yet")
+        };
+        let Some(block_hash) = tx.get::<tables::CanonicalHeaders>(block)? else {
+            eyre::bail!("block {block} not found")
+        };
+        let Some(header) = tx.get::<tables::Headers>(block)? else {
+            eyre::bail!("header of block {block} not found")
+        };
+        let genesis_hash = tx.get::<tables::CanonicalHeaders>(0)?.unwrap_or_default();
+        drop(tx);
+        eyre::ensure!(
+            genesis_hash == chain.genesis_hash(),
+            "database does not belong to chain {}",
+            chain.chain
+        );
+
+        std::fs::create_dir_all(&self.output)?;
+        info!(target: "reth::cli", block, path = %self.output.display(), "Creating snapshot");
+
+        let mut segments = Vec::new();
+        let mut from = 0;
+        while from <= block {
+            let to = block.min(from + self.blocks_per_segment - 1);
+            segments.extend(write_block_segments(db, &self.output, (block, block_hash), from, to)?);
+            info!(target: "reth::cli", from, to, "Wrote block segments");
+            from = to + 1;
+        }
+        segments.push(write_state_segment(db, &self.output, (block, block_hash))?);
+
+        let manifest = SnapshotManifest {
+            version: SNAPSHOT_VERSION,
+            chain_id: chain.chain.id(),
+            genesis_hash,
+            block,
+            block_hash,
+            state_root: header.state_root,
+            segments,
+        };
+        manifest.write(&self.output)?;
+
+        info!(target: "reth::cli", block, hash = ?block_hash, "Snapshot created");
+        Ok(())
+    }
+}
+
+/// Ensures that the snapshot block is still canonical, so the blocks below it read by the
+/// transaction belong to the snapshot.
+fn ensure_canonical<'a, TX: DbTx<'a>>(
+    tx: &TX,
+    (block, block_hash): (BlockNumber, BlockHash),
+) -> eyre::Result<()> {
+    eyre::ensure!(
+        tx.get::<tables::CanonicalHeaders>(block)? == Some(block_hash),
+        "block {block} was unwound while the snapshot was created"
+    );
+    Ok(())
+}
+
+/// Ensures that the snapshot block is still the block the execution stage reached, so the plain
+/// state read by the transaction is the state of the snapshot.
+fn ensure_unchanged<'a, TX: DbTx<'a>>(
+    tx: &TX,
+    snapshot_block: (BlockNumber, BlockHash),
+) -> eyre::Result<()> {
+    ensure_canonical(tx, snapshot_block)?;
+    let block = snapshot_block.0;
+    eyre::ensure!(
+        EXECUTION.get_progress(tx)? == Some(block),
+        "the node executed new blocks while the snapshot was created, stop it and retry"
+    );
+    Ok(())
+}
+
+/// Writes the headers, bodies and receipts segments of the given range of blocks.
+fn write_block_segments<DB: Database>(
+    db: &DB,
+    dir: &Path,
+    snapshot_block: (BlockNumber, BlockHash),
+    from: BlockNumber,
+    to: BlockNumber,
+) -> eyre::Result<[SegmentInfo; 3]> {
+    let file_name = |kind: &str| format!("{kind}-{from:010}-{to:010}.rlp.gz");
+    let mut headers = SegmentWriter::<Header>::create(&dir.join(file_name("headers")))?;
+    let mut bodies = SegmentWriter::<BlockBody>::create(&dir.join(file_name("bodies")))?;
+    let mut receipts =
+        SegmentWriter::<Vec<ReceiptWithBloom>>::create(&dir.join(file_name("receipts")))?;
+
+    let numbers = (from..=to).collect::<Vec<_>>();
+    for batch in numbers.chunks(READ_BATCH_SIZE) {
+        let tx = db.tx()?;
+        // the blocks below the snapshot block are only canonical if the snapshot block still is
+        ensure_canonical(&tx, snapshot_block)?;
+
+        let mut transactions_cursor = tx.cursor_read::<tables::Transactions>()?;
+        let mut receipts_cursor = tx.cursor_read::<tables::Receipts>()?;
+        for &number in batch {
+            let Some(header) = tx.get::<tables::Headers>(number)? else {
+                eyre::bail!("header of block {number} not found")
+            };
+            let Some(indices) = tx.get::<tables::BlockBodyIndices>(number)? else {
+                eyre::bail!("body of block {number} not found")
+            };
+            let tx_range = indices.first_tx_num..indices.first_tx_num + indices.tx_count;
+
+            let transactions = transactions_cursor
+                .walk_range(tx_range.clone())?
+                .map(|entry| entry.map(|(_, tx)| TransactionSigned::from(tx)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let ommers =
+                tx.get::<tables::BlockOmmers>(number)?.map(|o| o.ommers).unwrap_or_default();
+            // empty withdrawals are not stored
+            let withdrawals = header.withdrawals_root.map(|_| {
+                tx.get::<tables::BlockWithdrawals>(number)
+                    .map(|w| w.map(|w| w.withdrawals).unwrap_or_default())
+            });
+            let withdrawals = withdrawals.transpose()?;
+
+            let block_receipts = receipts_cursor
+                .walk_range(tx_range)?
+                .map(|entry| entry.map(|(_, receipt)| receipt.with_bloom()))
+                .collect::<Result<Vec<_>, _>>()?;
+            eyre::ensure!(
+                block_receipts.len() == transactions.len(),
+                "receipts of block {number} are not available"
+            );
+
+            headers.write(&header)?;
+            bodies.write(&BlockBody { transactions, ommers, withdrawals })?;
+            receipts.write(&block_receipts)?;
+        }
+    }
+
+    Ok([
+        headers.finish(SegmentKind::Headers, file_name("headers"), from, to)?,
+        bodies.finish(SegmentKind::Bodies, file_name("bodies"), from, to)?,
+        receipts.finish(SegmentKind::Receipts, file_name("receipts"), from, to)?,
+    ])
+}
+
+/// Writes the latest plain state, including code and storage, into the state segment.
+///
+/// The plain state is only the state of the snapshot block as long as no new block is executed,
+/// which every batch checks.
+fn write_state_segment<DB: Database>(
+    db: &DB,
+    dir: &Path,
+    snapshot_block: (BlockNumber, BlockHash),
+) -> eyre::Result<SegmentInfo> {
+    let block = snapshot_block.0;
+    let file_name = format!("state-{block:010}.rlp.gz");
+    let mut state = SegmentWriter::<StateEntry>::create(&dir.join(&file_name))?;
+
+    // the last account of the previous batch, the next batch starts after it
+    let mut last: Option<Address> = None;
+    loop {
+        let tx = db.tx()?;
+        ensure_unchanged(&tx, snapshot_block)?;
+
+        let mut storage_cursor = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        let mut accounts_cursor = tx.cursor_read::<tables::PlainAccountState>()?;
+        let start = last;
+        let mut accounts = accounts_cursor
+            .walk(start)?
+            // the walk starts at the last account of the previous batch
+            .skip_while(move |entry| {
+                matches!((entry, start), (Ok((address, _)), Some(start)) if *address == start)
+            })
+            .take(READ_BATCH_SIZE)
+            .peekable();
+        if accounts.peek().is_none() {
+            break
+        }
+
+        for entry in accounts {
+            let (address, account) = entry?;
+            last = Some(address);
+
+            let code = match account.bytecode_hash {
+                Some(code_hash) => match tx.get::<tables::Bytecodes>(code_hash)? {
+                    Some(code) => code.original_bytes().into(),
+                    None => eyre::bail!("code {code_hash} of account {address} not found"),
+                },
+                None => Default::default(),
+            };
+
+            let mut storage = Vec::new();
+            let mut walker = storage_cursor.walk_dup(Some(address), None)?;
+            while let Some((key, slot)) = walker.next().transpose()? {
+                if key != address {
+                    break
+                }
+                storage.push(StorageSlot { key: slot.key, value: slot.value });
+            }
+
+            state.write(&StateEntry {
+                address,
+                nonce: account.nonce,
+                balance: account.balance,
+                code,
+                storage,
+            })?;
+        }
+    }
+
+    let info = state.finish(SegmentKind::State, file_name, block, block)?;
+    info!(target: "reth::cli", accounts = info.entries, "Wrote state segment");
+    Ok(info)
+}
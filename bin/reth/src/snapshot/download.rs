@@ -0,0 +1,401 @@
+use super::segment::{
+    sha256_file, SegmentInfo, SegmentKind, SegmentReader, SnapshotManifest, StateEntry,
+    MANIFEST_FILE,
+};
+use crate::dirs::{DataDirPath, MaybePlatformPath};
+use clap::Parser;
+use hyper::{body::HttpBody, client::HttpConnector, Body, Client, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use reth_db::{
+    cursor::DbCursorRO,
+    database::Database,
+    mdbx::{Env, WriteMap},
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_primitives::{
+    keccak256,
+    proofs::{calculate_receipt_root, calculate_transaction_root},
+    Account, BlockBody, Bytecode, ChainSpec, Header, PruneCheckpoint, PruneSegment,
+    ReceiptWithBloom, SealedBlock, StorageEntry, H256,
+};
+use reth_provider::insert_block;
+use reth_rlp::Decodable;
+use reth_staged_sync::utils::{chainspec::genesis_value_parser, init::init_db};
+use reth_stages::{
+    stages::{BODIES, EXECUTION, HEADERS, SENDER_RECOVERY, TOTAL_DIFFICULTY, TRANSACTION_LOOKUP},
+    StageId,
+};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info};
+
+/// The number of blocks or accounts that are written per database transaction.
+const COMMIT_THRESHOLD: u64 = 10_000;
+
+/// The last block of an unfinished import, which is resumed after it.
+const SNAPSHOT_BLOCKS: StageId = StageId("SnapshotImportBlocks");
+/// The number of accounts of an unfinished import, which is resumed after them.
+const SNAPSHOT_STATE: StageId = StageId("SnapshotImportState");
+
+/// Downloads a snapshot served by `reth snapshot serve` and imports it into a new database.
+///
+/// The server is not trusted: the last block of the snapshot must have the hash given with
+/// `--block-hash`, obtained from a trusted source such as a synced node or a block explorer. Every
+/// segment is verified against the checksum in the manifest, the block headers must form a chain
+/// from the genesis block to that block and the transactions and receipts must match the roots of
+/// their headers.
+///
+/// The snapshot doesn't contain the changesets of its blocks, which is recorded as a
+/// [PruneSegment::History] checkpoint: the node refuses state queries below the last block of the
+/// snapshot. The hashed state and the state trie are rebuilt and checked against the state root
+/// when the node is started.
+///
+/// The import is committed in batches. An interrupted import is resumed by running the command
+/// again, the node must not be started before the import finished.
+#[derive(Debug, Parser)]
+pub struct DownloadCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The path to the database folder. If not specified, it will be set in the data dir for the
+    /// chain being used.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    db: Option<PathBuf>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    /// - holesky
+    /// - dev
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// The base URL of the snapshot server, e.g. `https://snapshots.example.com`.
+    #[arg(long, value_name = "URL")]
+    url: String,
+
+    /// The hash of the last block of the snapshot, the trust anchor of the import.
+    ///
+    /// The snapshot is rejected unless its blocks lead to this block.
+    #[arg(long, value_name = "HASH", verbatim_doc_comment)]
+    block_hash: H256,
+
+    /// The directory the segments are downloaded to.
+    ///
+    /// Defaults to `snapshot` in the data dir. Segments that were already downloaded and match
+    /// their checksum are not downloaded again.
+    #[arg(long, value_name = "DIR", verbatim_doc_comment)]
+    download_dir: Option<PathBuf>,
+
+    /// Keep the downloaded segments after they were imported.
+    #[arg(long)]
+    keep_segments: bool,
+}
+
+impl DownloadCommand {
+    /// Execute `download-snapshot` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+
+        // use the overridden db path if specified
+        let db_path = self.db.clone().unwrap_or(data_dir.db_path());
+        let download_dir = self.download_dir.clone().unwrap_or(data_dir.snapshot_path());
+
+        let client = Client::builder().build::<_, Body>(
+            HttpsConnectorBuilder::new().with_native_roots().https_or_http().enable_http1().build(),
+        );
+
+        let manifest = {
+            let res = client.get(self.file_url(MANIFEST_FILE)?).await?;
+            eyre::ensure!(res.status().is_success(), "failed to fetch manifest: {}", res.status());
+            serde_json::from_slice::<SnapshotManifest>(
+                &hyper::body::to_bytes(res.into_body()).await?,
+            )?
+        };
+        manifest.validate()?;
+        eyre::ensure!(
+            manifest.genesis_hash == self.chain.genesis_hash(),
+            "snapshot is for chain {} with genesis {:?}",
+            manifest.chain_id,
+            manifest.genesis_hash
+        );
+        eyre::ensure!(
+            manifest.block_hash == self.block_hash,
+            "snapshot ends at block {} with hash {:?}, expected {:?}",
+            manifest.block,
+            manifest.block_hash,
+            self.block_hash
+        );
+        info!(target: "reth::cli", block = manifest.block, hash = ?manifest.block_hash, "Fetched snapshot manifest");
+
+        let db = init_db(&db_path)?;
+        let tx = db.tx()?;
+        let is_empty = tx.cursor_read::<tables::CanonicalHeaders>()?.first()?.is_none();
+        let resumed = SNAPSHOT_BLOCKS.get_progress(&tx)?;
+        drop(tx);
+        eyre::ensure!(
+            is_empty || resumed.is_some(),
+            "database at {} is not empty, remove it to import a snapshot",
+            db_path.display()
+        );
+        if let Some(block) = resumed {
+            info!(target: "reth::cli", block, "Resuming snapshot import");
+        }
+
+        std::fs::create_dir_all(&download_dir)?;
+        for segment in &manifest.segments {
+            self.download_segment(&client, &download_dir, segment).await?;
+        }
+
+        import_snapshot(&db, &download_dir, &manifest)?;
+
+        if !self.keep_segments {
+            std::fs::remove_dir_all(&download_dir)?;
+        }
+        info!(target: "reth::cli", block = manifest.block, "Snapshot imported");
+        Ok(())
+    }
+
+    fn file_url(&self, file_name: &str) -> eyre::Result<Uri> {
+        Ok(format!("{}/{file_name}", self.url.trim_end_matches('/')).parse()?)
+    }
+
+    /// Downloads the segment and verifies its checksum.
+    async fn download_segment(
+        &self,
+        client: &Client<HttpsConnector<HttpConnector>>,
+        dir: &Path,
+        segment: &SegmentInfo,
+    ) -> eyre::Result<()> {
+        let path = dir.join(&segment.file_name);
+        if path.exists() &&
+            std::fs::metadata(&path)?.len() == segment.size &&
+            sha256_file(&path)? == segment.sha256
+        {
+            debug!(target: "reth::cli", file_name = segment.file_name, "Segment already downloaded");
+            return Ok(())
+        }
+
+        info!(target: "reth::cli", file_name = segment.file_name, size = segment.size, "Downloading segment");
+        let res = client.get(self.file_url(&segment.file_name)?).await?;
+        eyre::ensure!(
+            res.status().is_success(),
+            "failed to fetch segment {}: {}",
+            segment.file_name,
+            res.status()
+        );
+
+        let part = dir.join(format!("{}.part", segment.file_name));
+        let mut file = tokio::fs::File::create(&part).await?;
+        let mut hasher = Sha256::new();
+        let mut len = 0u64;
+        let mut body = res.into_body();
+        while let Some(chunk) = body.data().await {
+            let chunk = chunk?;
+            len += chunk.len() as u64;
+            eyre::ensure!(len <= segment.size, "segment {} is too large", segment.file_name);
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+
+        if len != segment.size || H256::from_slice(&hasher.finalize()) != segment.sha256 {
+            tokio::fs::remove_file(&part).await?;
+            eyre::bail!("checksum mismatch of segment {}", segment.file_name)
+        }
+        tokio::fs::rename(&part, &path).await?;
+        Ok(())
+    }
+}
+
+/// Reads the entries of all segments of a kind in order.
+struct SegmentsReader<'a, T> {
+    dir: &'a Path,
+    segments: VecDeque<&'a SegmentInfo>,
+    current: Option<SegmentReader<T>>,
+}
+
+impl<'a, T: Decodable> SegmentsReader<'a, T> {
+    fn new(dir: &'a Path, manifest: &'a SnapshotManifest, kind: SegmentKind) -> Self {
+        Self { dir, segments: manifest.segments(kind).collect(), current: None }
+    }
+
+    fn read(&mut self) -> eyre::Result<Option<T>> {
+        loop {
+            if let Some(entry) = self.current.as_mut().map(|reader| reader.read()).transpose()? {
+                if entry.is_some() {
+                    return Ok(entry)
+                }
+            }
+            let Some(segment) = self.segments.pop_front() else { return Ok(None) };
+            self.current = Some(SegmentReader::open(&self.dir.join(&segment.file_name))?);
+        }
+    }
+}
+
+/// Writes the verified segments into the database and marks the blocks as synced.
+///
+/// The progress is saved with every commit, a resumed import verifies the blocks that were
+/// already imported against the database and skips them and the imported accounts.
+///
+/// The hashing, merkle and history stages are left for the pipeline, which verifies the imported
+/// state against the state root of the last block. The caller must have checked the hash of the
+/// last block in the manifest against a trusted hash, the blocks are verified to lead to it.
+fn import_snapshot(
+    db: &Env<WriteMap>,
+    dir: &Path,
+    manifest: &SnapshotManifest,
+) -> eyre::Result<()> {
+    let mut headers = SegmentsReader::<Header>::new(dir, manifest, SegmentKind::Headers);
+    let mut bodies = SegmentsReader::<BlockBody>::new(dir, manifest, SegmentKind::Bodies);
+    let mut receipts =
+        SegmentsReader::<Vec<ReceiptWithBloom>>::new(dir, manifest, SegmentKind::Receipts);
+
+    let mut tx = db.tx_mut()?;
+    let imported_blocks = SNAPSHOT_BLOCKS.get_progress(&tx)?;
+    let imported_accounts = SNAPSHOT_STATE.get_progress(&tx)?.unwrap_or_default();
+    let mut parent_hash = H256::zero();
+    let mut state_root = H256::zero();
+    for number in 0..=manifest.block {
+        let (Some(header), Some(body), Some(block_receipts)) =
+            (headers.read()?, bodies.read()?, receipts.read()?)
+        else {
+            eyre::bail!("segments end before block {number}")
+        };
+        let header = header.seal_slow();
+        eyre::ensure!(
+            header.number == number,
+            "unexpected header {} at block {number}",
+            header.number
+        );
+        if number == 0 {
+            eyre::ensure!(header.hash() == manifest.genesis_hash, "genesis hash mismatch");
+        } else {
+            eyre::ensure!(
+                header.parent_hash == parent_hash,
+                "block {number} is not a child of its parent"
+            );
+        }
+        eyre::ensure!(
+            calculate_transaction_root(&body.transactions) == header.transactions_root,
+            "transactions of block {number} do not match the header"
+        );
+        eyre::ensure!(
+            calculate_receipt_root(&block_receipts) == header.receipts_root,
+            "receipts of block {number} do not match the header"
+        );
+        parent_hash = header.hash();
+        state_root = header.state_root;
+
+        if imported_blocks.map_or(false, |imported| number <= imported) {
+            eyre::ensure!(
+                tx.get::<tables::CanonicalHeaders>(number)? == Some(parent_hash),
+                "block {number} of the unfinished import does not match the snapshot"
+            );
+            continue
+        }
+
+        let block = SealedBlock {
+            header,
+            body: body.transactions,
+            ommers: body.ommers,
+            withdrawals: body.withdrawals,
+        };
+        let indices = insert_block(&tx, block, None)?;
+        for (tx_num, receipt) in (indices.first_tx_num..).zip(block_receipts) {
+            tx.put::<tables::Receipts>(tx_num, receipt.receipt)?;
+        }
+
+        if number % COMMIT_THRESHOLD == 0 || number == manifest.block {
+            SNAPSHOT_BLOCKS.save_progress(&tx, number)?;
+            tx.commit()?;
+            tx = db.tx_mut()?;
+            info!(target: "reth::cli", block = number, to = manifest.block, "Imported blocks");
+        }
+    }
+    eyre::ensure!(parent_hash == manifest.block_hash, "last block does not match the manifest");
+    eyre::ensure!(state_root == manifest.state_root, "state root does not match the manifest");
+
+    let mut state = SegmentsReader::<StateEntry>::new(dir, manifest, SegmentKind::State);
+    let mut accounts = 0u64;
+    while let Some(entry) = state.read()? {
+        accounts += 1;
+        if accounts <= imported_accounts {
+            continue
+        }
+
+        let bytecode_hash = if entry.code.is_empty() {
+            None
+        } else {
+            let hash = keccak256(&entry.code);
+            tx.put::<tables::Bytecodes>(hash, Bytecode::new_raw_with_hash(entry.code.0, hash))?;
+            Some(hash)
+        };
+        tx.put::<tables::PlainAccountState>(
+            entry.address,
+            Account { nonce: entry.nonce, balance: entry.balance, bytecode_hash },
+        )?;
+        for slot in entry.storage {
+            tx.put::<tables::PlainStorageState>(
+                entry.address,
+                StorageEntry { key: slot.key, value: slot.value },
+            )?;
+        }
+
+        if accounts % COMMIT_THRESHOLD == 0 {
+            SNAPSHOT_STATE.save_progress(&tx, accounts)?;
+            tx.commit()?;
+            tx = db.tx_mut()?;
+            info!(target: "reth::cli", accounts, "Imported accounts");
+        }
+    }
+
+    for stage in [HEADERS, TOTAL_DIFFICULTY, BODIES, SENDER_RECOVERY, EXECUTION, TRANSACTION_LOOKUP]
+    {
+        stage.save_progress(&tx, manifest.block)?;
+    }
+    // there are no changesets of the imported blocks, the state before the last block is unknown
+    tx.put::<tables::PruneCheckpoints>(
+        PruneSegment::History,
+        PruneCheckpoint {
+            to_block: manifest.block + 1,
+            pruned: 0,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            finished: true,
+        },
+    )?;
+    for stage in [SNAPSHOT_BLOCKS, SNAPSHOT_STATE] {
+        tx.delete::<tables::SyncStage>(stage.0.to_string(), None)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! Commands for creating and serving snapshots that other nodes can bootstrap from.
+use crate::dirs::{DataDirPath, MaybePlatformPath};
+use clap::{Parser, Subcommand};
+use reth_db::mdbx::{Env, EnvKind, WriteMap};
+use reth_primitives::ChainSpec;
+use reth_staged_sync::utils::chainspec::genesis_value_parser;
+use std::{path::PathBuf, sync::Arc};
+
+mod create;
+mod download;
+mod segment;
+mod serve;
+
+pub use create::CreateCommand;
+pub use download::DownloadCommand;
+pub use segment::{SegmentInfo, SegmentKind, SnapshotManifest, StateEntry, StorageSlot};
+pub use serve::ServeCommand;
+
+/// `reth snapshot` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The path to the database folder. If not specified, it will be set in the data dir for the
+    /// chain being used.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    db: Option<PathBuf>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    /// - holesky
+    /// - dev
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth snapshot` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands {
+    /// Writes the chain and the latest state into compressed segments and a manifest
+    Create(CreateCommand),
+    /// Serves a snapshot to other nodes over HTTP(S)
+    Serve(ServeCommand),
+}
+
+impl Command {
+    /// Execute `snapshot` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::Create(command) => {
+                // add network name to data dir
+                let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+
+                // use the overridden db path if specified
+                let db_path = self.db.clone().unwrap_or(data_dir.db_path());
+
+                let db = Env::<WriteMap>::open(db_path.as_ref(), EnvKind::RO)?;
+                command.execute(&db, &self.chain)
+            }
+            Subcommands::Serve(command) => command.execute().await,
+        }
+    }
+}
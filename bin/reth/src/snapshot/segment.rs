@@ -0,0 +1,369 @@
+//! The manifest and the file format of snapshot segments.
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use reth_primitives::{Address, BlockNumber, Bytes, H256, U256};
+use reth_rlp::{Decodable, Encodable, RlpDecodable, RlpEncodable};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+/// The name of the manifest file in a snapshot directory.
+pub(crate) const MANIFEST_FILE: &str = "manifest.json";
+
+/// The version of the snapshot format.
+pub(crate) const SNAPSHOT_VERSION: u32 = 1;
+
+/// Describes a snapshot and the segments it consists of.
+///
+/// The manifest is the only file a client needs to trust: every segment is verified against the
+/// size and checksum listed here before it's imported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+    /// The version of the snapshot format.
+    pub version: u32,
+    /// The id of the chain the snapshot was created from.
+    pub chain_id: u64,
+    /// The hash of the genesis block.
+    pub genesis_hash: H256,
+    /// The last block of the snapshot, the state segment holds the state at this block.
+    pub block: BlockNumber,
+    /// The hash of the last block.
+    pub block_hash: H256,
+    /// The state root of the last block.
+    pub state_root: H256,
+    /// All segments of the snapshot.
+    pub segments: Vec<SegmentInfo>,
+}
+
+impl SnapshotManifest {
+    /// Reads the manifest from the given snapshot directory.
+    pub fn read(dir: &Path) -> eyre::Result<Self> {
+        let manifest =
+            serde_json::from_reader(BufReader::new(File::open(dir.join(MANIFEST_FILE))?))?;
+        Ok(manifest)
+    }
+
+    /// Writes the manifest into the given snapshot directory.
+    pub fn write(&self, dir: &Path) -> eyre::Result<()> {
+        let mut out = BufWriter::new(File::create(dir.join(MANIFEST_FILE))?);
+        serde_json::to_writer_pretty(&mut out, self)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Returns the segment with the given file name.
+    pub fn segment(&self, file_name: &str) -> Option<&SegmentInfo> {
+        self.segments.iter().find(|segment| segment.file_name == file_name)
+    }
+
+    /// Returns the segments of the given kind, ordered by block.
+    pub fn segments(&self, kind: SegmentKind) -> impl Iterator<Item = &SegmentInfo> {
+        self.segments.iter().filter(move |segment| segment.kind == kind)
+    }
+
+    /// Checks that the manifest is complete and doesn't reference files outside of the snapshot.
+    ///
+    /// The headers, bodies and receipts segments must each cover all blocks up to the last block
+    /// without gaps, and there must be exactly one state segment.
+    pub fn validate(&self) -> eyre::Result<()> {
+        eyre::ensure!(
+            self.version == SNAPSHOT_VERSION,
+            "unsupported snapshot version {}, expected {SNAPSHOT_VERSION}",
+            self.version
+        );
+        for segment in &self.segments {
+            eyre::ensure!(
+                !segment.file_name.is_empty() &&
+                    segment
+                        .file_name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) &&
+                    !segment.file_name.starts_with('.'),
+                "invalid segment file name {:?}",
+                segment.file_name
+            );
+        }
+        for kind in [SegmentKind::Headers, SegmentKind::Bodies, SegmentKind::Receipts] {
+            let mut next = 0;
+            for segment in self.segments(kind) {
+                eyre::ensure!(
+                    segment.from == next && segment.from <= segment.to,
+                    "{kind:?} segment {} does not continue at block {next}",
+                    segment.file_name
+                );
+                next = segment.to + 1;
+            }
+            eyre::ensure!(
+                next == self.block + 1,
+                "{kind:?} segments end before block {}",
+                self.block
+            );
+        }
+        eyre::ensure!(
+            self.segments(SegmentKind::State).count() == 1,
+            "snapshot must contain exactly one state segment"
+        );
+        Ok(())
+    }
+}
+
+/// The data stored in a segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SegmentKind {
+    /// One RLP encoded [Header](reth_primitives::Header) per block.
+    Headers,
+    /// One RLP encoded [BlockBody](reth_primitives::BlockBody) per block.
+    Bodies,
+    /// One RLP list of [ReceiptWithBloom](reth_primitives::ReceiptWithBloom) per block.
+    Receipts,
+    /// One [StateEntry] per account of the state at the last block, ordered by address.
+    State,
+}
+
+/// A file of the snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentInfo {
+    /// The data stored in the segment.
+    pub kind: SegmentKind,
+    /// The name of the file within the snapshot directory.
+    pub file_name: String,
+    /// The first block of the segment.
+    pub from: BlockNumber,
+    /// The last block of the segment (inclusive).
+    pub to: BlockNumber,
+    /// The number of entries in the segment.
+    pub entries: u64,
+    /// The size of the compressed file in bytes.
+    pub size: u64,
+    /// The SHA-256 checksum of the compressed file.
+    pub sha256: H256,
+}
+
+/// An account of the state segment, including its code and storage.
+#[derive(Debug, Clone, Default, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct StateEntry {
+    /// The address of the account.
+    pub address: Address,
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The code of the account, empty if it has none.
+    pub code: Bytes,
+    /// The non-zero storage slots of the account, ordered by key.
+    pub storage: Vec<StorageSlot>,
+}
+
+/// A storage slot of a [StateEntry].
+#[derive(Debug, Clone, Default, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct StorageSlot {
+    /// The key of the slot.
+    pub key: H256,
+    /// The value of the slot.
+    pub value: U256,
+}
+
+/// Hashes everything written to the inner writer.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    len: u64,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.len += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writes the entries of a segment file.
+///
+/// Entries are RLP encoded and prefixed with their length as a big endian `u32`, the file is gzip
+/// compressed.
+pub(crate) struct SegmentWriter<T> {
+    encoder: GzEncoder<HashingWriter<BufWriter<File>>>,
+    entries: u64,
+    buf: Vec<u8>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Encodable> SegmentWriter<T> {
+    /// Creates the segment file.
+    pub(crate) fn create(path: &Path) -> io::Result<Self> {
+        let inner = HashingWriter {
+            inner: BufWriter::new(File::create(path)?),
+            hasher: Sha256::new(),
+            len: 0,
+        };
+        Ok(Self {
+            encoder: GzEncoder::new(inner, Compression::default()),
+            entries: 0,
+            buf: Vec::new(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Appends an entry.
+    pub(crate) fn write(&mut self, entry: &T) -> io::Result<()> {
+        self.buf.clear();
+        entry.encode(&mut self.buf);
+        let len = u32::try_from(self.buf.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "segment entry too large"))?;
+        self.encoder.write_all(&len.to_be_bytes())?;
+        self.encoder.write_all(&self.buf)?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Flushes the file and returns its description.
+    pub(crate) fn finish(
+        self,
+        kind: SegmentKind,
+        file_name: String,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> io::Result<SegmentInfo> {
+        let mut inner = self.encoder.finish()?;
+        inner.flush()?;
+        Ok(SegmentInfo {
+            kind,
+            file_name,
+            from,
+            to,
+            entries: self.entries,
+            size: inner.len,
+            sha256: H256::from_slice(&inner.hasher.finalize()),
+        })
+    }
+}
+
+/// Reads the entries of a segment file written by a [SegmentWriter].
+pub(crate) struct SegmentReader<T> {
+    decoder: GzDecoder<BufReader<File>>,
+    buf: Vec<u8>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Decodable> SegmentReader<T> {
+    /// Opens the segment file.
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            decoder: GzDecoder::new(BufReader::new(File::open(path)?)),
+            buf: Vec::new(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the next entry or `None` at the end of the file.
+    pub(crate) fn read(&mut self) -> eyre::Result<Option<T>> {
+        let mut len = [0u8; 4];
+        match self.decoder.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err.into()),
+        }
+        self.buf.resize(u32::from_be_bytes(len) as usize, 0);
+        self.decoder.read_exact(&mut self.buf)?;
+
+        let mut buf = self.buf.as_slice();
+        let entry = T::decode(&mut buf)?;
+        eyre::ensure!(buf.is_empty(), "trailing bytes after segment entry");
+        Ok(Some(entry))
+    }
+}
+
+/// Returns the SHA-256 checksum of the file.
+pub(crate) fn sha256_file(path: &Path) -> io::Result<H256> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut BufReader::new(File::open(path)?), &mut hasher)?;
+    Ok(H256::from_slice(&hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.rlp.gz");
+
+        let entries = vec![
+            StateEntry { address: Address::random(), nonce: 1, ..Default::default() },
+            StateEntry {
+                address: Address::random(),
+                balance: U256::from(10),
+                code: Bytes::from(vec![0x60, 0x00]),
+                storage: vec![StorageSlot { key: H256::random(), value: U256::from(1) }],
+                ..Default::default()
+            },
+        ];
+        let mut writer = SegmentWriter::create(&path).unwrap();
+        for entry in &entries {
+            writer.write(entry).unwrap();
+        }
+        let info = writer.finish(SegmentKind::State, "state.rlp.gz".to_string(), 0, 0).unwrap();
+        assert_eq!(info.entries, 2);
+        assert_eq!(info.size, std::fs::metadata(&path).unwrap().len());
+        assert_eq!(info.sha256, sha256_file(&path).unwrap());
+
+        let mut reader = SegmentReader::<StateEntry>::open(&path).unwrap();
+        let mut read = Vec::new();
+        while let Some(entry) = reader.read().unwrap() {
+            read.push(entry);
+        }
+        assert_eq!(read, entries);
+    }
+
+    #[test]
+    fn validates_manifest() {
+        let segment = |kind, file_name: &str, from, to| SegmentInfo {
+            kind,
+            file_name: file_name.to_string(),
+            from,
+            to,
+            entries: 0,
+            size: 0,
+            sha256: H256::zero(),
+        };
+        let mut manifest = SnapshotManifest {
+            version: SNAPSHOT_VERSION,
+            chain_id: 1,
+            genesis_hash: H256::zero(),
+            block: 9,
+            block_hash: H256::zero(),
+            state_root: H256::zero(),
+            segments: vec![
+                segment(SegmentKind::Headers, "headers-0", 0, 4),
+                segment(SegmentKind::Headers, "headers-5", 5, 9),
+                segment(SegmentKind::Bodies, "bodies", 0, 9),
+                segment(SegmentKind::Receipts, "receipts", 0, 9),
+                segment(SegmentKind::State, "state", 9, 9),
+            ],
+        };
+        manifest.validate().unwrap();
+
+        // gap in the headers
+        manifest.segments[1].from = 6;
+        assert!(manifest.validate().is_err());
+        manifest.segments[1].from = 5;
+
+        // file outside of the snapshot directory
+        manifest.segments[4].file_name = "../state".to_string();
+        assert!(manifest.validate().is_err());
+    }
+}
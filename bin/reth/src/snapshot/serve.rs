@@ -0,0 +1,146 @@
+use super::segment::{SnapshotManifest, MANIFEST_FILE};
+use clap::Parser;
+use hyper::{
+    body::{Body, Bytes},
+    server::conn::Http,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use reth_rpc_builder::RpcTlsConfig;
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{fs::File, io::AsyncReadExt, net::TcpListener};
+use tracing::{debug, info, trace};
+
+/// Serves a snapshot to other nodes over HTTP(S).
+///
+/// Only the manifest and the segments it lists are served, other files in the directory are not
+/// accessible.
+#[derive(Debug, Parser)]
+pub struct ServeCommand {
+    /// The directory of the snapshot, as written by `reth snapshot create`.
+    #[arg(long, value_name = "DIR")]
+    dir: PathBuf,
+
+    /// The address the server listens on.
+    #[arg(long, value_name = "SOCKET", default_value = "127.0.0.1:8548")]
+    addr: SocketAddr,
+
+    /// Path to the PEM encoded certificate chain, enables TLS.
+    #[arg(long, value_name = "PATH", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// Path to the PEM encoded private key of the certificate.
+    #[arg(long, value_name = "PATH", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+}
+
+impl ServeCommand {
+    /// Execute `snapshot serve` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let manifest = SnapshotManifest::read(&self.dir)?;
+        manifest.validate()?;
+        let acceptor = match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => Some(RpcTlsConfig::new(cert, key).acceptor()?),
+            _ => None,
+        };
+
+        let listener = TcpListener::bind(self.addr).await?;
+        info!(
+            target: "reth::cli",
+            addr = %listener.local_addr()?,
+            block = manifest.block,
+            tls = acceptor.is_some(),
+            "Serving snapshot"
+        );
+
+        let snapshot = Arc::new((self.dir, manifest));
+        loop {
+            let (stream, remote_addr) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    debug!(target: "reth::cli", %err, "Failed to accept connection");
+                    continue
+                }
+            };
+            let snapshot = snapshot.clone();
+            let service = service_fn(move |req| {
+                let snapshot = snapshot.clone();
+                async move { Ok::<_, Infallible>(serve(&snapshot.0, &snapshot.1, req).await) }
+            });
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let res = match acceptor {
+                    Some(acceptor) => match acceptor.accept(stream).await {
+                        Ok(stream) => Http::new().serve_connection(stream, service).await,
+                        Err(err) => {
+                            trace!(target: "reth::cli", %remote_addr, %err, "TLS handshake failed");
+                            return
+                        }
+                    },
+                    None => Http::new().serve_connection(stream, service).await,
+                };
+                if let Err(err) = res {
+                    trace!(target: "reth::cli", %remote_addr, %err, "Connection closed");
+                }
+            });
+        }
+    }
+}
+
+/// Responds with the requested file if it's the manifest or one of its segments.
+async fn serve(dir: &Path, manifest: &SnapshotManifest, req: Request<Body>) -> Response<Body> {
+    if req.method() != Method::GET {
+        return status(StatusCode::METHOD_NOT_ALLOWED)
+    }
+    let file_name = req.uri().path().trim_start_matches('/');
+    if file_name != MANIFEST_FILE && manifest.segment(file_name).is_none() {
+        return status(StatusCode::NOT_FOUND)
+    }
+
+    let mut file = match File::open(dir.join(file_name)).await {
+        Ok(file) => file,
+        Err(err) => {
+            debug!(target: "reth::cli", file_name, %err, "Failed to open snapshot file");
+            return status(StatusCode::NOT_FOUND)
+        }
+    };
+    let len = match file.metadata().await {
+        Ok(metadata) => metadata.len(),
+        Err(_) => return status(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    // stream the file in chunks instead of loading segments into memory
+    let (mut sender, body) = Body::channel();
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            match file.read(&mut buf).await {
+                Ok(0) => break,
+                Ok(n) => {
+                    if sender.send_data(Bytes::copy_from_slice(&buf[..n])).await.is_err() {
+                        break
+                    }
+                }
+                Err(_) => {
+                    sender.abort();
+                    break
+                }
+            }
+        }
+    });
+
+    Response::builder()
+        .header(hyper::header::CONTENT_LENGTH, len)
+        .header(hyper::header::CONTENT_TYPE, "application/octet-stream")
+        .body(body)
+        .expect("valid response")
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    Response::builder().status(status).body(Body::empty()).expect("valid response")
+}
@@ -16,7 +16,10 @@ use reth_staged_sync::{
     Config,
 };
 use reth_stages::{
-    stages::{BodyStage, ExecutionStage, MerkleStage, SenderRecoveryStage, TransactionLookupStage},
+    stages::{
+        BodyStage, ExecutionStage, IndexLogsStage, MerkleStage, SenderRecoveryStage,
+        TransactionLookupStage,
+    },
     ExecInput, Stage, StageId, UnwindInput,
 };
 use std::{net::SocketAddr, path::PathBuf, sync::Arc};
@@ -47,6 +50,8 @@ pub struct Command {
     /// - mainnet
     /// - goerli
     /// - sepolia
+    /// - holesky
+    /// - dev
     #[arg(
         long,
         value_name = "CHAIN_OR_PATH",
@@ -132,12 +137,8 @@ impl Command {
                 let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
 
                 let mut config = config;
-                config.peers.connect_trusted_nodes_only = self.network.trusted_only;
-                if !self.network.trusted_peers.is_empty() {
-                    self.network.trusted_peers.iter().for_each(|peer| {
-                        config.peers.trusted_nodes.insert(*peer);
-                    });
-                }
+                self.network.apply_peers(&mut config.peers)?;
+                self.network.apply_sessions(&mut config.sessions);
 
                 let default_secret_key_path = data_dir.p2p_secret_path();
                 let p2p_secret_key = get_secret_key(&default_secret_key_path)?;
@@ -146,7 +147,12 @@ impl Command {
 
                 let network = self
                     .network
-                    .network_config(&config, self.chain.clone(), p2p_secret_key, default_peers_path)
+                    .network_config(
+                        &config,
+                        self.chain.clone(),
+                        p2p_secret_key,
+                        default_peers_path,
+                    )?
                     .build(Arc::new(ShareableDatabase::new(db.clone(), self.chain.clone())))
                     .start_network()
                     .await?;
@@ -209,6 +215,16 @@ impl Command {
 
                 stage.execute(&mut tx, input).await?;
             }
+            StageEnum::LogIndex => {
+                let mut stage = IndexLogsStage { commit_threshold: num_blocks };
+
+                // Unwind first
+                if !self.skip_unwind {
+                    stage.unwind(&mut tx, unwind).await?;
+                }
+
+                stage.execute(&mut tx, input).await?;
+            }
             _ => {}
         }
 
@@ -14,11 +14,31 @@ use reth_interfaces::{
     },
     test_utils::generators::random_block_range,
 };
-use reth_primitives::{BlockHashOrNumber, HeadersDirection, SealedHeader};
+use reth_primitives::{
+    accumulator::HeaderAccumulator, BlockHashOrNumber, ChainSpec, HeadersDirection, SealedHeader,
+};
 use reth_provider::insert_canonical_block;
 use std::{collections::BTreeMap, path::Path};
 use tracing::info;
 
+/// Loads the SSZ encoded header accumulator from the given file.
+///
+/// If the chain has a trusted accumulator root, the root of the loaded accumulator must match it.
+pub fn load_header_accumulator(path: &Path, chain: &ChainSpec) -> Result<HeaderAccumulator> {
+    let bytes = std::fs::read(path)
+        .wrap_err_with(|| format!("Could not read header accumulator {}", path.display()))?;
+    let accumulator = HeaderAccumulator::decode_ssz(&bytes)?;
+    if let Some(root) = HeaderAccumulator::trusted_root(chain.chain) {
+        eyre::ensure!(
+            accumulator.root() == root,
+            "header accumulator root {:?} does not match the trusted root {root:?}",
+            accumulator.root()
+        );
+    }
+    info!(target: "reth::cli", epochs = accumulator.num_epochs(), "Loaded header accumulator");
+    Ok(accumulator)
+}
+
 /// Get a single header from network
 pub async fn get_single_header<Client>(
     client: Client,
@@ -1,9 +1,15 @@
 //! This module contains the version message for the program.
+use reth_rpc_types::engine::{ClientCode, ClientVersionV1};
+
 include!(concat!(env!("OUT_DIR"), "/built.rs"));
 
 const VERSION: &str = PKG_VERSION;
 const NAME: &str = PKG_NAME;
 const SHA: Option<&str> = GIT_COMMIT_HASH_SHORT;
+const COMMIT: &str = match GIT_COMMIT_HASH {
+    Some(hash) => hash,
+    None => panic!("the git commit hash is required to build reth"),
+};
 const OS: &str = std::env::consts::OS;
 
 /// The version message for the current program, like
@@ -11,3 +17,54 @@ const OS: &str = std::env::consts::OS;
 pub fn version_message() -> String {
     format!("{}/v{}/{}-{}", NAME, VERSION, OS, SHA.unwrap())
 }
+
+/// The client version advertised to peers in the RLPx `Hello` message, which is also returned by
+/// `web3_clientVersion`.
+///
+/// This is the [version message](version_message), like `reth/v0.1.0/macos-6fc95a5`. The identity
+/// is appended as suffix, like `reth/v0.1.0/macos-6fc95a5/my-node`.
+pub fn p2p_client_version(identity: Option<&str>) -> String {
+    let version = version_message();
+    match identity {
+        Some(identity) => format!("{version}/{identity}"),
+        None => version,
+    }
+}
+
+/// The version information of this client as exchanged with the consensus layer client via
+/// `engine_getClientVersionV1`.
+pub fn client_version() -> ClientVersionV1 {
+    ClientVersionV1 {
+        code: ClientCode::RH,
+        name: "Reth".to_string(),
+        version: format!("v{VERSION}"),
+        commit: client_version_commit(COMMIT),
+    }
+}
+
+/// The first four bytes of the commit hash as hex string, as required by
+/// `engine_getClientVersionV1`.
+///
+/// The length of git's short hash depends on the repository, so the full hash is truncated instead.
+fn client_version_commit(hash: &str) -> String {
+    hash.chars().take(8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn p2p_client_version_with_identity() {
+        let version = p2p_client_version(None);
+        assert!(version.starts_with(&format!("{NAME}/v{VERSION}/{OS}-")));
+
+        assert_eq!(p2p_client_version(Some("node-1")), format!("{version}/node-1"));
+    }
+
+    #[test]
+    fn client_version_commit_is_four_bytes() {
+        assert_eq!(client_version_commit("fa4ff922a9c4e7d0b4d1bb8c77f2e5c1c2d3e4f5"), "fa4ff922");
+        assert_eq!(client_version().commit, COMMIT[..8]);
+    }
+}
@@ -1,4 +1,4 @@
-use metrics::Counter;
+use metrics::{Counter, Gauge};
 use reth_metrics_derive::Metrics;
 
 /// Beacon consensus engine metrics.
@@ -11,4 +11,6 @@ pub(crate) struct Metrics {
     pub(crate) forkchoice_updated_messages: Counter,
     /// The total count of new payload messages received.
     pub(crate) new_payload_messages: Counter,
+    /// The number of payloads that were imported optimistically and await validation.
+    pub(crate) optimistic_payloads: Gauge,
 }
@@ -1,22 +1,23 @@
-use crate::engine::{message::OnForkChoiceUpdated, metrics::Metrics};
+use crate::engine::{message::OnForkChoiceUpdated, metrics::Metrics, optimistic::OptimisticSync};
 use futures::{Future, FutureExt, StreamExt, TryFutureExt};
 use reth_db::{database::Database, tables, transaction::DbTx};
 use reth_interfaces::{
     blockchain_tree::{BlockStatus, BlockchainTreeEngine},
     consensus::ForkchoiceState,
     executor::Error as ExecutorError,
-    sync::SyncStateUpdater,
+    sync::{SyncState, SyncStateUpdater},
     Error,
 };
 use reth_payload_builder::{PayloadBuilderAttributes, PayloadBuilderHandle};
 use reth_primitives::{
-    listener::EventListeners, BlockNumber, Header, SealedBlock, SealedHeader, H256, U256,
+    listener::EventListeners, BlockNumHash, BlockNumber, Header, SealedBlock, SealedHeader, H256,
+    U256,
 };
 use reth_rpc_types::engine::{
     ExecutionPayload, ForkchoiceUpdated, PayloadAttributes, PayloadStatus, PayloadStatusEnum,
     PayloadValidationError,
 };
-use reth_stages::{stages::FINISH, Pipeline};
+use reth_stages::{stages::FINISH, ControlFlow, Pipeline};
 use reth_tasks::TaskSpawner;
 use schnellru::{ByLength, LruMap};
 use std::{
@@ -40,6 +41,8 @@ pub use error::{BeaconEngineError, BeaconEngineResult, BeaconForkChoiceUpdateErr
 
 mod metrics;
 
+mod optimistic;
+
 mod pipeline_state;
 
 pub use pipeline_state::PipelineState;
@@ -174,6 +177,8 @@ where
     /// Tracks the header of invalid payloads that were rejected by the engine because they're
     /// invalid.
     invalid_headers: InvalidHeaderCache,
+    /// Tracks the payloads that were imported while the chain below them wasn't validated yet.
+    optimistic: OptimisticSync,
     /// Consensus engine metrics.
     metrics: Metrics,
 }
@@ -238,6 +243,7 @@ where
             payload_builder,
             listeners: EventListeners::default(),
             invalid_headers: InvalidHeaderCache::new(MAX_INVALID_HEADERS),
+            optimistic: OptimisticSync::default(),
             metrics: Metrics::default(),
         };
 
@@ -295,10 +301,31 @@ where
     /// to a forkchoice update.
     fn check_invalid_ancestor(&mut self, head: H256) -> Option<PayloadStatus> {
         // check if the head was previously marked as invalid
-        let (parent_hash, parent_number) = {
-            let header = self.invalid_headers.get(&head)?;
-            (header.parent_hash, header.number.saturating_sub(1))
-        };
+        let header = self.invalid_headers.get(&head)?.clone();
+        Some(self.prepare_invalid_response(&header))
+    }
+
+    /// Checks if the given `check` hash points to an invalid header, inserting the given `head`
+    /// block hash into the invalid header cache if the `check` hash has a known invalid ancestor.
+    ///
+    /// This ensures that descendants of invalid payloads are rejected with the same latest valid
+    /// hash as the first invalid block of the chain.
+    fn check_invalid_ancestor_with_head(
+        &mut self,
+        check: H256,
+        head: H256,
+    ) -> Option<PayloadStatus> {
+        let header = self.invalid_headers.get(&check)?.clone();
+        self.invalid_headers.insert_with_invalid_ancestor(head, header.clone());
+        Some(self.prepare_invalid_response(&header))
+    }
+
+    /// Returns the `INVALID` status for a payload that links to the given invalid header.
+    ///
+    /// The latest valid hash is the parent of the first invalid block in the chain.
+    fn prepare_invalid_response(&self, invalid_ancestor: &Header) -> PayloadStatus {
+        let parent_hash = invalid_ancestor.parent_hash;
+        let parent_number = invalid_ancestor.number.saturating_sub(1);
         let mut latest_valid_hash = parent_hash;
 
         // Edge case: the `latestValid` field is the zero hash if the parent block is the terminal
@@ -309,12 +336,10 @@ where
             }
         }
 
-        let status = PayloadStatus::from_status(PayloadStatusEnum::Invalid {
+        PayloadStatus::from_status(PayloadStatusEnum::Invalid {
             validation_error: PayloadValidationError::LinksToRejectedPayload.to_string(),
         })
-        .with_latest_valid_hash(latest_valid_hash);
-
-        Some(status)
+        .with_latest_valid_hash(latest_valid_hash)
     }
 
     /// Called to resolve chain forks and ensure that the Execution layer is working with the latest
@@ -335,6 +360,13 @@ where
         // TODO: check PoW / EIP-3675 terminal block conditions for the fork choice head
         // TODO: ensure validity of the payload (is this satisfied already?)
 
+        // A head that links to a previously rejected payload is invalid regardless of whether the
+        // pipeline is running. The forkchoice state MUST NOT be updated in this case.
+        if let Some(invalid_ancestor) = self.check_invalid_ancestor(state.head_block_hash) {
+            debug!(target: "consensus::engine", head=?state.head_block_hash, "Head was previously marked as invalid");
+            return Ok(OnForkChoiceUpdated::with_invalid(invalid_ancestor))
+        }
+
         let is_first_forkchoice = self.forkchoice_state.is_none();
         self.forkchoice_state = Some(state);
         let status = if self.is_pipeline_idle() {
@@ -344,6 +376,7 @@ where
                         .get_block_number(state.head_block_hash)?
                         .expect("was canonicalized, so it exists");
                     debug!(target: "consensus::engine", hash=?state.head_block_hash, number=head_block_number, "canonicalized new head");
+                    self.on_tree_updated();
 
                     let pipeline_min_progress =
                         FINISH.get_progress(&self.db.tx()?)?.unwrap_or_default();
@@ -368,6 +401,13 @@ where
                 }
             }
         } else {
+            // The running pipeline may target an older head. If the new head is unknown, another
+            // run is required as soon as the current one finishes.
+            if self.get_block_number(state.head_block_hash)?.is_none() &&
+                self.blockchain_tree.block_by_hash(state.head_block_hash).is_none()
+            {
+                self.require_pipeline_run(PipelineTarget::Head);
+            }
             trace!(target: "consensus::engine", "Pipeline is syncing, skipping forkchoice update");
             PayloadStatus::from_status(PayloadStatusEnum::Syncing)
         };
@@ -390,13 +430,12 @@ where
         error: Error,
         is_first_forkchoice: bool,
     ) -> PayloadStatus {
-        warn!(target: "consensus::engine", ?error, ?state, "Error canonicalizing the head hash");
-
-        // check if the new head was previously invalidated, if so then we deem this FCU
-        // as invalid
-        if let Some(invalid_ancestor) = self.check_invalid_ancestor(state.head_block_hash) {
-            debug!(target: "consensus::engine", head=?state.head_block_hash, "Head was previously marked as invalid");
-            return invalid_ancestor
+        if self.optimistic.is_optimistic(&state.head_block_hash) {
+            // the head was imported optimistically, it can't be canonicalized until the chain
+            // below it is validated
+            debug!(target: "consensus::engine", ?error, ?state, "Head is not validated yet");
+        } else {
+            warn!(target: "consensus::engine", ?error, ?state, "Error canonicalizing the head hash");
         }
 
         // If this is the first forkchoice received, start downloading from safe block
@@ -409,7 +448,6 @@ where
         } else {
             PipelineTarget::Head
         };
-        self.require_pipeline_run(target);
         match error {
             Error::Execution(error @ ExecutorError::BlockPreMerge { .. }) => {
                 PayloadStatus::from_status(PayloadStatusEnum::Invalid {
@@ -417,7 +455,10 @@ where
                 })
                 .with_latest_valid_hash(H256::zero())
             }
-            _ => PayloadStatus::from_status(PayloadStatusEnum::Syncing),
+            _ => {
+                self.require_pipeline_run(target);
+                PayloadStatus::from_status(PayloadStatusEnum::Syncing)
+            }
         }
     }
 
@@ -499,6 +540,14 @@ where
             }
         };
 
+        // Engine-API rule: a payload that extends a previously rejected payload is invalid. This
+        // is checked before anything else so that descendants of invalid payloads are rejected
+        // even while the pipeline is syncing.
+        if let Some(status) = self.check_invalid_ancestor_with_head(parent_hash, block_hash) {
+            debug!(target: "consensus::engine", ?block_hash, ?parent_hash, "Payload links to previously rejected payload");
+            return status
+        }
+
         let header = block.header.clone();
 
         let status = if self.is_pipeline_idle() {
@@ -512,6 +561,7 @@ where
                                 block_number,
                                 block_hash,
                             ));
+                            self.on_tree_updated();
                             PayloadStatusEnum::Valid
                         }
                         BlockStatus::Accepted => {
@@ -519,15 +569,23 @@ where
                                 block_number,
                                 block_hash,
                             ));
+                            self.on_tree_updated();
                             PayloadStatusEnum::Accepted
                         }
-                        BlockStatus::Disconnected => PayloadStatusEnum::Syncing,
+                        BlockStatus::Disconnected => {
+                            // the block was buffered until its ancestors are available
+                            self.on_optimistic_payload(BlockNumHash::new(block_number, block_hash));
+                            PayloadStatusEnum::Syncing
+                        }
                     };
                     PayloadStatus::new(status, latest_valid_hash)
                 }
                 Err(error) => {
-                    // payload is deemed invalid, insert it into the cache
-                    self.invalid_headers.insert(header);
+                    // only cache the payload if it failed validation, internal errors are not
+                    // conclusive
+                    if is_validation_error(&error) {
+                        self.invalid_headers.insert(header);
+                    }
 
                     let latest_valid_hash =
                         self.latest_valid_hash_for_invalid_payload(parent_hash, Some(&error));
@@ -537,18 +595,61 @@ where
             }
         } else if let Err(error) = self.blockchain_tree.buffer_block_without_sender(block) {
             // received a new payload while we're still syncing to the target
+            if is_validation_error(&error) {
+                self.invalid_headers.insert(header);
+            }
             let latest_valid_hash =
                 self.latest_valid_hash_for_invalid_payload(parent_hash, Some(&error));
             let status = PayloadStatusEnum::Invalid { validation_error: error.to_string() };
             PayloadStatus::new(status, latest_valid_hash)
         } else {
-            // successfully buffered the block
+            // successfully buffered the block, the pipeline holds the write lock on the database,
+            // so the block can't be validated yet and `latestValidHash` MUST be null
+            self.on_optimistic_payload(BlockNumHash::new(block_number, block_hash));
             PayloadStatus::from_status(PayloadStatusEnum::Syncing)
         };
         trace!(target: "consensus::engine", ?block_hash, block_number, ?status, "Returning payload status");
         status
     }
 
+    /// Records a payload that was buffered without being validated.
+    ///
+    /// The payload is executed as soon as the chain below it is validated, see
+    /// [Self::restore_tree_if_possible] and [Self::on_tree_updated].
+    fn on_optimistic_payload(&mut self, block: BlockNumHash) {
+        self.optimistic.insert(block);
+        self.metrics.optimistic_payloads.set(self.optimistic.len() as f64);
+        self.update_sync_state();
+    }
+
+    /// Called after the tree connected or canonicalized blocks.
+    ///
+    /// Connecting a block also connects its buffered descendants, and canonicalizing commits them
+    /// to the database, so optimistically imported payloads may be validated without a pipeline
+    /// run.
+    fn on_tree_updated(&mut self) {
+        if self.optimistic.len() == 0 {
+            return
+        }
+        if let Err(error) = self.resolve_optimistic_payloads() {
+            error!(target: "consensus::engine", ?error, "Failed to resolve optimistic payloads");
+        }
+    }
+
+    /// Reports the node as syncing while there are optimistically imported payloads that are not
+    /// validated yet, so that RPC doesn't consider the node synced before validation caught up.
+    ///
+    /// This is a no-op while the pipeline is running, since the pipeline reports its own progress.
+    fn update_sync_state(&self) {
+        if let Some(PipelineState::Idle(pipeline)) = &self.pipeline_state {
+            if let Some(updater) = pipeline.sync_state_updater() {
+                let state =
+                    if self.optimistic.len() > 0 { SyncState::Syncing } else { SyncState::Idle };
+                updater.update_sync_state(state);
+            }
+        }
+    }
+
     /// Returns the next pipeline state depending on the current value of the next action.
     /// Resets the next action to the default value.
     fn next_pipeline_state(
@@ -589,6 +690,9 @@ where
 
     /// Attempt to restore the tree with the finalized block number.
     /// If the finalized block is missing from the database, trigger the pipeline run.
+    ///
+    /// Restoring the tree executes all optimistically imported payloads that connect to the
+    /// validated chain.
     fn restore_tree_if_possible(
         &mut self,
         state: ForkchoiceState,
@@ -597,11 +701,14 @@ where
             Some(number) => {
                 // Attempt to restore the tree.
                 self.blockchain_tree.restore_canonical_hashes(number)?;
+                self.resolve_optimistic_payloads()?;
 
-                // After restoring the tree, check if the head block is missing.
+                // After restoring the tree, check if the head block is missing. The head doesn't
+                // need to be synced by the pipeline if it was executed from the buffered payloads.
                 self.db
                     .view(|tx| tx.get::<tables::HeaderNumbers>(state.head_block_hash))??
-                    .is_none()
+                    .is_none() &&
+                    self.blockchain_tree.block_by_hash(state.head_block_hash).is_none()
             }
             None => true,
         };
@@ -611,6 +718,28 @@ where
         Ok(())
     }
 
+    /// Advances the validated chain segment to the progress of the pipeline and resolves all
+    /// optimistically imported payloads that were validated since.
+    ///
+    /// Blocks committed by the tree advance the pipeline progress as well, so this covers payloads
+    /// validated during live sync.
+    fn resolve_optimistic_payloads(&mut self) -> Result<(), reth_interfaces::Error> {
+        let last_validated = FINISH.get_progress(&self.db.tx()?)?.unwrap_or_default();
+        let tree = &self.blockchain_tree;
+        let resolved =
+            self.optimistic.on_validated(last_validated, |hash| tree.block_by_hash(hash).is_some());
+        self.metrics.optimistic_payloads.set(self.optimistic.len() as f64);
+        debug!(
+            target: "consensus::engine",
+            last_validated = self.optimistic.last_validated(),
+            optimistic_head = ?self.optimistic.optimistic_head(),
+            resolved,
+            "Resolved optimistic payloads"
+        );
+        self.update_sync_state();
+        Ok(())
+    }
+
     /// Check if the engine reached max block as specified by `max_block` parameter.
     fn has_reached_max_block(&self, progress: BlockNumber) -> bool {
         if self.max_block.map_or(false, |target| progress >= target) {
@@ -704,7 +833,9 @@ where
 
                             match result {
                                 Ok(ctrl) => {
-                                    if ctrl.is_unwind() {
+                                    if let ControlFlow::Unwind { target, .. } = ctrl {
+                                        // blocks above the unwind target are no longer validated
+                                        this.optimistic.on_unwind(target);
                                         this.require_pipeline_run(PipelineTarget::Head);
                                     } else {
                                         // Terminate the sync early if it's reached the maximum user
@@ -747,12 +878,38 @@ where
 
             // If the pipeline is idle, break from the loop.
             if this.is_pipeline_idle() {
+                this.update_sync_state();
                 return Poll::Pending
             }
         }
     }
 }
 
+/// Returns `true` if the error was caused by an invalid block rather than by an internal failure.
+///
+/// Only blocks that failed validation may be recorded as invalid.
+fn is_validation_error(error: &Error) -> bool {
+    match error {
+        Error::Consensus(_) => true,
+        Error::Execution(error) => matches!(
+            error,
+            ExecutorError::EVM { .. } |
+                ExecutorError::VerificationFailed |
+                ExecutorError::SenderRecoveryError |
+                ExecutorError::ReceiptCumulativeGasUsedDiff { .. } |
+                ExecutorError::ReceiptLogCountDiff { .. } |
+                ExecutorError::ReceiptLogDiff |
+                ExecutorError::ExecutionSuccessDiff { .. } |
+                ExecutorError::ReceiptRootDiff { .. } |
+                ExecutorError::BloomLogDiff { .. } |
+                ExecutorError::TransactionGasLimitMoreThenAvailableBlockGas { .. } |
+                ExecutorError::BlockGasUsed { .. } |
+                ExecutorError::BlockPreMerge { .. }
+        ),
+        _ => false,
+    }
+}
+
 /// Denotes the next action that the [BeaconConsensusEngine] should take.
 #[derive(Debug, Default)]
 enum BeaconEngineAction {
@@ -787,6 +944,11 @@ impl InvalidHeaderCache {
         self.headers.get(hash).map(|h| &*h)
     }
 
+    /// Inserts an invalid block into the cache, with a given invalid ancestor.
+    fn insert_with_invalid_ancestor(&mut self, header_hash: H256, invalid_ancestor: Header) {
+        self.headers.insert(header_hash, invalid_ancestor);
+    }
+
     /// Inserts a new header into the map.
     fn insert(&mut self, header: SealedHeader) {
         let hash = header.hash;
@@ -1065,6 +1227,19 @@ mod tests {
         assert_matches!(rx.await, Ok(Ok(())));
     }
 
+    #[test]
+    fn only_validation_errors_are_conclusive() {
+        assert!(is_validation_error(&Error::Execution(ExecutorError::VerificationFailed)));
+        assert!(is_validation_error(&Error::Execution(ExecutorError::BlockPreMerge {
+            hash: H256::zero()
+        })));
+        assert!(!is_validation_error(&Error::Execution(ExecutorError::ProviderError)));
+        assert!(!is_validation_error(&Error::Execution(ExecutorError::CanonicalCommit {
+            inner: String::new()
+        })));
+        assert!(!is_validation_error(&Error::Database(reth_interfaces::db::Error::Read(0))));
+    }
+
     fn insert_blocks<'a, DB: Database>(db: &DB, mut blocks: impl Iterator<Item = &'a SealedBlock>) {
         let mut transaction = Transaction::new(db).unwrap();
         blocks
@@ -1150,6 +1325,62 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
+        #[tokio::test]
+        async fn unknown_head_triggers_pipeline_run() {
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+            // the engine only propagates the error if the pipeline runs for the second time
+            let (consensus_engine, env) = setup_consensus_engine(
+                chain_spec,
+                VecDeque::from([
+                    Ok(ExecOutput { done: true, stage_progress: 0 }),
+                    Err(StageError::ChannelClosed),
+                ]),
+                Vec::default(),
+            );
+
+            let genesis = random_block(0, None, None, Some(0));
+            let block1 = random_block(1, Some(genesis.hash), None, Some(0));
+            insert_blocks(env.db.as_ref(), [&genesis, &block1].into_iter());
+            env.db.update(|tx| FINISH.save_progress(tx, block1.number)).unwrap().unwrap();
+
+            let engine_rx = spawn_consensus_engine(consensus_engine);
+
+            let forkchoice = ForkchoiceState {
+                head_block_hash: block1.hash,
+                finalized_block_hash: block1.hash,
+                ..Default::default()
+            };
+            let result = env.send_forkchoice_retry_on_syncing(forkchoice).await.unwrap();
+            assert_eq!(
+                result,
+                ForkchoiceUpdated::new(PayloadStatus::new(
+                    PayloadStatusEnum::Valid,
+                    Some(block1.hash)
+                ))
+            );
+
+            // the head is neither in the database nor in the tree
+            let result = env
+                .send_forkchoice_updated(ForkchoiceState {
+                    head_block_hash: H256::random(),
+                    ..forkchoice
+                })
+                .await
+                .unwrap();
+            assert_eq!(result, ForkchoiceUpdated::from_status(PayloadStatusEnum::Syncing));
+
+            assert_matches!(
+                engine_rx.await,
+                Ok(Err(BeaconEngineError::Pipeline(n))) if matches!(*n.as_ref(), PipelineError::Stage(StageError::ChannelClosed))
+            );
+        }
+
         #[tokio::test]
         async fn unknown_head_hash() {
             let chain_spec = Arc::new(
@@ -1405,6 +1636,103 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
+        #[tokio::test]
+        async fn optimistic_payload_resolved_during_live_sync() {
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+            let (mut consensus_engine, env) = setup_consensus_engine(
+                chain_spec,
+                VecDeque::default(),
+                Vec::from([PostState::default(), PostState::default()]),
+            );
+
+            let genesis = random_block(0, None, None, Some(0));
+            let block1 = random_block(1, Some(genesis.hash), None, Some(0));
+            let block2 = random_block(2, Some(block1.hash), None, Some(0));
+            insert_blocks(env.db.as_ref(), [&genesis].into_iter());
+            consensus_engine.blockchain_tree.restore_canonical_hashes(0).unwrap();
+
+            // the parent is unknown, so the payload is buffered and imported optimistically
+            let result = consensus_engine.on_new_payload(block2.clone().into());
+            assert_eq!(result, PayloadStatus::from_status(PayloadStatusEnum::Syncing));
+            assert!(consensus_engine.optimistic.is_optimistic(&block2.hash));
+
+            // the parent connects the buffered payload without a pipeline run
+            let result = consensus_engine.on_new_payload(block1.clone().into());
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Valid)
+                .with_latest_valid_hash(block1.hash);
+            assert_eq!(result, expected_result);
+            assert!(consensus_engine.blockchain_tree.block_by_hash(block2.hash).is_some());
+            assert_eq!(consensus_engine.optimistic.len(), 0);
+        }
+
+        #[tokio::test]
+        async fn payload_with_invalid_ancestor() {
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+            // no execution results are provided, so executing any payload fails
+            let (consensus_engine, env) = setup_consensus_engine(
+                chain_spec,
+                VecDeque::from([Ok(ExecOutput { done: true, stage_progress: 0 })]),
+                Vec::default(),
+            );
+
+            let genesis = random_block(0, None, None, Some(0));
+            let block1 = random_block(1, Some(genesis.hash), None, Some(0));
+            insert_blocks(env.db.as_ref(), [&genesis, &block1].into_iter());
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            let forkchoice = ForkchoiceState {
+                head_block_hash: block1.hash,
+                finalized_block_hash: block1.hash,
+                ..Default::default()
+            };
+            env.send_forkchoice_retry_on_syncing(forkchoice).await.unwrap();
+
+            // the first payload fails execution
+            let block2 = random_block(2, Some(block1.hash), None, Some(0));
+            let result =
+                env.send_new_payload_retry_on_syncing(block2.clone().into()).await.unwrap();
+            assert_matches!(result.status, PayloadStatusEnum::Invalid { .. });
+            assert_eq!(result.latest_valid_hash, Some(block1.hash));
+
+            // descendants are rejected with the latest valid hash of the invalid chain
+            let block3 = random_block(3, Some(block2.hash), None, Some(0));
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Invalid {
+                validation_error: PayloadValidationError::LinksToRejectedPayload.to_string(),
+            })
+            .with_latest_valid_hash(block1.hash);
+            let result = env.send_new_payload(block3.clone().into()).await.unwrap();
+            assert_eq!(result, expected_result);
+
+            let block4 = random_block(4, Some(block3.hash), None, Some(0));
+            let result = env.send_new_payload(block4.clone().into()).await.unwrap();
+            assert_eq!(result, expected_result);
+
+            // forkchoice updates to any block of the invalid chain are rejected
+            let result = env
+                .send_forkchoice_updated(ForkchoiceState {
+                    head_block_hash: block4.hash,
+                    ..forkchoice
+                })
+                .await
+                .unwrap();
+            assert_eq!(result, ForkchoiceUpdated::new(expected_result));
+
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
         #[tokio::test]
         async fn payload_pre_merge() {
             let data = BlockChainTestData::default();
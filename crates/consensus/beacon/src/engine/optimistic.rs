@@ -0,0 +1,134 @@
+use reth_primitives::{BlockNumHash, BlockNumber, H256};
+use std::collections::{BTreeMap, HashSet};
+
+/// Keeps track of payloads that were imported optimistically.
+///
+/// While the pipeline is backfilling the chain, payloads received from the CL can't be executed
+/// because the state of their ancestors is not available yet. These payloads are buffered and
+/// answered with `SYNCING`, which allows the CL to follow the chain optimistically. Once the
+/// pipeline caught up, the buffered payloads are executed on top of the validated chain.
+#[derive(Debug, Default)]
+pub(crate) struct OptimisticSync {
+    /// The highest block that was fully validated by the pipeline.
+    last_validated: BlockNumber,
+    /// Payloads that were imported but are not validated yet, keyed by block number.
+    pending: BTreeMap<BlockNumber, HashSet<H256>>,
+}
+
+impl OptimisticSync {
+    /// Returns the highest block that was fully validated by the pipeline.
+    pub(crate) fn last_validated(&self) -> BlockNumber {
+        self.last_validated
+    }
+
+    /// Returns the number of payloads that await validation.
+    pub(crate) fn len(&self) -> usize {
+        self.pending.values().map(HashSet::len).sum()
+    }
+
+    /// Returns the highest payload that awaits validation.
+    pub(crate) fn optimistic_head(&self) -> Option<BlockNumHash> {
+        let (number, hashes) = self.pending.iter().next_back()?;
+        hashes.iter().next().map(|hash| BlockNumHash { number: *number, hash: *hash })
+    }
+
+    /// Returns `true` if the given block was imported optimistically and is not validated yet.
+    pub(crate) fn is_optimistic(&self, hash: &H256) -> bool {
+        self.pending.values().any(|hashes| hashes.contains(hash))
+    }
+
+    /// Records a payload that was imported without being validated.
+    ///
+    /// Payloads at or below the last validated block are ignored.
+    pub(crate) fn insert(&mut self, block: BlockNumHash) {
+        if block.number > self.last_validated {
+            self.pending.entry(block.number).or_default().insert(block.hash);
+        }
+    }
+
+    /// Advances the validated segment to `last_validated` and resolves all pending payloads that
+    /// are either covered by the validated segment or were `executed` since.
+    ///
+    /// Returns the number of resolved payloads.
+    pub(crate) fn on_validated(
+        &mut self,
+        last_validated: BlockNumber,
+        executed: impl Fn(H256) -> bool,
+    ) -> usize {
+        let before = self.len();
+        self.last_validated = self.last_validated.max(last_validated);
+
+        // everything at or below the validated segment is no longer optimistic
+        self.pending = self.pending.split_off(&(self.last_validated + 1));
+        self.pending.retain(|_, hashes| {
+            hashes.retain(|hash| !executed(*hash));
+            !hashes.is_empty()
+        });
+
+        before - self.len()
+    }
+
+    /// Moves the validated segment back to the `target` block after the pipeline unwound the
+    /// chain.
+    ///
+    /// Pending payloads are kept, they are resolved once the pipeline validated them again.
+    pub(crate) fn on_unwind(&mut self, target: BlockNumber) {
+        self.last_validated = self.last_validated.min(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(number: BlockNumber) -> BlockNumHash {
+        BlockNumHash { number, hash: H256::from_low_u64_be(number) }
+    }
+
+    #[test]
+    fn tracks_optimistic_segment() {
+        let mut optimistic = OptimisticSync::default();
+        for number in 10..15 {
+            optimistic.insert(block(number));
+        }
+        assert_eq!(optimistic.len(), 5);
+        assert_eq!(optimistic.optimistic_head(), Some(block(14)));
+        assert!(optimistic.is_optimistic(&block(12).hash));
+
+        // pipeline validated up to block 11, the tree executed block 12 on top of it
+        let resolved = optimistic.on_validated(11, |hash| hash == block(12).hash);
+        assert_eq!(resolved, 3);
+        assert_eq!(optimistic.last_validated(), 11);
+        assert!(!optimistic.is_optimistic(&block(12).hash));
+        assert!(optimistic.is_optimistic(&block(13).hash));
+
+        // payloads below the validated segment are not tracked
+        optimistic.insert(block(5));
+        assert_eq!(optimistic.len(), 2);
+
+        // the validated segment never moves backwards
+        assert_eq!(optimistic.on_validated(3, |_| false), 0);
+        assert_eq!(optimistic.last_validated(), 11);
+
+        assert_eq!(optimistic.on_validated(20, |_| false), 2);
+        assert_eq!(optimistic.optimistic_head(), None);
+    }
+
+    #[test]
+    fn unwind_moves_validated_segment_back() {
+        let mut optimistic = OptimisticSync::default();
+        optimistic.on_validated(20, |_| false);
+
+        // the unwind target is above the validated segment
+        optimistic.on_unwind(25);
+        assert_eq!(optimistic.last_validated(), 20);
+
+        optimistic.on_unwind(10);
+        assert_eq!(optimistic.last_validated(), 10);
+
+        // payloads above the unwind target are optimistic again
+        optimistic.insert(block(15));
+        assert!(optimistic.is_optimistic(&block(15).hash));
+        assert_eq!(optimistic.on_validated(15, |_| false), 1);
+    }
+}
@@ -119,6 +119,8 @@ pub enum ConsensusError {
     WithdrawalIndexInvalid { got: u64, expected: u64 },
     #[error("Missing withdrawals")]
     BodyWithdrawalsMissing,
+    #[error("Headers of epoch {epoch} do not match the header accumulator.")]
+    HeaderAccumulatorMismatch { epoch: u64 },
     /// Error for a transaction that violates consensus.
     #[error(transparent)]
     InvalidTransaction(#[from] InvalidTransactionError),
@@ -41,6 +41,17 @@ pub enum ProviderError {
         /// The account address
         address: Address,
     },
+    /// The history indices lag too far behind the changesets to look up the changes of the
+    /// blocks they don't cover.
+    #[error("History indices at block #{checkpoint} lag more than {max_unindexed} blocks behind block #{last_block_number}")]
+    HistoryIndexLag {
+        /// The highest block covered by the history index
+        checkpoint: BlockNumber,
+        /// The highest block with a changeset
+        last_block_number: BlockNumber,
+        /// The maximum number of blocks looked up in the changesets
+        max_unindexed: u64,
+    },
     /// The total difficulty for a block is missing.
     #[error("Total difficulty not found for block #{number}")]
     TotalDifficulty { number: BlockNumber },
@@ -92,4 +103,12 @@ pub enum ProviderError {
     /// Unable to compute state root on top of historical block
     #[error("Unable to compute state root on top of historical block")]
     StateRootNotAvailableForHistoricalBlock,
+    /// The state history of the block was pruned or never imported, e.g. below a snapshot.
+    #[error("State history of block #{block_number} is not available, the oldest available block is #{lowest_available_block}")]
+    StateHistoryPruned {
+        /// The requested block
+        block_number: BlockNumber,
+        /// The lowest block whose state is available
+        lowest_available_block: BlockNumber,
+    },
 }
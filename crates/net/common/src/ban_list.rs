@@ -2,6 +2,8 @@
 use reth_primitives::PeerId;
 use std::{collections::HashMap, net::IpAddr, time::Instant};
 
+pub use ipnet::IpNet;
+
 /// Determines whether or not the IP is globally routable.
 /// Should be replaced with [`IpAddr::is_global`](std::net::IpAddr::is_global) once it is stable.
 pub fn is_global(ip: &IpAddr) -> bool {
@@ -23,6 +25,10 @@ pub struct BanList {
     banned_ips: HashMap<IpAddr, Option<Instant>>,
     /// A set of [`PeerId`] whose packets get dropped instantly.
     banned_peers: HashMap<PeerId, Option<Instant>>,
+    /// Networks that are never connected to, e.g. to comply with IP policies.
+    ///
+    /// Unlike banned IPs, these also apply to non-global IPs and never expire.
+    denied_networks: Vec<IpNet>,
 }
 
 impl BanList {
@@ -42,7 +48,25 @@ impl BanList {
         banned_peers: HashMap<PeerId, Option<Instant>>,
         banned_ips: HashMap<IpAddr, Option<Instant>>,
     ) -> Self {
-        Self { banned_ips, banned_peers }
+        Self { banned_ips, banned_peers, denied_networks: Vec::new() }
+    }
+
+    /// Denies all IPs of the given networks, see also [`BanList::deny_network`].
+    pub fn with_denied_networks(mut self, networks: impl IntoIterator<Item = IpNet>) -> Self {
+        self.denied_networks.extend(networks);
+        self
+    }
+
+    /// Denies all IPs of the network permanently.
+    pub fn deny_network(&mut self, network: IpNet) {
+        if !self.denied_networks.contains(&network) {
+            self.denied_networks.push(network);
+        }
+    }
+
+    /// Returns the networks whose IPs are denied.
+    pub fn denied_networks(&self) -> &[IpNet] {
+        &self.denied_networks
     }
 
     /// Removes all peers that are no longer banned.
@@ -90,10 +114,25 @@ impl BanList {
         self.is_banned_peer(peer_id) || self.is_banned_ip(ip)
     }
 
-    /// checks the ban list to see if it contains the given ip
+    /// checks the ban list to see if it contains the given ip or a network it belongs to
     #[inline]
     pub fn is_banned_ip(&self, ip: &IpAddr) -> bool {
-        self.banned_ips.contains_key(ip)
+        self.banned_ips.contains_key(ip) || self.is_denied_ip(ip)
+    }
+
+    /// Returns true if the ip belongs to a denied network.
+    pub fn is_denied_ip(&self, ip: &IpAddr) -> bool {
+        if self.denied_networks.is_empty() {
+            return false
+        }
+        // ipv4 addresses may be received as ipv4-mapped ipv6 addresses
+        let mapped = match ip {
+            IpAddr::V6(ip) => ip.to_ipv4_mapped().map(IpAddr::V4),
+            IpAddr::V4(_) => None,
+        };
+        self.denied_networks
+            .iter()
+            .any(|net| net.contains(ip) || mapped.map_or(false, |ip| net.contains(&ip)))
     }
 
     /// checks the ban list to see if it contains the given ip
@@ -102,6 +141,16 @@ impl BanList {
         self.banned_peers.contains_key(peer_id)
     }
 
+    /// Returns all banned peers and until when they are banned, `None` if indefinitely.
+    pub fn banned_peers(&self) -> impl Iterator<Item = (PeerId, Option<Instant>)> + '_ {
+        self.banned_peers.iter().map(|(peer_id, until)| (*peer_id, *until))
+    }
+
+    /// Returns all banned IPs and until when they are banned, `None` if indefinitely.
+    pub fn banned_ips(&self) -> impl Iterator<Item = (IpAddr, Option<Instant>)> + '_ {
+        self.banned_ips.iter().map(|(ip, until)| (*ip, *until))
+    }
+
     /// Unbans the ip address
     pub fn unban_ip(&mut self, ip: &IpAddr) {
         self.banned_ips.remove(ip);
@@ -175,6 +224,18 @@ mod tests {
         assert!(!banlist.is_banned_ip(&ip));
     }
 
+    #[test]
+    fn can_deny_network() {
+        let banlist = BanList::default()
+            .with_denied_networks(["1.2.3.0/24".parse().unwrap(), "10.0.0.0/8".parse().unwrap()]);
+        assert!(banlist.is_banned_ip(&IpAddr::from([1, 2, 3, 4])));
+        assert!(banlist.is_banned_ip(&IpAddr::from([10, 1, 2, 3])));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([1, 2, 4, 1])));
+
+        let mapped = IpAddr::V6(std::net::Ipv4Addr::new(1, 2, 3, 4).to_ipv6_mapped());
+        assert!(banlist.is_banned_ip(&mapped));
+    }
+
     #[test]
     fn cannot_ban_non_global() {
         let mut ip = IpAddr::from([0, 0, 0, 0]);
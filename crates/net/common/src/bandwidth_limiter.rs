@@ -0,0 +1,239 @@
+//! Support for limiting the bandwidth of streams.
+
+use crate::stream::HasRemoteAddr;
+use std::{
+    future::Future,
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    time::{Instant, Sleep},
+};
+
+/// The number of bytes a throttled stream waits for once the limiter is exhausted, so it isn't
+/// woken up for every single byte.
+const MIN_TRANSFER_SIZE: f64 = 16.0 * 1024.0;
+
+/// A token bucket that limits the number of bytes per second that are transferred through all
+/// streams that share it.
+///
+/// Bursts of up to one second worth of bytes are allowed.
+#[derive(Debug, Clone)]
+pub struct BandwidthLimiter {
+    inner: Arc<Mutex<TokenBucket>>,
+}
+
+impl BandwidthLimiter {
+    /// Creates a new limiter that allows `bytes_per_sec` bytes per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_sec` is zero.
+    pub fn new(bytes_per_sec: u64) -> Self {
+        assert!(bytes_per_sec > 0, "bandwidth limit must not be zero");
+        let rate = bytes_per_sec as f64;
+        let bucket = TokenBucket { rate, tokens: rate, last_refill: Instant::now() };
+        Self { inner: Arc::new(Mutex::new(bucket)) }
+    }
+
+    /// Returns the number of bytes that can be transferred now, or how long to wait until bytes
+    /// can be transferred again.
+    pub fn available(&self) -> Result<usize, Duration> {
+        let mut bucket = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        bucket.refill(Instant::now());
+        if bucket.tokens >= 1.0 {
+            return Ok(bucket.tokens as usize)
+        }
+        let missing = MIN_TRANSFER_SIZE.min(bucket.rate) - bucket.tokens;
+        Err(Duration::from_secs_f64(missing / bucket.rate))
+    }
+
+    /// Records bytes that were transferred.
+    ///
+    /// Concurrent transfers can overdraw the limiter, which is then paid back before bytes can be
+    /// transferred again.
+    pub fn consume(&self, num_bytes: usize) {
+        let mut bucket = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        bucket.tokens -= num_bytes as f64;
+    }
+}
+
+/// The state of a [BandwidthLimiter].
+#[derive(Debug)]
+struct TokenBucket {
+    /// The number of bytes per second.
+    rate: f64,
+    /// The number of bytes that can be transferred, negative if the bucket was overdrawn.
+    tokens: f64,
+    /// When the bucket was last refilled.
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Adds the bytes that accumulated since the last refill.
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+    }
+}
+
+/// Wraps around a stream that implements [`AsyncRead`] + [`AsyncWrite`] and limits the download
+/// and upload bandwidth through it.
+///
+/// Reading is paused while the download limiter is exhausted, which also throttles the remote via
+/// TCP flow control.
+#[derive(Debug)]
+#[pin_project::pin_project]
+pub struct ThrottledStream<S> {
+    /// The stream this throttles
+    #[pin]
+    inner: S,
+    /// Limits the bytes read from the stream.
+    download: Option<BandwidthLimiter>,
+    /// Limits the bytes written to the stream.
+    upload: Option<BandwidthLimiter>,
+    /// Wakes up the reader once bytes can be read again.
+    read_delay: Option<Pin<Box<Sleep>>>,
+    /// Wakes up the writer once bytes can be written again.
+    write_delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S> ThrottledStream<S> {
+    /// Creates a new [`ThrottledStream`] wrapping around the provided stream, a limiter that is
+    /// `None` doesn't limit its direction.
+    pub fn new(
+        inner: S,
+        download: Option<BandwidthLimiter>,
+        upload: Option<BandwidthLimiter>,
+    ) -> Self {
+        Self { inner, download, upload, read_delay: None, write_delay: None }
+    }
+}
+
+/// Polls until the limiter allows bytes to be transferred and returns their number.
+fn poll_available(
+    limiter: &BandwidthLimiter,
+    delay: &mut Option<Pin<Box<Sleep>>>,
+    cx: &mut Context<'_>,
+) -> Poll<usize> {
+    loop {
+        if let Some(sleep) = delay.as_mut() {
+            ready!(sleep.as_mut().poll(cx));
+            *delay = None;
+        }
+        match limiter.available() {
+            Ok(available) => return Poll::Ready(available),
+            Err(wait) => *delay = Some(Box::pin(tokio::time::sleep(wait))),
+        }
+    }
+}
+
+impl<Stream: AsyncRead> AsyncRead for ThrottledStream<Stream> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.project();
+        let Some(limiter) = this.download.as_ref() else { return this.inner.poll_read(cx, buf) };
+
+        let available = ready!(poll_available(limiter, this.read_delay, cx));
+        let num_bytes = if available < buf.remaining() {
+            let mut limited = ReadBuf::new(buf.initialize_unfilled_to(available));
+            ready!(this.inner.poll_read(cx, &mut limited))?;
+            let num_bytes = limited.filled().len();
+            buf.advance(num_bytes);
+            num_bytes
+        } else {
+            let init_num_bytes = buf.filled().len();
+            ready!(this.inner.poll_read(cx, buf))?;
+            buf.filled().len() - init_num_bytes
+        };
+        limiter.consume(num_bytes);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Stream: AsyncWrite> AsyncWrite for ThrottledStream<Stream> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let Some(limiter) = this.upload.as_ref() else { return this.inner.poll_write(cx, buf) };
+
+        let available = ready!(poll_available(limiter, this.write_delay, cx));
+        let num_bytes = ready!(this.inner.poll_write(cx, &buf[..buf.len().min(available)]))?;
+        limiter.consume(num_bytes);
+        Poll::Ready(Ok(num_bytes))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.project();
+        this.inner.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.project();
+        this.inner.poll_shutdown(cx)
+    }
+}
+
+impl<S: HasRemoteAddr> HasRemoteAddr for ThrottledStream<S> {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.inner.remote_addr()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_limiter_refills() {
+        let limiter = BandwidthLimiter::new(1024);
+        assert_eq!(limiter.available(), Ok(1024));
+
+        // the limiter is overdrawn and needs to be paid back
+        limiter.consume(1536);
+        assert_eq!(limiter.available(), Err(Duration::from_millis(1500)));
+
+        tokio::time::advance(Duration::from_millis(250)).await;
+        assert!(limiter.available().is_err());
+        tokio::time::advance(Duration::from_millis(500)).await;
+        assert_eq!(limiter.available(), Ok(256));
+
+        // no more than one second worth of bytes accumulate
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(limiter.available(), Ok(1024));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttled_stream() {
+        let (client, mut server) = duplex(64 * 1024);
+        let limiter = BandwidthLimiter::new(1000);
+        let mut client = ThrottledStream::new(client, Some(limiter.clone()), Some(limiter));
+
+        // the first second worth of bytes is written right away
+        let start = Instant::now();
+        client.write_all(&[0u8; 1000]).await.unwrap();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // the rest has to wait for the limiter, which is shared with the download
+        client.write_all(&[0u8; 500]).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(500));
+
+        server.write_all(&[0u8; 500]).await.unwrap();
+        let mut buf = [0u8; 500];
+        client.read_exact(&mut buf).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_secs(1));
+    }
+}
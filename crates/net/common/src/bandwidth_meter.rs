@@ -33,10 +33,7 @@ use std::{
     },
     task::{ready, Context, Poll},
 };
-use tokio::{
-    io::{AsyncRead, AsyncWrite, ReadBuf},
-    net::TcpStream,
-};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 
 use crate::stream::HasRemoteAddr;
 
@@ -47,6 +44,28 @@ struct BandwidthMeterInner {
     inbound: AtomicU64,
     /// Measures the number of outbound packets
     outbound: AtomicU64,
+    /// The meter all bytes are accounted to as well, if this meter is a child meter.
+    parent: Option<Arc<BandwidthMeterInner>>,
+}
+
+impl BandwidthMeterInner {
+    fn new(parent: Option<Arc<BandwidthMeterInner>>) -> Self {
+        Self { inbound: AtomicU64::new(0), outbound: AtomicU64::new(0), parent }
+    }
+
+    fn record_inbound(&self, num_bytes: u64) {
+        self.inbound.fetch_add(num_bytes, Ordering::Relaxed);
+        if let Some(parent) = &self.parent {
+            parent.record_inbound(num_bytes);
+        }
+    }
+
+    fn record_outbound(&self, num_bytes: u64) {
+        self.outbound.fetch_add(num_bytes, Ordering::Relaxed);
+        if let Some(parent) = &self.parent {
+            parent.record_outbound(num_bytes);
+        }
+    }
 }
 
 /// Public shareable struct used for getting bandwidth metering info
@@ -71,16 +90,29 @@ impl BandwidthMeter {
     pub fn total_outbound(&self) -> u64 {
         self.inner.outbound.load(Ordering::Relaxed)
     }
+
+    /// Returns a new [`BandwidthMeter`] that meters a subset of the streams of this meter, e.g. a
+    /// single session.
+    ///
+    /// All bytes recorded by the child meter are recorded by this meter as well.
+    pub fn child(&self) -> Self {
+        Self { inner: Arc::new(BandwidthMeterInner::new(Some(Arc::clone(&self.inner)))) }
+    }
+
+    /// Records bytes that have been downloaded.
+    pub fn record_inbound(&self, num_bytes: usize) {
+        self.inner.record_inbound(u64::try_from(num_bytes).unwrap_or(u64::max_value()))
+    }
+
+    /// Records bytes that have been uploaded.
+    pub fn record_outbound(&self, num_bytes: usize) {
+        self.inner.record_outbound(u64::try_from(num_bytes).unwrap_or(u64::max_value()))
+    }
 }
 
 impl Default for BandwidthMeter {
     fn default() -> Self {
-        Self {
-            inner: Arc::new(BandwidthMeterInner {
-                inbound: AtomicU64::new(0),
-                outbound: AtomicU64::new(0),
-            }),
-        }
+        Self { inner: Arc::new(BandwidthMeterInner::new(None)) }
     }
 }
 
@@ -127,10 +159,7 @@ impl<Stream: AsyncRead> AsyncRead for MeteredStream<Stream> {
             ready!(this.inner.poll_read(cx, buf))?;
             buf.filled().len() - init_num_bytes
         };
-        this.meter
-            .inner
-            .inbound
-            .fetch_add(u64::try_from(num_bytes).unwrap_or(u64::max_value()), Ordering::Relaxed);
+        this.meter.record_inbound(num_bytes);
         Poll::Ready(Ok(()))
     }
 }
@@ -143,10 +172,7 @@ impl<Stream: AsyncWrite> AsyncWrite for MeteredStream<Stream> {
     ) -> Poll<io::Result<usize>> {
         let this = self.project();
         let num_bytes = ready!(this.inner.poll_write(cx, buf))?;
-        this.meter
-            .inner
-            .outbound
-            .fetch_add(u64::try_from(num_bytes).unwrap_or(u64::max_value()), Ordering::Relaxed);
+        this.meter.record_outbound(num_bytes);
         Poll::Ready(Ok(num_bytes))
     }
 
@@ -161,7 +187,7 @@ impl<Stream: AsyncWrite> AsyncWrite for MeteredStream<Stream> {
     }
 }
 
-impl HasRemoteAddr for MeteredStream<TcpStream> {
+impl<S: HasRemoteAddr> HasRemoteAddr for MeteredStream<S> {
     fn remote_addr(&self) -> Option<SocketAddr> {
         self.inner.remote_addr()
     }
@@ -270,4 +296,27 @@ mod tests {
         assert_bandwidth_counts(&shared_client_bandwidth_meter, 8, 8);
         assert_bandwidth_counts(&shared_server_bandwidth_meter, 8, 8);
     }
+
+    #[tokio::test]
+    async fn test_child_meters() {
+        let (client_1, server_1) = duplex(64);
+        let (client_2, server_2) = duplex(64);
+
+        let shared_client_bandwidth_meter = BandwidthMeter::default();
+        let client_1_meter = shared_client_bandwidth_meter.child();
+        let client_2_meter = shared_client_bandwidth_meter.child();
+
+        let mut metered_client_1 = MeteredStream::new_with_meter(client_1, client_1_meter.clone());
+        let mut metered_server_1 = MeteredStream::new(server_1);
+        let mut metered_client_2 = MeteredStream::new_with_meter(client_2, client_2_meter.clone());
+        let mut metered_server_2 = MeteredStream::new(server_2);
+
+        duplex_stream_ping_pong(&mut metered_client_1, &mut metered_server_1).await;
+        duplex_stream_ping_pong(&mut metered_client_2, &mut metered_server_2).await;
+        duplex_stream_ping_pong(&mut metered_client_2, &mut metered_server_2).await;
+
+        assert_bandwidth_counts(&client_1_meter, 4, 4);
+        assert_bandwidth_counts(&client_2_meter, 8, 8);
+        assert_bandwidth_counts(&shared_client_bandwidth_meter, 12, 12);
+    }
 }
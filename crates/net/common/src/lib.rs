@@ -8,8 +8,10 @@
 //! Shared types across reth-net
 
 pub mod ban_list;
+pub mod bandwidth_limiter;
 pub mod bandwidth_meter;
 /// Traits related to tokio streams
 pub mod stream;
 
 pub mod ratelimit;
+pub mod socket;
@@ -0,0 +1,67 @@
+//! Helpers to bind the sockets of a dual-stack node.
+
+use socket2::{Domain, Protocol, Socket, Type};
+use std::{io, net::SocketAddr};
+use tokio::net::{TcpListener, UdpSocket};
+
+/// The maximum number of pending connections of a [TcpListener].
+const LISTEN_BACKLOG: i32 = 1024;
+
+/// Binds a [TcpListener] to the given address.
+///
+/// Unlike [TcpListener::bind], an IPv6 listener only accepts IPv6 connections, so that an IPv4
+/// listener can be bound to the same port next to it.
+///
+/// Must be called from within a tokio runtime.
+pub fn bind_tcp_listener(addr: SocketAddr) -> io::Result<TcpListener> {
+    let socket = new_socket(addr, Type::STREAM, Protocol::TCP)?;
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(LISTEN_BACKLOG)?;
+    TcpListener::from_std(socket.into())
+}
+
+/// Binds a [UdpSocket] to the given address.
+///
+/// Unlike [UdpSocket::bind], an IPv6 socket only receives IPv6 datagrams, so that an IPv4 socket
+/// can be bound to the same port next to it.
+///
+/// Must be called from within a tokio runtime.
+pub fn bind_udp_socket(addr: SocketAddr) -> io::Result<UdpSocket> {
+    let socket = new_socket(addr, Type::DGRAM, Protocol::UDP)?;
+    socket.bind(&addr.into())?;
+    UdpSocket::from_std(socket.into())
+}
+
+/// Creates a new non-blocking socket for the address family of the given address.
+fn new_socket(addr: SocketAddr, ty: Type, protocol: Protocol) -> io::Result<Socket> {
+    let socket = Socket::new(Domain::for_address(addr), ty, Some(protocol))?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    socket.set_nonblocking(true)?;
+    Ok(socket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    #[tokio::test]
+    async fn bind_both_families_to_same_port() {
+        let v4 = bind_tcp_listener((Ipv4Addr::LOCALHOST, 0).into()).unwrap();
+        let port = v4.local_addr().unwrap().port();
+        // the host might not support IPv6
+        if let Ok(v6) = bind_tcp_listener((Ipv6Addr::LOCALHOST, port).into()) {
+            assert_eq!(v6.local_addr().unwrap().port(), port);
+        }
+
+        let v4 = bind_udp_socket((Ipv4Addr::LOCALHOST, 0).into()).unwrap();
+        let port = v4.local_addr().unwrap().port();
+        if let Ok(v6) = bind_udp_socket((Ipv6Addr::LOCALHOST, port).into()) {
+            assert_eq!(v6.local_addr().unwrap().port(), port);
+        }
+    }
+}
@@ -3,7 +3,9 @@
 //! This basis of this file has been taken from the discv5 codebase:
 //! <https://github.com/sigp/discv5>
 
-use reth_net_common::ban_list::BanList;
+use crate::{IngressRateLimit, ALPHA, ENDPOINT_PROOF_EXPIRATION, MAX_NODES_PING};
+use discv5::kbucket::MAX_NODES_PER_BUCKET;
+use reth_net_common::ban_list::{BanList, IpNet};
 use reth_net_nat::{NatResolver, ResolveNatInterval};
 use reth_primitives::{
     bytes::{Bytes, BytesMut},
@@ -28,6 +30,8 @@ pub struct Discv4Config {
     pub udp_egress_message_buffer: usize,
     /// Size of the channel buffer for incoming messages.
     pub udp_ingress_message_buffer: usize,
+    /// Limits for inbound packets, enforced before packets are decoded. Disabled if `None`.
+    pub ingress_rate_limit: Option<IngressRateLimit>,
     /// The number of allowed failures for `FindNode` requests. Default: 5.
     pub max_find_node_failures: u8,
     /// The interval to use when checking for expired nodes that need to be re-pinged. Default:
@@ -35,8 +39,22 @@ pub struct Discv4Config {
     pub ping_interval: Duration,
     /// The duration of we consider a ping timed out.
     pub ping_expiration: Duration,
+    /// The duration after which the endpoint proof of a node is considered expired and the node
+    /// is re-pinged. Default: 24h, mirrors geth's `bondExpiration`.
+    pub bond_expiration: Duration,
+    /// The maximum number of nodes with an expired endpoint proof that are re-pinged every
+    /// `ping_interval`. Default: 32.
+    pub max_re_pings: usize,
+    /// Endpoint proofs that expire within this window are renewed with a `Ping` before a
+    /// `FindNode` is sent to the node, because strict peers drop `FindNode` packets once the proof
+    /// expired on their end. Disabled if `None`. Default: 1h.
+    pub bond_renewal_window: Option<Duration>,
     /// The rate at which lookups should be triggered.
     pub lookup_interval: Duration,
+    /// The maximum number of nodes per bucket of the routing table. Default: 16.
+    pub max_nodes_per_bucket: usize,
+    /// The number of concurrent `FindNode` requests of a lookup. Default: 3.
+    pub lookup_concurrency: usize,
     /// The duration of we consider a FindNode request timed out.
     pub request_timeout: Duration,
     /// The duration after which we consider an enr request timed out.
@@ -69,6 +87,11 @@ pub struct Discv4Config {
     /// If configured and a `external_ip_resolver` is configured, try to resolve the external ip
     /// using this interval.
     pub resolve_external_ip_interval: Option<Duration>,
+    /// The record of the local node in the other IP address family, if the node runs dual-stack.
+    ///
+    /// Its endpoint is advertised in the local [`Enr`](enr::Enr) next to the service's own
+    /// endpoint, and an IPv6 service socket won't receive IPv4 traffic.
+    pub dual_stack_record: Option<NodeRecord>,
 }
 
 impl Discv4Config {
@@ -120,15 +143,21 @@ impl Default for Discv4Config {
             udp_egress_message_buffer: 1024,
             /// Every outgoing request will eventually lead to an incoming response
             udp_ingress_message_buffer: 1024,
+            ingress_rate_limit: Some(Default::default()),
             max_find_node_failures: 5,
             ping_interval: Duration::from_secs(300),
             /// unified expiration and timeout durations, mirrors geth's `expiration` duration
             ping_expiration: Duration::from_secs(20),
             enr_expiration: Duration::from_secs(20),
+            bond_expiration: ENDPOINT_PROOF_EXPIRATION,
+            max_re_pings: MAX_NODES_PING,
+            bond_renewal_window: Some(Duration::from_secs(60 * 60)),
             neighbours_expiration: Duration::from_secs(20),
             request_timeout: Duration::from_secs(20),
 
             lookup_interval: Duration::from_secs(20),
+            max_nodes_per_bucket: MAX_NODES_PER_BUCKET,
+            lookup_concurrency: ALPHA,
             ban_list: Default::default(),
             ban_duration: Some(Duration::from_secs(3600)), // 1 hour
             bootstrap_nodes: Default::default(),
@@ -140,6 +169,7 @@ impl Default for Discv4Config {
             external_ip_resolver: Some(Default::default()),
             /// By default retry public IP using a 5min interval
             resolve_external_ip_interval: Some(Duration::from_secs(60 * 5)),
+            dual_stack_record: None,
         }
     }
 }
@@ -164,6 +194,12 @@ impl Discv4ConfigBuilder {
         self
     }
 
+    /// Sets the limits for inbound packets, `None` disables rate limiting.
+    pub fn ingress_rate_limit(&mut self, limit: Option<IngressRateLimit>) -> &mut Self {
+        self.config.ingress_rate_limit = limit;
+        self
+    }
+
     /// Sets the channel size for outgoing messages
     pub fn udp_egress_message_buffer(&mut self, udp_egress_message_buffer: usize) -> &mut Self {
         self.config.udp_egress_message_buffer = udp_egress_message_buffer;
@@ -194,6 +230,25 @@ impl Discv4ConfigBuilder {
         self
     }
 
+    /// Sets the duration after which endpoint proofs expire
+    pub fn bond_expiration(&mut self, duration: Duration) -> &mut Self {
+        self.config.bond_expiration = duration;
+        self
+    }
+
+    /// Sets the maximum number of nodes with expired endpoint proofs re-pinged per interval
+    pub fn max_re_pings(&mut self, max_re_pings: usize) -> &mut Self {
+        self.config.max_re_pings = max_re_pings;
+        self
+    }
+
+    /// Sets the window before the expiration of an endpoint proof in which it is renewed before
+    /// sending a `FindNode`, `None` disables the renewal.
+    pub fn bond_renewal_window(&mut self, window: Option<Duration>) -> &mut Self {
+        self.config.bond_renewal_window = window;
+        self
+    }
+
     /// Sets the expiration duration for enr requests
     pub fn enr_request_expiration(&mut self, duration: Duration) -> &mut Self {
         self.config.enr_expiration = duration;
@@ -258,12 +313,32 @@ impl Discv4ConfigBuilder {
         self
     }
 
+    /// Denies all IPs of the given networks, see also [`BanList::deny_network`].
+    pub fn deny_networks(&mut self, networks: impl IntoIterator<Item = IpNet>) -> &mut Self {
+        for network in networks {
+            self.config.ban_list.deny_network(network);
+        }
+        self
+    }
+
     /// Sets the lookup interval duration.
     pub fn lookup_interval(&mut self, lookup_interval: Duration) -> &mut Self {
         self.config.lookup_interval = lookup_interval;
         self
     }
 
+    /// Sets the maximum number of nodes per bucket of the routing table.
+    pub fn max_nodes_per_bucket(&mut self, max_nodes_per_bucket: usize) -> &mut Self {
+        self.config.max_nodes_per_bucket = max_nodes_per_bucket;
+        self
+    }
+
+    /// Sets the number of concurrent `FindNode` requests of a lookup.
+    pub fn lookup_concurrency(&mut self, lookup_concurrency: usize) -> &mut Self {
+        self.config.lookup_concurrency = lookup_concurrency;
+        self
+    }
+
     /// Set the default duration for which nodes are banned for. This timeouts are checked every 5
     /// minutes, so the precision will be to the nearest 5 minutes. If set to `None`, bans from
     /// the filter will last indefinitely. Default is 1 hour.
@@ -299,6 +374,13 @@ impl Discv4ConfigBuilder {
         self
     }
 
+    /// Sets the record of the local node in the other IP address family, see
+    /// [`Discv4Config::dual_stack_record`].
+    pub fn dual_stack_record(&mut self, dual_stack_record: Option<NodeRecord>) -> &mut Self {
+        self.config.dual_stack_record = dual_stack_record;
+        self
+    }
+
     /// Returns the configured [`Discv4Config`]
     pub fn build(&self) -> Discv4Config {
         self.config.clone()
@@ -318,6 +400,8 @@ mod tests {
             .add_boot_nodes(HashSet::new())
             .ban_duration(None)
             .lookup_interval(Duration::from_secs(3))
+            .max_nodes_per_bucket(32)
+            .lookup_concurrency(5)
             .enable_lookup(true)
             .build();
     }
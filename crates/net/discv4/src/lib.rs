@@ -65,6 +65,10 @@ pub use config::{Discv4Config, Discv4ConfigBuilder};
 mod node;
 use node::{kad_key, NodeKey};
 
+mod rate_limit;
+pub use rate_limit::IngressRateLimit;
+use rate_limit::IngressRateLimiter;
+
 // reexport NodeRecord primitive
 pub use reth_primitives::NodeRecord;
 
@@ -91,7 +95,7 @@ const ALPHA: usize = 3;
 
 /// Maximum number of nodes to ping at concurrently. 2 full `Neighbours` responses with 16 _new_
 /// nodes. This will apply some backpressure in recursive lookups.
-const MAX_NODES_PING: usize = 2 * MAX_NODES_PER_BUCKET;
+pub(crate) const MAX_NODES_PING: usize = 2 * MAX_NODES_PER_BUCKET;
 
 /// The size of the datagram is limited [`MAX_PACKET_SIZE`], 16 nodes, as the discv4 specifies don't
 /// fit in one datagram. The safe number of nodes that always fit in a datagram is 12, with worst
@@ -100,10 +104,10 @@ const MAX_NODES_PING: usize = 2 * MAX_NODES_PER_BUCKET;
 /// Even in the best case where all nodes are IPv4, only 14 nodes fit into one packet.
 const SAFE_MAX_DATAGRAM_NEIGHBOUR_RECORDS: usize = (MAX_PACKET_SIZE - 109) / 91;
 
-/// The timeout used to identify expired nodes, 24h
+/// The default timeout used to identify expired nodes, 24h
 ///
 /// Mirrors geth's `bondExpiration` of 24h
-const ENDPOINT_PROOF_EXPIRATION: Duration = Duration::from_secs(24 * 60 * 60);
+pub(crate) const ENDPOINT_PROOF_EXPIRATION: Duration = Duration::from_secs(24 * 60 * 60);
 
 type EgressSender = mpsc::Sender<(Bytes, SocketAddr)>;
 type EgressReceiver = mpsc::Receiver<(Bytes, SocketAddr)>;
@@ -195,7 +199,12 @@ impl Discv4 {
         secret_key: SecretKey,
         config: Discv4Config,
     ) -> io::Result<(Self, Discv4Service)> {
-        let socket = UdpSocket::bind(local_address).await?;
+        let socket = if config.dual_stack_record.is_some() {
+            // the other address family is served by a separate socket on the same port
+            reth_net_common::socket::bind_udp_socket(local_address)?
+        } else {
+            UdpSocket::bind(local_address).await?
+        };
         let local_addr = socket.local_addr()?;
         local_node_record.udp_port = local_addr.port();
         trace!( target : "discv4",  ?local_addr,"opened UDP socket");
@@ -235,7 +244,10 @@ impl Discv4 {
         self.lookup_node(None).await
     }
 
-    /// Looks up the given node id
+    /// Starts a `FindNode` recursive lookup that locates the closest nodes to the given node id.
+    ///
+    /// Returns the closest nodes that were discovered during the lookup, see also
+    /// [`Self::lookup_self`].
     pub async fn lookup(&self, node_id: PeerId) -> Result<Vec<NodeRecord>, Discv4Error> {
         self.lookup_node(Some(node_id)).await
     }
@@ -247,6 +259,13 @@ impl Discv4 {
         Ok(rx.await?)
     }
 
+    /// Returns the signed EIP-868 [`Enr`] of the node.
+    pub async fn local_enr(&self) -> Result<Enr<SecretKey>, Discv4Error> {
+        let (tx, rx) = oneshot::channel();
+        self.to_service.send(Discv4Command::LocalEnr(tx)).await?;
+        Ok(rx.await?)
+    }
+
     /// Triggers a new self lookup without expecting a response
     pub fn send_lookup_self(&self) {
         let cmd = Discv4Command::Lookup { node_id: None, tx: None };
@@ -411,7 +430,7 @@ impl Discv4Service {
         let mut tasks = JoinSet::<()>::new();
 
         let udp = Arc::clone(&socket);
-        tasks.spawn(receive_loop(udp, ingress_tx, local_node_record.id));
+        tasks.spawn(receive_loop(udp, ingress_tx, local_node_record.id, config.ingress_rate_limit));
 
         let udp = Arc::clone(&socket);
         tasks.spawn(send_loop(udp, egress_rx));
@@ -419,7 +438,7 @@ impl Discv4Service {
         let kbuckets = KBucketsTable::new(
             NodeKey::from(&local_node_record).into(),
             Duration::from_secs(60),
-            MAX_NODES_PER_BUCKET,
+            config.max_nodes_per_bucket,
             None,
             None,
         );
@@ -447,13 +466,15 @@ impl Discv4Service {
         // for EIP-868 construct an ENR
         let local_eip_868_enr = {
             let mut builder = EnrBuilder::new("v4");
-            builder.ip(local_node_record.address);
-            if local_node_record.address.is_ipv4() {
-                builder.udp4(local_node_record.udp_port);
-                builder.tcp4(local_node_record.tcp_port);
-            } else {
-                builder.udp6(local_node_record.udp_port);
-                builder.tcp6(local_node_record.tcp_port);
+            for record in std::iter::once(&local_node_record).chain(&config.dual_stack_record) {
+                builder.ip(record.address);
+                if record.address.is_ipv4() {
+                    builder.udp4(record.udp_port);
+                    builder.tcp4(record.tcp_port);
+                } else {
+                    builder.udp6(record.udp_port);
+                    builder.tcp6(record.tcp_port);
+                }
             }
 
             for (key, val) in config.additional_eip868_rlp_pairs.iter() {
@@ -507,6 +528,41 @@ impl Discv4Service {
             self.local_node_record.address = external_ip;
             let _ = self.local_eip_868_enr.set_ip(external_ip, &self.secret_key);
             info!(target : "discv4", enr=?self.local_eip_868_enr, "Updated local ENR");
+            self.on_local_enr_update();
+        }
+    }
+
+    /// Sets the pair in the EIP-868 [`Enr`] of the node.
+    ///
+    /// If the value changed, the record is re-signed with a bumped sequence number.
+    pub fn set_eip868_rlp_pair(&mut self, key: Vec<u8>, rlp: Bytes) {
+        if self.local_eip_868_enr.get_raw_rlp(&key) == Some(rlp.as_ref()) {
+            return
+        }
+        if let Err(err) = self.local_eip_868_enr.insert_raw_rlp(key, rlp, &self.secret_key) {
+            warn!(target : "discv4", %err, "Failed to update local ENR");
+            return
+        }
+        debug!(target : "discv4", seq=self.local_eip_868_enr.seq(), "Updated local ENR");
+        self.on_local_enr_update();
+    }
+
+    /// Pings all nodes with a proven endpoint after the local [`Enr`] changed.
+    ///
+    /// The pings carry the new sequence number, so the nodes request the updated record instead of
+    /// relying on the stale one until the next re-ping.
+    fn on_local_enr_update(&mut self) {
+        if !self.config.enable_eip868 {
+            return
+        }
+        let nodes = self
+            .kbuckets
+            .iter_ref()
+            .filter(|entry| entry.node.value.has_endpoint_proof)
+            .map(|entry| entry.node.value.record)
+            .collect::<Vec<_>>();
+        for node in nodes {
+            self.try_ping(node, PingReason::RePing)
         }
     }
 
@@ -615,10 +671,10 @@ impl Discv4Service {
 
     /// Starts the recursive lookup process for the given target, <https://github.com/ethereum/devp2p/blob/master/discv4.md#recursive-lookup>.
     ///
-    /// At first the `ALPHA` (==3, defined concurrency factor) nodes that are closest to the target
-    /// in the underlying DHT are selected to seed the lookup via `FindNode` requests. In the
-    /// recursive step, the initiator resends FindNode to nodes it has learned about from previous
-    /// queries.
+    /// At first the `lookup_concurrency` (default `ALPHA` == 3, defined concurrency factor) nodes
+    /// that are closest to the target in the underlying DHT are selected to seed the lookup via
+    /// `FindNode` requests. In the recursive step, the initiator resends FindNode to nodes it has
+    /// learned about from previous queries.
     ///
     /// This takes an optional Sender through which all successfully discovered nodes are sent once
     /// the request has finished.
@@ -626,7 +682,7 @@ impl Discv4Service {
         trace!(target : "discv4", ?target, "Starting lookup");
         let target_key = kad_key(target);
 
-        // Start a lookup context with the 16 (MAX_NODES_PER_BUCKET) closest nodes
+        // Start a lookup context with the closest nodes, 16 by default (MAX_NODES_PER_BUCKET)
         let ctx = LookupContext::new(
             target_key.clone(),
             self.kbuckets
@@ -635,13 +691,14 @@ impl Discv4Service {
                     node.value.has_endpoint_proof &&
                         !self.pending_find_nodes.contains_key(&node.key.preimage().0)
                 })
-                .take(MAX_NODES_PER_BUCKET)
+                .take(self.config.max_nodes_per_bucket)
                 .map(|n| (target_key.distance(&n.key), n.value.record)),
             tx,
         );
 
-        // From those 16, pick the 3 closest to start the concurrent lookup.
-        let closest = ctx.closest(ALPHA);
+        // From those, pick the `lookup_concurrency` (3 by default) closest to start the concurrent
+        // lookup.
+        let closest = ctx.closest(self.config.lookup_concurrency);
 
         if closest.is_empty() && self.pending_find_nodes.is_empty() {
             // no closest nodes, and no lookup in progress: table is empty.
@@ -655,7 +712,38 @@ impl Discv4Service {
         trace!(target : "discv4", ?target, num = closest.len(), "Start lookup closest nodes");
 
         for node in closest {
-            self.find_node(&node, ctx.clone());
+            self.find_node_or_renew_bond(&node, ctx.clone());
+        }
+    }
+
+    /// Sends a `FindNode` to the node, unless its endpoint proof is about to expire.
+    ///
+    /// In that case the proof is renewed with a `Ping` first and the `FindNode` is sent once the
+    /// `Pong` arrives, see [`Discv4Config::bond_renewal_window`].
+    fn find_node_or_renew_bond(&mut self, node: &NodeRecord, ctx: LookupContext) {
+        if self.is_bond_expiring(node.id) {
+            trace!(target : "discv4", ?node, "Renewing endpoint proof before FindNode");
+            // mark it as queried so it's not selected again while the ping is active
+            ctx.mark_queried(node.id);
+            self.try_ping(*node, PingReason::Lookup(*node, ctx));
+        } else {
+            self.find_node(node, ctx);
+        }
+    }
+
+    /// Returns true if the endpoint proof of the node expires within the configured
+    /// [`Discv4Config::bond_renewal_window`].
+    fn is_bond_expiring(&mut self, node_id: PeerId) -> bool {
+        let Some(window) = self.config.bond_renewal_window else { return false };
+        let bond_expiration = self.config.bond_expiration;
+        match self.kbuckets.entry(&kad_key(node_id)) {
+            kbucket::Entry::Present(entry, _) => {
+                entry.value().expires_within(bond_expiration, window)
+            }
+            kbucket::Entry::Pending(mut entry, _) => {
+                entry.value().expires_within(bond_expiration, window)
+            }
+            _ => false,
         }
     }
 
@@ -1198,8 +1286,9 @@ impl Discv4Service {
         }
 
         // get the next closest nodes, not yet queried nodes and start over.
-        let closest =
-            ctx.filter_closest(ALPHA, |node| !self.pending_find_nodes.contains_key(&node.id));
+        let closest = ctx.filter_closest(self.config.lookup_concurrency, |node| {
+            !self.pending_find_nodes.contains_key(&node.id)
+        });
 
         for closest in closest {
             let key = kad_key(closest.id);
@@ -1232,7 +1321,7 @@ impl Discv4Service {
                 BucketEntry::SelfEntry => {
                     // we received our own node entry
                 }
-                _ => self.find_node(&closest, ctx.clone()),
+                _ => self.find_node_or_renew_bond(&closest, ctx.clone()),
             }
         }
     }
@@ -1243,6 +1332,12 @@ impl Discv4Service {
         let expire = self.send_neighbours_expiration();
         let all_nodes = self.kbuckets.closest_values(&key).collect::<Vec<_>>();
 
+        // nodes of denied networks are never handed out
+        let all_nodes = all_nodes
+            .into_iter()
+            .filter(|node| !self.config.ban_list.is_denied_ip(&node.value.record.address))
+            .collect::<Vec<_>>();
+
         for nodes in all_nodes.chunks(SAFE_MAX_DATAGRAM_NEIGHBOUR_RECORDS) {
             let nodes = nodes.iter().map(|node| node.value.record).collect::<Vec<NodeRecord>>();
             trace!( target : "discv4",  len = nodes.len(), to=?to,"Sent neighbours packet");
@@ -1311,7 +1406,7 @@ impl Discv4Service {
             // least half full)
             if failures > (self.config.max_find_node_failures as usize) {
                 if let Some(bucket) = self.kbuckets.get_bucket(&key) {
-                    if bucket.num_entries() < MAX_NODES_PER_BUCKET / 2 {
+                    if bucket.num_entries() < self.config.max_nodes_per_bucket / 2 {
                         // skip half empty bucket
                         continue
                     }
@@ -1329,11 +1424,12 @@ impl Discv4Service {
         let mut nodes = self
             .kbuckets
             .iter_ref()
-            .filter(|entry| entry.node.value.is_expired())
+            .filter(|entry| entry.node.value.is_expired(self.config.bond_expiration))
             .map(|n| n.node.value)
             .collect::<Vec<_>>();
         nodes.sort_by(|a, b| a.last_seen.cmp(&b.last_seen));
-        let to_ping = nodes.into_iter().map(|n| n.record).take(MAX_NODES_PING).collect::<Vec<_>>();
+        let to_ping =
+            nodes.into_iter().map(|n| n.record).take(self.config.max_re_pings).collect::<Vec<_>>();
         for node in to_ping {
             self.try_ping(node, PingReason::RePing)
         }
@@ -1446,6 +1542,9 @@ impl Discv4Service {
                                 let rx = self.update_stream();
                                 let _ = tx.send(rx);
                             }
+                            Discv4Command::LocalEnr(tx) => {
+                                let _ = tx.send(self.local_eip_868_enr.clone());
+                            }
                             Discv4Command::BanPeer(node_id) => self.ban_node(node_id),
                             Discv4Command::Remove(node_id) => {
                                 self.remove_node(node_id);
@@ -1460,11 +1559,7 @@ impl Discv4Service {
                             Discv4Command::SetEIP868RLPPair { key, rlp } => {
                                 debug!(target: "discv4", key=%String::from_utf8_lossy(&key), "Update EIP-868 extension pair");
 
-                                let _ = self.local_eip_868_enr.insert_raw_rlp(
-                                    key,
-                                    rlp,
-                                    &self.secret_key,
-                                );
+                                self.set_eip868_rlp_pair(key, rlp);
                             }
                             Discv4Command::SetTcpPort(port) => {
                                 debug!(target: "discv4", %port, "Update tcp port");
@@ -1494,6 +1589,10 @@ impl Discv4Service {
                         warn!(target : "discv4", ?from, ?err, packet=?hex::encode(&data),   "bad packet");
                     }
                     IngressEvent::Packet(remote_addr, Packet { msg, node_id, hash }) => {
+                        if self.config.ban_list.is_denied_ip(&remote_addr.ip()) {
+                            trace!(target : "discv4", from=?remote_addr, "ignoring packet from denied network");
+                            continue
+                        }
                         trace!( target : "discv4",  r#type=?msg.msg_type(), from=?remote_addr,"received packet");
                         let event = match msg {
                             Message::Ping(ping) => {
@@ -1586,7 +1685,14 @@ pub(crate) async fn send_loop(udp: Arc<UdpSocket>, rx: EgressReceiver) {
 }
 
 /// Continuously awaits new incoming messages and sends them back through the channel.
-pub(crate) async fn receive_loop(udp: Arc<UdpSocket>, tx: IngressSender, local_id: PeerId) {
+///
+/// Packets that exceed the [IngressRateLimit] are dropped before they are decoded.
+pub(crate) async fn receive_loop(
+    udp: Arc<UdpSocket>,
+    tx: IngressSender,
+    local_id: PeerId,
+    rate_limit: Option<IngressRateLimit>,
+) {
     let send = |event: IngressEvent| async {
         let _ = tx.send(event).await.map_err(|err| {
             warn!(
@@ -1597,6 +1703,8 @@ pub(crate) async fn receive_loop(udp: Arc<UdpSocket>, tx: IngressSender, local_i
         });
     };
 
+    let mut rate_limiter = rate_limit.map(IngressRateLimiter::new);
+
     loop {
         let mut buf = [0; MAX_PACKET_SIZE];
         let res = udp.recv_from(&mut buf).await;
@@ -1606,6 +1714,12 @@ pub(crate) async fn receive_loop(udp: Arc<UdpSocket>, tx: IngressSender, local_i
                 send(IngressEvent::RecvError(err)).await;
             }
             Ok((read, remote_addr)) => {
+                if let Some(limiter) = rate_limiter.as_mut() {
+                    if !limiter.check(remote_addr.ip(), Instant::now()) {
+                        trace!(target : "discv4", ?remote_addr, "Dropped packet exceeding rate limit");
+                        continue
+                    }
+                }
                 let packet = &buf[..read];
                 match Message::decode(packet) {
                     Ok(packet) => {
@@ -1638,6 +1752,7 @@ enum Discv4Command {
     Lookup { node_id: Option<PeerId>, tx: Option<NodeRecordSender> },
     SetLookupInterval(Duration),
     Updates(OneshotSender<ReceiverStream<DiscoveryUpdate>>),
+    LocalEnr(OneshotSender<Enr<SecretKey>>),
 }
 
 /// Event type receiver produces
@@ -1937,8 +2052,13 @@ impl NodeEntry {
 
 impl NodeEntry {
     /// Returns true if the node should be re-pinged.
-    fn is_expired(&self) -> bool {
-        self.last_seen.elapsed() > ENDPOINT_PROOF_EXPIRATION
+    fn is_expired(&self, bond_expiration: Duration) -> bool {
+        self.last_seen.elapsed() > bond_expiration
+    }
+
+    /// Returns true if the endpoint proof expires within the given window.
+    fn expires_within(&self, bond_expiration: Duration, window: Duration) -> bool {
+        self.last_seen.elapsed() + window > bond_expiration
     }
 }
 
@@ -1973,7 +2093,11 @@ mod tests {
     use crate::test_utils::{create_discv4, create_discv4_with_config, rng_endpoint, rng_record};
     use rand::{thread_rng, Rng};
     use reth_primitives::{hex_literal::hex, mainnet_nodes, ForkHash};
-    use std::{future::poll_fn, net::Ipv4Addr};
+    use reth_rlp::Encodable;
+    use std::{
+        future::poll_fn,
+        net::{Ipv4Addr, Ipv6Addr},
+    };
 
     #[test]
     fn test_local_rotator() {
@@ -2139,6 +2263,45 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_renew_bond_before_find_node() {
+        reth_tracing::init_test_tracing();
+
+        // every proof is within the renewal window
+        let config = Discv4Config::builder()
+            .bond_expiration(Duration::from_secs(60))
+            .bond_renewal_window(Some(Duration::from_secs(120)))
+            .build();
+        let (_discv4, mut service) = create_discv4_with_config(config).await;
+
+        let id = PeerId::random();
+        let key = kad_key(id);
+        let record = NodeRecord::new("0.0.0.0:0".parse().unwrap(), id);
+
+        let _ = service.kbuckets.insert_or_update(
+            &key,
+            NodeEntry::new_proven(record),
+            NodeStatus {
+                direction: ConnectionDirection::Incoming,
+                state: ConnectionState::Connected,
+            },
+        );
+
+        service.lookup_self();
+        assert!(service.pending_find_nodes.is_empty());
+        assert!(matches!(
+            service.pending_pings.get(&id).map(|ping| &ping.reason),
+            Some(PingReason::Lookup(..))
+        ));
+
+        // without a renewal window the FindNode is sent right away
+        service.pending_pings.clear();
+        service.config.bond_renewal_window = None;
+        service.lookup_self();
+        assert!(service.pending_find_nodes.contains_key(&id));
+        assert!(service.pending_pings.is_empty());
+    }
+
     #[tokio::test]
     async fn test_no_local_in_closest() {
         reth_tracing::init_test_tracing();
@@ -2206,6 +2369,61 @@ mod tests {
         assert_eq!(ctx.inner.closest_nodes.borrow().len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_update_eip868_pair() {
+        reth_tracing::init_test_tracing();
+        let fork_id = ForkId { hash: ForkHash(hex!("743f3d89")), next: 16191202 };
+        let config = Discv4Config::builder().add_eip868_pair("eth", fork_id).build();
+        let (_discv4, mut service) = create_discv4_with_config(config).await;
+        let seq = service.enr_seq().unwrap();
+
+        let id = PeerId::random();
+        let record = NodeRecord::new("0.0.0.0:0".parse().unwrap(), id);
+        let _ = service.kbuckets.insert_or_update(
+            &kad_key(id),
+            NodeEntry::new_proven(record),
+            NodeStatus {
+                direction: ConnectionDirection::Incoming,
+                state: ConnectionState::Connected,
+            },
+        );
+
+        // setting the same value doesn't bump the sequence number
+        let mut buf = BytesMut::new();
+        fork_id.encode(&mut buf);
+        service.set_eip868_rlp_pair(b"eth".to_vec(), buf.freeze());
+        assert_eq!(service.enr_seq(), Some(seq));
+        assert!(service.pending_pings.is_empty());
+
+        let next = ForkId { hash: ForkHash(hex!("b715077d")), next: 0 };
+        let mut buf = BytesMut::new();
+        next.encode(&mut buf);
+        service.set_eip868_rlp_pair(b"eth".to_vec(), buf.freeze());
+        assert_eq!(service.enr_seq(), Some(seq + 1));
+        // nodes in the table are pinged with the new sequence number
+        assert!(service.pending_pings.contains_key(&id));
+    }
+
+    #[tokio::test]
+    async fn test_dual_stack_enr() {
+        reth_tracing::init_test_tracing();
+        let ipv6 = NodeRecord {
+            address: Ipv6Addr::LOCALHOST.into(),
+            tcp_port: 30304,
+            udp_port: 30305,
+            id: PeerId::random(),
+        };
+        let config = Discv4Config::builder().dual_stack_record(Some(ipv6)).build();
+        let (_discv4, service) = create_discv4_with_config(config).await;
+
+        // both address families are advertised
+        let enr = &service.local_eip_868_enr;
+        assert_eq!(enr.udp4(), Some(service.local_node_record.udp_port));
+        assert_eq!(enr.ip6(), Some(Ipv6Addr::LOCALHOST));
+        assert_eq!(enr.udp6(), Some(30305));
+        assert_eq!(enr.tcp6(), Some(30304));
+    }
+
     #[tokio::test]
     async fn test_service_commands() {
         reth_tracing::init_test_tracing();
@@ -0,0 +1,156 @@
+//! Rate limiting of inbound packets.
+//!
+//! Decoding a packet recovers the sender's public key from the packet's signature, which is
+//! expensive. Limits are therefore enforced on the raw datagrams, before they are decoded.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of IPs that are tracked at once.
+const MAX_TRACKED_IPS: usize = 10_000;
+
+/// Limits for inbound packets, in packets per second.
+///
+/// Both limits are token buckets that allow bursts of up to one second worth of packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IngressRateLimit {
+    /// The maximum number of packets per second from a single IP. Default: 200
+    pub packets_per_ip: u32,
+    /// The maximum number of packets per second from all IPs. Default: 5000
+    pub packets: u32,
+}
+
+impl Default for IngressRateLimit {
+    fn default() -> Self {
+        Self { packets_per_ip: 200, packets: 5_000 }
+    }
+}
+
+/// A token bucket that refills at `rate` tokens per second, up to `rate` tokens.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32, now: Instant) -> Self {
+        Self { tokens: rate as f64, last_refill: now }
+    }
+
+    /// Refills the bucket and takes a token if available.
+    fn try_take(&mut self, rate: u32, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate as f64).min(rate as f64);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if the bucket would be full again at the given time.
+    fn is_refilled(&self, rate: u32, now: Instant) -> bool {
+        let to_refill = (rate as f64 - self.tokens) / rate.max(1) as f64;
+        now.saturating_duration_since(self.last_refill) >= Duration::from_secs_f64(to_refill)
+    }
+}
+
+/// Enforces the [IngressRateLimit] on inbound packets.
+#[derive(Debug)]
+pub(crate) struct IngressRateLimiter {
+    limit: IngressRateLimit,
+    global: TokenBucket,
+    per_ip: HashMap<IpAddr, TokenBucket>,
+}
+
+impl IngressRateLimiter {
+    pub(crate) fn new(limit: IngressRateLimit) -> Self {
+        Self {
+            limit,
+            global: TokenBucket::new(limit.packets, Instant::now()),
+            per_ip: HashMap::new(),
+        }
+    }
+
+    /// Returns true if a packet from the IP is within the limits and should be processed.
+    pub(crate) fn check(&mut self, ip: IpAddr, now: Instant) -> bool {
+        let IngressRateLimit { packets_per_ip, packets } = self.limit;
+
+        if !self.per_ip.contains_key(&ip) && self.per_ip.len() >= MAX_TRACKED_IPS {
+            // buckets that are full again are equivalent to new buckets
+            self.per_ip.retain(|_, bucket| !bucket.is_refilled(packets_per_ip, now));
+            if self.per_ip.len() >= MAX_TRACKED_IPS {
+                return false
+            }
+        }
+
+        let bucket = self.per_ip.entry(ip).or_insert_with(|| TokenBucket::new(packets_per_ip, now));
+        bucket.try_take(packets_per_ip, now) && self.global.try_take(packets, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn limits_per_ip() {
+        let mut limiter =
+            IngressRateLimiter::new(IngressRateLimit { packets_per_ip: 10, packets: 1_000 });
+        let now = Instant::now();
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let other = IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8));
+
+        assert!((0..10).all(|_| limiter.check(ip, now)));
+        assert!(!limiter.check(ip, now));
+        // other IPs are not affected
+        assert!(limiter.check(other, now));
+
+        // the bucket refills over time
+        let later = now + Duration::from_millis(500);
+        assert!((0..5).all(|_| limiter.check(ip, later)));
+        assert!(!limiter.check(ip, later));
+    }
+
+    #[test]
+    fn limits_globally() {
+        let mut limiter =
+            IngressRateLimiter::new(IngressRateLimit { packets_per_ip: 10, packets: 15 });
+        let now = Instant::now();
+        let first = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let second = IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8));
+
+        assert!((0..10).all(|_| limiter.check(first, now)));
+        assert!((0..5).all(|_| limiter.check(second, now)));
+        assert!(!limiter.check(second, now));
+
+        assert!(limiter.check(second, now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn evicts_refilled_ips() {
+        let mut limiter =
+            IngressRateLimiter::new(IngressRateLimit { packets_per_ip: 10, packets: u32::MAX });
+        let now = Instant::now();
+        for i in 0..MAX_TRACKED_IPS as u32 {
+            assert!(limiter.check(IpAddr::V4(Ipv4Addr::from(i)), now));
+        }
+        // all slots are taken by active IPs
+        assert!(!limiter.check(IpAddr::V4(Ipv4Addr::from(u32::MAX)), now));
+
+        let later = now + Duration::from_secs(1);
+        assert!(limiter.check(IpAddr::V4(Ipv4Addr::from(u32::MAX)), later));
+        assert_eq!(limiter.per_ip.len(), 1);
+    }
+}
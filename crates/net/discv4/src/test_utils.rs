@@ -65,7 +65,7 @@ impl MockDiscovery {
         let mut tasks = JoinSet::<()>::new();
 
         let udp = Arc::clone(&socket);
-        tasks.spawn(receive_loop(udp, ingress_tx, local_enr.id));
+        tasks.spawn(receive_loop(udp, ingress_tx, local_enr.id, None));
 
         let udp = Arc::clone(&socket);
         tasks.spawn(send_loop(udp, egress_rx));
@@ -0,0 +1,68 @@
+use crate::Enr;
+use reth_primitives::bytes::{Bytes, BytesMut};
+use reth_rlp::Encodable;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+/// The default port for discv5 via UDP.
+///
+/// This is the port consensus layer clients use by default.
+pub const DEFAULT_DISCOVERY_V5_PORT: u16 = 9000;
+
+/// Settings for the [Discv5Service](crate::Discv5Service).
+#[derive(Debug, Clone)]
+pub struct Discv5Config {
+    /// The UDP socket the discv5 service listens on.
+    ///
+    /// The service listens on a single socket, so it's reachable via IPv4 or IPv6, unless the
+    /// system accepts IPv4 connections on an unspecified IPv6 socket.
+    ///
+    /// Default: `0.0.0.0:9000`
+    pub discovery_addr: SocketAddr,
+    /// The IP address advertised in the local ENR, if it differs from the listen address.
+    pub external_ip: Option<IpAddr>,
+    /// Nodes to bootstrap the routing table with.
+    pub bootstrap_nodes: Vec<Enr>,
+    /// The interval at which random lookups are performed.
+    ///
+    /// Default: 20s
+    pub lookup_interval: Duration,
+    /// Additional RLP encoded key value pairs of the local ENR, like the `eth` fork id.
+    pub additional_eip868_rlp_pairs: HashMap<Vec<u8>, Bytes>,
+    /// The settings of the discv5 server, like its request timeouts and session limits.
+    pub discv5_config: discv5::Discv5Config,
+}
+
+impl Discv5Config {
+    /// Adds a new key value pair to the local ENR, the value is RLP encoded.
+    pub fn add_eip868_pair(&mut self, key: impl AsRef<[u8]>, value: impl Encodable) -> &mut Self {
+        let mut buf = BytesMut::new();
+        value.encode(&mut buf);
+        self.add_eip868_rlp_pair(key, buf.freeze())
+    }
+
+    /// Adds a new key value pair to the local ENR, the value is already RLP encoded.
+    pub fn add_eip868_rlp_pair(&mut self, key: impl AsRef<[u8]>, rlp: Bytes) -> &mut Self {
+        self.additional_eip868_rlp_pairs.insert(key.as_ref().to_vec(), rlp);
+        self
+    }
+}
+
+impl Default for Discv5Config {
+    fn default() -> Self {
+        Self {
+            discovery_addr: SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                DEFAULT_DISCOVERY_V5_PORT,
+            ),
+            external_ip: None,
+            bootstrap_nodes: Default::default(),
+            lookup_interval: Duration::from_secs(20),
+            additional_eip868_rlp_pairs: Default::default(),
+            discv5_config: discv5::Discv5ConfigBuilder::new().build(),
+        }
+    }
+}
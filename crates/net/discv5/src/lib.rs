@@ -0,0 +1,291 @@
+#![warn(missing_docs, unreachable_pub, unused_crate_dependencies)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+
+//! Node discovery via [discv5](https://github.com/ethereum/devp2p/blob/master/discv5/discv5.md).
+//!
+//! The discv5 network is shared with consensus layer clients. Execution layer nodes are
+//! recognized by the `eth` entry of their ENR ([EIP-868](https://eips.ethereum.org/EIPS/eip-868)),
+//! all other nodes are ignored.
+
+pub use config::{Discv5Config, DEFAULT_DISCOVERY_V5_PORT};
+pub use discv5;
+use discv5::{
+    enr::{CombinedKey, CombinedPublicKey, EnrBuilder, EnrPublicKey, NodeId},
+    Discv5Event,
+};
+use reth_primitives::{ForkId, NodeRecord, PeerId};
+use reth_rlp::Decodable;
+use secp256k1::SecretKey;
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tokio::{
+    sync::{mpsc, mpsc::error::TrySendError},
+    task::JoinHandle,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, trace};
+
+mod config;
+
+/// The ENR type of the discv5 network.
+pub type Enr = discv5::Enr;
+
+/// The key of the ENR entry that identifies execution layer nodes.
+const ETH_ENR_KEY: &[u8] = b"eth";
+
+/// The frontend of the [Discv5Service] that can be used to interact with the running service.
+#[derive(Clone)]
+pub struct Discv5 {
+    inner: Arc<discv5::Discv5>,
+}
+
+// === impl Discv5 ===
+
+impl Discv5 {
+    /// Binds the discv5 service to the configured socket and bootstraps its routing table.
+    ///
+    /// The local ENR advertises the TCP port of the given [NodeRecord] and the UDP port of the
+    /// configured discovery address.
+    pub async fn bind(
+        local_node_record: NodeRecord,
+        secret_key: SecretKey,
+        config: Discv5Config,
+    ) -> io::Result<(Self, Discv5Service)> {
+        let key = CombinedKey::secp256k1_from_bytes(&mut secret_key.secret_bytes())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+
+        let local_enr = {
+            let mut builder = EnrBuilder::new("v4");
+            let ip = config.external_ip.unwrap_or_else(|| config.discovery_addr.ip());
+            if !ip.is_unspecified() {
+                builder.ip(ip);
+            }
+            if ip.is_ipv4() {
+                builder.udp4(config.discovery_addr.port()).tcp4(local_node_record.tcp_port);
+            } else {
+                builder.udp6(config.discovery_addr.port()).tcp6(local_node_record.tcp_port);
+            }
+            for (key, value) in &config.additional_eip868_rlp_pairs {
+                builder.add_value_rlp(key, value.clone());
+            }
+            builder.build(&key).map_err(into_io_error)?
+        };
+
+        let mut discv5 =
+            discv5::Discv5::new(local_enr, key, config.discv5_config).map_err(into_io_error)?;
+        for enr in config.bootstrap_nodes.iter().cloned() {
+            if let Err(err) = discv5.add_enr(enr) {
+                debug!(target: "discv5", %err, "Failed to add bootstrap node");
+            }
+        }
+        discv5.start(config.discovery_addr).await.map_err(into_io_error)?;
+        let events = discv5.event_stream().await.map_err(into_io_error)?;
+
+        let discv5 = Arc::new(discv5);
+        let service = Discv5Service {
+            discv5: Arc::clone(&discv5),
+            events,
+            lookup_interval: tokio::time::interval(config.lookup_interval),
+            lookup: None,
+            update_listeners: Vec::with_capacity(1),
+        };
+        Ok((Self { inner: discv5 }, service))
+    }
+
+    /// Returns the local ENR of the node.
+    pub fn local_enr(&self) -> Enr {
+        self.inner.local_enr()
+    }
+
+    /// Returns the socket the service is listening on.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        let enr = self.inner.local_enr();
+        enr.udp4_socket().map(SocketAddr::V4).or_else(|| enr.udp6_socket().map(SocketAddr::V6))
+    }
+
+    /// Bans the IP address permanently.
+    pub fn ban_ip(&self, ip: IpAddr) {
+        self.inner.ban_ip(ip, None)
+    }
+}
+
+impl std::fmt::Debug for Discv5 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Discv5").field("local_enr", &self.inner.local_enr()).finish()
+    }
+}
+
+/// A node discovered via discv5 that advertises the `eth` ENR entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiscoveredNode {
+    /// The record of the node, its TCP port is used for RLPx connections.
+    pub node_record: NodeRecord,
+    /// The fork id of the node, if the `eth` entry could be decoded.
+    pub fork_id: Option<ForkId>,
+}
+
+/// The service that drives discv5 lookups and forwards discovered execution layer nodes.
+#[must_use = "Service does nothing unless polled"]
+pub struct Discv5Service {
+    /// The discv5 server.
+    discv5: Arc<discv5::Discv5>,
+    /// Events emitted by the discv5 server.
+    events: mpsc::Receiver<Discv5Event>,
+    /// The interval at which random lookups are started.
+    lookup_interval: tokio::time::Interval,
+    /// The lookup in progress, a new lookup is only started once it finished.
+    lookup: Option<JoinHandle<()>>,
+    /// All subscribers of discovered nodes.
+    update_listeners: Vec<mpsc::Sender<DiscoveredNode>>,
+}
+
+// === impl Discv5Service ===
+
+impl Discv5Service {
+    /// Returns a new stream that yields all discovered execution layer nodes.
+    pub fn update_stream(&mut self) -> ReceiverStream<DiscoveredNode> {
+        let (tx, rx) = mpsc::channel(512);
+        self.update_listeners.push(tx);
+        ReceiverStream::new(rx)
+    }
+
+    /// Spawns the service onto a new task.
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        tokio::task::spawn(async move { self.run().await })
+    }
+
+    /// Runs the service until the discv5 server shuts down.
+    async fn run(&mut self) {
+        loop {
+            tokio::select! {
+                _ = self.lookup_interval.tick() => {
+                    if self.lookup.as_ref().map_or(false, |lookup| !lookup.is_finished()) {
+                        trace!(target: "discv5", "Skipping lookup, previous lookup in progress");
+                        continue
+                    }
+                    let discv5 = Arc::clone(&self.discv5);
+                    self.lookup = Some(tokio::task::spawn(async move {
+                        match discv5.find_node(NodeId::random()).await {
+                            Ok(nodes) => {
+                                trace!(target: "discv5", found = nodes.len(), "Lookup finished")
+                            }
+                            Err(err) => debug!(target: "discv5", ?err, "Lookup failed"),
+                        }
+                    }));
+                }
+                event = self.events.recv() => {
+                    let Some(event) = event else { return };
+                    let enr = match event {
+                        Discv5Event::Discovered(enr) => enr,
+                        Discv5Event::SessionEstablished(enr, _) => enr,
+                        _ => continue,
+                    };
+                    if let Some(node) = enr_to_discovered_node(&enr) {
+                        self.notify(node);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sends the node to all listeners.
+    ///
+    /// Listeners that are closed are removed.
+    fn notify(&mut self, node: DiscoveredNode) {
+        self.update_listeners.retain_mut(|listener| match listener.try_send(node) {
+            Ok(()) => true,
+            Err(err) => match err {
+                TrySendError::Full(_) => true,
+                TrySendError::Closed(_) => false,
+            },
+        });
+    }
+}
+
+impl std::fmt::Debug for Discv5Service {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Discv5Service").field("local_enr", &self.discv5.local_enr()).finish()
+    }
+}
+
+/// Converts the ENR of an execution layer node into a [DiscoveredNode].
+///
+/// Returns `None` if the ENR has no `eth` entry, isn't signed with a secp256k1 key or doesn't
+/// advertise an IP address and TCP port.
+pub fn enr_to_discovered_node(enr: &Enr) -> Option<DiscoveredNode> {
+    let mut eth = enr.get(ETH_ENR_KEY)?;
+    let fork_id = ForkId::decode(&mut eth).ok();
+
+    let public_key = enr.public_key();
+    if !matches!(public_key, CombinedPublicKey::Secp256k1(_)) {
+        return None
+    }
+    // the uncompressed key without the `0x04` prefix
+    let public_key = public_key.encode_uncompressed();
+    let id = PeerId::from_slice(&public_key[public_key.len().checked_sub(64)?..]);
+
+    let node_record = if let (Some(ip), Some(tcp_port)) = (enr.ip4(), enr.tcp4()) {
+        NodeRecord { address: ip.into(), tcp_port, udp_port: enr.udp4().unwrap_or(tcp_port), id }
+    } else {
+        let (ip, tcp_port) = (enr.ip6()?, enr.tcp6()?);
+        NodeRecord { address: ip.into(), tcp_port, udp_port: enr.udp6().unwrap_or(tcp_port), id }
+    }
+    .into_ipv4_mapped();
+
+    Some(DiscoveredNode { node_record, fork_id })
+}
+
+fn into_io_error(err: impl std::fmt::Debug) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, format!("{err:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{bytes::BytesMut, Hardfork, MAINNET};
+    use reth_rlp::Encodable;
+    use secp256k1::rand::thread_rng;
+    use std::net::Ipv4Addr;
+
+    fn enr(secret_key: &SecretKey, eth: Option<ForkId>) -> Enr {
+        let key = CombinedKey::secp256k1_from_bytes(&mut secret_key.secret_bytes()).unwrap();
+        let mut builder = EnrBuilder::new("v4");
+        builder.ip4(Ipv4Addr::new(10, 0, 0, 1)).tcp4(30303).udp4(9000);
+        if let Some(fork_id) = eth {
+            let mut buf = BytesMut::new();
+            fork_id.encode(&mut buf);
+            builder.add_value_rlp(ETH_ENR_KEY, buf.freeze());
+        }
+        builder.build(&key).unwrap()
+    }
+
+    #[test]
+    fn converts_eth_enr() {
+        let secret_key = SecretKey::new(&mut thread_rng());
+        let fork_id = MAINNET.hardfork_fork_id(Hardfork::Frontier).unwrap();
+
+        let node = enr_to_discovered_node(&enr(&secret_key, Some(fork_id))).unwrap();
+        let expected = NodeRecord::from_secret_key(
+            SocketAddr::new(Ipv4Addr::new(10, 0, 0, 1).into(), 30303),
+            &secret_key,
+        );
+        assert_eq!(node.node_record.id, expected.id);
+        assert_eq!(node.node_record.address, expected.address);
+        assert_eq!(node.node_record.tcp_port, 30303);
+        assert_eq!(node.node_record.udp_port, 9000);
+        assert_eq!(node.fork_id, Some(fork_id));
+    }
+
+    #[test]
+    fn ignores_non_eth_enr() {
+        let secret_key = SecretKey::new(&mut thread_rng());
+        assert!(enr_to_discovered_node(&enr(&secret_key, None)).is_none());
+    }
+}
@@ -0,0 +1,233 @@
+//! Construction of signed EIP-1459 trees for publishing node lists via DNS.
+//!
+//! The layout of the tree follows the reference implementation in geth: entries are grouped into
+//! branches of at most [MAX_BRANCH_CHILDREN] children, so every branch fits into a single TXT
+//! record.
+
+use crate::tree::{BranchEntry, DnsEntry, LinkEntry, NodeEntry, TreeRootEntry};
+use data_encoding::BASE32_NOPAD;
+use enr::{Enr, EnrKey};
+use reth_primitives::keccak256;
+use secp256k1::{Message, PublicKey, SecretKey, SECP256K1};
+use std::collections::BTreeMap;
+
+/// The maximum number of child hashes of a branch, so the branch fits into a 370 bytes TXT record.
+pub const MAX_BRANCH_CHILDREN: usize = 13;
+
+/// A signed tree of node records and links to other trees.
+#[derive(Debug, Clone)]
+pub struct DnsTree {
+    /// The signed root of the tree.
+    root: TreeRootEntry,
+    /// All entries of the tree by their hash.
+    entries: BTreeMap<String, DnsEntry<SecretKey>>,
+    /// The public key of the key that signed the root.
+    public_key: PublicKey,
+}
+
+// === impl DnsTree ===
+
+impl DnsTree {
+    /// Builds the tree of the given nodes and links and signs its root with the given key.
+    ///
+    /// The sequence number must be increased with every update of a published tree, clients ignore
+    /// roots with a lower sequence number than the one they already know.
+    pub fn new(
+        mut nodes: Vec<Enr<SecretKey>>,
+        mut links: Vec<LinkEntry<SecretKey>>,
+        sequence_number: u64,
+        key: &SecretKey,
+    ) -> Self {
+        // sort the entries so the tree is independent of the input order
+        nodes.sort_by_key(|enr| enr.node_id());
+        nodes.dedup_by_key(|enr| enr.node_id());
+        links.sort_by_key(|link| link.to_string());
+        links.dedup();
+
+        let mut entries = BTreeMap::new();
+        let enr_root = build_subtree(
+            nodes.into_iter().map(|enr| DnsEntry::Node(NodeEntry { enr })).collect(),
+            &mut entries,
+        );
+        let link_root =
+            build_subtree(links.into_iter().map(DnsEntry::Link).collect(), &mut entries);
+
+        let mut root =
+            TreeRootEntry { enr_root, link_root, sequence_number, signature: Default::default() };
+        root.signature = sign_root(&root, key).into();
+
+        Self { root, entries, public_key: key.public() }
+    }
+
+    /// Returns the signed root of the tree.
+    pub fn root(&self) -> &TreeRootEntry {
+        &self.root
+    }
+
+    /// Returns all entries of the tree by their hash, excluding the root.
+    pub fn entries(&self) -> &BTreeMap<String, DnsEntry<SecretKey>> {
+        &self.entries
+    }
+
+    /// Returns the link to the tree when it's published under the given domain.
+    pub fn link(&self, domain: impl Into<String>) -> LinkEntry<SecretKey> {
+        LinkEntry { domain: domain.into(), pubkey: self.public_key }
+    }
+
+    /// Returns the TXT records that publish the tree under the given domain, as pairs of the fully
+    /// qualified record name and the record content.
+    ///
+    /// The root is published at the domain itself, all other entries at `<hash>.<domain>`.
+    pub fn txt_records(&self, domain: &str) -> Vec<(String, String)> {
+        std::iter::once((domain.to_string(), self.root.to_string()))
+            .chain(
+                self.entries
+                    .iter()
+                    .map(|(hash, entry)| (format!("{hash}.{domain}"), entry.to_string())),
+            )
+            .collect()
+    }
+}
+
+/// Returns the subdomain of an entry: the base32 encoding of the first 16 bytes of the keccak256
+/// hash of its text.
+fn entry_hash(entry: &DnsEntry<SecretKey>) -> String {
+    BASE32_NOPAD.encode(&keccak256(entry.to_string().as_bytes())[..16])
+}
+
+/// Adds the entries to the tree and returns the hash of the subtree root.
+fn build_subtree(
+    entries: Vec<DnsEntry<SecretKey>>,
+    tree: &mut BTreeMap<String, DnsEntry<SecretKey>>,
+) -> String {
+    let root = build_entry(entries, tree);
+    let hash = entry_hash(&root);
+    tree.insert(hash.clone(), root);
+    hash
+}
+
+/// Groups the entries into branches until a single entry remains.
+fn build_entry(
+    mut entries: Vec<DnsEntry<SecretKey>>,
+    tree: &mut BTreeMap<String, DnsEntry<SecretKey>>,
+) -> DnsEntry<SecretKey> {
+    if entries.len() == 1 {
+        return entries.pop().expect("exists")
+    }
+    if entries.len() <= MAX_BRANCH_CHILDREN {
+        let children = entries
+            .into_iter()
+            .map(|entry| {
+                let hash = entry_hash(&entry);
+                tree.insert(hash.clone(), entry);
+                hash
+            })
+            .collect();
+        return DnsEntry::Branch(BranchEntry { children })
+    }
+
+    let mut subtrees = Vec::with_capacity(entries.len() / MAX_BRANCH_CHILDREN + 1);
+    while !entries.is_empty() {
+        let rest = entries.split_off(entries.len().min(MAX_BRANCH_CHILDREN));
+        subtrees.push(build_entry(std::mem::replace(&mut entries, rest), tree));
+    }
+    build_entry(subtrees, tree)
+}
+
+/// Signs the root with a 65 bytes recoverable signature, as expected by clients.
+fn sign_root(root: &TreeRootEntry, key: &SecretKey) -> Vec<u8> {
+    let content = root.content();
+    let msg = Message::from_slice(keccak256(content.as_bytes()).as_bytes()).expect("is 32 bytes");
+    let (rec_id, sig) = SECP256K1.sign_ecdsa_recoverable(&msg, key).serialize_compact();
+    let mut signature = sig.to_vec();
+    signature.push(rec_id.to_i32() as u8);
+    signature
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enr::EnrBuilder;
+    use secp256k1::rand::thread_rng;
+    use std::net::Ipv4Addr;
+
+    fn enr(port: u16) -> Enr<SecretKey> {
+        let key = SecretKey::new(&mut thread_rng());
+        EnrBuilder::new("v4").ip4(Ipv4Addr::LOCALHOST).tcp4(port).udp4(port).build(&key).unwrap()
+    }
+
+    /// Collects all node records reachable from the given hash.
+    fn resolve_nodes(
+        records: &BTreeMap<String, String>,
+        domain: &str,
+        hash: &str,
+        nodes: &mut Vec<String>,
+    ) {
+        let txt = &records[&format!("{hash}.{domain}")];
+        match txt.parse::<DnsEntry<SecretKey>>().unwrap() {
+            DnsEntry::Branch(branch) => {
+                for child in branch.children {
+                    resolve_nodes(records, domain, &child, nodes);
+                }
+            }
+            DnsEntry::Node(node) => {
+                nodes.push(node.enr.to_base64());
+            }
+            entry => unreachable!("unexpected entry {entry}"),
+        }
+    }
+
+    #[test]
+    fn build_and_resolve_tree() {
+        let key = SecretKey::new(&mut thread_rng());
+        let domain = "nodes.example.org";
+        let nodes = (0..100).map(|i| enr(30000 + i)).collect::<Vec<_>>();
+        let other = SecretKey::new(&mut thread_rng());
+        let link = LinkEntry { domain: "other.example.org".to_string(), pubkey: other.public() };
+
+        let tree = DnsTree::new(nodes.clone(), vec![link.clone()], 7, &key);
+        let records = tree.txt_records(domain).into_iter().collect::<BTreeMap<_, _>>();
+
+        // every record fits into a single TXT string
+        assert!(records.values().all(|txt| txt.len() <= 370));
+
+        let root: TreeRootEntry = records[domain].parse().unwrap();
+        assert_eq!(root.signature.len(), 65);
+        assert!(root.verify::<SecretKey>(&tree.link(domain).pubkey));
+        assert_eq!(root.sequence_number, 7);
+
+        let mut resolved = Vec::new();
+        resolve_nodes(&records, domain, &root.enr_root, &mut resolved);
+        let mut expected = nodes.iter().map(|enr| enr.to_base64()).collect::<Vec<_>>();
+        expected.sort();
+        resolved.sort();
+        assert_eq!(resolved, expected);
+
+        let resolved_link: LinkEntry<SecretKey> =
+            records[&format!("{}.{domain}", root.link_root)].parse().unwrap();
+        assert_eq!(resolved_link, link);
+    }
+
+    #[test]
+    fn build_empty_tree() {
+        let key = SecretKey::new(&mut thread_rng());
+        let tree = DnsTree::new(vec![], vec![], 1, &key);
+        let branch = &tree.entries()[&tree.root().enr_root];
+        assert_eq!(branch.to_string(), "enrtree-branch:");
+        assert!(matches!(
+            branch.to_string().parse::<DnsEntry<SecretKey>>().unwrap(),
+            DnsEntry::Branch(BranchEntry { children }) if children.is_empty()
+        ));
+    }
+
+    #[test]
+    fn tree_is_deterministic() {
+        let key = SecretKey::new(&mut thread_rng());
+        let mut nodes = (0..20).map(|i| enr(30000 + i)).collect::<Vec<_>>();
+        let tree = DnsTree::new(nodes.clone(), vec![], 1, &key);
+        nodes.reverse();
+        let reversed = DnsTree::new(nodes, vec![], 1, &key);
+        assert_eq!(tree.root().enr_root, reversed.root().enr_root);
+        assert_eq!(tree.root().link_root, reversed.root().link_root);
+    }
+}
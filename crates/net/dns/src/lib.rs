@@ -13,6 +13,7 @@ use crate::{
     sync::{ResolveKind, SyncAction},
     tree::{DnsEntry, LinkEntry},
 };
+pub use builder::{DnsTree, MAX_BRANCH_CHILDREN};
 pub use config::DnsDiscoveryConfig;
 use enr::Enr;
 use error::ParseDnsEntryError;
@@ -42,6 +43,7 @@ use tokio_stream::{
 };
 use tracing::{debug, trace, warn};
 
+mod builder;
 mod config;
 mod error;
 mod query;
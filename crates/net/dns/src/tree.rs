@@ -119,7 +119,7 @@ impl TreeRootEntry {
     /// ```text
     /// e=<enr-root> l=<link-root> seq=<sequence-number> sig=<signature>
     /// ```
-    fn content(&self) -> String {
+    pub(crate) fn content(&self) -> String {
         format!(
             "{} e={} l={} seq={}",
             ROOT_V1_PREFIX, self.enr_root, self.link_root, self.sequence_number
@@ -201,6 +201,10 @@ impl BranchEntry {
             Ok(hash.to_string())
         }
 
+        // a branch without children is valid, e.g. the link subtree of a tree without links
+        if input.trim().is_empty() {
+            return Ok(Self { children: Vec::new() })
+        }
         let children =
             input.trim().split(',').map(ensure_valid_hash).collect::<ParseEntryResult<Vec<_>>>()?;
         Ok(Self { children })
@@ -24,6 +24,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 /// The multiplier for the number of connected peers.
@@ -451,6 +452,10 @@ pub struct BodiesDownloaderBuilder {
     max_buffered_responses: usize,
     /// The maximum number of requests to send concurrently.
     concurrent_requests_range: RangeInclusive<usize>,
+    /// The timeout of a single request.
+    request_timeout: Duration,
+    /// The maximum number of consecutive retries of a single request.
+    max_retries: usize,
 }
 
 impl Default for BodiesDownloaderBuilder {
@@ -460,6 +465,8 @@ impl Default for BodiesDownloaderBuilder {
             stream_batch_size: 1000,
             max_buffered_responses: 30000,
             concurrent_requests_range: 5..=100,
+            request_timeout: Duration::from_secs(30),
+            max_retries: 10,
         }
     }
 }
@@ -492,6 +499,19 @@ impl BodiesDownloaderBuilder {
         self
     }
 
+    /// Set the timeout after which a pending request is retried.
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Set the number of consecutive retries of a failed request after which the request is
+    /// restarted with high priority.
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
     /// Consume self and return the concurrent donwloader.
     pub fn build<B, DB>(
         self,
@@ -508,9 +528,16 @@ impl BodiesDownloaderBuilder {
             stream_batch_size,
             concurrent_requests_range,
             max_buffered_responses,
+            request_timeout,
+            max_retries,
         } = self;
         let metrics = DownloaderMetrics::new(BODIES_DOWNLOADER_SCOPE);
-        let in_progress_queue = BodiesRequestQueue::new(metrics.clone());
+        metrics.request_timeout.set(request_timeout.as_secs_f64());
+        metrics.max_retries.set(max_retries as f64);
+        metrics.max_concurrent_requests.set(*concurrent_requests_range.end() as f64);
+        metrics.max_buffered_responses.set(max_buffered_responses as f64);
+        let in_progress_queue =
+            BodiesRequestQueue::new(metrics.clone(), request_timeout, max_retries);
         BodiesDownloader {
             client: Arc::new(client),
             consensus,
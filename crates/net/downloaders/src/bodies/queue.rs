@@ -15,6 +15,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 /// The wrapper around [FuturesUnordered] that keeps information
@@ -25,6 +26,10 @@ pub(crate) struct BodiesRequestQueue<B: BodiesClient> {
     inner: FuturesUnordered<BodiesRequestFuture<B>>,
     /// The downloader metrics.
     metrics: DownloaderMetrics,
+    /// The timeout of a single request.
+    request_timeout: Duration,
+    /// The maximum number of consecutive retries of a single request.
+    max_retries: usize,
     /// Last requested block number.
     pub(crate) last_requested_block_number: Option<BlockNumber>,
 }
@@ -34,8 +39,18 @@ where
     B: BodiesClient + 'static,
 {
     /// Create new instance of request queue.
-    pub(crate) fn new(metrics: DownloaderMetrics) -> Self {
-        Self { metrics, inner: Default::default(), last_requested_block_number: None }
+    pub(crate) fn new(
+        metrics: DownloaderMetrics,
+        request_timeout: Duration,
+        max_retries: usize,
+    ) -> Self {
+        Self {
+            metrics,
+            request_timeout,
+            max_retries,
+            inner: Default::default(),
+            last_requested_block_number: None,
+        }
     }
 
     /// Returns `true` if the queue is empty.
@@ -74,6 +89,8 @@ where
         // Create request and push into the queue.
         self.inner.push(
             BodiesRequestFuture::new(client, consensus, priority, self.metrics.clone())
+                .with_request_timeout(self.request_timeout)
+                .with_max_retries(self.max_retries)
                 .with_headers(request),
         )
     }
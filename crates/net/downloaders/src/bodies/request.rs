@@ -13,8 +13,10 @@ use std::{
     collections::VecDeque,
     pin::Pin,
     sync::Arc,
-    task::{ready, Context, Poll},
+    task::{Context, Poll},
+    time::Duration,
 };
+use tokio::time::{sleep, Sleep};
 
 /// Body request implemented as a [Future].
 ///
@@ -25,6 +27,11 @@ use std::{
 /// It then proceeds to verify the downloaded bodies. In case of an validation error,
 /// the future will start over.
 ///
+/// If a request timeout is set, a request that is not answered in time is treated as a failed
+/// request. Failed requests are retried indefinitely, like the headers downloader does. If a retry
+/// limit is set and the request failed more than `max_retries` times in a row, it is restarted
+/// with high priority, so it is sent ahead of the other queued requests.
+///
 /// The future will filter out any empty headers (see [reth_primitives::Header::is_empty]) from the
 /// request. If [BodiesRequestFuture] was initialized with all empty headers, no request will be
 /// dispatched and they will be immediately returned upon polling.
@@ -45,6 +52,14 @@ pub(crate) struct BodiesRequestFuture<B: BodiesClient> {
     buffer: Vec<BlockResponse>,
     fut: Option<B::Output>,
     last_request_len: Option<usize>,
+    /// The timeout of a single request, if any.
+    request_timeout: Option<Duration>,
+    /// Fires when the pending request times out. Created on first poll of the request.
+    timeout: Option<Pin<Box<Sleep>>>,
+    /// The maximum number of consecutive retries before the request is restarted, if any.
+    max_retries: Option<usize>,
+    /// The number of consecutive failed requests.
+    retries: usize,
 }
 
 impl<B> BodiesRequestFuture<B>
@@ -67,9 +82,26 @@ where
             buffer: Default::default(),
             last_request_len: None,
             fut: None,
+            request_timeout: None,
+            timeout: None,
+            max_retries: None,
+            retries: 0,
         }
     }
 
+    /// Sets the timeout after which a pending request is considered failed.
+    pub(crate) fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = Some(request_timeout);
+        self
+    }
+
+    /// Sets the number of consecutive retries after which the request is restarted with high
+    /// priority.
+    pub(crate) fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
     pub(crate) fn with_headers(mut self, headers: Vec<SealedHeader>) -> Self {
         self.buffer.reserve_exact(headers.len());
         self.headers = VecDeque::from(headers);
@@ -81,12 +113,26 @@ where
         self
     }
 
+    /// Handles a failed request and resubmits it.
+    ///
+    /// If the request exhausted its retries, it is restarted with high priority.
     fn on_error(&mut self, error: DownloadError, peer_id: Option<PeerId>) {
         self.metrics.increment_errors(&error);
         tracing::error!(target: "downloaders::bodies", ?peer_id, %error, "Error requesting bodies");
         if let Some(peer_id) = peer_id {
             self.client.report_bad_message(peer_id);
         }
+
+        self.retries += 1;
+        if self.max_retries.map_or(false, |max_retries| self.retries > max_retries) {
+            tracing::warn!(target: "downloaders::bodies", retries = self.retries, "Bodies request exhausted retries, restarting request");
+            self.metrics.exhausted_retries.increment(1);
+            self.retries = 0;
+            self.priority = Priority::High;
+        } else {
+            self.metrics.retried_requests.increment(1);
+        }
+
         self.submit_request(self.next_request().expect("existing hashes to resubmit"));
     }
 
@@ -102,6 +148,7 @@ where
         let client = Arc::clone(&self.client);
         self.last_request_len = Some(req.len());
         self.fut = Some(client.get_block_bodies_with_priority(req, self.priority));
+        self.timeout = None;
     }
 
     /// Process block response.
@@ -133,12 +180,14 @@ where
 
         // Buffer block responses
         self.try_buffer_blocks(bodies)?;
+        self.retries = 0;
 
         // Submit next request if any
         if let Some(req) = self.next_request() {
             self.submit_request(req);
         } else {
             self.fut = None;
+            self.timeout = None;
         }
 
         Ok(())
@@ -201,20 +250,32 @@ where
             // Check if there is a pending requests. It might not exist if all
             // headers are empty and there is nothing to download.
             if let Some(fut) = this.fut.as_mut() {
-                match ready!(fut.poll_unpin(cx)) {
-                    Ok(response) => {
+                match fut.poll_unpin(cx) {
+                    Poll::Ready(Ok(response)) => {
                         let peer_id = response.peer_id();
                         if let Err(error) = this.on_block_response(response) {
                             this.on_error(error, Some(peer_id));
                         }
                     }
-                    Err(error) => {
+                    Poll::Ready(Err(error)) => {
                         if error.is_channel_closed() {
                             return Poll::Ready(Err(error.into()))
                         }
 
                         this.on_error(error.into(), None);
                     }
+                    Poll::Pending => {
+                        let Some(request_timeout) = this.request_timeout else {
+                            return Poll::Pending
+                        };
+                        let timeout =
+                            this.timeout.get_or_insert_with(|| Box::pin(sleep(request_timeout)));
+                        if timeout.poll_unpin(cx).is_pending() {
+                            return Poll::Pending
+                        }
+
+                        this.on_error(DownloadError::Timeout, None);
+                    }
                 }
             }
 
@@ -287,4 +348,31 @@ mod tests {
             (headers.into_iter().filter(|h| !h.is_empty()).count() as u64 + 1) / 2
         );
     }
+
+    /// Check that the request is restarted with high priority once the retries are exhausted.
+    #[tokio::test]
+    async fn request_is_restarted_after_max_retries() {
+        let (headers, bodies) = generate_bodies(0..=19);
+
+        // The client responds with no bodies to every request.
+        let client = Arc::new(
+            TestBodiesClient::default()
+                .with_bodies(bodies)
+                .with_max_batch_size(0)
+                .with_should_delay(true),
+        );
+        let mut fut = BodiesRequestFuture::new(
+            client.clone(),
+            Arc::new(TestConsensus::default()),
+            Priority::Normal,
+            DownloaderMetrics::new(TEST_SCOPE),
+        )
+        .with_max_retries(2)
+        .with_headers(headers);
+
+        // The request keeps being retried instead of failing the download.
+        assert!(tokio::time::timeout(Duration::from_secs(1), &mut fut).await.is_err());
+        assert!(client.times_requested() > 3);
+        assert_eq!(fut.priority, Priority::High);
+    }
 }
@@ -8,7 +8,7 @@ use rayon::prelude::*;
 use reth_interfaces::{
     consensus::Consensus,
     p2p::{
-        error::{DownloadError, DownloadResult, PeerRequestResult},
+        error::{DownloadError, DownloadResult, PeerRequestResult, RequestError},
         headers::{
             client::{HeadersClient, HeadersRequest},
             downloader::{validate_header_download, HeaderDownloader, SyncTarget},
@@ -22,13 +22,15 @@ use reth_primitives::{
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use std::{
     cmp::{Ordering, Reverse},
-    collections::{binary_heap::PeekMut, BinaryHeap},
+    collections::{binary_heap::PeekMut, BinaryHeap, HashMap},
     future::Future,
     pin::Pin,
     sync::Arc,
     task::{ready, Context, Poll},
+    time::Duration,
 };
-use tracing::trace;
+use tokio::time::{sleep, Sleep};
+use tracing::{trace, warn};
 
 /// A heuristic that is used to determine the number of requests that should be prepared for a peer.
 /// This should ensure that there are always requests lined up for peers to handle while the
@@ -74,6 +76,12 @@ pub struct ReverseHeadersDownloader<H: HeadersClient> {
     stream_batch_size: usize,
     /// Maximum amount of received headers to buffer internally.
     max_buffered_responses: usize,
+    /// The timeout of a single request.
+    request_timeout: Duration,
+    /// The maximum number of consecutive retries of a single request.
+    max_retries: usize,
+    /// The number of failed attempts of requests, keyed by the start block of the request.
+    request_retries: HashMap<BlockNumber, usize>,
     /// Contains the request to retrieve the headers for the sync target
     ///
     /// This will give us the block number of the `sync_target`, after which we can send multiple
@@ -257,6 +265,10 @@ where
             validated.push(parent);
         }
 
+        if let Some(block_number) = request.start.as_number() {
+            self.request_retries.remove(&block_number);
+        }
+
         // update tracked block info (falling block number)
         self.next_chain_tip_block_number =
             validated.last().expect("exists").number.saturating_sub(1);
@@ -482,7 +494,8 @@ where
 
     /// Handles the error of a bad response
     ///
-    /// This will re-submit the request.
+    /// This will re-submit the request. If the request exhausted its retries, all pending requests
+    /// are discarded and the download restarts from the next expected block instead.
     fn on_headers_error(&mut self, err: HeadersResponseError) {
         let HeadersResponseError { request, peer_id, error } = err;
 
@@ -491,10 +504,34 @@ where
         // Update error metric
         self.metrics.increment_errors(&error);
 
+        if let Some(block_number) = request.start.as_number() {
+            let retries = self.request_retries.entry(block_number).or_default();
+            *retries += 1;
+            if *retries > self.max_retries {
+                warn!(target: "downloaders::headers", %block_number, %error, "Headers request exhausted retries, restarting requests");
+                self.metrics.exhausted_retries.increment(1);
+                self.restart_requests();
+                return
+            }
+        }
+
         // Re-submit the request
+        self.metrics.retried_requests.increment(1);
         self.submit_request(request, Priority::High);
     }
 
+    /// Discards all in-flight requests and unvalidated responses and resumes requesting headers
+    /// from the next expected block.
+    fn restart_requests(&mut self) {
+        self.next_request_block_number = self.next_chain_tip_block_number;
+        self.buffered_responses.clear();
+        self.in_progress_queue.clear();
+        self.request_retries.clear();
+
+        self.metrics.in_flight_requests.set(0.);
+        self.metrics.buffered_responses.set(0.);
+    }
+
     /// Attempts to validate the buffered responses
     ///
     /// Returns an error if the next expected response was popped, but failed validation.
@@ -543,6 +580,8 @@ where
         HeadersRequestFuture {
             request: Some(request.clone()),
             fut: client.get_headers_with_priority(request, priority),
+            request_timeout: self.request_timeout,
+            timeout: None,
         }
     }
 
@@ -557,6 +596,7 @@ where
         self.queued_validated_headers.clear();
         self.buffered_responses.clear();
         self.in_progress_queue.clear();
+        self.request_retries.clear();
 
         self.metrics.in_flight_requests.set(0.);
         self.metrics.buffered_responses.set(0.);
@@ -805,9 +845,14 @@ where
 }
 
 /// A future that returns a list of [`Header`] on success.
+///
+/// Resolves with [RequestError::Timeout] if the request isn't answered before the timeout fires.
 struct HeadersRequestFuture<F> {
     request: Option<HeadersRequest>,
     fut: F,
+    request_timeout: Duration,
+    /// Created on first poll, so the future can be created outside of the runtime.
+    timeout: Option<Pin<Box<Sleep>>>,
 }
 
 impl<F> Future for HeadersRequestFuture<F>
@@ -818,7 +863,15 @@ where
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
-        let outcome = ready!(this.fut.poll_unpin(cx));
+        let outcome = match this.fut.poll_unpin(cx) {
+            Poll::Ready(outcome) => outcome,
+            Poll::Pending => {
+                let request_timeout = this.request_timeout;
+                let timeout = this.timeout.get_or_insert_with(|| Box::pin(sleep(request_timeout)));
+                ready!(timeout.poll_unpin(cx));
+                Err(RequestError::Timeout)
+            }
+        };
         let request = this.request.take().unwrap();
 
         Poll::Ready(HeadersRequestOutcome { request, outcome })
@@ -994,6 +1047,10 @@ pub struct ReverseHeadersDownloaderBuilder {
     max_concurrent_requests: usize,
     /// How many responses to buffer
     max_buffered_responses: usize,
+    /// The timeout of a single request
+    request_timeout: Duration,
+    /// How many times a failed request is retried
+    max_retries: usize,
 }
 
 impl Default for ReverseHeadersDownloaderBuilder {
@@ -1004,6 +1061,8 @@ impl Default for ReverseHeadersDownloaderBuilder {
             max_concurrent_requests: 150,
             min_concurrent_requests: 5,
             max_buffered_responses: 750,
+            request_timeout: Duration::from_secs(30),
+            max_retries: 10,
         }
     }
 }
@@ -1056,6 +1115,23 @@ impl ReverseHeadersDownloaderBuilder {
         self
     }
 
+    /// Set the timeout of a single request.
+    ///
+    /// A request that isn't answered within this duration is treated as failed and retried.
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Set how many times a failed request is retried.
+    ///
+    /// Once a request has failed more often than this, the [ReverseHeadersDownloader] discards all
+    /// pending requests and resumes downloading from the next expected block.
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
     /// Build [ReverseHeadersDownloader] with provided consensus
     /// and header client implementations
     pub fn build<H>(self, client: H, consensus: Arc<dyn Consensus>) -> ReverseHeadersDownloader<H>
@@ -1068,7 +1144,14 @@ impl ReverseHeadersDownloaderBuilder {
             min_concurrent_requests,
             max_concurrent_requests,
             max_buffered_responses,
+            request_timeout,
+            max_retries,
         } = self;
+        let metrics = DownloaderMetrics::new(HEADERS_DOWNLOADER_SCOPE);
+        metrics.request_timeout.set(request_timeout.as_secs_f64());
+        metrics.max_retries.set(max_retries as f64);
+        metrics.max_concurrent_requests.set(max_concurrent_requests as f64);
+        metrics.max_buffered_responses.set(max_buffered_responses as f64);
         ReverseHeadersDownloader {
             consensus,
             client: Arc::new(client),
@@ -1084,11 +1167,14 @@ impl ReverseHeadersDownloaderBuilder {
             max_concurrent_requests,
             stream_batch_size,
             max_buffered_responses,
+            request_timeout,
+            max_retries,
+            request_retries: Default::default(),
             sync_target_request: None,
             in_progress_queue: Default::default(),
             buffered_responses: Default::default(),
             queued_validated_headers: Default::default(),
-            metrics: DownloaderMetrics::new(HEADERS_DOWNLOADER_SCOPE),
+            metrics,
         }
     }
 }
@@ -1272,6 +1358,38 @@ mod tests {
         assert_eq!(Some(downloader.next_request_block_number), downloader.local_block_number());
     }
 
+    /// Tests that pending requests are restarted once a request exhausted its retries
+    #[test]
+    fn test_restart_requests_after_max_retries() {
+        let client = Arc::new(TestHeadersClient::default());
+        let mut downloader = ReverseHeadersDownloaderBuilder::default()
+            .max_retries(1)
+            .build(Arc::clone(&client), Arc::new(TestConsensus::default()));
+        downloader.next_chain_tip_block_number = 1000;
+        downloader.next_request_block_number = 500;
+
+        let request = HeadersRequest {
+            start: 900u64.into(),
+            limit: 100,
+            direction: HeadersDirection::Falling,
+        };
+        let error = || HeadersResponseError {
+            request: request.clone(),
+            peer_id: None,
+            error: DownloadError::EmptyResponse,
+        };
+
+        // the first failure is retried
+        downloader.on_headers_error(error());
+        assert_eq!(downloader.in_progress_queue.len(), 1);
+        assert_eq!(downloader.next_request_block_number, 500);
+
+        // the second failure exhausts the retries
+        downloader.on_headers_error(error());
+        assert!(downloader.in_progress_queue.is_empty());
+        assert_eq!(downloader.next_request_block_number, 1000);
+    }
+
     #[test]
     fn test_resp_order() {
         let mut heap = BinaryHeap::new();
@@ -1,5 +1,5 @@
 use metrics::{Counter, Gauge};
-use reth_interfaces::p2p::error::DownloadError;
+use reth_interfaces::p2p::error::{DownloadError, RequestError};
 use reth_metrics_derive::Metrics;
 
 /// Common downloader metrics.
@@ -42,13 +42,27 @@ pub struct DownloaderMetrics {
     pub validation_errors: Counter,
     /// Number of unexpected errors while requesting items
     pub unexpected_errors: Counter,
+    /// Number of times a failed request was retried
+    pub retried_requests: Counter,
+    /// Number of requests that were restarted after exhausting all retries
+    pub exhausted_retries: Counter,
+    /// The configured timeout of a single request in seconds
+    pub request_timeout: Gauge,
+    /// The configured maximum number of retries of a single request
+    pub max_retries: Gauge,
+    /// The configured maximum number of concurrent requests
+    pub max_concurrent_requests: Gauge,
+    /// The configured maximum number of buffered responses
+    pub max_buffered_responses: Gauge,
 }
 
 impl DownloaderMetrics {
     /// Increment errors counter.
     pub fn increment_errors(&self, error: &DownloadError) {
         match error {
-            DownloadError::Timeout => self.timeout_errors.increment(1),
+            DownloadError::Timeout | DownloadError::RequestError(RequestError::Timeout) => {
+                self.timeout_errors.increment(1)
+            }
             DownloadError::HeaderValidation { .. } | DownloadError::BodyValidation { .. } => {
                 self.validation_errors.increment(1)
             }
@@ -96,6 +96,25 @@ impl proptest::arbitrary::Arbitrary for Capability {
     }
 }
 
+/// A capability that is not known to this crate, together with the number of messages it reserves.
+///
+/// Custom subprotocols must announce how many message ids they use, because the message id
+/// offsets of all capabilities that are ordered after it depend on it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Protocol {
+    /// The name and version of the subprotocol.
+    pub cap: Capability,
+    /// The number of message ids the subprotocol reserves.
+    pub messages: u8,
+}
+
+impl Protocol {
+    /// Create a new `Protocol` for the given capability that uses `messages` message ids.
+    pub fn new(cap: Capability, messages: u8) -> Self {
+        Self { cap, messages }
+    }
+}
+
 /// Represents all capabilities of a node.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Capabilities {
@@ -181,6 +200,9 @@ pub enum SharedCapability {
     /// The `eth` capability.
     Eth { version: EthVersion, offset: u8 },
 
+    /// A custom capability that was registered as [`Protocol`].
+    Custom { name: SmolStr, version: u8, offset: u8, messages: u8 },
+
     /// An unknown capability.
     UnknownCapability { name: SmolStr, version: u8, offset: u8 },
 }
@@ -194,10 +216,44 @@ impl SharedCapability {
         }
     }
 
+    /// Creates a new [`SharedCapability`] based on the given name, offset, and version, resolving
+    /// capabilities other than `eth` via the given [`Protocol`]s.
+    pub(crate) fn with_protocols(
+        name: &str,
+        version: u8,
+        offset: u8,
+        protocols: &[Protocol],
+    ) -> Result<Self, SharedCapabilityError> {
+        if name != "eth" {
+            if let Some(protocol) =
+                protocols.iter().find(|p| p.cap.name == name && p.cap.version == version as usize)
+            {
+                return Ok(Self::Custom {
+                    name: name.into(),
+                    version,
+                    offset,
+                    messages: protocol.messages,
+                })
+            }
+        }
+        Self::new(name, version, offset)
+    }
+
+    /// Returns true if the message id belongs to this capability.
+    ///
+    /// The id is expected to be the offset id of the message as it is sent over the wire.
+    pub fn contains_message_id(&self, id: u8) -> bool {
+        match self.num_messages() {
+            Ok(messages) => id >= self.offset() && id - self.offset() < messages,
+            Err(_) => false,
+        }
+    }
+
     /// Returns the name of the capability.
     pub fn name(&self) -> &str {
         match self {
             SharedCapability::Eth { .. } => "eth",
+            SharedCapability::Custom { name, .. } => name,
             SharedCapability::UnknownCapability { name, .. } => name,
         }
     }
@@ -206,6 +262,7 @@ impl SharedCapability {
     pub fn version(&self) -> u8 {
         match self {
             SharedCapability::Eth { version, .. } => *version as u8,
+            SharedCapability::Custom { version, .. } => *version,
             SharedCapability::UnknownCapability { version, .. } => *version,
         }
     }
@@ -214,6 +271,7 @@ impl SharedCapability {
     pub fn offset(&self) -> u8 {
         match self {
             SharedCapability::Eth { offset, .. } => *offset,
+            SharedCapability::Custom { offset, .. } => *offset,
             SharedCapability::UnknownCapability { offset, .. } => *offset,
         }
     }
@@ -222,6 +280,7 @@ impl SharedCapability {
     pub fn num_messages(&self) -> Result<u8, SharedCapabilityError> {
         match self {
             SharedCapability::Eth { version, .. } => Ok(version.total_messages()),
+            SharedCapability::Custom { messages, .. } => Ok(*messages),
             _ => Err(SharedCapabilityError::UnknownCapability),
         }
     }
@@ -39,6 +39,10 @@ pub enum P2PStreamError {
     Disconnected(DisconnectReason),
     #[error("unknown disconnect reason: {0}")]
     UnknownDisconnectReason(#[from] UnknownDisconnectReason),
+    #[error("capability {0} is not shared with the peer")]
+    UnknownCapability(String),
+    #[error("message id {id} is out of range for capability {name}")]
+    UnknownCapabilityMessageId { name: String, id: usize },
 }
 
 // === impl P2PStreamError ===
@@ -27,5 +27,9 @@ pub use crate::{
     disconnect::{CanDisconnect, DisconnectReason},
     ethstream::{EthStream, UnauthedEthStream, MAX_MESSAGE_SIZE},
     hello::HelloMessage,
-    p2pstream::{P2PMessage, P2PMessageID, P2PStream, ProtocolVersion, UnauthedP2PStream},
+    p2pstream::{
+        shared_capability_offsets, P2PMessage, P2PMessageID, P2PStream, ProtocolVersion,
+        UnauthedP2PStream,
+    },
+    pinger::{PingConfig, DEFAULT_MAX_MISSED_PINGS, DEFAULT_PING_INTERVAL, DEFAULT_PING_TIMEOUT},
 };
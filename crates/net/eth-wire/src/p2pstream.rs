@@ -1,20 +1,22 @@
 #![allow(dead_code, unreachable_pub, missing_docs, unused_variables)]
 use crate::{
-    capability::{Capability, SharedCapability},
+    capability::{Capability, Protocol, RawCapabilityMessage, SharedCapability},
     disconnect::CanDisconnect,
     errors::{P2PHandshakeError, P2PStreamError},
-    pinger::{Pinger, PingerEvent},
+    pinger::{PingConfig, Pinger, PingerEvent, DEFAULT_PING_INTERVAL, DEFAULT_PING_TIMEOUT},
     DisconnectReason, HelloMessage,
 };
 use futures::{Sink, SinkExt, StreamExt};
 use metrics::counter;
 use pin_project::pin_project;
 use reth_codecs::derive_arbitrary;
+use reth_net_common::bandwidth_meter::BandwidthMeter;
 use reth_primitives::{
     bytes::{Buf, BufMut, Bytes, BytesMut},
     hex,
 };
 use reth_rlp::{Decodable, DecodeError, Encodable, EMPTY_LIST_CODE};
+use smol_str::SmolStr;
 use std::{
     collections::{BTreeSet, HashMap, HashSet, VecDeque},
     io,
@@ -42,14 +44,6 @@ const MAX_P2P_MESSAGE_ID: u8 = P2PMessageID::Pong as u8;
 /// handshake has timed out.
 const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
 
-/// [`PING_TIMEOUT`] determines the amount of time to wait before determining that a `p2p` ping has
-/// timed out.
-const PING_TIMEOUT: Duration = Duration::from_secs(15);
-
-/// [`PING_INTERVAL`] determines the amount of time to wait between sending `p2p` ping messages
-/// when the peer is responsive.
-const PING_INTERVAL: Duration = Duration::from_secs(60);
-
 /// [`GRACE_PERIOD`] determines the amount of time to wait for a peer to disconnect after sending a
 /// [`P2PMessage::Disconnect`] message.
 const GRACE_PERIOD: Duration = Duration::from_secs(2);
@@ -58,18 +52,40 @@ const GRACE_PERIOD: Duration = Duration::from_secs(2);
 /// `p2p` stream.
 const MAX_P2P_CAPACITY: usize = 64;
 
+/// [`MAX_CAPABILITY_MESSAGES`] is the maximum number of received messages of additional
+/// subprotocols that are buffered until they are taken.
+const MAX_CAPABILITY_MESSAGES: usize = 64;
+
 /// An un-authenticated [`P2PStream`]. This is consumed and returns a [`P2PStream`] after the
 /// `Hello` handshake is completed.
 #[pin_project]
 pub struct UnauthedP2PStream<S> {
     #[pin]
     inner: S,
+    /// Additional subprotocols that are multiplexed next to `eth` if the peer shares them.
+    protocols: Vec<Protocol>,
+    /// The keepalive settings of the authenticated stream.
+    ping_config: PingConfig,
 }
 
 impl<S> UnauthedP2PStream<S> {
     /// Create a new `UnauthedP2PStream` from a type `S` which implements `Stream` and `Sink`.
     pub fn new(inner: S) -> Self {
-        Self { inner }
+        Self { inner, protocols: Vec::new(), ping_config: PingConfig::default() }
+    }
+
+    /// Sets the keepalive settings of the [`P2PStream`] returned by the handshake.
+    pub fn with_ping_config(mut self, ping_config: PingConfig) -> Self {
+        self.ping_config = ping_config;
+        self
+    }
+
+    /// Sets the additional subprotocols that should be negotiated during the handshake.
+    ///
+    /// Their capabilities are expected to be part of the local `Hello` message.
+    pub fn with_protocols(mut self, protocols: Vec<Protocol>) -> Self {
+        self.protocols = protocols;
+        self
     }
 }
 
@@ -141,20 +157,34 @@ where
             })
         }
 
-        // determine shared capabilities (currently returns only one capability)
-        let capability_res =
-            set_capability_offsets(hello.capabilities, their_hello.capabilities.clone());
+        // determine shared capabilities, `eth` is handled by the stream itself and all other
+        // shared subprotocols are multiplexed next to it
+        let capability_res = shared_capability_offsets(
+            hello.capabilities,
+            their_hello.capabilities.clone(),
+            &self.protocols,
+        )
+        .and_then(|mut shared| {
+            let eth = shared
+                .iter()
+                .position(|cap| matches!(cap, SharedCapability::Eth { .. }))
+                .ok_or(P2PStreamError::HandshakeError(P2PHandshakeError::NoSharedCapabilities))?;
+            let eth = shared.remove(eth);
+            Ok((eth, shared))
+        });
 
-        let shared_capability = match capability_res {
+        let (shared_capability, extra_capabilities) = match capability_res {
             Err(err) => {
                 // we don't share any capabilities, send a disconnect message
                 self.send_disconnect(DisconnectReason::UselessPeer).await?;
                 Err(err)
             }
-            Ok(cap) => Ok(cap),
+            Ok(caps) => Ok(caps),
         }?;
 
-        let stream = P2PStream::new(self.inner, shared_capability);
+        let stream = P2PStream::new(self.inner, shared_capability)
+            .with_extra_capabilities(extra_capabilities)
+            .with_ping_config(self.ping_config);
 
         Ok((stream, their_hello))
     }
@@ -209,6 +239,22 @@ pub struct P2PStream<S> {
     /// The supported capability for this stream.
     shared_capability: SharedCapability,
 
+    /// Additional shared subprotocols that are multiplexed over this stream.
+    extra_capabilities: Vec<SharedCapability>,
+
+    /// Received messages of the [`Self::extra_capabilities`], with message ids relative to the
+    /// capability's offset.
+    capability_messages: VecDeque<(SmolStr, RawCapabilityMessage)>,
+
+    /// Meters the reserved `p2p` messages.
+    p2p_meter: CapabilityMeter,
+
+    /// Meters the messages of the [`Self::shared_capability`].
+    capability_meter: CapabilityMeter,
+
+    /// Meters the messages of the [`Self::extra_capabilities`], in the same order.
+    extra_meters: Vec<CapabilityMeter>,
+
     /// Outgoing messages buffered for sending to the underlying stream.
     outgoing_messages: VecDeque<Bytes>,
 
@@ -226,18 +272,126 @@ impl<S> P2PStream<S> {
             inner,
             encoder: snap::raw::Encoder::new(),
             decoder: snap::raw::Decoder::new(),
-            pinger: Pinger::new(PING_INTERVAL, PING_TIMEOUT),
+            pinger: Pinger::new(DEFAULT_PING_INTERVAL, DEFAULT_PING_TIMEOUT),
+            capability_meter: CapabilityMeter::new(capability.name()),
             shared_capability: capability,
+            extra_capabilities: Vec::new(),
+            capability_messages: VecDeque::new(),
+            p2p_meter: CapabilityMeter::new("p2p"),
+            extra_meters: Vec::new(),
             outgoing_messages: VecDeque::new(),
             disconnecting: false,
         }
     }
 
+    /// Sets the keepalive settings of this stream.
+    pub fn with_ping_config(mut self, config: PingConfig) -> Self {
+        self.pinger = Pinger::new(config.interval, config.timeout)
+            .with_max_missed_pings(config.max_missed_pings);
+        self
+    }
+
+    /// Sets the additional shared subprotocols that are multiplexed next to the primary
+    /// capability.
+    ///
+    /// Messages of these capabilities are not yielded by the [`Stream`] but buffered, see
+    /// [`P2PStream::take_capability_message`].
+    pub fn with_extra_capabilities(mut self, capabilities: Vec<SharedCapability>) -> Self {
+        self.extra_meters =
+            capabilities.iter().map(|cap| CapabilityMeter::new(cap.name())).collect();
+        self.extra_capabilities = capabilities;
+        self
+    }
+
+    /// Returns the [`BandwidthMeter`]s of all capabilities of this stream, including the reserved
+    /// `p2p` messages.
+    ///
+    /// The meters count the snappy compressed messages, without the framing of the encryption
+    /// layer.
+    pub fn bandwidth_meters(&self) -> Vec<(SmolStr, BandwidthMeter)> {
+        std::iter::once(&self.p2p_meter)
+            .chain(std::iter::once(&self.capability_meter))
+            .chain(self.extra_meters.iter())
+            .map(|meter| (meter.name.clone(), meter.meter.clone()))
+            .collect()
+    }
+
+    /// Returns the meter of the capability the message id belongs to.
+    fn meter_for(&self, id: u8) -> &CapabilityMeter {
+        if id <= MAX_RESERVED_MESSAGE_ID {
+            return &self.p2p_meter
+        }
+        self.extra_capabilities
+            .iter()
+            .position(|cap| cap.contains_message_id(id))
+            .map(|idx| &self.extra_meters[idx])
+            .unwrap_or(&self.capability_meter)
+    }
+
     /// Returns the shared capability for this stream.
     pub fn shared_capability(&self) -> &SharedCapability {
         &self.shared_capability
     }
 
+    /// Returns the additional shared subprotocols of this stream.
+    pub fn extra_capabilities(&self) -> &[SharedCapability] {
+        &self.extra_capabilities
+    }
+
+    /// Returns the next buffered message of an additional subprotocol, together with the name of
+    /// its capability.
+    ///
+    /// Only a limited number of messages are buffered, the stream stops reading from the
+    /// connection until they are taken.
+    pub fn take_capability_message(&mut self) -> Option<(SmolStr, RawCapabilityMessage)> {
+        self.capability_messages.pop_front()
+    }
+
+    /// Queues in a message of an additional subprotocol.
+    ///
+    /// The message id is expected to be relative to the capability, the offset of the capability
+    /// is added before sending.
+    pub fn start_send_capability(
+        &mut self,
+        name: &str,
+        msg: RawCapabilityMessage,
+    ) -> Result<(), P2PStreamError> {
+        let capability = self
+            .extra_capabilities
+            .iter()
+            .find(|cap| cap.name() == name)
+            .ok_or_else(|| P2PStreamError::UnknownCapability(name.into()))?;
+        let id = u8::try_from(msg.id)
+            .ok()
+            .map(|id| id.saturating_add(capability.offset()))
+            .filter(|id| capability.contains_message_id(*id))
+            .ok_or_else(|| P2PStreamError::UnknownCapabilityMessageId {
+                name: name.into(),
+                id: msg.id,
+            })?;
+
+        if self.outgoing_messages.len() >= MAX_P2P_CAPACITY {
+            return Err(P2PStreamError::SendBufferFull)
+        }
+
+        let mut compressed = BytesMut::zeroed(1 + snap::raw::max_compress_len(msg.payload.len()));
+        let compressed_size =
+            self.encoder.compress(&msg.payload, &mut compressed[1..]).map_err(|err| {
+                tracing::debug!(
+                    ?err,
+                    msg=%hex::encode(&msg.payload),
+                    "error compressing capability message"
+                );
+                err
+            })?;
+        compressed.truncate(compressed_size + 1);
+        compressed[0] = id;
+        self.meter_for(id).on_outbound(compressed.len());
+        self.outgoing_messages.push_back(compressed.freeze());
+
+        Ok(())
+    }
+
     /// Returns `true` if the connection is about to disconnect.
     pub fn is_disconnecting(&self) -> bool {
         self.disconnecting
@@ -248,6 +402,7 @@ impl<S> P2PStream<S> {
         let pong = P2PMessage::Pong;
         let mut pong_bytes = BytesMut::with_capacity(pong.length());
         pong.encode(&mut pong_bytes);
+        self.p2p_meter.on_outbound(pong_bytes.len());
         self.outgoing_messages.push_back(pong_bytes.freeze());
     }
 
@@ -256,6 +411,7 @@ impl<S> P2PStream<S> {
         let ping = P2PMessage::Ping;
         let mut ping_bytes = BytesMut::with_capacity(ping.length());
         ping.encode(&mut ping_bytes);
+        self.p2p_meter.on_outbound(ping_bytes.len());
         self.outgoing_messages.push_back(ping_bytes.freeze());
     }
 
@@ -293,6 +449,7 @@ impl<S> P2PStream<S> {
         // message
         compressed[0] = buf[0];
 
+        self.p2p_meter.on_outbound(compressed.len());
         self.outgoing_messages.push_back(compressed.freeze());
         self.disconnecting = true;
         Ok(())
@@ -329,6 +486,12 @@ where
             return Poll::Ready(None)
         }
 
+        if this.capability_messages.len() >= MAX_CAPABILITY_MESSAGES {
+            // apply backpressure until the buffered messages of the additional subprotocols are
+            // taken, the consumer polls the stream again afterwards
+            return Poll::Pending
+        }
+
         // we should loop here to ensure we don't return Poll::Pending if we have a message to
         // return behind any pings we need to respond to
         while let Poll::Ready(res) = this.inner.poll_next_unpin(cx) {
@@ -364,6 +527,7 @@ where
             })?;
 
             let id = *bytes.first().ok_or(P2PStreamError::EmptyProtocolMessage)?;
+            this.meter_for(id).on_inbound(bytes.len());
             match id {
                 _ if id == P2PMessageID::Ping as u8 => {
                     if this.outgoing_messages.len() > MAX_P2P_CAPACITY {
@@ -421,6 +585,21 @@ where
                     //  * `eth/67` is reserved message IDs 0x10 - 0x19.
                     //  * `qrs/65` is reserved message IDs 0x1a - 0x21.
                     //
+                    if let Some(cap) =
+                        this.extra_capabilities.iter().find(|cap| cap.contains_message_id(id))
+                    {
+                        let msg = RawCapabilityMessage {
+                            id: (id - cap.offset()) as usize,
+                            payload: decompress_buf.split_off(1).freeze(),
+                        };
+                        this.capability_messages.push_back((cap.name().into(), msg));
+                        if this.capability_messages.len() >= MAX_CAPABILITY_MESSAGES {
+                            // stop reading until the buffered messages are taken
+                            return Poll::Pending
+                        }
+                        continue
+                    }
+
                     decompress_buf[0] = bytes[0] - this.shared_capability.offset();
 
                     return Poll::Ready(Some(Ok(decompress_buf)))
@@ -501,6 +680,7 @@ where
         // all messages sent in this stream are subprotocol messages, so we need to switch the
         // message id based on the offset
         compressed[0] = item[0] + this.shared_capability.offset();
+        this.capability_meter.on_outbound(compressed.len());
         this.outgoing_messages.push_back(compressed.freeze());
 
         Ok(())
@@ -530,6 +710,32 @@ where
     }
 }
 
+/// Meters the bytes of the messages of a single capability, for the stream and in the
+/// `p2pstream` metrics.
+#[derive(Debug)]
+struct CapabilityMeter {
+    /// The name of the capability, used as label of the metrics.
+    name: SmolStr,
+    /// The bytes of this stream.
+    meter: BandwidthMeter,
+}
+
+impl CapabilityMeter {
+    fn new(name: &str) -> Self {
+        Self { name: name.into(), meter: BandwidthMeter::default() }
+    }
+
+    fn on_inbound(&self, num_bytes: usize) {
+        self.meter.record_inbound(num_bytes);
+        counter!("p2pstream.inbound_bytes", num_bytes as u64, "capability" => self.name.to_string());
+    }
+
+    fn on_outbound(&self, num_bytes: usize) {
+        self.meter.record_outbound(num_bytes);
+        counter!("p2pstream.outbound_bytes", num_bytes as u64, "capability" => self.name.to_string());
+    }
+}
+
 /// Determines the offsets for each shared capability between the input list of peer
 /// capabilities and the input list of locally supported capabilities.
 ///
@@ -540,6 +746,24 @@ pub fn set_capability_offsets(
     local_capabilities: Vec<Capability>,
     peer_capabilities: Vec<Capability>,
 ) -> Result<SharedCapability, P2PStreamError> {
+    // NOTE: since the `P2PStream` itself only handles one capability, we return the capability
+    // with the lowest offset.
+    Ok(shared_capability_offsets(local_capabilities, peer_capabilities, &[])?
+        .first()
+        .ok_or(P2PStreamError::HandshakeError(P2PHandshakeError::NoSharedCapabilities))?
+        .clone())
+}
+
+/// Determines all shared capabilities and their offsets, like [`set_capability_offsets`].
+///
+/// Shared capabilities other than `eth` are only included if they are one of the given
+/// [`Protocol`]s, because the number of messages of a capability is required to determine the
+/// offsets of the capabilities that follow it.
+pub fn shared_capability_offsets(
+    local_capabilities: Vec<Capability>,
+    peer_capabilities: Vec<Capability>,
+    protocols: &[Protocol],
+) -> Result<Vec<SharedCapability>, P2PStreamError> {
     // find intersection of capabilities
     let our_capabilities = local_capabilities.into_iter().collect::<HashSet<_>>();
 
@@ -593,14 +817,15 @@ pub fn set_capability_offsets(
     for name in shared_capability_names {
         let version = shared_capabilities.get(&name).unwrap();
 
-        let shared_capability = SharedCapability::new(&name, *version as u8, offset)?;
+        let shared_capability =
+            SharedCapability::with_protocols(&name, *version as u8, offset, protocols)?;
 
         match shared_capability {
             SharedCapability::UnknownCapability { .. } => {
                 // Capabilities which are not shared are ignored
                 tracing::debug!("unknown capability: name={:?}, version={}", name, version,);
             }
-            SharedCapability::Eth { .. } => {
+            SharedCapability::Eth { .. } | SharedCapability::Custom { .. } => {
                 // increment the offset if the capability is known
                 offset += shared_capability.num_messages()?;
 
@@ -609,17 +834,7 @@ pub fn set_capability_offsets(
         }
     }
 
-    // TODO: support multiple capabilities - we would need a new Stream type to go on top of
-    // `P2PStream` containing its capability. `P2PStream` would still send pings and handle
-    // pongs, but instead contain a map of capabilities to their respective stream / channel.
-    // Each channel would be responsible for containing the offset for that stream and would
-    // only increment / decrement message IDs.
-    // NOTE: since the `P2PStream` currently only supports one capability, we set the
-    // capability with the lowest offset.
-    Ok(shared_with_offsets
-        .first()
-        .ok_or(P2PStreamError::HandshakeError(P2PHandshakeError::NoSharedCapabilities))?
-        .clone())
+    Ok(shared_with_offsets)
 }
 
 /// This represents only the reserved `p2p` subprotocol messages.
@@ -1007,6 +1222,43 @@ mod tests {
         ))
     }
 
+    #[test]
+    fn test_custom_capability_offsets() {
+        let before = Protocol::new(Capability::new("bor".into(), 1), 4);
+        let after = Protocol::new(Capability::new("snap".into(), 1), 8);
+        let unknown = Capability::new("les".into(), 2);
+        let local_capabilities: Vec<Capability> =
+            vec![EthVersion::Eth67.into(), before.cap.clone(), after.cap.clone(), unknown.clone()];
+        let peer_capabilities: Vec<Capability> =
+            vec![after.cap.clone(), unknown, EthVersion::Eth67.into(), before.cap.clone()];
+
+        let shared =
+            shared_capability_offsets(local_capabilities, peer_capabilities, &[before, after])
+                .unwrap();
+
+        let base = MAX_RESERVED_MESSAGE_ID + 1;
+        assert_eq!(
+            shared,
+            vec![
+                SharedCapability::Custom {
+                    name: "bor".into(),
+                    version: 1,
+                    offset: base,
+                    messages: 4
+                },
+                SharedCapability::Eth { version: EthVersion::Eth67, offset: base + 4 },
+                SharedCapability::Custom {
+                    name: "snap".into(),
+                    version: 1,
+                    offset: base + 4 + EthVersion::Eth67.total_messages(),
+                    messages: 8
+                },
+            ]
+        );
+        assert!(shared[0].contains_message_id(base + 3));
+        assert!(!shared[0].contains_message_id(base + 4));
+    }
+
     #[test]
     fn snappy_decode_encode_ping() {
         let snappy_ping = b"\x02\x01\0\xc0";
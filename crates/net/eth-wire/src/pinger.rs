@@ -7,6 +7,44 @@ use std::{
 use tokio::time::{Instant, Interval, Sleep};
 use tokio_stream::Stream;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The default interval between two `p2p` pings when the peer is responsive.
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The default amount of time to wait for the pong of a `p2p` ping.
+pub const DEFAULT_PING_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The default number of consecutive pings a peer may miss before it is disconnected.
+pub const DEFAULT_MAX_MISSED_PINGS: u32 = 1;
+
+/// Configures the `p2p` keepalive of a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PingConfig {
+    /// The interval between two pings when the peer is responsive.
+    pub interval: Duration,
+    /// The amount of time to wait for the pong of a ping.
+    pub timeout: Duration,
+    /// The number of consecutive pings the peer may miss before it is disconnected.
+    ///
+    /// A missed ping is followed up with another ping right away, which gives peers on high
+    /// latency links more time to respond.
+    pub max_missed_pings: u32,
+}
+
+impl Default for PingConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_PING_INTERVAL,
+            timeout: DEFAULT_PING_TIMEOUT,
+            max_missed_pings: DEFAULT_MAX_MISSED_PINGS,
+        }
+    }
+}
+
 /// The pinger is a state machine that is created with a maximum number of pongs that can be
 /// missed.
 #[derive(Debug)]
@@ -17,6 +55,10 @@ pub(crate) struct Pinger {
     timeout_timer: Pin<Box<Sleep>>,
     /// The timeout duration for each ping.
     timeout: Duration,
+    /// The number of consecutive pings that timed out.
+    missed_pings: u32,
+    /// The number of consecutive pings that may time out before the peer is timed out.
+    max_missed_pings: u32,
     /// Keeps track of the state
     state: PingState,
 }
@@ -34,12 +76,21 @@ impl Pinger {
             ping_interval: tokio::time::interval_at(now + ping_interval, ping_interval),
             timeout_timer: Box::pin(timeout_timer),
             timeout: timeout_duration,
+            missed_pings: 0,
+            max_missed_pings: 1,
         }
     }
 
+    /// Sets the number of consecutive pings that may time out before the peer is timed out.
+    pub(crate) fn with_max_missed_pings(mut self, max_missed_pings: u32) -> Self {
+        self.max_missed_pings = max_missed_pings.max(1);
+        self
+    }
+
     /// Mark a pong as received, and transition the pinger to the `Ready` state if it was in the
     /// `WaitingForPong` state. Unsets the sleep timer.
     pub(crate) fn on_pong(&mut self) -> Result<(), PingerError> {
+        self.missed_pings = 0;
         match self.state {
             PingState::Ready => Err(PingerError::UnexpectedPong),
             PingState::WaitingForPong => {
@@ -78,6 +129,12 @@ impl Pinger {
             }
             PingState::WaitingForPong => {
                 if self.timeout_timer.is_elapsed() {
+                    self.missed_pings += 1;
+                    if self.missed_pings < self.max_missed_pings {
+                        // give the peer another chance and ping again right away
+                        self.timeout_timer.as_mut().reset(Instant::now() + self.timeout);
+                        return Poll::Ready(Ok(PingerEvent::Ping))
+                    }
                     self.state = PingState::TimedOut;
                     return Poll::Ready(Ok(PingerEvent::Timeout))
                 }
@@ -144,4 +201,26 @@ mod tests {
 
         assert_eq!(pinger.next().await.unwrap().unwrap(), PingerEvent::Ping);
     }
+
+    #[tokio::test]
+    async fn test_max_missed_pings() {
+        let interval = Duration::from_millis(300);
+        let timeout = Duration::from_millis(20);
+        let mut pinger = Pinger::new(interval, timeout).with_max_missed_pings(3);
+        assert_eq!(pinger.next().await.unwrap().unwrap(), PingerEvent::Ping);
+
+        // the first two missed pings are followed up with another ping
+        tokio::time::sleep(timeout).await;
+        assert_eq!(pinger.next().await.unwrap().unwrap(), PingerEvent::Ping);
+        tokio::time::sleep(timeout).await;
+        assert_eq!(pinger.next().await.unwrap().unwrap(), PingerEvent::Ping);
+        tokio::time::sleep(timeout).await;
+        assert_eq!(pinger.next().await.unwrap().unwrap(), PingerEvent::Timeout);
+
+        // a pong resets the missed pings
+        pinger.on_pong().unwrap();
+        assert_eq!(pinger.next().await.unwrap().unwrap(), PingerEvent::Ping);
+        tokio::time::sleep(timeout).await;
+        assert_eq!(pinger.next().await.unwrap().unwrap(), PingerEvent::Ping);
+    }
 }
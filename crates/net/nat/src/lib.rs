@@ -5,7 +5,16 @@
     attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
 ))]
 
-//! Helpers for resolving the external IP.
+//! Helpers for resolving the external IP and mapping ports on the router.
+
+mod mapping;
+mod natpmp;
+
+pub use mapping::{
+    add_port_mapping, maintain_port_mappings, GrantedPortMapping, MappingProtocol, NatError,
+    PortMapping, DEFAULT_PORT_MAPPING_LEASE,
+};
+pub use natpmp::NatPmpError;
 
 use igd::aio::search_gateway;
 use pin_project_lite::pin_project;
@@ -30,8 +39,10 @@ pub enum NatResolver {
     /// Resolve with any available resolver.
     #[default]
     Any,
-    /// Resolve via Upnp
+    /// Resolve via Upnp, and map the node's ports on the gateway.
     Upnp,
+    /// Resolve via NAT-PMP, and map the node's ports on the gateway.
+    NatPmp,
     /// Resolve external IP via [public_ip::Resolver]
     PublicIp,
     /// Use the given [IpAddr]
@@ -47,6 +58,12 @@ impl NatResolver {
     pub async fn external_addr(self) -> Option<IpAddr> {
         external_addr_with(self).await
     }
+
+    /// Returns true if the resolver creates port mappings on the router, see
+    /// [maintain_port_mappings].
+    pub fn supports_port_mapping(&self) -> bool {
+        matches!(self, NatResolver::Upnp | NatResolver::NatPmp)
+    }
 }
 
 impl fmt::Display for NatResolver {
@@ -54,6 +71,7 @@ impl fmt::Display for NatResolver {
         match self {
             NatResolver::Any => f.write_str("any"),
             NatResolver::Upnp => f.write_str("upnp"),
+            NatResolver::NatPmp => f.write_str("pmp"),
             NatResolver::PublicIp => f.write_str("publicip"),
             NatResolver::ExternalIp(ip) => write!(f, "extip:{ip}"),
             NatResolver::None => f.write_str("none"),
@@ -79,6 +97,7 @@ impl FromStr for NatResolver {
         let r = match s {
             "any" => NatResolver::Any,
             "upnp" => NatResolver::Upnp,
+            "pmp" | "natpmp" => NatResolver::NatPmp,
             "none" => NatResolver::None,
             "publicip" | "public-ip" => NatResolver::PublicIp,
             s => {
@@ -174,6 +193,7 @@ pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
             .await
         }
         NatResolver::Upnp => resolve_external_ip_upnp().await,
+        NatResolver::NatPmp => resolve_external_ip_natpmp().await,
         NatResolver::PublicIp => resolve_external_ip().await,
         NatResolver::ExternalIp(ip) => Some(ip),
         NatResolver::None => None,
@@ -241,6 +261,16 @@ async fn resolve_external_ip_upnp() -> Option<IpAddr> {
         .ok()
 }
 
+async fn resolve_external_ip_natpmp() -> Option<IpAddr> {
+    mapping::resolve_external_ip_natpmp()
+        .await
+        .map_err(|err| {
+            warn!(target: "net::nat", ?err, "failed to resolve external ip via nat-pmp gateway");
+            err
+        })
+        .ok()
+}
+
 async fn resolve_external_ip() -> Option<IpAddr> {
     public_ip::addr().await
 }
@@ -270,10 +300,30 @@ mod tests {
         dbg!(ip);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn add_upnp_port_mapping() {
+        reth_tracing::init_test_tracing();
+        let lease = add_port_mapping(
+            NatResolver::Upnp,
+            PortMapping::tcp(30303),
+            30303,
+            DEFAULT_PORT_MAPPING_LEASE,
+        )
+        .await;
+        dbg!(lease);
+    }
+
     #[test]
     fn test_from_str() {
         assert_eq!(NatResolver::Any, "any".parse().unwrap());
         assert_eq!(NatResolver::None, "none".parse().unwrap());
+        assert_eq!(NatResolver::Upnp, "upnp".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp, "pmp".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp, "natpmp".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp.to_string(), "pmp");
+        assert!(NatResolver::NatPmp.supports_port_mapping());
+        assert!(!NatResolver::Any.supports_port_mapping());
 
         let ip = NatResolver::ExternalIp(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
         let s = "extip:0.0.0.0";
@@ -0,0 +1,203 @@
+//! Router port mappings via UPnP and NAT-PMP.
+
+use crate::{natpmp, natpmp::NatPmpError, NatResolver};
+use igd::{aio::search_gateway, PortMappingProtocol};
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+use tracing::{debug, warn};
+
+/// The lease requested for a port mapping.
+pub const DEFAULT_PORT_MAPPING_LEASE: Duration = Duration::from_secs(20 * 60);
+
+/// The minimum interval between two renewals of the port mappings, in case the router grants a
+/// very short lease.
+const MIN_RENEWAL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The description of the port mappings, shown in the router's interface.
+const PORT_MAPPING_DESCRIPTION: &str = "reth";
+
+/// The transport protocol of a [PortMapping].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MappingProtocol {
+    /// A TCP port, e.g. the RLPx listener.
+    Tcp,
+    /// A UDP port, e.g. the discovery service.
+    Udp,
+}
+
+impl fmt::Display for MappingProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingProtocol::Tcp => f.write_str("tcp"),
+            MappingProtocol::Udp => f.write_str("udp"),
+        }
+    }
+}
+
+/// A local port that should be reachable via the same external port of the router.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PortMapping {
+    /// The transport protocol of the port.
+    pub protocol: MappingProtocol,
+    /// The local port, which is also requested as external port.
+    pub port: u16,
+}
+
+impl PortMapping {
+    /// A mapping for the given TCP port.
+    pub fn tcp(port: u16) -> Self {
+        Self { protocol: MappingProtocol::Tcp, port }
+    }
+
+    /// A mapping for the given UDP port.
+    pub fn udp(port: u16) -> Self {
+        Self { protocol: MappingProtocol::Udp, port }
+    }
+}
+
+/// A port mapping the router granted.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct GrantedPortMapping {
+    /// The external port the router forwards to the local port.
+    ///
+    /// NAT-PMP routers can grant a different port than the requested one.
+    pub external_port: u16,
+    /// The lease after which the mapping has to be renewed.
+    pub lease: Duration,
+}
+
+/// Errors that can occur when talking to the router.
+#[derive(Debug, thiserror::Error)]
+pub enum NatError {
+    /// The configured [NatResolver] can't create port mappings.
+    #[error("Nat Resolver {0} does not support port mappings")]
+    Unsupported(NatResolver),
+    /// No UPnP gateway was found.
+    #[error("failed to find upnp gateway: {0}")]
+    UpnpSearch(#[from] igd::SearchError),
+    /// The UPnP gateway rejected the port mapping.
+    #[error("failed to add upnp port mapping: {0}")]
+    UpnpAddPort(#[from] igd::AddPortError),
+    /// A NAT-PMP request failed.
+    #[error("nat-pmp request failed: {0}")]
+    NatPmp(#[from] NatPmpError),
+    /// Failed to determine the local address of the gateway's network.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Asks the router to forward the external port to the local port of the [PortMapping], via the
+/// protocol of the given [NatResolver].
+///
+/// Only [NatResolver::Upnp] and [NatResolver::NatPmp] support port mappings. Returns the external
+/// port and the lease the router granted.
+pub async fn add_port_mapping(
+    resolver: NatResolver,
+    mapping: PortMapping,
+    external_port: u16,
+    lease: Duration,
+) -> Result<GrantedPortMapping, NatError> {
+    match resolver {
+        NatResolver::Upnp => add_port_mapping_upnp(mapping, external_port, lease).await,
+        NatResolver::NatPmp => add_port_mapping_natpmp(mapping, external_port, lease).await,
+        resolver => Err(NatError::Unsupported(resolver)),
+    }
+}
+
+/// Creates the port mappings and keeps them alive by renewing them before their lease expires.
+///
+/// This never resolves and is intended to be spawned as a task. Failures are logged and retried
+/// on the next renewal, because the router might only become reachable later. Once the task is
+/// dropped, the mappings expire with their lease.
+///
+/// The local port is requested as external port. If the router grants a different external port,
+/// that port is requested on renewals, so it stays stable. Peers only learn the local port, so a
+/// warning is logged in that case.
+pub async fn maintain_port_mappings(resolver: NatResolver, mappings: Vec<PortMapping>) {
+    let mut external_ports = mappings.iter().map(|mapping| mapping.port).collect::<Vec<_>>();
+    loop {
+        let mut renew_in = DEFAULT_PORT_MAPPING_LEASE / 2;
+        for (mapping, external_port) in mappings.iter().zip(external_ports.iter_mut()) {
+            match add_port_mapping(resolver, *mapping, *external_port, DEFAULT_PORT_MAPPING_LEASE)
+                .await
+            {
+                Ok(granted) => {
+                    debug!(target: "net::nat", %resolver, ?mapping, ?granted, "added port mapping");
+                    if granted.external_port != *external_port {
+                        warn!(
+                            target: "net::nat",
+                            %resolver,
+                            ?mapping,
+                            external_port = granted.external_port,
+                            "router mapped a different external port, peers can't reach the advertised port"
+                        );
+                        *external_port = granted.external_port;
+                    }
+                    renew_in = renew_in.min(granted.lease / 2);
+                }
+                Err(err) => {
+                    warn!(target: "net::nat", %resolver, ?mapping, %err, "failed to add port mapping");
+                }
+            }
+        }
+        tokio::time::sleep(renew_in.max(MIN_RENEWAL_INTERVAL)).await;
+    }
+}
+
+async fn add_port_mapping_upnp(
+    mapping: PortMapping,
+    external_port: u16,
+    lease: Duration,
+) -> Result<GrantedPortMapping, NatError> {
+    let gateway = search_gateway(Default::default()).await?;
+    let local_ip = local_ip_towards(gateway.addr).await?;
+    let protocol = match mapping.protocol {
+        MappingProtocol::Tcp => PortMappingProtocol::TCP,
+        MappingProtocol::Udp => PortMappingProtocol::UDP,
+    };
+    gateway
+        .add_port(
+            protocol,
+            external_port,
+            SocketAddr::new(local_ip, mapping.port),
+            lease.as_secs() as u32,
+            PORT_MAPPING_DESCRIPTION,
+        )
+        .await?;
+    Ok(GrantedPortMapping { external_port, lease })
+}
+
+async fn add_port_mapping_natpmp(
+    mapping: PortMapping,
+    external_port: u16,
+    lease: Duration,
+) -> Result<GrantedPortMapping, NatError> {
+    let op = match mapping.protocol {
+        MappingProtocol::Tcp => natpmp::OP_MAP_TCP,
+        MappingProtocol::Udp => natpmp::OP_MAP_UDP,
+    };
+    let granted = natpmp::map_port(op, mapping.port, external_port, lease).await?;
+    Ok(GrantedPortMapping { external_port: granted.external_port, lease: granted.lifetime })
+}
+
+/// Resolves the external IP of the NAT-PMP gateway.
+pub(crate) async fn resolve_external_ip_natpmp() -> Result<IpAddr, NatError> {
+    Ok(IpAddr::V4(natpmp::external_address().await?))
+}
+
+/// Returns the local IP the OS routes packets to the gateway from, which is the address the
+/// gateway has to forward to.
+async fn local_ip_towards(gateway: SocketAddr) -> Result<IpAddr, NatError> {
+    let unspecified = match gateway {
+        SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    // connecting a udp socket doesn't send anything, it only selects the route
+    let socket = UdpSocket::bind((unspecified, 0)).await?;
+    socket.connect(gateway).await?;
+    Ok(socket.local_addr()?.ip())
+}
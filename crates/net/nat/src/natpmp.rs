@@ -0,0 +1,210 @@
+//! A minimal NAT-PMP client, see [RFC 6886](https://www.rfc-editor.org/rfc/rfc6886).
+//!
+//! Only the two requests reth needs are supported: resolving the gateway's external address and
+//! mapping a port.
+
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+
+/// The port the gateway listens on for NAT-PMP requests.
+const NATPMP_PORT: u16 = 5351;
+
+/// The NAT-PMP protocol version.
+const VERSION: u8 = 0;
+
+/// The opcode of the external address request.
+const OP_EXTERNAL_ADDRESS: u8 = 0;
+
+/// The opcode of a UDP port mapping request.
+pub(crate) const OP_MAP_UDP: u8 = 1;
+
+/// The opcode of a TCP port mapping request.
+pub(crate) const OP_MAP_TCP: u8 = 2;
+
+/// The timeout of the first attempt, which doubles on every retry (RFC 6886 section 3.1).
+const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// The number of attempts before giving up.
+///
+/// The RFC suggests 9 attempts (~64s), which is far too long for node startup.
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Errors of the NAT-PMP client.
+#[derive(Debug, thiserror::Error)]
+pub enum NatPmpError {
+    /// The default gateway could not be determined.
+    #[error("failed to determine the default gateway")]
+    NoGateway,
+    /// The gateway did not respond.
+    #[error("nat-pmp gateway did not respond")]
+    Timeout,
+    /// The gateway responded with a non-zero result code.
+    #[error("nat-pmp gateway returned result code {0}")]
+    ResultCode(u16),
+    /// The gateway sent a response that doesn't match the request.
+    #[error("unexpected nat-pmp response")]
+    UnexpectedResponse,
+    /// Failed to talk to the gateway.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// A port mapping granted by the gateway.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) struct MappingResponse {
+    pub(crate) external_port: u16,
+    pub(crate) lifetime: Duration,
+}
+
+/// Asks the default gateway for its external address.
+pub(crate) async fn external_address() -> Result<Ipv4Addr, NatPmpError> {
+    let res = request(&[VERSION, OP_EXTERNAL_ADDRESS], OP_EXTERNAL_ADDRESS, 12).await?;
+    Ok(Ipv4Addr::new(res[8], res[9], res[10], res[11]))
+}
+
+/// Asks the default gateway to forward the external port to the internal port.
+///
+/// `op` is either [OP_MAP_UDP] or [OP_MAP_TCP].
+pub(crate) async fn map_port(
+    op: u8,
+    internal_port: u16,
+    external_port: u16,
+    lifetime: Duration,
+) -> Result<MappingResponse, NatPmpError> {
+    let mut req = [0u8; 12];
+    req[0] = VERSION;
+    req[1] = op;
+    req[4..6].copy_from_slice(&internal_port.to_be_bytes());
+    req[6..8].copy_from_slice(&external_port.to_be_bytes());
+    req[8..12].copy_from_slice(&(lifetime.as_secs().min(u32::MAX as u64) as u32).to_be_bytes());
+
+    let res = request(&req, op, 16).await?;
+    if u16::from_be_bytes([res[8], res[9]]) != internal_port {
+        return Err(NatPmpError::UnexpectedResponse)
+    }
+    Ok(MappingResponse {
+        external_port: u16::from_be_bytes([res[10], res[11]]),
+        lifetime: Duration::from_secs(
+            u32::from_be_bytes([res[12], res[13], res[14], res[15]]) as u64
+        ),
+    })
+}
+
+/// Sends the request to the default gateway, retrying with exponential backoff, and returns the
+/// validated response of `len` bytes.
+async fn request(req: &[u8], op: u8, len: usize) -> Result<Vec<u8>, NatPmpError> {
+    let gateway = default_gateway().ok_or(NatPmpError::NoGateway)?;
+    request_from(SocketAddr::from((gateway, NATPMP_PORT)), req, op, len).await
+}
+
+async fn request_from(
+    gateway: SocketAddr,
+    req: &[u8],
+    op: u8,
+    len: usize,
+) -> Result<Vec<u8>, NatPmpError> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.connect(gateway).await?;
+
+    let mut buf = [0u8; 16];
+    let mut timeout = INITIAL_TIMEOUT;
+    for _ in 0..MAX_ATTEMPTS {
+        socket.send(req).await?;
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let read = match tokio::time::timeout_at(deadline, socket.recv(&mut buf)).await {
+                Ok(read) => read?,
+                Err(_) => break,
+            };
+            // ignore datagrams that aren't a response to this request, e.g. a late response to
+            // another request
+            if read < 4 || buf[0] != VERSION || buf[1] != 128 + op {
+                continue
+            }
+            let result = u16::from_be_bytes([buf[2], buf[3]]);
+            if result != 0 {
+                return Err(NatPmpError::ResultCode(result))
+            }
+            if read < len {
+                return Err(NatPmpError::UnexpectedResponse)
+            }
+            return Ok(buf[..len].to_vec())
+        }
+        timeout *= 2;
+    }
+    Err(NatPmpError::Timeout)
+}
+
+/// Returns the IPv4 default gateway of the host.
+#[cfg(target_os = "linux")]
+fn default_gateway() -> Option<Ipv4Addr> {
+    parse_proc_net_route(&std::fs::read_to_string("/proc/net/route").ok()?)
+}
+
+/// Returns the IPv4 default gateway of the host.
+///
+/// Only supported on Linux.
+#[cfg(not(target_os = "linux"))]
+fn default_gateway() -> Option<Ipv4Addr> {
+    None
+}
+
+/// Parses the gateway of the default route from the contents of `/proc/net/route`.
+///
+/// The addresses are the hex encoded network byte order words, printed in host byte order.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_net_route(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let destination = fields.nth(1)?;
+        let gateway = fields.next()?;
+        if destination != "00000000" {
+            return None
+        }
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.to_ne_bytes())).filter(|ip| !ip.is_unspecified())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_default_route() {
+        let routes =
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+                      eth0\t0000A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+                      eth0\t00000000\t0100A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0\n";
+        assert_eq!(parse_proc_net_route(routes), Some(Ipv4Addr::new(192, 168, 0, 1)));
+        assert_eq!(parse_proc_net_route("Iface\tDestination\tGateway\n"), None);
+    }
+
+    #[tokio::test]
+    async fn map_port_against_fake_gateway() {
+        let gateway = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = gateway.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 12];
+            let (_, from) = gateway.recv_from(&mut buf).await.unwrap();
+            assert_eq!(buf[1], OP_MAP_TCP);
+            let mut res = [0u8; 16];
+            res[1] = 128 + OP_MAP_TCP;
+            res[8..10].copy_from_slice(&buf[4..6]);
+            res[10..12].copy_from_slice(&30304u16.to_be_bytes());
+            res[12..16].copy_from_slice(&600u32.to_be_bytes());
+            gateway.send_to(&res, from).await.unwrap();
+        });
+
+        let mut req = [0u8; 12];
+        req[1] = OP_MAP_TCP;
+        req[4..6].copy_from_slice(&30303u16.to_be_bytes());
+        let res = request_from(addr, &req, OP_MAP_TCP, 16).await.unwrap();
+        assert_eq!(u16::from_be_bytes([res[10], res[11]]), 30304);
+        assert_eq!(u32::from_be_bytes([res[12], res[13], res[14], res[15]]), 600);
+    }
+}
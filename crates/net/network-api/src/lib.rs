@@ -12,8 +12,9 @@
 use async_trait::async_trait;
 use reth_eth_wire::DisconnectReason;
 use reth_primitives::{NodeRecord, PeerId};
-use reth_rpc_types::NetworkStatus;
+use reth_rpc_types::{BannedPeer, ConnectedPeer, NetworkStatus, PeerDisconnect, PeerEvent};
 use std::net::SocketAddr;
+use tokio::sync::broadcast;
 
 pub use error::NetworkError;
 pub use reputation::{Reputation, ReputationChangeKind};
@@ -41,6 +42,9 @@ pub trait NetworkInfo: Send + Sync {
 
     /// Returns `true` if the network is undergoing sync.
     fn is_syncing(&self) -> bool;
+
+    /// Returns `true` if the node accepts incoming connections.
+    fn is_listening(&self) -> bool;
 }
 
 /// Provides general purpose information about Peers in the network.
@@ -67,12 +71,20 @@ pub trait Peers: PeersInfo {
         self.add_peer_kind(peer, PeerKind::Trusted, addr);
     }
 
+    /// Adds a static peer to the peer set.
+    fn add_static_peer(&self, peer: PeerId, addr: SocketAddr) {
+        self.add_peer_kind(peer, PeerKind::Static, addr);
+    }
+
     /// Adds a peer to the known peer set, with the given kind.
     fn add_peer_kind(&self, peer: PeerId, kind: PeerKind, addr: SocketAddr);
 
     /// Removes a peer from the peer set that corresponds to given kind.
     fn remove_peer(&self, peer: PeerId, kind: PeerKind);
 
+    /// Bans the peer for the configured ban duration and disconnects it if connected.
+    fn ban_peer(&self, peer: PeerId);
+
     /// Disconnect an existing connection to the given peer.
     fn disconnect_peer(&self, peer: PeerId);
 
@@ -84,6 +96,26 @@ pub trait Peers: PeersInfo {
 
     /// Get the reputation of a peer.
     async fn reputation_by_id(&self, peer_id: PeerId) -> Result<Option<Reputation>, NetworkError>;
+
+    /// Returns the details of all peers there's an active session with.
+    async fn connected_peers(&self) -> Result<Vec<ConnectedPeer>, NetworkError>;
+
+    /// Returns all banned peers.
+    async fn banned_peers(&self) -> Result<Vec<BannedPeer>, NetworkError>;
+
+    /// Lifts the bans of all peers.
+    fn clear_bans(&self);
+
+    /// Returns the most recent disconnects of active sessions and failed handshakes, oldest
+    /// first.
+    async fn recent_disconnects(&self) -> Result<Vec<PeerDisconnect>, NetworkError>;
+
+    /// Returns a new receiver for peer lifecycle events: established and dropped sessions and
+    /// failed handshakes.
+    ///
+    /// Events are only delivered from the moment this is called, a lagging receiver misses the
+    /// oldest events.
+    fn peer_events(&self) -> broadcast::Receiver<PeerEvent>;
 }
 
 /// Represents the kind of peer
@@ -93,5 +125,11 @@ pub enum PeerKind {
     #[default]
     Basic,
     /// Trusted peer.
+    ///
+    /// Trusted peers are never slashed and are connected to even if all slots are occupied.
     Trusted,
+    /// Static peer.
+    ///
+    /// Static peers are redialed whenever the session to them is dropped.
+    Static,
 }
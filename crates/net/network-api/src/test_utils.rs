@@ -4,8 +4,11 @@ use crate::{
 use async_trait::async_trait;
 use reth_eth_wire::{DisconnectReason, ProtocolVersion};
 use reth_primitives::{rpc::Chain::Mainnet, NodeRecord, PeerId};
-use reth_rpc_types::{EthProtocolInfo, NetworkStatus};
+use reth_rpc_types::{
+    BannedPeer, ConnectedPeer, EthProtocolInfo, NetworkStatus, PeerDisconnect, PeerEvent,
+};
 use std::net::{IpAddr, SocketAddr};
+use tokio::sync::broadcast;
 
 /// A type that implements all network trait that does nothing.
 ///
@@ -28,7 +31,10 @@ impl NetworkInfo for NoopNetwork {
                 head: Default::default(),
                 network: 1,
                 genesis: Default::default(),
+                config: None,
             },
+            boot_nodes: Vec::new(),
+            enr: None,
         })
     }
 
@@ -39,6 +45,10 @@ impl NetworkInfo for NoopNetwork {
     fn is_syncing(&self) -> bool {
         false
     }
+
+    fn is_listening(&self) -> bool {
+        true
+    }
 }
 
 impl PeersInfo for NoopNetwork {
@@ -57,6 +67,8 @@ impl Peers for NoopNetwork {
 
     fn remove_peer(&self, _peer: PeerId, _kind: PeerKind) {}
 
+    fn ban_peer(&self, _peer: PeerId) {}
+
     fn disconnect_peer(&self, _peer: PeerId) {}
 
     fn disconnect_peer_with_reason(&self, _peer: PeerId, _reason: DisconnectReason) {}
@@ -66,4 +78,23 @@ impl Peers for NoopNetwork {
     async fn reputation_by_id(&self, _peer_id: PeerId) -> Result<Option<Reputation>, NetworkError> {
         Ok(None)
     }
+
+    async fn connected_peers(&self) -> Result<Vec<ConnectedPeer>, NetworkError> {
+        Ok(Vec::new())
+    }
+
+    async fn banned_peers(&self) -> Result<Vec<BannedPeer>, NetworkError> {
+        Ok(Vec::new())
+    }
+
+    fn clear_bans(&self) {}
+
+    async fn recent_disconnects(&self) -> Result<Vec<PeerDisconnect>, NetworkError> {
+        Ok(Vec::new())
+    }
+
+    fn peer_events(&self) -> broadcast::Receiver<PeerEvent> {
+        // the sender is dropped right away, so the receiver is closed
+        broadcast::channel(1).1
+    }
 }
@@ -1,9 +1,10 @@
 //! Builder support for configuring the entire setup.
 
 use crate::{
-    eth_requests::EthRequestHandler, transactions::TransactionsManager, NetworkHandle,
-    NetworkManager,
+    eth_requests::EthRequestHandler, protocol::RlpxSubProtocolHandler,
+    transactions::TransactionsManager, NetworkHandle, NetworkManager,
 };
+use reth_eth_wire::capability::Protocol;
 use reth_transaction_pool::TransactionPool;
 use tokio::sync::mpsc;
 
@@ -30,6 +31,19 @@ impl<C, Tx, Eth> NetworkBuilder<C, Tx, Eth> {
         (handle, network, transactions, request_handler)
     }
 
+    /// Registers a custom RLPx subprotocol that is multiplexed over the existing peer sessions.
+    ///
+    /// The protocol's capability is announced to all peers and the handler is notified about
+    /// every session with a peer that shares it.
+    pub fn add_rlpx_protocol(
+        mut self,
+        capability: Protocol,
+        handler: impl RlpxSubProtocolHandler,
+    ) -> Self {
+        self.network.add_rlpx_sub_protocol(capability, handler);
+        self
+    }
+
     /// Creates a new [`TransactionsManager`] and wires it to the network.
     pub fn transactions<Pool: TransactionPool>(
         self,
@@ -8,6 +8,7 @@ use crate::{
     NetworkHandle, NetworkManager,
 };
 use reth_discv4::{Discv4Config, Discv4ConfigBuilder, DEFAULT_DISCOVERY_PORT};
+use reth_discv5::Discv5Config;
 use reth_dns_discovery::DnsDiscoveryConfig;
 use reth_ecies::util::pk2id;
 use reth_eth_wire::{HelloMessage, Status};
@@ -40,10 +41,19 @@ pub struct NetworkConfig<C> {
     pub dns_discovery_config: Option<DnsDiscoveryConfig>,
     /// How to set up discovery.
     pub discovery_v4_config: Option<Discv4Config>,
+    /// How to set up discovery over discv5, disabled if `None`.
+    pub discovery_v5_config: Option<Discv5Config>,
+    /// Whether discovered nodes are only dialed once their fork id is known from their ENR.
+    pub enforce_enr_fork_id: bool,
     /// Address to use for discovery
     pub discovery_addr: SocketAddr,
     /// Address to listen for incoming connections
     pub listener_addr: SocketAddr,
+    /// IPv6 address to use for discovery next to `discovery_addr`, if the node runs dual-stack.
+    pub discovery_addr_ipv6: Option<SocketAddr>,
+    /// IPv6 address to listen for incoming connections next to `listener_addr`, if the node runs
+    /// dual-stack.
+    pub listener_addr_ipv6: Option<SocketAddr>,
     /// How to instantiate peer manager.
     pub peers_config: PeersConfig,
     /// How to configure the [SessionManager](crate::session::SessionManager).
@@ -123,12 +133,21 @@ pub struct NetworkConfigBuilder {
     dns_discovery_config: Option<DnsDiscoveryConfig>,
     /// How to set up discovery.
     discovery_v4_builder: Option<Discv4ConfigBuilder>,
+    /// How to set up discovery over discv5.
+    #[serde(skip)]
+    discovery_v5_config: Option<Discv5Config>,
+    /// Whether discovered nodes are only dialed once their fork id is known from their ENR.
+    enforce_enr_fork_id: bool,
     /// All boot nodes to start network discovery with.
     boot_nodes: HashSet<NodeRecord>,
     /// Address to use for discovery
     discovery_addr: Option<SocketAddr>,
     /// Listener for incoming connections
     listener_addr: Option<SocketAddr>,
+    /// IPv6 address to use for discovery, if the node runs dual-stack
+    discovery_addr_ipv6: Option<SocketAddr>,
+    /// IPv6 listener for incoming connections, if the node runs dual-stack
+    listener_addr_ipv6: Option<SocketAddr>,
     /// How to instantiate peer manager.
     peers_config: Option<PeersConfig>,
     /// How to configure the sessions manager
@@ -155,9 +174,13 @@ impl NetworkConfigBuilder {
             secret_key,
             dns_discovery_config: Some(Default::default()),
             discovery_v4_builder: Some(Default::default()),
+            discovery_v5_config: None,
+            enforce_enr_fork_id: false,
             boot_nodes: Default::default(),
             discovery_addr: None,
             listener_addr: None,
+            discovery_addr_ipv6: None,
+            listener_addr_ipv6: None,
             peers_config: None,
             sessions_config: None,
             chain_spec: Arc::new(MAINNET.clone()),
@@ -260,12 +283,57 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the IPv6 socket address the network will listen on next to the
+    /// [listener address](NetworkConfigBuilder::listener_addr), which must be an IPv4 address.
+    ///
+    /// By default, the network only listens on a single address.
+    pub fn listener_addr_ipv6(mut self, listener_addr: SocketAddr) -> Self {
+        self.listener_addr_ipv6 = Some(listener_addr);
+        self
+    }
+
+    /// Sets the IPv6 socket address the discovery network will listen on next to the
+    /// [discovery address](NetworkConfigBuilder::discovery_addr), which must be an IPv4 address.
+    ///
+    /// Discv4 is then run on both addresses and the local ENR advertises both address families.
+    pub fn discovery_addr_ipv6(mut self, discovery_addr: SocketAddr) -> Self {
+        self.discovery_addr_ipv6 = Some(discovery_addr);
+        self
+    }
+
     /// Sets the discv4 config to use.
     pub fn discovery(mut self, builder: Discv4ConfigBuilder) -> Self {
         self.discovery_v4_builder = Some(builder);
         self
     }
 
+    /// Modifies the discv4 config with the given function.
+    ///
+    /// Does nothing if discv4 is disabled.
+    pub fn configure_discovery(mut self, f: impl FnOnce(&mut Discv4ConfigBuilder)) -> Self {
+        if let Some(builder) = self.discovery_v4_builder.as_mut() {
+            f(builder);
+        }
+        self
+    }
+
+    /// Sets the discv5 config to use.
+    ///
+    /// discv5 is disabled by default.
+    pub fn discovery_v5(mut self, config: Discv5Config) -> Self {
+        self.discovery_v5_config = Some(config);
+        self
+    }
+
+    /// Only dial discovered nodes once their fork id is known from their ENR and compatible with
+    /// the local chain.
+    ///
+    /// Nodes whose ENR has no `eth` entry are never dialed.
+    pub fn enforce_enr_fork_id(mut self, enforce: bool) -> Self {
+        self.enforce_enr_fork_id = enforce;
+        self
+    }
+
     /// Sets the dns discovery config to use.
     pub fn dns_discovery(mut self, config: DnsDiscoveryConfig) -> Self {
         self.dns_discovery_config = Some(config);
@@ -286,7 +354,7 @@ impl NetworkConfigBuilder {
 
     /// Disables all discovery.
     pub fn disable_discovery(self) -> Self {
-        self.disable_discv4_discovery().disable_dns_discovery()
+        self.disable_discv4_discovery().disable_discv5_discovery().disable_dns_discovery()
     }
 
     /// Disables all discovery if the given condition is true.
@@ -304,6 +372,12 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Disable the discv5 discovery.
+    pub fn disable_discv5_discovery(mut self) -> Self {
+        self.discovery_v5_config = None;
+        self
+    }
+
     /// Disable the DNS discovery if the given condition is true.
     pub fn disable_dns_discovery_if(self, disable: bool) -> Self {
         if disable {
@@ -330,9 +404,13 @@ impl NetworkConfigBuilder {
             secret_key,
             mut dns_discovery_config,
             discovery_v4_builder,
+            discovery_v5_config,
+            enforce_enr_fork_id,
             boot_nodes,
             discovery_addr,
             listener_addr,
+            discovery_addr_ipv6,
+            listener_addr_ipv6,
             peers_config,
             sessions_config,
             chain_spec,
@@ -364,6 +442,13 @@ impl NetworkConfigBuilder {
         // set a fork filter based on the chain spec and head
         let fork_filter = chain_spec.fork_filter(head);
 
+        // networks denied for sessions are also denied for discovery
+        let peers_config = peers_config.unwrap_or_default();
+        let discovery_v4_config = discovery_v4_builder.map(|mut builder| {
+            builder.deny_networks(peers_config.ban_list.denied_networks().iter().copied());
+            builder.build()
+        });
+
         // If default DNS config is used then we add the known dns network to bootstrap from
         if let Some(dns_networks) =
             dns_discovery_config.as_mut().and_then(|c| c.bootstrap_dns_networks.as_mut())
@@ -380,12 +465,16 @@ impl NetworkConfigBuilder {
             secret_key,
             boot_nodes,
             dns_discovery_config,
-            discovery_v4_config: discovery_v4_builder.map(|builder| builder.build()),
+            discovery_v4_config,
+            discovery_v5_config,
+            enforce_enr_fork_id,
             discovery_addr: discovery_addr.unwrap_or_else(|| {
                 SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, DEFAULT_DISCOVERY_PORT))
             }),
             listener_addr,
-            peers_config: peers_config.unwrap_or_default(),
+            discovery_addr_ipv6,
+            listener_addr_ipv6,
+            peers_config,
             sessions_config: sessions_config.unwrap_or_default(),
             chain_spec,
             block_import: Box::<ProofOfStakeBlockImport>::default(),
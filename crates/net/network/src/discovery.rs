@@ -3,6 +3,7 @@
 use crate::error::{NetworkError, ServiceKind};
 use futures::StreamExt;
 use reth_discv4::{DiscoveryUpdate, Discv4, Discv4Config};
+use reth_discv5::{DiscoveredNode, Discv5, Discv5Config};
 use reth_dns_discovery::{
     DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
 };
@@ -33,6 +34,19 @@ pub struct Discovery {
     discv4_updates: Option<ReceiverStream<DiscoveryUpdate>>,
     /// The handle to the spawned discv4 service
     _discv4_service: Option<JoinHandle<()>>,
+    /// Handler to interact with the discv4 service of the IPv6 address family, if the node runs
+    /// dual-stack.
+    discv4_ipv6: Option<Discv4>,
+    /// All KAD table updates from the IPv6 discv4 service.
+    discv4_ipv6_updates: Option<ReceiverStream<DiscoveryUpdate>>,
+    /// The handle to the spawned IPv6 discv4 service
+    _discv4_ipv6_service: Option<JoinHandle<()>>,
+    /// Handler to interact with the Discovery v5 service
+    discv5: Option<Discv5>,
+    /// Execution layer nodes discovered by the discv5 service.
+    discv5_updates: Option<ReceiverStream<DiscoveredNode>>,
+    /// The handle to the spawned discv5 service
+    _discv5_service: Option<JoinHandle<()>>,
     /// Handler to interact with the DNS discovery service
     _dns_discovery: Option<DnsDiscoveryHandle>,
     /// Updates from the DNS discovery service.
@@ -41,30 +55,68 @@ pub struct Discovery {
     _dns_disc_service: Option<JoinHandle<()>>,
     /// Events buffered until polled.
     queued_events: VecDeque<DiscoveryEvent>,
+    /// Whether nodes are only reported once their [ForkId] is known from their ENR.
+    enforce_enr_fork_id: bool,
 }
 
 impl Discovery {
     /// Spawns the discovery service.
     ///
-    /// This will spawn the [`reth_discv4::Discv4Service`] onto a new task and establish a listener
-    /// channel to receive all discovered nodes.
+    /// This will spawn the [`reth_discv4::Discv4Service`] and, if configured, the
+    /// [`reth_discv5::Discv5Service`] onto new tasks and establish listener channels to receive all
+    /// discovered nodes.
+    ///
+    /// If an IPv6 discovery address is given next to the IPv4 one, a second discv4 service is run
+    /// on it and both advertise the endpoints of both address families in their ENR.
     pub async fn new(
         discovery_addr: SocketAddr,
+        discovery_addr_ipv6: Option<SocketAddr>,
         sk: SecretKey,
         discv4_config: Option<Discv4Config>,
+        discv5_config: Option<Discv5Config>,
         dns_discovery_config: Option<DnsDiscoveryConfig>,
     ) -> Result<Self, NetworkError> {
-        // setup discv4
         let local_enr = NodeRecord::from_secret_key(discovery_addr, &sk);
-        let (discv4, discv4_updates, _discv4_service) = if let Some(disc_config) = discv4_config {
-            let (discv4, mut discv4_service) =
-                Discv4::bind(discovery_addr, local_enr, sk, disc_config).await.map_err(|err| {
-                    NetworkError::from_io_error(err, ServiceKind::Discovery(discovery_addr))
+        let local_enr_ipv6 = discovery_addr_ipv6.map(|addr| NodeRecord::from_secret_key(addr, &sk));
+
+        let (discv4_config, discv4_ipv6_config) = match (discv4_config, local_enr_ipv6) {
+            (Some(mut config), Some(local_enr_ipv6)) => {
+                let mut ipv6_config = config.clone();
+                // each service can only reach the boot nodes of its own address family
+                config.bootstrap_nodes.retain(|node| !node.address.is_ipv6());
+                ipv6_config.bootstrap_nodes.retain(|node| node.address.is_ipv6());
+                // the external IP is only resolved for the IPv4 address family
+                ipv6_config.external_ip_resolver = None;
+                config.dual_stack_record = Some(local_enr_ipv6);
+                ipv6_config.dual_stack_record = Some(local_enr);
+                (Some(config), Some(ipv6_config))
+            }
+            (config, _) => (config, None),
+        };
+
+        // setup discv4
+        let (discv4, discv4_updates, _discv4_service) = match discv4_config {
+            Some(config) => spawn_discv4(discovery_addr, local_enr, sk, config).await?,
+            None => (None, None, None),
+        };
+        let (discv4_ipv6, discv4_ipv6_updates, _discv4_ipv6_service) =
+            match (discv4_ipv6_config, local_enr_ipv6) {
+                (Some(config), Some(local_enr_ipv6)) => {
+                    spawn_discv4(local_enr_ipv6.udp_addr(), local_enr_ipv6, sk, config).await?
+                }
+                _ => (None, None, None),
+            };
+
+        // setup discv5
+        let (discv5, discv5_updates, _discv5_service) = if let Some(config) = discv5_config {
+            let discv5_addr = config.discovery_addr;
+            let (discv5, mut discv5_service) =
+                Discv5::bind(local_enr, sk, config).await.map_err(|err| {
+                    NetworkError::from_io_error(err, ServiceKind::Discovery(discv5_addr))
                 })?;
-            let discv4_updates = discv4_service.update_stream();
-            // spawn the service
-            let _discv4_service = discv4_service.spawn();
-            (Some(discv4), Some(discv4_updates), Some(_discv4_service))
+            let discv5_updates = discv5_service.update_stream();
+            let _discv5_service = discv5_service.spawn();
+            (Some(discv5), Some(discv5_updates), Some(_discv5_service))
         } else {
             (None, None, None)
         };
@@ -88,34 +140,65 @@ impl Discovery {
             discv4,
             discv4_updates,
             _discv4_service,
+            discv4_ipv6,
+            discv4_ipv6_updates,
+            _discv4_ipv6_service,
+            discv5,
+            discv5_updates,
+            _discv5_service,
             discovered_nodes: Default::default(),
             queued_events: Default::default(),
             _dns_disc_service,
             _dns_discovery,
             dns_discovery_updates,
+            enforce_enr_fork_id: false,
         })
     }
 
+    /// Sets whether nodes are only reported once their [ForkId] is known from their ENR.
+    ///
+    /// If enabled, nodes without an `eth` entry in their ENR are never reported, see also
+    /// <https://eips.ethereum.org/EIPS/eip-868>
+    pub fn with_enforce_enr_fork_id(mut self, enforce: bool) -> Self {
+        self.enforce_enr_fork_id = enforce;
+        self
+    }
+
+    /// Returns all running discv4 services.
+    fn discv4_services(&self) -> impl Iterator<Item = &Discv4> + '_ {
+        self.discv4.iter().chain(self.discv4_ipv6.iter())
+    }
+
     /// Updates the `eth:ForkId` field in discv4.
-    #[allow(unused)]
     pub(crate) fn update_fork_id(&self, fork_id: ForkId) {
-        if let Some(discv4) = &self.discv4 {
+        for discv4 in self.discv4_services() {
             discv4.set_eip868_rlp("eth".as_bytes().to_vec(), fork_id)
         }
     }
 
     /// Bans the [`IpAddr`] in the discovery service.
     pub(crate) fn ban_ip(&self, ip: IpAddr) {
-        if let Some(discv4) = &self.discv4 {
+        for discv4 in self.discv4_services() {
             discv4.ban_ip(ip)
         }
+        if let Some(discv5) = &self.discv5 {
+            discv5.ban_ip(ip)
+        }
     }
 
     /// Bans the [`PeerId`] and [`IpAddr`] in the discovery service.
     pub(crate) fn ban(&self, peer_id: PeerId, ip: IpAddr) {
-        if let Some(discv4) = &self.discv4 {
+        for discv4 in self.discv4_services() {
             discv4.ban(peer_id, ip)
         }
+        if let Some(discv5) = &self.discv5 {
+            discv5.ban_ip(ip)
+        }
+    }
+
+    /// Returns the handle to the discv4 service, if discv4 is enabled.
+    pub(crate) fn discv4(&self) -> Option<Discv4> {
+        self.discv4.clone()
     }
 
     /// Returns the id with which the local identifies itself in the network
@@ -124,14 +207,24 @@ impl Discovery {
     }
 
     /// Add a node to the discv4 table.
+    ///
+    /// If the node runs dual-stack, IPv6 nodes are added to the table of the IPv6 service.
     pub(crate) fn add_discv4_node(&self, node: NodeRecord) {
-        if let Some(discv4) = &self.discv4 {
+        let discv4 = match &self.discv4_ipv6 {
+            Some(discv4_ipv6) if node.address.is_ipv6() => Some(discv4_ipv6),
+            _ => self.discv4.as_ref(),
+        };
+        if let Some(discv4) = discv4 {
             discv4.add_node(node);
         }
     }
 
     /// Processes an incoming [NodeRecord] update from a discovery service
     fn on_node_record_update(&mut self, record: NodeRecord, fork_id: Option<ForkId>) {
+        if self.enforce_enr_fork_id && fork_id.is_none() {
+            // the node is reported once its fork id was retrieved
+            return
+        }
         let id = record.id;
         let addr = record.tcp_addr();
         match self.discovered_nodes.entry(id) {
@@ -153,7 +246,11 @@ impl Discovery {
                 self.on_node_record_update(record, None);
             }
             DiscoveryUpdate::EnrForkId(node, fork_id) => {
-                self.queued_events.push_back(DiscoveryEvent::EnrForkId(node.id, fork_id))
+                if self.enforce_enr_fork_id && !self.discovered_nodes.contains_key(&node.id) {
+                    self.on_node_record_update(node, Some(fork_id));
+                } else {
+                    self.queued_events.push_back(DiscoveryEvent::EnrForkId(node.id, fork_id))
+                }
             }
             DiscoveryUpdate::Removed(node) => {
                 self.discovered_nodes.remove(&node);
@@ -183,6 +280,18 @@ impl Discovery {
                 self.on_discv4_update(update)
             }
 
+            while let Some(Poll::Ready(Some(update))) =
+                self.discv4_ipv6_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
+            {
+                self.on_discv4_update(update)
+            }
+
+            while let Some(Poll::Ready(Some(update))) =
+                self.discv5_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
+            {
+                self.on_node_record_update(update.node_record, update.fork_id);
+            }
+
             while let Some(Poll::Ready(Some(update))) =
                 self.dns_discovery_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
             {
@@ -197,6 +306,28 @@ impl Discovery {
     }
 }
 
+/// Binds a discv4 service to the address and spawns it.
+///
+/// Returns the handle to the service, the stream of its updates and its task.
+#[allow(clippy::type_complexity)]
+async fn spawn_discv4(
+    discovery_addr: SocketAddr,
+    local_enr: NodeRecord,
+    sk: SecretKey,
+    config: Discv4Config,
+) -> Result<
+    (Option<Discv4>, Option<ReceiverStream<DiscoveryUpdate>>, Option<JoinHandle<()>>),
+    NetworkError,
+> {
+    let (discv4, mut discv4_service) = Discv4::bind(discovery_addr, local_enr, sk, config)
+        .await
+        .map_err(|err| NetworkError::from_io_error(err, ServiceKind::Discovery(discovery_addr)))?;
+    let discv4_updates = discv4_service.update_stream();
+    // spawn the service
+    let discv4_service = discv4_service.spawn();
+    Ok((Some(discv4), Some(discv4_updates), Some(discv4_service)))
+}
+
 #[cfg(test)]
 impl Discovery {
     /// Returns a Discovery instance that does nothing and is intended for testing purposes.
@@ -215,9 +346,16 @@ impl Discovery {
             discv4_updates: Default::default(),
             queued_events: Default::default(),
             _discv4_service: Default::default(),
+            discv4_ipv6: None,
+            discv4_ipv6_updates: None,
+            _discv4_ipv6_service: None,
+            discv5: None,
+            discv5_updates: None,
+            _discv5_service: None,
             _dns_discovery: None,
             dns_discovery_updates: None,
             _dns_disc_service: None,
+            enforce_enr_fork_id: false,
         }
     }
 }
@@ -234,6 +372,7 @@ pub enum DiscoveryEvent {
 mod tests {
     use super::*;
     use rand::thread_rng;
+    use reth_primitives::{Hardfork, MAINNET};
     use secp256k1::SECP256K1;
     use std::net::{Ipv4Addr, SocketAddrV4};
 
@@ -242,9 +381,47 @@ mod tests {
         let mut rng = thread_rng();
         let (secret_key, _) = SECP256K1.generate_keypair(&mut rng);
         let discovery_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0));
-        let _discovery =
-            Discovery::new(discovery_addr, secret_key, Default::default(), Default::default())
-                .await
-                .unwrap();
+        let _discovery = Discovery::new(
+            discovery_addr,
+            None,
+            secret_key,
+            Default::default(),
+            None,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn enforce_enr_fork_id() {
+        let mut discovery = Discovery::noop().with_enforce_enr_fork_id(true);
+        let record = NodeRecord {
+            address: Ipv4Addr::LOCALHOST.into(),
+            tcp_port: 30303,
+            udp_port: 30303,
+            id: PeerId::random(),
+        };
+        let fork_id = MAINNET.hardfork_fork_id(Hardfork::Frontier).unwrap();
+
+        // nodes without a known fork id are held back
+        discovery.on_discv4_update(DiscoveryUpdate::Added(record));
+        assert!(discovery.queued_events.is_empty());
+
+        discovery.on_discv4_update(DiscoveryUpdate::EnrForkId(record, fork_id));
+        match discovery.queued_events.pop_front() {
+            Some(DiscoveryEvent::Discovered { peer_id, fork_id: Some(id), .. }) => {
+                assert_eq!(peer_id, record.id);
+                assert_eq!(id, fork_id);
+            }
+            _ => panic!("expected discovered node"),
+        }
+
+        // updates of known nodes are forwarded as is
+        discovery.on_discv4_update(DiscoveryUpdate::EnrForkId(record, fork_id));
+        assert!(matches!(
+            discovery.queued_events.pop_front(),
+            Some(DiscoveryEvent::EnrForkId(peer_id, _)) if peer_id == record.id
+        ));
     }
 }
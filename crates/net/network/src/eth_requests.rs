@@ -8,7 +8,8 @@ use reth_eth_wire::{
 };
 use reth_interfaces::p2p::error::RequestResult;
 use reth_primitives::{BlockBody, BlockHashOrNumber, Header, HeadersDirection, PeerId};
-use reth_provider::{BlockProvider, HeaderProvider};
+use reth_provider::{BlockProvider, HeaderProvider, ReceiptProvider};
+use reth_rlp::Encodable;
 use std::{
     borrow::Borrow,
     future::Future,
@@ -26,22 +27,23 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 /// Used to limit lookups.
 const MAX_HEADERS_SERVE: usize = 1024;
 
-/// Maximum number of block headers to serve.
+/// Maximum number of block bodies to serve.
 ///
 /// Used to limit lookups. With 24KB block sizes nowadays, the practical limit will always be
 /// SOFT_RESPONSE_LIMIT.
 const MAX_BODIES_SERVE: usize = 1024;
 
-/// Estimated size in bytes of an RLP encoded body.
-// TODO: check 24kb blocksize assumption
-const APPROX_BODY_SIZE: usize = 24 * 1024;
+/// Maximum number of block receipt lists to serve.
+///
+/// Used to limit lookups.
+const MAX_RECEIPTS_SERVE: usize = 1024;
 
 /// Maximum size of replies to data retrievals.
+///
+/// The response includes the item that exceeds the limit, so the response is never empty if the
+/// first item is available.
 const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
 
-/// Estimated size in bytes of an RLP encoded header.
-const APPROX_HEADER_SIZE: usize = 500;
-
 /// Manages eth related requests on top of the p2p network.
 ///
 /// This can be spawned to another task and is supposed to be run as background service.
@@ -82,13 +84,15 @@ where
         let mut block: BlockHashOrNumber = match start_block {
             BlockHashOrNumber::Hash(start) => start.into(),
             BlockHashOrNumber::Number(num) => {
-                let Some(hash) = self.client.block_hash(num).unwrap_or_default() else { return headers };
+                let Some(hash) = self.client.block_hash(num).unwrap_or_default() else {
+                    return headers
+                };
                 hash.into()
             }
         };
 
         let skip = skip as u64;
-        let mut total_bytes = APPROX_HEADER_SIZE;
+        let mut total_bytes = 0;
 
         for _ in 0..limit {
             if let Some(header) = self.client.header_by_hash_or_number(block).unwrap_or_default() {
@@ -117,14 +121,13 @@ where
                     }
                 }
 
+                total_bytes += header.length();
                 headers.push(header);
 
                 if headers.len() >= MAX_HEADERS_SERVE {
                     break
                 }
 
-                total_bytes += APPROX_HEADER_SIZE;
-
                 if total_bytes > SOFT_RESPONSE_LIMIT {
                     break
                 }
@@ -154,7 +157,7 @@ where
     ) {
         let mut bodies = Vec::new();
 
-        let mut total_bytes = APPROX_BODY_SIZE;
+        let mut total_bytes = 0;
 
         for hash in request.0 {
             if let Some(block) = self.client.block_by_hash(hash).unwrap_or_default() {
@@ -164,10 +167,9 @@ where
                     withdrawals: block.withdrawals,
                 };
 
+                total_bytes += body.length();
                 bodies.push(body);
 
-                total_bytes += APPROX_BODY_SIZE;
-
                 if total_bytes > SOFT_RESPONSE_LIMIT {
                     break
                 }
@@ -182,6 +184,43 @@ where
 
         let _ = response.send(Ok(BlockBodies(bodies)));
     }
+
+    fn on_receipts_request(
+        &mut self,
+        _peer_id: PeerId,
+        request: GetReceipts,
+        response: oneshot::Sender<RequestResult<Receipts>>,
+    ) {
+        let mut receipts = Vec::new();
+
+        let mut total_bytes = 0;
+
+        for hash in request.0 {
+            if let Some(block_receipts) =
+                self.client.receipts_by_block(hash.into()).unwrap_or_default()
+            {
+                let block_receipts = block_receipts
+                    .into_iter()
+                    .map(|receipt| receipt.with_bloom())
+                    .collect::<Vec<_>>();
+
+                total_bytes += block_receipts.iter().map(|receipt| receipt.length()).sum::<usize>();
+                receipts.push(block_receipts);
+
+                if total_bytes > SOFT_RESPONSE_LIMIT {
+                    break
+                }
+
+                if receipts.len() >= MAX_RECEIPTS_SERVE {
+                    break
+                }
+            } else {
+                break
+            }
+        }
+
+        let _ = response.send(Ok(Receipts(receipts)));
+    }
 }
 
 /// An endless future.
@@ -208,7 +247,9 @@ where
                         this.on_bodies_request(peer_id, request, response)
                     }
                     IncomingEthRequest::GetNodeData { .. } => {}
-                    IncomingEthRequest::GetReceipts { .. } => {}
+                    IncomingEthRequest::GetReceipts { peer_id, request, response } => {
+                        this.on_receipts_request(peer_id, request, response)
+                    }
                 },
             }
         }
@@ -11,12 +11,14 @@ use reth_interfaces::p2p::{
 use reth_network_api::ReputationChangeKind;
 use reth_primitives::{BlockBody, Header, PeerId, H256};
 use std::{
+    cmp,
     collections::{HashMap, VecDeque},
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -24,6 +26,16 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 mod client;
 pub use client::FetchClient;
 
+/// The weight of a new sample in the moving averages of [`PeerStats`].
+const PEER_STATS_SMOOTHING: f64 = 0.25;
+
+/// A response is considered slow if it took longer than this factor times the average latency of
+/// the other peers.
+const SLOW_RESPONSE_FACTOR: f64 = 2.0;
+
+/// The number of consecutive slow responses after which a peer is demoted.
+const MAX_SLOW_RESPONSES: u8 = 3;
+
 /// Manages data fetching operations.
 ///
 /// This type is hooked into the staged sync pipeline and delegates download request to available
@@ -76,8 +88,18 @@ impl StateFetcher {
         best_number: u64,
         timeout: Arc<AtomicU64>,
     ) {
-        self.peers
-            .insert(peer_id, Peer { state: PeerState::Idle, best_hash, best_number, timeout });
+        self.peers.insert(
+            peer_id,
+            Peer {
+                state: PeerState::Idle,
+                best_hash,
+                best_number,
+                timeout,
+                request_started: None,
+                headers_stats: Default::default(),
+                bodies_stats: Default::default(),
+            },
+        );
     }
 
     /// Removes the peer from the peer list, after which it is no longer available for future
@@ -117,25 +139,57 @@ impl StateFetcher {
         }
     }
 
-    /// Returns the _next_ idle peer that's ready to accept a request,
-    /// prioritizing those with the lowest timeout/latency.
-    /// Once a peer has been yielded, it will be moved to the end of the map
-    fn next_peer(&mut self) -> Option<PeerId> {
+    /// Returns the _next_ idle peer that's ready to accept a request of the given kind,
+    /// prioritizing the fastest peers.
+    ///
+    /// See [`Peer::cmp_priority`].
+    fn next_peer(&self, kind: RequestKind) -> Option<PeerId> {
         self.peers
             .iter()
             .filter(|(_, peer)| peer.state.is_idle())
-            .min_by_key(|(_, peer)| peer.timeout())
+            .min_by(|(_, a), (_, b)| a.cmp_priority(b, kind))
             .map(|(id, _)| *id)
     }
 
+    /// Returns the average latency of all other peers that responded to requests of the given
+    /// kind.
+    fn average_latency_of_others(&self, peer_id: &PeerId, kind: RequestKind) -> Option<Duration> {
+        let (sum, count) = self
+            .peers
+            .iter()
+            .filter(|(id, _)| *id != peer_id)
+            .filter_map(|(_, peer)| peer.stats(kind).latency)
+            .fold((Duration::ZERO, 0u32), |(sum, count), latency| (sum + latency, count + 1));
+        (count > 0).then(|| sum / count)
+    }
+
+    /// Updates the latency and throughput of the peer for a finished request.
+    ///
+    /// `items` is the number of delivered items, or `None` if the request timed out.
+    fn on_request_finished(&mut self, peer_id: PeerId, kind: RequestKind, items: Option<usize>) {
+        let average_latency = self.average_latency_of_others(&peer_id, kind);
+        let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+        let Some(started) = peer.request_started.take() else { return };
+        let stats = peer.stats_mut(kind);
+        match items {
+            Some(items) => {
+                let elapsed = started.elapsed();
+                let is_slow = average_latency
+                    .map_or(false, |average| elapsed > average.mul_f64(SLOW_RESPONSE_FACTOR));
+                stats.on_response(elapsed, items, is_slow);
+            }
+            None => stats.on_timeout(),
+        }
+    }
+
     /// Returns the next action to return
     fn poll_action(&mut self) -> PollAction {
         // we only check and not pop here since we don't know yet whether a peer is available.
-        if self.queued_requests.is_empty() {
-            return PollAction::NoRequests
-        }
+        let Some(request) = self.queued_requests.front() else { return PollAction::NoRequests };
 
-        let Some(peer_id) = self.next_peer() else { return PollAction::NoPeersAvailable };
+        let Some(peer_id) = self.next_peer(request.kind()) else {
+            return PollAction::NoPeersAvailable
+        };
 
         let request = self.queued_requests.pop_front().expect("not empty; qed");
         let request = self.prepare_block_request(peer_id, request);
@@ -184,6 +238,7 @@ impl StateFetcher {
         // update the peer's state
         if let Some(peer) = self.peers.get_mut(&peer_id) {
             peer.state = req.peer_state();
+            peer.request_started = Some(Instant::now());
         }
 
         match req {
@@ -235,6 +290,10 @@ impl StateFetcher {
             .map(|r| res.is_likely_bad_headers_response(&r.request))
             .unwrap_or_default();
 
+        if !is_likely_bad_response {
+            self.on_request_finished(peer_id, RequestKind::Headers, response_items(&res));
+        }
+
         if let Some(resp) = resp {
             // delegate the response
             let _ = resp.response.send(res.map(|h| (peer_id, h).into()));
@@ -260,6 +319,7 @@ impl StateFetcher {
         peer_id: PeerId,
         res: RequestResult<Vec<BlockBody>>,
     ) -> Option<BlockResponseOutcome> {
+        self.on_request_finished(peer_id, RequestKind::Bodies, response_items(&res));
         if let Some(resp) = self.inflight_bodies_requests.remove(&peer_id) {
             let _ = resp.response.send(res.map(|b| (peer_id, b).into()));
         }
@@ -281,6 +341,17 @@ impl StateFetcher {
     }
 }
 
+/// Returns the number of items of a response, or `None` if the request timed out.
+///
+/// Other errors yield `Some(0)`, so they count as a response without any delivered items.
+fn response_items<T>(res: &RequestResult<Vec<T>>) -> Option<usize> {
+    match res {
+        Ok(items) => Some(items.len()),
+        Err(RequestError::Timeout) => None,
+        Err(_) => Some(0),
+    }
+}
+
 /// The outcome of [`StateFetcher::poll_action`]
 enum PollAction {
     Ready(FetchAction),
@@ -298,12 +369,111 @@ struct Peer {
     best_number: u64,
     /// Tracks the current timeout value we use for the peer.
     timeout: Arc<AtomicU64>,
+    /// When the peer's current request was sent.
+    request_started: Option<Instant>,
+    /// Performance of the peer for `GetBlockHeaders` requests.
+    headers_stats: PeerStats,
+    /// Performance of the peer for `GetBlockBodies` requests.
+    bodies_stats: PeerStats,
 }
 
 impl Peer {
     fn timeout(&self) -> u64 {
         self.timeout.load(Ordering::Relaxed)
     }
+
+    fn stats(&self, kind: RequestKind) -> &PeerStats {
+        match kind {
+            RequestKind::Headers => &self.headers_stats,
+            RequestKind::Bodies => &self.bodies_stats,
+        }
+    }
+
+    fn stats_mut(&mut self, kind: RequestKind) -> &mut PeerStats {
+        match kind {
+            RequestKind::Headers => &mut self.headers_stats,
+            RequestKind::Bodies => &mut self.bodies_stats,
+        }
+    }
+
+    /// Compares the priority of two peers for a request of the given kind, the peer that should be
+    /// preferred is ordered first.
+    ///
+    /// Demoted peers are only used if no other peer is available. Peers that didn't respond to a
+    /// request of this kind yet are tried first, so they get measured. Otherwise the peer with the
+    /// highest throughput is preferred, falling back to the lowest timeout.
+    fn cmp_priority(&self, other: &Self, kind: RequestKind) -> cmp::Ordering {
+        let (stats, other_stats) = (self.stats(kind), other.stats(kind));
+        stats
+            .is_demoted()
+            .cmp(&other_stats.is_demoted())
+            .then_with(|| match (stats.throughput, other_stats.throughput) {
+                (None, None) => cmp::Ordering::Equal,
+                (None, Some(_)) => cmp::Ordering::Less,
+                (Some(_), None) => cmp::Ordering::Greater,
+                (Some(a), Some(b)) => b.total_cmp(&a),
+            })
+            .then_with(|| self.timeout().cmp(&other.timeout()))
+    }
+}
+
+/// Tracks how fast a peer responds to requests of one kind.
+#[derive(Debug, Clone, Copy, Default)]
+struct PeerStats {
+    /// Moving average of the response latency, `None` until the first response.
+    latency: Option<Duration>,
+    /// Moving average of the delivered items per second, `None` until the first response.
+    throughput: Option<f64>,
+    /// The number of consecutive slow or timed out responses.
+    slow_responses: u8,
+}
+
+// === impl PeerStats ===
+
+impl PeerStats {
+    /// Records a response with the given number of items.
+    fn on_response(&mut self, elapsed: Duration, items: usize, is_slow: bool) {
+        self.latency = Some(match self.latency {
+            Some(latency) => {
+                latency.mul_f64(1.0 - PEER_STATS_SMOOTHING) + elapsed.mul_f64(PEER_STATS_SMOOTHING)
+            }
+            None => elapsed,
+        });
+        let throughput = items as f64 / elapsed.as_secs_f64().max(0.001);
+        self.record_throughput(throughput);
+        if is_slow {
+            self.slow_responses = self.slow_responses.saturating_add(1);
+        } else {
+            self.slow_responses = 0;
+        }
+    }
+
+    /// Records a timed out request.
+    fn on_timeout(&mut self) {
+        self.record_throughput(0.0);
+        self.slow_responses = self.slow_responses.saturating_add(1);
+    }
+
+    fn record_throughput(&mut self, throughput: f64) {
+        self.throughput = Some(match self.throughput {
+            Some(current) => {
+                current * (1.0 - PEER_STATS_SMOOTHING) + throughput * PEER_STATS_SMOOTHING
+            }
+            None => throughput,
+        });
+    }
+
+    /// Returns `true` if the peer was consistently slow.
+    fn is_demoted(&self) -> bool {
+        self.slow_responses >= MAX_SLOW_RESPONSES
+    }
+}
+
+/// The kind of a [`DownloadRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RequestKind {
+    Headers,
+    Bodies,
 }
 
 /// Tracks the state of an individual peer
@@ -377,6 +547,14 @@ impl DownloadRequest {
         }
     }
 
+    /// Returns the kind of the request.
+    fn kind(&self) -> RequestKind {
+        match self {
+            DownloadRequest::GetBlockHeaders { .. } => RequestKind::Headers,
+            DownloadRequest::GetBlockBodies { .. } => RequestKind::Bodies,
+        }
+    }
+
     fn get_priority(&self) -> &Priority {
         match self {
             DownloadRequest::GetBlockHeaders { priority, .. } => priority,
@@ -444,17 +622,17 @@ mod tests {
         fetcher.new_active_peer(peer1, H256::random(), 1, Arc::new(AtomicU64::new(1)));
         fetcher.new_active_peer(peer2, H256::random(), 2, Arc::new(AtomicU64::new(1)));
 
-        let first_peer = fetcher.next_peer().unwrap();
+        let first_peer = fetcher.next_peer(RequestKind::Headers).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         // Pending disconnect for first_peer
         fetcher.on_pending_disconnect(&first_peer);
         // first_peer now isn't idle, so we should get other peer
-        let second_peer = fetcher.next_peer().unwrap();
+        let second_peer = fetcher.next_peer(RequestKind::Headers).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         assert_ne!(first_peer, second_peer);
         // without idle peers, returns None
         fetcher.on_pending_disconnect(&second_peer);
-        assert_eq!(fetcher.next_peer(), None);
+        assert_eq!(fetcher.next_peer(RequestKind::Headers), None);
     }
 
     #[tokio::test]
@@ -473,13 +651,56 @@ mod tests {
         fetcher.new_active_peer(peer3, H256::random(), 3, Arc::new(AtomicU64::new(50)));
 
         // Must always get peer1 (lowest timeout)
-        assert_eq!(fetcher.next_peer(), Some(peer1));
-        assert_eq!(fetcher.next_peer(), Some(peer1));
+        assert_eq!(fetcher.next_peer(RequestKind::Headers), Some(peer1));
+        assert_eq!(fetcher.next_peer(RequestKind::Headers), Some(peer1));
         // peer2's timeout changes below peer1's
         peer2_timeout.store(10, Ordering::Relaxed);
         // Then we get peer 2 always (now lowest)
-        assert_eq!(fetcher.next_peer(), Some(peer2));
-        assert_eq!(fetcher.next_peer(), Some(peer2));
+        assert_eq!(fetcher.next_peer(RequestKind::Headers), Some(peer2));
+        assert_eq!(fetcher.next_peer(RequestKind::Headers), Some(peer2));
+    }
+
+    #[tokio::test]
+    async fn test_latency_aware_peer_selection() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let fast = H512::random();
+        let slow = H512::random();
+        fetcher.new_active_peer(fast, H256::random(), 1, Arc::new(AtomicU64::new(100)));
+        fetcher.new_active_peer(slow, H256::random(), 1, Arc::new(AtomicU64::new(1)));
+
+        let respond = |fetcher: &mut StateFetcher, peer_id: PeerId, elapsed: Duration| {
+            fetcher.peers.get_mut(&peer_id).unwrap().request_started =
+                Some(Instant::now() - elapsed);
+            fetcher.on_request_finished(peer_id, RequestKind::Bodies, Some(100));
+        };
+
+        // without measurements the peer with the lowest timeout is preferred
+        assert_eq!(fetcher.next_peer(RequestKind::Bodies), Some(slow));
+
+        // peers that were not measured yet are tried first
+        respond(&mut fetcher, fast, Duration::from_millis(100));
+        assert_eq!(fetcher.next_peer(RequestKind::Bodies), Some(slow));
+
+        respond(&mut fetcher, slow, Duration::from_secs(1));
+        assert_eq!(fetcher.next_peer(RequestKind::Bodies), Some(fast));
+        // headers and bodies are measured separately
+        assert_eq!(fetcher.next_peer(RequestKind::Headers), Some(slow));
+
+        // consistently slow peers are demoted
+        for _ in 1..MAX_SLOW_RESPONSES {
+            assert!(!fetcher.peers[&slow].bodies_stats.is_demoted());
+            respond(&mut fetcher, slow, Duration::from_secs(1));
+        }
+        assert!(fetcher.peers[&slow].bodies_stats.is_demoted());
+
+        // demoted peers are only used if no other peer is available
+        fetcher.peers.get_mut(&fast).unwrap().state = PeerState::GetBlockBodies;
+        assert_eq!(fetcher.next_peer(RequestKind::Bodies), Some(slow));
+
+        // a fast response lifts the demotion
+        respond(&mut fetcher, slow, Duration::from_millis(100));
+        assert!(!fetcher.peers[&slow].bodies_stats.is_demoted());
     }
 
     #[tokio::test]
@@ -132,6 +132,7 @@ mod message;
 mod metrics;
 mod network;
 pub mod peers;
+pub mod protocol;
 mod session;
 mod state;
 mod swarm;
@@ -141,10 +142,13 @@ pub use builder::NetworkBuilder;
 pub use config::{NetworkConfig, NetworkConfigBuilder};
 pub use discovery::Discovery;
 pub use fetch::FetchClient;
-pub use manager::{NetworkEvent, NetworkManager};
+pub use manager::{NetworkEvent, NetworkManager, ReceivedMessage};
 pub use message::PeerRequest;
 pub use network::NetworkHandle;
-pub use peers::PeersConfig;
+pub use peers::{
+    BackoffCurve, PeerBackoffDurations, PeersConfig, PersistedBan, PersistedIpBan, PersistedPeer,
+    PersistedPeers,
+};
 pub use session::{PeerInfo, SessionsConfig};
 
-pub use reth_eth_wire::DisconnectReason;
+pub use reth_eth_wire::{DisconnectReason, HelloMessage};
@@ -1,6 +1,7 @@
 //! Contains connection-oriented interfaces.
 
 use futures::{ready, Stream};
+use reth_net_common::socket;
 
 use std::{
     io,
@@ -22,6 +23,8 @@ pub struct ConnectionListener {
     /// The active tcp listener for incoming connections.
     #[pin]
     incoming: TcpListenerStream,
+    /// The local address and listener of the IPv6 address family, if the node runs dual-stack.
+    ipv6: Option<(SocketAddr, TcpListenerStream)>,
 }
 
 impl ConnectionListener {
@@ -32,29 +35,65 @@ impl ConnectionListener {
         Ok(Self::new(listener, local_addr))
     }
 
+    /// Creates a new [`TcpListener`] for each address family that listens for incoming
+    /// connections.
+    ///
+    /// The IPv6 listener only accepts IPv6 connections, so both listeners can use the same port.
+    pub async fn bind_dual_stack(addr: SocketAddr, addr_ipv6: SocketAddr) -> io::Result<Self> {
+        if !addr.is_ipv4() || !addr_ipv6.is_ipv6() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected an IPv4 and an IPv6 address, got {addr} and {addr_ipv6}"),
+            ))
+        }
+        let mut this = Self::bind(addr).await?;
+        let listener = socket::bind_tcp_listener(addr_ipv6)?;
+        let local_addr = listener.local_addr()?;
+        this.ipv6 = Some((local_addr, TcpListenerStream { inner: listener }));
+        Ok(this)
+    }
+
     /// Creates a new connection listener stream.
     pub(crate) fn new(listener: TcpListener, local_address: SocketAddr) -> Self {
-        Self { local_address, incoming: TcpListenerStream { inner: listener } }
+        Self { local_address, incoming: TcpListenerStream { inner: listener }, ipv6: None }
     }
 
     /// Polls the type to make progress.
     pub fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<ListenerEvent> {
         let this = self.project();
-        match ready!(this.incoming.poll_next(cx)) {
-            Some(Ok((stream, remote_addr))) => {
-                Poll::Ready(ListenerEvent::Incoming { stream, remote_addr })
-            }
-            Some(Err(err)) => Poll::Ready(ListenerEvent::Error(err)),
-            None => {
-                Poll::Ready(ListenerEvent::ListenerClosed { local_address: *this.local_address })
-            }
+        if let Poll::Ready(event) = poll_incoming(this.incoming, *this.local_address, cx) {
+            return Poll::Ready(event)
         }
+        if let Some((local_address, incoming)) = this.ipv6 {
+            return poll_incoming(Pin::new(incoming), *local_address, cx)
+        }
+        Poll::Pending
     }
 
     /// Returns the socket address this listener listens on.
     pub fn local_address(&self) -> SocketAddr {
         self.local_address
     }
+
+    /// Returns the socket address of the IPv6 listener, if the node runs dual-stack.
+    pub fn local_address_ipv6(&self) -> Option<SocketAddr> {
+        self.ipv6.as_ref().map(|(addr, _)| *addr)
+    }
+}
+
+/// Polls the next event of a listener that listens on the given address.
+fn poll_incoming(
+    incoming: Pin<&mut TcpListenerStream>,
+    local_address: SocketAddr,
+    cx: &mut Context<'_>,
+) -> Poll<ListenerEvent> {
+    match ready!(incoming.poll_next(cx)) {
+        Some(Ok((stream, remote_addr))) => {
+            Poll::Ready(ListenerEvent::Incoming { stream, remote_addr })
+        }
+        Some(Err(err)) => Poll::Ready(ListenerEvent::Error(err)),
+        None => Poll::Ready(ListenerEvent::ListenerClosed { local_address }),
+    }
 }
 
 /// Event type produced by the [`TcpListenerStream`].
@@ -102,7 +141,7 @@ impl Stream for TcpListenerStream {
 mod tests {
     use super::*;
     use futures::pin_mut;
-    use std::net::{Ipv4Addr, SocketAddrV4};
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4};
     use tokio::macros::support::poll_fn;
 
     #[tokio::test(flavor = "multi_thread")]
@@ -125,4 +164,33 @@ mod tests {
 
         let _ = TcpStream::connect(local_addr).await.unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dual_stack_listener() {
+        let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0));
+        let Ok(listener) =
+            ConnectionListener::bind_dual_stack(addr, (Ipv6Addr::LOCALHOST, 0).into()).await
+        else {
+            // the host doesn't support IPv6
+            return
+        };
+        let local_addr = listener.local_address();
+        let local_addr_ipv6 = listener.local_address_ipv6().unwrap();
+        assert!(local_addr_ipv6.is_ipv6());
+
+        tokio::task::spawn(async move {
+            pin_mut!(listener);
+            for _ in 0..2 {
+                match poll_fn(|cx| listener.as_mut().poll(cx)).await {
+                    ListenerEvent::Incoming { .. } => {}
+                    _ => {
+                        panic!("unexpected event")
+                    }
+                }
+            }
+        });
+
+        let _ = TcpStream::connect(local_addr).await.unwrap();
+        let _ = TcpStream::connect(local_addr_ipv6).await.unwrap();
+    }
 }
@@ -25,8 +25,11 @@ use crate::{
     message::{NewBlockMessage, PeerMessage, PeerRequest, PeerRequestSender},
     metrics::{DisconnectMetrics, NetworkMetrics},
     network::{NetworkHandle, NetworkHandleMessage},
-    peers::{PeersHandle, PeersManager},
-    session::SessionManager,
+    peers::{
+        unix_timestamp, PeersHandle, PeersManager, PersistedBan, PersistedIpBan, PersistedPeer,
+    },
+    protocol::RlpxSubProtocolHandler,
+    session::{PendingSessionHandshakeError, SessionManager},
     state::NetworkState,
     swarm::{NetworkConnectionState, Swarm, SwarmEvent},
     transactions::NetworkTransactionEvent,
@@ -35,15 +38,19 @@ use crate::{
 use futures::{Future, StreamExt};
 use parking_lot::Mutex;
 use reth_eth_wire::{
-    capability::{Capabilities, CapabilityMessage},
-    DisconnectReason, EthVersion, Status,
+    capability::{Capabilities, CapabilityMessage, Protocol},
+    DisconnectReason, EthMessageID, EthVersion, Status,
 };
 use reth_net_common::bandwidth_meter::BandwidthMeter;
 use reth_network_api::ReputationChangeKind;
-use reth_primitives::{listener::EventListeners, NodeRecord, PeerId, H256};
+use reth_primitives::{listener::EventListeners, ChainSpec, NodeRecord, PeerId, H256};
 use reth_provider::BlockProvider;
-use reth_rpc_types::{EthProtocolInfo, NetworkStatus};
+use reth_rpc_types::{
+    ChainConfig, DisconnectInitiator, EthProtocolInfo, NetworkStatus, PeerDisconnect, PeerEvent,
+    PeerEventKind,
+};
 use std::{
+    collections::VecDeque,
     net::SocketAddr,
     pin::Pin,
     sync::{
@@ -52,7 +59,7 @@ use std::{
     },
     task::{Context, Poll},
 };
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{debug, error, info, trace, warn};
 /// Manages the _entire_ state of the network.
@@ -95,6 +102,10 @@ pub struct NetworkManager<C> {
     block_import: Box<dyn BlockImport>,
     /// All listeners for high level network events.
     event_listeners: EventListeners<NetworkEvent>,
+    /// All listeners for the `eth` messages received from peers.
+    ///
+    /// Messages are dropped for listeners that fall behind.
+    message_listeners: Vec<mpsc::Sender<ReceivedMessage>>,
     /// Sender half to send events to the
     /// [`TransactionsManager`](crate::transactions::TransactionsManager) task, if configured.
     to_transactions_manager: Option<mpsc::UnboundedSender<NetworkTransactionEvent>>,
@@ -108,10 +119,27 @@ pub struct NetworkManager<C> {
     num_active_peers: Arc<AtomicUsize>,
     /// Metrics for the Network
     metrics: NetworkMetrics,
-    /// Disconnect metrics for the Network
-    disconnect_metrics: DisconnectMetrics,
+    /// Metrics of the disconnects we initiated, by reason
+    local_disconnect_metrics: DisconnectMetrics,
+    /// Metrics of the disconnects the peers initiated, by reason
+    remote_disconnect_metrics: DisconnectMetrics,
+    /// The most recent disconnects, queried via `admin_recentDisconnects`.
+    recent_disconnects: VecDeque<PeerDisconnect>,
+    /// The bootnodes the network was configured with.
+    boot_nodes: Vec<NodeRecord>,
+    /// The chain spec of the network.
+    chain_spec: Arc<ChainSpec>,
+    /// Sender half of the channel peer lifecycle events are broadcast on, subscribed to via the
+    /// [`NetworkHandle`].
+    peer_events: broadcast::Sender<PeerEvent>,
 }
 
+/// The capacity of the channel peer lifecycle events are broadcast on.
+const PEER_EVENTS_CHANNEL_CAPACITY: usize = 256;
+
+/// The number of recent disconnects that are kept.
+const MAX_RECENT_DISCONNECTS: usize = 256;
+
 // === impl NetworkManager ===
 impl<C> NetworkManager<C> {
     /// Sets the dedicated channel for events indented for the
@@ -126,6 +154,17 @@ impl<C> NetworkManager<C> {
         self.to_eth_request_handler = Some(tx);
     }
 
+    /// Registers a custom RLPx subprotocol that is multiplexed over the sessions next to `eth`.
+    ///
+    /// See also [`NetworkBuilder::add_rlpx_protocol`](crate::NetworkBuilder::add_rlpx_protocol).
+    pub fn add_rlpx_sub_protocol(
+        &mut self,
+        protocol: Protocol,
+        handler: impl RlpxSubProtocolHandler,
+    ) {
+        self.swarm.sessions_mut().add_rlpx_sub_protocol(protocol, Arc::new(handler));
+    }
+
     /// Returns the [`NetworkHandle`] that can be cloned and shared.
     ///
     /// The [`NetworkHandle`] can be used to interact with this [`NetworkManager`]
@@ -153,8 +192,12 @@ where
             client,
             secret_key,
             mut discovery_v4_config,
+            mut discovery_v5_config,
+            enforce_enr_fork_id,
             discovery_addr,
             listener_addr,
+            discovery_addr_ipv6,
+            listener_addr_ipv6,
             peers_config,
             sessions_config,
             chain_spec,
@@ -169,12 +212,16 @@ where
             ..
         } = config;
 
-        let peers_manager = PeersManager::new(peers_config);
+        let mut peers_manager = PeersManager::new(peers_config);
+        peers_manager.set_local_fork_id(status.forkid);
         let peers_handle = peers_manager.handle();
 
-        let incoming = ConnectionListener::bind(listener_addr).await.map_err(|err| {
-            NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr))
-        })?;
+        let incoming = if let Some(listener_addr_ipv6) = listener_addr_ipv6 {
+            ConnectionListener::bind_dual_stack(listener_addr, listener_addr_ipv6).await
+        } else {
+            ConnectionListener::bind(listener_addr).await
+        }
+        .map_err(|err| NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr)))?;
         let listener_address = Arc::new(Mutex::new(incoming.local_address()));
 
         discovery_v4_config = discovery_v4_config.map(|mut disc_config| {
@@ -184,11 +231,24 @@ where
             disc_config
         });
 
-        let discovery =
-            Discovery::new(discovery_addr, secret_key, discovery_v4_config, dns_discovery_config)
-                .await?;
+        discovery_v5_config = discovery_v5_config.map(|mut disc_config| {
+            disc_config.add_eip868_pair("eth", status.forkid);
+            disc_config
+        });
+
+        let discovery = Discovery::new(
+            discovery_addr,
+            discovery_addr_ipv6,
+            secret_key,
+            discovery_v4_config,
+            discovery_v5_config,
+            dns_discovery_config,
+        )
+        .await?
+        .with_enforce_enr_fork_id(enforce_enr_fork_id);
         // need to retrieve the addr here since provided port could be `0`
         let local_peer_id = discovery.local_id();
+        let discv4 = discovery.discv4();
 
         let num_active_peers = Arc::new(AtomicUsize::new(0));
         let bandwidth_meter: BandwidthMeter = BandwidthMeter::default();
@@ -214,6 +274,7 @@ where
         let swarm = Swarm::new(incoming, sessions, state, NetworkConnectionState::default());
 
         let (to_manager_tx, from_handle_rx) = mpsc::unbounded_channel();
+        let (peer_events, _) = broadcast::channel(PEER_EVENTS_CHANNEL_CAPACITY);
 
         let handle = NetworkHandle::new(
             Arc::clone(&num_active_peers),
@@ -224,6 +285,8 @@ where
             network_mode,
             bandwidth_meter,
             Arc::new(AtomicU64::new(chain_spec.chain.id())),
+            discv4,
+            peer_events.clone(),
         );
 
         Ok(Self {
@@ -232,11 +295,20 @@ where
             from_handle_rx: UnboundedReceiverStream::new(from_handle_rx),
             block_import,
             event_listeners: Default::default(),
+            message_listeners: Vec::new(),
             to_transactions_manager: None,
             to_eth_request_handler: None,
             num_active_peers,
             metrics: Default::default(),
-            disconnect_metrics: Default::default(),
+            local_disconnect_metrics: DisconnectMetrics::new_with_labels(&[("initiator", "local")]),
+            remote_disconnect_metrics: DisconnectMetrics::new_with_labels(&[(
+                "initiator",
+                "remote",
+            )]),
+            recent_disconnects: VecDeque::with_capacity(MAX_RECENT_DISCONNECTS),
+            boot_nodes: boot_nodes.into_iter().collect(),
+            chain_spec,
+            peer_events,
         })
     }
 
@@ -285,6 +357,12 @@ where
         self.swarm.listener().local_address()
     }
 
+    /// Returns the IPv6 [`SocketAddr`] that listens for incoming connections, if the network runs
+    /// dual-stack.
+    pub fn local_addr_ipv6(&self) -> Option<SocketAddr> {
+        self.swarm.listener().local_address_ipv6()
+    }
+
     /// Returns the configured genesis hash
     pub fn genesis_hash(&self) -> H256 {
         self.swarm.state().genesis_hash()
@@ -305,6 +383,23 @@ where
         self.swarm.state().peers().iter_peers()
     }
 
+    /// Returns the peers that should be persisted across restarts, see also [`PersistedPeer`].
+    pub fn persisted_peers(&self) -> impl Iterator<Item = PersistedPeer> + '_ {
+        self.swarm.state().peers().persisted_peers()
+    }
+
+    /// Returns the banned peers that should stay banned across restarts, see also
+    /// [`PersistedBan`].
+    pub fn persisted_bans(&self) -> impl Iterator<Item = PersistedBan> + '_ {
+        self.swarm.state().peers().persisted_bans()
+    }
+
+    /// Returns the banned IPs that should stay banned across restarts, see also
+    /// [`PersistedIpBan`].
+    pub fn persisted_ip_bans(&self) -> impl Iterator<Item = PersistedIpBan> + '_ {
+        self.swarm.state().peers().persisted_ip_bans()
+    }
+
     /// Returns a new [`PeersHandle`] that can be cloned and shared.
     ///
     /// The [`PeersHandle`] can be used to interact with the network's peer set.
@@ -333,7 +428,10 @@ where
                 head: status.blockhash,
                 network: status.chain.id(),
                 genesis: status.genesis,
+                config: Some(ChainConfig::from(&*self.chain_spec)),
             },
+            boot_nodes: self.boot_nodes.clone(),
+            enr: None,
         }
     }
 
@@ -450,8 +548,27 @@ where
         }
     }
 
+    /// Sends the message to all message listeners, dropping it for listeners that are full and
+    /// removing the closed ones.
+    fn notify_message_listeners(&mut self, msg: ReceivedMessage) {
+        self.message_listeners.retain(|listener| match listener.try_send(msg) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                trace!(target : "net", ?msg, "message listener is full, dropping message");
+                true
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        });
+    }
+
     /// Handles a received Message from the peer's session.
     fn on_peer_message(&mut self, peer_id: PeerId, msg: PeerMessage) {
+        if !self.message_listeners.is_empty() {
+            if let Some(message_id) = msg.message_id() {
+                self.notify_message_listeners(ReceivedMessage { peer_id, message_id });
+            }
+        }
+
         match msg {
             PeerMessage::NewBlockHashes(hashes) => {
                 self.within_pow_or_disconnect(peer_id, |this| {
@@ -490,12 +607,81 @@ where
         }
     }
 
+    /// Broadcasts a peer lifecycle event to all `admin_peerEvents` subscribers.
+    fn notify_peer_event(
+        &self,
+        kind: PeerEventKind,
+        peer: Option<PeerId>,
+        error: Option<String>,
+        reason: Option<DisconnectReason>,
+        remote: SocketAddr,
+    ) {
+        let event = PeerEvent {
+            kind,
+            peer,
+            error,
+            code: reason.map(|reason| reason as u8),
+            reason: reason.map(|reason| reason.to_string()),
+            remote,
+        };
+        // there may be no subscribers
+        let _ = self.peer_events.send(event);
+    }
+
+    /// Records a closed connection in the recent disconnects, and the disconnect reason, if any,
+    /// in the metrics.
+    fn on_disconnect(
+        &mut self,
+        peer: Option<PeerId>,
+        remote: SocketAddr,
+        initiator: DisconnectInitiator,
+        reason: Option<DisconnectReason>,
+        error: Option<String>,
+    ) {
+        if let Some(reason) = reason {
+            match initiator {
+                DisconnectInitiator::Local => self.local_disconnect_metrics.increment(reason),
+                DisconnectInitiator::Remote => self.remote_disconnect_metrics.increment(reason),
+            }
+        }
+        if self.recent_disconnects.len() == MAX_RECENT_DISCONNECTS {
+            self.recent_disconnects.pop_front();
+        }
+        self.recent_disconnects.push_back(PeerDisconnect {
+            peer,
+            remote,
+            initiator,
+            code: reason.map(|reason| reason as u8),
+            reason: reason.map(|reason| reason.to_string()),
+            error,
+            timestamp: unix_timestamp(),
+        });
+    }
+
+    /// Records a pending session that was closed before it was authenticated.
+    ///
+    /// Without an error, the session was dropped by the local node.
+    fn on_pending_disconnect(
+        &mut self,
+        peer: Option<PeerId>,
+        remote: SocketAddr,
+        error: Option<&PendingSessionHandshakeError>,
+    ) {
+        let reason = error.and_then(|err| err.as_disconnected());
+        let initiator =
+            if reason.is_some() { DisconnectInitiator::Remote } else { DisconnectInitiator::Local };
+        self.on_disconnect(peer, remote, initiator, reason, error.map(ToString::to_string));
+    }
+
     /// Handler for received messages from a handle
     fn on_handle_message(&mut self, msg: NetworkHandleMessage) {
         match msg {
             NetworkHandleMessage::EventListener(tx) => {
                 self.event_listeners.push_listener(tx);
             }
+            NetworkHandleMessage::MessageListener(tx) => {
+                self.message_listeners.push(tx);
+            }
             NetworkHandleMessage::AnnounceBlock(block, hash) => {
                 if self.handle.mode().is_stake() {
                     error!(target : "net", "Block propagation is not supported in POS - [EIP-3675](https://eips.ethereum.org/EIPS/eip-3675#devp2p)");
@@ -523,6 +709,9 @@ where
             NetworkHandleMessage::RemovePeer(peer_id, kind) => {
                 self.swarm.state_mut().remove_peer(peer_id, kind);
             }
+            NetworkHandleMessage::BanPeer(peer_id) => {
+                self.swarm.state_mut().peers_mut().ban_peer_and_disconnect(peer_id);
+            }
             NetworkHandleMessage::DisconnectPeer(peer_id, reason) => {
                 self.swarm.sessions_mut().disconnect(peer_id, reason);
             }
@@ -543,6 +732,9 @@ where
             NetworkHandleMessage::GetReputationById(peer_id, tx) => {
                 let _ = tx.send(self.swarm.state_mut().peers().get_reputation(&peer_id));
             }
+            NetworkHandleMessage::GetRecentDisconnects(tx) => {
+                let _ = tx.send(self.recent_disconnects.iter().cloned().collect());
+            }
             NetworkHandleMessage::FetchClient(tx) => {
                 let _ = tx.send(self.fetch_client());
             }
@@ -622,10 +814,22 @@ where
                         }
                         SwarmEvent::TcpListenerClosed { remote_addr } => {
                             trace!(target : "net", ?remote_addr, "TCP listener closed.");
+                            if remote_addr == this.local_addr() {
+                                this.handle.set_listening(false);
+                            }
                         }
                         SwarmEvent::TcpListenerError(err) => {
                             trace!(target : "net", ?err, "TCP connection error.");
                         }
+                        SwarmEvent::IncomingConnectionRejected { remote_addr, reason } => {
+                            this.on_disconnect(
+                                None,
+                                remote_addr,
+                                DisconnectInitiator::Local,
+                                Some(reason),
+                                None,
+                            );
+                        }
                         SwarmEvent::IncomingTcpConnection { remote_addr, session_id } => {
                             trace!(target : "net", ?session_id, ?remote_addr, "Incoming connection");
                             this.metrics.total_incoming_connections.increment(1);
@@ -668,6 +872,11 @@ where
                                     .state_mut()
                                     .peers_mut()
                                     .on_incoming_session_established(peer_id, remote_addr);
+                            } else {
+                                this.swarm
+                                    .state_mut()
+                                    .peers_mut()
+                                    .on_outgoing_session_established(&peer_id);
                             }
                             this.event_listeners.notify(NetworkEvent::SessionEstablished {
                                 peer_id,
@@ -678,6 +887,13 @@ where
                                 status,
                                 messages,
                             });
+                            this.notify_peer_event(
+                                PeerEventKind::Add,
+                                Some(peer_id),
+                                None,
+                                None,
+                                remote_addr,
+                            );
                         }
                         SwarmEvent::PeerAdded(peer_id) => {
                             trace!(target: "net", ?peer_id, "Peer added");
@@ -693,7 +909,12 @@ where
                                 .tracked_peers
                                 .set(this.swarm.state().peers().num_known_peers() as f64);
                         }
-                        SwarmEvent::SessionClosed { peer_id, remote_addr, error } => {
+                        SwarmEvent::SessionClosed {
+                            peer_id,
+                            remote_addr,
+                            error,
+                            reason: local,
+                        } => {
                             let total_active =
                                 this.num_active_peers.fetch_sub(1, Ordering::Relaxed) - 1;
                             this.metrics.connected_peers.set(total_active as f64);
@@ -731,13 +952,34 @@ where
                             this.metrics
                                 .outgoing_connections
                                 .set(this.swarm.state().peers().num_outbound_connections() as f64);
-                            if let Some(reason) = reason {
-                                this.disconnect_metrics.increment(reason);
-                            }
+                            let (initiator, disconnect_reason) = match (reason, local) {
+                                (Some(reason), _) => (DisconnectInitiator::Remote, Some(reason)),
+                                (None, Some(local)) => (DisconnectInitiator::Local, Some(local)),
+                                // the session was closed because of an error, or the peer closed
+                                // the connection without sending a disconnect
+                                (None, None) if error.is_some() => {
+                                    (DisconnectInitiator::Local, None)
+                                }
+                                (None, None) => (DisconnectInitiator::Remote, None),
+                            };
+                            this.on_disconnect(
+                                Some(peer_id),
+                                remote_addr,
+                                initiator,
+                                disconnect_reason,
+                                error.as_ref().map(ToString::to_string),
+                            );
                             this.metrics.backed_off_peers.set(
                                 this.swarm.state().peers().num_backed_off_peers().saturating_sub(1)
                                     as f64,
                             );
+                            this.notify_peer_event(
+                                PeerEventKind::Drop,
+                                Some(peer_id),
+                                error.as_ref().map(ToString::to_string),
+                                reason.or(local),
+                                remote_addr,
+                            );
                             this.event_listeners
                                 .notify(NetworkEvent::SessionClosed { peer_id, reason });
                         }
@@ -755,15 +997,22 @@ where
                                     .peers_mut()
                                     .on_incoming_pending_session_dropped(remote_addr, err);
                                 this.metrics.pending_session_failures.increment(1);
-                                if let Some(reason) = err.as_disconnected() {
-                                    this.disconnect_metrics.increment(reason);
-                                }
+                                this.notify_peer_event(
+                                    PeerEventKind::HandshakeFailed,
+                                    None,
+                                    Some(err.to_string()),
+                                    err.as_disconnected(),
+                                    remote_addr,
+                                );
                             } else {
                                 this.swarm
                                     .state_mut()
                                     .peers_mut()
-                                    .on_incoming_pending_session_gracefully_closed();
+                                    .on_incoming_pending_session_gracefully_closed(
+                                        remote_addr.ip(),
+                                    );
                             }
+                            this.on_pending_disconnect(None, remote_addr, error.as_ref());
                             this.metrics.closed_sessions.increment(1);
                             this.metrics
                                 .incoming_connections
@@ -793,15 +1042,20 @@ where
                                     err,
                                 );
                                 this.metrics.pending_session_failures.increment(1);
-                                if let Some(reason) = err.as_disconnected() {
-                                    this.disconnect_metrics.increment(reason);
-                                }
+                                this.notify_peer_event(
+                                    PeerEventKind::HandshakeFailed,
+                                    Some(peer_id),
+                                    Some(err.to_string()),
+                                    err.as_disconnected(),
+                                    remote_addr,
+                                );
                             } else {
                                 this.swarm
                                     .state_mut()
                                     .peers_mut()
                                     .on_pending_session_gracefully_closed(&peer_id);
                             }
+                            this.on_pending_disconnect(Some(peer_id), remote_addr, error.as_ref());
                             this.metrics.closed_sessions.increment(1);
                             this.metrics
                                 .outgoing_connections
@@ -899,3 +1153,14 @@ pub enum NetworkEvent {
     /// Event emitted when a new peer is removed
     PeerRemoved(PeerId),
 }
+
+/// An `eth` message received from the peer of an active session.
+///
+/// Responses to requests issued by this node are not included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReceivedMessage {
+    /// The identifier of the peer that sent the message.
+    pub peer_id: PeerId,
+    /// The id of the received message.
+    pub message_id: EthMessageID,
+}
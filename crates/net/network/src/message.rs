@@ -6,8 +6,8 @@
 use futures::FutureExt;
 use reth_eth_wire::{
     capability::RawCapabilityMessage, message::RequestPair, BlockBodies, BlockHeaders, EthMessage,
-    GetBlockBodies, GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts, NewBlock,
-    NewBlockHashes, NewPooledTransactionHashes, NodeData, PooledTransactions, Receipts,
+    EthMessageID, GetBlockBodies, GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts,
+    NewBlock, NewBlockHashes, NewPooledTransactionHashes, NodeData, PooledTransactions, Receipts,
     SharedTransactions, Transactions,
 };
 use reth_interfaces::p2p::error::{RequestError, RequestResult};
@@ -60,6 +60,25 @@ pub enum PeerMessage {
     Other(RawCapabilityMessage),
 }
 
+// === impl PeerMessage ===
+
+impl PeerMessage {
+    /// Returns the [`EthMessageID`] of the message, if it is an `eth` message.
+    pub fn message_id(&self) -> Option<EthMessageID> {
+        let id = match self {
+            PeerMessage::NewBlockHashes(_) => EthMessageID::NewBlockHashes,
+            PeerMessage::NewBlock(_) => EthMessageID::NewBlock,
+            PeerMessage::ReceivedTransaction(_) | PeerMessage::SendTransactions(_) => {
+                EthMessageID::Transactions
+            }
+            PeerMessage::PooledTransactions(_) => EthMessageID::NewPooledTransactionHashes,
+            PeerMessage::EthRequest(req) => req.message_id(),
+            PeerMessage::Other(_) => return None,
+        };
+        Some(id)
+    }
+}
+
 /// Request Variants that only target block related data.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[allow(missing_docs)]
@@ -123,6 +142,17 @@ impl PeerRequest {
         };
     }
 
+    /// Returns the [`EthMessageID`] of the request.
+    pub fn message_id(&self) -> EthMessageID {
+        match self {
+            PeerRequest::GetBlockHeaders { .. } => EthMessageID::GetBlockHeaders,
+            PeerRequest::GetBlockBodies { .. } => EthMessageID::GetBlockBodies,
+            PeerRequest::GetPooledTransactions { .. } => EthMessageID::GetPooledTransactions,
+            PeerRequest::GetNodeData { .. } => EthMessageID::GetNodeData,
+            PeerRequest::GetReceipts { .. } => EthMessageID::GetReceipts,
+        }
+    }
+
     /// Returns the [`EthMessage`] for this type
     pub fn create_request_message(&self, request_id: u64) -> EthMessage {
         match self {
@@ -49,10 +49,16 @@ pub struct TransactionsManagerMetrics {
     pub(crate) messages_with_already_seen_hashes: Counter,
     /// Total number of messages with already seen full transactions
     pub(crate) messages_with_already_seen_transactions: Counter,
+    /// Total number of transactions that were requested again from an alternate peer, because
+    /// the peer they were requested from didn't deliver them
+    pub(crate) refetched_transactions: Counter,
 }
 
 /// Metrics for Disconnection types
 ///
+/// The network tracks the disconnects initiated by the local node and by the peers separately,
+/// labeled with `initiator`.
+///
 /// These are just counters, and ideally we would implement these metrics on a peer-by-peer basis,
 /// in that we do not double-count peers for `TooManyPeers` if we make an outgoing connection and
 /// get disconnected twice
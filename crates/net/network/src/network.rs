@@ -1,9 +1,14 @@
 use crate::{
-    config::NetworkMode, manager::NetworkEvent, message::PeerRequest, peers::PeersHandle,
-    session::PeerInfo, FetchClient,
+    config::NetworkMode,
+    manager::{NetworkEvent, ReceivedMessage},
+    message::PeerRequest,
+    peers::PeersHandle,
+    session::PeerInfo,
+    FetchClient,
 };
 use async_trait::async_trait;
 use parking_lot::Mutex;
+use reth_discv4::Discv4;
 use reth_eth_wire::{DisconnectReason, NewBlock, NewPooledTransactionHashes, SharedTransactions};
 use reth_interfaces::{
     p2p::headers::client::StatusUpdater,
@@ -14,7 +19,7 @@ use reth_network_api::{
     NetworkError, NetworkInfo, PeerKind, Peers, PeersInfo, Reputation, ReputationChangeKind,
 };
 use reth_primitives::{Head, NodeRecord, PeerId, TransactionSigned, H256};
-use reth_rpc_types::NetworkStatus;
+use reth_rpc_types::{BannedPeer, ConnectedPeer, NetworkStatus, PeerDisconnect, PeerEvent};
 use std::{
     net::SocketAddr,
     sync::{
@@ -22,8 +27,8 @@ use std::{
         Arc,
     },
 };
-use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
-use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio::sync::{broadcast, mpsc, mpsc::UnboundedSender, oneshot};
+use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
 
 /// A _shareable_ network frontend. Used to interact with the network.
 ///
@@ -48,6 +53,8 @@ impl NetworkHandle {
         network_mode: NetworkMode,
         bandwidth_meter: BandwidthMeter,
         chain_id: Arc<AtomicU64>,
+        discv4: Option<Discv4>,
+        peer_events: broadcast::Sender<PeerEvent>,
     ) -> Self {
         let inner = NetworkInner {
             num_active_peers,
@@ -58,7 +65,10 @@ impl NetworkHandle {
             network_mode,
             bandwidth_meter,
             is_syncing: Arc::new(AtomicBool::new(true)),
+            is_listening: AtomicBool::new(true),
             chain_id,
+            discv4,
+            peer_events,
         };
         Self { inner: Arc::new(inner) }
     }
@@ -75,6 +85,19 @@ impl NetworkHandle {
         &self.inner.peers
     }
 
+    /// Returns the handle to the discv4 service, if discv4 is enabled.
+    ///
+    /// The handle can be used to run on-demand lookups, see [`Discv4::lookup`] and
+    /// [`Discv4::lookup_self`].
+    pub fn discv4(&self) -> Option<&Discv4> {
+        self.inner.discv4.as_ref()
+    }
+
+    /// Sets whether the node accepts incoming connections.
+    pub(crate) fn set_listening(&self, is_listening: bool) {
+        self.inner.is_listening.store(is_listening, Ordering::Relaxed)
+    }
+
     fn manager(&self) -> &UnboundedSender<NetworkHandleMessage> {
         &self.inner.to_manager_tx
     }
@@ -86,6 +109,16 @@ impl NetworkHandle {
         UnboundedReceiverStream::new(rx)
     }
 
+    /// Creates a new listener channel for the `eth` messages received from peers, which buffers
+    /// up to `buffer` messages.
+    ///
+    /// Messages are dropped while the buffer is full.
+    pub fn message_listener(&self, buffer: usize) -> ReceiverStream<ReceivedMessage> {
+        let (tx, rx) = mpsc::channel(buffer);
+        let _ = self.manager().send(NetworkHandleMessage::MessageListener(tx));
+        ReceiverStream::new(rx)
+    }
+
     /// Returns a new [`FetchClient`] that can be cloned and shared.
     ///
     /// The [`FetchClient`] is the entrypoint for sending requests to the network.
@@ -180,7 +213,11 @@ impl PeersInfo for NetworkHandle {
     fn local_node_record(&self) -> NodeRecord {
         let id = *self.peer_id();
         let socket_addr = *self.inner.listener_address.lock();
-        NodeRecord::new(socket_addr, id)
+        let mut record = NodeRecord::new(socket_addr, id);
+        if let Some(discv4) = self.discv4() {
+            record.udp_port = discv4.local_addr().port();
+        }
+        record
     }
 }
 
@@ -198,6 +235,12 @@ impl Peers for NetworkHandle {
         self.send_message(NetworkHandleMessage::RemovePeer(peer, kind))
     }
 
+    /// Sends a message to the [`NetworkManager`](crate::NetworkManager) to ban the peer and
+    /// disconnect an existing connection to it.
+    fn ban_peer(&self, peer: PeerId) {
+        self.send_message(NetworkHandleMessage::BanPeer(peer))
+    }
+
     /// Sends a message to the [`NetworkManager`](crate::NetworkManager)  to disconnect an existing
     /// connection to the given peer.
     fn disconnect_peer(&self, peer: PeerId) {
@@ -220,6 +263,33 @@ impl Peers for NetworkHandle {
         let _ = self.manager().send(NetworkHandleMessage::GetReputationById(peer_id, tx));
         Ok(rx.await?)
     }
+
+    async fn connected_peers(&self) -> Result<Vec<ConnectedPeer>, NetworkError> {
+        let peers = self.get_peers().await?;
+        Ok(peers.into_iter().map(PeerInfo::into_connected_peer).collect())
+    }
+
+    async fn banned_peers(&self) -> Result<Vec<BannedPeer>, NetworkError> {
+        let bans = self.peers_handle().banned_peers().await;
+        Ok(bans
+            .into_iter()
+            .map(|ban| BannedPeer { id: ban.peer_id, expires_at: ban.expires_at })
+            .collect())
+    }
+
+    fn clear_bans(&self) {
+        self.peers_handle().clear_bans()
+    }
+
+    async fn recent_disconnects(&self) -> Result<Vec<PeerDisconnect>, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.manager().send(NetworkHandleMessage::GetRecentDisconnects(tx));
+        Ok(rx.await?)
+    }
+
+    fn peer_events(&self) -> broadcast::Receiver<PeerEvent> {
+        self.inner.peer_events.subscribe()
+    }
 }
 
 #[async_trait]
@@ -231,7 +301,11 @@ impl NetworkInfo for NetworkHandle {
     async fn network_status(&self) -> Result<NetworkStatus, NetworkError> {
         let (tx, rx) = oneshot::channel();
         let _ = self.manager().send(NetworkHandleMessage::GetStatus(tx));
-        rx.await.map_err(Into::into)
+        let mut status = rx.await?;
+        if let Some(discv4) = self.discv4() {
+            status.enr = discv4.local_enr().await.ok().map(|enr| enr.to_base64());
+        }
+        Ok(status)
     }
 
     fn chain_id(&self) -> u64 {
@@ -241,6 +315,10 @@ impl NetworkInfo for NetworkHandle {
     fn is_syncing(&self) -> bool {
         SyncStateProvider::is_syncing(self)
     }
+
+    fn is_listening(&self) -> bool {
+        self.inner.is_listening.load(Ordering::Relaxed)
+    }
 }
 
 impl StatusUpdater for NetworkHandle {
@@ -281,8 +359,15 @@ struct NetworkInner {
     bandwidth_meter: BandwidthMeter,
     /// Represents if the network is currently syncing.
     is_syncing: Arc<AtomicBool>,
+    /// Represents if the listener of the local address still accepts incoming connections.
+    is_listening: AtomicBool,
     /// The chain id
     chain_id: Arc<AtomicU64>,
+    /// The handle to the discv4 service, if enabled.
+    discv4: Option<Discv4>,
+    /// Sender half of the channel the [`crate::NetworkManager`] broadcasts peer lifecycle events
+    /// on.
+    peer_events: broadcast::Sender<PeerEvent>,
 }
 
 /// Internal messages that can be passed to the  [`NetworkManager`](crate::NetworkManager).
@@ -292,10 +377,14 @@ pub(crate) enum NetworkHandleMessage {
     AddPeerAddress(PeerId, PeerKind, SocketAddr),
     /// Removes a peer from the peerset corresponding to the given kind.
     RemovePeer(PeerId, PeerKind),
+    /// Bans a peer and disconnects it if connected.
+    BanPeer(PeerId),
     /// Disconnect a connection to a peer if it exists.
     DisconnectPeer(PeerId, Option<DisconnectReason>),
     /// Add a new listener for [`NetworkEvent`].
     EventListener(UnboundedSender<NetworkEvent>),
+    /// Add a new listener for the `eth` messages received from peers.
+    MessageListener(mpsc::Sender<ReceivedMessage>),
     /// Broadcast event to announce a new block to all nodes.
     AnnounceBlock(NewBlock, H256),
     /// Sends the list of transactions to the given peer.
@@ -323,6 +412,8 @@ pub(crate) enum NetworkHandleMessage {
     GetPeerInfoById(PeerId, oneshot::Sender<Option<PeerInfo>>),
     /// Get the reputation for a specific peer
     GetReputationById(PeerId, oneshot::Sender<Option<Reputation>>),
+    /// Get the most recent disconnects
+    GetRecentDisconnects(oneshot::Sender<Vec<PeerDisconnect>>),
     /// Gracefully shutdown network
     Shutdown(oneshot::Sender<()>),
 }
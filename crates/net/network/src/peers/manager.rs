@@ -2,13 +2,14 @@ use crate::{
     error::{BackoffKind, SessionError},
     peers::{
         reputation::{is_banned_reputation, DEFAULT_REPUTATION},
-        ReputationChangeWeights, DEFAULT_MAX_PEERS_INBOUND, DEFAULT_MAX_PEERS_OUTBOUND,
+        ReputationChangeWeights, DEFAULT_MAX_CONCURRENT_DIALS, DEFAULT_MAX_PEERS_INBOUND,
+        DEFAULT_MAX_PEERS_OUTBOUND, DEFAULT_MAX_PEERS_PER_IP, DEFAULT_MAX_PEERS_PER_SUBNET,
     },
     session::{Direction, PendingSessionHandshakeError},
 };
 use futures::StreamExt;
 use reth_eth_wire::{errors::EthStreamError, DisconnectReason};
-use reth_net_common::ban_list::BanList;
+use reth_net_common::ban_list::{is_global, BanList, IpNet};
 use reth_network_api::{PeerKind, ReputationChangeKind};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use std::{
@@ -72,6 +73,19 @@ impl PeersHandle {
 
         rx.await.unwrap_or_default()
     }
+
+    /// Returns all banned peers.
+    pub async fn banned_peers(&self) -> Vec<PersistedBan> {
+        let (tx, rx) = oneshot::channel();
+        self.send(PeerCommand::GetBans(tx));
+
+        rx.await.unwrap_or_default()
+    }
+
+    /// Lifts the bans of all peers.
+    pub fn clear_bans(&self) {
+        self.send(PeerCommand::ClearBans);
+    }
 }
 
 /// Maintains the state of _all_ the peers known to the network.
@@ -110,6 +124,16 @@ pub(crate) struct PeersManager {
     connect_trusted_nodes_only: bool,
     /// Timestamp of the last time [Self::tick] was called.
     last_tick: Instant,
+    /// Peers we're dialing that haven't established a session yet.
+    pending_dials: HashSet<PeerId>,
+    /// The [`ForkId`] of the local node, peers that announced the same fork id are dialed first.
+    local_fork_id: Option<ForkId>,
+    /// The number of pending and active sessions per ip and per subnet.
+    ip_connections: IpConnectionCounts,
+    /// The ip addresses of the sessions that are counted in `ip_connections` by peer.
+    ///
+    /// Pending incoming sessions are tracked by ip only, until the session is established.
+    session_ips: HashMap<PeerId, IpAddr>,
 }
 
 impl PeersManager {
@@ -119,12 +143,16 @@ impl PeersManager {
             refill_slots_interval,
             connection_info,
             reputation_weights,
-            ban_list,
+            mut ban_list,
             ban_duration,
             backoff_durations,
             trusted_nodes,
+            static_nodes,
             connect_trusted_nodes_only,
             basic_nodes,
+            persisted_peers,
+            persisted_bans,
+            persisted_ip_bans,
             ..
         } = config;
         let (manager_tx, handle_rx) = mpsc::unbounded_channel();
@@ -133,16 +161,57 @@ impl PeersManager {
         // We use half of the interval to decrease the max duration to `150%` in worst case
         let unban_interval = ban_duration.min(backoff_durations.low) / 2;
 
-        let mut peers = HashMap::with_capacity(trusted_nodes.len() + basic_nodes.len());
+        let mut peers = HashMap::with_capacity(
+            trusted_nodes.len() + static_nodes.len() + persisted_peers.len() + basic_nodes.len(),
+        );
 
         for NodeRecord { address, tcp_port, udp_port: _, id } in trusted_nodes {
             peers.entry(id).or_insert_with(|| Peer::trusted(SocketAddr::from((address, tcp_port))));
         }
 
+        for NodeRecord { address, tcp_port, udp_port: _, id } in static_nodes {
+            peers.entry(id).or_insert_with(|| {
+                Peer::with_kind(SocketAddr::from((address, tcp_port)), PeerKind::Static)
+            });
+        }
+
+        for PersistedPeer { node: NodeRecord { address, tcp_port, udp_port: _, id }, reputation } in
+            persisted_peers
+        {
+            peers.entry(id).or_insert_with(|| Peer {
+                reputation,
+                session_established: true,
+                ..Peer::new(SocketAddr::from((address, tcp_port)))
+            });
+        }
+
         for NodeRecord { address, tcp_port, udp_port: _, id } in basic_nodes {
             peers.entry(id).or_insert_with(|| Peer::new(SocketAddr::from((address, tcp_port))));
         }
 
+        // bans that expired while the node was offline are dropped
+        let now_unix = unix_timestamp();
+        let ban_until = |expires_at: Option<u64>| match expires_at {
+            None => Some(None),
+            Some(expires_at) if expires_at > now_unix => {
+                Some(Some(std::time::Instant::now() + Duration::from_secs(expires_at - now_unix)))
+            }
+            Some(_) => None,
+        };
+        for PersistedBan { peer_id, expires_at } in persisted_bans {
+            if let Some(until) = ban_until(expires_at) {
+                ban_list.ban_peer_with(peer_id, until);
+            }
+        }
+        for PersistedIpBan { ip, expires_at } in persisted_ip_bans {
+            if let Some(until) = ban_until(expires_at) {
+                ban_list.ban_ip_with(ip, until);
+            }
+        }
+
+        // configured peers of denied networks are never dialed
+        peers.retain(|_, peer| !ban_list.is_denied_ip(&peer.addr.ip()));
+
         Self {
             peers,
             manager_tx,
@@ -161,9 +230,20 @@ impl PeersManager {
             backoff_durations,
             connect_trusted_nodes_only,
             last_tick: Instant::now(),
+            pending_dials: Default::default(),
+            local_fork_id: None,
+            ip_connections: Default::default(),
+            session_ips: Default::default(),
         }
     }
 
+    /// Sets the [`ForkId`] of the local node.
+    ///
+    /// Peers that announced the same [`ForkId`] are preferred when filling outbound slots.
+    pub(crate) fn set_local_fork_id(&mut self, fork_id: ForkId) {
+        self.local_fork_id = Some(fork_id);
+    }
+
     /// Returns a new [`PeersHandle`] that can send commands to this type.
     pub(crate) fn handle(&self) -> PeersHandle {
         PeersHandle { manager_tx: self.manager_tx.clone() }
@@ -180,6 +260,57 @@ impl PeersManager {
         self.peers.iter().map(|(peer_id, v)| NodeRecord::new(v.addr, *peer_id))
     }
 
+    /// Returns the peers that are worth remembering across restarts: all peers a session was
+    /// established with, except banned peers and peers whose listening port is unknown.
+    pub(crate) fn persisted_peers(&self) -> impl Iterator<Item = PersistedPeer> + '_ {
+        self.peers
+            .iter()
+            .filter(|(_, peer)| {
+                peer.session_established && !peer.remove_after_disconnect && !peer.is_banned()
+            })
+            .map(|(peer_id, peer)| PersistedPeer {
+                node: NodeRecord::new(peer.addr, *peer_id),
+                reputation: peer.reputation,
+            })
+    }
+
+    /// Returns all banned peers with the unix timestamp at which their ban expires.
+    pub(crate) fn persisted_bans(&self) -> impl Iterator<Item = PersistedBan> + '_ {
+        let now = std::time::Instant::now();
+        let now_unix = unix_timestamp();
+        self.ban_list.banned_peers().map(move |(peer_id, until)| PersistedBan {
+            peer_id,
+            expires_at: until
+                .map(|until| now_unix + until.saturating_duration_since(now).as_secs()),
+        })
+    }
+
+    /// Returns all banned IPs with the unix timestamp at which their ban expires.
+    pub(crate) fn persisted_ip_bans(&self) -> impl Iterator<Item = PersistedIpBan> + '_ {
+        let now = std::time::Instant::now();
+        let now_unix = unix_timestamp();
+        self.ban_list.banned_ips().map(move |(ip, until)| PersistedIpBan {
+            ip,
+            expires_at: until
+                .map(|until| now_unix + until.saturating_duration_since(now).as_secs()),
+        })
+    }
+
+    /// Lifts the bans of all peers and IPs and resets the reputation of banned peers.
+    fn clear_bans(&mut self) {
+        let banned = self.ban_list.banned_peers().map(|(peer_id, _)| peer_id).collect::<Vec<_>>();
+        for peer_id in banned {
+            if let Some(peer) = self.peers.get_mut(&peer_id) {
+                peer.unban();
+            }
+            self.unban_peer(peer_id);
+        }
+        let banned_ips = self.ban_list.banned_ips().map(|(ip, _)| ip).collect::<Vec<_>>();
+        for ip in banned_ips {
+            self.ban_list.unban_ip(&ip);
+        }
+    }
+
     /// Returns the number of currently active inbound connections.
     #[inline]
     pub(crate) fn num_inbound_connections(&self) -> usize {
@@ -201,7 +332,9 @@ impl PeersManager {
     /// Invoked when a new _incoming_ tcp connection is accepted.
     ///
     /// returns an error if the inbound ip address is on the ban list or
-    /// we have reached our limit for max inbound connections
+    /// we have reached our limit for max inbound connections.
+    ///
+    /// Connections from the ip address of a trusted peer are accepted regardless of the limit.
     pub(crate) fn on_incoming_pending_session(
         &mut self,
         addr: IpAddr,
@@ -209,23 +342,61 @@ impl PeersManager {
         if self.ban_list.is_banned_ip(&addr) {
             return Err(InboundConnectionError::IpBanned)
         }
-        if !self.connection_info.has_in_capacity() {
+        let is_trusted = self.is_trusted_ip(&addr);
+        if !self.connection_info.has_in_capacity() && !is_trusted {
             return Err(InboundConnectionError::ExceedsLimit(self.connection_info.max_inbound))
         }
+        if !is_trusted && self.connection_info.exceeds_ip_limits(&addr, &self.ip_connections, 0) {
+            return Err(InboundConnectionError::ExceedsIpLimit)
+        }
         // keep track of new connection
         self.connection_info.inc_in();
+        self.inc_ip(addr);
         Ok(())
     }
 
+    /// Counts a new session to the ip address.
+    fn inc_ip(&mut self, ip: IpAddr) {
+        let subnet = self.connection_info.subnet(&ip);
+        self.ip_connections.inc(ip, subnet);
+    }
+
+    /// Releases a session to the ip address.
+    fn dec_ip(&mut self, ip: IpAddr) {
+        let subnet = self.connection_info.subnet(&ip);
+        self.ip_connections.dec(ip, subnet);
+    }
+
+    /// Counts the ip of a new outgoing session to the peer.
+    fn track_session_ip(&mut self, peer_id: PeerId, ip: IpAddr) {
+        self.release_session_ip(&peer_id);
+        self.session_ips.insert(peer_id, ip);
+        self.inc_ip(ip);
+    }
+
+    /// Releases the counted ip of the peer's session, if any.
+    fn release_session_ip(&mut self, peer_id: &PeerId) {
+        if let Some(ip) = self.session_ips.remove(peer_id) {
+            self.dec_ip(ip);
+        }
+    }
+
+    /// Returns true if the ip address belongs to a trusted peer.
+    fn is_trusted_ip(&self, addr: &IpAddr) -> bool {
+        self.peers.values().any(|peer| peer.is_trusted() && peer.addr.ip() == *addr)
+    }
+
     /// Invoked when a previous call to [Self::on_incoming_pending_session] succeeded but it was
     /// rejected.
-    pub(crate) fn on_incoming_pending_session_rejected_internally(&mut self) {
+    pub(crate) fn on_incoming_pending_session_rejected_internally(&mut self, addr: IpAddr) {
         self.connection_info.decr_in();
+        self.dec_ip(addr);
     }
 
     /// Invoked when a pending session was closed.
-    pub(crate) fn on_incoming_pending_session_gracefully_closed(&mut self) {
-        self.connection_info.decr_in()
+    pub(crate) fn on_incoming_pending_session_gracefully_closed(&mut self, addr: IpAddr) {
+        self.connection_info.decr_in();
+        self.dec_ip(addr);
     }
 
     /// Invoked when a pending session was closed.
@@ -243,7 +414,8 @@ impl PeersManager {
             }
         }
 
-        self.connection_info.decr_in()
+        self.connection_info.decr_in();
+        self.dec_ip(remote_addr.ip());
     }
 
     /// Called when a new _incoming_ active session was established to the given peer.
@@ -251,8 +423,17 @@ impl PeersManager {
     /// This will update the state of the peer if not yet tracked.
     ///
     /// If the reputation of the peer is below the `BANNED_REPUTATION` threshold, a disconnect will
-    /// be scheduled.
+    /// be scheduled. The same applies if the session exceeds the per ip or per subnet limits.
     pub(crate) fn on_incoming_session_established(&mut self, peer_id: PeerId, addr: SocketAddr) {
+        // the ip was counted when the pending session was accepted, from now on it's released
+        // with the peer's session. If we're dialing the peer at the same time, only one of the
+        // sessions remains, so the count of the pending session is released right away.
+        if self.session_ips.contains_key(&peer_id) {
+            self.dec_ip(addr.ip());
+        } else {
+            self.session_ips.insert(peer_id, addr.ip());
+        }
+
         // we only need to check the peer id here as the ip address will have been checked at
         // on_inbound_pending_session. We also check if the peer is in the backoff list here.
         if self.ban_list.is_banned_peer(&peer_id) {
@@ -271,6 +452,7 @@ impl PeersManager {
                     return
                 }
                 value.state = PeerConnectionState::In;
+                value.session_established = true;
             }
             Entry::Vacant(entry) => {
                 // peer is missing in the table, we add it but mark it as to be removed after
@@ -281,6 +463,29 @@ impl PeersManager {
                 self.queued_actions.push_back(PeerAction::PeerAdded(peer_id));
             }
         }
+
+        // other sessions from the same ip or subnet may have been established in the meantime
+        let ip = addr.ip();
+        let is_trusted = self.peers.get(&peer_id).map_or(false, |peer| peer.is_trusted()) ||
+            self.is_trusted_ip(&ip);
+        if !is_trusted && self.connection_info.exceeds_ip_limits(&ip, &self.ip_connections, 1) {
+            trace!(target: "net::peers", ?peer_id, ?addr, "established session exceeds the ip limits");
+            if let Some(peer) = self.peers.get_mut(&peer_id) {
+                peer.state.disconnect();
+            }
+            self.queued_actions.push_back(PeerAction::Disconnect {
+                peer_id,
+                reason: Some(DisconnectReason::TooManyPeers),
+            });
+        }
+    }
+
+    /// Invoked after a session was established with a peer we connected to.
+    pub(crate) fn on_outgoing_session_established(&mut self, peer_id: &PeerId) {
+        self.pending_dials.remove(peer_id);
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.session_established = true;
+        }
     }
 
     /// Bans the peer temporarily with the configured ban timeout
@@ -289,6 +494,24 @@ impl PeersManager {
         self.queued_actions.push_back(PeerAction::BanPeer { peer_id });
     }
 
+    /// Bans the peer temporarily with the configured ban timeout and disconnects it, if connected.
+    ///
+    /// The peer is released again once the ban expired.
+    pub(crate) fn ban_peer_and_disconnect(&mut self, peer_id: PeerId) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            // ensures the peer isn't dialed while banned
+            peer.reputation = i32::MIN;
+            if peer.state.is_connected() {
+                peer.state.disconnect();
+                self.queued_actions.push_back(PeerAction::Disconnect {
+                    peer_id,
+                    reason: Some(DisconnectReason::DisconnectRequested),
+                });
+            }
+        }
+        self.ban_peer(peer_id);
+    }
+
     /// Bans the IP temporarily with the configured ban timeout
     fn ban_ip(&mut self, ip: IpAddr) {
         self.ban_list.ban_ip_until(ip, std::time::Instant::now() + self.ban_duration);
@@ -369,6 +592,8 @@ impl PeersManager {
 
     /// Gracefully disconnected a pending session
     pub(crate) fn on_pending_session_gracefully_closed(&mut self, peer_id: &PeerId) {
+        self.pending_dials.remove(peer_id);
+        self.release_session_ip(peer_id);
         if let Some(peer) = self.peers.get_mut(peer_id) {
             peer.state = PeerConnectionState::Idle;
         } else {
@@ -389,6 +614,7 @@ impl PeersManager {
 
     /// Gracefully disconnected an active session
     pub(crate) fn on_active_session_gracefully_closed(&mut self, peer_id: PeerId) {
+        self.release_session_ip(&peer_id);
         match self.peers.entry(peer_id) {
             Entry::Occupied(mut entry) => {
                 self.connection_info.decr_state(entry.get().state);
@@ -403,6 +629,11 @@ impl PeersManager {
                     // session to that peer
                     entry.get_mut().severe_backoff_counter = 0;
                     entry.get_mut().state = PeerConnectionState::Idle;
+                    if entry.get().is_static() {
+                        // static peers are redialed once the backoff expired
+                        let backoff_until = std::time::Instant::now() + self.backoff_durations.low;
+                        self.backoff_peer_until(peer_id, backoff_until);
+                    }
                     return
                 }
             }
@@ -444,7 +675,13 @@ impl PeersManager {
     ) {
         trace!(target: "net::peers", ?remote_addr, ?peer_id, ?err, "handling failed connection");
 
-        if err.is_fatal_protocol_error() {
+        self.pending_dials.remove(peer_id);
+        self.release_session_ip(peer_id);
+
+        let is_persistent =
+            self.peers.get(peer_id).map_or(false, |peer| peer.is_trusted() || peer.is_static());
+
+        if err.is_fatal_protocol_error() && !is_persistent {
             trace!(target: "net::peers", ?remote_addr, ?peer_id, ?err, "fatal connection error");
             // remove the peer to which we can't establish a connection due to protocol related
             // issues.
@@ -467,7 +704,17 @@ impl PeersManager {
             let mut backoff_until = None;
 
             if let Some(peer) = self.peers.get_mut(peer_id) {
-                if let Some(kind) = err.should_backoff() {
+                // trusted and static peers are never removed, instead they're retried with a
+                // backoff, static peers are also retried if the error doesn't require a backoff
+                let kind = if err.is_fatal_protocol_error() {
+                    Some(BackoffKind::High)
+                } else if peer.is_static() {
+                    Some(err.should_backoff().unwrap_or(BackoffKind::Low))
+                } else {
+                    err.should_backoff()
+                };
+
+                if let Some(kind) = kind {
                     // Increment peer.backoff_counter
                     if kind.is_severe() {
                         peer.severe_backoff_counter += 1;
@@ -480,7 +727,7 @@ impl PeersManager {
                     // connections, so we will hold off on attempting any new connections for a
                     // while
                     backoff_until = Some(backoff_time);
-                } else {
+                } else if !peer.is_trusted() {
                     // If the error was not a backoff error, we reduce the peer's reputation
                     let reputation_change = self.reputation_weights.change(reputation_change);
                     peer.reputation = peer.reputation.saturating_add(reputation_change.as_i32());
@@ -500,11 +747,14 @@ impl PeersManager {
     /// Invoked if a session was disconnected because there's already a connection to the peer.
     ///
     /// If the session was an outgoing connection, this means that the peer initiated a connection
-    /// to us at the same time and this connection is already established.
-    pub(crate) fn on_already_connected(&mut self, direction: Direction) {
+    /// to us at the same time and this connection is already established. The counted ip then
+    /// belongs to the established session and is released once that session is closed.
+    pub(crate) fn on_already_connected(&mut self, remote_addr: SocketAddr, direction: Direction) {
         match direction {
-            Direction::Incoming => {}
-            Direction::Outgoing(_) => {
+            // the ip of the pending incoming session was counted when it was accepted
+            Direction::Incoming => self.dec_ip(remote_addr.ip()),
+            Direction::Outgoing(peer_id) => {
+                self.pending_dials.remove(&peer_id);
                 // need to decrement the outgoing counter
                 self.connection_info.decr_out();
             }
@@ -582,7 +832,7 @@ impl PeersManager {
     /// Removes the tracked node from the set.
     pub(crate) fn remove_peer(&mut self, peer_id: PeerId) {
         let Entry::Occupied(entry) = self.peers.entry(peer_id) else { return };
-        if entry.get().is_trusted() {
+        if entry.get().is_trusted() || entry.get().is_static() {
             return
         }
         let mut peer = entry.remove();
@@ -618,21 +868,40 @@ impl PeersManager {
         peer.kind = PeerKind::Basic;
     }
 
+    /// Removes the tracked node from the static set.
+    pub(crate) fn remove_peer_from_static_set(&mut self, peer_id: PeerId) {
+        let Entry::Occupied(mut entry) = self.peers.entry(peer_id) else { return };
+        if !entry.get().is_static() {
+            return
+        }
+
+        let peer = entry.get_mut();
+
+        peer.kind = PeerKind::Basic;
+    }
+
     /// Returns the idle peer with the highest reputation.
     ///
     /// Peers that are `trusted`, see [PeerKind], are prioritized as long as they're not currently
-    /// marked as banned or backed off.
+    /// marked as banned or backed off. Otherwise, peers that announced the local [`ForkId`] are
+    /// preferred over peers with an unknown or different fork id.
     ///
     /// If `connect_trusted_nodes_only` is enabled, see [PeersConfig], then this will only consider
     /// `trusted` peers.
     ///
     /// Returns `None` if no peer is available.
     fn best_unconnected(&mut self) -> Option<(PeerId, &mut Peer)> {
+        let ip_counts = &self.ip_connections;
+        let connection_info = &self.connection_info;
+        let local_fork_id = self.local_fork_id;
+        let on_local_fork = |peer: &Peer| local_fork_id.is_some() && peer.fork_id == local_fork_id;
         let mut unconnected = self.peers.iter_mut().filter(|(_, peer)| {
             peer.state.is_unconnected() &&
                 !peer.is_banned() &&
                 !peer.is_backed_off() &&
-                (!self.connect_trusted_nodes_only || peer.is_trusted())
+                (!self.connect_trusted_nodes_only || peer.is_trusted()) &&
+                (peer.is_trusted() ||
+                    !connection_info.exceeds_ip_limits(&peer.addr.ip(), ip_counts, 0))
         });
 
         // keep track of the best peer, if there's one
@@ -648,8 +917,10 @@ impl PeersManager {
                 return Some((*maybe_better.0, maybe_better.1))
             }
 
-            // otherwise we keep track of the best peer using the reputation
-            if maybe_better.1.reputation > best_peer.1.reputation {
+            // otherwise we keep track of the best peer using the fork id and the reputation
+            if (on_local_fork(maybe_better.1), maybe_better.1.reputation) >
+                (on_local_fork(best_peer.1), best_peer.1.reputation)
+            {
                 best_peer = maybe_better;
             }
         }
@@ -660,12 +931,40 @@ impl PeersManager {
     /// [`PeerAction::Connect`] actions.
     ///
     /// New connections are only initiated, if slots are available and appropriate peers are
-    /// available.
+    /// available. Trusted and static peers are connected to regardless of the available slots.
     fn fill_outbound_slots(&mut self) {
         self.tick();
 
-        // as long as there a slots available try to fill them with the best peers
-        while self.connection_info.has_out_capacity() {
+        let connect_trusted_nodes_only = self.connect_trusted_nodes_only;
+        let persistent = self
+            .peers
+            .iter_mut()
+            .filter(|(_, peer)| {
+                peer.state.is_unconnected() &&
+                    !peer.is_banned() &&
+                    !peer.is_backed_off() &&
+                    (peer.is_trusted() || (peer.is_static() && !connect_trusted_nodes_only))
+            })
+            .map(|(peer_id, peer)| {
+                trace!(target : "net::peers", ?peer_id, addr=?peer.addr, "schedule persistent connection");
+                peer.state = PeerConnectionState::Out;
+                PeerAction::Connect { peer_id: *peer_id, remote_addr: peer.addr }
+            })
+            .collect::<Vec<_>>();
+        for action in persistent {
+            if let PeerAction::Connect { peer_id, remote_addr } = action {
+                self.pending_dials.insert(peer_id);
+                self.track_session_ip(peer_id, remote_addr.ip());
+            }
+            self.connection_info.inc_out();
+            self.queued_actions.push_back(action);
+        }
+
+        // as long as there a slots available try to fill them with the best peers, without
+        // exceeding the number of concurrent dials
+        while self.connection_info.has_out_capacity() &&
+            self.connection_info.has_dial_capacity(self.pending_dials.len())
+        {
             let action = {
                 let (peer_id, peer) = match self.best_unconnected() {
                     Some(peer) => peer,
@@ -683,6 +982,10 @@ impl PeersManager {
                 PeerAction::Connect { peer_id, remote_addr: peer.addr }
             };
 
+            if let PeerAction::Connect { peer_id, remote_addr } = action {
+                self.pending_dials.insert(peer_id);
+                self.track_session_ip(peer_id, remote_addr.ip());
+            }
             self.connection_info.inc_out();
             self.queued_actions.push_back(action);
         }
@@ -714,6 +1017,10 @@ impl PeersManager {
                     PeerCommand::GetPeers(tx) => {
                         let _ = tx.send(self.iter_peers().collect());
                     }
+                    PeerCommand::GetBans(tx) => {
+                        let _ = tx.send(self.persisted_bans().collect());
+                    }
+                    PeerCommand::ClearBans => self.clear_bans(),
                 }
             }
 
@@ -776,6 +1083,28 @@ pub struct ConnectionInfo {
     max_outbound: usize,
     /// Maximum allowed inbound connections.
     max_inbound: usize,
+    /// Maximum allowed connections to peers with the same ip.
+    ///
+    /// Trusted peers and peers of local networks are exempt.
+    #[cfg_attr(feature = "serde", serde(default = "default_max_per_ip"))]
+    max_per_ip: usize,
+    /// Maximum allowed connections to peers of the same subnet, see also
+    /// `ipv4_subnet_prefix_len` and `ipv6_subnet_prefix_len`.
+    ///
+    /// Trusted peers and peers of local networks are exempt.
+    #[cfg_attr(feature = "serde", serde(default = "default_max_per_subnet"))]
+    max_per_subnet: usize,
+    /// The prefix length of the IPv4 subnets connections are limited for.
+    #[cfg_attr(feature = "serde", serde(default = "default_ipv4_subnet_prefix_len"))]
+    ipv4_subnet_prefix_len: u8,
+    /// The prefix length of the IPv6 subnets connections are limited for.
+    #[cfg_attr(feature = "serde", serde(default = "default_ipv6_subnet_prefix_len"))]
+    ipv6_subnet_prefix_len: u8,
+    /// Maximum outbound dials that are in progress at the same time.
+    ///
+    /// Dials to trusted and static peers are not limited.
+    #[cfg_attr(feature = "serde", serde(default = "default_max_concurrent_dials"))]
+    max_concurrent_dials: usize,
 }
 
 // === impl ConnectionInfo ===
@@ -786,6 +1115,11 @@ impl ConnectionInfo {
         self.num_outbound < self.max_outbound
     }
 
+    ///  Returns `true` if another dial can be started while `pending_dials` are in progress.
+    fn has_dial_capacity(&self, pending_dials: usize) -> bool {
+        pending_dials < self.max_concurrent_dials
+    }
+
     ///  Returns `true` if there's still capacity for a new incoming connection.
     fn has_in_capacity(&self) -> bool {
         self.num_inbound < self.max_inbound
@@ -814,6 +1148,88 @@ impl ConnectionInfo {
     fn decr_in(&mut self) {
         self.num_inbound -= 1;
     }
+
+    /// Returns the subnet of the ip that connections are limited for.
+    fn subnet(&self, ip: &IpAddr) -> Option<IpNet> {
+        let prefix_len = match ip {
+            IpAddr::V4(_) => self.ipv4_subnet_prefix_len,
+            IpAddr::V6(_) => self.ipv6_subnet_prefix_len,
+        };
+        IpNet::new(*ip, prefix_len).ok().map(|net| net.trunc())
+    }
+
+    /// Returns true if another connection to the ip would exceed the per ip or per subnet limits,
+    /// given the current connection counts.
+    ///
+    /// `counted` is the number of connections in `counts` that belong to the checked connection
+    /// itself, e.g. `1` for a session that is already established.
+    ///
+    /// Ips of local networks are exempt from the limits.
+    fn exceeds_ip_limits(&self, ip: &IpAddr, counts: &IpConnectionCounts, counted: usize) -> bool {
+        if !is_global(ip) {
+            return false
+        }
+        let same_ip = counts.per_ip.get(ip).copied().unwrap_or_default().saturating_sub(counted);
+        let same_subnet = self
+            .subnet(ip)
+            .and_then(|subnet| counts.per_subnet.get(&subnet).copied())
+            .unwrap_or_default()
+            .saturating_sub(counted);
+        same_ip >= self.max_per_ip || same_subnet >= self.max_per_subnet
+    }
+}
+
+/// The number of pending and active sessions per ip and per subnet, see
+/// [`ConnectionInfo::subnet`].
+#[derive(Debug, Default)]
+struct IpConnectionCounts {
+    per_ip: HashMap<IpAddr, usize>,
+    per_subnet: HashMap<IpNet, usize>,
+}
+
+impl IpConnectionCounts {
+    fn inc(&mut self, ip: IpAddr, subnet: Option<IpNet>) {
+        *self.per_ip.entry(ip).or_default() += 1;
+        if let Some(subnet) = subnet {
+            *self.per_subnet.entry(subnet).or_default() += 1;
+        }
+    }
+
+    /// Decrements the counts and removes the entries that dropped to zero.
+    fn dec(&mut self, ip: IpAddr, subnet: Option<IpNet>) {
+        fn dec_entry<K: std::hash::Hash + Eq>(map: &mut HashMap<K, usize>, key: K) {
+            if let Entry::Occupied(mut entry) = map.entry(key) {
+                *entry.get_mut() -= 1;
+                if *entry.get() == 0 {
+                    entry.remove();
+                }
+            }
+        }
+        dec_entry(&mut self.per_ip, ip);
+        if let Some(subnet) = subnet {
+            dec_entry(&mut self.per_subnet, subnet);
+        }
+    }
+}
+
+const fn default_max_per_ip() -> usize {
+    DEFAULT_MAX_PEERS_PER_IP
+}
+
+const fn default_max_per_subnet() -> usize {
+    DEFAULT_MAX_PEERS_PER_SUBNET
+}
+
+const fn default_ipv4_subnet_prefix_len() -> u8 {
+    24
+}
+
+const fn default_ipv6_subnet_prefix_len() -> u8 {
+    56
+}
+
+const fn default_max_concurrent_dials() -> usize {
+    DEFAULT_MAX_CONCURRENT_DIALS
 }
 
 impl Default for ConnectionInfo {
@@ -823,6 +1239,11 @@ impl Default for ConnectionInfo {
             num_inbound: 0,
             max_outbound: DEFAULT_MAX_PEERS_OUTBOUND,
             max_inbound: DEFAULT_MAX_PEERS_INBOUND,
+            max_per_ip: DEFAULT_MAX_PEERS_PER_IP,
+            max_per_subnet: DEFAULT_MAX_PEERS_PER_SUBNET,
+            ipv4_subnet_prefix_len: default_ipv4_subnet_prefix_len(),
+            ipv6_subnet_prefix_len: default_ipv6_subnet_prefix_len(),
+            max_concurrent_dials: DEFAULT_MAX_CONCURRENT_DIALS,
         }
     }
 }
@@ -846,6 +1267,8 @@ pub struct Peer {
     backed_off: bool,
     /// Counts number of times the peer was backed off due to a severe [BackoffKind].
     severe_backoff_counter: u32,
+    /// Whether a session with the peer was established at some point.
+    session_established: bool,
 }
 
 // === impl Peer ===
@@ -869,6 +1292,7 @@ impl Peer {
             kind: Default::default(),
             backed_off: false,
             severe_backoff_counter: 0,
+            session_established: false,
         }
     }
 
@@ -885,7 +1309,12 @@ impl Peer {
     }
 
     /// Applies a reputation change to the peer and returns what action should be taken.
+    ///
+    /// Trusted peers are never slashed.
     fn apply_reputation(&mut self, reputation: i32) -> ReputationChangeOutcome {
+        if self.is_trusted() && reputation < 0 {
+            return ReputationChangeOutcome::None
+        }
         let previous = self.reputation;
         // we add reputation since negative reputation change decrease total reputation
         self.reputation = previous.saturating_add(reputation);
@@ -930,6 +1359,12 @@ impl Peer {
     fn is_trusted(&self) -> bool {
         matches!(self.kind, PeerKind::Trusted)
     }
+
+    /// Returns whether this peer is static
+    #[inline]
+    fn is_static(&self) -> bool {
+        matches!(self.kind, PeerKind::Static)
+    }
 }
 
 /// Outcomes when a reputation change is applied to a peer
@@ -1006,6 +1441,10 @@ pub(crate) enum PeerCommand {
     GetPeer(PeerId, oneshot::Sender<Option<Peer>>),
     /// Get node information on all peers
     GetPeers(oneshot::Sender<Vec<NodeRecord>>),
+    /// Get all banned peers
+    GetBans(oneshot::Sender<Vec<PersistedBan>>),
+    /// Lift all peer bans
+    ClearBans,
 }
 
 /// Actions the peer manager can trigger.
@@ -1049,11 +1488,23 @@ pub struct PeersConfig {
     pub refill_slots_interval: Duration,
     /// Trusted nodes to connect to.
     pub trusted_nodes: HashSet<NodeRecord>,
+    /// Static nodes to connect to, these are redialed whenever the session is dropped.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub static_nodes: HashSet<NodeRecord>,
     /// Connect to trusted nodes only?
     pub connect_trusted_nodes_only: bool,
     /// Basic nodes to connect to.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub basic_nodes: HashSet<NodeRecord>,
+    /// Peers known from a previous run, with their reputation.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub persisted_peers: HashSet<PersistedPeer>,
+    /// Peers banned in a previous run.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub persisted_bans: Vec<PersistedBan>,
+    /// IPs banned in a previous run.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub persisted_ip_bans: Vec<PersistedIpBan>,
     /// How long to ban bad peers.
     #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
     pub ban_duration: Duration,
@@ -1080,8 +1531,12 @@ impl Default for PeersConfig {
             ban_duration: Duration::from_secs(60 * 60 * 12),
             backoff_durations: Default::default(),
             trusted_nodes: Default::default(),
+            static_nodes: Default::default(),
             connect_trusted_nodes_only: false,
             basic_nodes: Default::default(),
+            persisted_peers: Default::default(),
+            persisted_bans: Default::default(),
+            persisted_ip_bans: Default::default(),
         }
     }
 }
@@ -1115,12 +1570,49 @@ impl PeersConfig {
         self
     }
 
+    /// Maximum allowed connections to peers with the same ip.
+    pub fn with_max_per_ip(mut self, max_per_ip: usize) -> Self {
+        self.connection_info.max_per_ip = max_per_ip;
+        self
+    }
+
+    /// Maximum allowed connections to peers of the same subnet.
+    pub fn with_max_per_subnet(mut self, max_per_subnet: usize) -> Self {
+        self.connection_info.max_per_subnet = max_per_subnet;
+        self
+    }
+
+    /// Maximum outbound dials that are in progress at the same time.
+    pub fn with_max_concurrent_dials(mut self, max_concurrent_dials: usize) -> Self {
+        self.connection_info.max_concurrent_dials = max_concurrent_dials;
+        self
+    }
+
+    /// How long to backoff peers we failed to connect to.
+    pub fn with_backoff_durations(mut self, backoff_durations: PeerBackoffDurations) -> Self {
+        self.backoff_durations = backoff_durations;
+        self
+    }
+
+    /// The prefix lengths of the IPv4 and IPv6 subnets connections are limited for.
+    pub fn with_subnet_prefix_lens(mut self, ipv4: u8, ipv6: u8) -> Self {
+        self.connection_info.ipv4_subnet_prefix_len = ipv4;
+        self.connection_info.ipv6_subnet_prefix_len = ipv6;
+        self
+    }
+
     /// Nodes to always connect to.
     pub fn with_trusted_nodes(mut self, nodes: HashSet<NodeRecord>) -> Self {
         self.trusted_nodes = nodes;
         self
     }
 
+    /// Nodes to redial whenever the session to them is dropped.
+    pub fn with_static_nodes(mut self, nodes: HashSet<NodeRecord>) -> Self {
+        self.static_nodes = nodes;
+        self
+    }
+
     /// Connect only to trusted nodes.
     pub fn with_connect_trusted_nodes_only(mut self, trusted_only: bool) -> Self {
         self.connect_trusted_nodes_only = trusted_only;
@@ -1133,7 +1625,28 @@ impl PeersConfig {
         self
     }
 
+    /// Peers known from a previous run, see also [`PersistedPeer`].
+    pub fn with_persisted_peers(mut self, peers: HashSet<PersistedPeer>) -> Self {
+        self.persisted_peers = peers;
+        self
+    }
+
+    /// IP bans from a previous run, see also [`PersistedIpBan`].
+    pub fn with_persisted_ip_bans(mut self, bans: Vec<PersistedIpBan>) -> Self {
+        self.persisted_ip_bans = bans;
+        self
+    }
+
+    /// Bans from a previous run, see also [`PersistedBan`].
+    pub fn with_persisted_bans(mut self, bans: Vec<PersistedBan>) -> Self {
+        self.persisted_bans = bans;
+        self
+    }
+
     /// Read from file nodes available at launch. Ignored if None.
+    ///
+    /// The file contains [`PersistedPeers`] or, if written by an older version, a list of
+    /// [`NodeRecord`]s.
     pub fn with_basic_nodes_from_file(
         self,
         optional_file: Option<impl AsRef<Path>>,
@@ -1147,11 +1660,71 @@ impl PeersConfig {
             Err(e) => Err(e)?,
         };
         info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved peers");
-        let nodes: HashSet<NodeRecord> = serde_json::from_reader(reader)?;
-        Ok(self.with_basic_nodes(nodes))
+        match serde_json::from_reader(reader)? {
+            PersistedPeersFile::Peers(PersistedPeers { peers, bans, ip_bans }) => Ok(self
+                .with_persisted_peers(peers.into_iter().collect())
+                .with_persisted_bans(bans)
+                .with_persisted_ip_bans(ip_bans)),
+            PersistedPeersFile::Nodes(nodes) => Ok(self.with_basic_nodes(nodes)),
+        }
     }
 }
 
+/// The contents of the known peers file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedPeers {
+    /// Peers a session was established with.
+    pub peers: Vec<PersistedPeer>,
+    /// Banned peers.
+    pub bans: Vec<PersistedBan>,
+    /// Banned IPs.
+    pub ip_bans: Vec<PersistedIpBan>,
+}
+
+/// A peer that is written to the known peers file, so it is known again after a restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedPeer {
+    /// The record of the peer.
+    pub node: NodeRecord,
+    /// The reputation of the peer.
+    pub reputation: i32,
+}
+
+/// A banned peer that is written to the known peers file, so it stays banned after a restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedBan {
+    /// The banned peer.
+    pub peer_id: PeerId,
+    /// The unix timestamp in seconds at which the ban expires, `None` if it never expires.
+    pub expires_at: Option<u64>,
+}
+
+/// A banned IP that is written to the known peers file, so it stays banned after a restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedIpBan {
+    /// The banned IP.
+    pub ip: IpAddr,
+    /// The unix timestamp in seconds at which the ban expires, `None` if it never expires.
+    pub expires_at: Option<u64>,
+}
+
+/// The known peers file.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(untagged))]
+enum PersistedPeersFile {
+    Peers(PersistedPeers),
+    /// The list of node records written by older versions.
+    Nodes(HashSet<NodeRecord>),
+}
+
+/// Returns the current unix timestamp in seconds.
+pub(crate) fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
 /// The durations to use when a backoff should be applied to a peer.
 ///
 /// See also [`BackoffKind`](BackoffKind).
@@ -1172,6 +1745,9 @@ pub struct PeerBackoffDurations {
     /// Maximum total backoff duration.
     #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
     pub max: Duration,
+    /// How the backoff grows with the number of severe backoffs of a peer.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub curve: BackoffCurve,
 }
 
 impl PeerBackoffDurations {
@@ -1186,10 +1762,16 @@ impl PeerBackoffDurations {
 
     /// Returns the timestamp until which we should backoff.
     ///
-    /// The Backoff duration is capped by the configured maximum backoff duration.
+    /// The Backoff duration grows with the `backoff_counter` according to the [`BackoffCurve`] and
+    /// is capped by the configured maximum backoff duration.
     pub fn backoff_until(&self, kind: BackoffKind, backoff_counter: u32) -> std::time::Instant {
         let backoff_time = self.backoff(kind);
-        let backoff_time = backoff_time + backoff_time * backoff_counter;
+        let backoff_time = match self.curve {
+            BackoffCurve::Linear => backoff_time + backoff_time * backoff_counter,
+            BackoffCurve::Exponential => {
+                backoff_time.saturating_mul(2u32.saturating_pow(backoff_counter))
+            }
+        };
         let now = std::time::Instant::now();
         now + backoff_time.min(self.max)
     }
@@ -1205,13 +1787,29 @@ impl Default for PeerBackoffDurations {
             high: Duration::from_secs(60 * 15),
             // 1h
             max: Duration::from_secs(60 * 60),
+            curve: Default::default(),
         }
     }
 }
 
+/// How the backoff of a peer grows with the number of times it was backed off for severe
+/// reasons, see [`PeerBackoffDurations::backoff_until`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum BackoffCurve {
+    /// The backoff grows by the base duration: `base * (1 + n)`.
+    #[default]
+    Linear,
+    /// The backoff doubles: `base * 2^n`.
+    Exponential,
+}
+
 #[derive(Debug, Error)]
 pub enum InboundConnectionError {
     ExceedsLimit(usize),
+    /// There are already too many connections to the ip or its subnet.
+    ExceedsIpLimit,
     IpBanned,
 }
 
@@ -1227,12 +1825,12 @@ mod test {
     use crate::{
         error::BackoffKind,
         peers::{
-            manager::{ConnectionInfo, PeerBackoffDurations, PeerConnectionState},
+            manager::{BackoffCurve, ConnectionInfo, PeerBackoffDurations, PeerConnectionState},
             reputation::DEFAULT_REPUTATION,
             PeerAction,
         },
         session::PendingSessionHandshakeError,
-        PeersConfig,
+        PeersConfig, PersistedBan, PersistedIpBan, PersistedPeer, PersistedPeers,
     };
     use reth_discv4::NodeRecord;
     use reth_eth_wire::{
@@ -1241,7 +1839,7 @@ mod test {
     };
     use reth_net_common::ban_list::BanList;
     use reth_network_api::ReputationChangeKind;
-    use reth_primitives::{PeerId, H512};
+    use reth_primitives::{ForkHash, ForkId, PeerId, H512};
     use std::{
         collections::HashSet,
         future::{poll_fn, Future},
@@ -1520,6 +2118,27 @@ mod test {
         assert!(backoff_time.duration_since(now) > backoff_duration);
     }
 
+    #[test]
+    fn test_exponential_backoff() {
+        let durations = PeerBackoffDurations {
+            low: Duration::from_secs(10),
+            max: Duration::from_secs(60),
+            curve: BackoffCurve::Exponential,
+            ..Default::default()
+        };
+        let now = std::time::Instant::now();
+
+        // 10s * 2^2
+        let backoff_time = durations.backoff_until(BackoffKind::Low, 2);
+        assert!(backoff_time.duration_since(now) >= Duration::from_secs(40));
+        assert!(backoff_time.duration_since(now) < Duration::from_secs(41));
+
+        // capped by the maximum
+        let backoff_time = durations.backoff_until(BackoffKind::Low, 40);
+        assert!(backoff_time.duration_since(now) >= Duration::from_secs(60));
+        assert!(backoff_time.duration_since(now) < Duration::from_secs(61));
+    }
+
     #[tokio::test]
     async fn test_ban_on_active_drop() {
         let peer = PeerId::random();
@@ -1639,7 +2258,7 @@ mod test {
 
         assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
         assert_eq!(peers.connection_info.num_inbound, 1);
-        peers.on_incoming_pending_session_rejected_internally();
+        peers.on_incoming_pending_session_rejected_internally(socket_addr.ip());
         assert_eq!(peers.connection_info.num_inbound, 0);
     }
 
@@ -1650,7 +2269,7 @@ mod test {
 
         assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
         assert_eq!(peers.connection_info.num_inbound, 1);
-        peers.on_incoming_pending_session_gracefully_closed();
+        peers.on_incoming_pending_session_gracefully_closed(socket_addr.ip());
         assert_eq!(peers.connection_info.num_inbound, 0);
     }
 
@@ -1864,6 +2483,115 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_ip_limits() {
+        let mut peers =
+            PeersManager::new(PeersConfig::default().with_max_per_ip(1).with_max_per_subnet(2));
+
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        peers.on_incoming_pending_session(ip).unwrap();
+        peers.on_incoming_session_established(PeerId::random(), SocketAddr::new(ip, 30303));
+        assert!(matches!(
+            peers.on_incoming_pending_session(ip),
+            Err(super::InboundConnectionError::ExceedsIpLimit)
+        ));
+
+        // same /24 subnet
+        let neighbour = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 5));
+        peers.on_incoming_pending_session(neighbour).unwrap();
+        peers.on_incoming_session_established(PeerId::random(), SocketAddr::new(neighbour, 30303));
+        let third = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 6));
+        assert!(matches!(
+            peers.on_incoming_pending_session(third),
+            Err(super::InboundConnectionError::ExceedsIpLimit)
+        ));
+
+        // the subnet is not dialed either
+        let candidate = PeerId::random();
+        peers.add_peer(candidate, SocketAddr::new(third, 30303), None);
+        assert!(peers.best_unconnected().is_none());
+
+        // trusted peers are exempt
+        peers.add_trusted_peer(candidate, SocketAddr::new(third, 30303));
+        assert_eq!(peers.best_unconnected().map(|(peer_id, _)| peer_id), Some(candidate));
+        assert!(peers.on_incoming_pending_session(third).is_ok());
+
+        // local networks are exempt
+        let local = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+        for _ in 0..3 {
+            peers.on_incoming_pending_session(local).unwrap();
+            peers.on_incoming_session_established(PeerId::random(), SocketAddr::new(local, 30303));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ip_limits_pending_sessions() {
+        let mut peers = PeersManager::new(PeersConfig::default().with_max_per_ip(1));
+
+        // parallel pending sessions from the same ip are rejected
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        peers.on_incoming_pending_session(ip).unwrap();
+        assert!(matches!(
+            peers.on_incoming_pending_session(ip),
+            Err(super::InboundConnectionError::ExceedsIpLimit)
+        ));
+
+        // the count is released with the pending session
+        peers.on_incoming_pending_session_gracefully_closed(ip);
+        assert!(peers.ip_connections.per_ip.is_empty());
+        assert!(peers.ip_connections.per_subnet.is_empty());
+
+        // and with the established session
+        let peer_id = PeerId::random();
+        peers.on_incoming_pending_session(ip).unwrap();
+        peers.on_incoming_session_established(peer_id, SocketAddr::new(ip, 30303));
+        assert!(peers.on_incoming_pending_session(ip).is_err());
+        peers.on_active_session_gracefully_closed(peer_id);
+        assert!(peers.ip_connections.per_ip.is_empty());
+        assert!(peers.on_incoming_pending_session(ip).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_ip_limits_recheck_on_established() {
+        let mut peers = PeersManager::new(PeersConfig::default().with_max_per_ip(1));
+
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        peers.on_incoming_pending_session(ip).unwrap();
+        // another session to the same ip was counted in the meantime
+        peers.inc_ip(ip);
+
+        let peer_id = PeerId::random();
+        peers.on_incoming_session_established(peer_id, SocketAddr::new(ip, 30303));
+        assert!(peers.queued_actions.iter().any(|action| matches!(
+            action,
+            PeerAction::Disconnect { peer_id: id, reason: Some(DisconnectReason::TooManyPeers) }
+                if *id == peer_id
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_denied_networks() {
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3));
+        let socket_addr = SocketAddr::new(ip, 8008);
+        let ban_list = BanList::default().with_denied_networks(["10.0.0.0/8".parse().unwrap()]);
+        let static_node = NodeRecord::new(socket_addr, PeerId::random());
+        let config = PeersConfig::default()
+            .with_ban_list(ban_list)
+            .with_static_nodes(HashSet::from([static_node]));
+        let mut peer_manager = PeersManager::new(config);
+
+        // never dialed
+        assert_eq!(peer_manager.num_known_peers(), 0);
+        peer_manager.add_peer(PeerId::random(), socket_addr, None);
+        assert_eq!(peer_manager.num_known_peers(), 0);
+
+        // never accepted
+        assert!(matches!(
+            peer_manager.on_incoming_pending_session(ip),
+            Err(super::InboundConnectionError::IpBanned {})
+        ));
+    }
+
     #[tokio::test]
     async fn test_on_active_inbound_ban_list() {
         let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2));
@@ -1955,6 +2683,73 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_peers_on_local_fork_are_prioritized() {
+        let fork_id = ForkId { hash: ForkHash([0xfc, 0x64, 0xec, 0x04]), next: 1150000 };
+        let mut peers = PeersManager::new(PeersConfig::default().with_max_outbound(1));
+        peers.set_local_fork_id(fork_id);
+
+        let other_fork = PeerId::random();
+        let other_fork_sock = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        peers.add_peer(
+            other_fork,
+            other_fork_sock,
+            Some(ForkId { hash: ForkHash([0xde, 0xad, 0xbe, 0xef]), next: 0 }),
+        );
+        let local_fork = PeerId::random();
+        let local_fork_sock = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 3)), 8008);
+        peers.add_peer(local_fork, local_fork_sock, Some(fork_id));
+
+        // the peer on the local fork is preferred, even with a lower reputation
+        peers.peers.get_mut(&other_fork).unwrap().reputation = DEFAULT_REPUTATION + 100;
+
+        for _ in 0..2 {
+            assert!(matches!(event!(peers), PeerAction::PeerAdded(_)));
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, remote_addr } => {
+                assert_eq!(peer_id, local_fork);
+                assert_eq!(remote_addr, local_fork_sock);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_dials() {
+        let mut peers = PeersManager::new(PeersConfig::default().with_max_concurrent_dials(1));
+
+        let first = PeerId::random();
+        peers.add_peer(first, SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008), None);
+        let second = PeerId::random();
+        peers.add_peer(
+            second,
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 3)), 8008),
+            None,
+        );
+
+        for _ in 0..2 {
+            assert!(matches!(event!(peers), PeerAction::PeerAdded(_)));
+        }
+        let dialed = match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => peer_id,
+            _ => unreachable!(),
+        };
+
+        // no further dial until the pending one resolved
+        peers.fill_outbound_slots();
+        assert!(peers.queued_actions.is_empty());
+
+        peers.on_outgoing_session_established(&dialed);
+        peers.fill_outbound_slots();
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_ne!(peer_id, dialed);
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[tokio::test]
     async fn test_connect_trusted_nodes_only() {
         let trusted_peer = PeerId::random();
@@ -1993,6 +2788,171 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_trusted_peers_are_not_slashed() {
+        let trusted_peer = PeerId::random();
+        let trusted_sock = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let config = PeersConfig::default()
+            .with_max_outbound(0)
+            .with_trusted_nodes(HashSet::from([NodeRecord::new(trusted_sock, trusted_peer)]));
+        let mut peers = PeersManager::new(config);
+
+        // trusted peers are dialed even if there are no free slots
+        peers.fill_outbound_slots();
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => assert_eq!(peer_id, trusted_peer),
+            _ => unreachable!(),
+        }
+
+        peers.apply_reputation_change(&trusted_peer, ReputationChangeKind::BadProtocol);
+        assert_eq!(peers.get_reputation(&trusted_peer), Some(DEFAULT_REPUTATION));
+
+        // fatal errors don't remove the peer, it is backed off instead
+        peers.on_active_session_dropped(
+            &trusted_sock,
+            &trusted_peer,
+            &EthStreamError::P2PStreamError(P2PStreamError::Disconnected(
+                DisconnectReason::UselessPeer,
+            )),
+        );
+        let peer = peers.peers.get(&trusted_peer).unwrap();
+        assert!(peer.is_backed_off());
+        assert!(!peer.is_banned());
+    }
+
+    #[tokio::test]
+    async fn test_static_peers_are_redialed() {
+        let static_peer = PeerId::random();
+        let static_sock = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let config = PeersConfig::default()
+            .with_static_nodes(HashSet::from([NodeRecord::new(static_sock, static_peer)]));
+        let mut peers = PeersManager::new(config);
+
+        peers.fill_outbound_slots();
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => assert_eq!(peer_id, static_peer),
+            _ => unreachable!(),
+        }
+
+        // static peers are not removed when discovery drops them
+        peers.remove_peer(static_peer);
+        assert!(peers.peers.contains_key(&static_peer));
+
+        peers.on_active_session_gracefully_closed(static_peer);
+        let peer = peers.peers.get(&static_peer).unwrap();
+        assert_eq!(peer.state, PeerConnectionState::Idle);
+        assert!(peer.is_backed_off());
+
+        // the peer is redialed once the backoff expired
+        peers.peers.get_mut(&static_peer).unwrap().backed_off = false;
+        peers.fill_outbound_slots();
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => assert_eq!(peer_id, static_peer),
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ban_peer_and_disconnect() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::default();
+        peers.add_peer(peer, socket_addr, None);
+
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => assert_eq!(peer_id, peer),
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => assert_eq!(peer_id, peer),
+            _ => unreachable!(),
+        }
+
+        peers.ban_peer_and_disconnect(peer);
+        match event!(peers) {
+            PeerAction::Disconnect { peer_id, .. } => assert_eq!(peer_id, peer),
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::BanPeer { peer_id } => assert_eq!(peer_id, peer),
+            _ => unreachable!(),
+        }
+        assert!(peers.ban_list.is_banned_peer(&peer));
+        assert!(peers.peers.get(&peer).unwrap().is_banned());
+
+        peers.clear_bans();
+        match event!(peers) {
+            PeerAction::UnBanPeer { peer_id } => assert_eq!(peer_id, peer),
+            _ => unreachable!(),
+        }
+        assert!(!peers.ban_list.is_banned_peer(&peer));
+        assert!(!peers.peers.get(&peer).unwrap().is_banned());
+    }
+
+    #[tokio::test]
+    async fn test_persisted_bans() {
+        let now = super::unix_timestamp();
+        let permanent = PersistedBan { peer_id: PeerId::random(), expires_at: None };
+        let temporary = PersistedBan { peer_id: PeerId::random(), expires_at: Some(now + 600) };
+        let expired = PersistedBan { peer_id: PeerId::random(), expires_at: Some(now - 1) };
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let ip_ban = PersistedIpBan { ip, expires_at: Some(now + 600) };
+        let config = PeersConfig::default()
+            .with_persisted_bans(vec![permanent, temporary, expired])
+            .with_persisted_ip_bans(vec![ip_ban]);
+        let mut peers = PeersManager::new(config);
+
+        assert!(peers.ban_list.is_banned_peer(&permanent.peer_id));
+        assert!(peers.ban_list.is_banned_peer(&temporary.peer_id));
+        assert!(!peers.ban_list.is_banned_peer(&expired.peer_id));
+
+        let mut bans = peers.persisted_bans().collect::<Vec<_>>();
+        assert_eq!(bans.len(), 2);
+        bans.retain(|ban| ban.peer_id == temporary.peer_id);
+        let expires_at = bans[0].expires_at.unwrap();
+        assert!(expires_at.abs_diff(now + 600) <= 1);
+
+        assert!(peers.ban_list.is_banned_ip(&ip));
+        let ip_bans = peers.persisted_ip_bans().collect::<Vec<_>>();
+        assert_eq!(ip_bans.len(), 1);
+        assert!(ip_bans[0].expires_at.unwrap().abs_diff(now + 600) <= 1);
+
+        // clearing the bans lifts both peer and IP bans
+        peers.clear_bans();
+        assert_eq!(peers.persisted_bans().count(), 0);
+        assert_eq!(peers.persisted_ip_bans().count(), 0);
+        assert!(!peers.ban_list.is_banned_ip(&ip));
+    }
+
+    #[test]
+    fn test_parse_persisted_peers_file() {
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8009);
+        let persisted = PersistedPeers {
+            peers: vec![PersistedPeer {
+                node: NodeRecord::new(addr, PeerId::random()),
+                reputation: -100,
+            }],
+            bans: vec![PersistedBan { peer_id: PeerId::random(), expires_at: Some(1_700_000_000) }],
+            ip_bans: vec![PersistedIpBan {
+                ip: IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)),
+                expires_at: None,
+            }],
+        };
+        let json = serde_json::to_string(&persisted).unwrap();
+        match serde_json::from_str(&json).unwrap() {
+            super::PersistedPeersFile::Peers(peers) => assert_eq!(peers, persisted),
+            super::PersistedPeersFile::Nodes(_) => unreachable!(),
+        }
+
+        // the format of older versions
+        let nodes = HashSet::from([persisted.peers[0].node]);
+        let json = serde_json::to_string(&nodes).unwrap();
+        match serde_json::from_str(&json).unwrap() {
+            super::PersistedPeersFile::Nodes(parsed) => assert_eq!(parsed, nodes),
+            super::PersistedPeersFile::Peers(_) => unreachable!(),
+        }
+    }
+
     #[tokio::test]
     async fn test_tick() {
         let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2));
@@ -2063,4 +3023,42 @@ mod test {
         assert_eq!(peer.state, PeerConnectionState::Idle);
         assert!(!peer.remove_after_disconnect);
     }
+
+    #[tokio::test]
+    async fn test_persisted_peers() {
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8009);
+        let persisted =
+            PersistedPeer { node: NodeRecord::new(addr, PeerId::random()), reputation: -100 };
+        let basic = NodeRecord::new(addr, PeerId::random());
+        let config = PeersConfig::default()
+            .with_persisted_peers(HashSet::from([persisted]))
+            .with_basic_nodes(HashSet::from([basic]));
+        let mut peers = PeersManager::new(config);
+        assert_eq!(peers.get_reputation(&persisted.node.id), Some(-100));
+        assert_eq!(peers.persisted_peers().collect::<Vec<_>>(), vec![persisted]);
+
+        // peers are persisted once a session was established
+        peers.on_outgoing_session_established(&basic.id);
+        assert_eq!(peers.persisted_peers().count(), 2);
+
+        // incoming peers are not persisted, their listening port is unknown
+        let incoming = PeerId::random();
+        peers.on_incoming_pending_session(addr.ip()).unwrap();
+        peers.on_incoming_session_established(incoming, addr);
+        assert!(!peers.persisted_peers().any(|peer| peer.node.id == incoming));
+    }
+
+    #[test]
+    fn test_parse_persisted_peers() {
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8009);
+        let persisted =
+            PersistedPeer { node: NodeRecord::new(addr, PeerId::random()), reputation: 1024 };
+        let json = serde_json::to_string(&persisted).unwrap();
+        let entries: Vec<super::PersistedPeerEntry> =
+            serde_json::from_str(&format!("[{json}, \"{}\"]", persisted.node)).unwrap();
+        assert!(matches!(entries[0], super::PersistedPeerEntry::Peer(peer) if peer == persisted));
+        assert!(
+            matches!(entries[1], super::PersistedPeerEntry::Node(node) if node == persisted.node)
+        );
+    }
 }
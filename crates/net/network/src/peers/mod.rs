@@ -3,8 +3,11 @@
 mod manager;
 mod reputation;
 
-pub(crate) use manager::{InboundConnectionError, PeerAction, PeersManager};
-pub use manager::{Peer, PeersConfig, PeersHandle};
+pub(crate) use manager::{unix_timestamp, InboundConnectionError, PeerAction, PeersManager};
+pub use manager::{
+    BackoffCurve, Peer, PeerBackoffDurations, PeersConfig, PeersHandle, PersistedBan,
+    PersistedIpBan, PersistedPeer, PersistedPeers,
+};
 pub use reputation::ReputationChangeWeights;
 pub use reth_network_api::PeerKind;
 
@@ -13,3 +16,12 @@ pub(crate) const DEFAULT_MAX_PEERS_OUTBOUND: usize = 100;
 
 /// Maximum number of available slots for inbound sessions.
 pub(crate) const DEFAULT_MAX_PEERS_INBOUND: usize = 30;
+
+/// Maximum number of sessions to peers with the same ip.
+pub(crate) const DEFAULT_MAX_PEERS_PER_IP: usize = 4;
+
+/// Maximum number of sessions to peers of the same subnet.
+pub(crate) const DEFAULT_MAX_PEERS_PER_SUBNET: usize = 10;
+
+/// Maximum number of outbound dials that are in progress at the same time.
+pub(crate) const DEFAULT_MAX_CONCURRENT_DIALS: usize = 30;
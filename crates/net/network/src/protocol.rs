@@ -0,0 +1,172 @@
+//! Support for custom RLPx subprotocols that are multiplexed over the sessions' connections.
+//!
+//! A subprotocol is registered with [`NetworkBuilder::add_rlpx_protocol`](crate::NetworkBuilder).
+//! Its capability is announced in the `Hello` message and, if the remote peer shares it, the
+//! protocol's messages are exchanged next to `eth` over the same connection.
+
+use reth_eth_wire::capability::{Capability, Protocol, RawCapabilityMessage, SharedCapability};
+use reth_primitives::PeerId;
+use std::{
+    fmt,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::sync::{mpsc, mpsc::error::TrySendError};
+
+/// The number of messages a [`ProtocolConnection`] can queue until the session sent them.
+const PROTOCOL_MESSAGE_BUFFER: usize = 64;
+
+/// Handles the messages of a custom RLPx subprotocol.
+///
+/// All functions are invoked from the session's task and should not block.
+pub trait RlpxSubProtocolHandler: fmt::Debug + Send + Sync + 'static {
+    /// Invoked when a session with a peer that shares the protocol was established.
+    ///
+    /// The [`ProtocolConnection`] can be used to send messages to the peer for as long as the
+    /// session is active.
+    fn on_connection_established(&self, peer_id: PeerId, conn: ProtocolConnection);
+
+    /// Invoked for every message of the protocol that was received from the peer.
+    ///
+    /// The message id is relative to the protocol, as if it were the only capability.
+    fn on_message(&self, peer_id: PeerId, msg: RawCapabilityMessage);
+
+    /// Invoked when the session with the peer was closed.
+    fn on_connection_closed(&self, _peer_id: PeerId) {}
+}
+
+/// The sending half of a custom subprotocol's connection with a peer.
+#[derive(Debug, Clone)]
+pub struct ProtocolConnection {
+    /// The negotiated capability.
+    capability: Capability,
+    /// Messages that should be sent to the peer by the session.
+    to_session: mpsc::Sender<RawCapabilityMessage>,
+}
+
+// === impl ProtocolConnection ===
+
+impl ProtocolConnection {
+    /// Returns the negotiated capability of this connection.
+    pub fn capability(&self) -> &Capability {
+        &self.capability
+    }
+
+    /// Queues a message that is sent to the peer, waiting until the session has room for it.
+    ///
+    /// The message id is relative to the protocol. Returns the message if the session is already
+    /// closed.
+    pub async fn send(&self, msg: RawCapabilityMessage) -> Result<(), RawCapabilityMessage> {
+        self.to_session.send(msg).await.map_err(|err| err.0)
+    }
+
+    /// Queues a message that is sent to the peer, if the session has room for it.
+    ///
+    /// The message id is relative to the protocol.
+    pub fn try_send(
+        &self,
+        msg: RawCapabilityMessage,
+    ) -> Result<(), TrySendError<RawCapabilityMessage>> {
+        self.to_session.try_send(msg)
+    }
+
+    /// Returns `true` if the session with the peer is closed.
+    pub fn is_closed(&self) -> bool {
+        self.to_session.is_closed()
+    }
+}
+
+/// A registered subprotocol.
+#[derive(Debug, Clone)]
+struct RlpxSubProtocol {
+    protocol: Protocol,
+    handler: Arc<dyn RlpxSubProtocolHandler>,
+}
+
+/// All custom subprotocols that are registered with the network.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RlpxSubProtocols {
+    protocols: Vec<RlpxSubProtocol>,
+}
+
+// === impl RlpxSubProtocols ===
+
+impl RlpxSubProtocols {
+    /// Registers a new subprotocol, replacing any protocol with the same capability.
+    pub(crate) fn push(&mut self, protocol: Protocol, handler: Arc<dyn RlpxSubProtocolHandler>) {
+        self.protocols.retain(|p| p.protocol.cap != protocol.cap);
+        self.protocols.push(RlpxSubProtocol { protocol, handler });
+    }
+
+    /// Returns the [`Protocol`]s that should be negotiated during the handshake.
+    pub(crate) fn protocols(&self) -> Vec<Protocol> {
+        self.protocols.iter().map(|p| p.protocol.clone()).collect()
+    }
+
+    /// Notifies the handlers of all negotiated capabilities about the new session and returns the
+    /// protocols the session needs to drive.
+    pub(crate) fn on_session_established(
+        &self,
+        peer_id: PeerId,
+        shared: &[SharedCapability],
+    ) -> Vec<ActiveProtocol> {
+        shared
+            .iter()
+            .filter_map(|cap| {
+                let capability = Capability::new(cap.name().into(), cap.version() as usize);
+                let handler = self
+                    .protocols
+                    .iter()
+                    .find(|p| p.protocol.cap == capability)
+                    .map(|p| Arc::clone(&p.handler))?;
+                let (to_session, from_handler) = mpsc::channel(PROTOCOL_MESSAGE_BUFFER);
+                handler.on_connection_established(
+                    peer_id,
+                    ProtocolConnection { capability: capability.clone(), to_session },
+                );
+                Some(ActiveProtocol { peer_id, capability, handler, from_handler })
+            })
+            .collect()
+    }
+}
+
+/// A negotiated subprotocol of an active session.
+///
+/// Notifies the handler once dropped, which happens when the session is closed.
+#[derive(Debug)]
+pub(crate) struct ActiveProtocol {
+    peer_id: PeerId,
+    capability: Capability,
+    handler: Arc<dyn RlpxSubProtocolHandler>,
+    /// Messages the handler wants to send to the peer.
+    from_handler: mpsc::Receiver<RawCapabilityMessage>,
+}
+
+// === impl ActiveProtocol ===
+
+impl ActiveProtocol {
+    /// The name of the negotiated capability.
+    pub(crate) fn name(&self) -> &str {
+        &self.capability.name
+    }
+
+    /// Forwards a message received from the peer to the handler.
+    pub(crate) fn on_message(&self, msg: RawCapabilityMessage) {
+        self.handler.on_message(self.peer_id, msg)
+    }
+
+    /// Polls the next message the handler wants to send to the peer.
+    pub(crate) fn poll_outgoing(&mut self, cx: &mut Context<'_>) -> Poll<RawCapabilityMessage> {
+        match self.from_handler.poll_recv(cx) {
+            Poll::Ready(Some(msg)) => Poll::Ready(msg),
+            // the handler dropped all connections, so there's nothing left to send
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for ActiveProtocol {
+    fn drop(&mut self) {
+        self.handler.on_connection_closed(self.peer_id)
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::{
     message::{NewBlockMessage, PeerMessage, PeerRequest, PeerResponse, PeerResponseResult},
+    protocol::ActiveProtocol,
     session::{
         config::INITIAL_REQUEST_TIMEOUT,
         handle::{ActiveSessionMessage, SessionCommand},
@@ -13,14 +14,14 @@ use fnv::FnvHashMap;
 use futures::{stream::Fuse, SinkExt, StreamExt};
 use reth_ecies::stream::ECIESStream;
 use reth_eth_wire::{
-    capability::Capabilities,
+    capability::{Capabilities, RawCapabilityMessage},
     errors::{EthHandshakeError, EthStreamError, P2PStreamError},
     message::{EthBroadcastMessage, RequestPair},
     DisconnectReason, EthMessage, EthStream, P2PStream,
 };
 use reth_interfaces::p2p::error::RequestError;
 use reth_metrics_common::metered_sender::MeteredSender;
-use reth_net_common::bandwidth_meter::MeteredStream;
+use reth_net_common::{bandwidth_limiter::ThrottledStream, bandwidth_meter::MeteredStream};
 use reth_primitives::PeerId;
 use std::{
     collections::VecDeque,
@@ -64,7 +65,9 @@ pub(crate) struct ActiveSession {
     /// Keeps track of request ids.
     pub(crate) next_id: u64,
     /// The underlying connection.
-    pub(crate) conn: EthStream<P2PStream<ECIESStream<MeteredStream<TcpStream>>>>,
+    pub(crate) conn: EthStream<P2PStream<ECIESStream<MeteredStream<ThrottledStream<TcpStream>>>>>,
+    /// Custom subprotocols that are multiplexed over the connection.
+    pub(crate) protocols: Vec<ActiveProtocol>,
     /// Identifier of the node we're connected to.
     pub(crate) remote_peer_id: PeerId,
     /// The address we're connected to.
@@ -85,6 +88,10 @@ pub(crate) struct ActiveSession {
     pub(crate) inflight_requests: FnvHashMap<u64, InflightRequest>,
     /// All requests that were sent by the remote peer.
     pub(crate) received_requests_from_remote: Vec<ReceivedRequest>,
+    /// The maximum number of requests of the remote peer that are served at the same time.
+    ///
+    /// No messages are read from the connection while the limit is reached.
+    pub(crate) max_concurrent_requests_from_remote: usize,
     /// Buffered messages that should be handled and sent to the peer.
     pub(crate) queued_outgoing: VecDeque<OutgoingMessage>,
     /// The maximum time we wait for a response from a peer.
@@ -94,6 +101,8 @@ pub(crate) struct ActiveSession {
     /// If an [ActiveSession] does not receive a response at all within this duration then it is
     /// considered a protocol violation and the session will initiate a drop.
     pub(crate) protocol_breach_request_timeout: Duration,
+    /// The reason we sent to the peer if we initiated the disconnect.
+    pub(crate) local_disconnect_reason: Option<DisconnectReason>,
 }
 
 impl ActiveSession {
@@ -109,6 +118,31 @@ impl ActiveSession {
         id
     }
 
+    /// Forwards all buffered messages of custom subprotocols to their handlers.
+    ///
+    /// Returns `true` if any message was handled.
+    fn on_incoming_protocol_messages(&mut self) -> bool {
+        let mut progress = false;
+        while let Some((name, msg)) = self.conn.inner_mut().take_capability_message() {
+            progress = true;
+            if let Some(protocol) = self.protocols.iter().find(|p| p.name() == name) {
+                protocol.on_message(msg);
+            }
+        }
+        progress
+    }
+
+    /// Returns the next message a custom subprotocol wants to send to the peer.
+    fn poll_outgoing_protocol_message(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Option<(String, RawCapabilityMessage)> {
+        self.protocols.iter_mut().find_map(|protocol| match protocol.poll_outgoing(cx) {
+            Poll::Ready(msg) => Some((protocol.name().to_string(), msg)),
+            Poll::Pending => None,
+        })
+    }
+
     /// Handle a message read from the connection.
     ///
     /// Returns an error if the message is considered to be in violation of the protocol.
@@ -355,6 +389,7 @@ impl ActiveSession {
         let _ = self.to_session.clone().try_send(ActiveSessionMessage::Disconnected {
             peer_id: self.remote_peer_id,
             remote_addr: self.remote_addr,
+            reason: self.local_disconnect_reason,
         });
     }
 
@@ -374,7 +409,9 @@ impl ActiveSession {
             .inner_mut()
             .start_disconnect(reason)
             .map_err(P2PStreamError::from)
-            .map_err(Into::into)
+            .map_err(EthStreamError::from)?;
+        self.local_disconnect_reason = Some(reason);
+        Ok(())
     }
 
     /// Flushes the disconnect message and emits the corresponding message
@@ -524,6 +561,13 @@ impl Future for ActiveSession {
                         this.close_on_error(err);
                         return Poll::Ready(())
                     }
+                } else if let Some((name, msg)) = this.poll_outgoing_protocol_message(cx) {
+                    // the sink is ready, so there's room for the message
+                    progress = true;
+                    if let Err(err) = this.conn.inner_mut().start_send_capability(&name, msg) {
+                        // the handler sent a message that doesn't belong to its protocol
+                        debug!(target: "net::session", ?err, %name, remote_peer_id=?this.remote_peer_id, "dropping invalid protocol message");
+                    }
                 } else {
                     // no more messages to send over the wire
                     break
@@ -551,7 +595,22 @@ impl Future for ActiveSession {
                     }
                 }
 
-                match this.conn.poll_next_unpin(cx) {
+                // apply backpressure until the responses to the peer's requests are ready, the
+                // pending responses are polled above and wake the session once ready
+                if this.received_requests_from_remote.len() >=
+                    this.max_concurrent_requests_from_remote
+                {
+                    break 'receive
+                }
+
+                let next = this.conn.poll_next_unpin(cx);
+
+                // messages of custom subprotocols are buffered by the stream while reading
+                if this.on_incoming_protocol_messages() {
+                    progress = true;
+                }
+
+                match next {
                     Poll::Pending => break,
                     Poll::Ready(None) => {
                         if this.is_disconnecting() {
@@ -709,13 +768,17 @@ mod tests {
 
     use super::*;
     use crate::session::{
-        config::{INITIAL_REQUEST_TIMEOUT, PROTOCOL_BREACH_REQUEST_TIMEOUT},
+        config::{
+            INITIAL_REQUEST_TIMEOUT, MAX_CONCURRENT_REQUESTS_FROM_REMOTE,
+            PROTOCOL_BREACH_REQUEST_TIMEOUT,
+        },
         handle::PendingSessionEvent,
         start_pending_incoming_session,
     };
     use reth_ecies::util::pk2id;
     use reth_eth_wire::{
-        GetBlockBodies, HelloMessage, Status, StatusBuilder, UnauthedEthStream, UnauthedP2PStream,
+        GetBlockBodies, HelloMessage, PingConfig, Status, StatusBuilder, UnauthedEthStream,
+        UnauthedP2PStream,
     };
     use reth_net_common::bandwidth_meter::BandwidthMeter;
     use reth_primitives::{ForkFilter, Hardfork, MAINNET};
@@ -784,8 +847,10 @@ mod tests {
             let session_id = self.next_id();
             let (_disconnect_tx, disconnect_rx) = oneshot::channel();
             let (pending_sessions_tx, pending_sessions_rx) = mpsc::channel(1);
-            let metered_stream =
-                MeteredStream::new_with_meter(stream, self.bandwidth_meter.clone());
+            let metered_stream = MeteredStream::new_with_meter(
+                ThrottledStream::new(stream, None, None),
+                self.bandwidth_meter.clone(),
+            );
 
             tokio::task::spawn(start_pending_incoming_session(
                 disconnect_rx,
@@ -795,6 +860,8 @@ mod tests {
                 remote_addr,
                 self.secret_key,
                 self.hello.clone(),
+                Vec::new(),
+                PingConfig::default(),
                 self.status,
                 self.fork_filter.clone(),
             ));
@@ -830,8 +897,10 @@ mod tests {
                         internal_request_tx: ReceiverStream::new(messages_rx).fuse(),
                         inflight_requests: Default::default(),
                         conn,
+                        protocols: Vec::new(),
                         queued_outgoing: Default::default(),
                         received_requests_from_remote: Default::default(),
+                        max_concurrent_requests_from_remote: MAX_CONCURRENT_REQUESTS_FROM_REMOTE,
                         internal_request_timeout_interval: tokio::time::interval(
                             INITIAL_REQUEST_TIMEOUT,
                         ),
@@ -839,6 +908,7 @@ mod tests {
                             INITIAL_REQUEST_TIMEOUT.as_millis() as u64,
                         )),
                         protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
+                        local_disconnect_reason: None,
                     }
                 }
                 ev => {
@@ -4,6 +4,7 @@ use crate::{
     peers::{DEFAULT_MAX_PEERS_INBOUND, DEFAULT_MAX_PEERS_OUTBOUND},
     session::{Direction, ExceedsSessionLimit},
 };
+use reth_eth_wire::PingConfig;
 use std::time::Duration;
 
 /// Default request timeout for a single request.
@@ -16,6 +17,14 @@ pub const INITIAL_REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
 /// This is the time a peer has to answer a response.
 pub const PROTOCOL_BREACH_REQUEST_TIMEOUT: Duration = Duration::from_secs(2 * 60);
 
+/// Default timeout for establishing the tcp connection of an outbound session.
+pub const DIAL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Default maximum number of requests of a peer that are served at the same time.
+///
+/// Messages of the peer are not read while the limit is reached.
+pub const MAX_CONCURRENT_REQUESTS_FROM_REMOTE: usize = 8;
+
 /// Configuration options when creating a [SessionManager](crate::session::SessionManager).
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -37,6 +46,25 @@ pub struct SessionsConfig {
     /// `PROTOCOL_BREACH_REQUEST_TIMEOUT`) this is considered a protocol violation and results in a
     /// dropped session.
     pub protocol_breach_request_timeout: Duration,
+    /// The `p2p` keepalive of the sessions.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ping: PingConfig,
+    /// How long to wait for the tcp connection of an outbound session to be established.
+    #[cfg_attr(feature = "serde", serde(default = "default_dial_timeout"))]
+    pub dial_timeout: Duration,
+    /// The maximum number of requests of a peer that are served at the same time.
+    #[cfg_attr(feature = "serde", serde(default = "default_max_concurrent_requests_from_remote"))]
+    pub max_concurrent_requests_from_remote: usize,
+    /// The maximum number of bytes per second that are downloaded across all sessions.
+    ///
+    /// By default, or if zero, the download bandwidth isn't limited.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_download_rate: Option<u64>,
+    /// The maximum number of bytes per second that are uploaded across all sessions.
+    ///
+    /// By default, or if zero, the upload bandwidth isn't limited.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_upload_rate: Option<u64>,
 }
 
 impl Default for SessionsConfig {
@@ -55,6 +83,11 @@ impl Default for SessionsConfig {
             limits: Default::default(),
             initial_internal_request_timeout: INITIAL_REQUEST_TIMEOUT,
             protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
+            ping: PingConfig::default(),
+            dial_timeout: DIAL_TIMEOUT,
+            max_concurrent_requests_from_remote: MAX_CONCURRENT_REQUESTS_FROM_REMOTE,
+            max_download_rate: None,
+            max_upload_rate: None,
         }
     }
 }
@@ -69,6 +102,57 @@ impl SessionsConfig {
         self.session_event_buffer = n;
         self
     }
+
+    /// Sets the maximum initial time we wait for a response from the peer before we timeout a
+    /// request _internally_.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.initial_internal_request_timeout = timeout;
+        self
+    }
+
+    /// Sets the `p2p` keepalive of the sessions.
+    pub fn with_ping_config(mut self, ping: PingConfig) -> Self {
+        self.ping = ping;
+        self
+    }
+
+    /// Sets how long to wait for the tcp connection of an outbound session to be established.
+    pub fn with_dial_timeout(mut self, timeout: Duration) -> Self {
+        self.dial_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum number of requests of a peer that are served at the same time.
+    pub fn with_max_concurrent_requests_from_remote(mut self, max: usize) -> Self {
+        self.max_concurrent_requests_from_remote = max;
+        self
+    }
+
+    /// Sets the maximum number of bytes per second that are downloaded across all sessions.
+    ///
+    /// A rate of zero disables the limit.
+    pub fn with_max_download_rate(mut self, bytes_per_sec: u64) -> Self {
+        self.max_download_rate = Some(bytes_per_sec);
+        self
+    }
+
+    /// Sets the maximum number of bytes per second that are uploaded across all sessions.
+    ///
+    /// A rate of zero disables the limit.
+    pub fn with_max_upload_rate(mut self, bytes_per_sec: u64) -> Self {
+        self.max_upload_rate = Some(bytes_per_sec);
+        self
+    }
+}
+
+#[cfg(feature = "serde")]
+const fn default_dial_timeout() -> Duration {
+    DIAL_TIMEOUT
+}
+
+#[cfg(feature = "serde")]
+const fn default_max_concurrent_requests_from_remote() -> usize {
+    MAX_CONCURRENT_REQUESTS_FROM_REMOTE
 }
 
 /// Limits for sessions.
@@ -9,9 +9,15 @@ use reth_eth_wire::{
     errors::EthStreamError,
     DisconnectReason, EthStream, EthVersion, P2PStream, Status,
 };
-use reth_net_common::bandwidth_meter::MeteredStream;
-use reth_primitives::PeerId;
-use std::{io, net::SocketAddr, sync::Arc, time::Instant};
+use reth_net_common::{
+    bandwidth_limiter::ThrottledStream,
+    bandwidth_meter::{BandwidthMeter, MeteredStream},
+};
+use reth_primitives::{NodeRecord, PeerId};
+use reth_rpc_types::{
+    ConnectedPeer, ConnectedPeerNetwork, ConnectedPeerProtocols, EthPeerInfo, PeerBandwidth,
+};
+use std::{collections::BTreeMap, io, net::SocketAddr, sync::Arc, time::Instant};
 use tokio::{
     net::TcpStream,
     sync::{mpsc, oneshot},
@@ -65,6 +71,12 @@ pub(crate) struct ActiveSessionHandle {
     pub(crate) client_version: Arc<String>,
     /// The address we're connected to
     pub(crate) remote_addr: SocketAddr,
+    /// The `Status` message the peer sent during the handshake.
+    pub(crate) status: Status,
+    /// Meters all bytes of the connection.
+    pub(crate) bandwidth_meter: BandwidthMeter,
+    /// Meters the messages of the connection, by capability.
+    pub(crate) capability_meters: Vec<(String, BandwidthMeter)>,
 }
 
 // === impl ActiveSessionHandle ===
@@ -75,6 +87,26 @@ impl ActiveSessionHandle {
         // Note: we clone the sender which ensures the channel has capacity to send the message
         let _ = self.commands_to_session.clone().try_send(SessionCommand::Disconnect { reason });
     }
+
+    /// Returns the [`PeerInfo`] of the session.
+    pub(crate) fn peer_info(&self) -> PeerInfo {
+        PeerInfo {
+            remote_id: self.remote_id,
+            direction: self.direction,
+            remote_addr: self.remote_addr,
+            capabilities: self.capabilities.clone(),
+            client_version: self.client_version.clone(),
+            eth_version: self.version,
+            status: self.status,
+            session_established: self.established,
+            bandwidth: meter_snapshot(&self.bandwidth_meter),
+            capability_bandwidth: self
+                .capability_meters
+                .iter()
+                .map(|(name, meter)| (name.clone(), meter_snapshot(meter)))
+                .collect(),
+        }
+    }
 }
 
 /// Info about an active peer session.
@@ -90,6 +122,55 @@ pub struct PeerInfo {
     pub remote_addr: SocketAddr,
     /// The direction of the session
     pub direction: Direction,
+    /// The negotiated eth version of the session
+    pub eth_version: EthVersion,
+    /// The `Status` message the peer sent during the handshake
+    pub status: Status,
+    /// The timestamp when the session has been established
+    pub session_established: Instant,
+    /// Bytes exchanged over the connection when the info was taken
+    pub bandwidth: PeerBandwidth,
+    /// Bytes of the messages exchanged by capability when the info was taken
+    pub capability_bandwidth: BTreeMap<String, PeerBandwidth>,
+}
+
+// === impl PeerInfo ===
+
+impl PeerInfo {
+    /// Converts the info into the `admin_peers` representation.
+    pub fn into_connected_peer(self) -> ConnectedPeer {
+        ConnectedPeer {
+            enode: NodeRecord::new(self.remote_addr, self.remote_id),
+            id: self.remote_id,
+            name: self.client_version.to_string(),
+            caps: self
+                .capabilities
+                .capabilities()
+                .iter()
+                .map(|cap| format!("{}/{}", cap.name, cap.version))
+                .collect(),
+            network: ConnectedPeerNetwork {
+                remote_address: self.remote_addr,
+                inbound: self.direction.is_incoming(),
+                connected_secs: self.session_established.elapsed().as_secs(),
+                bandwidth: self.bandwidth,
+                capability_bandwidth: self.capability_bandwidth,
+            },
+            protocols: ConnectedPeerProtocols {
+                eth: EthPeerInfo {
+                    version: u8::from(self.eth_version) as u64,
+                    difficulty: self.status.total_difficulty,
+                    head: self.status.blockhash,
+                    fork_id: self.status.forkid,
+                },
+            },
+        }
+    }
+}
+
+/// Returns the current totals of the meter.
+fn meter_snapshot(meter: &BandwidthMeter) -> PeerBandwidth {
+    PeerBandwidth { ingress: meter.total_inbound(), egress: meter.total_outbound() }
 }
 
 /// Events a pending session can produce.
@@ -107,9 +188,11 @@ pub(crate) enum PendingSessionEvent {
         peer_id: PeerId,
         capabilities: Arc<Capabilities>,
         status: Status,
-        conn: EthStream<P2PStream<ECIESStream<MeteredStream<TcpStream>>>>,
+        conn: EthStream<P2PStream<ECIESStream<MeteredStream<ThrottledStream<TcpStream>>>>>,
         direction: Direction,
         client_id: String,
+        /// Meters all bytes of the connection.
+        bandwidth_meter: BandwidthMeter,
     },
     /// Handshake unsuccessful, session was disconnected.
     Disconnected {
@@ -152,7 +235,7 @@ pub(crate) enum SessionCommand {
 #[derive(Debug)]
 pub(crate) enum ActiveSessionMessage {
     /// Session was gracefully disconnected.
-    Disconnected { peer_id: PeerId, remote_addr: SocketAddr },
+    Disconnected { peer_id: PeerId, remote_addr: SocketAddr, reason: Option<DisconnectReason> },
     /// Session was closed due an error
     ClosedOnConnectionError {
         peer_id: PeerId,
@@ -1,6 +1,7 @@
 //! Support for handling peer sessions.
 use crate::{
     message::PeerMessage,
+    protocol::{RlpxSubProtocolHandler, RlpxSubProtocols},
     session::{
         active::ActiveSession,
         config::SessionCounter,
@@ -15,12 +16,14 @@ use fnv::FnvHashMap;
 use futures::{future::Either, io, FutureExt, StreamExt};
 use reth_ecies::{stream::ECIESStream, ECIESError};
 use reth_eth_wire::{
-    capability::{Capabilities, CapabilityMessage},
+    capability::{Capabilities, CapabilityMessage, Protocol},
     errors::EthStreamError,
-    DisconnectReason, EthVersion, HelloMessage, Status, UnauthedEthStream, UnauthedP2PStream,
+    DisconnectReason, EthVersion, HelloMessage, PingConfig, Status, UnauthedEthStream,
+    UnauthedP2PStream,
 };
 use reth_metrics_common::metered_sender::MeteredSender;
 use reth_net_common::{
+    bandwidth_limiter::{BandwidthLimiter, ThrottledStream},
     bandwidth_meter::{BandwidthMeter, MeteredStream},
     stream::HasRemoteAddr,
 };
@@ -66,6 +69,12 @@ pub(crate) struct SessionManager {
     /// If an [ActiveSession] does not receive a response at all within this duration then it is
     /// considered a protocol violation and the session will initiate a drop.
     protocol_breach_request_timeout: Duration,
+    /// The `p2p` keepalive of the sessions.
+    ping_config: PingConfig,
+    /// How long to wait for the tcp connection of an outbound session.
+    dial_timeout: Duration,
+    /// The maximum number of requests of a peer that are served at the same time.
+    max_concurrent_requests_from_remote: usize,
     /// The secret key used for authenticating sessions.
     secret_key: SecretKey,
     /// The `Status` message to send to peers.
@@ -101,6 +110,12 @@ pub(crate) struct SessionManager {
     active_session_rx: ReceiverStream<ActiveSessionMessage>,
     /// Used to measure inbound & outbound bandwidth across all managed streams
     bandwidth_meter: BandwidthMeter,
+    /// Limits the download bandwidth across all managed streams, if configured.
+    download_limiter: Option<BandwidthLimiter>,
+    /// Limits the upload bandwidth across all managed streams, if configured.
+    upload_limiter: Option<BandwidthLimiter>,
+    /// Custom subprotocols that are multiplexed over the sessions next to `eth`.
+    protocols: RlpxSubProtocols,
 }
 
 // === impl SessionManager ===
@@ -124,6 +139,9 @@ impl SessionManager {
             counter: SessionCounter::new(config.limits),
             initial_internal_request_timeout: config.initial_internal_request_timeout,
             protocol_breach_request_timeout: config.protocol_breach_request_timeout,
+            ping_config: config.ping,
+            dial_timeout: config.dial_timeout,
+            max_concurrent_requests_from_remote: config.max_concurrent_requests_from_remote,
             secret_key,
             status,
             hello_message,
@@ -137,6 +155,15 @@ impl SessionManager {
             active_session_tx: MeteredSender::new(active_session_tx, "network_active_session"),
             active_session_rx: ReceiverStream::new(active_session_rx),
             bandwidth_meter,
+            download_limiter: config
+                .max_download_rate
+                .filter(|rate| *rate > 0)
+                .map(BandwidthLimiter::new),
+            upload_limiter: config
+                .max_upload_rate
+                .filter(|rate| *rate > 0)
+                .map(BandwidthLimiter::new),
+            protocols: Default::default(),
         }
     }
 
@@ -163,6 +190,20 @@ impl SessionManager {
         self.hello_message.clone()
     }
 
+    /// Registers a custom subprotocol and announces its capability in the `Hello` message.
+    ///
+    /// This only affects sessions that are established afterwards.
+    pub(crate) fn add_rlpx_sub_protocol(
+        &mut self,
+        protocol: Protocol,
+        handler: Arc<dyn RlpxSubProtocolHandler>,
+    ) {
+        if !self.hello_message.capabilities.contains(&protocol.cap) {
+            self.hello_message.capabilities.push(protocol.cap.clone());
+        }
+        self.protocols.push(protocol, handler);
+    }
+
     /// Spawns the given future onto a new task that is tracked in the `spawned_tasks`
     /// [`JoinSet`](tokio::task::JoinSet).
     fn spawn<F>(&self, f: F)
@@ -204,9 +245,15 @@ impl SessionManager {
 
         let (disconnect_tx, disconnect_rx) = oneshot::channel();
         let pending_events = self.pending_sessions_tx.clone();
-        let metered_stream = MeteredStream::new_with_meter(stream, self.bandwidth_meter.clone());
+        let stream = ThrottledStream::new(
+            stream,
+            self.download_limiter.clone(),
+            self.upload_limiter.clone(),
+        );
+        let metered_stream = MeteredStream::new_with_meter(stream, self.bandwidth_meter.child());
         let secret_key = self.secret_key;
         let hello_message = self.hello_message.clone();
+        let protocols = self.protocols.protocols();
         let status = self.status;
         let fork_filter = self.fork_filter.clone();
         self.spawn(start_pending_incoming_session(
@@ -217,6 +264,8 @@ impl SessionManager {
             remote_addr,
             secret_key,
             hello_message,
+            protocols,
+            self.ping_config,
             status,
             fork_filter,
         ));
@@ -239,9 +288,10 @@ impl SessionManager {
             let pending_events = self.pending_sessions_tx.clone();
             let secret_key = self.secret_key;
             let hello_message = self.hello_message.clone();
+            let protocols = self.protocols.protocols();
             let fork_filter = self.fork_filter.clone();
             let status = self.status;
-            let band_with_meter = self.bandwidth_meter.clone();
+            let band_with_meter = self.bandwidth_meter.child();
             self.spawn(start_pending_outbound_session(
                 disconnect_rx,
                 pending_events,
@@ -250,9 +300,14 @@ impl SessionManager {
                 remote_peer_id,
                 secret_key,
                 hello_message,
+                protocols,
+                self.ping_config,
                 status,
                 fork_filter,
                 band_with_meter,
+                self.download_limiter.clone(),
+                self.upload_limiter.clone(),
+                self.dial_timeout,
             ));
 
             let handle = PendingSessionHandle {
@@ -339,14 +394,14 @@ impl SessionManager {
             }
             Poll::Ready(Some(event)) => {
                 return match event {
-                    ActiveSessionMessage::Disconnected { peer_id, remote_addr } => {
+                    ActiveSessionMessage::Disconnected { peer_id, remote_addr, reason } => {
                         trace!(
                             target : "net::session",
                             ?peer_id,
                             "gracefully disconnected active session."
                         );
                         self.remove_active_session(&peer_id);
-                        Poll::Ready(SessionEvent::Disconnected { peer_id, remote_addr })
+                        Poll::Ready(SessionEvent::Disconnected { peer_id, remote_addr, reason })
                     }
                     ActiveSessionMessage::ClosedOnConnectionError {
                         peer_id,
@@ -393,6 +448,7 @@ impl SessionManager {
                 status,
                 direction,
                 client_id,
+                bandwidth_meter,
             } => {
                 // move from pending to established.
                 self.remove_pending_session(&session_id);
@@ -434,6 +490,18 @@ impl SessionManager {
                 // negotiated version
                 let version = conn.version();
 
+                let capability_meters = conn
+                    .inner()
+                    .bandwidth_meters()
+                    .into_iter()
+                    .map(|(name, meter)| (name.to_string(), meter))
+                    .collect();
+
+                // custom subprotocols the peer shares with us
+                let protocols = self
+                    .protocols
+                    .on_session_established(peer_id, conn.inner().extra_capabilities());
+
                 let session = ActiveSession {
                     next_id: 0,
                     remote_peer_id: peer_id,
@@ -446,13 +514,16 @@ impl SessionManager {
                     internal_request_tx: ReceiverStream::new(messages_rx).fuse(),
                     inflight_requests: Default::default(),
                     conn,
+                    protocols,
                     queued_outgoing: Default::default(),
                     received_requests_from_remote: Default::default(),
+                    max_concurrent_requests_from_remote: self.max_concurrent_requests_from_remote,
                     internal_request_timeout_interval: tokio::time::interval(
                         self.initial_internal_request_timeout,
                     ),
                     internal_request_timeout: Arc::clone(&timeout),
                     protocol_breach_request_timeout: self.protocol_breach_request_timeout,
+                    local_disconnect_reason: None,
                 };
 
                 self.spawn(session);
@@ -468,6 +539,9 @@ impl SessionManager {
                     commands_to_session,
                     client_version: Arc::clone(&client_version),
                     remote_addr,
+                    status,
+                    bandwidth_meter,
+                    capability_meters,
                 };
 
                 self.active_sessions.insert(peer_id, handle);
@@ -558,29 +632,14 @@ impl SessionManager {
 
     /// Returns [`PeerInfo`] for all connected peers
     pub(crate) fn get_peer_info(&self) -> Vec<PeerInfo> {
-        self.active_sessions
-            .values()
-            .map(|session| PeerInfo {
-                remote_id: session.remote_id,
-                direction: session.direction,
-                remote_addr: session.remote_addr,
-                capabilities: session.capabilities.clone(),
-                client_version: session.client_version.clone(),
-            })
-            .collect()
+        self.active_sessions.values().map(ActiveSessionHandle::peer_info).collect()
     }
 
     /// Returns [`PeerInfo`] for a given peer.
     ///
     /// Returns `None` if there's no active session to the peer.
     pub(crate) fn get_peer_info_by_id(&self, peer_id: PeerId) -> Option<PeerInfo> {
-        self.active_sessions.get(&peer_id).map(|session| PeerInfo {
-            remote_id: session.remote_id,
-            direction: session.direction,
-            remote_addr: session.remote_addr,
-            capabilities: session.capabilities.clone(),
-            client_version: session.client_version.clone(),
-        })
+        self.active_sessions.get(&peer_id).map(ActiveSessionHandle::peer_info)
     }
 }
 
@@ -661,6 +720,8 @@ pub(crate) enum SessionEvent {
     Disconnected {
         peer_id: PeerId,
         remote_addr: SocketAddr,
+        /// The reason we sent to the peer, if we initiated the disconnect.
+        reason: Option<DisconnectReason>,
     },
 }
 
@@ -671,6 +732,15 @@ pub(crate) enum PendingSessionHandshakeError {
     Ecies(ECIESError),
 }
 
+impl std::fmt::Display for PendingSessionHandshakeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PendingSessionHandshakeError::Eth(err) => write!(f, "{err}"),
+            PendingSessionHandshakeError::Ecies(err) => write!(f, "{err}"),
+        }
+    }
+}
+
 impl PendingSessionHandshakeError {
     /// Returns the [`DisconnectReason`] if the error is a disconnect message
     pub fn as_disconnected(&self) -> Option<DisconnectReason> {
@@ -719,11 +789,13 @@ pub struct ExceedsSessionLimit(pub(crate) u32);
 pub(crate) async fn start_pending_incoming_session(
     disconnect_rx: oneshot::Receiver<()>,
     session_id: SessionId,
-    stream: MeteredStream<TcpStream>,
+    stream: MeteredStream<ThrottledStream<TcpStream>>,
     events: mpsc::Sender<PendingSessionEvent>,
     remote_addr: SocketAddr,
     secret_key: SecretKey,
     hello: HelloMessage,
+    protocols: Vec<Protocol>,
+    ping_config: PingConfig,
     status: Status,
     fork_filter: ForkFilter,
 ) {
@@ -736,6 +808,8 @@ pub(crate) async fn start_pending_incoming_session(
         secret_key,
         Direction::Incoming,
         hello,
+        protocols,
+        ping_config,
         status,
         fork_filter,
     )
@@ -753,12 +827,21 @@ async fn start_pending_outbound_session(
     remote_peer_id: PeerId,
     secret_key: SecretKey,
     hello: HelloMessage,
+    protocols: Vec<Protocol>,
+    ping_config: PingConfig,
     status: Status,
     fork_filter: ForkFilter,
     bandwidth_meter: BandwidthMeter,
+    download_limiter: Option<BandwidthLimiter>,
+    upload_limiter: Option<BandwidthLimiter>,
+    dial_timeout: Duration,
 ) {
-    let stream = match TcpStream::connect(remote_addr).await {
-        Ok(stream) => MeteredStream::new_with_meter(stream, bandwidth_meter),
+    let connect = tokio::time::timeout(dial_timeout, TcpStream::connect(remote_addr));
+    let stream = match connect.await.unwrap_or_else(|_| Err(io::ErrorKind::TimedOut.into())) {
+        Ok(stream) => {
+            let stream = ThrottledStream::new(stream, download_limiter, upload_limiter);
+            MeteredStream::new_with_meter(stream, bandwidth_meter)
+        }
         Err(error) => {
             let _ = events
                 .send(PendingSessionEvent::OutgoingConnectionError {
@@ -780,6 +863,8 @@ async fn start_pending_outbound_session(
         secret_key,
         Direction::Outgoing(remote_peer_id),
         hello,
+        protocols,
+        ping_config,
         status,
         fork_filter,
     )
@@ -791,15 +876,18 @@ async fn start_pending_outbound_session(
 async fn authenticate(
     disconnect_rx: oneshot::Receiver<()>,
     events: mpsc::Sender<PendingSessionEvent>,
-    stream: MeteredStream<TcpStream>,
+    stream: MeteredStream<ThrottledStream<TcpStream>>,
     session_id: SessionId,
     remote_addr: SocketAddr,
     secret_key: SecretKey,
     direction: Direction,
     hello: HelloMessage,
+    protocols: Vec<Protocol>,
+    ping_config: PingConfig,
     status: Status,
     fork_filter: ForkFilter,
 ) {
+    let bandwidth_meter = stream.get_bandwidth_meter().clone();
     let stream = match get_eciess_stream(stream, secret_key, direction).await {
         Ok(stream) => stream,
         Err(error) => {
@@ -815,7 +903,8 @@ async fn authenticate(
         }
     };
 
-    let unauthed = UnauthedP2PStream::new(stream);
+    let unauthed =
+        UnauthedP2PStream::new(stream).with_protocols(protocols).with_ping_config(ping_config);
 
     let auth = authenticate_stream(
         unauthed,
@@ -825,6 +914,7 @@ async fn authenticate(
         hello,
         status,
         fork_filter,
+        bandwidth_meter,
     )
     .boxed();
 
@@ -865,13 +955,14 @@ async fn get_eciess_stream<Io: AsyncRead + AsyncWrite + Unpin + HasRemoteAddr>(
 /// On Success return the authenticated stream as [`PendingSessionEvent`]
 #[allow(clippy::too_many_arguments)]
 async fn authenticate_stream(
-    stream: UnauthedP2PStream<ECIESStream<MeteredStream<TcpStream>>>,
+    stream: UnauthedP2PStream<ECIESStream<MeteredStream<ThrottledStream<TcpStream>>>>,
     session_id: SessionId,
     remote_addr: SocketAddr,
     direction: Direction,
     hello: HelloMessage,
     status: Status,
     fork_filter: ForkFilter,
+    bandwidth_meter: BandwidthMeter,
 ) -> PendingSessionEvent {
     // conduct the p2p handshake and return the authenticated stream
     let (p2p_stream, their_hello) = match stream.handshake(hello).await {
@@ -911,5 +1002,6 @@ async fn authenticate_stream(
         conn: eth_stream,
         direction,
         client_id: their_hello.client_version,
+        bandwidth_meter,
     }
 }
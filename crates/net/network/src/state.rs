@@ -226,6 +226,7 @@ where
 
     /// Invoked when a new [`ForkId`] is activated.
     pub(crate) fn update_fork_id(&mut self, fork_id: ForkId) {
+        self.peers_manager.set_local_fork_id(fork_id);
         self.discovery.update_fork_id(fork_id)
     }
 
@@ -268,6 +269,7 @@ where
         match kind {
             PeerKind::Basic => self.peers_manager.remove_peer(peer_id),
             PeerKind::Trusted => self.peers_manager.remove_peer_from_trusted_set(peer_id),
+            PeerKind::Static => self.peers_manager.remove_peer_from_static_set(peer_id),
         }
     }
 
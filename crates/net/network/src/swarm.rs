@@ -153,7 +153,7 @@ where
             }
             SessionEvent::AlreadyConnected { peer_id, remote_addr, direction } => {
                 trace!( target: "net", ?peer_id, ?remote_addr, ?direction, "already connected");
-                self.state.peers_mut().on_already_connected(direction);
+                self.state.peers_mut().on_already_connected(remote_addr, direction);
                 None
             }
             SessionEvent::ValidMessage { peer_id, message } => {
@@ -168,13 +168,18 @@ where
             SessionEvent::OutgoingPendingSessionClosed { remote_addr, peer_id, error } => {
                 Some(SwarmEvent::OutgoingPendingSessionClosed { remote_addr, peer_id, error })
             }
-            SessionEvent::Disconnected { peer_id, remote_addr } => {
+            SessionEvent::Disconnected { peer_id, remote_addr, reason } => {
                 self.state.on_session_closed(peer_id);
-                Some(SwarmEvent::SessionClosed { peer_id, remote_addr, error: None })
+                Some(SwarmEvent::SessionClosed { peer_id, remote_addr, error: None, reason })
             }
             SessionEvent::SessionClosedOnConnectionError { peer_id, remote_addr, error } => {
                 self.state.on_session_closed(peer_id);
-                Some(SwarmEvent::SessionClosed { peer_id, remote_addr, error: Some(error) })
+                Some(SwarmEvent::SessionClosed {
+                    peer_id,
+                    remote_addr,
+                    error: Some(error),
+                    reason: None,
+                })
             }
             SessionEvent::OutgoingConnectionError { remote_addr, peer_id, error } => {
                 Some(SwarmEvent::OutgoingConnectionError { peer_id, remote_addr, error })
@@ -210,10 +215,21 @@ where
                         }
                         InboundConnectionError::ExceedsLimit(limit) => {
                             trace!(target: "net", %limit, ?remote_addr, "Exceeded incoming connection limit; disconnecting");
-                            self.sessions.disconnect_incoming_connection(
-                                stream,
-                                DisconnectReason::TooManyPeers,
-                            );
+                            let reason = DisconnectReason::TooManyPeers;
+                            self.sessions.disconnect_incoming_connection(stream, reason);
+                            return Some(SwarmEvent::IncomingConnectionRejected {
+                                remote_addr,
+                                reason,
+                            })
+                        }
+                        InboundConnectionError::ExceedsIpLimit => {
+                            trace!(target: "net", ?remote_addr, "Exceeded connection limit of the ip address; disconnecting");
+                            let reason = DisconnectReason::TooManyPeers;
+                            self.sessions.disconnect_incoming_connection(stream, reason);
+                            return Some(SwarmEvent::IncomingConnectionRejected {
+                                remote_addr,
+                                reason,
+                            })
                         }
                     }
                     return None
@@ -228,7 +244,7 @@ where
                         warn!(target: "net", ?err, "Incoming connection rejected");
                         self.state_mut()
                             .peers_mut()
-                            .on_incoming_pending_session_rejected_internally();
+                            .on_incoming_pending_session_rejected_internally(remote_addr.ip());
                     }
                 }
             }
@@ -384,6 +400,14 @@ pub(crate) enum SwarmEvent {
         /// Address of the remote peer.
         remote_addr: SocketAddr,
     },
+    /// An incoming connection was rejected with a disconnect before the session was
+    /// authenticated.
+    IncomingConnectionRejected {
+        /// Address of the remote peer.
+        remote_addr: SocketAddr,
+        /// The reason sent to the peer.
+        reason: DisconnectReason,
+    },
     /// An outbound connection is initiated.
     OutgoingTcpConnection {
         /// Address of the remote peer.
@@ -406,6 +430,8 @@ pub(crate) enum SwarmEvent {
         remote_addr: SocketAddr,
         /// Whether the session was closed due to an error
         error: Option<EthStreamError>,
+        /// The reason we sent to the peer, if we initiated the disconnect.
+        reason: Option<DisconnectReason>,
     },
     /// Admin rpc: new peer added
     PeerAdded(PeerId),
@@ -1,12 +1,15 @@
 //! A network implementation for testing purposes.
 
 use crate::{
-    error::NetworkError, eth_requests::EthRequestHandler, NetworkConfig, NetworkConfigBuilder,
-    NetworkEvent, NetworkHandle, NetworkManager,
+    error::NetworkError, eth_requests::EthRequestHandler, protocol::RlpxSubProtocolHandler,
+    NetworkConfig, NetworkConfigBuilder, NetworkEvent, NetworkHandle, NetworkManager,
 };
 use futures::{FutureExt, StreamExt};
 use pin_project::pin_project;
-use reth_eth_wire::{capability::Capability, DisconnectReason, HelloBuilder};
+use reth_eth_wire::{
+    capability::{Capability, Protocol},
+    DisconnectReason, HelloBuilder,
+};
 use reth_primitives::PeerId;
 use reth_provider::{test_utils::NoopProvider, BlockProvider, HeaderProvider};
 use secp256k1::SecretKey;
@@ -237,6 +240,15 @@ where
         self.network.handle().clone()
     }
 
+    /// Registers a custom RLPx subprotocol with the peer's network.
+    pub fn add_rlpx_sub_protocol(
+        &mut self,
+        protocol: Protocol,
+        handler: impl RlpxSubProtocolHandler,
+    ) {
+        self.network.add_rlpx_sub_protocol(protocol, handler);
+    }
+
     /// Set a new request handler that's connected tot the peer's network
     pub fn install_request_handler(&mut self) {
         let (tx, rx) = unbounded_channel();
@@ -23,14 +23,18 @@ use reth_transaction_pool::{
     ValidPoolTransaction,
 };
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     future::Future,
     num::NonZeroUsize,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::Interval,
 };
-use tokio::sync::{mpsc, oneshot};
 use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
 use tracing::{debug, trace};
 
@@ -43,6 +47,22 @@ const NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT: usize = 4096;
 /// The target size for the message of full transactions.
 const MAX_FULL_TRANSACTIONS_PACKET_SIZE: usize = 100 * 1024;
 
+/// Soft limit for the number of hashes requested with a single `GetPooledTransactions` request.
+const GET_POOLED_TRANSACTIONS_SOFT_LIMIT_NUM_HASHES: usize = 256;
+
+/// Soft limit for the byte size of a `PooledTransactions` response.
+const POOLED_TRANSACTIONS_RESPONSE_SOFT_LIMIT_BYTE_SIZE: usize = 2 * 1024 * 1024;
+
+/// How long no transactions are requested from a peer that responded without some of the
+/// requested transactions.
+const POOLED_TRANSACTIONS_FETCH_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Interval at which announced transactions that couldn't be requested yet are retried.
+const PENDING_HASHES_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Maximum number of announced transactions that are kept until they can be requested.
+const MAX_PENDING_HASHES: usize = 32 * 1024;
+
 /// The future for inserting a function into the pool
 pub type PoolImportFuture = Pin<Box<dyn Future<Output = PoolResult<TxHash>> + Send + 'static>>;
 
@@ -91,8 +111,13 @@ pub struct TransactionsManager<Pool> {
     ///
     /// From which we get all new incoming transaction related messages.
     network_events: UnboundedReceiverStream<NetworkEvent>,
-    /// All currently active requests for pooled transactions.
-    inflight_requests: Vec<GetPooledTxRequest>,
+    /// Requests the transactions announced by peers.
+    ///
+    /// Peers announce their pool when a session is established, the fetcher ensures the same
+    /// transactions are not requested from every peer.
+    transaction_fetcher: TransactionFetcher,
+    /// Interval at which the announced transactions that couldn't be requested yet are retried.
+    retry_pending_hashes_interval: Interval,
     /// All currently pending transactions grouped by peers.
     ///
     /// This way we can track incoming transactions and prevent multiple pool imports for the same
@@ -110,6 +135,8 @@ pub struct TransactionsManager<Pool> {
     pending_transactions: ReceiverStream<TxHash>,
     /// Incoming events from the [`NetworkManager`](crate::NetworkManager).
     transaction_events: UnboundedReceiverStream<NetworkTransactionEvent>,
+    /// Whether the node was syncing when the manager was last polled.
+    was_syncing: bool,
     /// TransactionsManager metrics
     metrics: TransactionsManagerMetrics,
 }
@@ -128,12 +155,14 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
 
         // install a listener for new transactions
         let pending = pool.pending_transactions_listener();
+        let was_syncing = network.is_syncing();
 
         Self {
             pool,
             network,
             network_events,
-            inflight_requests: Default::default(),
+            transaction_fetcher: Default::default(),
+            retry_pending_hashes_interval: tokio::time::interval(PENDING_HASHES_RETRY_INTERVAL),
             transactions_by_peers: Default::default(),
             pool_imports: Default::default(),
             peers: Default::default(),
@@ -141,6 +170,7 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
             command_rx: UnboundedReceiverStream::new(command_rx),
             pending_transactions: ReceiverStream::new(pending),
             transaction_events: UnboundedReceiverStream::new(from_network),
+            was_syncing,
             metrics: Default::default(),
         }
     }
@@ -166,10 +196,19 @@ where
         response: oneshot::Sender<RequestResult<PooledTransactions>>,
     ) {
         if let Some(peer) = self.peers.get_mut(&peer_id) {
+            // respond with as many transactions as fit into the soft limit of the response
+            let mut size = 0;
             let transactions = self
                 .pool
                 .get_all(request.0)
                 .into_iter()
+                .take_while(|tx| {
+                    let fits = size == 0 ||
+                        size + tx.encoded_length <=
+                            POOLED_TRANSACTIONS_RESPONSE_SOFT_LIMIT_BYTE_SIZE;
+                    size += tx.encoded_length;
+                    fits
+                })
                 .map(|tx| tx.transaction.to_recovered_transaction().into_signed())
                 .collect::<Vec<_>>();
 
@@ -286,31 +325,28 @@ where
         let mut num_already_seen = 0;
 
         if let Some(peer) = self.peers.get_mut(&peer_id) {
-            let mut hashes = msg.into_hashes();
+            // the announced sizes, eth/68 only
+            let mut announced: Vec<(TxHash, Option<usize>)> = match msg {
+                NewPooledTransactionHashes::Eth66(msg) => {
+                    msg.0.into_iter().map(|hash| (hash, None)).collect()
+                }
+                NewPooledTransactionHashes::Eth68(msg) => {
+                    msg.hashes.into_iter().zip(msg.sizes.into_iter().map(Some)).collect()
+                }
+            };
             // keep track of the transactions the peer knows
-            for tx in hashes.iter().copied() {
-                if !peer.transactions.insert(tx) {
+            for (tx, _) in announced.iter() {
+                if !peer.transactions.insert(*tx) {
                     num_already_seen += 1;
                 }
             }
 
-            self.pool.retain_unknown(&mut hashes);
+            let mut unknown = announced.iter().map(|(hash, _)| *hash).collect::<Vec<_>>();
+            self.pool.retain_unknown(&mut unknown);
+            let unknown = unknown.into_iter().collect::<HashSet<_>>();
+            announced.retain(|(hash, _)| unknown.contains(hash));
 
-            if hashes.is_empty() {
-                // nothing to request
-                return
-            }
-
-            // request the missing transactions
-            let (response, rx) = oneshot::channel();
-            let req = PeerRequest::GetPooledTransactions {
-                request: GetPooledTransactions(hashes),
-                response,
-            };
-
-            if peer.request_tx.try_send(req).is_ok() {
-                self.inflight_requests.push(GetPooledTxRequest { peer_id, response: rx })
-            }
+            self.transaction_fetcher.request_transactions(peer_id, peer, announced);
 
             if num_already_seen > 0 {
                 self.metrics.messages_with_already_seen_hashes.increment(1);
@@ -353,6 +389,7 @@ where
             NetworkEvent::SessionClosed { peer_id, .. } => {
                 // remove the peer
                 self.peers.remove(&peer_id);
+                self.transaction_fetcher.on_session_closed(&peer_id);
             }
             NetworkEvent::SessionEstablished {
                 peer_id, client_version, messages, version, ..
@@ -370,32 +407,50 @@ where
                     },
                 );
 
-                // Send a `NewPooledTransactionHashes` to the peer with up to
-                // `NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT` transactions in the
-                // pool
+                // announce our pool, the peer requests the transactions it doesn't know
                 if !self.network.is_syncing() {
-                    let peer = self.peers.get_mut(&peer_id).expect("is present; qed");
-
-                    let mut msg_builder = PooledTransactionsHashesBuilder::new(version);
+                    self.announce_pooled_transactions(peer_id);
+                }
+            }
+            _ => {}
+        }
+    }
 
-                    let pooled_txs =
-                        self.pool.pooled_transactions_max(NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT);
-                    if pooled_txs.is_empty() {
-                        // do not send a message if there are no transactions in the pool
-                        return
-                    }
+    /// Sends the hashes of all transactions in the pool that are unknown to the peer.
+    ///
+    /// The hashes are split into `NewPooledTransactionHashes` messages of up to
+    /// `NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT` hashes.
+    fn announce_pooled_transactions(&mut self, peer_id: PeerId) {
+        let Some(peer) = self.peers.get_mut(&peer_id) else { return };
 
-                    for pooled_tx in pooled_txs.into_iter() {
-                        peer.transactions.insert(*pooled_tx.hash());
-                        msg_builder.push_pooled(pooled_tx);
-                    }
+        let pooled_txs = self
+            .pool
+            .pooled_transactions()
+            .into_iter()
+            .filter(|tx| peer.transactions.insert(*tx.hash()))
+            .collect::<Vec<_>>();
+
+        for chunk in pooled_txs.chunks(NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT) {
+            let mut msg_builder = PooledTransactionsHashesBuilder::new(peer.version);
+            for pooled_tx in chunk {
+                msg_builder.push_pooled(Arc::clone(pooled_tx));
+            }
+            self.network.send_transactions_hashes(peer_id, msg_builder.build());
+        }
+    }
 
-                    let msg = msg_builder.build();
-                    self.network.send_transactions_hashes(peer_id, msg);
-                }
+    /// Announces the pool to all peers once the node finished syncing.
+    ///
+    /// Sessions that were established while syncing skipped the exchange of pooled transactions.
+    fn on_sync_state(&mut self) {
+        let is_syncing = self.network.is_syncing();
+        if self.was_syncing && !is_syncing {
+            let peers = self.peers.keys().copied().collect::<Vec<_>>();
+            for peer_id in peers {
+                self.announce_pooled_transactions(peer_id);
             }
-            _ => {}
         }
+        self.was_syncing = is_syncing;
     }
 
     /// Starts the import process for the given transactions.
@@ -464,6 +519,51 @@ where
         }
     }
 
+    /// Imports the transactions of a `PooledTransactions` response and requests the transactions
+    /// the peer didn't deliver from other peers that announced them.
+    ///
+    /// Peers that respond with fewer transactions than requested are not requested from for a
+    /// while.
+    fn on_pooled_transactions_response(&mut self, response: GetPooledTxResponse) {
+        let GetPooledTxResponse { peer_id, requested, result } = response;
+        let delivered = match result {
+            Ok(Ok(txs)) => {
+                let delivered = txs.0.iter().map(|tx| tx.hash()).collect::<HashSet<_>>();
+                if requested.iter().any(|hash| !delivered.contains(hash)) {
+                    trace!(target: "net::tx", ?peer_id, requested=requested.len(), delivered=delivered.len(), "Peer responded with missing transactions");
+                    self.transaction_fetcher.backoff_peer(peer_id);
+                }
+                self.import_transactions(peer_id, txs.0, TransactionSource::Response);
+                delivered
+            }
+            Ok(Err(_)) | Err(_) => {
+                self.report_bad_message(peer_id);
+                HashSet::new()
+            }
+        };
+
+        let num_refetched = self.transaction_fetcher.on_resolved_request(
+            peer_id,
+            requested,
+            &delivered,
+            &self.peers,
+        );
+        self.metrics.refetched_transactions.increment(num_refetched as u64);
+    }
+
+    /// Requests the announced transactions that couldn't be requested yet, because the announcing
+    /// peers were backed off or busy, if they're still unknown to the pool.
+    fn retry_pending_hashes(&mut self) {
+        if self.network.is_syncing() || !self.transaction_fetcher.has_pending_hashes() {
+            return
+        }
+        let mut unknown =
+            self.transaction_fetcher.pending_hashes.keys().copied().collect::<Vec<_>>();
+        self.pool.retain_unknown(&mut unknown);
+        let unknown = unknown.into_iter().collect::<HashSet<_>>();
+        self.transaction_fetcher.retry_pending_hashes(&unknown, &self.peers);
+    }
+
     fn report_bad_message(&self, peer_id: PeerId) {
         trace!(target: "net::tx", ?peer_id, "Penalizing peer for bad transaction");
         self.metrics.reported_bad_transactions.increment(1);
@@ -503,6 +603,8 @@ where
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
 
+        this.on_sync_state();
+
         // drain network/peer related events
         while let Poll::Ready(Some(event)) = this.network_events.poll_next_unpin(cx) {
             this.on_network_event(event);
@@ -519,23 +621,13 @@ where
         }
 
         // Advance all requests.
-        // We remove each request one by one and add them back.
-        for idx in (0..this.inflight_requests.len()).rev() {
-            let mut req = this.inflight_requests.swap_remove(idx);
-            match req.response.poll_unpin(cx) {
-                Poll::Pending => {
-                    this.inflight_requests.push(req);
-                }
-                Poll::Ready(Ok(Ok(txs))) => {
-                    this.import_transactions(req.peer_id, txs.0, TransactionSource::Response);
-                }
-                Poll::Ready(Ok(Err(_))) => {
-                    this.report_bad_message(req.peer_id);
-                }
-                Poll::Ready(Err(_)) => {
-                    this.report_bad_message(req.peer_id);
-                }
-            }
+        while let Poll::Ready(response) = this.transaction_fetcher.poll(cx) {
+            this.on_pooled_transactions_response(response);
+        }
+
+        // Retry the announced transactions that couldn't be requested yet
+        if this.retry_pending_hashes_interval.poll_tick(cx).is_ready() {
+            this.retry_pending_hashes();
         }
 
         // Advance all imports
@@ -688,13 +780,286 @@ impl TransactionSource {
     }
 }
 
+/// Requests the transactions that were announced by peers.
+///
+/// A transaction is only requested from a single peer at a time. Other peers that announce the
+/// transaction in the meantime are remembered as alternates, and the transaction is requested
+/// from one of them if the peer fails to deliver it.
+///
+/// Transactions that can't be requested right away, because all peers that announced them are
+/// backed off or their sessions are busy, are kept as pending and retried later.
+#[derive(Default)]
+struct TransactionFetcher {
+    /// All currently active requests for pooled transactions.
+    inflight_requests: Vec<GetPooledTxRequest>,
+    /// Hashes of all transactions that are currently requested, with the alternate peers that
+    /// announced them as well.
+    inflight_hashes: HashMap<TxHash, Vec<PeerId>>,
+    /// Hashes of announced transactions that are not requested yet, with the peers that announced
+    /// them.
+    pending_hashes: HashMap<TxHash, Vec<PeerId>>,
+    /// Peers that responded without some of the requested transactions are not requested from
+    /// until the instant.
+    backed_off_peers: HashMap<PeerId, Instant>,
+}
+
+// === impl TransactionFetcher ===
+
+impl TransactionFetcher {
+    /// Returns `true` if the peer is currently not requested from.
+    fn is_backed_off(&mut self, peer_id: &PeerId) -> bool {
+        match self.backed_off_peers.get(peer_id) {
+            Some(until) if *until > Instant::now() => true,
+            Some(_) => {
+                self.backed_off_peers.remove(peer_id);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Stops requesting transactions from the peer for [`POOLED_TRANSACTIONS_FETCH_BACKOFF`].
+    fn backoff_peer(&mut self, peer_id: PeerId) {
+        self.backed_off_peers.insert(peer_id, Instant::now() + POOLED_TRANSACTIONS_FETCH_BACKOFF);
+    }
+
+    /// Invoked when the session with the peer was closed.
+    fn on_session_closed(&mut self, peer_id: &PeerId) {
+        self.backed_off_peers.remove(peer_id);
+    }
+
+    /// Returns `true` if there are announced transactions that are not requested yet.
+    fn has_pending_hashes(&self) -> bool {
+        !self.pending_hashes.is_empty()
+    }
+
+    /// Remembers that the transaction was announced by the peers, so it can be requested later.
+    fn insert_pending_hash(&mut self, hash: TxHash, peers: impl IntoIterator<Item = PeerId>) {
+        if self.pending_hashes.len() >= MAX_PENDING_HASHES &&
+            !self.pending_hashes.contains_key(&hash)
+        {
+            return
+        }
+        let announcers = self.pending_hashes.entry(hash).or_default();
+        for peer_id in peers {
+            if !announcers.contains(&peer_id) {
+                announcers.push(peer_id);
+            }
+        }
+    }
+
+    /// Requests the announced transactions from the peer, with their announced sizes, if known.
+    ///
+    /// Transactions that are already requested from another peer are not requested again, instead
+    /// the peer is remembered as an alternate. If the peer is backed off, the transactions are
+    /// kept as pending.
+    fn request_transactions(
+        &mut self,
+        peer_id: PeerId,
+        peer: &Peer,
+        announced: Vec<(TxHash, Option<usize>)>,
+    ) {
+        let is_backed_off = self.is_backed_off(&peer_id);
+        let mut hashes = Vec::with_capacity(announced.len());
+        for (hash, size) in announced {
+            if let Some(alternates) = self.inflight_hashes.get_mut(&hash) {
+                if !alternates.contains(&peer_id) {
+                    alternates.push(peer_id);
+                }
+            } else if is_backed_off {
+                self.insert_pending_hash(hash, [peer_id]);
+            } else {
+                hashes.push((hash, size));
+            }
+        }
+        for hash in self.send_requests(peer_id, peer, hashes) {
+            self.insert_pending_hash(hash, [peer_id]);
+        }
+    }
+
+    /// Requests the pending transactions that are still `unknown` to the pool from the peers that
+    /// announced them and are not backed off.
+    ///
+    /// Pending transactions that are known to the pool or whose announcing peers all disconnected
+    /// are discarded.
+    fn retry_pending_hashes(&mut self, unknown: &HashSet<TxHash>, peers: &HashMap<PeerId, Peer>) {
+        let mut retry: HashMap<PeerId, Vec<(TxHash, Vec<PeerId>)>> = HashMap::new();
+        for (hash, mut announcers) in std::mem::take(&mut self.pending_hashes) {
+            announcers.retain(|peer_id| peers.contains_key(peer_id));
+            if !unknown.contains(&hash) || announcers.is_empty() {
+                continue
+            }
+            if let Some(inflight) = self.inflight_hashes.get_mut(&hash) {
+                // requested in the meantime
+                inflight.extend(announcers);
+                continue
+            }
+            let Some(idx) = announcers.iter().position(|peer_id| !self.is_backed_off(peer_id))
+            else {
+                self.pending_hashes.insert(hash, announcers);
+                continue
+            };
+            let peer_id = announcers.remove(idx);
+            retry.entry(peer_id).or_default().push((hash, announcers));
+        }
+
+        for (peer_id, hashes) in retry {
+            self.request_with_alternates(peer_id, &peers[&peer_id], hashes);
+        }
+    }
+
+    /// Requests the hashes from the peer and remembers the alternate peers of each hash.
+    ///
+    /// Returns the number of transactions that were requested.
+    fn request_with_alternates(
+        &mut self,
+        peer_id: PeerId,
+        peer: &Peer,
+        hashes: Vec<(TxHash, Vec<PeerId>)>,
+    ) -> usize {
+        let unsent = self
+            .send_requests(peer_id, peer, hashes.iter().map(|(hash, _)| (*hash, None)).collect())
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let mut num_requested = 0;
+        for (hash, alternates) in hashes {
+            if unsent.contains(&hash) {
+                // the session is busy, the transaction is retried later
+                self.insert_pending_hash(hash, std::iter::once(peer_id).chain(alternates));
+            } else if let Some(inflight) = self.inflight_hashes.get_mut(&hash) {
+                inflight.extend(alternates);
+                num_requested += 1;
+            }
+        }
+        num_requested
+    }
+
+    /// Sends `GetPooledTransactions` requests for the hashes to the peer.
+    ///
+    /// The hashes are batched into requests of up to
+    /// [`GET_POOLED_TRANSACTIONS_SOFT_LIMIT_NUM_HASHES`] hashes, whose announced sizes add up to at
+    /// most [`POOLED_TRANSACTIONS_RESPONSE_SOFT_LIMIT_BYTE_SIZE`].
+    ///
+    /// Returns the hashes that couldn't be requested because the session is busy.
+    #[must_use]
+    fn send_requests(
+        &mut self,
+        peer_id: PeerId,
+        peer: &Peer,
+        hashes: Vec<(TxHash, Option<usize>)>,
+    ) -> Vec<TxHash> {
+        let mut batches = Vec::new();
+        let mut batch = Vec::new();
+        let mut batch_size = 0;
+        for (hash, size) in hashes {
+            let size = size.unwrap_or_default();
+            if !batch.is_empty() &&
+                (batch.len() >= GET_POOLED_TRANSACTIONS_SOFT_LIMIT_NUM_HASHES ||
+                    batch_size + size > POOLED_TRANSACTIONS_RESPONSE_SOFT_LIMIT_BYTE_SIZE)
+            {
+                batches.push(std::mem::take(&mut batch));
+                batch_size = 0;
+            }
+            batch.push(hash);
+            batch_size += size;
+        }
+        if !batch.is_empty() {
+            batches.push(batch);
+        }
+
+        let mut batches = batches.into_iter();
+        for hashes in batches.by_ref() {
+            let (response, rx) = oneshot::channel();
+            let req = PeerRequest::GetPooledTransactions {
+                request: GetPooledTransactions(hashes.clone()),
+                response,
+            };
+
+            if peer.request_tx.try_send(req).is_err() {
+                // the session is busy, the remaining batches are not sent either
+                return hashes.into_iter().chain(batches.flatten()).collect()
+            }
+            for hash in hashes.iter().copied() {
+                self.inflight_hashes.entry(hash).or_default();
+            }
+            self.inflight_requests.push(GetPooledTxRequest { peer_id, hashes, response: rx })
+        }
+        Vec::new()
+    }
+
+    /// Invoked when a request was resolved.
+    ///
+    /// Requests the requested transactions that were not delivered from alternate peers, and
+    /// returns the number of transactions that were requested again.
+    ///
+    /// If all alternates are backed off, the transaction is kept as pending.
+    fn on_resolved_request(
+        &mut self,
+        peer_id: PeerId,
+        requested: Vec<TxHash>,
+        delivered: &HashSet<TxHash>,
+        peers: &HashMap<PeerId, Peer>,
+    ) -> usize {
+        let mut refetch: HashMap<PeerId, Vec<(TxHash, Vec<PeerId>)>> = HashMap::new();
+        for hash in requested {
+            let Some(mut alternates) = self.inflight_hashes.remove(&hash) else { continue };
+            if delivered.contains(&hash) {
+                continue
+            }
+            alternates.retain(|alternate| *alternate != peer_id && peers.contains_key(alternate));
+            let Some(idx) = alternates.iter().position(|alternate| !self.is_backed_off(alternate))
+            else {
+                if !alternates.is_empty() {
+                    self.insert_pending_hash(hash, alternates);
+                }
+                continue
+            };
+            let alternate = alternates.remove(idx);
+            refetch.entry(alternate).or_default().push((hash, alternates));
+        }
+
+        let mut num_refetched = 0;
+        for (alternate, hashes) in refetch {
+            let Some(peer) = peers.get(&alternate) else { continue };
+            trace!(target: "net::tx", peer_id=?alternate, num_hashes=hashes.len(), "Requesting missing transactions from alternate peer");
+            num_refetched += self.request_with_alternates(alternate, peer, hashes);
+        }
+        num_refetched
+    }
+
+    /// Polls the next resolved request.
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<GetPooledTxResponse> {
+        for idx in 0..self.inflight_requests.len() {
+            if let Poll::Ready(result) = self.inflight_requests[idx].response.poll_unpin(cx) {
+                let req = self.inflight_requests.swap_remove(idx);
+                return Poll::Ready(GetPooledTxResponse {
+                    peer_id: req.peer_id,
+                    requested: req.hashes,
+                    result,
+                })
+            }
+        }
+        Poll::Pending
+    }
+}
+
 /// An inflight request for `PooledTransactions` from a peer
 #[allow(missing_docs)]
 struct GetPooledTxRequest {
     peer_id: PeerId,
+    /// The requested hashes.
+    hashes: Vec<TxHash>,
     response: oneshot::Receiver<RequestResult<PooledTransactions>>,
 }
 
+/// The outcome of a [`GetPooledTxRequest`].
+struct GetPooledTxResponse {
+    peer_id: PeerId,
+    /// The requested hashes.
+    requested: Vec<TxHash>,
+    result: Result<RequestResult<PooledTransactions>, oneshot::error::RecvError>,
+}
+
 /// Tracks a single peer
 struct Peer {
     /// Keeps track of transactions that we know the peer has seen.
@@ -739,6 +1104,7 @@ mod tests {
     use reth_rlp::Decodable;
     use reth_transaction_pool::test_utils::{testing_pool, MockTransaction};
     use secp256k1::SecretKey;
+    use std::future::poll_fn;
 
     #[tokio::test(flavor = "multi_thread")]
     #[cfg_attr(not(feature = "geth-tests"), ignore)]
@@ -943,4 +1309,247 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_requests_announced_transactions_in_chunks() {
+        reth_tracing::init_test_tracing();
+
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let client = NoopProvider::default();
+        let pool = testing_pool();
+        let config = NetworkConfigBuilder::new(secret_key)
+            .disable_discovery()
+            .listener_port(0)
+            .build(client);
+        let (network_handle, network, mut transactions, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone())
+            .split_with_handle();
+        tokio::task::spawn(network);
+        network_handle.update_sync_state(SyncState::Idle);
+
+        // two sessions that announce the same transactions
+        let mut sessions = Vec::new();
+        for _ in 0..2 {
+            let peer_id = PeerId::random();
+            let (to_session_tx, to_session_rx) = mpsc::channel(10);
+            transactions.peers.insert(
+                peer_id,
+                Peer {
+                    transactions: LruCache::new(
+                        NonZeroUsize::new(PEER_TRANSACTION_CACHE_LIMIT).unwrap(),
+                    ),
+                    request_tx: PeerRequestSender::new(peer_id, to_session_tx),
+                    version: EthVersion::Eth66,
+                    client_version: Default::default(),
+                },
+            );
+            sessions.push((peer_id, to_session_rx));
+        }
+        let hashes = (0..300).map(|_| H256::random()).collect::<Vec<_>>();
+        let announce = |peer_id| NetworkTransactionEvent::IncomingPooledTransactionHashes {
+            peer_id,
+            msg: NewPooledTransactionHashes66(hashes.clone()).into(),
+        };
+
+        transactions.on_network_tx_event(announce(sessions[0].0));
+        let mut requested = Vec::new();
+        while let Ok(req) = sessions[0].1.try_recv() {
+            let PeerRequest::GetPooledTransactions { request, .. } = req else {
+                panic!("unexpected request")
+            };
+            requested.push(request.0.len());
+        }
+        assert_eq!(requested, vec![GET_POOLED_TRANSACTIONS_SOFT_LIMIT_NUM_HASHES, 44]);
+
+        // the transactions are already requested from the first peer
+        transactions.on_network_tx_event(announce(sessions[1].0));
+        assert!(sessions[1].1.try_recv().is_err());
+
+        // eth/68 announcements are batched by the announced sizes as well
+        let size = POOLED_TRANSACTIONS_RESPONSE_SOFT_LIMIT_BYTE_SIZE / 2;
+        transactions.on_network_tx_event(
+            NetworkTransactionEvent::IncomingPooledTransactionHashes {
+                peer_id: sessions[1].0,
+                msg: NewPooledTransactionHashes68 {
+                    types: vec![0; 3],
+                    sizes: vec![size; 3],
+                    hashes: (0..3).map(|_| H256::random()).collect(),
+                }
+                .into(),
+            },
+        );
+        let mut requested = Vec::new();
+        while let Ok(req) = sessions[1].1.try_recv() {
+            let PeerRequest::GetPooledTransactions { request, .. } = req else {
+                panic!("unexpected request")
+            };
+            requested.push(request.0.len());
+        }
+        assert_eq!(requested, vec![2, 1]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refetch_missing_transactions_from_alternate() {
+        reth_tracing::init_test_tracing();
+
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let client = NoopProvider::default();
+        let pool = testing_pool();
+        let config = NetworkConfigBuilder::new(secret_key)
+            .disable_discovery()
+            .listener_port(0)
+            .build(client);
+        let (network_handle, network, mut transactions, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone())
+            .split_with_handle();
+        tokio::task::spawn(network);
+        network_handle.update_sync_state(SyncState::Idle);
+
+        // two sessions that announce the same transactions
+        let mut sessions = Vec::new();
+        for _ in 0..2 {
+            let peer_id = PeerId::random();
+            let (to_session_tx, to_session_rx) = mpsc::channel(10);
+            transactions.peers.insert(
+                peer_id,
+                Peer {
+                    transactions: LruCache::new(
+                        NonZeroUsize::new(PEER_TRANSACTION_CACHE_LIMIT).unwrap(),
+                    ),
+                    request_tx: PeerRequestSender::new(peer_id, to_session_tx),
+                    version: EthVersion::Eth66,
+                    client_version: Default::default(),
+                },
+            );
+            sessions.push((peer_id, to_session_rx));
+        }
+        let hashes = (0..3).map(|_| H256::random()).collect::<Vec<_>>();
+        for (peer_id, _) in sessions.iter() {
+            transactions.on_network_tx_event(
+                NetworkTransactionEvent::IncomingPooledTransactionHashes {
+                    peer_id: *peer_id,
+                    msg: NewPooledTransactionHashes66(hashes.clone()).into(),
+                },
+            );
+        }
+
+        // the first peer responds without the transactions
+        let Ok(PeerRequest::GetPooledTransactions { response, .. }) = sessions[0].1.try_recv()
+        else {
+            panic!("expected request")
+        };
+        assert!(sessions[1].1.try_recv().is_err());
+        response.send(Ok(PooledTransactions(Vec::new()))).unwrap();
+
+        let response = poll_fn(|cx| transactions.transaction_fetcher.poll(cx)).await;
+        transactions.on_pooled_transactions_response(response);
+
+        // the transactions are requested from the second peer instead
+        let Ok(PeerRequest::GetPooledTransactions { request, .. }) = sessions[1].1.try_recv()
+        else {
+            panic!("expected request")
+        };
+        assert_eq!(request.0, hashes);
+        assert!(transactions.transaction_fetcher.is_backed_off(&sessions[0].0));
+
+        // the first peer isn't requested from while backed off
+        let hashes = (0..3).map(|_| H256::random()).collect::<Vec<_>>();
+        transactions.on_network_tx_event(
+            NetworkTransactionEvent::IncomingPooledTransactionHashes {
+                peer_id: sessions[0].0,
+                msg: NewPooledTransactionHashes66(hashes).into(),
+            },
+        );
+        assert!(sessions[0].1.try_recv().is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_retry_pending_transactions() {
+        reth_tracing::init_test_tracing();
+
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let client = NoopProvider::default();
+        let pool = testing_pool();
+        let config = NetworkConfigBuilder::new(secret_key)
+            .disable_discovery()
+            .listener_port(0)
+            .build(client);
+        let (network_handle, network, mut transactions, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone())
+            .split_with_handle();
+        tokio::task::spawn(network);
+        network_handle.update_sync_state(SyncState::Idle);
+
+        // a session that can buffer a single request
+        let peer_id = PeerId::random();
+        let (to_session_tx, mut to_session_rx) = mpsc::channel(1);
+        transactions.peers.insert(
+            peer_id,
+            Peer {
+                transactions: LruCache::new(
+                    NonZeroUsize::new(PEER_TRANSACTION_CACHE_LIMIT).unwrap(),
+                ),
+                request_tx: PeerRequestSender::new(peer_id, to_session_tx),
+                version: EthVersion::Eth66,
+                client_version: Default::default(),
+            },
+        );
+
+        // the transactions announced while the peer is backed off are kept as pending
+        transactions.transaction_fetcher.backoff_peer(peer_id);
+        let backed_off = (0..3).map(|_| H256::random()).collect::<Vec<_>>();
+        transactions.on_network_tx_event(
+            NetworkTransactionEvent::IncomingPooledTransactionHashes {
+                peer_id,
+                msg: NewPooledTransactionHashes66(backed_off.clone()).into(),
+            },
+        );
+        assert!(to_session_rx.try_recv().is_err());
+        assert_eq!(transactions.transaction_fetcher.pending_hashes.len(), 3);
+
+        // and requested once the backoff expired
+        transactions.transaction_fetcher.backed_off_peers.clear();
+        transactions.retry_pending_hashes();
+        let Ok(PeerRequest::GetPooledTransactions { request, .. }) = to_session_rx.try_recv()
+        else {
+            panic!("expected request")
+        };
+        let mut requested = request.0;
+        requested.sort();
+        let mut expected = backed_off;
+        expected.sort();
+        assert_eq!(requested, expected);
+        assert!(!transactions.transaction_fetcher.has_pending_hashes());
+
+        // the transactions that can't be sent to the busy session are kept as pending
+        for _ in 0..2 {
+            transactions.on_network_tx_event(
+                NetworkTransactionEvent::IncomingPooledTransactionHashes {
+                    peer_id,
+                    msg: NewPooledTransactionHashes66(vec![H256::random()]).into(),
+                },
+            );
+        }
+        assert_eq!(transactions.transaction_fetcher.pending_hashes.len(), 1);
+        let busy = *transactions.transaction_fetcher.pending_hashes.keys().next().unwrap();
+
+        // and requested once the session has capacity again
+        assert!(to_session_rx.try_recv().is_ok());
+        transactions.retry_pending_hashes();
+        let Ok(PeerRequest::GetPooledTransactions { request, .. }) = to_session_rx.try_recv()
+        else {
+            panic!("expected request")
+        };
+        assert_eq!(request.0, vec![busy]);
+        assert!(!transactions.transaction_fetcher.has_pending_hashes());
+    }
 }
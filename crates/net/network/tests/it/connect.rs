@@ -19,6 +19,7 @@ use reth_network::{
 use reth_network_api::{NetworkInfo, Peers, PeersInfo};
 use reth_primitives::{mainnet_nodes, HeadersDirection, NodeRecord, PeerId};
 use reth_provider::test_utils::NoopProvider;
+use reth_rpc_types::PeerEventKind;
 use reth_transaction_pool::test_utils::testing_pool;
 use secp256k1::SecretKey;
 use std::{collections::HashSet, net::SocketAddr, time::Duration};
@@ -160,6 +161,40 @@ async fn test_get_peer() {
 
     let peers = handle0.get_peers().await.unwrap();
     assert_eq!(handle0.num_connected_peers(), peers.len());
+
+    let connected = handle0.connected_peers().await.unwrap();
+    assert_eq!(connected.len(), peers.len());
+    for peer in connected {
+        assert!(peer.id == *handle1.peer_id() || peer.id == *handle2.peer_id());
+        assert!(!peer.network.inbound);
+        assert!(peer.caps.iter().any(|cap| cap.starts_with("eth/")));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_peer_events() {
+    reth_tracing::init_test_tracing();
+
+    let net = Testnet::create(2).await;
+
+    let mut handles = net.handles();
+    let handle0 = handles.next().unwrap();
+    let handle1 = handles.next().unwrap();
+
+    drop(handles);
+    let _handle = net.spawn();
+
+    let mut events = handle0.peer_events();
+
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+    let event = events.recv().await.unwrap();
+    assert_eq!(event.kind, PeerEventKind::Add);
+    assert_eq!(event.peer, Some(*handle1.peer_id()));
+
+    handle0.disconnect_peer(*handle1.peer_id());
+    let event = events.recv().await.unwrap();
+    assert_eq!(event.kind, PeerEventKind::Drop);
+    assert_eq!(event.peer, Some(*handle1.peer_id()));
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -1,11 +1,16 @@
 //! Tests for eth related requests
 
+use futures::StreamExt;
 use rand::Rng;
+use reth_eth_wire::EthMessageID;
 use reth_interfaces::p2p::{
     bodies::client::BodiesClient,
     headers::client::{HeadersClient, HeadersRequest},
 };
-use reth_network::test_utils::{NetworkEventStream, Testnet};
+use reth_network::{
+    test_utils::{NetworkEventStream, Testnet},
+    ReceivedMessage,
+};
 use reth_network_api::{NetworkInfo, Peers};
 use reth_primitives::{
     Block, BlockBody, Bytes, Header, HeadersDirection, Signature, Transaction, TransactionKind,
@@ -75,6 +80,52 @@ async fn test_get_body() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_get_body_soft_response_limit() {
+    reth_tracing::init_test_tracing();
+    let mut rng = rand::thread_rng();
+    let mock_provider = Arc::new(MockEthProvider::default());
+
+    let mut net = Testnet::create_with(2, mock_provider.clone()).await;
+
+    // install request handlers
+    net.for_each_mut(|peer| peer.install_request_handler());
+
+    let handle0 = net.peers()[0].handle();
+    let mut events0 = NetworkEventStream::new(handle0.event_listener());
+
+    let handle1 = net.peers()[1].handle();
+
+    let _handle = net.spawn();
+
+    let fetch0 = handle0.fetch_client().await.unwrap();
+
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+    let connected = events0.next_session_established().await.unwrap();
+    assert_eq!(connected, *handle1.peer_id());
+
+    // blocks with a 1MB transaction each
+    let mut hashes = Vec::new();
+    for _ in 0..3 {
+        let block_hash = H256::random();
+        let mut tx = rng_transaction(&mut rng);
+        if let Transaction::Eip2930(ref mut inner) = tx.transaction {
+            inner.input = Bytes::from(vec![0; 1024 * 1024]);
+        }
+        let mut block = Block::default();
+        block
+            .body
+            .push(TransactionSigned::from_transaction_and_signature(tx.transaction, tx.signature));
+        mock_provider.add_block(block_hash, block);
+        hashes.push(block_hash);
+    }
+
+    // the response stops at the body that exceeds the 2MB soft limit
+    let res = fetch0.get_block_bodies(hashes).await;
+    assert!(res.is_ok(), "{res:?}");
+    assert_eq!(res.unwrap().1.len(), 2);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_get_header() {
     reth_tracing::init_test_tracing();
@@ -120,3 +171,42 @@ async fn test_get_header() {
         assert_eq!(headers[0], header);
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_message_received_event() {
+    reth_tracing::init_test_tracing();
+    let mock_provider = Arc::new(MockEthProvider::default());
+
+    let mut net = Testnet::create_with(2, mock_provider.clone()).await;
+
+    // install request handlers
+    net.for_each_mut(|peer| peer.install_request_handler());
+
+    let handle0 = net.peers()[0].handle();
+    let mut events0 = NetworkEventStream::new(handle0.event_listener());
+
+    let handle1 = net.peers()[1].handle();
+    let mut messages1 = handle1.message_listener(16);
+
+    let _handle = net.spawn();
+
+    let fetch0 = handle0.fetch_client().await.unwrap();
+
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+    let connected = events0.next_session_established().await.unwrap();
+    assert_eq!(connected, *handle1.peer_id());
+
+    let req = HeadersRequest {
+        start: H256::random().into(),
+        limit: 1,
+        direction: HeadersDirection::Falling,
+    };
+    let res = fetch0.get_headers(req).await;
+    assert!(res.is_ok(), "{res:?}");
+
+    let msg = messages1.next().await.unwrap();
+    assert_eq!(
+        msg,
+        ReceivedMessage { peer_id: *handle0.peer_id(), message_id: EthMessageID::GetBlockHeaders }
+    );
+}
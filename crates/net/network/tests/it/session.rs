@@ -1,13 +1,19 @@
 //! Session tests
 
 use futures::StreamExt;
-use reth_eth_wire::{capability::Capability, EthVersion};
+use reth_eth_wire::{
+    capability::{Capability, Protocol, RawCapabilityMessage},
+    EthVersion,
+};
 use reth_network::{
+    protocol::{ProtocolConnection, RlpxSubProtocolHandler},
     test_utils::{PeerConfig, Testnet},
     NetworkEvent,
 };
 use reth_network_api::{NetworkInfo, Peers};
+use reth_primitives::{bytes::Bytes, PeerId};
 use reth_provider::test_utils::NoopProvider;
+use tokio::sync::mpsc;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_session_established_with_highest_version() {
@@ -82,3 +88,87 @@ async fn test_session_established_with_different_capability() {
 
     handle.terminate().await;
 }
+
+/// Events emitted by the [`ForwardingProtocol`].
+#[derive(Debug)]
+enum ProtocolEvent {
+    Established(PeerId, ProtocolConnection),
+    Message(PeerId, RawCapabilityMessage),
+    Closed(PeerId),
+}
+
+/// A custom subprotocol that forwards everything to a channel.
+#[derive(Debug)]
+struct ForwardingProtocol(mpsc::UnboundedSender<ProtocolEvent>);
+
+impl RlpxSubProtocolHandler for ForwardingProtocol {
+    fn on_connection_established(&self, peer_id: PeerId, conn: ProtocolConnection) {
+        let _ = self.0.send(ProtocolEvent::Established(peer_id, conn));
+    }
+
+    fn on_message(&self, peer_id: PeerId, msg: RawCapabilityMessage) {
+        let _ = self.0.send(ProtocolEvent::Message(peer_id, msg));
+    }
+
+    fn on_connection_closed(&self, peer_id: PeerId) {
+        let _ = self.0.send(ProtocolEvent::Closed(peer_id));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_custom_rlpx_protocol() {
+    reth_tracing::init_test_tracing();
+
+    let mut net = Testnet::create(2).await;
+
+    // sorts before `eth`, so the offset of `eth` is shifted as well
+    let protocol = Protocol::new(Capability::new("aaa".into(), 1), 2);
+    let (tx0, mut events0) = mpsc::unbounded_channel();
+    let (tx1, mut events1) = mpsc::unbounded_channel();
+    net.peers_mut()[0].add_rlpx_sub_protocol(protocol.clone(), ForwardingProtocol(tx0));
+    net.peers_mut()[1].add_rlpx_sub_protocol(protocol, ForwardingProtocol(tx1));
+
+    let mut handles = net.handles();
+    let handle0 = handles.next().unwrap();
+    let handle1 = handles.next().unwrap();
+    drop(handles);
+
+    let handle = net.spawn();
+
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+
+    let conn0 = match events0.recv().await.unwrap() {
+        ProtocolEvent::Established(peer_id, conn) => {
+            assert_eq!(peer_id, *handle1.peer_id());
+            conn
+        }
+        ev => panic!("unexpected event: {ev:?}"),
+    };
+    let conn1 = match events1.recv().await.unwrap() {
+        ProtocolEvent::Established(peer_id, conn) => {
+            assert_eq!(peer_id, *handle0.peer_id());
+            conn
+        }
+        ev => panic!("unexpected event: {ev:?}"),
+    };
+
+    let msg = RawCapabilityMessage { id: 1, payload: Bytes::from_static(b"ping") };
+    conn0.send(msg.clone()).await.unwrap();
+    match events1.recv().await.unwrap() {
+        ProtocolEvent::Message(peer_id, received) => {
+            assert_eq!(peer_id, *handle0.peer_id());
+            assert_eq!(received, msg);
+        }
+        ev => panic!("unexpected event: {ev:?}"),
+    }
+
+    // message ids outside of the protocol's range are rejected
+    conn1.send(RawCapabilityMessage { id: 2, payload: Bytes::new() }).await.unwrap();
+    match events1.recv().await.unwrap() {
+        ProtocolEvent::Closed(peer_id) => assert_eq!(peer_id, *handle0.peer_id()),
+        ev => panic!("unexpected event: {ev:?}"),
+    }
+    assert!(conn1.is_closed());
+
+    handle.terminate().await;
+}
@@ -3,12 +3,12 @@ use reth_network::{
     error::{NetworkError, ServiceKind},
     Discovery, NetworkConfigBuilder, NetworkManager,
 };
-use reth_network_api::NetworkInfo;
+use reth_network_api::{NetworkInfo, PeersInfo};
 use reth_provider::test_utils::NoopProvider;
 use secp256k1::SecretKey;
 use std::{
     io,
-    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
 };
 
 fn is_addr_in_use_kind(err: NetworkError, kind: ServiceKind) -> bool {
@@ -31,6 +31,57 @@ async fn test_is_default_syncing() {
     assert!(network.handle().is_syncing());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_is_listening() {
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let config = NetworkConfigBuilder::new(secret_key)
+        .disable_discovery()
+        .listener_port(0)
+        .build(NoopProvider::default());
+    let network = NetworkManager::new(config).await.unwrap();
+    assert!(network.handle().is_listening());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_discv4_handle() {
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let config = NetworkConfigBuilder::new(secret_key)
+        .disable_discovery()
+        .listener_port(0)
+        .build(NoopProvider::default());
+    let network = NetworkManager::new(config).await.unwrap();
+    assert!(network.handle().discv4().is_none());
+
+    let config = NetworkConfigBuilder::new(secret_key)
+        .disable_dns_discovery()
+        .listener_port(0)
+        .discovery_addr(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)))
+        .build(NoopProvider::default());
+    let network = NetworkManager::new(config).await.unwrap();
+    let discv4 = network.handle().discv4().unwrap();
+    assert_ne!(discv4.local_addr().port(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_node_status() {
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let config = NetworkConfigBuilder::new(secret_key)
+        .disable_dns_discovery()
+        .listener_port(0)
+        .discovery_addr(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)))
+        .build(NoopProvider::default());
+    let network = NetworkManager::new(config).await.unwrap();
+    let handle = network.handle().clone();
+    tokio::task::spawn(network);
+
+    let record = handle.local_node_record();
+    assert_eq!(record.udp_port, handle.discv4().unwrap().local_addr().port());
+
+    let status = handle.network_status().await.unwrap();
+    assert!(status.enr.unwrap().starts_with("enr:"));
+    assert_eq!(status.eth_protocol_info.config.unwrap().chain_id, 1);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_listener_addr_in_use() {
     let secret_key = SecretKey::new(&mut rand::thread_rng());
@@ -47,8 +98,38 @@ async fn test_discovery_addr_in_use() {
     let secret_key = SecretKey::new(&mut rand::thread_rng());
     let disc_config = Discv4Config::default();
     let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, DEFAULT_DISCOVERY_PORT));
-    let _discovery = Discovery::new(addr, secret_key, Some(disc_config), None).await.unwrap();
+    let _discovery =
+        Discovery::new(addr, None, secret_key, Some(disc_config), None, None).await.unwrap();
     let disc_config = Discv4Config::default();
-    let result = Discovery::new(addr, secret_key, Some(disc_config), None).await;
+    let result = Discovery::new(addr, None, secret_key, Some(disc_config), None, None).await;
     assert!(is_addr_in_use_kind(result.err().unwrap(), ServiceKind::Discovery(addr)));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dual_stack_listener() {
+    if std::net::TcpListener::bind((Ipv6Addr::LOCALHOST, 0)).is_err() {
+        // the host doesn't support IPv6
+        return
+    }
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let mut discv4 = Discv4Config::builder();
+    discv4.external_ip_resolver(None);
+    let config = NetworkConfigBuilder::new(secret_key)
+        .disable_dns_discovery()
+        .discovery(discv4)
+        .listener_addr(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)))
+        .listener_addr_ipv6(SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 0, 0, 0)))
+        .discovery_addr(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)))
+        .discovery_addr_ipv6(SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 0, 0, 0)))
+        .build(NoopProvider::default());
+    let network = NetworkManager::new(config).await.unwrap();
+    assert!(network.local_addr().is_ipv4());
+    assert!(network.local_addr_ipv6().unwrap().is_ipv6());
+
+    // the ENR advertises both address families
+    let handle = network.handle().clone();
+    tokio::task::spawn(network);
+    let enr = handle.discv4().unwrap().local_enr().await.unwrap();
+    assert_eq!(enr.ip4(), Some(Ipv4Addr::LOCALHOST));
+    assert_eq!(enr.ip6(), Some(Ipv6Addr::LOCALHOST));
+}
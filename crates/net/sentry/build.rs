@@ -0,0 +1,38 @@
+//! Generates the gRPC client and server of the sentry service, see `src/proto.rs` for the
+//! messages.
+
+use tonic_build::manual::{Builder, Method, Service};
+
+fn main() {
+    let method = |name: &str, route: &str, input: &str, output: &str| {
+        Method::builder()
+            .name(name)
+            .route_name(route)
+            .input_type(format!("crate::proto::{input}"))
+            .output_type(format!("crate::proto::{output}"))
+            .codec_path("tonic::codec::ProstCodec")
+    };
+
+    let service = Service::builder()
+        .name("Sentry")
+        .package("reth.sentry")
+        .method(
+            method("get_block_headers", "GetBlockHeaders", "HeadersRequest", "PeerResponse")
+                .build(),
+        )
+        .method(
+            method("get_block_bodies", "GetBlockBodies", "BodiesRequest", "PeerResponse").build(),
+        )
+        .method(method("penalize_peer", "PenalizePeer", "PenalizePeerRequest", "Empty").build())
+        .method(method("peer_count", "PeerCount", "Empty", "PeerCountResponse").build())
+        .method(method("update_status", "UpdateStatus", "StatusRequest", "Empty").build())
+        .method(method("send_transactions", "SendTransactions", "Transactions", "Empty").build())
+        .method(
+            method("subscribe_transactions", "SubscribeTransactions", "Empty", "Transactions")
+                .server_streaming()
+                .build(),
+        )
+        .build();
+
+    Builder::new().compile(&[service]);
+}
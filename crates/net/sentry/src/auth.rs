@@ -0,0 +1,64 @@
+//! JWT authentication of the sentry service, the same scheme as the authenticated engine API.
+//!
+//! The node signs a token with the shared secret and sends it with every request as
+//! `authorization: Bearer <token>`. The sentry rejects requests without a valid token.
+
+use reth_rpc::{Claims, JwtSecret};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tonic::{metadata::MetadataValue, service::Interceptor, Request, Status};
+
+/// The metadata key of the token.
+const AUTHORIZATION: &str = "authorization";
+
+/// Rejects requests that don't carry a token signed with the secret.
+#[derive(Debug, Clone)]
+pub(crate) struct AuthInterceptor {
+    secret: JwtSecret,
+}
+
+impl AuthInterceptor {
+    pub(crate) fn new(secret: JwtSecret) -> Self {
+        Self { secret }
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let token = request
+            .metadata()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("missing bearer token"))?;
+        self.secret
+            .validate(token.to_string())
+            .map_err(|err| Status::unauthenticated(err.to_string()))?;
+        Ok(request)
+    }
+}
+
+/// Signs every request with a fresh token.
+#[derive(Debug, Clone)]
+pub(crate) struct BearerInterceptor {
+    secret: JwtSecret,
+}
+
+impl BearerInterceptor {
+    pub(crate) fn new(secret: JwtSecret) -> Self {
+        Self { secret }
+    }
+}
+
+impl Interceptor for BearerInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let iat = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let token = self
+            .secret
+            .encode(&Claims { iat, exp: None })
+            .map_err(|err| Status::internal(err.to_string()))?;
+        let value = MetadataValue::try_from(format!("Bearer {token}"))
+            .map_err(|err| Status::internal(err.to_string()))?;
+        request.metadata_mut().insert(AUTHORIZATION, value);
+        Ok(request)
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{
+    auth::BearerInterceptor,
+    proto::{
+        self, sentry_client, BodiesRequest, Empty, HeadersRequest as SentryHeadersRequest,
+        PeerResponse, PenalizePeerRequest, StatusRequest,
+    },
+    MAX_MESSAGE_SIZE,
+};
+use futures::{Future, FutureExt, StreamExt};
+use reth_interfaces::p2p::{
+    bodies::client::{BodiesClient, BodiesFut},
+    download::DownloadClient,
+    error::{PeerRequestResult, RequestError},
+    headers::client::{HeadersClient, HeadersFut, HeadersRequest, StatusUpdater},
+    priority::Priority,
+};
+use reth_primitives::{Head, PeerId, TransactionSigned, H256};
+use reth_rlp::Decodable;
+use reth_rpc::JwtSecret;
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tonic::{
+    service::interceptor::InterceptedService,
+    transport::{Channel, Endpoint},
+    Response, Status,
+};
+use tracing::debug;
+
+/// How long to wait for the connection to a sentry before trying the next one.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait before subscribing to the transactions of a sentry again after the
+/// subscription failed.
+const RESUBSCRIBE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The gRPC client of a single sentry, signs every request.
+type GrpcClient = sentry_client::SentryClient<InterceptedService<Channel, BearerInterceptor>>;
+
+/// Errors when creating a [SentryClient].
+#[derive(Debug, thiserror::Error)]
+pub enum SentryClientError {
+    /// No sentry URL was given.
+    #[error("no sentries configured")]
+    NoSentries,
+    /// A sentry URL is invalid.
+    #[error(transparent)]
+    InvalidUrl(#[from] tonic::transport::Error),
+}
+
+/// A p2p client that sends the requests of a node to one or more sentries.
+///
+/// Requests are sent to the sentries in turn. If a sentry can't be reached, the request is sent
+/// to the next one. Every request is authenticated with a JWT signed with the secret shared with
+/// the sentries.
+#[derive(Debug, Clone)]
+pub struct SentryClient {
+    inner: Arc<SentryClientInner>,
+}
+
+#[derive(Debug)]
+struct SentryClientInner {
+    /// The clients of the sentries.
+    sentries: Vec<GrpcClient>,
+    /// The position of the sentry that receives the next request.
+    next: AtomicUsize,
+    /// The total number of peers of the sentries, as of the last update.
+    num_connected_peers: AtomicUsize,
+}
+
+// === impl SentryClient ===
+
+impl SentryClient {
+    /// Creates a client for the sentries with the given URLs.
+    ///
+    /// The sentries are connected to lazily, so this must be called within a tokio runtime.
+    pub fn new<S: AsRef<str>>(
+        urls: impl IntoIterator<Item = S>,
+        secret: JwtSecret,
+    ) -> Result<Self, SentryClientError> {
+        let sentries = urls
+            .into_iter()
+            .map(|url| {
+                let channel = Endpoint::from_shared(url.as_ref().to_string())?
+                    .connect_timeout(CONNECT_TIMEOUT)
+                    .connect_lazy();
+                let client = sentry_client::SentryClient::with_interceptor(
+                    channel,
+                    BearerInterceptor::new(secret.clone()),
+                )
+                .max_decoding_message_size(MAX_MESSAGE_SIZE)
+                .max_encoding_message_size(MAX_MESSAGE_SIZE);
+                Ok(client)
+            })
+            .collect::<Result<Vec<_>, SentryClientError>>()?;
+        if sentries.is_empty() {
+            return Err(SentryClientError::NoSentries)
+        }
+        let inner = SentryClientInner {
+            sentries,
+            next: AtomicUsize::new(0),
+            num_connected_peers: AtomicUsize::new(0),
+        };
+        Ok(Self { inner: Arc::new(inner) })
+    }
+
+    /// Fetches the peer count of every sentry and updates the total number of connected peers.
+    ///
+    /// Sentries that can't be reached don't contribute any peers.
+    pub async fn update_peer_count(&self) {
+        let mut total = 0;
+        for mut sentry in self.inner.sentries.iter().cloned() {
+            match sentry.peer_count(Empty {}).await {
+                Ok(response) => total += response.into_inner().peer_count as usize,
+                Err(err) => debug!(target: "sentry", ?err, "Failed to fetch the peer count"),
+            }
+        }
+        self.inner.num_connected_peers.store(total, Ordering::Relaxed);
+    }
+
+    /// Updates the number of connected peers with the given period, forever.
+    pub async fn peer_count_updates(self, period: Duration) {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            self.update_peer_count().await;
+        }
+    }
+
+    /// Sends the transactions to the sentries, which send them to their peers.
+    pub fn send_transactions(&self, transactions: &[TransactionSigned]) {
+        let transactions = proto::Transactions::new(transactions);
+        self.notify_all(move |mut sentry| {
+            let transactions = transactions.clone();
+            async move { sentry.send_transactions(transactions).await }
+        })
+    }
+
+    /// Returns a stream of the transactions that the peers of the sentries broadcast.
+    ///
+    /// Subscriptions to sentries that can't be reached are retried until the stream is dropped.
+    pub fn transactions(&self) -> UnboundedReceiverStream<Vec<TransactionSigned>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        for sentry in self.inner.sentries.iter().cloned() {
+            tokio::spawn(subscribe_transactions(sentry, tx.clone()));
+        }
+        UnboundedReceiverStream::new(rx)
+    }
+
+    /// Returns the sentries in the order in which the next request tries them.
+    fn next_sentries(&self) -> Vec<GrpcClient> {
+        let sentries = &self.inner.sentries;
+        let start = self.inner.next.fetch_add(1, Ordering::Relaxed);
+        (0..sentries.len()).map(|idx| sentries[(start + idx) % sentries.len()].clone()).collect()
+    }
+
+    /// Sends the request to the sentries in turn until one of them responds.
+    ///
+    /// If no sentry can be reached, the request fails with
+    /// [RequestError::ConnectionDropped], which the downloaders retry.
+    fn request<T, F, Fut>(
+        &self,
+        request: F,
+    ) -> Pin<Box<dyn Future<Output = PeerRequestResult<Vec<T>>> + Send + Sync>>
+    where
+        T: Decodable + Send + 'static,
+        F: Fn(GrpcClient) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Response<PeerResponse>, Status>> + Send + 'static,
+    {
+        let sentries = self.next_sentries();
+        let (tx, rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let mut result = Err(RequestError::ConnectionDropped);
+            for sentry in sentries {
+                match request(sentry).await {
+                    Ok(response) => {
+                        result = response.into_inner().into_result();
+                        break
+                    }
+                    Err(err) => debug!(target: "sentry", ?err, "Failed to reach sentry"),
+                }
+            }
+            let _ = tx.send(result);
+        });
+        Box::pin(rx.map(|result| result.unwrap_or_else(|err| Err(err.into()))))
+    }
+
+    /// Sends the notification to all sentries, without waiting for them.
+    fn notify_all<F, Fut>(&self, notify: F)
+    where
+        F: Fn(GrpcClient) -> Fut,
+        Fut: Future<Output = Result<Response<Empty>, Status>> + Send + 'static,
+    {
+        for sentry in self.inner.sentries.iter().cloned() {
+            let fut = notify(sentry);
+            tokio::spawn(async move {
+                if let Err(err) = fut.await {
+                    debug!(target: "sentry", ?err, "Failed to notify sentry")
+                }
+            });
+        }
+    }
+}
+
+/// Forwards the transactions of the sentry until the receiver is dropped.
+async fn subscribe_transactions(
+    mut sentry: GrpcClient,
+    tx: mpsc::UnboundedSender<Vec<TransactionSigned>>,
+) {
+    while !tx.is_closed() {
+        match sentry.subscribe_transactions(Empty {}).await {
+            Ok(response) => {
+                let mut stream = response.into_inner();
+                while let Some(Ok(transactions)) = stream.next().await {
+                    match transactions.decode() {
+                        Ok(transactions) => {
+                            if tx.send(transactions).is_err() {
+                                return
+                            }
+                        }
+                        Err(err) => debug!(target: "sentry", ?err, "Invalid transactions"),
+                    }
+                }
+            }
+            Err(err) => debug!(target: "sentry", ?err, "Failed to subscribe to transactions"),
+        }
+        tokio::time::sleep(RESUBSCRIBE_INTERVAL).await;
+    }
+}
+
+impl DownloadClient for SentryClient {
+    fn report_bad_message(&self, peer_id: PeerId) {
+        // the peer is only known to the sentry that served the response, the others ignore it
+        let peer_id = peer_id.as_bytes().to_vec();
+        self.notify_all(move |mut sentry| {
+            let request = PenalizePeerRequest { peer_id: peer_id.clone() };
+            async move { sentry.penalize_peer(request).await }
+        })
+    }
+
+    fn num_connected_peers(&self) -> usize {
+        self.inner.num_connected_peers.load(Ordering::Relaxed)
+    }
+}
+
+impl HeadersClient for SentryClient {
+    type Output = HeadersFut;
+
+    fn get_headers_with_priority(
+        &self,
+        request: HeadersRequest,
+        priority: Priority,
+    ) -> Self::Output {
+        let request = SentryHeadersRequest::new(request, priority == Priority::High);
+        self.request(move |mut sentry| {
+            let request = request.clone();
+            async move { sentry.get_block_headers(request).await }
+        })
+    }
+}
+
+impl BodiesClient for SentryClient {
+    type Output = BodiesFut;
+
+    fn get_block_bodies_with_priority(
+        &self,
+        hashes: Vec<H256>,
+        priority: Priority,
+    ) -> Self::Output {
+        let request = BodiesRequest::new(&hashes, priority == Priority::High);
+        self.request(move |mut sentry| {
+            let request = request.clone();
+            async move { sentry.get_block_bodies(request).await }
+        })
+    }
+}
+
+impl StatusUpdater for SentryClient {
+    fn update_status(&self, head: Head) {
+        let request = StatusRequest::from(head);
+        self.notify_all(move |mut sentry| {
+            let request = request.clone();
+            async move { sentry.update_status(request).await }
+        })
+    }
+}
+
+/// A [StatusUpdater] that announces the head to the sentries and to the wrapped updater, e.g. the
+/// local network of the node.
+#[derive(Debug, Clone)]
+pub struct SentryStatusUpdater<S> {
+    sentries: SentryClient,
+    inner: S,
+}
+
+impl<S> SentryStatusUpdater<S> {
+    /// Creates a new updater that forwards to the sentries and the given updater.
+    pub fn new(sentries: SentryClient, inner: S) -> Self {
+        Self { sentries, inner }
+    }
+}
+
+impl<S: StatusUpdater> StatusUpdater for SentryStatusUpdater<S> {
+    fn update_status(&self, head: Head) {
+        self.sentries.update_status(head);
+        self.inner.update_status(head);
+    }
+}
@@ -0,0 +1,241 @@
+#![warn(missing_docs, unreachable_pub, unused_crate_dependencies)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+
+//! Sentry mode: running the p2p network in a standalone process.
+//!
+//! A sentry runs the devp2p stack and serves the sentry gRPC service with a [SentryServer]. Nodes
+//! connect to one or more sentries with a [SentryClient], which implements the p2p clients of the
+//! downloaders, so headers and bodies are fetched from the peers of the sentries, and exchanges
+//! transactions with the peers of the sentries ([SentryTransactions]). This lets operators scale
+//! the p2p layer independently of the node and place sentries in a DMZ.
+//!
+//! The service is authenticated with a JWT secret shared by the sentries and the nodes, like the
+//! authenticated engine API: requests without a token signed with the secret are rejected.
+
+pub use client::{SentryClient, SentryClientError, SentryStatusUpdater};
+pub use server::{SentryServer, SentryServerHandle};
+pub use transactions::{SentryTransactions, SentryTransactionsTask};
+
+mod auth;
+mod client;
+#[allow(unreachable_pub, missing_docs)]
+mod proto;
+mod server;
+mod transactions;
+
+/// The default port of the sentry gRPC server.
+pub const DEFAULT_SENTRY_PORT: u16 = 9091;
+
+/// The maximum size of a message of the sentry service.
+///
+/// Responses are bounded by the soft limit of the `eth` protocol responses, which is well below.
+const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{future, StreamExt};
+    use reth_interfaces::{
+        p2p::{
+            bodies::client::{BodiesClient, BodiesFut},
+            download::DownloadClient,
+            error::RequestError,
+            headers::client::{HeadersClient, HeadersFut, HeadersRequest, StatusUpdater},
+            priority::Priority,
+        },
+        test_utils::{generators::random_signed_tx, TestStatusUpdater},
+    };
+    use reth_primitives::{
+        BlockBody, BlockHashOrNumber, Head, Header, HeadersDirection, PeerId, WithPeerId, H256,
+    };
+    use reth_rpc::JwtSecret;
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+    use tokio::sync::watch;
+
+    const PEER: PeerId = PeerId::repeat_byte(1);
+
+    /// Answers every request itself, as if it was sent to the peer [PEER].
+    #[derive(Debug, Default)]
+    struct TestClient {
+        penalized: Mutex<Vec<PeerId>>,
+    }
+
+    impl DownloadClient for TestClient {
+        fn report_bad_message(&self, peer_id: PeerId) {
+            self.penalized.lock().unwrap().push(peer_id);
+        }
+
+        fn num_connected_peers(&self) -> usize {
+            3
+        }
+    }
+
+    impl HeadersClient for TestClient {
+        type Output = HeadersFut;
+
+        fn get_headers_with_priority(
+            &self,
+            request: HeadersRequest,
+            _priority: Priority,
+        ) -> Self::Output {
+            let BlockHashOrNumber::Number(start) = request.start else {
+                return Box::pin(future::err(RequestError::Timeout))
+            };
+            let headers = (start..start + request.limit)
+                .map(|number| Header { number, ..Default::default() });
+            Box::pin(future::ok(WithPeerId::new(PEER, headers.collect())))
+        }
+    }
+
+    impl BodiesClient for TestClient {
+        type Output = BodiesFut;
+
+        fn get_block_bodies_with_priority(
+            &self,
+            hashes: Vec<H256>,
+            _priority: Priority,
+        ) -> Self::Output {
+            let bodies = hashes.iter().map(|_| BlockBody::default()).collect();
+            Box::pin(future::ok(WithPeerId::new(PEER, bodies)))
+        }
+    }
+
+    async fn spawn_sentry(
+        client: Arc<TestClient>,
+        secret: JwtSecret,
+    ) -> (SentryServerHandle, String, watch::Receiver<Head>, SentryTransactions) {
+        let (status, head) = TestStatusUpdater::new();
+        let (transactions, _) = SentryTransactions::channel();
+        let (addr, handle) = SentryServer::new(client, status, transactions.clone(), secret)
+            .start(([127, 0, 0, 1], 0).into())
+            .await
+            .unwrap();
+        (handle, format!("http://{addr}"), head, transactions)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn requests_through_sentry() {
+        let secret = JwtSecret::random();
+        let test_client = Arc::new(TestClient::default());
+        let (_handle, url, mut head, _) = spawn_sentry(test_client.clone(), secret.clone()).await;
+        let client = SentryClient::new([url], secret).unwrap();
+
+        let request = HeadersRequest {
+            start: BlockHashOrNumber::Number(5),
+            limit: 2,
+            direction: HeadersDirection::Rising,
+        };
+        let (peer_id, headers) = client.get_headers(request).await.unwrap().split();
+        assert_eq!(peer_id, PEER);
+        assert_eq!(headers.iter().map(|header| header.number).collect::<Vec<_>>(), vec![5, 6]);
+
+        let bodies = client.get_block_bodies(vec![H256::zero(); 3]).await.unwrap();
+        assert_eq!(bodies.into_data(), vec![BlockBody::default(); 3]);
+
+        // peer request errors are passed on
+        let request = HeadersRequest {
+            start: BlockHashOrNumber::Hash(H256::zero()),
+            limit: 1,
+            direction: HeadersDirection::Rising,
+        };
+        assert_eq!(client.get_headers(request).await.unwrap_err(), RequestError::Timeout);
+
+        assert_eq!(client.num_connected_peers(), 0);
+        client.update_peer_count().await;
+        assert_eq!(client.num_connected_peers(), 3);
+
+        let status = Head { number: 10, ..Default::default() };
+        client.update_status(status);
+        head.changed().await.unwrap();
+        assert_eq!(*head.borrow(), status);
+
+        client.report_bad_message(PEER);
+        while test_client.penalized.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(*test_client.penalized.lock().unwrap(), vec![PEER]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn transactions_through_sentry() {
+        let secret = JwtSecret::random();
+        let (status, _head) = TestStatusUpdater::new();
+        let (transactions, mut outgoing) = SentryTransactions::channel();
+        let (addr, _handle) = SentryServer::new(
+            Arc::new(TestClient::default()),
+            status,
+            transactions.clone(),
+            secret.clone(),
+        )
+        .start(([127, 0, 0, 1], 0).into())
+        .await
+        .unwrap();
+        let client = SentryClient::new([format!("http://{addr}")], secret).unwrap();
+
+        let tx = random_signed_tx();
+        client.send_transactions(&[tx.clone()]);
+        assert_eq!(outgoing.recv().await.unwrap(), vec![tx.clone()]);
+
+        // transactions of the peers are only streamed to subscribed nodes, so broadcast until the
+        // subscription is established
+        let mut incoming = client.transactions();
+        let received = loop {
+            transactions.on_incoming(vec![tx.clone()]);
+            let next = tokio::time::timeout(Duration::from_millis(100), incoming.next());
+            if let Ok(received) = next.await {
+                break received.unwrap()
+            }
+        };
+        assert_eq!(received, vec![tx]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejects_unauthenticated_requests() {
+        let (_handle, url, _head, _) = spawn_sentry(Default::default(), JwtSecret::random()).await;
+
+        let mut unauthenticated =
+            proto::sentry_client::SentryClient::connect(url.clone()).await.unwrap();
+        let err = unauthenticated.peer_count(proto::Empty {}).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+
+        // a client with another secret can't reach the sentry
+        let client = SentryClient::new([url], JwtSecret::random()).unwrap();
+        let err = client.get_block_bodies(vec![H256::zero()]).await.unwrap_err();
+        assert_eq!(err, RequestError::ConnectionDropped);
+        client.update_peer_count().await;
+        assert_eq!(client.num_connected_peers(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fails_over_to_reachable_sentry() {
+        let secret = JwtSecret::random();
+        let (_handle, url, _head, _) = spawn_sentry(Default::default(), secret.clone()).await;
+        // nothing listens on the first sentry's port
+        let client = SentryClient::new(["http://127.0.0.1:1".to_string(), url], secret).unwrap();
+
+        for _ in 0..2 {
+            let bodies = client.get_block_bodies(vec![H256::zero()]).await.unwrap();
+            assert_eq!(bodies.peer_id(), PEER);
+        }
+        client.update_peer_count().await;
+        assert_eq!(client.num_connected_peers(), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unreachable_sentries() {
+        let client = SentryClient::new(["http://127.0.0.1:1"], JwtSecret::random()).unwrap();
+        let err = client.get_block_bodies(vec![H256::zero()]).await.unwrap_err();
+        assert_eq!(err, RequestError::ConnectionDropped);
+        assert!(matches!(
+            SentryClient::new(Vec::<String>::new(), JwtSecret::random()),
+            Err(SentryClientError::NoSentries)
+        ));
+    }
+}
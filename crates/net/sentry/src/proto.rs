@@ -0,0 +1,274 @@
+//! The messages of the sentry gRPC service and the generated client and server.
+//!
+//! Headers, bodies and transactions are sent RLP encoded, as on the wire of the `eth` protocol.
+
+use reth_interfaces::p2p::{
+    error::{PeerRequestResult, RequestError as PeerRequestError},
+    headers::client::HeadersRequest as PeerHeadersRequest,
+};
+use reth_primitives::{
+    BlockHashOrNumber, Head, HeadersDirection, PeerId, TransactionSigned, WithPeerId, H256, U256,
+};
+use reth_rlp::{Decodable, Encodable};
+
+include!(concat!(env!("OUT_DIR"), "/reth.sentry.Sentry.rs"));
+
+/// Request of `GetBlockHeaders`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HeadersRequest {
+    /// The hash of the first block, if the request starts at a hash.
+    #[prost(bytes = "vec", optional, tag = "1")]
+    pub start_hash: Option<Vec<u8>>,
+    /// The number of the first block, if the request starts at a number.
+    #[prost(uint64, tag = "2")]
+    pub start_number: u64,
+    /// The maximum number of headers.
+    #[prost(uint64, tag = "3")]
+    pub limit: u64,
+    /// Whether the headers are requested in falling order.
+    #[prost(bool, tag = "4")]
+    pub falling: bool,
+    /// Whether the request has high priority.
+    #[prost(bool, tag = "5")]
+    pub high_priority: bool,
+}
+
+/// Request of `GetBlockBodies`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BodiesRequest {
+    /// The hashes of the blocks.
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub hashes: Vec<Vec<u8>>,
+    /// Whether the request has high priority.
+    #[prost(bool, tag = "2")]
+    pub high_priority: bool,
+}
+
+/// Response of a request that the sentry sent to one of its peers.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PeerResponse {
+    /// The peer that responded.
+    #[prost(bytes = "vec", tag = "1")]
+    pub peer_id: Vec<u8>,
+    /// The RLP encoded headers or bodies.
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub items: Vec<Vec<u8>>,
+    /// The error of the request, if it failed.
+    #[prost(enumeration = "RequestError", optional, tag = "3")]
+    pub error: Option<i32>,
+}
+
+/// The reason a peer request of the sentry failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum RequestError {
+    /// See [PeerRequestError::ChannelClosed].
+    ChannelClosed = 0,
+    /// See [PeerRequestError::ConnectionDropped].
+    ConnectionDropped = 1,
+    /// See [PeerRequestError::UnsupportedCapability].
+    UnsupportedCapability = 2,
+    /// See [PeerRequestError::Timeout].
+    Timeout = 3,
+    /// See [PeerRequestError::BadResponse].
+    BadResponse = 4,
+}
+
+/// Request of `PenalizePeer`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PenalizePeerRequest {
+    /// The peer that sent a bad message.
+    #[prost(bytes = "vec", tag = "1")]
+    pub peer_id: Vec<u8>,
+}
+
+/// Response of `PeerCount`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PeerCountResponse {
+    /// The number of peers of the sentry.
+    #[prost(uint64, tag = "1")]
+    pub peer_count: u64,
+}
+
+/// Request of `UpdateStatus`, the head of the node.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StatusRequest {
+    /// See [Head::number].
+    #[prost(uint64, tag = "1")]
+    pub number: u64,
+    /// See [Head::hash].
+    #[prost(bytes = "vec", tag = "2")]
+    pub hash: Vec<u8>,
+    /// See [Head::difficulty], big endian.
+    #[prost(bytes = "vec", tag = "3")]
+    pub difficulty: Vec<u8>,
+    /// See [Head::total_difficulty], big endian.
+    #[prost(bytes = "vec", tag = "4")]
+    pub total_difficulty: Vec<u8>,
+    /// See [Head::timestamp].
+    #[prost(uint64, tag = "5")]
+    pub timestamp: u64,
+}
+
+/// RLP encoded transactions, sent by `SendTransactions` and streamed by `SubscribeTransactions`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Transactions {
+    /// The RLP encoded transactions.
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub transactions: Vec<Vec<u8>>,
+}
+
+/// An empty message.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Empty {}
+
+impl HeadersRequest {
+    pub(crate) fn new(request: PeerHeadersRequest, high_priority: bool) -> Self {
+        let (start_hash, start_number) = match request.start {
+            BlockHashOrNumber::Hash(hash) => (Some(hash.as_bytes().to_vec()), 0),
+            BlockHashOrNumber::Number(number) => (None, number),
+        };
+        Self {
+            start_hash,
+            start_number,
+            limit: request.limit,
+            falling: request.direction == HeadersDirection::Falling,
+            high_priority,
+        }
+    }
+
+    pub(crate) fn to_request(&self) -> Option<PeerHeadersRequest> {
+        let start = match &self.start_hash {
+            Some(hash) => BlockHashOrNumber::Hash(h256(hash)?),
+            None => BlockHashOrNumber::Number(self.start_number),
+        };
+        let direction =
+            if self.falling { HeadersDirection::Falling } else { HeadersDirection::Rising };
+        Some(PeerHeadersRequest { start, limit: self.limit, direction })
+    }
+}
+
+impl BodiesRequest {
+    pub(crate) fn new(hashes: &[H256], high_priority: bool) -> Self {
+        Self { hashes: hashes.iter().map(|hash| hash.as_bytes().to_vec()).collect(), high_priority }
+    }
+
+    pub(crate) fn to_hashes(&self) -> Option<Vec<H256>> {
+        self.hashes.iter().map(|hash| h256(hash)).collect()
+    }
+}
+
+impl PeerResponse {
+    /// Encodes the outcome of a peer request.
+    pub(crate) fn new<T: Encodable>(result: PeerRequestResult<Vec<T>>) -> Self {
+        match result {
+            Ok(response) => {
+                let (peer_id, items) = response.split();
+                Self {
+                    peer_id: peer_id.as_bytes().to_vec(),
+                    items: items.iter().map(rlp).collect(),
+                    error: None,
+                }
+            }
+            Err(err) => Self {
+                peer_id: Vec::new(),
+                items: Vec::new(),
+                error: Some(RequestError::from(err) as i32),
+            },
+        }
+    }
+
+    /// Decodes the outcome of the peer request, a response that can't be decoded is a
+    /// [PeerRequestError::BadResponse].
+    pub(crate) fn into_result<T: Decodable>(self) -> PeerRequestResult<Vec<T>> {
+        if let Some(err) = self.error {
+            let err = RequestError::from_i32(err).ok_or(PeerRequestError::BadResponse)?;
+            return Err(err.into())
+        }
+        let peer_id = peer_id(&self.peer_id).ok_or(PeerRequestError::BadResponse)?;
+        let items = self
+            .items
+            .iter()
+            .map(|item| T::decode(&mut item.as_slice()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| PeerRequestError::BadResponse)?;
+        Ok(WithPeerId::new(peer_id, items))
+    }
+}
+
+impl From<PeerRequestError> for RequestError {
+    fn from(err: PeerRequestError) -> Self {
+        match err {
+            PeerRequestError::ChannelClosed => RequestError::ChannelClosed,
+            PeerRequestError::ConnectionDropped => RequestError::ConnectionDropped,
+            PeerRequestError::UnsupportedCapability => RequestError::UnsupportedCapability,
+            PeerRequestError::Timeout => RequestError::Timeout,
+            PeerRequestError::BadResponse => RequestError::BadResponse,
+        }
+    }
+}
+
+impl From<RequestError> for PeerRequestError {
+    fn from(err: RequestError) -> Self {
+        match err {
+            RequestError::ChannelClosed => PeerRequestError::ChannelClosed,
+            RequestError::ConnectionDropped => PeerRequestError::ConnectionDropped,
+            RequestError::UnsupportedCapability => PeerRequestError::UnsupportedCapability,
+            RequestError::Timeout => PeerRequestError::Timeout,
+            RequestError::BadResponse => PeerRequestError::BadResponse,
+        }
+    }
+}
+
+impl From<Head> for StatusRequest {
+    fn from(head: Head) -> Self {
+        Self {
+            number: head.number,
+            hash: head.hash.as_bytes().to_vec(),
+            difficulty: head.difficulty.to_be_bytes_vec(),
+            total_difficulty: head.total_difficulty.to_be_bytes_vec(),
+            timestamp: head.timestamp,
+        }
+    }
+}
+
+impl StatusRequest {
+    pub(crate) fn to_head(&self) -> Option<Head> {
+        Some(Head {
+            number: self.number,
+            hash: h256(&self.hash)?,
+            difficulty: u256(&self.difficulty)?,
+            total_difficulty: u256(&self.total_difficulty)?,
+            timestamp: self.timestamp,
+        })
+    }
+}
+
+impl Transactions {
+    pub(crate) fn new(transactions: &[TransactionSigned]) -> Self {
+        Self { transactions: transactions.iter().map(rlp).collect() }
+    }
+
+    /// Decodes the transactions, fails if any of them is invalid.
+    pub(crate) fn decode(&self) -> Result<Vec<TransactionSigned>, reth_rlp::DecodeError> {
+        self.transactions.iter().map(|tx| TransactionSigned::decode(&mut tx.as_slice())).collect()
+    }
+}
+
+fn rlp<T: Encodable>(item: &T) -> Vec<u8> {
+    let mut buf = Vec::new();
+    item.encode(&mut buf);
+    buf
+}
+
+pub(crate) fn peer_id(bytes: &[u8]) -> Option<PeerId> {
+    (bytes.len() == PeerId::len_bytes()).then(|| PeerId::from_slice(bytes))
+}
+
+fn h256(bytes: &[u8]) -> Option<H256> {
+    (bytes.len() == 32).then(|| H256::from_slice(bytes))
+}
+
+fn u256(bytes: &[u8]) -> Option<U256> {
+    (bytes.len() <= 32).then(|| U256::from_be_slice(bytes))
+}
@@ -0,0 +1,199 @@
+use crate::{
+    auth::AuthInterceptor,
+    proto::{
+        self, sentry_server, BodiesRequest, Empty, HeadersRequest, PeerCountResponse, PeerResponse,
+        PenalizePeerRequest, StatusRequest,
+    },
+    transactions::SentryTransactions,
+    MAX_MESSAGE_SIZE,
+};
+use futures::{Stream, StreamExt};
+use reth_interfaces::p2p::{
+    bodies::client::BodiesClient,
+    download::DownloadClient,
+    headers::client::{HeadersClient, StatusUpdater},
+    priority::Priority,
+};
+use reth_rpc::JwtSecret;
+use std::{net::SocketAddr, pin::Pin};
+use tokio::{net::TcpListener, sync::oneshot};
+use tokio_stream::wrappers::{
+    errors::BroadcastStreamRecvError, BroadcastStream, TcpListenerStream,
+};
+use tonic::{service::interceptor::InterceptedService, Request, Response, Status};
+use tracing::{debug, warn};
+
+/// Serves the sentry gRPC service with the peers of the given p2p client.
+///
+/// Every request must be authenticated with a JWT signed with the shared secret, see
+/// [SentryClient](crate::SentryClient).
+#[derive(Debug, Clone)]
+pub struct SentryServer<Client, S> {
+    /// The client that sends the requests to the peers, e.g. the fetch client of the network.
+    client: Client,
+    /// Updates the head that is announced to the peers.
+    status: S,
+    /// Exchanges transactions with the peers.
+    transactions: SentryTransactions,
+    /// The secret the tokens of the nodes are signed with.
+    secret: JwtSecret,
+}
+
+impl<Client, S> SentryServer<Client, S> {
+    /// Creates a new sentry server.
+    pub fn new(
+        client: Client,
+        status: S,
+        transactions: SentryTransactions,
+        secret: JwtSecret,
+    ) -> Self {
+        Self { client, status, transactions, secret }
+    }
+}
+
+impl<Client, S> SentryServer<Client, S>
+where
+    Client: HeadersClient + BodiesClient + 'static,
+    S: StatusUpdater + 'static,
+{
+    /// Serves the sentry service on the given address.
+    ///
+    /// Returns the address the server listens on and the handle of the server, the server stops
+    /// once the handle is dropped.
+    pub async fn start(
+        self,
+        addr: SocketAddr,
+    ) -> std::io::Result<(SocketAddr, SentryServerHandle)> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+
+        let interceptor = AuthInterceptor::new(self.secret.clone());
+        let service = sentry_server::SentryServer::new(self)
+            .max_decoding_message_size(MAX_MESSAGE_SIZE)
+            .max_encoding_message_size(MAX_MESSAGE_SIZE);
+        let service = InterceptedService::new(service, interceptor);
+        let (tx, rx) = oneshot::channel();
+        let server = tonic::transport::Server::builder().add_service(service);
+        tokio::spawn(async move {
+            let shutdown = async move {
+                let _ = rx.await;
+            };
+            if let Err(err) = server
+                .serve_with_incoming_shutdown(TcpListenerStream::new(listener), shutdown)
+                .await
+            {
+                warn!(target: "sentry", ?err, "Sentry server failed");
+            }
+        });
+        Ok((local_addr, SentryServerHandle { _shutdown: tx }))
+    }
+}
+
+/// Stops the sentry server when dropped.
+#[derive(Debug)]
+pub struct SentryServerHandle {
+    _shutdown: oneshot::Sender<()>,
+}
+
+type TransactionsStream =
+    Pin<Box<dyn Stream<Item = Result<proto::Transactions, Status>> + Send + 'static>>;
+
+#[tonic::async_trait]
+impl<Client, S> sentry_server::Sentry for SentryServer<Client, S>
+where
+    Client: HeadersClient + BodiesClient + 'static,
+    S: StatusUpdater + 'static,
+{
+    async fn get_block_headers(
+        &self,
+        request: Request<HeadersRequest>,
+    ) -> Result<Response<PeerResponse>, Status> {
+        let request = request.into_inner();
+        let priority = priority(request.high_priority);
+        let request =
+            request.to_request().ok_or_else(|| Status::invalid_argument("invalid block hash"))?;
+        let result = self.client.get_headers_with_priority(request, priority).await;
+        Ok(Response::new(PeerResponse::new(result)))
+    }
+
+    async fn get_block_bodies(
+        &self,
+        request: Request<BodiesRequest>,
+    ) -> Result<Response<PeerResponse>, Status> {
+        let request = request.into_inner();
+        let hashes =
+            request.to_hashes().ok_or_else(|| Status::invalid_argument("invalid block hash"))?;
+        let result = self
+            .client
+            .get_block_bodies_with_priority(hashes, priority(request.high_priority))
+            .await;
+        Ok(Response::new(PeerResponse::new(result)))
+    }
+
+    async fn penalize_peer(
+        &self,
+        request: Request<PenalizePeerRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let peer_id = proto::peer_id(&request.into_inner().peer_id)
+            .ok_or_else(|| Status::invalid_argument("invalid peer id"))?;
+        self.client.report_bad_message(peer_id);
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn peer_count(&self, _: Request<Empty>) -> Result<Response<PeerCountResponse>, Status> {
+        let peer_count = self.client.num_connected_peers() as u64;
+        Ok(Response::new(PeerCountResponse { peer_count }))
+    }
+
+    async fn update_status(
+        &self,
+        request: Request<StatusRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let head = request
+            .into_inner()
+            .to_head()
+            .ok_or_else(|| Status::invalid_argument("invalid head"))?;
+        self.status.update_status(head);
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn send_transactions(
+        &self,
+        request: Request<proto::Transactions>,
+    ) -> Result<Response<Empty>, Status> {
+        let transactions = request
+            .into_inner()
+            .decode()
+            .map_err(|err| Status::invalid_argument(format!("invalid transaction: {err}")))?;
+        self.transactions.propagate(transactions);
+        Ok(Response::new(Empty {}))
+    }
+
+    type SubscribeTransactionsStream = TransactionsStream;
+
+    async fn subscribe_transactions(
+        &self,
+        _: Request<Empty>,
+    ) -> Result<Response<Self::SubscribeTransactionsStream>, Status> {
+        let stream =
+            BroadcastStream::new(self.transactions.subscribe()).filter_map(|result| async move {
+                match result {
+                    Ok(transactions) => Some(Ok(proto::Transactions::new(&transactions))),
+                    Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                        debug!(target: "sentry", skipped, "Transaction subscriber lagged");
+                        None
+                    }
+                }
+            });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// Returns the request priority for the flag.
+fn priority(high_priority: bool) -> Priority {
+    if high_priority {
+        Priority::High
+    } else {
+        Priority::Normal
+    }
+}
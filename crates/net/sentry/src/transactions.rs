@@ -0,0 +1,152 @@
+use futures::StreamExt;
+use reth_eth_wire::PooledTransactions;
+use reth_network::{transactions::NetworkTransactionEvent, NetworkEvent, NetworkHandle};
+use reth_primitives::{PeerId, TransactionSigned, TxHash};
+use schnellru::{ByLength, LruMap};
+use std::{collections::HashSet, sync::Arc};
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::trace;
+
+/// The number of transaction batches a subscriber can lag behind before batches are dropped.
+const INCOMING_TRANSACTIONS_CAPACITY: usize = 1024;
+
+/// The number of transaction hashes that are remembered to not send a transaction twice.
+const SEEN_TRANSACTIONS_CAPACITY: u32 = 100_000;
+
+/// Exchanges the transactions of the node with the peers of the sentry.
+///
+/// Transactions of the node are sent to all peers, transactions of the peers are streamed to the
+/// nodes, which propagate them back once their pool accepted them. The sentry has no pool of its
+/// own, so it answers `GetPooledTransactions` requests with no transactions and ignores announced
+/// transaction hashes: only transactions that peers broadcast in full reach the nodes.
+#[derive(Debug, Clone)]
+pub struct SentryTransactions {
+    /// Sends the transactions of the node to the [SentryTransactionsTask].
+    outgoing: mpsc::UnboundedSender<Vec<TransactionSigned>>,
+    /// Broadcasts the transactions received from the peers.
+    incoming: broadcast::Sender<Arc<Vec<TransactionSigned>>>,
+}
+
+impl SentryTransactions {
+    /// Creates the transactions handle and the task that exchanges the transactions with the
+    /// peers of the network.
+    ///
+    /// `events` must be registered with
+    /// [NetworkManager::set_transactions](reth_network::NetworkManager::set_transactions).
+    pub fn new(
+        network: NetworkHandle,
+        events: mpsc::UnboundedReceiver<NetworkTransactionEvent>,
+    ) -> (Self, SentryTransactionsTask) {
+        let (transactions, outgoing) = Self::channel();
+        let task = SentryTransactionsTask {
+            network_events: network.event_listener(),
+            network,
+            events: UnboundedReceiverStream::new(events),
+            outgoing: UnboundedReceiverStream::new(outgoing),
+            incoming: transactions.incoming.clone(),
+            peers: Default::default(),
+            seen: LruMap::new(ByLength::new(SEEN_TRANSACTIONS_CAPACITY)),
+        };
+        (transactions, task)
+    }
+
+    /// Creates the handle and the receiver of the transactions of the node.
+    pub(crate) fn channel() -> (Self, mpsc::UnboundedReceiver<Vec<TransactionSigned>>) {
+        let (outgoing, rx) = mpsc::unbounded_channel();
+        let (incoming, _) = broadcast::channel(INCOMING_TRANSACTIONS_CAPACITY);
+        (Self { outgoing, incoming }, rx)
+    }
+
+    /// Sends the transactions of the node to the peers.
+    pub(crate) fn propagate(&self, transactions: Vec<TransactionSigned>) {
+        let _ = self.outgoing.send(transactions);
+    }
+
+    /// Returns a receiver of the transactions received from the peers.
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<Arc<Vec<TransactionSigned>>> {
+        self.incoming.subscribe()
+    }
+
+    /// Broadcasts transactions as if they were received from a peer.
+    #[cfg(test)]
+    pub(crate) fn on_incoming(&self, transactions: Vec<TransactionSigned>) {
+        // there may be no node subscribed at the moment
+        let _ = self.incoming.send(Arc::new(transactions));
+    }
+}
+
+/// The task that exchanges the transactions of [SentryTransactions] with the peers of the
+/// network, runs until the network is dropped.
+#[derive(Debug)]
+pub struct SentryTransactionsTask {
+    network: NetworkHandle,
+    /// Sessions of the network.
+    network_events: UnboundedReceiverStream<NetworkEvent>,
+    /// Transaction messages of the peers.
+    events: UnboundedReceiverStream<NetworkTransactionEvent>,
+    /// Transactions of the node.
+    outgoing: UnboundedReceiverStream<Vec<TransactionSigned>>,
+    /// Broadcasts the transactions of the peers.
+    incoming: broadcast::Sender<Arc<Vec<TransactionSigned>>>,
+    /// The connected peers.
+    peers: HashSet<PeerId>,
+    /// Transactions that were already sent, so transactions that several nodes send aren't sent
+    /// twice.
+    seen: LruMap<TxHash, ()>,
+}
+
+impl SentryTransactionsTask {
+    /// Runs the task.
+    pub async fn run(mut self) {
+        loop {
+            tokio::select! {
+                Some(event) = self.network_events.next() => match event {
+                    NetworkEvent::SessionEstablished { peer_id, .. } => {
+                        self.peers.insert(peer_id);
+                    }
+                    NetworkEvent::SessionClosed { peer_id, .. } => {
+                        self.peers.remove(&peer_id);
+                    }
+                    _ => {}
+                },
+                Some(transactions) = self.outgoing.next() => self.propagate(transactions),
+                event = self.events.next() => match event {
+                    Some(event) => self.on_network_event(event),
+                    None => return,
+                },
+            }
+        }
+    }
+
+    fn on_network_event(&mut self, event: NetworkTransactionEvent) {
+        match event {
+            NetworkTransactionEvent::IncomingTransactions { peer_id, msg } => {
+                trace!(target: "sentry", ?peer_id, count = msg.0.len(), "Received transactions");
+                let _ = self.incoming.send(Arc::new(msg.0));
+            }
+            NetworkTransactionEvent::IncomingPooledTransactionHashes { .. } => {}
+            NetworkTransactionEvent::GetPooledTransactions { response, .. } => {
+                let _ = response.send(Ok(PooledTransactions::default()));
+            }
+        }
+    }
+
+    /// Sends the transactions that weren't seen yet to all peers.
+    fn propagate(&mut self, transactions: Vec<TransactionSigned>) {
+        let mut unseen = Vec::with_capacity(transactions.len());
+        for tx in transactions {
+            let hash = tx.hash();
+            if self.seen.get(&hash).is_none() {
+                self.seen.insert(hash, ());
+                unseen.push(Arc::new(tx));
+            }
+        }
+        if unseen.is_empty() {
+            return
+        }
+        for peer_id in &self.peers {
+            self.network.send_transactions(*peer_id, unseen.clone());
+        }
+    }
+}
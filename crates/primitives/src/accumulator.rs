@@ -0,0 +1,338 @@
+//! The accumulator of pre-merge block headers, as used by the Portal network and era files.
+//!
+//! See also <https://github.com/ethereum/portal-network-specs/blob/master/history-network.md>
+
+use crate::{BlockNumber, Chain, H256, U256};
+use hex_literal::hex;
+use sha2::{Digest, Sha256};
+
+/// The number of headers in an epoch of the accumulator.
+pub const EPOCH_SIZE: u64 = 8192;
+
+/// The maximum number of epochs in the accumulator.
+pub const MAX_HISTORICAL_EPOCHS: usize = 2048;
+
+/// The root of the accumulator of all pre-merge mainnet headers, the Portal network's master
+/// accumulator.
+pub const MAINNET_HEADER_ACCUMULATOR_ROOT: H256 =
+    H256(hex!("8eac399e24480dce3cfe06f4bdecba51c6e5d0c46200e3e8611a0b44a3a69ff9"));
+
+/// The root of the first epoch of the mainnet accumulator, the headers of blocks `0..8192`.
+pub const MAINNET_FIRST_EPOCH_ROOT: H256 =
+    H256(hex!("5ec1ffb8c3b146f42606c74ced973dc16ec5a107c0345858c343fc94780b4218"));
+
+/// The block hash and total difficulty of a header in the accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderRecord {
+    /// The hash of the header.
+    pub block_hash: H256,
+    /// The total difficulty at the header.
+    pub total_difficulty: U256,
+}
+
+impl HeaderRecord {
+    /// Returns the SSZ hash tree root of the record.
+    fn tree_hash_root(&self) -> H256 {
+        hash_pair(self.block_hash, H256(self.total_difficulty.to_le_bytes::<32>()))
+    }
+}
+
+/// Errors when decoding a [HeaderAccumulator].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HeaderAccumulatorError {
+    /// The encoding is not a valid SSZ encoded accumulator.
+    #[error("invalid SSZ encoding of the header accumulator")]
+    InvalidEncoding,
+    /// The accumulator has more epochs than allowed.
+    #[error("header accumulator has {0} epochs, max is {MAX_HISTORICAL_EPOCHS}")]
+    TooManyEpochs(usize),
+}
+
+/// The accumulator of pre-merge headers.
+///
+/// The headers are grouped into epochs of [EPOCH_SIZE] headers, the last epoch ends with the last
+/// pre-merge header and may be shorter. The accumulator stores the root of every epoch, so a
+/// complete epoch of headers can be verified by hashing their [HeaderRecord]s, without verifying
+/// the proof of work of the headers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderAccumulator {
+    historical_epochs: Vec<H256>,
+}
+
+impl HeaderAccumulator {
+    /// Creates a new accumulator with the given epoch roots.
+    pub fn new(historical_epochs: Vec<H256>) -> Result<Self, HeaderAccumulatorError> {
+        if historical_epochs.len() > MAX_HISTORICAL_EPOCHS {
+            return Err(HeaderAccumulatorError::TooManyEpochs(historical_epochs.len()))
+        }
+        Ok(Self { historical_epochs })
+    }
+
+    /// Decodes the SSZ encoding of the accumulator, e.g. the Portal network's master accumulator.
+    pub fn decode_ssz(bytes: &[u8]) -> Result<Self, HeaderAccumulatorError> {
+        // a container with a single variable size field: the offset of the field and the field
+        if bytes.len() < 4 {
+            return Err(HeaderAccumulatorError::InvalidEncoding)
+        }
+        let (offset, epochs) = bytes.split_at(4);
+        if offset != 4u32.to_le_bytes() || epochs.len() % 32 != 0 {
+            return Err(HeaderAccumulatorError::InvalidEncoding)
+        }
+        Self::new(epochs.chunks_exact(32).map(H256::from_slice).collect())
+    }
+
+    /// Returns the SSZ encoding of the accumulator.
+    pub fn encode_ssz(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(4 + self.historical_epochs.len() * 32);
+        out.extend_from_slice(&4u32.to_le_bytes());
+        for epoch in &self.historical_epochs {
+            out.extend_from_slice(epoch.as_bytes());
+        }
+        out
+    }
+
+    /// Returns the trusted accumulator root of the given chain, if known.
+    pub fn trusted_root(chain: Chain) -> Option<H256> {
+        (chain == Chain::mainnet()).then_some(MAINNET_HEADER_ACCUMULATOR_ROOT)
+    }
+
+    /// Returns the SSZ hash tree root of the accumulator.
+    pub fn root(&self) -> H256 {
+        mix_in_length(
+            merkleize(&self.historical_epochs, MAX_HISTORICAL_EPOCHS),
+            self.historical_epochs.len(),
+        )
+    }
+
+    /// Returns the number of epochs.
+    pub fn num_epochs(&self) -> usize {
+        self.historical_epochs.len()
+    }
+
+    /// Returns the root of the given epoch.
+    pub fn epoch_root(&self, epoch: u64) -> Option<H256> {
+        self.historical_epochs.get(usize::try_from(epoch).ok()?).copied()
+    }
+
+    /// Returns the epoch of the given block.
+    pub fn epoch_of(block: BlockNumber) -> u64 {
+        block / EPOCH_SIZE
+    }
+
+    /// Returns true if the block is part of an epoch of this accumulator.
+    ///
+    /// Note: This does not check whether the block is a pre-merge block.
+    pub fn covers(&self, block: BlockNumber) -> bool {
+        Self::epoch_of(block) < self.historical_epochs.len() as u64
+    }
+
+    /// Computes the root of an epoch from the records of its headers, in order.
+    pub fn compute_epoch_root(records: &[HeaderRecord]) -> H256 {
+        let leaves = records.iter().map(HeaderRecord::tree_hash_root).collect::<Vec<_>>();
+        mix_in_length(merkleize(&leaves, EPOCH_SIZE as usize), records.len())
+    }
+
+    /// Returns true if the records of the headers of the given epoch match the accumulator.
+    pub fn verify_epoch(&self, epoch: u64, records: &[HeaderRecord]) -> bool {
+        self.epoch_root(epoch) == Some(Self::compute_epoch_root(records))
+    }
+
+    /// Returns the merkle proof of the root of the given epoch against [Self::root].
+    ///
+    /// The proof consists of the sibling of every level of the tree of epochs, from the bottom up,
+    /// followed by the chunk of the length that's mixed into the root.
+    pub fn epoch_proof(&self, epoch: u64) -> Option<Vec<H256>> {
+        let mut index = usize::try_from(epoch).ok().filter(|i| *i < self.num_epochs())?;
+        let depth = MAX_HISTORICAL_EPOCHS.trailing_zeros() as usize;
+        let mut proof = Vec::with_capacity(depth + 1);
+        let mut layer = self.historical_epochs.clone();
+        let mut zero_hash = H256::zero();
+        for _ in 0..depth {
+            proof.push(layer.get(index ^ 1).copied().unwrap_or(zero_hash));
+            if layer.len() % 2 == 1 {
+                layer.push(zero_hash);
+            }
+            layer = layer.chunks_exact(2).map(|pair| hash_pair(pair[0], pair[1])).collect();
+            zero_hash = hash_pair(zero_hash, zero_hash);
+            index /= 2;
+        }
+        proof.push(length_chunk(self.num_epochs()));
+        Some(proof)
+    }
+
+    /// Returns true if the proof shows that `epoch_root` is the root of the given epoch of the
+    /// accumulator with the given root, see [Self::epoch_proof].
+    pub fn verify_epoch_proof(root: H256, epoch: u64, epoch_root: H256, proof: &[H256]) -> bool {
+        let depth = MAX_HISTORICAL_EPOCHS.trailing_zeros() as usize;
+        if proof.len() != depth + 1 || epoch >= MAX_HISTORICAL_EPOCHS as u64 {
+            return false
+        }
+        let (branch, length) = proof.split_at(depth);
+        let mut node = epoch_root;
+        for (level, sibling) in branch.iter().enumerate() {
+            node = if (epoch >> level) & 1 == 1 {
+                hash_pair(*sibling, node)
+            } else {
+                hash_pair(node, *sibling)
+            };
+        }
+        hash_pair(node, length[0]) == root
+    }
+}
+
+fn hash_pair(left: H256, right: H256) -> H256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    H256::from_slice(&hasher.finalize())
+}
+
+/// Computes the SSZ merkle root of the chunks, padded with zero chunks to the given limit.
+fn merkleize(chunks: &[H256], limit: usize) -> H256 {
+    let depth = limit.next_power_of_two().trailing_zeros() as usize;
+    let mut zero_hashes = vec![H256::zero(); depth + 1];
+    for i in 0..depth {
+        zero_hashes[i + 1] = hash_pair(zero_hashes[i], zero_hashes[i]);
+    }
+    if chunks.is_empty() {
+        return zero_hashes[depth]
+    }
+
+    let mut layer = chunks.to_vec();
+    for zero_hash in zero_hashes.iter().take(depth) {
+        if layer.len() % 2 == 1 {
+            layer.push(*zero_hash);
+        }
+        layer = layer.chunks_exact(2).map(|pair| hash_pair(pair[0], pair[1])).collect();
+    }
+    layer[0]
+}
+
+/// Mixes the length of a list into its merkle root.
+fn mix_in_length(root: H256, len: usize) -> H256 {
+    hash_pair(root, length_chunk(len))
+}
+
+/// Returns the chunk of the length of a list that's mixed into its root.
+fn length_chunk(len: usize) -> H256 {
+    let mut length = [0u8; 32];
+    length[..8].copy_from_slice(&(len as u64).to_le_bytes());
+    H256(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(n: u64) -> Vec<HeaderRecord> {
+        (0..n)
+            .map(|i| HeaderRecord {
+                block_hash: H256::from_low_u64_be(i),
+                total_difficulty: U256::from(i * 10),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn merkleize_pads_to_limit() {
+        let chunk = H256::repeat_byte(1);
+        assert_eq!(merkleize(&[chunk], 1), chunk);
+        assert_eq!(merkleize(&[chunk], 2), hash_pair(chunk, H256::zero()));
+        assert_eq!(
+            merkleize(&[chunk], 3),
+            hash_pair(hash_pair(chunk, H256::zero()), hash_pair(H256::zero(), H256::zero()))
+        );
+        assert_eq!(merkleize(&[], 2), hash_pair(H256::zero(), H256::zero()));
+    }
+
+    #[test]
+    fn verify_epochs() {
+        let full = records(EPOCH_SIZE);
+        let partial = records(100);
+        let accumulator = HeaderAccumulator::new(vec![
+            HeaderAccumulator::compute_epoch_root(&full),
+            HeaderAccumulator::compute_epoch_root(&partial),
+        ])
+        .unwrap();
+
+        assert!(accumulator.verify_epoch(0, &full));
+        assert!(accumulator.verify_epoch(1, &partial));
+        assert!(!accumulator.verify_epoch(1, &partial[..99]));
+        assert!(!accumulator.verify_epoch(2, &partial));
+
+        let mut tampered = partial;
+        tampered[5].total_difficulty += U256::from(1);
+        assert!(!accumulator.verify_epoch(1, &tampered));
+
+        assert!(accumulator.covers(EPOCH_SIZE * 2 - 1));
+        assert!(!accumulator.covers(EPOCH_SIZE * 2));
+    }
+
+    #[test]
+    fn mainnet_accumulator_root() {
+        assert_eq!(
+            HeaderAccumulator::trusted_root(Chain::mainnet()),
+            Some(H256(hex!("8eac399e24480dce3cfe06f4bdecba51c6e5d0c46200e3e8611a0b44a3a69ff9")))
+        );
+        assert_eq!(HeaderAccumulator::trusted_root(Chain::goerli()), None);
+    }
+
+    #[test]
+    fn verify_epoch_proofs() {
+        // as many epochs as the mainnet accumulator, the first is the real first mainnet epoch
+        let mut epochs = vec![MAINNET_FIRST_EPOCH_ROOT];
+        epochs.extend((1..1897).map(H256::from_low_u64_be));
+        let accumulator = HeaderAccumulator::new(epochs).unwrap();
+        let root = accumulator.root();
+
+        for epoch in [0, 1, 2, 1000, 1896] {
+            let epoch_root = accumulator.epoch_root(epoch).unwrap();
+            let proof = accumulator.epoch_proof(epoch).unwrap();
+            assert!(HeaderAccumulator::verify_epoch_proof(root, epoch, epoch_root, &proof));
+            assert!(!HeaderAccumulator::verify_epoch_proof(
+                MAINNET_HEADER_ACCUMULATOR_ROOT,
+                epoch,
+                epoch_root,
+                &proof
+            ));
+            assert!(!HeaderAccumulator::verify_epoch_proof(
+                root,
+                epoch,
+                H256::repeat_byte(0xff),
+                &proof
+            ));
+            assert!(!HeaderAccumulator::verify_epoch_proof(root, epoch, epoch_root, &proof[1..]));
+        }
+
+        let proof = accumulator.epoch_proof(1).unwrap();
+        assert!(!HeaderAccumulator::verify_epoch_proof(
+            root,
+            2,
+            accumulator.epoch_root(1).unwrap(),
+            &proof
+        ));
+        assert_eq!(accumulator.epoch_proof(1897), None);
+    }
+
+    #[test]
+    fn ssz_roundtrip() {
+        let accumulator =
+            HeaderAccumulator::new(vec![H256::repeat_byte(1), H256::repeat_byte(2)]).unwrap();
+        let encoded = accumulator.encode_ssz();
+        assert_eq!(encoded.len(), 4 + 64);
+        assert_eq!(HeaderAccumulator::decode_ssz(&encoded), Ok(accumulator.clone()));
+        assert_eq!(
+            HeaderAccumulator::decode_ssz(&encoded[..40]),
+            Err(HeaderAccumulatorError::InvalidEncoding)
+        );
+
+        // the root of a single field container is the root of the field
+        assert_eq!(
+            accumulator.root(),
+            mix_in_length(
+                merkleize(&[H256::repeat_byte(1), H256::repeat_byte(2)], MAX_HISTORICAL_EPOCHS),
+                2
+            )
+        );
+    }
+}
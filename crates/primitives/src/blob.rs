@@ -0,0 +1,68 @@
+use crate::{Bytes, H256};
+use reth_rlp::{RlpDecodable, RlpEncodable};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The version byte of versioned hashes derived from KZG commitments.
+///
+/// See also <https://eips.ethereum.org/EIPS/eip-4844#helpers>
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The blobs, commitments and proofs that accompany a blob transaction.
+///
+/// The sidecar is not part of the transaction's consensus encoding. It is kept while the
+/// transaction is in the pool and for the blob retention window after the transaction was mined.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize, RlpEncodable, RlpDecodable,
+)]
+pub struct BlobTransactionSidecar {
+    /// The blobs of the transaction.
+    pub blobs: Vec<Bytes>,
+    /// The KZG commitment of each blob.
+    pub commitments: Vec<Bytes>,
+    /// The KZG proof of each blob.
+    pub proofs: Vec<Bytes>,
+}
+
+impl BlobTransactionSidecar {
+    /// Returns the versioned hashes of all blobs, derived from their commitments.
+    pub fn versioned_hashes(&self) -> impl Iterator<Item = H256> + '_ {
+        self.commitments.iter().map(|commitment| kzg_to_versioned_hash(commitment))
+    }
+
+    /// Calculates a heuristic for the in-memory size of the sidecar.
+    pub fn size(&self) -> usize {
+        self.blobs
+            .iter()
+            .chain(self.commitments.iter())
+            .chain(self.proofs.iter())
+            .map(|item| item.len())
+            .sum()
+    }
+}
+
+/// Calculates the versioned hash of a KZG commitment.
+///
+/// This is the sha256 hash of the commitment with the first byte replaced by
+/// [VERSIONED_HASH_VERSION_KZG].
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> H256 {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    H256(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn versioned_hash_of_commitment() {
+        // commitment of the empty blob
+        let commitment = hex!("c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000");
+        assert_eq!(
+            kzg_to_versioned_hash(&commitment),
+            H256(hex!("010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014"))
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    net::{goerli_nodes, mainnet_nodes, sepolia_nodes},
+    net::{goerli_nodes, holesky_nodes, mainnet_nodes, sepolia_nodes},
     NodeRecord, U256,
 };
 use ethers_core::types::U64;
@@ -11,7 +11,8 @@ use std::{fmt, str::FromStr};
 // The chain spec module.
 mod spec;
 pub use spec::{
-    AllGenesisFormats, ChainSpec, ChainSpecBuilder, ForkCondition, GOERLI, MAINNET, SEPOLIA,
+    AllGenesisFormats, ChainSpec, ChainSpecBuilder, DepositContract, ForkCondition,
+    DEPOSIT_CONTRACT_TOPIC, DEV, GOERLI, HOLESKY, MAINNET, SEPOLIA,
 };
 
 // The chain info module.
@@ -44,6 +45,16 @@ impl Chain {
         Chain::Named(ethers_core::types::Chain::Sepolia)
     }
 
+    /// Returns the holesky chain.
+    pub const fn holesky() -> Self {
+        Chain::Id(17000)
+    }
+
+    /// Returns the dev chain.
+    pub const fn dev() -> Self {
+        Chain::Named(ethers_core::types::Chain::Dev)
+    }
+
     /// The id of the chain
     pub fn id(&self) -> u64 {
         match self {
@@ -79,6 +90,9 @@ impl Chain {
     /// Returns bootnodes for the given chain.
     pub fn bootnodes(self) -> Option<Vec<NodeRecord>> {
         use ethers_core::types::Chain::*;
+        if self.id() == Chain::holesky().id() {
+            return Some(holesky_nodes())
+        }
         match self.try_into().ok()? {
             Mainnet => Some(mainnet_nodes()),
             Goerli => Some(goerli_nodes()),
@@ -1,10 +1,10 @@
 use crate::{
-    constants::{EIP1559_INITIAL_BASE_FEE, EMPTY_WITHDRAWALS},
+    constants::{EIP1559_INITIAL_BASE_FEE, EMPTY_WITHDRAWALS, HOLESKY_GENESIS},
     forkid::ForkFilterKey,
     header::Head,
     proofs::genesis_state_root,
-    BlockNumber, Chain, ForkFilter, ForkHash, ForkId, Genesis, GenesisAccount, Hardfork, Header,
-    H160, H256, U256,
+    Address, BlockNumber, Chain, ForkFilter, ForkHash, ForkId, Genesis, GenesisAccount, Hardfork,
+    Header, NodeRecord, H160, H256, U256,
 };
 use ethers_core::utils::Genesis as EthersGenesis;
 use hex_literal::hex;
@@ -44,6 +44,12 @@ pub static MAINNET: Lazy<ChainSpec> = Lazy::new(|| ChainSpec {
         ),
         (Hardfork::Shanghai, ForkCondition::Timestamp(1681338455)),
     ]),
+    deposit_contract: Some(DepositContract::new(
+        H160(hex!("00000000219ab540356cbb839cbe05303d7705fa")),
+        11_052_984,
+        DEPOSIT_CONTRACT_TOPIC,
+    )),
+    bootnodes: Vec::new(),
 });
 
 /// The Goerli spec
@@ -72,6 +78,12 @@ pub static GOERLI: Lazy<ChainSpec> = Lazy::new(|| ChainSpec {
         ),
         (Hardfork::Shanghai, ForkCondition::Timestamp(1678832736)),
     ]),
+    deposit_contract: Some(DepositContract::new(
+        H160(hex!("ff50ed3d0ec03ac01d4c79aad74928bff48a7b2b")),
+        4_367_322,
+        DEPOSIT_CONTRACT_TOPIC,
+    )),
+    bootnodes: Vec::new(),
 });
 
 /// The Sepolia spec
@@ -104,6 +116,56 @@ pub static SEPOLIA: Lazy<ChainSpec> = Lazy::new(|| ChainSpec {
         ),
         (Hardfork::Shanghai, ForkCondition::Timestamp(1677557088)),
     ]),
+    deposit_contract: Some(DepositContract::new(
+        H160(hex!("7f02c3e3c98b133055b8b348b2ac625669ed295d")),
+        1_273_020,
+        DEPOSIT_CONTRACT_TOPIC,
+    )),
+    bootnodes: Vec::new(),
+});
+
+/// The Holesky spec
+pub static HOLESKY: Lazy<ChainSpec> = Lazy::new(|| ChainSpec {
+    chain: Chain::holesky(),
+    genesis: serde_json::from_str(include_str!("../../res/genesis/holesky.json"))
+        .expect("Can't deserialize Holesky genesis json"),
+    genesis_hash: Some(HOLESKY_GENESIS),
+    hardforks: BTreeMap::from([
+        (Hardfork::Frontier, ForkCondition::Block(0)),
+        (Hardfork::Homestead, ForkCondition::Block(0)),
+        (Hardfork::Dao, ForkCondition::Block(0)),
+        (Hardfork::Tangerine, ForkCondition::Block(0)),
+        (Hardfork::SpuriousDragon, ForkCondition::Block(0)),
+        (Hardfork::Byzantium, ForkCondition::Block(0)),
+        (Hardfork::Constantinople, ForkCondition::Block(0)),
+        (Hardfork::Petersburg, ForkCondition::Block(0)),
+        (Hardfork::Istanbul, ForkCondition::Block(0)),
+        (Hardfork::Berlin, ForkCondition::Block(0)),
+        (Hardfork::London, ForkCondition::Block(0)),
+        (Hardfork::Paris, ForkCondition::TTD { fork_block: Some(0), total_difficulty: U256::ZERO }),
+        (Hardfork::Shanghai, ForkCondition::Timestamp(1696000704)),
+    ]),
+    deposit_contract: Some(DepositContract::new(
+        H160(hex!("4242424242424242424242424242424242424242")),
+        0,
+        DEPOSIT_CONTRACT_TOPIC,
+    )),
+    bootnodes: Vec::new(),
+});
+
+/// A local development spec with all forks, up to and including Shanghai, activated at genesis.
+///
+/// The genesis allocates 10_000 ETH to each of the first ten accounts derived from the well known
+/// `test test test test test test test test test test test junk` mnemonic.
+pub static DEV: Lazy<ChainSpec> = Lazy::new(|| {
+    ChainSpecBuilder::default()
+        .chain(Chain::dev())
+        .genesis(
+            serde_json::from_str(include_str!("../../res/genesis/dev.json"))
+                .expect("Can't deserialize Dev genesis json"),
+        )
+        .shanghai_activated()
+        .build()
 });
 
 /// An Ethereum chain specification.
@@ -130,6 +192,16 @@ pub struct ChainSpec {
 
     /// The active hard forks and their activation conditions
     pub hardforks: BTreeMap<Hardfork, ForkCondition>,
+
+    /// The deposit contract deployed for proof of stake, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deposit_contract: Option<DepositContract>,
+
+    /// The bootnodes of the chain.
+    ///
+    /// If empty, the default bootnodes of the chain are used, see [`Chain::bootnodes`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bootnodes: Vec<NodeRecord>,
 }
 
 impl ChainSpec {
@@ -186,6 +258,23 @@ impl ChainSpec {
         &self.hardforks
     }
 
+    /// Returns the deposit contract of the chain, if any.
+    pub fn deposit_contract(&self) -> Option<&DepositContract> {
+        self.deposit_contract.as_ref()
+    }
+
+    /// Returns the bootnodes of the chain.
+    ///
+    /// These are the bootnodes configured in the spec, or the default bootnodes if the chain is a
+    /// known network.
+    pub fn bootnodes(&self) -> Option<Vec<NodeRecord>> {
+        if self.bootnodes.is_empty() {
+            self.chain.bootnodes()
+        } else {
+            Some(self.bootnodes.clone())
+        }
+    }
+
     /// Get the fork condition for the given fork.
     pub fn fork(&self, fork: Hardfork) -> ForkCondition {
         self.hardforks.get(&fork).copied().unwrap_or(ForkCondition::Never)
@@ -321,6 +410,8 @@ impl From<EthersGenesis> for ChainSpec {
             genesis: genesis_block,
             genesis_hash: None,
             hardforks,
+            deposit_contract: None,
+            bootnodes: Vec::new(),
         }
     }
 }
@@ -362,6 +453,8 @@ pub struct ChainSpecBuilder {
     chain: Option<Chain>,
     genesis: Option<Genesis>,
     hardforks: BTreeMap<Hardfork, ForkCondition>,
+    deposit_contract: Option<DepositContract>,
+    bootnodes: Vec<NodeRecord>,
 }
 
 impl ChainSpecBuilder {
@@ -371,6 +464,8 @@ impl ChainSpecBuilder {
             chain: Some(MAINNET.chain),
             genesis: Some(MAINNET.genesis.clone()),
             hardforks: MAINNET.hardforks.clone(),
+            deposit_contract: MAINNET.deposit_contract,
+            bootnodes: MAINNET.bootnodes.clone(),
         }
     }
 
@@ -380,18 +475,74 @@ impl ChainSpecBuilder {
         self
     }
 
+    /// Set the chain ID from its numeric value.
+    pub fn chain_id(self, chain_id: u64) -> Self {
+        self.chain(Chain::from(chain_id))
+    }
+
     /// Set the genesis block.
     pub fn genesis(mut self, genesis: Genesis) -> Self {
         self.genesis = Some(genesis);
         self
     }
 
+    /// Add an account to the genesis alloc, replacing any existing account at the same address.
+    ///
+    /// If no genesis block is set yet, this starts from [`Genesis::default`].
+    pub fn with_account(self, address: Address, account: GenesisAccount) -> Self {
+        self.with_accounts([(address, account)])
+    }
+
+    /// Add the given accounts to the genesis alloc, see [`Self::with_account`].
+    pub fn with_accounts(
+        mut self,
+        accounts: impl IntoIterator<Item = (Address, GenesisAccount)>,
+    ) -> Self {
+        self.genesis = Some(self.genesis.unwrap_or_default().extend_accounts(accounts));
+        self
+    }
+
+    /// Modify the genesis block with the given closure, e.g. to set the gas limit or timestamp.
+    ///
+    /// If no genesis block is set yet, the closure receives [`Genesis::default`].
+    pub fn map_genesis(mut self, f: impl FnOnce(Genesis) -> Genesis) -> Self {
+        self.genesis = Some(f(self.genesis.unwrap_or_default()));
+        self
+    }
+
+    /// Set the deposit contract of the chain.
+    pub fn deposit_contract(mut self, deposit_contract: DepositContract) -> Self {
+        self.deposit_contract = Some(deposit_contract);
+        self
+    }
+
+    /// Set the bootnodes of the chain, replacing the default bootnodes of known chains.
+    pub fn bootnodes(mut self, bootnodes: impl IntoIterator<Item = NodeRecord>) -> Self {
+        self.bootnodes = bootnodes.into_iter().collect();
+        self
+    }
+
     /// Add the given fork with the given activation condition to the spec.
     pub fn with_fork(mut self, fork: Hardfork, condition: ForkCondition) -> Self {
         self.hardforks.insert(fork, condition);
         self
     }
 
+    /// Add all given forks with their activation conditions to the spec.
+    pub fn with_forks(
+        mut self,
+        forks: impl IntoIterator<Item = (Hardfork, ForkCondition)>,
+    ) -> Self {
+        self.hardforks.extend(forks);
+        self
+    }
+
+    /// Remove the given fork from the spec.
+    pub fn without_fork(mut self, fork: Hardfork) -> Self {
+        self.hardforks.remove(&fork);
+        self
+    }
+
     /// Enable Frontier at genesis.
     pub fn frontier_activated(mut self) -> Self {
         self.hardforks.insert(Hardfork::Frontier, ForkCondition::Block(0));
@@ -483,6 +634,8 @@ impl ChainSpecBuilder {
             genesis: self.genesis.expect("The genesis is required"),
             genesis_hash: None,
             hardforks: self.hardforks,
+            deposit_contract: self.deposit_contract,
+            bootnodes: self.bootnodes,
         }
     }
 }
@@ -493,10 +646,34 @@ impl From<&ChainSpec> for ChainSpecBuilder {
             chain: Some(value.chain),
             genesis: Some(value.genesis.clone()),
             hardforks: value.hardforks.clone(),
+            deposit_contract: value.deposit_contract,
+            bootnodes: value.bootnodes.clone(),
         }
     }
 }
 
+/// The topic of the `DepositEvent` log emitted by the deposit contract.
+pub const DEPOSIT_CONTRACT_TOPIC: H256 =
+    H256(hex!("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"));
+
+/// The deposit contract used by the beacon chain to register validators.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositContract {
+    /// The address of the deposit contract.
+    pub address: Address,
+    /// The block at which the contract was deployed.
+    pub block: BlockNumber,
+    /// The topic of the deposit event.
+    pub topic: H256,
+}
+
+impl DepositContract {
+    /// Creates a new [`DepositContract`].
+    pub const fn new(address: Address, block: BlockNumber, topic: H256) -> Self {
+        Self { address, block, topic }
+    }
+}
+
 /// The condition at which a fork is activated.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum ForkCondition {
@@ -612,8 +789,9 @@ impl ForkCondition {
 #[cfg(test)]
 mod tests {
     use crate::{
-        AllGenesisFormats, Chain, ChainSpec, ChainSpecBuilder, ForkCondition, ForkHash, ForkId,
-        Genesis, Hardfork, Head, GOERLI, H256, MAINNET, SEPOLIA, U256,
+        AllGenesisFormats, Chain, ChainSpec, ChainSpecBuilder, DepositContract, ForkCondition,
+        ForkHash, ForkId, Genesis, GenesisAccount, Hardfork, Head, DEPOSIT_CONTRACT_TOPIC, DEV,
+        GOERLI, H160, H256, HOLESKY, HOLESKY_GENESIS, MAINNET, SEPOLIA, U256,
     };
     use bytes::BytesMut;
     use ethers_core::types as EtherType;
@@ -829,6 +1007,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn holesky_forkids() {
+        test_fork_ids(
+            &HOLESKY,
+            &[
+                (
+                    Head { number: 0, ..Default::default() },
+                    ForkId { hash: ForkHash([0xc6, 0x1a, 0x60, 0x98]), next: 1696000704 },
+                ),
+                // Last Paris block
+                (
+                    Head { number: 123, timestamp: 1696000703, ..Default::default() },
+                    ForkId { hash: ForkHash([0xc6, 0x1a, 0x60, 0x98]), next: 1696000704 },
+                ),
+                // First Shanghai block
+                (
+                    Head { number: 123, timestamp: 1696000704, ..Default::default() },
+                    ForkId { hash: ForkHash([0xfd, 0x4f, 0x01, 0x6b]), next: 0 },
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn holesky_genesis() {
+        assert_eq!(HOLESKY.chain.id(), 17000);
+        assert_eq!(HOLESKY.genesis_hash(), HOLESKY_GENESIS);
+        assert_eq!(ForkHash::from(HOLESKY.genesis_hash()), ForkHash([0xc6, 0x1a, 0x60, 0x98]));
+        assert_eq!(HOLESKY.bootnodes().map(|nodes| nodes.len()), Some(2));
+
+        let header = HOLESKY.genesis_header();
+        assert_eq!(header.nonce, 0x1234);
+        assert_eq!(header.timestamp, 1695902100);
+        assert_eq!(header.gas_limit, 25_000_000);
+        assert_eq!(header.base_fee_per_gas, Some(crate::constants::EIP1559_INITIAL_BASE_FEE));
+        // shanghai activates after genesis
+        assert_eq!(header.withdrawals_root, None);
+        assert_eq!(
+            header.state_root,
+            H256(hex_literal::hex!(
+                "69d8c9d72f6fa4ad42d4702b433707212f90db395eb54dc20bc85de253788783"
+            ))
+        );
+        // the header computed from the bundled genesis is the known genesis block
+        assert_eq!(header.hash_slow(), HOLESKY_GENESIS);
+    }
+
     /// Checks that time-based forks work
     ///
     /// This is based off of the test vectors here: https://github.com/ethereum/go-ethereum/blob/5c8cc10d1e05c23ff1108022f4150749e73c0ca1/core/forkid/forkid_test.go#L155-L188
@@ -1206,4 +1431,42 @@ mod tests {
         let hash = chainspec.genesis_header().hash_slow();
         assert_eq!(hash, expected_hash);
     }
+
+    #[test]
+    fn build_custom_chain_spec() {
+        let address = H160::from_low_u64_be(1);
+        let deposit_contract =
+            DepositContract::new(H160::from_low_u64_be(2), 0, DEPOSIT_CONTRACT_TOPIC);
+        let spec = ChainSpec::builder()
+            .chain_id(1337)
+            .with_account(address, GenesisAccount::default().with_balance(U256::from(1)))
+            .map_genesis(|genesis| genesis.with_gas_limit(30_000_000))
+            .shanghai_activated()
+            .without_fork(Hardfork::Dao)
+            .with_forks([(Hardfork::ArrowGlacier, ForkCondition::Never)])
+            .deposit_contract(deposit_contract)
+            .build();
+
+        assert_eq!(spec.chain.id(), 1337);
+        assert_eq!(spec.genesis.gas_limit, 30_000_000);
+        assert_eq!(spec.genesis.alloc[&address].balance, U256::from(1));
+        assert_eq!(spec.fork(Hardfork::Shanghai), ForkCondition::Timestamp(0));
+        assert_eq!(spec.fork(Hardfork::Dao), ForkCondition::Never);
+        assert_eq!(spec.deposit_contract(), Some(&deposit_contract));
+
+        // the builder round trips through an existing spec
+        let mainnet = ChainSpecBuilder::from(&*MAINNET).build();
+        assert_eq!(mainnet.deposit_contract, MAINNET.deposit_contract);
+        assert_eq!(mainnet.genesis_hash(), MAINNET.genesis_hash());
+    }
+
+    #[test]
+    fn dev_genesis() {
+        let header = DEV.genesis_header();
+        assert_eq!(DEV.chain.id(), 1337);
+        assert_eq!(DEV.genesis.alloc.len(), 10);
+        assert_eq!(header.base_fee_per_gas, Some(crate::constants::EIP1559_INITIAL_BASE_FEE));
+        assert!(header.withdrawals_root.is_some());
+        assert!(DEV.fork(Hardfork::Paris).active_at_block(0));
+    }
 }
@@ -62,6 +62,10 @@ pub const GOERLI_GENESIS: H256 =
 pub const SEPOLIA_GENESIS: H256 =
     H256(hex!("25a5cc106eea7138acab33231d7160d69cb777ee0c2c553fcddf5138993e6dd9"));
 
+/// Holesky genesis hash.
+pub const HOLESKY_GENESIS: H256 =
+    H256(hex!("b5f7f912443c940f21fd611f12828d75b534364ed9e95ca4e307729a4661bde4"));
+
 /// Keccak256 over empty array.
 pub const KECCAK_EMPTY: H256 =
     H256(hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"));
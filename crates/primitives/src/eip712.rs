@@ -0,0 +1,482 @@
+//! Hashing of typed structured data as specified in
+//! [EIP-712](https://eips.ethereum.org/EIPS/eip-712).
+//!
+//! The [TypedData] of an `eth_signTypedData` request is signed by signing its
+//! [signing hash](TypedData::signing_hash):
+//! `keccak256(0x19 ‖ 0x01 ‖ domainSeparator ‖ hashStruct(message))`.
+
+use crate::{keccak256, serde_helper::num::from_int_or_hex_opt, Address, H256, U256};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+/// The name of the type of the domain.
+pub const EIP712_DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Errors when hashing typed data.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Eip712Error {
+    /// A type is neither an atomic, dynamic or array type, nor one of the struct types.
+    #[error("unknown type {0}")]
+    UnknownType(String),
+    /// A struct value doesn't have a value for one of the fields of its type.
+    #[error("missing value for field {field} of {ty}")]
+    MissingField {
+        /// The struct type.
+        ty: String,
+        /// The name of the missing field.
+        field: String,
+    },
+    /// A value doesn't match its type.
+    #[error("invalid value {value} for type {ty}")]
+    InvalidValue {
+        /// The type of the value.
+        ty: String,
+        /// The value.
+        value: String,
+    },
+}
+
+/// A field of a struct type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypedDataField {
+    /// The name of the field.
+    pub name: String,
+    /// The type of the field, like `uint256`, `Person` or `Person[]`.
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// The domain of typed data, it separates the signatures of different dapps and chains.
+///
+/// Only the fields that are set are part of the `EIP712Domain` type.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712Domain {
+    /// The name of the dapp or protocol.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The major version of the dapp or protocol.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The chain the signature is valid on.
+    #[serde(
+        default,
+        deserialize_with = "from_int_or_hex_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub chain_id: Option<U256>,
+    /// The contract that verifies the signature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifying_contract: Option<Address>,
+    /// A salt to disambiguate the domain, as a last resort.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<H256>,
+}
+
+impl Eip712Domain {
+    /// Returns the domain separator, the hash of the domain as an `EIP712Domain` struct.
+    pub fn separator(&self) -> H256 {
+        let mut fields = Vec::new();
+        let mut data = Vec::new();
+        if let Some(name) = &self.name {
+            fields.push("string name");
+            data.extend_from_slice(keccak256(name.as_bytes()).as_bytes());
+        }
+        if let Some(version) = &self.version {
+            fields.push("string version");
+            data.extend_from_slice(keccak256(version.as_bytes()).as_bytes());
+        }
+        if let Some(chain_id) = self.chain_id {
+            fields.push("uint256 chainId");
+            data.extend_from_slice(&chain_id.to_be_bytes::<32>());
+        }
+        if let Some(verifying_contract) = self.verifying_contract {
+            fields.push("address verifyingContract");
+            data.extend_from_slice(&encode_address(verifying_contract));
+        }
+        if let Some(salt) = self.salt {
+            fields.push("bytes32 salt");
+            data.extend_from_slice(salt.as_bytes());
+        }
+
+        let ty = format!("{EIP712_DOMAIN_TYPE}({})", fields.join(","));
+        let mut encoded = keccak256(ty.as_bytes()).as_bytes().to_vec();
+        encoded.extend_from_slice(&data);
+        keccak256(encoded)
+    }
+}
+
+/// Typed structured data, as signed by `eth_signTypedData`.
+///
+/// Deserializes from the JSON object of the request, or from a string holding it as some wallets
+/// send it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    /// The struct types, by name.
+    ///
+    /// The `EIP712Domain` type can be included, but the domain is always hashed with the fields
+    /// that are set in [TypedData::domain].
+    pub types: BTreeMap<String, Vec<TypedDataField>>,
+    /// The type of the message.
+    pub primary_type: String,
+    /// The domain of the message.
+    pub domain: Eip712Domain,
+    /// The message, a struct of the primary type.
+    pub message: Value,
+}
+
+impl TypedData {
+    /// Returns the hash that's signed: `keccak256(0x19 ‖ 0x01 ‖ domainSeparator ‖
+    /// hashStruct(message))`.
+    ///
+    /// If the primary type is `EIP712Domain`, only the domain is signed.
+    pub fn signing_hash(&self) -> Result<H256, Eip712Error> {
+        let mut encoded = vec![0x19, 0x01];
+        encoded.extend_from_slice(self.domain.separator().as_bytes());
+        if self.primary_type != EIP712_DOMAIN_TYPE {
+            encoded
+                .extend_from_slice(self.hash_struct(&self.primary_type, &self.message)?.as_bytes());
+        }
+        Ok(keccak256(encoded))
+    }
+
+    /// Returns `hashStruct(value)`, the hash of the type hash and the encoded fields of the struct.
+    pub fn hash_struct(&self, ty: &str, value: &Value) -> Result<H256, Eip712Error> {
+        let fields = self.types.get(ty).ok_or_else(|| Eip712Error::UnknownType(ty.to_string()))?;
+        let value = value.as_object().ok_or_else(|| invalid_value(ty, value))?;
+
+        let mut encoded = self.type_hash(ty)?.as_bytes().to_vec();
+        for field in fields {
+            let field_value = value.get(&field.name).ok_or_else(|| Eip712Error::MissingField {
+                ty: ty.to_string(),
+                field: field.name.clone(),
+            })?;
+            encoded.extend_from_slice(&self.encode_value(&field.ty, field_value)?);
+        }
+        Ok(keccak256(encoded))
+    }
+
+    /// Returns `typeHash`, the hash of the encoded type.
+    pub fn type_hash(&self, ty: &str) -> Result<H256, Eip712Error> {
+        Ok(keccak256(self.encode_type(ty)?.as_bytes()))
+    }
+
+    /// Returns the encoding of the struct type, followed by the encodings of the struct types it
+    /// references, sorted by name, like `Mail(Person from,Person to,string contents)Person(string
+    /// name,address wallet)`.
+    pub fn encode_type(&self, ty: &str) -> Result<String, Eip712Error> {
+        let mut dependencies = BTreeSet::new();
+        self.collect_dependencies(ty, &mut dependencies)?;
+        dependencies.remove(ty);
+
+        let mut encoded = self.encode_single_type(ty)?;
+        for dependency in dependencies {
+            encoded.push_str(&self.encode_single_type(dependency)?);
+        }
+        Ok(encoded)
+    }
+
+    /// Returns the encoding of the struct type alone, like `Person(string name,address wallet)`.
+    fn encode_single_type(&self, ty: &str) -> Result<String, Eip712Error> {
+        let fields = self.types.get(ty).ok_or_else(|| Eip712Error::UnknownType(ty.to_string()))?;
+        let fields = fields
+            .iter()
+            .map(|field| format!("{} {}", field.ty, field.name))
+            .collect::<Vec<_>>()
+            .join(",");
+        Ok(format!("{ty}({fields})"))
+    }
+
+    /// Collects the struct type and all struct types it references, directly or through other
+    /// struct types.
+    fn collect_dependencies<'a>(
+        &'a self,
+        ty: &'a str,
+        dependencies: &mut BTreeSet<&'a str>,
+    ) -> Result<(), Eip712Error> {
+        let fields = self.types.get(ty).ok_or_else(|| Eip712Error::UnknownType(ty.to_string()))?;
+        if !dependencies.insert(ty) {
+            return Ok(())
+        }
+        for field in fields {
+            let field_ty = base_type(&field.ty);
+            if self.types.contains_key(field_ty) {
+                self.collect_dependencies(field_ty, dependencies)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes the value of the given type into 32 bytes.
+    ///
+    /// Atomic values are ABI encoded, dynamic values and arrays are hashed and structs are encoded
+    /// as their `hashStruct`.
+    fn encode_value(&self, ty: &str, value: &Value) -> Result<[u8; 32], Eip712Error> {
+        // arrays
+        if let Some(ty_without_suffix) = ty.strip_suffix(']') {
+            let (element_ty, len) =
+                ty_without_suffix.rsplit_once('[').ok_or_else(|| invalid_type(ty))?;
+            let elements = value.as_array().ok_or_else(|| invalid_value(ty, value))?;
+            if !len.is_empty() {
+                let len = len.parse::<usize>().map_err(|_| invalid_type(ty))?;
+                if elements.len() != len {
+                    return Err(invalid_value(ty, value))
+                }
+            }
+
+            let mut encoded = Vec::with_capacity(elements.len() * 32);
+            for element in elements {
+                encoded.extend_from_slice(&self.encode_value(element_ty, element)?);
+            }
+            return Ok(keccak256(encoded).0)
+        }
+
+        // structs
+        if self.types.contains_key(ty) {
+            return Ok(self.hash_struct(ty, value)?.0)
+        }
+
+        let invalid = || invalid_value(ty, value);
+        match ty {
+            "string" => Ok(keccak256(value.as_str().ok_or_else(invalid)?.as_bytes()).0),
+            "bytes" => Ok(keccak256(decode_hex(value).ok_or_else(invalid)?).0),
+            "bool" => {
+                let mut encoded = [0u8; 32];
+                encoded[31] = value.as_bool().ok_or_else(invalid)? as u8;
+                Ok(encoded)
+            }
+            "address" => {
+                let address = value.as_str().and_then(|s| Address::from_str(s).ok());
+                Ok(encode_address(address.ok_or_else(invalid)?))
+            }
+            _ => {
+                if let Some(len) = ty.strip_prefix("bytes") {
+                    let len = parse_size(len, 1, 32).ok_or_else(|| invalid_type(ty))?;
+                    let bytes = decode_hex(value).filter(|b| b.len() <= len).ok_or_else(invalid)?;
+                    let mut encoded = [0u8; 32];
+                    encoded[..bytes.len()].copy_from_slice(&bytes);
+                    Ok(encoded)
+                } else if let Some(bits) = ty.strip_prefix("uint") {
+                    let bits = parse_integer_bits(bits).ok_or_else(|| invalid_type(ty))?;
+                    let (negative, value) = parse_integer(value).ok_or_else(invalid)?;
+                    if negative || value.bit_len() > bits {
+                        return Err(invalid())
+                    }
+                    Ok(value.to_be_bytes())
+                } else if let Some(bits) = ty.strip_prefix("int") {
+                    let bits = parse_integer_bits(bits).ok_or_else(|| invalid_type(ty))?;
+                    let (negative, value) = parse_integer(value).ok_or_else(invalid)?;
+                    // the range is -2^(bits - 1) to 2^(bits - 1) - 1
+                    let limit = U256::from(1) << (bits - 1);
+                    if (negative && value > limit) || (!negative && value >= limit) {
+                        return Err(invalid())
+                    }
+                    let value = if negative { U256::ZERO.wrapping_sub(value) } else { value };
+                    Ok(value.to_be_bytes())
+                } else {
+                    Err(Eip712Error::UnknownType(ty.to_string()))
+                }
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TypedData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct TypedDataObject {
+            types: BTreeMap<String, Vec<TypedDataField>>,
+            primary_type: String,
+            #[serde(default)]
+            domain: Eip712Domain,
+            message: Value,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum TypedDataJson {
+            Object(TypedDataObject),
+            String(String),
+        }
+
+        let data = match TypedDataJson::deserialize(deserializer)? {
+            TypedDataJson::Object(data) => data,
+            TypedDataJson::String(s) => {
+                serde_json::from_str(&s).map_err(serde::de::Error::custom)?
+            }
+        };
+        let TypedDataObject { types, primary_type, domain, message } = data;
+        Ok(Self { types, primary_type, domain, message })
+    }
+}
+
+/// Returns the type without array suffixes, like `Person` for `Person[2][]`.
+fn base_type(ty: &str) -> &str {
+    ty.split_once('[').map_or(ty, |(base, _)| base)
+}
+
+/// Left pads the address to 32 bytes.
+fn encode_address(address: Address) -> [u8; 32] {
+    let mut encoded = [0u8; 32];
+    encoded[12..].copy_from_slice(address.as_bytes());
+    encoded
+}
+
+/// Decodes a `0x` prefixed hex string.
+fn decode_hex(value: &Value) -> Option<Vec<u8>> {
+    hex::decode(value.as_str()?.strip_prefix("0x")?).ok()
+}
+
+/// Parses the size suffix of a type, like the `32` of `bytes32`, and checks its range.
+fn parse_size(size: &str, min: usize, max: usize) -> Option<usize> {
+    let size = size.parse::<usize>().ok()?;
+    (min..=max).contains(&size).then_some(size)
+}
+
+/// Parses the bit size of an integer type, `256` if not set, like for `uint`.
+fn parse_integer_bits(bits: &str) -> Option<usize> {
+    if bits.is_empty() {
+        return Some(256)
+    }
+    parse_size(bits, 8, 256).filter(|bits| bits % 8 == 0)
+}
+
+/// Parses an integer from a JSON number or a decimal or `0x` prefixed hex string, as its sign and
+/// magnitude.
+fn parse_integer(value: &Value) -> Option<(bool, U256)> {
+    let s = match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(s) => s.clone(),
+        _ => return None,
+    };
+    let (negative, magnitude) = match s.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, s.as_str()),
+    };
+    let magnitude = match magnitude.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok()?,
+        None => U256::from_str_radix(magnitude, 10).ok()?,
+    };
+    Some((negative && magnitude != U256::ZERO, magnitude))
+}
+
+fn invalid_type(ty: &str) -> Eip712Error {
+    Eip712Error::UnknownType(ty.to_string())
+}
+
+fn invalid_value(ty: &str, value: &Value) -> Eip712Error {
+    Eip712Error::InvalidValue { ty: ty.to_string(), value: value.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    /// The example of the EIP.
+    fn mail() -> TypedData {
+        serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" }
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+                "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+                "contents": "Hello, Bob!"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn hash_eip_example() {
+        let data = mail();
+        assert_eq!(
+            data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            data.domain.separator(),
+            H256(hex!("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"))
+        );
+        assert_eq!(
+            data.hash_struct("Mail", &data.message).unwrap(),
+            H256(hex!("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"))
+        );
+        assert_eq!(
+            data.signing_hash().unwrap(),
+            H256(hex!("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"))
+        );
+
+        // wallets send the typed data as string, too
+        let s = serde_json::to_string(&data).unwrap();
+        assert_eq!(serde_json::from_value::<TypedData>(Value::String(s)).unwrap(), data);
+    }
+
+    #[test]
+    fn encode_atomic_and_array_values() {
+        let data = TypedData {
+            types: BTreeMap::from([(
+                "Values".to_string(),
+                vec![TypedDataField { name: "n".to_string(), ty: "int8".to_string() }],
+            )]),
+            primary_type: "Values".to_string(),
+            domain: Default::default(),
+            message: Value::Null,
+        };
+
+        let encoded = data.encode_value("int8", &serde_json::json!(-1)).unwrap();
+        assert_eq!(encoded, [0xff; 32]);
+        assert!(data.encode_value("int8", &serde_json::json!(-128)).is_ok());
+        assert!(data.encode_value("int8", &serde_json::json!(128)).is_err());
+        assert!(data.encode_value("uint8", &serde_json::json!("0x100")).is_err());
+        assert!(data.encode_value("uint", &serde_json::json!("-1")).is_err());
+
+        let encoded = data.encode_value("bytes2", &serde_json::json!("0x0102")).unwrap();
+        assert_eq!(&encoded[..3], &[1, 2, 0]);
+        assert!(data.encode_value("bytes1", &serde_json::json!("0x0102")).is_err());
+
+        // arrays hash the concatenated encodings of their elements
+        let mut elements = [0u8; 64];
+        elements[31] = 1;
+        let encoded = data.encode_value("bool[2]", &serde_json::json!([true, false])).unwrap();
+        assert_eq!(encoded, keccak256(elements).0);
+        assert!(data.encode_value("bool[3]", &serde_json::json!([true, false])).is_err());
+
+        assert_eq!(
+            data.hash_struct("Values", &serde_json::json!({})),
+            Err(Eip712Error::MissingField { ty: "Values".to_string(), field: "n".to_string() })
+        );
+        assert_eq!(
+            data.encode_value("Unknown", &Value::Null),
+            Err(Eip712Error::UnknownType("Unknown".to_string()))
+        );
+    }
+}
@@ -9,7 +9,7 @@ use crate::{
 };
 use ethers_core::utils::GenesisAccount as EthersGenesisAccount;
 use reth_rlp::{encode_fixed_size, length_of_length, Encodable, Header as RlpHeader};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use triehash::sec_trie_root;
 
 /// The genesis block specification.
@@ -106,7 +106,13 @@ pub struct GenesisAccount {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<Bytes>,
     /// The account's storage at genesis.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    ///
+    /// Keys and values may be hex strings shorter than 32 bytes, which are left padded with zeros.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_storage_map"
+    )]
     pub storage: Option<HashMap<H256, H256>>,
 }
 
@@ -124,6 +130,13 @@ impl GenesisAccount {
         len
     }
 
+    /// Returns the hash of the account's bytecode, or `None` if the account has no code.
+    ///
+    /// Empty bytecode is treated as no code at all.
+    pub fn code_hash(&self) -> Option<H256> {
+        self.code.as_ref().filter(|code| !code.is_empty()).map(keccak256)
+    }
+
     /// Set the nonce.
     pub fn with_nonce(mut self, nonce: Option<u64>) -> Self {
         self.nonce = nonce;
@@ -170,7 +183,7 @@ impl Encodable for GenesisAccount {
                 sec_trie_root::<KeccakHasher, _, _, _>(storage_values)
             })
             .encode(out);
-        self.code.as_ref().map_or(KECCAK_EMPTY, keccak256).encode(out);
+        self.code_hash().unwrap_or(KECCAK_EMPTY).encode(out);
     }
 
     fn length(&self) -> usize {
@@ -199,9 +212,37 @@ impl From<GenesisAccount> for Account {
             // nonce must exist, so we default to zero when converting a genesis account
             nonce: value.nonce.unwrap_or_default(),
             balance: value.balance,
-            bytecode_hash: value.code.map(keccak256),
+            bytecode_hash: value.code_hash(),
+        }
+    }
+}
+
+/// Deserializes the storage of a genesis account.
+///
+/// Genesis files produced by other clients frequently use hex strings shorter than 32 bytes for
+/// storage keys and values, e.g. `"0x01"`, so these are left padded before they're converted.
+fn deserialize_storage_map<'de, D>(deserializer: D) -> Result<Option<HashMap<H256, H256>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    fn parse_word<E: serde::de::Error>(value: &str) -> Result<H256, E> {
+        let value = value.strip_prefix("0x").unwrap_or(value);
+        if value.len() > 64 {
+            return Err(E::custom(format!("storage word exceeds 32 bytes: 0x{value}")))
         }
+        let padded = format!("{value:0>64}");
+        let bytes = hex::decode(padded).map_err(E::custom)?;
+        Ok(H256::from_slice(&bytes))
     }
+
+    let Some(storage) = Option::<HashMap<String, String>>::deserialize(deserializer)? else {
+        return Ok(None)
+    };
+    storage
+        .into_iter()
+        .map(|(key, value)| Ok((parse_word(&key)?, parse_word(&value)?)))
+        .collect::<Result<_, _>>()
+        .map(Some)
 }
 
 #[cfg(test)]
@@ -303,4 +344,31 @@ mod tests {
         assert_eq!(genesis_account.code, code);
         assert_eq!(genesis_account.storage, storage);
     }
+
+    #[test]
+    fn parse_genesis_account_with_short_storage_words() {
+        let account: GenesisAccount = serde_json::from_str(
+            r#"{
+                "balance": "0x0",
+                "code": "0x6001",
+                "storage": {
+                    "0x00": "0x01",
+                    "0000000000000000000000000000000000000000000000000000000000000002": "0xff"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let storage = account.storage.as_ref().unwrap();
+        assert_eq!(storage[&H256::zero()], H256::from_low_u64_be(1));
+        assert_eq!(storage[&H256::from_low_u64_be(2)], H256::from_low_u64_be(0xff));
+        assert_eq!(account.code_hash(), Some(keccak256(hex!("6001"))));
+    }
+
+    #[test]
+    fn empty_genesis_code_has_no_code_hash() {
+        let account = GenesisAccount::default().with_code(Some(Bytes::default()));
+        assert_eq!(account.code_hash(), None);
+        assert_eq!(Account::from(account).bytecode_hash, None);
+    }
 }
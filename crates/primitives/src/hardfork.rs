@@ -158,6 +158,8 @@ mod tests {
             genesis: Genesis::default(),
             genesis_hash: None,
             hardforks: BTreeMap::from([(Hardfork::Frontier, ForkCondition::Never)]),
+            deposit_contract: None,
+            bootnodes: Vec::new(),
         };
 
         assert_eq!(Hardfork::Frontier.fork_id(&spec), None);
@@ -170,6 +172,8 @@ mod tests {
             genesis: Genesis::default(),
             genesis_hash: None,
             hardforks: BTreeMap::from([(Hardfork::Shanghai, ForkCondition::Never)]),
+            deposit_contract: None,
+            bootnodes: Vec::new(),
         };
 
         assert_eq!(Hardfork::Shanghai.fork_filter(&spec), None);
@@ -10,14 +10,17 @@
 //! This crate contains Ethereum primitive types and helper functions.
 
 mod account;
+pub mod accumulator;
 pub mod basefee;
 mod bits;
+mod blob;
 mod block;
 pub mod bloom;
 mod chain;
 mod checkpoints;
 pub mod constants;
 pub mod contract;
+pub mod eip712;
 mod error;
 pub mod filter;
 mod forkid;
@@ -30,7 +33,9 @@ pub mod listener;
 mod log;
 mod net;
 mod peer;
+mod prune;
 mod receipt;
+pub mod stage;
 mod storage;
 mod transaction;
 pub mod trie;
@@ -41,18 +46,20 @@ pub mod proofs;
 
 pub use account::{Account, Bytecode};
 pub use bits::H512;
+pub use blob::{kzg_to_versioned_hash, BlobTransactionSidecar, VERSIONED_HASH_VERSION_KZG};
 pub use block::{
     Block, BlockBody, BlockHashOrNumber, BlockId, BlockNumHash, BlockNumberOrTag, BlockWithSenders,
     ForkBlock, SealedBlock, SealedBlockWithSenders,
 };
 pub use bloom::Bloom;
 pub use chain::{
-    AllGenesisFormats, Chain, ChainInfo, ChainSpec, ChainSpecBuilder, ForkCondition, GOERLI,
-    MAINNET, SEPOLIA,
+    AllGenesisFormats, Chain, ChainInfo, ChainSpec, ChainSpecBuilder, DepositContract,
+    ForkCondition, DEPOSIT_CONTRACT_TOPIC, DEV, GOERLI, HOLESKY, MAINNET, SEPOLIA,
 };
 pub use checkpoints::{AccountHashingCheckpoint, MerkleCheckpoint, StorageHashingCheckpoint};
 pub use constants::{
-    EMPTY_OMMER_ROOT, GOERLI_GENESIS, KECCAK_EMPTY, MAINNET_GENESIS, SEPOLIA_GENESIS,
+    EMPTY_OMMER_ROOT, GOERLI_GENESIS, HOLESKY_GENESIS, KECCAK_EMPTY, MAINNET_GENESIS,
+    SEPOLIA_GENESIS,
 };
 pub use forkid::{ForkFilter, ForkHash, ForkId, ForkTransition, ValidationError};
 pub use genesis::{Genesis, GenesisAccount};
@@ -62,10 +69,11 @@ pub use hex_bytes::Bytes;
 pub use integer_list::IntegerList;
 pub use log::Log;
 pub use net::{
-    goerli_nodes, mainnet_nodes, sepolia_nodes, NodeRecord, GOERLI_BOOTNODES, MAINNET_BOOTNODES,
-    SEPOLIA_BOOTNODES,
+    goerli_nodes, holesky_nodes, mainnet_nodes, sepolia_nodes, NodeRecord, GOERLI_BOOTNODES,
+    HOLESKY_BOOTNODES, MAINNET_BOOTNODES, SEPOLIA_BOOTNODES,
 };
 pub use peer::{PeerId, WithPeerId};
+pub use prune::{PruneCheckpoint, PruneSegment};
 pub use receipt::{Receipt, ReceiptWithBloom, ReceiptWithBloomRef};
 pub use revm_primitives::JumpMap;
 pub use serde_helper::JsonU256;
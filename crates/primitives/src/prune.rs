@@ -0,0 +1,40 @@
+use crate::BlockNumber;
+use reth_codecs::{main_codec, Compact};
+use serde::{Deserialize, Serialize};
+
+/// Segment of historical data that can be pruned.
+///
+/// Only data the pipeline doesn't read again is prunable, e.g. the recovered transaction senders
+/// are not since the execution stage needs them to re-execute blocks after an unwind. The
+/// changesets are read again only to unwind their blocks, so they must only be pruned below
+/// finalized blocks.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PruneSegment {
+    /// Receipts of canonical transactions.
+    Receipts,
+    /// Account and storage changesets, which the historical state of a block is restored from.
+    ///
+    /// The state of a block is available as long as the changesets of all later blocks are, so
+    /// pruning the changesets below block `n` keeps the state of block `n - 1` and later.
+    History,
+}
+
+impl PruneSegment {
+    /// All segments that can be pruned.
+    pub const ALL: [PruneSegment; 2] = [PruneSegment::Receipts, PruneSegment::History];
+}
+
+/// Saves the progress of the most recent prune run of a [PruneSegment].
+#[main_codec]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PruneCheckpoint {
+    /// All data of the segment below this block is pruned once the run is finished.
+    pub to_block: BlockNumber,
+    /// Number of entries the run deleted so far.
+    pub pruned: u64,
+    /// Unix timestamp of the last progress of the run, in seconds.
+    pub timestamp: u64,
+    /// Whether all data below `to_block` was deleted.
+    pub finished: bool,
+}
@@ -0,0 +1,15 @@
+//! Ids of the sync stages whose checkpoints are read outside of the pipeline.
+//!
+//! The checkpoint of a stage is stored under its id in the `SyncStage` table.
+
+/// Id of the stage that indexes the account changesets.
+pub const INDEX_ACCOUNT_HISTORY: &str = "IndexAccountHistory";
+
+/// Id of the stage that indexes the storage changesets.
+pub const INDEX_STORAGE_HISTORY: &str = "IndexStorageHistory";
+
+/// Id of the stage that indexes the logs of the receipts.
+pub const INDEX_LOGS: &str = "IndexLogs";
+
+/// Id of the last stage, its checkpoint is the highest fully synced block.
+pub const FINISH: &str = "Finish";
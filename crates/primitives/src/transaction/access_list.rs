@@ -45,4 +45,7 @@ pub struct AccessListWithGasUsed {
     pub access_list: AccessList,
     /// Estimated gas used with access list.
     pub gas_used: U256,
+    /// The error of the execution with the access list, if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
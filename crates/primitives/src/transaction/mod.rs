@@ -221,6 +221,20 @@ impl Transaction {
             }
         }
     }
+
+    /// Encodes the unsigned transaction with an empty signature, like geth encodes unsigned
+    /// transactions: the `v`, `r` and `s` values of all transaction types are zero.
+    pub fn encode_unsigned(&self, out: &mut dyn bytes::BufMut) {
+        let signature = Signature::default();
+        if !matches!(self, Transaction::Legacy(_)) {
+            out.put_u8(self.tx_type() as u8);
+        }
+        let header =
+            Header { list: true, payload_length: self.fields_len() + signature.payload_len() };
+        header.encode(out);
+        self.encode_fields(out);
+        signature.encode(out);
+    }
 }
 
 impl Compact for Transaction {
@@ -1185,6 +1199,40 @@ mod tests {
         assert_eq!(DecodeError::InputTooShort, res);
     }
 
+    #[test]
+    fn test_encode_unsigned() {
+        let legacy = Transaction::Legacy(TxLegacy {
+            chain_id: Some(1),
+            nonce: 0,
+            gas_price: 1,
+            gas_limit: 2,
+            to: TransactionKind::Create,
+            value: 3,
+            input: Bytes::default(),
+        });
+        let mut encoded = BytesMut::new();
+        legacy.encode_unsigned(&mut encoded);
+        // the signature is zero instead of the EIP-155 fields
+        assert_eq!(encoded[..], hex::decode("c98001028003808080").unwrap());
+
+        let eip1559 = Transaction::Eip1559(TxEip1559 {
+            chain_id: 1,
+            nonce: 0,
+            gas_limit: 2,
+            max_fee_per_gas: 3,
+            max_priority_fee_per_gas: 1,
+            to: TransactionKind::Create,
+            value: 0,
+            input: Bytes::default(),
+            access_list: Default::default(),
+        });
+        let mut encoded = BytesMut::new();
+        eip1559.encode_unsigned(&mut encoded);
+        let mut expected = BytesMut::new();
+        eip1559.encode_with_signature(&Signature::default(), &mut expected, false);
+        assert_eq!(encoded, expected);
+    }
+
     #[test]
     fn test_decode_create() {
         // tests that a contract creation tx encodes and decodes properly
@@ -2,8 +2,11 @@ use super::{
     nodes::{rlp_hash, BranchNode, ExtensionNode, LeafNode},
     BranchNodeCompact, Nibbles, TrieMask,
 };
-use crate::{keccak256, proofs::EMPTY_ROOT, H256};
-use std::{collections::HashMap, fmt::Debug};
+use crate::{keccak256, proofs::EMPTY_ROOT, Bytes, H256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+};
 
 mod state;
 pub use state::HashBuilderState;
@@ -11,6 +14,9 @@ pub use state::HashBuilderState;
 mod value;
 pub use value::HashBuilderValue;
 
+mod proof_retainer;
+pub use proof_retainer::ProofRetainer;
+
 /// A component used to construct the root hash of the trie. The primary purpose of a Hash Builder
 /// is to build the Merkle proof that is essential for verifying the integrity and authenticity of
 /// the trie's contents. It achieves this by constructing the root hash from the hashes of child
@@ -47,6 +53,7 @@ pub struct HashBuilder {
     stored_in_database: bool,
 
     updated_branch_nodes: Option<HashMap<Nibbles, BranchNodeCompact>>,
+    proof_retainer: Option<ProofRetainer>,
 
     rlp_buf: Vec<u8>,
 }
@@ -62,6 +69,7 @@ impl From<HashBuilderState> for HashBuilder {
             hash_masks: state.hash_masks,
             stored_in_database: state.stored_in_database,
             updated_branch_nodes: None,
+            proof_retainer: None,
             rlp_buf: Vec::with_capacity(32),
         }
     }
@@ -99,6 +107,23 @@ impl HashBuilder {
         }
     }
 
+    /// Enables the Hash Builder to retain the nodes on the paths to the given target keys.
+    ///
+    /// Call [HashBuilder::take_proofs] to get the retained nodes.
+    pub fn with_proof_retainer(mut self, targets: Vec<Nibbles>) -> Self {
+        self.proof_retainer = Some(ProofRetainer::new(targets));
+        self
+    }
+
+    /// Returns the retained proof nodes keyed by their path in the trie, if proof retention was
+    /// enabled with [HashBuilder::with_proof_retainer].
+    ///
+    /// Only the root and the nodes that are referenced by hash are retained, nodes shorter than
+    /// 32 bytes are embedded in their parent.
+    pub fn take_proofs(&mut self) -> BTreeMap<Nibbles, Bytes> {
+        self.proof_retainer.take().map(ProofRetainer::into_proofs).unwrap_or_default()
+    }
+
     /// Splits the [HashBuilder] into a [HashBuilder] and hash builder updates.
     pub fn split(mut self) -> (Self, HashMap<Nibbles, BranchNodeCompact>) {
         let updates = self.updated_branch_nodes.take();
@@ -252,6 +277,7 @@ impl HashBuilder {
 
                         self.rlp_buf.clear();
                         self.stack.push(leaf_node.rlp(&mut self.rlp_buf));
+                        self.retain_proof_from_buf(&current.slice(0, len_from));
                     }
                     HashBuilderValue::Hash(hash) => {
                         tracing::debug!(target: "trie::hash_builder", ?hash, "pushing branch node hash");
@@ -281,6 +307,7 @@ impl HashBuilder {
                 }, "extension node rlp");
                 self.rlp_buf.clear();
                 self.stack.push(extension_node.rlp(&mut self.rlp_buf));
+                self.retain_proof_from_buf(&current.slice(0, len_from));
                 self.resize_masks(len_from);
             }
 
@@ -292,7 +319,7 @@ impl HashBuilder {
             // Insert branch nodes in the stack
             if !succeeding.is_empty() || preceding_exists {
                 // Pushes the corresponding branch node to the stack
-                let children = self.push_branch_node(len);
+                let children = self.push_branch_node(&current, len);
                 // Need to store the branch node in an efficient format
                 // outside of the hash builder
                 self.store_branch_node(&current, len, children);
@@ -323,7 +350,7 @@ impl HashBuilder {
     /// Given the size of the longest common prefix, it proceeds to create a branch node
     /// from the state mask and existing stack state, and store its RLP to the top of the stack,
     /// after popping all the relevant elements from the stack.
-    fn push_branch_node(&mut self, len: usize) -> Vec<H256> {
+    fn push_branch_node(&mut self, current: &Nibbles, len: usize) -> Vec<H256> {
         let state_mask = self.groups[len];
         let hash_mask = self.hash_masks[len];
         let branch_node = BranchNode::new(&self.stack);
@@ -331,6 +358,7 @@ impl HashBuilder {
 
         self.rlp_buf.clear();
         let rlp = branch_node.rlp(state_mask, &mut self.rlp_buf);
+        self.retain_proof_from_buf(&current.slice(0, len));
 
         // Clears the stack from the branch node elements
         let first_child_idx = self.stack.len() - state_mask.count_ones() as usize;
@@ -387,6 +415,17 @@ impl HashBuilder {
         }
     }
 
+    /// Retains the node in the RLP buffer if it's on the path to one of the proof targets.
+    fn retain_proof_from_buf(&mut self, prefix: &Nibbles) {
+        // nodes shorter than a hash are embedded in their parent, except for the root
+        if !prefix.is_empty() && self.rlp_buf.len() < H256::len_bytes() {
+            return
+        }
+        if let Some(proof_retainer) = self.proof_retainer.as_mut() {
+            proof_retainer.retain(prefix, &self.rlp_buf)
+        }
+    }
+
     fn update_masks(&mut self, current: &Nibbles, len_from: usize) {
         if len_from > 0 {
             let flag = TrieMask::from_nibble(current[len_from - 1]);
@@ -563,6 +602,35 @@ mod tests {
         assert_hashed_trie_root(data.iter());
     }
 
+    #[test]
+    fn test_retains_proof() {
+        let data = (0..100u64)
+            .map(|i| {
+                (keccak256(H256::from_low_u64_be(i)), reth_rlp::encode_fixed_size(&U256::from(i)))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let target = Nibbles::unpack(keccak256(H256::from_low_u64_be(42)));
+
+        let mut hb = HashBuilder::default().with_proof_retainer(vec![target.clone()]);
+        data.iter().for_each(|(key, val)| {
+            hb.add_leaf(Nibbles::unpack(key), val.as_ref());
+        });
+        let root = hb.root();
+        assert_eq!(root, trie_root(data.iter().map(|(k, v)| (k, v.to_vec()))));
+
+        let proofs = hb.take_proofs();
+        assert!(proofs.keys().all(|path| target.has_prefix(path)));
+
+        // the path starts at the root and every node is referenced by its hash in the parent
+        let nodes = proofs.into_values().collect::<Vec<_>>();
+        assert!(nodes.len() > 1);
+        assert_eq!(keccak256(&nodes[0]), root);
+        for pair in nodes.windows(2) {
+            let child_hash = keccak256(&pair[1]);
+            assert!(pair[0].windows(32).any(|window| window == child_hash.as_bytes()));
+        }
+    }
+
     #[test]
     fn test_root_known_hash() {
         let root_hash = H256::random();
@@ -1,9 +1,10 @@
-use hashbrown::{HashMap, HashSet};
+use hashbrown::HashSet;
 use reth_primitives::{AccessList, AccessListItem, Address, H256};
 use revm::{
     interpreter::{opcode, InstructionResult, Interpreter},
     Database, EVMData, Inspector,
 };
+use std::collections::{BTreeMap, BTreeSet};
 
 /// An [Inspector] that collects touched accounts and storage slots.
 ///
@@ -12,28 +13,31 @@ use revm::{
 pub struct AccessListInspector {
     /// All addresses that should be excluded from the final accesslist
     excluded: HashSet<Address>,
-    /// All addresses and touched slots
-    access_list: HashMap<Address, HashSet<H256>>,
+    /// All addresses and touched slots, sorted so the produced lists can be compared
+    access_list: BTreeMap<Address, BTreeSet<H256>>,
 }
 
 impl AccessListInspector {
     /// Creates a new inspector instance
     ///
-    /// The `access_list` is the provided access list from the call request
+    /// The `access_list` is the provided access list from the call request. Like geth, excluded
+    /// addresses of the provided list are only kept if they have storage keys.
     pub fn new(
         access_list: AccessList,
         from: Address,
         to: Address,
         precompiles: Vec<Address>,
     ) -> Self {
-        AccessListInspector {
-            excluded: vec![from, to].iter().chain(precompiles.iter()).copied().collect(),
-            access_list: access_list
-                .0
-                .iter()
-                .map(|v| (v.address, v.storage_keys.iter().copied().collect()))
-                .collect(),
+        let excluded: HashSet<_> =
+            vec![from, to].iter().chain(precompiles.iter()).copied().collect();
+        let mut inspector = AccessListInspector { excluded, access_list: BTreeMap::new() };
+        for item in access_list.0 {
+            if item.storage_keys.is_empty() && inspector.excluded.contains(&item.address) {
+                continue
+            }
+            inspector.access_list.entry(item.address).or_default().extend(item.storage_keys);
         }
+        inspector
     }
 
     /// Returns list of addresses and storage keys used by the transaction. It gives you the list of
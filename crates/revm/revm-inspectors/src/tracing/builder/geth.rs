@@ -1,9 +1,13 @@
 //! Geth trace builder
 
 use crate::tracing::{types::CallTraceNode, TracingInspectorConfig};
-use reth_primitives::{Address, JsonU256, H256, U256};
+use reth_primitives::{Address, Bytes, JsonU256, H256, KECCAK_EMPTY, U256};
 use reth_rpc_types::trace::geth::*;
-use revm::interpreter::opcode;
+use revm::{
+    db::DatabaseRef,
+    interpreter::opcode,
+    primitives::{AccountInfo, State},
+};
 use std::collections::{BTreeMap, HashMap};
 
 /// A type for creating geth style traces
@@ -106,4 +110,192 @@ impl GethTraceBuilder {
             struct_logs,
         }
     }
+
+    /// Generate a geth-style trace for the `callTracer`, e.g. for `debug_traceTransaction`.
+    ///
+    /// Note: the gas of the top-level frame is the gas that was available to the top-level call,
+    /// not the gas limit of the transaction.
+    pub fn geth_call_traces(&self, opts: CallConfig) -> CallFrame {
+        if self.nodes.is_empty() {
+            return Default::default()
+        }
+        let include_logs = opts.with_log.unwrap_or_default();
+
+        // the top-level call is always the first node
+        if opts.only_top_call.unwrap_or_default() {
+            return self.nodes[0].geth_empty_call_frame(include_logs)
+        }
+
+        let mut frames = self
+            .nodes
+            .iter()
+            .map(|node| node.geth_empty_call_frame(include_logs))
+            .collect::<Vec<_>>();
+
+        // calls are recorded after their parent, so by moving the frames into their parents in
+        // reverse order every frame is complete before it is moved
+        while frames.len() > 1 {
+            let idx = frames.len() - 1;
+            let mut frame = frames.pop().expect("frames is not empty");
+            if let Some(calls) = frame.calls.as_mut() {
+                calls.reverse();
+            }
+            let parent = self.nodes[idx].parent.expect("only the top-level call has no parent");
+            frames[parent].calls.get_or_insert_with(Vec::new).push(frame);
+        }
+
+        let mut root = frames.pop().expect("frames is not empty");
+        if let Some(calls) = root.calls.as_mut() {
+            calls.reverse();
+        }
+        root
+    }
+
+    /// Generate a geth-style trace for the `prestateTracer`, e.g. for `debug_traceTransaction`.
+    ///
+    /// The `state` are the state changes of the transaction and `db` must still hold the state
+    /// the transaction was executed on.
+    ///
+    /// In the default mode all accounts touched by the transaction are returned with their state
+    /// before the transaction. In diff mode only the modified accounts are returned, with their
+    /// state before and the modified fields after the transaction.
+    pub fn geth_prestate_traces<DB>(
+        &self,
+        state: &State,
+        prestate_config: PreStateConfig,
+        db: &DB,
+    ) -> Result<PreStateFrame, DB::Error>
+    where
+        DB: DatabaseRef,
+    {
+        let diff_mode = prestate_config.diff_mode.unwrap_or_default();
+        let mut pre = BTreeMap::new();
+        let mut post = BTreeMap::new();
+
+        for (address, account) in state {
+            let pre_info = db.basic(*address)?;
+            let existed = pre_info.is_some();
+            let pre_code = match &pre_info {
+                Some(info) => account_code(info, db)?,
+                None => None,
+            };
+            let pre_info = pre_info.unwrap_or_default();
+
+            let mut pre_state = AccountState {
+                balance: Some(pre_info.balance),
+                nonce: Some(U256::from(pre_info.nonce)),
+                code: pre_code.clone(),
+                storage: None,
+            };
+
+            if !diff_mode {
+                let storage = account
+                    .storage
+                    .iter()
+                    .map(|(key, slot)| (H256::from(*key), H256::from(slot.original_value())))
+                    .collect::<BTreeMap<_, _>>();
+                pre_state.storage = (!storage.is_empty()).then_some(storage);
+                pre.insert(*address, pre_state);
+                continue
+            }
+
+            let mut pre_storage = BTreeMap::new();
+            let mut post_storage = BTreeMap::new();
+            for (key, slot) in account.storage.iter().filter(|(_, slot)| slot.is_changed()) {
+                pre_storage.insert(H256::from(*key), H256::from(slot.original_value()));
+                // like geth, cleared slots are omitted from the post state
+                if slot.present_value() != U256::ZERO {
+                    post_storage.insert(H256::from(*key), H256::from(slot.present_value()));
+                }
+            }
+            pre_state.storage = (!pre_storage.is_empty()).then_some(pre_storage);
+
+            let post_code = account_code(&account.info, db)?;
+            let mut post_state = AccountState::default();
+            if account.info.balance != pre_info.balance {
+                post_state.balance = Some(account.info.balance);
+            }
+            if account.info.nonce != pre_info.nonce {
+                post_state.nonce = Some(U256::from(account.info.nonce));
+            }
+            if post_code != pre_code {
+                post_state.code = post_code;
+            }
+            post_state.storage = (!post_storage.is_empty()).then_some(post_storage);
+
+            let modified = post_state != AccountState::default() || pre_state.storage.is_some();
+            if !modified && !account.is_destroyed {
+                continue
+            }
+            // accounts that didn't exist before the transaction have no pre state
+            if existed {
+                pre.insert(*address, pre_state);
+            }
+            // destroyed accounts have no post state
+            if !account.is_destroyed {
+                post.insert(*address, post_state);
+            }
+        }
+
+        if diff_mode {
+            Ok(PreStateFrame::Diff(DiffMode { pre, post }))
+        } else {
+            Ok(PreStateFrame::Default(PreStateMode(pre)))
+        }
+    }
+}
+
+/// Returns the hex encoded code of the account, if it has any.
+fn account_code<DB: DatabaseRef>(info: &AccountInfo, db: &DB) -> Result<Option<String>, DB::Error> {
+    let code = match &info.code {
+        Some(code) => code.clone(),
+        None if info.code_hash == KECCAK_EMPTY => return Ok(None),
+        None => db.code_by_hash(info.code_hash)?,
+    };
+    let code = &code.bytes()[..code.len()];
+    Ok((!code.is_empty()).then(|| Bytes::from(code).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracing::types::{CallKind, CallTrace, CallTraceNode};
+
+    fn node(idx: usize, parent: Option<usize>, children: Vec<usize>) -> CallTraceNode {
+        let trace = CallTrace {
+            address: Address::from_low_u64_be(idx as u64),
+            kind: if idx == 2 { CallKind::StaticCall } else { CallKind::Call },
+            success: true,
+            ..Default::default()
+        };
+        CallTraceNode { parent, children, idx, trace, ..Default::default() }
+    }
+
+    #[test]
+    fn nested_call_frames() {
+        let nodes = vec![
+            node(0, None, vec![1, 3]),
+            node(1, Some(0), vec![2]),
+            node(2, Some(1), vec![]),
+            node(3, Some(0), vec![]),
+        ];
+        let builder = GethTraceBuilder::new(nodes, TracingInspectorConfig::default_parity());
+
+        let frame = builder.geth_call_traces(CallConfig::default());
+        let calls = frame.calls.unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, Some(Address::from_low_u64_be(1)));
+        assert_eq!(calls[1].to, Some(Address::from_low_u64_be(3)));
+        assert!(calls[1].calls.is_none());
+
+        let nested = calls[0].calls.as_ref().unwrap();
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].typ, "STATICCALL");
+        assert_eq!(nested[0].value, None);
+
+        let top = builder
+            .geth_call_traces(CallConfig { only_top_call: Some(true), ..Default::default() });
+        assert_eq!(top.to, Some(Address::zero()));
+        assert!(top.calls.is_none());
+    }
 }
@@ -0,0 +1,451 @@
+//! Type bindings for the objects of geth's javascript tracer API.
+//!
+//! See <https://geth.ethereum.org/docs/developers/evm-tracing/custom-tracer#custom-javascript-tracing>
+
+use crate::tracing::js::builtins::{native, to_address, to_bigint, to_buf, to_word};
+use boa_engine::{
+    js_string, object::ObjectInitializer, property::Attribute, Context, JsArgs, JsError, JsObject,
+    JsResult, JsString, JsValue,
+};
+use reth_primitives::{bytes::Bytes, Address, H256, KECCAK_EMPTY, U256};
+use revm::{
+    interpreter::OpCode,
+    primitives::{AccountInfo, Bytecode, State},
+    Database, EVMData,
+};
+use std::{cell::Cell, marker::PhantomData, ptr::NonNull, rc::Rc};
+
+/// Read access to the state for the `db` object of a tracer.
+pub(crate) trait JsDb {
+    /// Returns the account, `None` if it doesn't exist.
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, String>;
+
+    /// Returns the code with the given hash.
+    fn code_by_hash(&mut self, code_hash: H256) -> Result<Bytecode, String>;
+
+    /// Returns the value of the storage slot of the account.
+    fn storage(&mut self, address: Address, slot: U256) -> Result<U256, String>;
+
+    /// Returns the code of the account.
+    fn code(&mut self, address: Address) -> Result<Vec<u8>, String> {
+        let Some(info) = self.basic(address)? else { return Ok(Vec::new()) };
+        let code = match info.code {
+            Some(code) => code,
+            None if info.code_hash == KECCAK_EMPTY => return Ok(Vec::new()),
+            None => self.code_by_hash(info.code_hash)?,
+        };
+        Ok(code.bytes()[..code.len()].to_vec())
+    }
+}
+
+/// The state during execution.
+///
+/// Accounts that weren't loaded by the transaction yet are read from the database directly, so
+/// reads of the tracer don't affect the access list of the transaction.
+impl<DB: Database> JsDb for EVMData<'_, DB> {
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, String> {
+        if let Some(account) = self.journaled_state.state.get(&address) {
+            return Ok(Some(account.info.clone()))
+        }
+        self.db.basic(address).map_err(|_| format!("failed to load account {address:?}"))
+    }
+
+    fn code_by_hash(&mut self, code_hash: H256) -> Result<Bytecode, String> {
+        self.db.code_by_hash(code_hash).map_err(|_| format!("failed to load code {code_hash:?}"))
+    }
+
+    fn storage(&mut self, address: Address, slot: U256) -> Result<U256, String> {
+        if let Some(value) =
+            self.journaled_state.state.get(&address).and_then(|account| account.storage.get(&slot))
+        {
+            return Ok(value.present_value())
+        }
+        self.db.storage(address, slot).map_err(|_| format!("failed to load storage of {address:?}"))
+    }
+}
+
+/// The state after the transaction: the changes of the transaction on top of the database it was
+/// executed on.
+pub(crate) struct StateWithChanges<'a, DB> {
+    pub(crate) changes: &'a State,
+    pub(crate) db: &'a DB,
+}
+
+impl<DB: revm::db::DatabaseRef> JsDb for StateWithChanges<'_, DB> {
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, String> {
+        if let Some(account) = self.changes.get(&address) {
+            return Ok(Some(account.info.clone()))
+        }
+        self.db.basic(address).map_err(|_| format!("failed to load account {address:?}"))
+    }
+
+    fn code_by_hash(&mut self, code_hash: H256) -> Result<Bytecode, String> {
+        self.db.code_by_hash(code_hash).map_err(|_| format!("failed to load code {code_hash:?}"))
+    }
+
+    fn storage(&mut self, address: Address, slot: U256) -> Result<U256, String> {
+        if let Some(value) =
+            self.changes.get(&address).and_then(|account| account.storage.get(&slot))
+        {
+            return Ok(value.present_value())
+        }
+        self.db.storage(address, slot).map_err(|_| format!("failed to load storage of {address:?}"))
+    }
+}
+
+/// A handle to the state that's only accessible while a tracer function is executed.
+#[derive(Clone, Default)]
+pub(crate) struct StateRef(Rc<Cell<Option<NonNull<dyn JsDb>>>>);
+
+impl StateRef {
+    /// Makes the state accessible until the returned guard is dropped.
+    pub(crate) fn set<'a>(&self, db: &'a mut (dyn JsDb + 'a)) -> StateGuard<'a> {
+        let ptr: NonNull<dyn JsDb + 'a> = NonNull::from(db);
+        // SAFETY: the lifetime is only erased to store the pointer, it's removed again when the
+        // guard, which borrows the state for `'a`, is dropped
+        let ptr: NonNull<dyn JsDb> = unsafe { std::mem::transmute(ptr) };
+        self.0.set(Some(ptr));
+        StateGuard { state: self.clone(), _borrow: PhantomData }
+    }
+
+    /// Executes the closure with the state, fails if it isn't accessible.
+    fn with<R>(&self, f: impl FnOnce(&mut dyn JsDb) -> Result<R, String>) -> JsResult<R> {
+        let Some(mut ptr) = self.0.get() else {
+            return Err(js_error("the state is only accessible while the tracer is executed"))
+        };
+        // SAFETY: the pointer is only set while the state is borrowed by the guard and tracer
+        // functions aren't executed concurrently
+        let db = unsafe { ptr.as_mut() };
+        f(db).map_err(js_error)
+    }
+}
+
+/// Removes the access to the state when dropped.
+pub(crate) struct StateGuard<'a> {
+    state: StateRef,
+    _borrow: PhantomData<&'a mut ()>,
+}
+
+impl Drop for StateGuard<'_> {
+    fn drop(&mut self) {
+        self.state.0.set(None);
+    }
+}
+
+/// The state of the interpreter before a step.
+#[derive(Debug)]
+pub(crate) struct StepSnapshot {
+    pub(crate) pc: usize,
+    pub(crate) op: u8,
+    pub(crate) gas: u64,
+    pub(crate) refund: u64,
+    pub(crate) depth: u64,
+    pub(crate) stack: Vec<U256>,
+    pub(crate) memory: Vec<u8>,
+    pub(crate) contract: Contract,
+}
+
+/// The contract that's executed by a step.
+#[derive(Debug, Clone)]
+pub(crate) struct Contract {
+    pub(crate) caller: Address,
+    pub(crate) address: Address,
+    pub(crate) value: U256,
+    pub(crate) input: Bytes,
+}
+
+/// A call frame that's entered.
+#[derive(Debug)]
+pub(crate) struct CallFrame {
+    pub(crate) kind: String,
+    pub(crate) from: Address,
+    pub(crate) to: Address,
+    pub(crate) input: Bytes,
+    pub(crate) gas: u64,
+    pub(crate) value: Option<U256>,
+}
+
+/// The result of a call frame that's exited.
+#[derive(Debug)]
+pub(crate) struct FrameResult {
+    pub(crate) gas_used: u64,
+    pub(crate) output: Bytes,
+    pub(crate) error: Option<String>,
+}
+
+/// The context of the transaction that's passed to the `result` function.
+#[derive(Debug)]
+pub(crate) struct TransactionContext {
+    pub(crate) kind: &'static str,
+    pub(crate) from: Address,
+    pub(crate) to: Option<Address>,
+    pub(crate) input: Bytes,
+    pub(crate) gas: u64,
+    pub(crate) gas_used: u64,
+    pub(crate) gas_price: U256,
+    pub(crate) value: U256,
+    pub(crate) block: u64,
+    pub(crate) output: Bytes,
+    pub(crate) error: Option<String>,
+}
+
+/// Creates a [JsError] with the message.
+pub(crate) fn js_error(msg: impl AsRef<str>) -> JsError {
+    JsError::from_opaque(js_str(msg.as_ref()))
+}
+
+/// Converts the string to a javascript string.
+pub(crate) fn js_str(s: &str) -> JsValue {
+    JsString::from(s).into()
+}
+
+/// Creates the `log` object that's passed to the `step` and `fault` functions.
+pub(crate) fn step_log(
+    step: Rc<StepSnapshot>,
+    cost: u64,
+    error: Option<String>,
+    ctx: &mut Context,
+) -> JsValue {
+    let op = op_object(step.op, ctx);
+    let stack = stack_object(step.clone(), ctx);
+    let memory = memory_object(step.clone(), ctx);
+    let contract = contract_object(&step.contract, ctx);
+    let (pc, gas, depth, refund) = (step.pc as u64, step.gas, step.depth, step.refund);
+
+    let log = ObjectInitializer::new(ctx)
+        .property(js_string!("op"), op, Attribute::all())
+        .property(js_string!("stack"), stack, Attribute::all())
+        .property(js_string!("memory"), memory, Attribute::all())
+        .property(js_string!("contract"), contract, Attribute::all())
+        .function(native(move |_, _, _| Ok(pc.into())), js_string!("getPC"), 0)
+        .function(native(move |_, _, _| Ok(gas.into())), js_string!("getGas"), 0)
+        .function(native(move |_, _, _| Ok(cost.into())), js_string!("getCost"), 0)
+        .function(native(move |_, _, _| Ok(depth.into())), js_string!("getDepth"), 0)
+        .function(native(move |_, _, _| Ok(refund.into())), js_string!("getRefund"), 0)
+        .function(
+            native(move |_, _, _| Ok(error.as_deref().map(js_str).unwrap_or_default())),
+            js_string!("getError"),
+            0,
+        )
+        .build();
+    log.into()
+}
+
+/// Creates the `log.op` object.
+fn op_object(op: u8, ctx: &mut Context) -> JsObject {
+    let name = OpCode::try_from_u8(op)
+        .map(|op| op.to_string())
+        .unwrap_or_else(|| format!("opcode {op:#x} not defined"));
+    let is_push = (0x60..=0x7f).contains(&op);
+    ObjectInitializer::new(ctx)
+        .function(native(move |_, _, _| Ok(u64::from(op).into())), js_string!("toNumber"), 0)
+        .function(native(move |_, _, _| Ok(js_str(&name))), js_string!("toString"), 0)
+        .function(native(move |_, _, _| Ok(is_push.into())), js_string!("isPush"), 0)
+        .build()
+}
+
+/// Creates the `log.stack` object.
+fn stack_object(step: Rc<StepSnapshot>, ctx: &mut Context) -> JsObject {
+    let len = step.stack.len() as u64;
+    ObjectInitializer::new(ctx)
+        .function(native(move |_, _, _| Ok(len.into())), js_string!("length"), 0)
+        .function(
+            native(move |_, args, ctx| {
+                let idx = args.get_or_undefined(0).to_index(ctx)? as usize;
+                let len = step.stack.len();
+                if idx >= len {
+                    return Err(js_error(format!(
+                        "tracer accessed out of bound stack: size {len}, index {idx}"
+                    )))
+                }
+                to_bigint(step.stack[len - 1 - idx])
+            }),
+            js_string!("peek"),
+            1,
+        )
+        .build()
+}
+
+/// Creates the `log.memory` object.
+fn memory_object(step: Rc<StepSnapshot>, ctx: &mut Context) -> JsObject {
+    let len = step.memory.len() as u64;
+    let slice_step = step.clone();
+    ObjectInitializer::new(ctx)
+        .function(native(move |_, _, _| Ok(len.into())), js_string!("length"), 0)
+        .function(
+            native(move |_, args, ctx| {
+                let start = args.get_or_undefined(0).to_index(ctx)? as usize;
+                let end = args.get_or_undefined(1).to_index(ctx)? as usize;
+                let memory = &slice_step.memory;
+                if start > end || end > memory.len() {
+                    return Err(js_error(format!(
+                        "tracer accessed out of bound memory: available {}, offset {start}, size {}",
+                        memory.len(),
+                        end.saturating_sub(start)
+                    )))
+                }
+                to_buf(&memory[start..end], ctx)
+            }),
+            js_string!("slice"),
+            2,
+        )
+        .function(
+            native(move |_, args, ctx| {
+                let offset = args.get_or_undefined(0).to_index(ctx)? as usize;
+                let memory = &step.memory;
+                if offset + 32 > memory.len() {
+                    return Err(js_error(format!(
+                        "tracer accessed out of bound memory: available {}, offset {offset}, size 32",
+                        memory.len()
+                    )))
+                }
+                to_bigint(U256::from_be_slice(&memory[offset..offset + 32]))
+            }),
+            js_string!("getUint"),
+            1,
+        )
+        .build()
+}
+
+/// Creates the `log.contract` object.
+fn contract_object(contract: &Contract, ctx: &mut Context) -> JsObject {
+    let Contract { caller, address, value, input } = contract.clone();
+    ObjectInitializer::new(ctx)
+        .function(
+            native(move |_, _, ctx| to_buf(caller.as_bytes(), ctx)),
+            js_string!("getCaller"),
+            0,
+        )
+        .function(
+            native(move |_, _, ctx| to_buf(address.as_bytes(), ctx)),
+            js_string!("getAddress"),
+            0,
+        )
+        .function(native(move |_, _, _| to_bigint(value)), js_string!("getValue"), 0)
+        .function(native(move |_, _, ctx| to_buf(&input, ctx)), js_string!("getInput"), 0)
+        .build()
+}
+
+/// Creates the frame object that's passed to the `enter` function.
+pub(crate) fn call_frame(frame: CallFrame, ctx: &mut Context) -> JsValue {
+    let CallFrame { kind, from, to, input, gas, value } = frame;
+    let frame = ObjectInitializer::new(ctx)
+        .function(native(move |_, _, _| Ok(js_str(&kind))), js_string!("getType"), 0)
+        .function(native(move |_, _, ctx| to_buf(from.as_bytes(), ctx)), js_string!("getFrom"), 0)
+        .function(native(move |_, _, ctx| to_buf(to.as_bytes(), ctx)), js_string!("getTo"), 0)
+        .function(native(move |_, _, ctx| to_buf(&input, ctx)), js_string!("getInput"), 0)
+        .function(native(move |_, _, _| Ok(gas.into())), js_string!("getGas"), 0)
+        .function(
+            native(move |_, _, _| match value {
+                Some(value) => to_bigint(value),
+                None => Ok(JsValue::undefined()),
+            }),
+            js_string!("getValue"),
+            0,
+        )
+        .build();
+    frame.into()
+}
+
+/// Creates the frame result object that's passed to the `exit` function.
+pub(crate) fn frame_result(result: FrameResult, ctx: &mut Context) -> JsValue {
+    let FrameResult { gas_used, output, error } = result;
+    let result = ObjectInitializer::new(ctx)
+        .function(native(move |_, _, _| Ok(gas_used.into())), js_string!("getGasUsed"), 0)
+        .function(native(move |_, _, ctx| to_buf(&output, ctx)), js_string!("getOutput"), 0)
+        .function(
+            native(move |_, _, _| Ok(error.as_deref().map(js_str).unwrap_or_default())),
+            js_string!("getError"),
+            0,
+        )
+        .build();
+    result.into()
+}
+
+/// Creates the `ctx` object that's passed to the `result` function.
+pub(crate) fn transaction_context(tx: TransactionContext, ctx: &mut Context) -> JsResult<JsValue> {
+    let from = to_buf(tx.from.as_bytes(), ctx)?;
+    let to = match tx.to {
+        Some(to) => to_buf(to.as_bytes(), ctx)?,
+        None => JsValue::undefined(),
+    };
+    let input = to_buf(&tx.input, ctx)?;
+    let output = to_buf(&tx.output, ctx)?;
+    let gas_price = to_bigint(tx.gas_price)?;
+    let value = to_bigint(tx.value)?;
+
+    let mut obj = ObjectInitializer::new(ctx);
+    obj.property(js_string!("type"), js_str(tx.kind), Attribute::all())
+        .property(js_string!("from"), from, Attribute::all())
+        .property(js_string!("to"), to, Attribute::all())
+        .property(js_string!("input"), input, Attribute::all())
+        .property(js_string!("gas"), tx.gas, Attribute::all())
+        .property(js_string!("gasUsed"), tx.gas_used, Attribute::all())
+        .property(js_string!("gasPrice"), gas_price, Attribute::all())
+        .property(js_string!("value"), value, Attribute::all())
+        .property(js_string!("block"), tx.block, Attribute::all())
+        .property(js_string!("output"), output, Attribute::all());
+    if let Some(error) = tx.error {
+        obj.property(js_string!("error"), js_str(&error), Attribute::all());
+    }
+    Ok(obj.build().into())
+}
+
+/// Creates the `db` object that's passed to the tracer functions.
+pub(crate) fn db_object(state: StateRef, ctx: &mut Context) -> JsObject {
+    let (balance_state, nonce_state, code_state, storage_state) =
+        (state.clone(), state.clone(), state.clone(), state.clone());
+    ObjectInitializer::new(ctx)
+        .function(
+            native(move |_, args, ctx| {
+                let address = to_address(args.get_or_undefined(0), ctx)?;
+                let balance = balance_state.with(|db| {
+                    Ok(db.basic(address)?.map(|info| info.balance).unwrap_or_default())
+                })?;
+                to_bigint(balance)
+            }),
+            js_string!("getBalance"),
+            1,
+        )
+        .function(
+            native(move |_, args, ctx| {
+                let address = to_address(args.get_or_undefined(0), ctx)?;
+                let nonce = nonce_state
+                    .with(|db| Ok(db.basic(address)?.map(|info| info.nonce).unwrap_or_default()))?;
+                Ok(nonce.into())
+            }),
+            js_string!("getNonce"),
+            1,
+        )
+        .function(
+            native(move |_, args, ctx| {
+                let address = to_address(args.get_or_undefined(0), ctx)?;
+                let code = code_state.with(|db| db.code(address))?;
+                to_buf(&code, ctx)
+            }),
+            js_string!("getCode"),
+            1,
+        )
+        .function(
+            native(move |_, args, ctx| {
+                let address = to_address(args.get_or_undefined(0), ctx)?;
+                let slot = U256::from_be_bytes(to_word(args.get_or_undefined(1), ctx)?);
+                let value = storage_state.with(|db| db.storage(address, slot))?;
+                to_buf(&value.to_be_bytes::<32>(), ctx)
+            }),
+            js_string!("getState"),
+            2,
+        )
+        .function(
+            native(move |_, args, ctx| {
+                let address = to_address(args.get_or_undefined(0), ctx)?;
+                let exists = state.with(|db| {
+                    Ok(db.basic(address)?.map(|info| !info.is_empty()).unwrap_or_default())
+                })?;
+                Ok(exists.into())
+            }),
+            js_string!("exists"),
+            1,
+        )
+        .build()
+}
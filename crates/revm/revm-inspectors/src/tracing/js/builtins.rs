@@ -0,0 +1,152 @@
+//! Builtin functions of geth's javascript tracer API.
+//!
+//! See <https://geth.ethereum.org/docs/developers/evm-tracing/custom-tracer#custom-javascript-tracing>
+
+use crate::tracing::js::bindings::{js_error, js_str};
+use boa_engine::{
+    js_string, object::builtins::JsUint8Array, Context, JsArgs, JsBigInt, JsResult, JsValue,
+    NativeFunction, Source,
+};
+use reth_primitives::{
+    contract::{create2_address_from_code, create_address},
+    hex, Address, U256,
+};
+use std::collections::HashSet;
+
+/// Wraps the closure in a [NativeFunction].
+pub(crate) fn native<F>(f: F) -> NativeFunction
+where
+    F: Fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue> + 'static,
+{
+    // SAFETY: the closures of the tracer bindings don't capture any garbage collected values
+    unsafe { NativeFunction::from_closure(f) }
+}
+
+/// Registers the global functions that are available to tracers.
+pub(crate) fn register_builtins(ctx: &mut Context, precompiles: HashSet<Address>) -> JsResult<()> {
+    ctx.register_global_callable(js_string!("toHex"), 1, NativeFunction::from_fn_ptr(to_hex))?;
+    ctx.register_global_callable(js_string!("toWord"), 1, NativeFunction::from_fn_ptr(to_word_fn))?;
+    ctx.register_global_callable(
+        js_string!("toAddress"),
+        1,
+        NativeFunction::from_fn_ptr(to_address_fn),
+    )?;
+    ctx.register_global_callable(
+        js_string!("toContract"),
+        2,
+        NativeFunction::from_fn_ptr(to_contract),
+    )?;
+    ctx.register_global_callable(
+        js_string!("toContract2"),
+        3,
+        NativeFunction::from_fn_ptr(to_contract2),
+    )?;
+    ctx.register_global_callable(js_string!("slice"), 3, NativeFunction::from_fn_ptr(slice))?;
+    ctx.register_global_callable(
+        js_string!("isPrecompiled"),
+        1,
+        native(move |_, args, ctx| {
+            let address = to_address(args.get_or_undefined(0), ctx)?;
+            Ok(precompiles.contains(&address).into())
+        }),
+    )?;
+    // tracers written for geth use the `bigInt` library, `BigInt` is the native equivalent
+    ctx.eval(Source::from_bytes("var bigInt = BigInt;"))?;
+    Ok(())
+}
+
+/// Converts the bytes to a `Uint8Array`.
+pub(crate) fn to_buf(bytes: &[u8], ctx: &mut Context) -> JsResult<JsValue> {
+    Ok(JsUint8Array::from_iter(bytes.iter().copied(), ctx)?.into())
+}
+
+/// Converts a byte array, like a `Uint8Array`, or a hex string to bytes.
+pub(crate) fn from_buf(value: &JsValue, ctx: &mut Context) -> JsResult<Vec<u8>> {
+    if let Some(s) = value.as_string() {
+        let s = s.to_std_string_escaped();
+        return hex::decode(s.trim_start_matches("0x"))
+            .map_err(|err| js_error(format!("invalid hex string {s}: {err}")))
+    }
+    let Some(obj) = value.as_object() else {
+        return Err(js_error("expected a byte array or a hex string"))
+    };
+    let len = obj.get(js_string!("length"), ctx)?.to_length(ctx)?;
+    (0..len).map(|idx| obj.get(idx, ctx)?.to_uint8(ctx)).collect()
+}
+
+/// Converts the value to a `BigInt`.
+pub(crate) fn to_bigint(value: U256) -> JsResult<JsValue> {
+    let bigint = JsBigInt::from_string(&value.to_string())
+        .ok_or_else(|| js_error(format!("failed to convert {value} to a bigint")))?;
+    Ok(bigint.into())
+}
+
+/// Converts the value to an address, shorter values are left padded.
+pub(crate) fn to_address(value: &JsValue, ctx: &mut Context) -> JsResult<Address> {
+    let bytes = from_buf(value, ctx)?;
+    if bytes.len() > 20 {
+        return Err(js_error(format!("invalid address of {} bytes", bytes.len())))
+    }
+    let mut address = Address::zero();
+    address.0[20 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(address)
+}
+
+/// Converts the value to a 32 byte word, shorter values are left padded.
+pub(crate) fn to_word(value: &JsValue, ctx: &mut Context) -> JsResult<[u8; 32]> {
+    let bytes = from_buf(value, ctx)?;
+    if bytes.len() > 32 {
+        return Err(js_error(format!("invalid word of {} bytes", bytes.len())))
+    }
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(word)
+}
+
+/// `toHex(buf)`: the hex string of the bytes.
+fn to_hex(_: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<JsValue> {
+    let bytes = from_buf(args.get_or_undefined(0), ctx)?;
+    Ok(js_str(&format!("0x{}", hex::encode(bytes))))
+}
+
+/// `toWord(hexOrBuf)`: the bytes left padded to 32 bytes.
+fn to_word_fn(_: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<JsValue> {
+    let word = to_word(args.get_or_undefined(0), ctx)?;
+    to_buf(&word, ctx)
+}
+
+/// `toAddress(hexOrBuf)`: the bytes left padded to 20 bytes.
+fn to_address_fn(_: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<JsValue> {
+    let address = to_address(args.get_or_undefined(0), ctx)?;
+    to_buf(address.as_bytes(), ctx)
+}
+
+/// `toContract(from, nonce)`: the address of a contract created with `CREATE`.
+fn to_contract(_: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<JsValue> {
+    let from = to_address(args.get_or_undefined(0), ctx)?;
+    let nonce = args.get_or_undefined(1).to_index(ctx)?;
+    to_buf(create_address(from, nonce).as_bytes(), ctx)
+}
+
+/// `toContract2(from, salt, initcode)`: the address of a contract created with `CREATE2`.
+fn to_contract2(_: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<JsValue> {
+    let from = to_address(args.get_or_undefined(0), ctx)?;
+    let salt = U256::from_be_bytes(to_word(args.get_or_undefined(1), ctx)?);
+    let init_code = from_buf(args.get_or_undefined(2), ctx)?;
+    to_buf(create2_address_from_code(from, init_code, salt).as_bytes(), ctx)
+}
+
+/// `slice(buf, start, end)`: the bytes in the range `start..end`.
+fn slice(_: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<JsValue> {
+    let bytes = from_buf(args.get_or_undefined(0), ctx)?;
+    let start = args.get_or_undefined(1).to_index(ctx)? as usize;
+    let end = args.get_or_undefined(2).to_index(ctx)? as usize;
+    if start > end || end > bytes.len() {
+        return Err(js_error(format!(
+            "tracer accessed out of bound memory: available {}, offset {start}, size {}",
+            bytes.len(),
+            end.saturating_sub(start)
+        )))
+    }
+    to_buf(&bytes[start..end], ctx)
+}
@@ -0,0 +1,743 @@
+//! Javascript tracer support, see [JsInspector].
+
+use crate::tracing::{
+    js::{
+        bindings::{
+            call_frame, db_object, frame_result, js_str, step_log, transaction_context, CallFrame,
+            Contract, FrameResult, StateRef, StateWithChanges, StepSnapshot, TransactionContext,
+        },
+        builtins::register_builtins,
+    },
+    types::CallKind,
+    utils::get_create_address,
+};
+use boa_engine::{Context, JsError, JsObject, JsString, JsValue, Source};
+use reth_primitives::{bytes::Bytes, Address};
+use revm::{
+    db::DatabaseRef,
+    inspectors::GasInspector,
+    interpreter::{
+        return_ok, CallInputs, CallScheme, CreateInputs, Gas, InstructionResult, Interpreter,
+    },
+    primitives::{Env, ExecutionResult, Output, ResultAndState, TransactTo},
+    Database, EVMData, Inspector,
+};
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+mod bindings;
+mod builtins;
+
+/// The maximum number of iterations of a single loop of a tracer function.
+pub const MAX_LOOP_ITERATIONS: u64 = 10_000_000;
+
+/// The maximum depth of nested function calls of a tracer function.
+pub const MAX_RECURSION_DEPTH: usize = 256;
+
+/// Errors of a [JsInspector].
+#[derive(Debug, thiserror::Error)]
+pub enum JsInspectorError {
+    /// The code of the tracer failed to evaluate.
+    #[error("failed to evaluate the tracer: {0}")]
+    EvalCode(JsError),
+    /// The code of the tracer doesn't evaluate to an object.
+    #[error("the tracer is not an object")]
+    ExpectedJsObject,
+    /// The tracer has no `result` function.
+    #[error("trace object must expose a function result()")]
+    ResultFunctionMissing,
+    /// The tracer has no `fault` function.
+    #[error("trace object must expose a function fault()")]
+    FaultFunctionMissing,
+    /// The tracer only has one of the `enter` and `exit` functions.
+    #[error("trace object must expose either both or none of enter() and exit()")]
+    EnterExitMismatch,
+    /// The `setup` function of the tracer failed.
+    #[error("failed to set up the tracer: {0}")]
+    SetupCallFailed(JsError),
+    /// A function of the tracer failed.
+    #[error("{err} in server-side tracer function '{name}'")]
+    TracerFunction {
+        /// The name of the function
+        name: &'static str,
+        /// The error thrown by the function
+        err: JsError,
+    },
+    /// The tracer didn't finish before its deadline.
+    #[error("execution timeout")]
+    Timeout,
+    /// Any other javascript error.
+    #[error(transparent)]
+    JsError(#[from] JsError),
+}
+
+/// An inspector that executes a user supplied javascript tracer.
+///
+/// The tracer is a javascript object that implements the functions of geth's tracer object
+/// model, see <https://geth.ethereum.org/docs/developers/evm-tracing/custom-tracer#custom-javascript-tracing>:
+///   - `result(ctx, db)` returns the result of the tracer once the transaction was executed
+///   - `fault(log, db)` is called when a step fails
+///   - `step(log, db)` is called for every executed step, it's optional
+///   - `enter(frame)` and `exit(frameResult)` are called when a call frame, other than the
+///     top-level one, is entered and exited, they're optional
+///   - `setup(config)` is called with the tracer config, as JSON string, when the tracer is
+///     created, it's optional
+///
+/// Once the transaction was executed, the result is obtained with [JsInspector::json_result].
+///
+/// If a timeout is set with [JsInspector::with_timeout], the execution is stopped once the
+/// deadline passed and the result is a [JsInspectorError::Timeout]. The deadline is checked
+/// between the calls of the tracer functions. A single call can't be interrupted, so the
+/// interpreter is limited to [MAX_LOOP_ITERATIONS] iterations per loop and a call depth of
+/// [MAX_RECURSION_DEPTH], a call that exceeds them fails.
+pub struct JsInspector {
+    ctx: Context,
+    /// The tracer object
+    obj: JsObject,
+    result_fn: JsObject,
+    fault_fn: JsObject,
+    step_fn: Option<JsObject>,
+    enter_fn: Option<JsObject>,
+    exit_fn: Option<JsObject>,
+    /// The `db` object that's passed to the tracer functions
+    db: JsObject,
+    /// Gives the `db` object access to the state while a tracer function is executed
+    state: StateRef,
+    /// The step that's currently executed
+    pending_step: Option<StepSnapshot>,
+    /// The number of active call frames
+    call_depth: usize,
+    /// The first error of a tracer function, tracing stops once it's set
+    error: Option<JsInspectorError>,
+    /// The deadline after which the execution is stopped
+    deadline: Option<Instant>,
+    /// The gas inspector used to track remaining gas.
+    gas_inspector: GasInspector,
+}
+
+// === impl JsInspector ===
+
+impl JsInspector {
+    /// Creates a new inspector for the tracer code, the config is passed to the tracer's
+    /// `setup` function.
+    ///
+    /// The precompiles are the addresses `isPrecompiled` checks against.
+    pub fn new(
+        code: String,
+        config: serde_json::Value,
+        precompiles: impl IntoIterator<Item = Address>,
+    ) -> Result<Self, JsInspectorError> {
+        let mut ctx = Context::default();
+        ctx.runtime_limits_mut().set_loop_iteration_limit(MAX_LOOP_ITERATIONS);
+        ctx.runtime_limits_mut().set_recursion_limit(MAX_RECURSION_DEPTH);
+        register_builtins(&mut ctx, precompiles.into_iter().collect())?;
+
+        let obj = ctx
+            .eval(Source::from_bytes(format!("({code})").as_bytes()))
+            .map_err(JsInspectorError::EvalCode)?
+            .as_object()
+            .cloned()
+            .ok_or(JsInspectorError::ExpectedJsObject)?;
+
+        let result_fn = Self::function(&obj, "result", &mut ctx)?
+            .ok_or(JsInspectorError::ResultFunctionMissing)?;
+        let fault_fn = Self::function(&obj, "fault", &mut ctx)?
+            .ok_or(JsInspectorError::FaultFunctionMissing)?;
+        let step_fn = Self::function(&obj, "step", &mut ctx)?;
+        let enter_fn = Self::function(&obj, "enter", &mut ctx)?;
+        let exit_fn = Self::function(&obj, "exit", &mut ctx)?;
+        if enter_fn.is_some() != exit_fn.is_some() {
+            return Err(JsInspectorError::EnterExitMismatch)
+        }
+
+        if let Some(setup_fn) = Self::function(&obj, "setup", &mut ctx)? {
+            let config = if config.is_null() { "{}".to_string() } else { config.to_string() };
+            setup_fn
+                .call(&obj.clone().into(), &[js_str(&config)], &mut ctx)
+                .map_err(JsInspectorError::SetupCallFailed)?;
+        }
+
+        let state = StateRef::default();
+        let db = db_object(state.clone(), &mut ctx);
+
+        Ok(Self {
+            ctx,
+            obj,
+            result_fn,
+            fault_fn,
+            step_fn,
+            enter_fn,
+            exit_fn,
+            db,
+            state,
+            pending_step: None,
+            call_depth: 0,
+            error: None,
+            deadline: None,
+            gas_inspector: Default::default(),
+        })
+    }
+
+    /// Stops the execution once the timeout, starting now, elapsed.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    /// Returns true if the deadline passed, which is recorded as the error of the tracer unless
+    /// a tracer function failed before.
+    fn is_timed_out(&mut self) -> bool {
+        if !self.deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+            return false
+        }
+        self.error.get_or_insert(JsInspectorError::Timeout);
+        true
+    }
+
+    /// Returns the function of the tracer object with the given name, if it has one.
+    fn function(
+        obj: &JsObject,
+        name: &str,
+        ctx: &mut Context,
+    ) -> Result<Option<JsObject>, JsInspectorError> {
+        let value = obj.get(JsString::from(name), ctx)?;
+        Ok(value.as_object().filter(|f| f.is_callable()).cloned())
+    }
+
+    /// Calls the tracer's `result` function with the outcome of the transaction and returns its
+    /// result as JSON.
+    ///
+    /// The `db` must be the database the transaction was executed on, the `db` object of the
+    /// tracer reads the changes of the transaction on top of it.
+    pub fn json_result<DB: DatabaseRef>(
+        &mut self,
+        res: &ResultAndState,
+        env: &Env,
+        db: &DB,
+    ) -> Result<serde_json::Value, JsInspectorError> {
+        if let Some(err) = self.error.take() {
+            return Err(err)
+        }
+
+        let (output, error, created) = match &res.result {
+            ExecutionResult::Success { output, .. } => match output {
+                Output::Call(out) => (out.clone(), None, None),
+                Output::Create(out, address) => (out.clone(), None, *address),
+            },
+            ExecutionResult::Revert { output, .. } => {
+                (output.clone(), Some("execution reverted".to_string()), None)
+            }
+            ExecutionResult::Halt { reason, .. } => {
+                (Bytes::new(), Some(format!("{reason:?}")), None)
+            }
+        };
+        let (kind, to) = match env.tx.transact_to {
+            TransactTo::Call(to) => ("CALL", Some(to)),
+            TransactTo::Create(_) => ("CREATE", created),
+        };
+        let tx = TransactionContext {
+            kind,
+            from: env.tx.caller,
+            to,
+            input: env.tx.data.clone(),
+            gas: env.tx.gas_limit,
+            gas_used: res.result.gas_used(),
+            gas_price: env.tx.gas_price,
+            value: env.tx.value,
+            block: u64::try_from(env.block.number).unwrap_or(u64::MAX),
+            output,
+            error,
+        };
+        let tx = transaction_context(tx, &mut self.ctx)?;
+
+        let mut state = StateWithChanges { changes: &res.state, db };
+        let _guard = self.state.set(&mut state);
+        let result = self
+            .result_fn
+            .call(&self.obj.clone().into(), &[tx, self.db.clone().into()], &mut self.ctx)
+            .map_err(|err| JsInspectorError::TracerFunction { name: "result", err })?;
+        if result.is_undefined() {
+            return Ok(serde_json::Value::Null)
+        }
+        Ok(result.to_json(&mut self.ctx)?)
+    }
+
+    /// Returns true if the tracer wants to be notified about call frames.
+    fn traces_frames(&self) -> bool {
+        self.enter_fn.is_some() && self.error.is_none()
+    }
+
+    /// Calls the tracer function, the first error is recorded and stops tracing.
+    fn try_call(&mut self, name: &'static str, f: &JsObject, args: &[JsValue]) {
+        if let Err(err) = f.call(&self.obj.clone().into(), args, &mut self.ctx) {
+            self.error = Some(JsInspectorError::TracerFunction { name, err });
+        }
+    }
+
+    /// Calls the tracer's `enter` function.
+    fn enter<DB: Database>(&mut self, data: &mut EVMData<'_, DB>, frame: CallFrame) {
+        let Some(enter_fn) = self.enter_fn.clone() else { return };
+        let frame = call_frame(frame, &mut self.ctx);
+        let _guard = self.state.set(data);
+        self.try_call("enter", &enter_fn, &[frame]);
+    }
+
+    /// Calls the tracer's `exit` function.
+    fn exit<DB: Database>(&mut self, data: &mut EVMData<'_, DB>, result: FrameResult) {
+        let Some(exit_fn) = self.exit_fn.clone() else { return };
+        let result = frame_result(result, &mut self.ctx);
+        let _guard = self.state.set(data);
+        self.try_call("exit", &exit_fn, &[result]);
+    }
+}
+
+impl<DB> Inspector<DB> for JsInspector
+where
+    DB: Database,
+{
+    fn initialize_interp(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+    ) -> InstructionResult {
+        self.gas_inspector.initialize_interp(interp, data, is_static)
+    }
+
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+    ) -> InstructionResult {
+        self.gas_inspector.step(interp, data, is_static);
+        if self.is_timed_out() {
+            // stops the current frame, the steps of all parent frames are stopped the same way
+            return InstructionResult::Stop
+        }
+        if self.error.is_some() {
+            return InstructionResult::Continue
+        }
+
+        // the stack and memory are only needed by the `step` function
+        let record_state = self.step_fn.is_some();
+        let pc = interp.program_counter();
+        self.pending_step = Some(StepSnapshot {
+            pc,
+            op: interp.contract.bytecode.bytecode()[pc],
+            gas: self.gas_inspector.gas_remaining(),
+            refund: interp.gas.refunded() as u64,
+            depth: data.journaled_state.depth(),
+            stack: record_state.then(|| interp.stack.data().clone()).unwrap_or_default(),
+            memory: record_state.then(|| interp.memory.data().clone()).unwrap_or_default(),
+            contract: Contract {
+                caller: interp.contract.caller,
+                address: interp.contract.address,
+                value: interp.contract.value,
+                input: interp.contract.input.clone(),
+            },
+        });
+
+        InstructionResult::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+        eval: InstructionResult,
+    ) -> InstructionResult {
+        self.gas_inspector.step_end(interp, data, is_static, eval);
+        let Some(step) = self.pending_step.take() else { return InstructionResult::Continue };
+        if self.error.is_some() {
+            return InstructionResult::Continue
+        }
+
+        let step = Rc::new(step);
+        let cost = step.gas.saturating_sub(self.gas_inspector.gas_remaining());
+        let _guard = self.state.set(data);
+
+        if let Some(step_fn) = self.step_fn.clone() {
+            let log = step_log(step.clone(), cost, None, &mut self.ctx);
+            self.try_call("step", &step_fn, &[log, self.db.clone().into()]);
+        }
+
+        // reverts aren't faults
+        if eval as u8 > InstructionResult::Revert as u8 && self.error.is_none() {
+            let log = step_log(step, cost, Some(format!("{eval:?}")), &mut self.ctx);
+            let fault_fn = self.fault_fn.clone();
+            self.try_call("fault", &fault_fn, &[log, self.db.clone().into()]);
+        }
+
+        InstructionResult::Continue
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+        is_static: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.gas_inspector.call(data, inputs, is_static);
+
+        self.call_depth += 1;
+        if self.call_depth > 1 && self.traces_frames() {
+            // determine correct `from` and `to`  based on the call scheme
+            let (from, to) = match inputs.context.scheme {
+                CallScheme::DelegateCall | CallScheme::CallCode => {
+                    (inputs.context.address, inputs.context.code_address)
+                }
+                _ => (inputs.context.caller, inputs.context.address),
+            };
+            let value = match inputs.context.scheme {
+                CallScheme::StaticCall => None,
+                // delegate calls inherit the value of the parent call
+                CallScheme::DelegateCall => Some(inputs.context.apparent_value),
+                _ => Some(inputs.transfer.value),
+            };
+            let frame = CallFrame {
+                kind: CallKind::from(inputs.context.scheme).to_string(),
+                from,
+                to,
+                input: inputs.input.clone(),
+                gas: inputs.gas_limit,
+                value,
+            };
+            self.enter(data, frame);
+        }
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CallInputs,
+        gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+        is_static: bool,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.gas_inspector.call_end(data, inputs, gas, ret, out.clone(), is_static);
+
+        self.call_depth -= 1;
+        if self.call_depth > 0 && self.traces_frames() {
+            let result =
+                FrameResult { gas_used: gas.spend(), output: out.clone(), error: error(ret) };
+            self.exit(data, result);
+        }
+
+        (ret, gas, out)
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.gas_inspector.create(data, inputs);
+
+        self.call_depth += 1;
+        if self.call_depth > 1 && self.traces_frames() {
+            let _ = data.journaled_state.load_account(inputs.caller, data.db);
+            let nonce = data.journaled_state.account(inputs.caller).info.nonce;
+            let frame = CallFrame {
+                kind: CallKind::from(inputs.scheme).to_string(),
+                from: inputs.caller,
+                to: get_create_address(inputs, nonce),
+                input: inputs.init_code.clone(),
+                gas: inputs.gas_limit,
+                value: Some(inputs.value),
+            };
+            self.enter(data, frame);
+        }
+
+        (InstructionResult::Continue, None, Gas::new(inputs.gas_limit), Bytes::default())
+    }
+
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CreateInputs,
+        status: InstructionResult,
+        address: Option<Address>,
+        gas: Gas,
+        retdata: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.gas_inspector.create_end(data, inputs, status, address, gas, retdata.clone());
+
+        self.call_depth -= 1;
+        if self.call_depth > 0 && self.traces_frames() {
+            let result = FrameResult {
+                gas_used: gas.spend(),
+                output: retdata.clone(),
+                error: error(status),
+            };
+            self.exit(data, result);
+        }
+
+        (status, address, gas, retdata)
+    }
+}
+
+impl std::fmt::Debug for JsInspector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsInspector")
+            .field("call_depth", &self.call_depth)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the error of a call frame that didn't succeed.
+fn error(status: InstructionResult) -> Option<String> {
+    match status {
+        return_ok!() => None,
+        InstructionResult::Revert => Some("execution reverted".to_string()),
+        status => Some(format!("{status:?}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        contract::{create2_address_from_code, create_address},
+        U256,
+    };
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::{AccountInfo, Bytecode, Eval, State},
+        EVM,
+    };
+    use serde_json::json;
+
+    fn result_and_state() -> ResultAndState {
+        ResultAndState {
+            result: ExecutionResult::Success {
+                reason: Eval::Stop,
+                gas_used: 21_000,
+                gas_refunded: 0,
+                logs: vec![],
+                output: Output::Call(Bytes::new()),
+            },
+            state: State::default(),
+        }
+    }
+
+    #[test]
+    fn tracer_result() {
+        let code = r#"{
+            setup: function(cfg) { this.cfg = JSON.parse(cfg); },
+            fault: function() {},
+            result: function(ctx, db) {
+                return {
+                    type: ctx.type,
+                    gasUsed: ctx.gasUsed,
+                    to: toHex(ctx.to),
+                    balance: db.getBalance(ctx.to).toString(),
+                    label: this.cfg.label,
+                };
+            }
+        }"#;
+        let mut inspector =
+            JsInspector::new(code.to_string(), json!({ "label": "test" }), vec![]).unwrap();
+
+        let mut env = Env::default();
+        env.tx.transact_to = TransactTo::Call(Address::from_low_u64_be(1));
+        env.tx.value = U256::from(1);
+        let result = inspector.json_result(&result_and_state(), &env, &EmptyDB::default()).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "type": "CALL",
+                "gasUsed": 21000,
+                "to": "0x0000000000000000000000000000000000000001",
+                "balance": "0",
+                "label": "test",
+            })
+        );
+    }
+
+    #[test]
+    fn tracer_functions() {
+        let missing_result = "{ fault: function() {} }";
+        assert!(matches!(
+            JsInspector::new(missing_result.to_string(), json!(null), vec![]),
+            Err(JsInspectorError::ResultFunctionMissing)
+        ));
+
+        let missing_exit = "{ fault: function() {}, result: function() {}, enter: function() {} }";
+        assert!(matches!(
+            JsInspector::new(missing_exit.to_string(), json!(null), vec![]),
+            Err(JsInspectorError::EnterExitMismatch)
+        ));
+
+        let throws = "{ fault: function() {}, result: function() { throw 'oops' } }";
+        let mut inspector = JsInspector::new(throws.to_string(), json!(null), vec![]).unwrap();
+        let err = inspector
+            .json_result(&result_and_state(), &Env::default(), &EmptyDB::default())
+            .unwrap_err();
+        assert!(matches!(err, JsInspectorError::TracerFunction { name: "result", .. }));
+    }
+
+    #[test]
+    fn tracer_runtime_limits() {
+        let endless_loop = "{ fault: function() {}, result: function() { while (true) {} } }";
+        let mut inspector =
+            JsInspector::new(endless_loop.to_string(), json!(null), vec![]).unwrap();
+        let err = inspector
+            .json_result(&result_and_state(), &Env::default(), &EmptyDB::default())
+            .unwrap_err();
+        assert!(matches!(err, JsInspectorError::TracerFunction { name: "result", .. }));
+
+        let endless_recursion = r#"{
+            fault: function() {},
+            recurse: function() { return this.recurse(); },
+            result: function() { return this.recurse(); }
+        }"#;
+        let mut inspector =
+            JsInspector::new(endless_recursion.to_string(), json!(null), vec![]).unwrap();
+        let err = inspector
+            .json_result(&result_and_state(), &Env::default(), &EmptyDB::default())
+            .unwrap_err();
+        assert!(matches!(err, JsInspectorError::TracerFunction { name: "result", .. }));
+    }
+
+    #[test]
+    fn tracer_builtins() {
+        let code = r#"{
+            fault: function() {},
+            result: function() {
+                return {
+                    word: toHex(toWord("0x0102")),
+                    address: toHex(toAddress("0x01")),
+                    contract: toHex(toContract("0x0000000000000000000000000000000000000001", 1)),
+                    contract2: toHex(toContract2("0x01", "0x02", [0x60, 0x00])),
+                    slice: toHex(slice(toWord("0x0102"), 30, 32)),
+                    precompiled: isPrecompiled("0x01"),
+                    notPrecompiled: isPrecompiled("0x02"),
+                    bigInt: (bigInt("100000000000000000000") + bigInt(1)).toString(),
+                };
+            }
+        }"#;
+        let mut inspector =
+            JsInspector::new(code.to_string(), json!(null), vec![Address::from_low_u64_be(1)])
+                .unwrap();
+        let result = inspector
+            .json_result(&result_and_state(), &Env::default(), &EmptyDB::default())
+            .unwrap();
+
+        let from = Address::from_low_u64_be(1);
+        let contract = create_address(from, 1);
+        let contract2 = create2_address_from_code(from, [0x60, 0x00], U256::from(2));
+        assert_eq!(
+            result,
+            json!({
+                "word": format!("0x{}0102", "00".repeat(30)),
+                "address": "0x0000000000000000000000000000000000000001",
+                "contract": format!("{contract:?}"),
+                "contract2": format!("{contract2:?}"),
+                "slice": "0x0102",
+                "precompiled": true,
+                "notPrecompiled": false,
+                "bigInt": "100000000000000000001",
+            })
+        );
+
+        let out_of_bounds = r#"{
+            fault: function() {},
+            result: function() { return toHex(slice(toWord("0x01"), 30, 33)); }
+        }"#;
+        let mut inspector =
+            JsInspector::new(out_of_bounds.to_string(), json!(null), vec![]).unwrap();
+        let err = inspector
+            .json_result(&result_and_state(), &Env::default(), &EmptyDB::default())
+            .unwrap_err();
+        assert!(matches!(err, JsInspectorError::TracerFunction { name: "result", .. }));
+    }
+
+    #[test]
+    fn tracer_db() {
+        let code = r#"{
+            fault: function() {},
+            result: function(ctx, db) {
+                return {
+                    balance: db.getBalance(ctx.to).toString(),
+                    nonce: db.getNonce(ctx.to),
+                    code: toHex(db.getCode(ctx.to)),
+                    state: toHex(db.getState(ctx.to, "0x01")),
+                    exists: db.exists(ctx.to),
+                    missing: db.exists("0x02"),
+                };
+            }
+        }"#;
+        let mut inspector = JsInspector::new(code.to_string(), json!(null), vec![]).unwrap();
+
+        let address = Address::from_low_u64_be(1);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            address,
+            AccountInfo {
+                balance: U256::from(10),
+                nonce: 3,
+                code: Some(Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00]))),
+                ..Default::default()
+            },
+        );
+        db.insert_account_storage(address, U256::from(1), U256::from(0xff)).unwrap();
+
+        let mut env = Env::default();
+        env.tx.transact_to = TransactTo::Call(address);
+        let result = inspector.json_result(&result_and_state(), &env, &db).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "balance": "10",
+                "nonce": 3,
+                "code": "0x6000",
+                "state": format!("0x{}ff", "00".repeat(31)),
+                "exists": true,
+                "missing": false,
+            })
+        );
+    }
+
+    #[test]
+    fn tracer_timeout() {
+        let code = r#"{
+            steps: 0,
+            step: function() { this.steps++; },
+            fault: function() {},
+            result: function() { return this.steps; }
+        }"#;
+        let mut inspector = JsInspector::new(code.to_string(), json!(null), vec![])
+            .unwrap()
+            .with_timeout(Duration::from_millis(100));
+
+        // JUMPDEST PUSH1 0 JUMP, loops until it runs out of gas
+        let contract = Address::from_low_u64_be(0x1000);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            contract,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(Bytes::from_static(&[0x5b, 0x60, 0x00, 0x56]))),
+                ..Default::default()
+            },
+        );
+
+        let mut evm = EVM::new();
+        evm.database(db);
+        evm.env.block.gas_limit = U256::from(u64::MAX);
+        evm.env.tx.gas_limit = u64::MAX / 2;
+        evm.env.tx.transact_to = TransactTo::Call(contract);
+        let res = evm.inspect(&mut inspector).unwrap();
+
+        let env = evm.env.clone();
+        let err = inspector.json_result(&res, &env, &*evm.db().unwrap()).unwrap_err();
+        assert!(matches!(err, JsInspectorError::Timeout));
+    }
+}
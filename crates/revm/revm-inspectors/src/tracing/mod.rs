@@ -18,6 +18,8 @@ mod arena;
 mod builder;
 mod config;
 mod fourbyte;
+#[cfg(feature = "js-tracer")]
+pub mod js;
 mod opcount;
 mod types;
 mod utils;
@@ -108,6 +110,7 @@ impl TracingInspector {
         value: U256,
         kind: CallKind,
         caller: Address,
+        gas_limit: u64,
     ) {
         self.trace_stack.push(self.traces.push_trace(
             0,
@@ -120,6 +123,7 @@ impl TracingInspector {
                 status: InstructionResult::Continue,
                 caller,
                 last_call_return_value: self.last_call_return_data.clone(),
+                gas_limit,
                 ..Default::default()
             },
         ));
@@ -323,6 +327,7 @@ where
             inputs.transfer.value,
             inputs.context.scheme.into(),
             from,
+            inputs.gas_limit,
         );
 
         (InstructionResult::Continue, Gas::new(0), Bytes::new())
@@ -365,6 +370,7 @@ where
             inputs.value,
             inputs.scheme.into(),
             inputs.caller,
+            inputs.gas_limit,
         );
 
         (InstructionResult::Continue, None, Gas::new(inputs.gas_limit), Bytes::default())
@@ -3,7 +3,7 @@
 use crate::tracing::utils::convert_memory;
 use reth_primitives::{bytes::Bytes, Address, H256, U256};
 use reth_rpc_types::trace::{
-    geth::StructLog,
+    geth::{CallFrame, CallLogFrame, StructLog},
     parity::{
         Action, ActionType, CallAction, CallOutput, CallType, CreateAction, CreateOutput,
         SelfdestructAction, TraceOutput,
@@ -28,6 +28,19 @@ pub enum CallKind {
     Create2,
 }
 
+impl std::fmt::Display for CallKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallKind::Call => write!(f, "CALL"),
+            CallKind::StaticCall => write!(f, "STATICCALL"),
+            CallKind::CallCode => write!(f, "CALLCODE"),
+            CallKind::DelegateCall => write!(f, "DELEGATECALL"),
+            CallKind::Create => write!(f, "CREATE"),
+            CallKind::Create2 => write!(f, "CREATE2"),
+        }
+    }
+}
+
 impl From<CallScheme> for CallKind {
     fn from(scheme: CallScheme) -> Self {
         match scheme {
@@ -101,6 +114,8 @@ pub(crate) struct CallTrace {
     pub(crate) output: Bytes,
     /// The return data of the last call, if any
     pub(crate) last_call_return_value: Option<Bytes>,
+    /// The gas limit of the call
+    pub(crate) gas_limit: u64,
     /// The gas cost of the call
     pub(crate) gas_used: u64,
     /// The status of the trace's call
@@ -124,6 +139,7 @@ impl Default for CallTrace {
             data: Default::default(),
             output: Default::default(),
             last_call_return_value: None,
+            gas_limit: Default::default(),
             gas_used: Default::default(),
             status: InstructionResult::Continue,
             call_context: Default::default(),
@@ -177,6 +193,49 @@ impl CallTraceNode {
         }
     }
 
+    /// Returns the geth `callTracer` frame of this call, without the frames of its children.
+    pub(crate) fn geth_empty_call_frame(&self, include_logs: bool) -> CallFrame {
+        let kind = self.kind();
+        let mut frame = CallFrame {
+            typ: kind.to_string(),
+            from: self.trace.caller,
+            to: Some(self.trace.address),
+            // static and delegate calls don't transfer any value
+            value: (!matches!(kind, CallKind::StaticCall | CallKind::DelegateCall))
+                .then_some(self.trace.value),
+            gas: U256::from(self.trace.gas_limit),
+            gas_used: U256::from(self.trace.gas_used),
+            input: self.trace.data.clone().into(),
+            output: (!self.trace.output.is_empty()).then(|| self.trace.output.clone().into()),
+            error: None,
+            calls: None,
+            logs: None,
+        };
+
+        if !self.trace.success {
+            frame.error = Some(match self.status() {
+                InstructionResult::Revert => "execution reverted".to_string(),
+                status => format!("{status:?}"),
+            });
+        }
+
+        // like geth, the logs of failed calls are discarded
+        if include_logs && self.trace.success && !self.logs.is_empty() {
+            frame.logs = Some(
+                self.logs
+                    .iter()
+                    .map(|log| CallLogFrame {
+                        address: Some(self.trace.address),
+                        topics: Some(log.topics.clone()),
+                        data: Some(log.data.clone().into()),
+                    })
+                    .collect(),
+            );
+        }
+
+        frame
+    }
+
     /// Returns the `Action` for a parity trace
     pub(crate) fn parity_action(&self) -> Action {
         if self.status() == InstructionResult::SelfDestruct {
@@ -1,6 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::NodeRecord;
-use reth_rpc_types::NodeInfo;
+use reth_rpc_types::{BannedPeer, ConnectedPeer, NodeInfo, PeerDisconnect, PeerEvent};
 
 /// Admin namespace rpc interface that gives access to several non-standard RPC methods.
 #[cfg_attr(not(feature = "client"), rpc(server))]
@@ -8,6 +8,8 @@ use reth_rpc_types::NodeInfo;
 #[async_trait::async_trait]
 pub trait AdminApi {
     /// Adds the given node record to the peerset.
+    ///
+    /// The node is added as static peer that is redialed whenever the session to it drops.
     #[method(name = "admin_addPeer")]
     fn add_peer(&self, record: NodeRecord) -> RpcResult<bool>;
 
@@ -28,11 +30,29 @@ pub trait AdminApi {
     #[method(name = "admin_removeTrustedPeer")]
     fn remove_trusted_peer(&self, record: NodeRecord) -> RpcResult<bool>;
 
-    /// Creates an RPC subscription which serves events received from the network.
+    /// Returns all peers there's an active session with.
+    #[method(name = "admin_peers")]
+    async fn peers(&self) -> RpcResult<Vec<ConnectedPeer>>;
+
+    /// Returns all banned peers and when their ban expires.
+    #[method(name = "admin_bannedPeers")]
+    async fn banned_peers(&self) -> RpcResult<Vec<BannedPeer>>;
+
+    /// Lifts the bans of all peers.
+    #[method(name = "admin_clearBans")]
+    fn clear_bans(&self) -> RpcResult<bool>;
+
+    /// Returns the most recent disconnects, oldest first, with the side that initiated them and
+    /// the reason that was sent.
+    #[method(name = "admin_recentDisconnects")]
+    async fn recent_disconnects(&self) -> RpcResult<Vec<PeerDisconnect>>;
+
+    /// Creates an RPC subscription which streams peer lifecycle events: established and dropped
+    /// sessions and failed handshakes.
     #[subscription(
         name = "admin_peerEvents",
         unsubscribe = "admin_peerEvents_unsubscribe",
-        item = String
+        item = PeerEvent
     )]
     fn subscribe_peer_events(&self);
 
@@ -2,8 +2,8 @@ use jsonrpsee::{core::RpcResult as Result, proc_macros::rpc};
 use reth_primitives::{BlockId, BlockNumberOrTag, Bytes, H256};
 use reth_rpc_types::{
     trace::geth::{
-        BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTraceFrame,
-        TraceResult,
+        BlockTraceResult, DebugSubscriptionKind, GethDebugTracingCallOptions,
+        GethDebugTracingOptions, GethTraceFrame, TraceResult,
     },
     CallRequest, RichBlock,
 };
@@ -33,13 +33,22 @@ pub trait DebugApi {
     async fn bad_blocks(&self) -> Result<Vec<RichBlock>>;
 
     /// Returns the structured logs created during the execution of EVM between two blocks
-    /// (excluding start) as a JSON object.
-    #[method(name = "debug_traceChain")]
-    async fn debug_trace_chain(
+    /// (excluding start), streaming the traces of each block as soon as it was traced.
+    ///
+    /// Like in geth, this is only available as subscription: `debug_subscribe` with `traceChain`
+    /// as the kind of the subscription.
+    #[subscription(
+        name = "debug_subscribe" => "debug_subscription",
+        unsubscribe = "debug_unsubscribe",
+        item = BlockTraceResult
+    )]
+    fn debug_trace_chain(
         &self,
+        kind: DebugSubscriptionKind,
         start_exclusive: BlockNumberOrTag,
         end_inclusive: BlockNumberOrTag,
-    ) -> Result<Vec<BlockTraceResult>>;
+        opts: Option<GethDebugTracingOptions>,
+    );
 
     /// The `debug_traceBlock` method will return a full stack trace of all invoked opcodes of all
     /// transaction that were included in this block.
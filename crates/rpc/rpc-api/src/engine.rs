@@ -4,8 +4,9 @@ use reth_primitives::{
 };
 use reth_rpc_types::{
     engine::{
-        ExecutionPayload, ExecutionPayloadBodies, ExecutionPayloadEnvelope, ForkchoiceState,
-        ForkchoiceUpdated, PayloadAttributes, PayloadId, PayloadStatus, TransitionConfiguration,
+        BlobAndProofV1, ClientVersionV1, ExecutionPayload, ExecutionPayloadBodies,
+        ExecutionPayloadEnvelope, ForkchoiceState, ForkchoiceUpdated, PayloadAttributes, PayloadId,
+        PayloadStatus, TransitionConfiguration,
     },
     state::StateOverride,
     CallRequest, Log, RichBlock, SyncStatus,
@@ -86,6 +87,24 @@ pub trait EngineApi {
     /// See also <https://github.com/ethereum/execution-apis/blob/6452a6b194d7db269bf1dbd087a267251d3cc7f8/src/engine/common.md#capabilities>
     #[method(name = "engine_exchangeCapabilities")]
     async fn exchange_capabilities(&self, capabilities: Vec<String>) -> Result<Vec<String>>;
+
+    /// Exchanges client version information with the consensus layer client.
+    ///
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/identification.md#engine_getclientversionv1>
+    #[method(name = "engine_getClientVersionV1")]
+    async fn get_client_version_v1(
+        &self,
+        client_version: ClientVersionV1,
+    ) -> Result<Vec<ClientVersionV1>>;
+
+    /// Returns the blobs and proofs for the given versioned hashes from the blob store.
+    ///
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_getblobsv1>
+    #[method(name = "engine_getBlobsV1")]
+    async fn get_blobs_v1(
+        &self,
+        versioned_hashes: Vec<H256>,
+    ) -> Result<Vec<Option<BlobAndProofV1>>>;
 }
 
 /// A subset of the ETH rpc interface: <https://ethereum.github.io/execution-apis/api-documentation/>
@@ -4,8 +4,9 @@ use reth_primitives::{
     H256, H64, U256, U64,
 };
 use reth_rpc_types::{
-    state::StateOverride, CallRequest, EIP1186AccountProofResponse, FeeHistory, Index, RichBlock,
-    SyncStatus, Transaction, TransactionReceipt, TransactionRequest, Work,
+    state::StateOverride, BlockOverrides, Bundle, CallBundle, CallBundleResponse, CallRequest,
+    EIP1186AccountProofResponse, EthCallResponse, FeeHistory, FillTransactionResult, Index,
+    RichBlock, StateContext, SyncStatus, Transaction, TransactionReceipt, TransactionRequest, Work,
 };
 
 /// Eth rpc interface: <https://ethereum.github.io/execution-apis/api-documentation/>
@@ -104,10 +105,22 @@ pub trait EthApi {
         index: Index,
     ) -> Result<Option<Transaction>>;
 
+    /// Returns the mined transaction of the sender with the given nonce.
+    #[method(name = "eth_getTransactionBySenderAndNonce")]
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: U64,
+    ) -> Result<Option<Transaction>>;
+
     /// Returns the receipt of a transaction by transaction hash.
     #[method(name = "eth_getTransactionReceipt")]
     async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>>;
 
+    /// Returns the receipts of all transactions in a block.
+    #[method(name = "eth_getBlockReceipts")]
+    async fn block_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>>;
+
     /// Returns the balance of the account of given address.
     #[method(name = "eth_getBalance")]
     async fn balance(&self, address: Address, block_number: Option<BlockId>) -> Result<U256>;
@@ -134,14 +147,39 @@ pub trait EthApi {
     async fn get_code(&self, address: Address, block_number: Option<BlockId>) -> Result<Bytes>;
 
     /// Executes a new message call immediately without creating a transaction on the block chain.
+    ///
+    /// The state overrides are applied before the call, the block overrides replace the header
+    /// fields of the block the call is executed in.
     #[method(name = "eth_call")]
     async fn call(
         &self,
         request: CallRequest,
         block_number: Option<BlockId>,
         state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<Bytes>;
 
+    /// Simulates the bundles of calls in order, each call on top of the state changes of the
+    /// previous ones, and returns the result of each call, per bundle.
+    ///
+    /// The calls are executed in the block of the state context, after the transactions of the
+    /// block it selects. The state overrides are applied before the first call.
+    ///
+    /// Follows Erigon's `eth_callMany`.
+    #[method(name = "eth_callMany")]
+    async fn call_many(
+        &self,
+        bundles: Vec<Bundle>,
+        state_context: Option<StateContext>,
+        state_override: Option<StateOverride>,
+    ) -> Result<Vec<Vec<EthCallResponse>>>;
+
+    /// Simulates a bundle of signed transactions on top of the given state block and returns the
+    /// result of each transaction and the payment to the coinbase, following the Flashbots
+    /// `eth_callBundle` schema.
+    #[method(name = "eth_callBundle")]
+    async fn call_bundle(&self, bundle: CallBundle) -> Result<CallBundleResponse>;
+
     /// Generates an access list for a transaction.
     ///
     /// This method creates an [EIP2930](https://eips.ethereum.org/EIPS/eip-2930) type accessList based on a given Transaction.
@@ -165,11 +203,15 @@ pub trait EthApi {
 
     /// Generates and returns an estimate of how much gas is necessary to allow the transaction to
     /// complete.
+    ///
+    /// The estimation is done with the given state and block overrides applied, like `eth_call`.
     #[method(name = "eth_estimateGas")]
     async fn estimate_gas(
         &self,
         request: CallRequest,
         block_number: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<U256>;
 
     /// Returns the current price per gas in wei.
@@ -180,6 +222,10 @@ pub trait EthApi {
     #[method(name = "eth_maxPriorityFeePerGas")]
     async fn max_priority_fee_per_gas(&self) -> Result<U256>;
 
+    /// Introduced in EIP-4844, returns the blob gas price of the next block.
+    #[method(name = "eth_blobBaseFee")]
+    async fn blob_base_fee(&self) -> Result<U256>;
+
     /// Returns the Transaction fee history
     ///
     /// Introduced in EIP-1159 for getting information on the appropriate priority fee to use.
@@ -225,6 +271,11 @@ pub trait EthApi {
     #[method(name = "eth_sendTransaction")]
     async fn send_transaction(&self, request: TransactionRequest) -> Result<H256>;
 
+    /// Fills in the nonce, gas limit, fees and chain id of the transaction request and returns the
+    /// unsigned transaction and its RLP encoding.
+    #[method(name = "eth_fillTransaction")]
+    async fn fill_transaction(&self, request: TransactionRequest) -> Result<FillTransactionResult>;
+
     /// Sends signed transaction, returning its hash.
     #[method(name = "eth_sendRawTransaction")]
     async fn send_raw_transaction(&self, bytes: Bytes) -> Result<H256>;
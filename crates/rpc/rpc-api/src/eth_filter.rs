@@ -1,6 +1,6 @@
 use jsonrpsee::{core::RpcResult as Result, proc_macros::rpc};
 use reth_primitives::filter::Filter;
-use reth_rpc_types::{FilterChanges, FilterId, Log};
+use reth_rpc_types::{FilterChanges, FilterId, Log, LogsPage};
 
 /// Rpc Interface for poll-based ethereum filter API.
 #[cfg_attr(not(feature = "client"), rpc(server))]
@@ -33,4 +33,12 @@ pub trait EthFilterApi {
     /// Returns logs matching given filter object.
     #[method(name = "eth_getLogs")]
     async fn logs(&self, filter: Filter) -> Result<Vec<Log>>;
+
+    /// Returns logs matching given filter object like `eth_getLogs`, but pages through the range
+    /// instead of failing if the server limits are exceeded.
+    ///
+    /// If a limit is reached, the logs up to a block boundary are returned together with the
+    /// `nextBlock` to use as `fromBlock` of the next request.
+    #[method(name = "eth_getLogsPage")]
+    async fn logs_page(&self, filter: Filter) -> Result<LogsPage>;
 }
@@ -16,7 +16,11 @@ mod eth;
 mod eth_filter;
 mod eth_pubsub;
 mod net;
+mod otterscan;
+mod personal;
+mod reth;
 mod trace;
+mod txpool;
 mod web3;
 
 /// re-export of all server traits
@@ -32,7 +36,11 @@ pub mod servers {
         eth_filter::EthFilterApiServer,
         eth_pubsub::EthPubSubApiServer,
         net::NetApiServer,
+        otterscan::OtterscanApiServer,
+        personal::PersonalApiServer,
+        reth::{RethAdminApiServer, RethApiServer},
         trace::TraceApiServer,
+        txpool::TxPoolApiServer,
         web3::Web3ApiServer,
     };
 }
@@ -50,7 +58,11 @@ pub mod clients {
         engine::{EngineApiClient, EngineEthApiClient},
         eth::EthApiClient,
         net::NetApiClient,
+        otterscan::OtterscanApiClient,
+        personal::PersonalApiClient,
+        reth::{RethAdminApiClient, RethApiClient},
         trace::TraceApiClient,
+        txpool::TxPoolApiClient,
         web3::Web3ApiClient,
     };
 }
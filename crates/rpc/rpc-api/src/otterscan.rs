@@ -0,0 +1,103 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, H256, U64};
+use reth_rpc_types::{
+    BlockDetails, ContractCreator, InternalOperation, OtsBlockTransactions, TraceEntry,
+    TransactionsWithReceipts,
+};
+
+/// Otterscan rpc interface, the API the Otterscan block explorer is built on.
+#[cfg_attr(not(feature = "client"), rpc(server))]
+#[cfg_attr(feature = "client", rpc(server, client))]
+#[async_trait::async_trait]
+pub trait OtterscanApi {
+    /// Returns whether the address has code at the given block.
+    #[method(name = "ots_hasCode")]
+    async fn has_code(&self, address: Address, block_id: Option<BlockId>) -> RpcResult<bool>;
+
+    /// Returns the version of the Otterscan API the node implements.
+    #[method(name = "ots_getApiLevel")]
+    async fn get_api_level(&self) -> RpcResult<u64>;
+
+    /// Returns the ether transfers of a transaction that aren't visible in the transaction
+    /// itself: transfers by internal calls, self-destructs and contract creations.
+    #[method(name = "ots_getInternalOperations")]
+    async fn get_internal_operations(&self, tx_hash: H256) -> RpcResult<Vec<InternalOperation>>;
+
+    /// Returns the output of a failed transaction, like the revert reason.
+    #[method(name = "ots_getTransactionError")]
+    async fn get_transaction_error(&self, tx_hash: H256) -> RpcResult<Bytes>;
+
+    /// Returns the call tree of a transaction, in the order the calls were made.
+    #[method(name = "ots_traceTransaction")]
+    async fn trace_transaction(&self, tx_hash: H256) -> RpcResult<Vec<TraceEntry>>;
+
+    /// Returns the block with the given number without its transactions, with the ether it
+    /// issued and the fees its transactions paid.
+    #[method(name = "ots_getBlockDetails")]
+    async fn get_block_details(
+        &self,
+        block_number: BlockNumberOrTag,
+    ) -> RpcResult<Option<BlockDetails>>;
+
+    /// Returns the block with the given hash without its transactions, with the ether it issued
+    /// and the fees its transactions paid.
+    #[method(name = "ots_getBlockDetailsByHash")]
+    async fn get_block_details_by_hash(&self, block_hash: H256) -> RpcResult<Option<BlockDetails>>;
+
+    /// Returns a page of the transactions of a block, with their receipts.
+    #[method(name = "ots_getBlockTransactions")]
+    async fn get_block_transactions(
+        &self,
+        block_number: BlockNumberOrTag,
+        page_number: usize,
+        page_size: usize,
+    ) -> RpcResult<OtsBlockTransactions>;
+
+    /// Returns the transactions that involve the address in blocks before the given block, the
+    /// most recent first.
+    ///
+    /// The search starts at the latest block if the block number is `0`. The page contains at
+    /// least `page_size` transactions, unless there are fewer, but it always ends at a block
+    /// boundary.
+    ///
+    /// Only the transactions of blocks that changed the account or its storage or have logs
+    /// emitted by the address are found, so calls to the address without any of these effects,
+    /// like failed calls, are missing.
+    #[method(name = "ots_searchTransactionsBefore")]
+    async fn search_transactions_before(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> RpcResult<TransactionsWithReceipts>;
+
+    /// Returns the transactions that involve the address in blocks after the given block, the
+    /// most recent first.
+    ///
+    /// The search starts at the genesis block if the block number is `0`. The page contains at
+    /// least `page_size` transactions, unless there are fewer, but it always ends at a block
+    /// boundary.
+    ///
+    /// Only the transactions of blocks that changed the account or its storage or have logs
+    /// emitted by the address are found, so calls to the address without any of these effects,
+    /// like failed calls, are missing.
+    #[method(name = "ots_searchTransactionsAfter")]
+    async fn search_transactions_after(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> RpcResult<TransactionsWithReceipts>;
+
+    /// Returns the hash of the mined transaction of the sender with the given nonce.
+    #[method(name = "ots_getTransactionBySenderAndNonce")]
+    async fn get_transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: U64,
+    ) -> RpcResult<Option<H256>>;
+
+    /// Returns the transaction that created the contract and its sender.
+    #[method(name = "ots_getContractCreator")]
+    async fn get_contract_creator(&self, address: Address) -> RpcResult<Option<ContractCreator>>;
+}
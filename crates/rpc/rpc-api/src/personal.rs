@@ -0,0 +1,54 @@
+use jsonrpsee::{core::RpcResult as Result, proc_macros::rpc};
+use reth_primitives::{Address, Bytes, H256};
+use reth_rpc_types::TransactionRequest;
+
+/// Personal rpc interface, manages the accounts of the local signer.
+///
+/// Only meant for development chains, see
+/// [here](https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-personal) for more details.
+#[cfg_attr(not(feature = "client"), rpc(server))]
+#[cfg_attr(feature = "client", rpc(server, client))]
+#[async_trait::async_trait]
+pub trait PersonalApi {
+    /// Returns the addresses of all accounts of the local signer.
+    #[method(name = "personal_listAccounts")]
+    async fn list_accounts(&self) -> Result<Vec<Address>>;
+
+    /// Creates a new account protected by the password and returns its address.
+    #[method(name = "personal_newAccount")]
+    async fn new_account(&self, password: String) -> Result<Address>;
+
+    /// Imports the hex encoded, unencrypted secret key as an account protected by the password
+    /// and returns its address.
+    #[method(name = "personal_importRawKey")]
+    async fn import_raw_key(&self, key: String, password: String) -> Result<Address>;
+
+    /// Unlocks the account for `eth_sign` and `eth_sendTransaction` for the duration in seconds,
+    /// 300 seconds by default and indefinitely for 0.
+    #[method(name = "personal_unlockAccount")]
+    async fn unlock_account(
+        &self,
+        address: Address,
+        password: String,
+        duration: Option<u64>,
+    ) -> Result<bool>;
+
+    /// Locks the account.
+    #[method(name = "personal_lockAccount")]
+    async fn lock_account(&self, address: Address) -> Result<bool>;
+
+    /// Signs the transaction with the key of the `from` account, decrypted with the password, and
+    /// sends it, returning its hash.
+    #[method(name = "personal_sendTransaction")]
+    async fn send_transaction(&self, request: TransactionRequest, password: String)
+        -> Result<H256>;
+
+    /// Returns the signature of `keccak256("\x19Ethereum Signed Message:\n" + len(message) +
+    /// message)` by the account, decrypted with the password.
+    #[method(name = "personal_sign")]
+    async fn sign(&self, message: Bytes, address: Address, password: String) -> Result<Bytes>;
+
+    /// Returns the address of the account that created the signature with `personal_sign`.
+    #[method(name = "personal_ecRecover")]
+    async fn ec_recover(&self, message: Bytes, signature: Bytes) -> Result<Address>;
+}
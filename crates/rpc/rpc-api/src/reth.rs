@@ -0,0 +1,43 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_primitives::{Address, BlockId, BlockNumber, PruneSegment};
+use reth_rpc_types::{BalanceChange, PruneRun, PruneStatus, SyncEvent};
+use std::collections::BTreeMap;
+
+/// Reth namespace rpc interface that gives access to reth specific information about the node.
+#[cfg_attr(not(feature = "client"), rpc(server))]
+#[cfg_attr(feature = "client", rpc(server, client))]
+#[async_trait::async_trait]
+pub trait RethApi {
+    /// Returns the lowest available block of every prunable segment and the last prune run.
+    #[method(name = "reth_pruneStatus")]
+    async fn prune_status(&self) -> RpcResult<PruneStatus>;
+
+    /// Returns the balance changes of all accounts whose balance was changed by the block.
+    ///
+    /// The balances are looked up in the account changesets, without executing the block.
+    #[method(name = "reth_getBalanceChangesInBlock")]
+    async fn balance_changes_in_block(
+        &self,
+        block_id: BlockId,
+    ) -> RpcResult<BTreeMap<Address, BalanceChange>>;
+
+    /// Creates a subscription that streams the events of the sync pipeline and prune runs.
+    #[subscription(
+        name = "reth_subscribeSyncEvents" => "reth_syncEvents",
+        unsubscribe = "reth_unsubscribeSyncEvents",
+        item = SyncEvent
+    )]
+    fn subscribe_sync_events(&self);
+}
+
+/// Reth namespace rpc interface for methods that modify the node's data.
+///
+/// These are only exposed together with the `admin` namespace.
+#[cfg_attr(not(feature = "client"), rpc(server))]
+#[cfg_attr(feature = "client", rpc(server, client))]
+#[async_trait::async_trait]
+pub trait RethAdminApi {
+    /// Prunes all data of the given segment below `to_block`.
+    #[method(name = "reth_prune")]
+    async fn prune(&self, segment: PruneSegment, to_block: BlockNumber) -> RpcResult<PruneRun>;
+}
@@ -0,0 +1,155 @@
+use std::{fmt, net::IpAddr, str::FromStr};
+
+/// A range of IP addresses in CIDR notation, e.g. `10.0.0.0/8` or `fd00::/8`.
+///
+/// A plain address is treated as a network that only contains that address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+// === impl IpNetwork ===
+
+impl IpNetwork {
+    /// Creates a new network, returns `None` if the prefix length exceeds the address length.
+    pub fn new(addr: IpAddr, prefix: u8) -> Option<Self> {
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        (prefix <= max).then_some(Self { addr, prefix })
+    }
+
+    /// Returns true if the address is part of this network.
+    ///
+    /// IPv4-mapped IPv6 addresses, as reported for IPv4 peers of dual-stack sockets, are matched
+    /// against IPv4 networks.
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        match (self.addr, canonical(*addr)) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = IpNetworkParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || IpNetworkParseError(s.to_string());
+        match s.split_once('/') {
+            Some((addr, prefix)) => {
+                let addr = addr.parse::<IpAddr>().map_err(|_| err())?;
+                let prefix = prefix.parse::<u8>().map_err(|_| err())?;
+                Self::new(addr, prefix).ok_or_else(err)
+            }
+            None => {
+                let addr = s.parse::<IpAddr>().map_err(|_| err())?;
+                Ok(Self { addr, prefix: if addr.is_ipv4() { 32 } else { 128 } })
+            }
+        }
+    }
+}
+
+/// Error returned when parsing an invalid [IpNetwork].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid IP network: {0}, expected an address or CIDR range, e.g. 10.0.0.0/8")]
+pub struct IpNetworkParseError(String);
+
+/// A list of networks that are allowed to connect to a server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpAllowlist(Vec<IpNetwork>);
+
+// === impl IpAllowlist ===
+
+impl IpAllowlist {
+    /// Creates a new allowlist from the given networks.
+    pub fn new(networks: impl IntoIterator<Item = IpNetwork>) -> Self {
+        Self(networks.into_iter().collect())
+    }
+
+    /// Returns the allowed networks.
+    pub fn networks(&self) -> &[IpNetwork] {
+        &self.0
+    }
+
+    /// Returns true if the address is part of any allowed network.
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        self.0.iter().any(|network| network.contains(addr))
+    }
+}
+
+impl FromIterator<IpNetwork> for IpAllowlist {
+    fn from_iter<T: IntoIterator<Item = IpNetwork>>(iter: T) -> Self {
+        Self::new(iter)
+    }
+}
+
+/// Parses a comma separated list of networks, e.g. `127.0.0.1,10.0.0.0/8`.
+impl FromStr for IpAllowlist {
+    type Err = IpNetworkParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',').map(|network| network.trim().parse()).collect()
+    }
+}
+
+/// Converts IPv4-mapped IPv6 addresses to IPv4.
+fn canonical(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => IpAddr::V6(v6),
+        },
+        v4 => v4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    #[test]
+    fn parse_and_match_networks() {
+        let allowlist: IpAllowlist = "127.0.0.1, 10.0.0.0/8,fd00::/8".parse().unwrap();
+        assert_eq!(allowlist.networks().len(), 3);
+
+        assert!(allowlist.contains(&IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        assert!(allowlist.contains(&IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3))));
+        assert!(!allowlist.contains(&IpAddr::V4(Ipv4Addr::new(11, 0, 0, 1))));
+        assert!(!allowlist.contains(&IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2))));
+
+        assert!(allowlist.contains(&"fd12::1".parse().unwrap()));
+        assert!(!allowlist.contains(&IpAddr::V6(Ipv6Addr::LOCALHOST)));
+
+        // ipv4 peers of dual-stack sockets
+        assert!(allowlist.contains(&IpAddr::V6(Ipv4Addr::new(10, 0, 0, 1).to_ipv6_mapped())));
+    }
+
+    #[test]
+    fn match_all() {
+        let network: IpNetwork = "0.0.0.0/0".parse().unwrap();
+        assert!(network.contains(&IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))));
+        assert_eq!(network.to_string(), "0.0.0.0/0");
+    }
+
+    #[test]
+    fn invalid_networks() {
+        assert!("10.0.0.0/33".parse::<IpNetwork>().is_err());
+        assert!("::/129".parse::<IpNetwork>().is_err());
+        assert!("localhost".parse::<IpNetwork>().is_err());
+        assert!("127.0.0.1,".parse::<IpAllowlist>().is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
     constants,
     error::{RpcError, ServerKind},
+    proxy::{proxy_secret, ProxyValidator, RpcProxy},
+    Endpoint, IpAllowlist, IpcServerBuilder,
 };
 use hyper::header::AUTHORIZATION;
 pub use jsonrpsee::server::ServerBuilder;
@@ -8,19 +10,25 @@ use jsonrpsee::{
     http_client::HeaderMap,
     server::{RpcModule, ServerHandle},
 };
+use reth_ipc::server::SecurityAttributes;
 use reth_network_api::{NetworkInfo, Peers};
-use reth_provider::{BlockProvider, EvmEnvProvider, HeaderProvider, StateProviderFactory};
+use reth_provider::{
+    AccountHistoryProvider, BlockProvider, EvmEnvProvider, HeaderProvider, LogIndexProvider,
+    StageCheckpointProvider, StateProviderFactory,
+};
 use reth_rpc::{
     eth::cache::EthStateCache, AuthLayer, Claims, EngineEthApi, EthApi, EthFilter,
     JwtAuthValidator, JwtSecret,
 };
 use reth_rpc_api::{servers::*, EngineApiServer};
-use reth_tasks::TaskSpawner;
+use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::TransactionPool;
 use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
+    sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
+use tokio::sync::watch;
 
 /// Configure and launch a _standalone_ auth server with `engine` and a _new_ `eth` namespace.
 #[allow(clippy::too_many_arguments)]
@@ -36,6 +44,9 @@ pub async fn launch<Client, Pool, Network, Tasks, EngineApi>(
 where
     Client: BlockProvider
         + HeaderProvider
+        + StageCheckpointProvider
+        + LogIndexProvider
+        + AccountHistoryProvider
         + StateProviderFactory
         + EvmEnvProvider
         + Clone
@@ -47,9 +58,10 @@ where
     EngineApi: EngineApiServer,
 {
     // spawn a new cache task
-    let eth_cache = EthStateCache::spawn_with(client.clone(), Default::default(), executor);
+    let eth_cache = EthStateCache::spawn_with(client.clone(), Default::default(), executor.clone());
     let eth_api = EthApi::new(client.clone(), pool.clone(), network, eth_cache.clone());
     let eth_filter = EthFilter::new(client, pool, eth_cache.clone());
+    executor.spawn(Box::pin(eth_filter.clone().clear_stale_filters()));
     launch_with_eth_api(eth_api, eth_filter, engine_api, socket_addr, secret).await
 }
 
@@ -64,6 +76,9 @@ pub async fn launch_with_eth_api<Client, Pool, Network, EngineApi>(
 where
     Client: BlockProvider
         + HeaderProvider
+        + StageCheckpointProvider
+        + LogIndexProvider
+        + AccountHistoryProvider
         + StateProviderFactory
         + EvmEnvProvider
         + Clone
@@ -93,7 +108,13 @@ where
     let local_addr = server.local_addr()?;
 
     let handle = server.start(module)?;
-    Ok(AuthServerHandle { handle, local_addr, secret })
+    Ok(AuthServerHandle {
+        handle,
+        local_addr: Some(local_addr),
+        ipc_endpoint: None,
+        secret,
+        proxy_shutdown: None,
+    })
 }
 
 /// Server configuration for the auth server.
@@ -103,6 +124,12 @@ pub struct AuthServerConfig {
     pub(crate) socket_addr: SocketAddr,
     /// The secrete for the auth layer of the server.
     pub(crate) secret: JwtSecret,
+    /// Source addresses that are allowed to connect to the server.
+    pub(crate) allowlist: Option<IpAllowlist>,
+    /// The unix socket or named pipe to serve on instead of TCP.
+    pub(crate) ipc_endpoint: Option<String>,
+    /// The executor the allowlist proxy is spawned on.
+    pub(crate) executor: Option<Box<dyn TaskSpawner>>,
 }
 
 // === impl AuthServerConfig ===
@@ -115,30 +142,98 @@ impl AuthServerConfig {
 
     /// Convenience function to start a server in one step.
     pub async fn start(self, module: AuthRpcModule) -> Result<AuthServerHandle, RpcError> {
-        let Self { socket_addr, secret } = self;
+        let Self { socket_addr, secret, allowlist, ipc_endpoint, executor } = self;
+
+        // access to the socket is controlled by the filesystem permissions, so requests are not
+        // authenticated
+        if let Some(ipc_endpoint) = ipc_endpoint {
+            let mut endpoint = Endpoint::new(ipc_endpoint.clone());
+            endpoint.set_security_attributes(auth_ipc_security_attributes().map_err(|err| {
+                RpcError::Custom(format!("Failed to configure {ipc_endpoint}: {err}"))
+            })?);
+            let server = IpcServerBuilder::default().build_with_endpoint(endpoint)?;
+            let handle = server.start(module.inner).await?;
+            return Ok(AuthServerHandle {
+                handle,
+                local_addr: None,
+                ipc_endpoint: Some(ipc_endpoint),
+                secret,
+                proxy_shutdown: None,
+            })
+        }
+
+        // with an allowlist the server is only reachable via the proxy, which authenticates itself
+        // with this secret
+        let proxy_secret = proxy_secret();
+        let proxy_guard =
+            allowlist.is_some().then(|| AuthLayer::new(ProxyValidator::new(proxy_secret.clone())));
 
         // Create auth middleware.
         let middleware = tower::ServiceBuilder::new()
+            .option_layer(proxy_guard)
             .layer(AuthLayer::new(JwtAuthValidator::new(secret.clone())));
 
+        let bind_addr = if allowlist.is_some() {
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
+        } else {
+            socket_addr
+        };
+
         // By default, both http and ws are enabled.
         let server =
-            ServerBuilder::new().set_middleware(middleware).build(socket_addr).await.map_err(
+            ServerBuilder::new().set_middleware(middleware).build(bind_addr).await.map_err(
                 |err| RpcError::from_jsonrpsee_error(err, ServerKind::Auth(socket_addr)),
             )?;
 
-        let local_addr = server.local_addr()?;
+        let mut local_addr = server.local_addr()?;
+        let mut proxy_shutdown = None;
+        if allowlist.is_some() {
+            let executor = executor.unwrap_or_else(|| Box::<TokioTaskExecutor>::default());
+            let proxy =
+                RpcProxy::bind(socket_addr, None, local_addr, proxy_secret, allowlist, executor)
+                    .await
+                    .map_err(|err| RpcError::from_io_error(err, ServerKind::Auth(socket_addr)))?;
+            local_addr = proxy
+                .local_addr()
+                .map_err(|err| RpcError::from_io_error(err, ServerKind::Auth(socket_addr)))?;
+            let (shutdown_tx, shutdown_rx) = watch::channel(());
+            proxy.spawn(shutdown_rx);
+            proxy_shutdown = Some(Arc::new(shutdown_tx));
+        }
 
         let handle = server.start(module.inner)?;
-        Ok(AuthServerHandle { handle, local_addr, secret })
+        Ok(AuthServerHandle {
+            handle,
+            local_addr: Some(local_addr),
+            ipc_endpoint: None,
+            secret,
+            proxy_shutdown,
+        })
     }
 }
 
+/// The permissions of the auth server's unix socket.
+///
+/// Requests over the socket are not authenticated, so only the owner may connect to it.
+#[cfg(unix)]
+fn auth_ipc_security_attributes() -> std::io::Result<SecurityAttributes> {
+    SecurityAttributes::empty().set_mode(0o600)
+}
+
+/// The security attributes of the auth server's named pipe.
+#[cfg(not(unix))]
+fn auth_ipc_security_attributes() -> std::io::Result<SecurityAttributes> {
+    Ok(SecurityAttributes::empty())
+}
+
 /// Builder type for configuring an `AuthServerConfig`.
 #[derive(Clone, Debug)]
 pub struct AuthServerConfigBuilder {
     socket_addr: Option<SocketAddr>,
     secret: JwtSecret,
+    allowlist: Option<IpAllowlist>,
+    ipc_endpoint: Option<String>,
+    executor: Option<Box<dyn TaskSpawner>>,
 }
 
 // === impl AuthServerConfigBuilder ===
@@ -146,7 +241,7 @@ pub struct AuthServerConfigBuilder {
 impl AuthServerConfigBuilder {
     /// Create a new `AuthServerConfigBuilder` with the given `secret`.
     pub fn new(secret: JwtSecret) -> Self {
-        Self { socket_addr: None, secret }
+        Self { socket_addr: None, secret, allowlist: None, ipc_endpoint: None, executor: None }
     }
 
     /// Set the socket address for the server.
@@ -166,6 +261,31 @@ impl AuthServerConfigBuilder {
         self.secret = secret;
         self
     }
+
+    /// Restricts the server to connections from the given networks, in addition to the JWT
+    /// authentication.
+    pub fn allowlist(mut self, allowlist: IpAllowlist) -> Self {
+        self.allowlist = Some(allowlist);
+        self
+    }
+
+    /// Serves the API on the given unix socket or named pipe instead of TCP.
+    ///
+    /// Since access is restricted via the permissions of the socket, the JWT authentication is not
+    /// required for these connections.
+    pub fn ipc_endpoint(mut self, path: impl Into<String>) -> Self {
+        self.ipc_endpoint = Some(path.into());
+        self
+    }
+
+    /// Set the executor the allowlist proxy is spawned on.
+    ///
+    /// Defaults to [TokioTaskExecutor].
+    pub fn executor(mut self, executor: Box<dyn TaskSpawner>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
     /// Build the `AuthServerConfig`.
     pub fn build(self) -> AuthServerConfig {
         AuthServerConfig {
@@ -173,6 +293,9 @@ impl AuthServerConfigBuilder {
                 SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), constants::DEFAULT_AUTH_PORT)
             }),
             secret: self.secret,
+            allowlist: self.allowlist,
+            ipc_endpoint: self.ipc_endpoint,
+            executor: self.executor,
         }
     }
 }
@@ -217,32 +340,43 @@ impl AuthRpcModule {
 #[derive(Clone, Debug)]
 #[must_use = "Server stops if dropped"]
 pub struct AuthServerHandle {
-    local_addr: SocketAddr,
+    local_addr: Option<SocketAddr>,
+    ipc_endpoint: Option<String>,
     handle: ServerHandle,
     secret: JwtSecret,
+    /// Stops the allowlist proxy when fired or dropped
+    proxy_shutdown: Option<Arc<watch::Sender<()>>>,
 }
 
 // === impl AuthServerHandle ===
 
 impl AuthServerHandle {
     /// Returns the [`SocketAddr`] of the http server if started.
-    pub fn local_addr(&self) -> SocketAddr {
+    pub fn local_addr(&self) -> Option<SocketAddr> {
         self.local_addr
     }
 
+    /// Returns the path of the ipc endpoint if the server serves on a unix socket or named pipe.
+    pub fn ipc_endpoint(&self) -> Option<&str> {
+        self.ipc_endpoint.as_deref()
+    }
+
     /// Tell the server to stop without waiting for the server to stop.
     pub fn stop(self) -> Result<(), RpcError> {
+        if let Some(proxy_shutdown) = self.proxy_shutdown {
+            let _ = proxy_shutdown.send(());
+        }
         Ok(self.handle.stop()?)
     }
 
     /// Returns the url to the http server
-    pub fn http_url(&self) -> String {
-        format!("http://{}", self.local_addr)
+    pub fn http_url(&self) -> Option<String> {
+        self.local_addr.map(|addr| format!("http://{addr}"))
     }
 
     /// Returns the url to the ws server
-    pub fn ws_url(&self) -> String {
-        format!("ws://{}", self.local_addr)
+    pub fn ws_url(&self) -> Option<String> {
+        self.local_addr.map(|addr| format!("ws://{addr}"))
     }
 
     fn bearer(&self) -> String {
@@ -260,19 +394,23 @@ impl AuthServerHandle {
     }
 
     /// Returns a http client connected to the server.
-    pub fn http_client(&self) -> jsonrpsee::http_client::HttpClient {
-        jsonrpsee::http_client::HttpClientBuilder::default()
+    pub fn http_client(&self) -> Option<jsonrpsee::http_client::HttpClient> {
+        let url = self.http_url()?;
+        let client = jsonrpsee::http_client::HttpClientBuilder::default()
             .set_headers(HeaderMap::from_iter([(AUTHORIZATION, self.bearer().parse().unwrap())]))
-            .build(self.http_url())
-            .expect("Failed to create http client")
+            .build(url)
+            .expect("Failed to create http client");
+        Some(client)
     }
 
     /// Returns a ws client connected to the server.
-    pub async fn ws_client(&self) -> jsonrpsee::ws_client::WsClient {
-        jsonrpsee::ws_client::WsClientBuilder::default()
+    pub async fn ws_client(&self) -> Option<jsonrpsee::ws_client::WsClient> {
+        let url = self.ws_url()?;
+        let client = jsonrpsee::ws_client::WsClientBuilder::default()
             .set_headers(HeaderMap::from_iter([(AUTHORIZATION, self.bearer().parse().unwrap())]))
-            .build(self.ws_url())
+            .build(url)
             .await
-            .expect("Failed to create ws client")
+            .expect("Failed to create ws client");
+        Some(client)
     }
 }
@@ -38,6 +38,9 @@ pub enum RpcError {
         /// IO error.
         error: io::Error,
     },
+    /// Failed to configure TLS.
+    #[error("Failed to configure TLS: {0}")]
+    Tls(String),
     /// Custom error.
     #[error("{0}")]
     Custom(String),
@@ -61,4 +64,13 @@ impl RpcError {
             _ => err.into(),
         }
     }
+
+    /// Converts an [io::Error] that occurred while binding a server to a more descriptive
+    /// `RpcError`.
+    pub fn from_io_error(err: io::Error, kind: ServerKind) -> RpcError {
+        if err.kind() == ErrorKind::AddrInUse {
+            return RpcError::AddressAlreadyInUse { kind, error: err }
+        }
+        RpcError::Custom(format!("Failed to bind {kind}: {err}"))
+    }
 }
@@ -1,6 +1,11 @@
 use reth_rpc::{
-    eth::cache::{EthStateCache, EthStateCacheConfig},
-    EthApi, EthFilter, EthPubSub,
+    eth::{
+        bloom_cache::BloomCacheConfig,
+        cache::{EthStateCache, EthStateCacheConfig},
+        gas_oracle::GasPriceOracleConfig,
+        EthFilterConfig,
+    },
+    EthApi, EthFilter, EthPubSub, TraceCacheConfig,
 };
 use serde::{Deserialize, Serialize};
 
@@ -23,12 +28,35 @@ pub struct EthConfig {
     /// Settings for the caching layer
     pub cache: EthStateCacheConfig,
 
+    /// Settings for the logs bloom cache used by `eth_getLogs`
+    #[serde(default)]
+    pub bloom_cache: BloomCacheConfig,
+
+    /// Limits for log queries
+    #[serde(default)]
+    pub filter: EthFilterConfig,
+
     /// The maximum number of tracing calls that can be executed in concurrently.
     pub max_tracing_requests: usize,
+
+    /// Settings for the cache of `debug_` and `trace_` results
+    #[serde(default)]
+    pub trace_cache: TraceCacheConfig,
+
+    /// Settings for the gas price oracle behind `eth_gasPrice`
+    #[serde(default)]
+    pub gas_oracle: GasPriceOracleConfig,
 }
 
 impl Default for EthConfig {
     fn default() -> Self {
-        Self { cache: EthStateCacheConfig::default(), max_tracing_requests: 10 }
+        Self {
+            cache: EthStateCacheConfig::default(),
+            bloom_cache: BloomCacheConfig::default(),
+            filter: EthFilterConfig::default(),
+            max_tracing_requests: 10,
+            trace_cache: TraceCacheConfig::default(),
+            gas_oracle: GasPriceOracleConfig::default(),
+        }
     }
 }
@@ -25,13 +25,13 @@
 //!
 //! ```
 //! use reth_network_api::{NetworkInfo, Peers};
-//! use reth_provider::{BlockProvider, CanonStateSubscriptions, StateProviderFactory, EvmEnvProvider};
+//! use reth_provider::{AccountHistoryProvider, BlockProvider, CanonStateSubscriptions, LogIndexProvider, PruneProvider, StageCheckpointProvider, StateProviderFactory, EvmEnvProvider};
 //! use reth_rpc_builder::{RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig};
 //! use reth_tasks::TokioTaskExecutor;
 //! use reth_transaction_pool::TransactionPool;
 //! pub async fn launch<Client, Pool, Network, Events>(client: Client, pool: Pool, network: Network, events: Events)
 //! where
-//!     Client: BlockProvider + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+//!     Client: BlockProvider + StageCheckpointProvider + LogIndexProvider + AccountHistoryProvider + PruneProvider + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
 //!     Pool: TransactionPool + Clone + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions +  Clone + 'static,
@@ -58,7 +58,7 @@
 //! ```
 //! use tokio::try_join;
 //! use reth_network_api::{NetworkInfo, Peers};
-//! use reth_provider::{BlockProvider, CanonStateSubscriptions, StateProviderFactory, EvmEnvProvider};
+//! use reth_provider::{AccountHistoryProvider, BlockProvider, CanonStateSubscriptions, LogIndexProvider, PruneProvider, StageCheckpointProvider, StateProviderFactory, EvmEnvProvider};
 //! use reth_rpc::JwtSecret;
 //! use reth_rpc_builder::{RethRpcModule, RpcModuleBuilder, RpcServerConfig, TransportRpcModuleConfig};
 //! use reth_tasks::TokioTaskExecutor;
@@ -67,7 +67,7 @@
 //! use reth_rpc_builder::auth::AuthServerConfig;
 //! pub async fn launch<Client, Pool, Network, Events, EngineApi>(client: Client, pool: Pool, network: Network, events: Events, engine_api: EngineApi)
 //! where
-//!     Client: BlockProvider + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+//!     Client: BlockProvider + StageCheckpointProvider + LogIndexProvider + AccountHistoryProvider + PruneProvider + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
 //!     Pool: TransactionPool + Clone + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions +  Clone + 'static,
@@ -104,15 +104,22 @@ use jsonrpsee::{
     server::{IdProvider, Server, ServerHandle},
     RpcModule,
 };
+use proxy::{proxy_secret, ProxyValidator, RpcProxy};
 use reth_ipc::server::IpcServer;
 use reth_network_api::{NetworkInfo, Peers};
-use reth_provider::{BlockProvider, CanonStateSubscriptions, EvmEnvProvider, StateProviderFactory};
+use reth_provider::{
+    AccountHistoryProvider, BlockProvider, CanonStateSubscriptions, EvmEnvProvider,
+    LogIndexProvider, PruneProvider, StageCheckpointProvider, StateProviderFactory,
+};
 use reth_rpc::{
-    eth::cache::EthStateCache, AdminApi, DebugApi, EngineEthApi, EthApi, EthFilter, EthPubSub,
-    EthSubscriptionIdProvider, NetApi, TraceApi, TracingCallGuard, Web3Api,
+    eth::{bloom_cache::BloomCache, cache::EthStateCache, DevSigner, PersonalSigner},
+    AdminApi, AuthLayer, BlockingTaskPool, BlockingTaskPoolConfig, DebugApi, EngineEthApi, EthApi,
+    EthFilter, EthPubSub, EthSubscriptionIdProvider, NetApi, OtterscanApi, PersonalApi, RethApi,
+    TraceApi, TraceCache, TracingCallGuard, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::{servers::*, EngineApiServer};
-use reth_tasks::TaskSpawner;
+use reth_rpc_types::SyncEvent;
+use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::TransactionPool;
 use serde::{Deserialize, Serialize, Serializer};
 use std::{
@@ -120,15 +127,24 @@ use std::{
     fmt,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     str::FromStr,
+    sync::Arc,
 };
 use strum::{AsRefStr, EnumString, EnumVariantNames, ParseError, VariantNames};
-use tower::layer::util::{Identity, Stack};
+use tokio::sync::{broadcast, watch};
+use tower::{
+    layer::util::{Identity, Stack},
+    util::Either,
+};
 use tower_http::cors::CorsLayer;
 use tracing::{instrument, trace};
 
 /// Auth server utilities.
 pub mod auth;
 
+/// Source IP allowlists for the servers.
+mod allowlist;
+pub use allowlist::{IpAllowlist, IpNetwork, IpNetworkParseError};
+
 /// Cors utilities.
 mod cors;
 
@@ -141,6 +157,13 @@ mod eth;
 /// Common RPC constants.
 pub mod constants;
 
+/// Proxy in front of the servers that terminates TLS and forwards client addresses.
+mod proxy;
+
+/// TLS termination for the http and ws servers.
+mod tls;
+pub use tls::RpcTlsConfig;
+
 // re-export for convenience
 use crate::auth::AuthRpcModule;
 pub use crate::eth::{EthConfig, EthHandlers};
@@ -158,14 +181,23 @@ pub async fn launch<Client, Pool, Network, Tasks, Events>(
     events: Events,
 ) -> Result<RpcServerHandle, RpcError>
 where
-    Client: BlockProvider + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+    Client: BlockProvider
+        + StageCheckpointProvider
+        + LogIndexProvider
+        + AccountHistoryProvider
+        + PruneProvider
+        + StateProviderFactory
+        + EvmEnvProvider
+        + Clone
+        + Unpin
+        + 'static,
     Pool: TransactionPool + Clone + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
     Events: CanonStateSubscriptions + Clone + 'static,
 {
     let module_config = module_config.into();
-    let server_config = server_config.into();
+    let server_config = server_config.into().with_executor(Box::new(executor.clone()));
     RpcModuleBuilder::new(client, pool, network, executor, events)
         .build(module_config)
         .start_server(server_config)
@@ -187,6 +219,10 @@ pub struct RpcModuleBuilder<Client, Pool, Network, Tasks, Events> {
     executor: Tasks,
     /// Provides access to chain events, such as new blocks, required by pubsub.
     events: Events,
+    /// The channel the sync events streamed by `reth_subscribeSyncEvents` are sent on.
+    sync_events: Option<broadcast::Sender<SyncEvent>>,
+    /// The signers of the accounts of `eth_accounts`, `eth_sign` and `eth_sendTransaction`.
+    signers: Vec<DevSigner>,
 }
 
 // === impl RpcBuilder ===
@@ -200,16 +236,23 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
         executor: Tasks,
         events: Events,
     ) -> Self {
-        Self { client, pool, network, executor, events }
+        Self { client, pool, network, executor, events, sync_events: None, signers: Vec::new() }
     }
 
     /// Configure the client instance.
     pub fn with_client<C>(self, client: C) -> RpcModuleBuilder<C, Pool, Network, Tasks, Events>
     where
-        C: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
+        C: BlockProvider
+            + StageCheckpointProvider
+            + LogIndexProvider
+            + AccountHistoryProvider
+            + PruneProvider
+            + StateProviderFactory
+            + EvmEnvProvider
+            + 'static,
     {
-        let Self { pool, network, executor, events, .. } = self;
-        RpcModuleBuilder { client, network, pool, executor, events }
+        let Self { pool, network, executor, events, sync_events, signers, .. } = self;
+        RpcModuleBuilder { client, network, pool, executor, events, sync_events, signers }
     }
 
     /// Configure the transaction pool instance.
@@ -217,8 +260,8 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
     where
         P: TransactionPool + 'static,
     {
-        let Self { client, network, executor, events, .. } = self;
-        RpcModuleBuilder { client, network, pool, executor, events }
+        let Self { client, network, executor, events, sync_events, signers, .. } = self;
+        RpcModuleBuilder { client, network, pool, executor, events, sync_events, signers }
     }
 
     /// Configure the network instance.
@@ -226,8 +269,8 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
     where
         N: NetworkInfo + Peers + 'static,
     {
-        let Self { client, pool, executor, events, .. } = self;
-        RpcModuleBuilder { client, network, pool, executor, events }
+        let Self { client, pool, executor, events, sync_events, signers, .. } = self;
+        RpcModuleBuilder { client, network, pool, executor, events, sync_events, signers }
     }
 
     /// Configure the task executor to use for additional tasks.
@@ -235,8 +278,8 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
     where
         T: TaskSpawner + 'static,
     {
-        let Self { pool, network, client, events, .. } = self;
-        RpcModuleBuilder { client, network, pool, executor, events }
+        let Self { pool, network, client, events, sync_events, signers, .. } = self;
+        RpcModuleBuilder { client, network, pool, executor, events, sync_events, signers }
     }
 
     /// Configure the event subscriber instance
@@ -244,14 +287,40 @@ impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Networ
     where
         E: CanonStateSubscriptions + 'static,
     {
-        let Self { client, pool, executor, network, .. } = self;
-        RpcModuleBuilder { client, network, pool, executor, events }
+        let Self { client, pool, executor, network, sync_events, signers, .. } = self;
+        RpcModuleBuilder { client, network, pool, executor, events, sync_events, signers }
+    }
+
+    /// Configure the channel the events of the sync pipeline are sent on.
+    ///
+    /// These are streamed to `reth_subscribeSyncEvents` subscribers.
+    pub fn with_sync_events(mut self, sync_events: broadcast::Sender<SyncEvent>) -> Self {
+        self.sync_events = Some(sync_events);
+        self
+    }
+
+    /// Adds a signer whose accounts are returned by `eth_accounts` and can be used with `eth_sign`
+    /// and `eth_sendTransaction`.
+    ///
+    /// Without signers, the `eth_` namespace has no accounts.
+    pub fn with_signer(mut self, signer: DevSigner) -> Self {
+        self.signers.push(signer);
+        self
     }
 }
 
 impl<Client, Pool, Network, Tasks, Events> RpcModuleBuilder<Client, Pool, Network, Tasks, Events>
 where
-    Client: BlockProvider + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+    Client: BlockProvider
+        + StageCheckpointProvider
+        + LogIndexProvider
+        + AccountHistoryProvider
+        + PruneProvider
+        + StateProviderFactory
+        + EvmEnvProvider
+        + Clone
+        + Unpin
+        + 'static,
     Pool: TransactionPool + Clone + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
@@ -271,7 +340,7 @@ where
     {
         let mut modules = TransportRpcModules::default();
 
-        let Self { client, pool, network, executor, events } = self;
+        let Self { client, pool, network, executor, events, sync_events, signers } = self;
 
         let TransportRpcModuleConfig { http, ws, ipc, config } = module_config;
 
@@ -283,6 +352,8 @@ where
             events,
             config.unwrap_or_default(),
         );
+        registry.sync_events = sync_events;
+        registry.signers = signers;
 
         modules.http = registry.maybe_module(http.as_ref());
         modules.ws = registry.maybe_module(ws.as_ref());
@@ -300,7 +371,7 @@ where
     pub fn build(self, module_config: TransportRpcModuleConfig) -> TransportRpcModules<()> {
         let mut modules = TransportRpcModules::default();
 
-        let Self { client, pool, network, executor, events } = self;
+        let Self { client, pool, network, executor, events, sync_events, signers } = self;
 
         if !module_config.is_empty() {
             let TransportRpcModuleConfig { http, ws, ipc, config } = module_config;
@@ -313,6 +384,8 @@ where
                 events,
                 config.unwrap_or_default(),
             );
+            registry.sync_events = sync_events;
+            registry.signers = signers;
 
             modules.http = registry.maybe_module(http.as_ref());
             modules.ws = registry.maybe_module(ws.as_ref());
@@ -334,6 +407,9 @@ impl Default for RpcModuleBuilder<(), (), (), (), ()> {
 pub struct RpcModuleConfig {
     /// `eth` namespace settings
     eth: EthConfig,
+    /// Settings for the pool expensive calls are executed on
+    #[serde(default)]
+    blocking_pool: BlockingTaskPoolConfig,
 }
 
 // === impl RpcModuleConfig ===
@@ -343,12 +419,23 @@ impl RpcModuleConfig {
     pub fn builder() -> RpcModuleConfigBuilder {
         RpcModuleConfigBuilder::default()
     }
+
+    /// Returns the `eth` namespace settings
+    pub fn eth(&self) -> &EthConfig {
+        &self.eth
+    }
+
+    /// Returns the settings for the [BlockingTaskPool]
+    pub fn blocking_pool(&self) -> &BlockingTaskPoolConfig {
+        &self.blocking_pool
+    }
 }
 
 /// Configures [RpcModuleConfig]
 #[derive(Default)]
 pub struct RpcModuleConfigBuilder {
     eth: Option<EthConfig>,
+    blocking_pool: Option<BlockingTaskPoolConfig>,
 }
 
 // === impl RpcModuleConfigBuilder ===
@@ -360,10 +447,19 @@ impl RpcModuleConfigBuilder {
         self
     }
 
+    /// Configures the pool expensive calls are executed on
+    pub fn blocking_pool(mut self, blocking_pool: BlockingTaskPoolConfig) -> Self {
+        self.blocking_pool = Some(blocking_pool);
+        self
+    }
+
     /// Consumes the type and creates the [RpcModuleConfig]
     pub fn build(self) -> RpcModuleConfig {
-        let RpcModuleConfigBuilder { eth } = self;
-        RpcModuleConfig { eth: eth.unwrap_or_default() }
+        let RpcModuleConfigBuilder { eth, blocking_pool } = self;
+        RpcModuleConfig {
+            eth: eth.unwrap_or_default(),
+            blocking_pool: blocking_pool.unwrap_or_default(),
+        }
     }
 }
 
@@ -396,10 +492,15 @@ impl RpcModuleSelection {
         [RethRpcModule::Eth, RethRpcModule::Net, RethRpcModule::Web3];
 
     /// Returns a selection of [RethRpcModule] with all [RethRpcModule::VARIANTS].
+    ///
+    /// This excludes [RethRpcModule::Personal] which must be selected explicitly.
     pub fn all_modules() -> Vec<RethRpcModule> {
         RpcModuleSelection::try_from_selection(RethRpcModule::VARIANTS.iter().copied())
             .expect("valid selection")
             .into_selection()
+            .into_iter()
+            .filter(|module| *module != RethRpcModule::Personal)
+            .collect()
     }
 
     /// Creates a new [RpcModuleSelection::Selection] from the given items.
@@ -439,7 +540,16 @@ impl RpcModuleSelection {
         config: RpcModuleConfig,
     ) -> RpcModule<()>
     where
-        Client: BlockProvider + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+        Client: BlockProvider
+            + StageCheckpointProvider
+            + LogIndexProvider
+            + AccountHistoryProvider
+            + PruneProvider
+            + StateProviderFactory
+            + EvmEnvProvider
+            + Clone
+            + Unpin
+            + 'static,
         Pool: TransactionPool + Clone + 'static,
         Network: NetworkInfo + Peers + Clone + 'static,
         Tasks: TaskSpawner + Clone + 'static,
@@ -503,8 +613,16 @@ pub enum RethRpcModule {
     Eth,
     /// `net_` module
     Net,
+    /// `ots_` module
+    Ots,
+    /// `personal_` module, only meant for development chains
+    Personal,
+    /// `reth_` module
+    Reth,
     /// `trace_` module
     Trace,
+    /// `txpool_` module
+    Txpool,
     /// `web3_` module
     Web3,
 }
@@ -535,8 +653,21 @@ pub struct RethModuleRegistry<Client, Pool, Network, Tasks, Events> {
     config: RpcModuleConfig,
     /// Holds a clone of all the eth namespace handlers
     eth: Option<EthHandlers<Client, Pool, Network, Events>>,
+    /// Holds a clone of the reth namespace handler, shared with the admin namespace
+    reth: Option<RethApi<Client>>,
+    /// The channel the sync events streamed by the reth namespace are sent on
+    sync_events: Option<broadcast::Sender<SyncEvent>>,
+    /// The signers of the accounts of the eth namespace
+    signers: Vec<DevSigner>,
+    /// The signer of the accounts of the personal namespace, shared with the eth namespace once
+    /// the personal namespace is registered
+    personal_signer: Option<PersonalSigner>,
     /// to put trace calls behind semaphore
     tracing_call_guard: TracingCallGuard,
+    /// Holds the cache shared by the debug and trace namespace handlers
+    trace_cache: Option<TraceCache>,
+    /// The pool expensive calls are executed on
+    blocking_pool: Option<BlockingTaskPool>,
     /// Contains the [Methods] of a module
     modules: HashMap<RethRpcModule, Methods>,
 }
@@ -560,9 +691,15 @@ impl<Client, Pool, Network, Tasks, Events>
             pool,
             network,
             eth: None,
+            reth: None,
+            sync_events: None,
+            signers: Vec::new(),
+            personal_signer: None,
             executor,
             modules: Default::default(),
             tracing_call_guard: TracingCallGuard::new(config.eth.max_tracing_requests),
+            trace_cache: None,
+            blocking_pool: None,
             config,
             events,
         }
@@ -587,13 +724,6 @@ impl<Client, Pool, Network, Tasks, Events> RethModuleRegistry<Client, Pool, Netw
 where
     Network: NetworkInfo + Peers + Clone + 'static,
 {
-    /// Register Admin Namespace
-    pub fn register_admin(&mut self) -> &mut Self {
-        self.modules
-            .insert(RethRpcModule::Admin, AdminApi::new(self.network.clone()).into_rpc().into());
-        self
-    }
-
     /// Register Web3 Namespace
     pub fn register_web3(&mut self) -> &mut Self {
         self.modules
@@ -604,12 +734,38 @@ where
 
 impl<Client, Pool, Network, Tasks, Events> RethModuleRegistry<Client, Pool, Network, Tasks, Events>
 where
-    Client: BlockProvider + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+    Client: BlockProvider
+        + StageCheckpointProvider
+        + LogIndexProvider
+        + AccountHistoryProvider
+        + PruneProvider
+        + StateProviderFactory
+        + EvmEnvProvider
+        + Clone
+        + Unpin
+        + 'static,
     Pool: TransactionPool + Clone + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
     Events: CanonStateSubscriptions + Clone + 'static,
 {
+    /// Register Admin Namespace
+    ///
+    /// This also includes the `reth_` methods that modify the node's data.
+    pub fn register_admin(&mut self) -> &mut Self {
+        let mut module = AdminApi::new(self.network.clone()).into_rpc();
+        module.merge(RethAdminApiServer::into_rpc(self.reth_api())).expect("No conflicts");
+        self.modules.insert(RethRpcModule::Admin, module.into());
+        self
+    }
+
+    /// Register Reth Namespace
+    pub fn register_reth(&mut self) -> &mut Self {
+        let reth_api = self.reth_api();
+        self.modules.insert(RethRpcModule::Reth, RethApiServer::into_rpc(reth_api).into());
+        self
+    }
+
     /// Register Eth Namespace
     pub fn register_eth(&mut self) -> &mut Self {
         let eth_api = self.eth_api();
@@ -620,9 +776,15 @@ where
     /// Register Debug Namespace
     pub fn register_debug(&mut self) -> &mut Self {
         let eth_api = self.eth_api();
+        let eth_cache = self.eth_cache();
+        let trace_cache = self.trace_cache();
+        let blocking_pool = self.blocking_pool();
         self.modules.insert(
             RethRpcModule::Debug,
-            DebugApi::new(self.client.clone(), eth_api, self.tracing_call_guard.clone())
+            DebugApi::new(self.client.clone(), eth_api, eth_cache, self.tracing_call_guard.clone())
+                .with_trace_cache(trace_cache)
+                .with_blocking_pool(blocking_pool)
+                .with_subscription_task_spawner(Box::new(self.executor.clone()))
                 .into_rpc()
                 .into(),
         );
@@ -660,6 +822,46 @@ where
         self
     }
 
+    /// Register Otterscan Namespace
+    pub fn register_ots(&mut self) -> &mut Self {
+        let eth_api = self.eth_api();
+        let eth_cache = self.eth_cache();
+        let trace_cache = self.trace_cache();
+        let blocking_pool = self.blocking_pool();
+        let trace_api = TraceApi::new(
+            self.client.clone(),
+            eth_api.clone(),
+            eth_cache,
+            self.tracing_call_guard.clone(),
+        )
+        .with_trace_cache(trace_cache)
+        .with_blocking_pool(blocking_pool);
+        self.modules.insert(
+            RethRpcModule::Ots,
+            OtterscanApi::new(self.client.clone(), eth_api, trace_api).into_rpc().into(),
+        );
+        self
+    }
+
+    /// Register Personal Namespace
+    ///
+    /// The namespace manages accounts with keys held in memory, it should only be exposed on
+    /// development chains.
+    pub fn register_personal(&mut self) -> &mut Self {
+        let eth_api = self.eth_api();
+        let signer = self.personal_signer();
+        self.modules
+            .insert(RethRpcModule::Personal, PersonalApi::new(eth_api, signer).into_rpc().into());
+        self
+    }
+
+    /// Register TxPool Namespace
+    pub fn register_txpool(&mut self) -> &mut Self {
+        self.modules
+            .insert(RethRpcModule::Txpool, TxPoolApi::new(self.pool.clone()).into_rpc().into());
+        self
+    }
+
     /// Helper function to create a [RpcModule] if it's not `None`
     fn maybe_module(&mut self, config: Option<&RpcModuleSelection>) -> Option<RpcModule<()>> {
         let config = config?;
@@ -688,19 +890,36 @@ where
     ) -> Vec<Methods> {
         let EthHandlers { api: eth_api, cache: eth_cache, filter: eth_filter, pubsub: eth_pubsub } =
             self.with_eth(|eth| eth.clone());
+        let reth_api = self.reth_api();
+        let trace_cache = self.trace_cache();
+        let blocking_pool = self.blocking_pool();
+        let namespaces = namespaces.collect::<Vec<_>>();
+        // only hand the personal signer to the eth namespace if the personal namespace is selected
+        let personal_signer =
+            namespaces.contains(&RethRpcModule::Personal).then(|| self.personal_signer());
         namespaces
+            .into_iter()
             .map(|namespace| {
                 self.modules
                     .entry(namespace)
                     .or_insert_with(|| match namespace {
                         RethRpcModule::Admin => {
-                            AdminApi::new(self.network.clone()).into_rpc().into()
+                            // merge the `reth_` methods that modify the node's data
+                            let mut module = AdminApi::new(self.network.clone()).into_rpc();
+                            module
+                                .merge(RethAdminApiServer::into_rpc(reth_api.clone()))
+                                .expect("No conflicts");
+                            module.into()
                         }
                         RethRpcModule::Debug => DebugApi::new(
                             self.client.clone(),
                             eth_api.clone(),
+                            eth_cache.clone(),
                             self.tracing_call_guard.clone(),
                         )
+                        .with_trace_cache(trace_cache.clone())
+                        .with_blocking_pool(blocking_pool.clone())
+                        .with_subscription_task_spawner(Box::new(self.executor.clone()))
                         .into_rpc()
                         .into(),
                         RethRpcModule::Eth => {
@@ -714,14 +933,40 @@ where
                         RethRpcModule::Net => {
                             NetApi::new(self.network.clone(), eth_api.clone()).into_rpc().into()
                         }
+                        RethRpcModule::Ots => OtterscanApi::new(
+                            self.client.clone(),
+                            eth_api.clone(),
+                            TraceApi::new(
+                                self.client.clone(),
+                                eth_api.clone(),
+                                eth_cache.clone(),
+                                self.tracing_call_guard.clone(),
+                            )
+                            .with_trace_cache(trace_cache.clone())
+                            .with_blocking_pool(blocking_pool.clone()),
+                        )
+                        .into_rpc()
+                        .into(),
                         RethRpcModule::Trace => TraceApi::new(
                             self.client.clone(),
                             eth_api.clone(),
                             eth_cache.clone(),
                             self.tracing_call_guard.clone(),
                         )
+                        .with_trace_cache(trace_cache.clone())
+                        .with_blocking_pool(blocking_pool.clone())
+                        .into_rpc()
+                        .into(),
+                        RethRpcModule::Personal => PersonalApi::new(
+                            eth_api.clone(),
+                            personal_signer.clone().expect("personal namespace is selected"),
+                        )
                         .into_rpc()
                         .into(),
+                        RethRpcModule::Reth => RethApiServer::into_rpc(reth_api.clone()).into(),
+                        RethRpcModule::Txpool => {
+                            TxPoolApi::new(self.pool.clone()).into_rpc().into()
+                        }
                         RethRpcModule::Web3 => Web3Api::new(self.network.clone()).into_rpc().into(),
                     })
                     .clone()
@@ -748,21 +993,50 @@ where
                 self.config.eth.cache.clone(),
                 self.executor.clone(),
             );
-            let api = EthApi::new(
+            self.executor.spawn(Box::pin(
+                cache.clone().update_on_canonical_state(self.events.subscribe_to_canonical_state()),
+            ));
+            let api = EthApi::with_gas_oracle_config(
                 self.client.clone(),
                 self.pool.clone(),
                 self.network.clone(),
                 cache.clone(),
-            );
-            let filter = EthFilter::new(self.client.clone(), self.pool.clone(), cache.clone());
+                self.config.eth.gas_oracle,
+            )
+            .with_blocking_pool(self.blocking_pool());
+            for signer in &self.signers {
+                api.add_signer(signer.clone());
+            }
+            if let Some(signer) = &self.personal_signer {
+                api.add_personal_signer(signer.clone());
+            }
+            let bloom_cache = BloomCache::new(self.config.eth.bloom_cache.clone());
+            self.executor.spawn(Box::pin(
+                bloom_cache
+                    .clone()
+                    .update_on_canonical_state(self.events.subscribe_to_canonical_state()),
+            ));
+            let filter = EthFilter::with_config(
+                self.client.clone(),
+                self.pool.clone(),
+                cache.clone(),
+                self.config.eth.filter,
+                Some(bloom_cache),
+            )
+            .with_blocking_pool(self.blocking_pool());
+            self.executor.spawn(Box::pin(filter.clone().clear_stale_filters()));
 
-            let pubsub = EthPubSub::new(
+            let mut pubsub = EthPubSub::with_spawner(
                 self.client.clone(),
                 self.pool.clone(),
                 self.events.clone(),
                 self.network.clone(),
                 cache.clone(),
+                Box::new(self.executor.clone()),
             );
+            if let Some(sync_events) = &self.sync_events {
+                pubsub = pubsub.with_sync_events(sync_events.clone());
+            }
 
             let eth = EthHandlers { api, cache, filter, pubsub };
             self.eth = Some(eth);
@@ -770,6 +1044,51 @@ where
         f(self.eth.as_ref().expect("exists; qed"))
     }
 
+    /// Returns the [PersonalSigner] of the personal namespace.
+    ///
+    /// The signer is created and added to the eth namespace the first time this is called.
+    fn personal_signer(&mut self) -> PersonalSigner {
+        if let Some(signer) = &self.personal_signer {
+            return signer.clone()
+        }
+        let signer = PersonalSigner::default();
+        if let Some(eth) = &self.eth {
+            eth.api.add_personal_signer(signer.clone());
+        }
+        self.personal_signer = Some(signer.clone());
+        signer
+    }
+
+    /// Returns the [TraceCache] shared by the debug and trace handlers.
+    ///
+    /// This will spawn the task that keeps the cache in sync with the canonical chain if this is
+    /// the first time the cache is requested.
+    fn trace_cache(&mut self) -> TraceCache {
+        if let Some(trace_cache) = &self.trace_cache {
+            return trace_cache.clone()
+        }
+        let trace_cache = TraceCache::new(self.config.eth.trace_cache.clone());
+        self.executor.spawn(Box::pin(
+            trace_cache
+                .clone()
+                .update_on_canonical_state(self.events.subscribe_to_canonical_state()),
+        ));
+        self.trace_cache = Some(trace_cache.clone());
+        trace_cache
+    }
+
+    /// Returns the [BlockingTaskPool] shared by the handlers of expensive calls.
+    ///
+    /// This will spawn the pool's threads if this is the first time the pool is requested.
+    fn blocking_pool(&mut self) -> BlockingTaskPool {
+        self.blocking_pool
+            .get_or_insert_with(|| {
+                BlockingTaskPool::new(self.config.blocking_pool)
+                    .expect("failed to spawn RPC blocking task pool")
+            })
+            .clone()
+    }
+
     /// Returns the configured [EthHandlers] or creates it if it does not exist yet
     fn eth_handlers(&mut self) -> EthHandlers<Client, Pool, Network, Events> {
         self.with_eth(|handlers| handlers.clone())
@@ -779,6 +1098,18 @@ where
     fn eth_api(&mut self) -> EthApi<Client, Pool, Network> {
         self.with_eth(|handlers| handlers.api.clone())
     }
+
+    /// Returns the configured [RethApi] or creates it if it does not exist yet
+    fn reth_api(&mut self) -> RethApi<Client> {
+        self.reth
+            .get_or_insert_with(|| match &self.sync_events {
+                Some(sync_events) => {
+                    RethApi::with_sync_events(self.client.clone(), sync_events.clone())
+                }
+                None => RethApi::new(self.client.clone()),
+            })
+            .clone()
+    }
 }
 
 /// A builder type for configuring and launching the servers that will handle RPC requests.
@@ -800,16 +1131,24 @@ pub struct RpcServerConfig {
     http_cors_domains: Option<String>,
     /// Address where to bind the http server to
     http_addr: Option<SocketAddr>,
+    /// Source addresses that are allowed to connect to the http server
+    http_allowlist: Option<IpAllowlist>,
     /// Configs for WS server
     ws_server_config: Option<ServerBuilder>,
     /// Allowed CORS Domains for ws.
     ws_cors_domains: Option<String>,
     /// Address where to bind the ws server to
     ws_addr: Option<SocketAddr>,
+    /// Source addresses that are allowed to connect to the ws server
+    ws_allowlist: Option<IpAllowlist>,
     /// Configs for JSON-RPC IPC server
     ipc_server_config: Option<IpcServerBuilder>,
     /// The Endpoint where to launch the ipc server
     ipc_endpoint: Option<Endpoint>,
+    /// TLS configuration for the http and ws servers
+    tls: Option<RpcTlsConfig>,
+    /// The executor the proxies in front of the http and ws servers are spawned on
+    executor: Option<Box<dyn TaskSpawner>>,
 }
 
 impl fmt::Debug for RpcServerConfig {
@@ -818,10 +1157,14 @@ impl fmt::Debug for RpcServerConfig {
             .field("http_server_config", &self.http_server_config)
             .field("http_cors_domains", &self.http_cors_domains)
             .field("http_addr", &self.http_addr)
+            .field("http_allowlist", &self.http_allowlist)
             .field("ws_server_config", &self.ws_server_config)
             .field("ws_addr", &self.ws_addr)
+            .field("ws_allowlist", &self.ws_allowlist)
             .field("ipc_server_config", &self.ipc_server_config)
             .field("ipc_endpoint", &self.ipc_endpoint.as_ref().map(|endpoint| endpoint.path()))
+            .field("tls", &self.tls)
+            .field("executor", &self.executor)
             .finish()
     }
 }
@@ -897,6 +1240,24 @@ impl RpcServerConfig {
         self
     }
 
+    /// Restricts the http server to connections from the given networks.
+    ///
+    /// The server is then bound to localhost and only reachable through a proxy listening on the
+    /// configured address, which closes connections from all other addresses as soon as they are
+    /// accepted.
+    pub fn with_http_allowlist(mut self, allowlist: IpAllowlist) -> Self {
+        self.http_allowlist = Some(allowlist);
+        self
+    }
+
+    /// Restricts the ws server to connections from the given networks.
+    ///
+    /// See also [RpcServerConfig::with_http_allowlist].
+    pub fn with_ws_allowlist(mut self, allowlist: IpAllowlist) -> Self {
+        self.ws_allowlist = Some(allowlist);
+        self
+    }
+
     /// Configures the ipc server
     ///
     /// Note: this always configures an [EthSubscriptionIdProvider] [IdProvider] for convenience.
@@ -934,6 +1295,24 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures TLS for the http and ws servers.
+    ///
+    /// The servers are then bound to localhost and only reachable through a proxy listening on
+    /// the configured addresses, which terminates TLS. The servers reject requests that weren't
+    /// forwarded by the proxy, so they can't be reached on localhost without TLS.
+    pub fn with_tls(mut self, tls: RpcTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Configures the executor the proxies in front of the http and ws servers are spawned on.
+    ///
+    /// Defaults to [TokioTaskExecutor].
+    pub fn with_executor(mut self, executor: Box<dyn TaskSpawner>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
     /// Returns true if any server is configured.
     ///
     /// If no server is configured, no server will be be launched on [RpcServerConfig::start].
@@ -974,6 +1353,25 @@ impl RpcServerConfig {
     pub async fn build(self) -> Result<RpcServer, RpcError> {
         let mut server = RpcServer::empty();
 
+        let tls_acceptor = self.tls.as_ref().map(RpcTlsConfig::acceptor).transpose()?;
+        server.tls = tls_acceptor.is_some();
+        // with TLS or an allowlist the servers are only reachable via the proxy, which
+        // authenticates itself with this secret
+        let secret = proxy_secret();
+        let executor = self.executor.unwrap_or_else(|| Box::<TokioTaskExecutor>::default());
+        let behind_proxy =
+            |allowlist: &Option<IpAllowlist>| tls_acceptor.is_some() || allowlist.is_some();
+        let bind_addr = |addr: SocketAddr, allowlist: &Option<IpAllowlist>| {
+            if behind_proxy(allowlist) {
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
+            } else {
+                addr
+            }
+        };
+        let proxy_guard = |allowlist: &Option<IpAllowlist>| {
+            behind_proxy(allowlist).then(|| AuthLayer::new(ProxyValidator::new(secret.clone())))
+        };
+
         let http_socket_addr = self.http_addr.unwrap_or(SocketAddr::V4(SocketAddrV4::new(
             Ipv4Addr::UNSPECIFIED,
             DEFAULT_HTTP_RPC_PORT,
@@ -982,22 +1380,50 @@ impl RpcServerConfig {
         if let Some(builder) = self.http_server_config {
             if let Some(cors) = self.http_cors_domains.as_deref().map(cors::create_cors_layer) {
                 let cors = cors.map_err(|err| RpcError::Custom(err.to_string()))?;
-                let middleware = tower::ServiceBuilder::new().layer(cors);
-                let http_server =
-                    builder.set_middleware(middleware).build(http_socket_addr).await.map_err(
-                        |err| {
-                            RpcError::from_jsonrpsee_error(err, ServerKind::Http(http_socket_addr))
-                        },
-                    )?;
+                let middleware = tower::ServiceBuilder::new()
+                    .option_layer(proxy_guard(&self.http_allowlist))
+                    .layer(cors);
+                let http_server = builder
+                    .set_middleware(middleware)
+                    .build(bind_addr(http_socket_addr, &self.http_allowlist))
+                    .await
+                    .map_err(|err| {
+                        RpcError::from_jsonrpsee_error(err, ServerKind::Http(http_socket_addr))
+                    })?;
                 server.http_local_addr = http_server.local_addr().ok();
                 server.http = Some(WsHttpServer::WithCors(http_server));
             } else {
-                let http_server = builder.build(http_socket_addr).await.map_err(|err| {
-                    RpcError::from_jsonrpsee_error(err, ServerKind::Http(http_socket_addr))
-                })?;
+                let middleware =
+                    tower::ServiceBuilder::new().option_layer(proxy_guard(&self.http_allowlist));
+                let http_server = builder
+                    .set_middleware(middleware)
+                    .build(bind_addr(http_socket_addr, &self.http_allowlist))
+                    .await
+                    .map_err(|err| {
+                        RpcError::from_jsonrpsee_error(err, ServerKind::Http(http_socket_addr))
+                    })?;
                 server.http_local_addr = http_server.local_addr().ok();
                 server.http = Some(WsHttpServer::Plain(http_server));
             }
+
+            if behind_proxy(&self.http_allowlist) {
+                if let Some(backend) = server.http_local_addr {
+                    let proxy = RpcProxy::bind(
+                        http_socket_addr,
+                        tls_acceptor.clone(),
+                        backend,
+                        secret.clone(),
+                        self.http_allowlist.clone(),
+                        executor.clone(),
+                    )
+                    .await
+                    .map_err(|err| {
+                        RpcError::from_io_error(err, ServerKind::Http(http_socket_addr))
+                    })?;
+                    server.http_local_addr = proxy.local_addr().ok();
+                    server.http_proxy = Some(proxy);
+                }
+            }
         }
 
         let ws_socket_addr = self.ws_addr.unwrap_or(SocketAddr::V4(SocketAddrV4::new(
@@ -1008,20 +1434,48 @@ impl RpcServerConfig {
         if let Some(builder) = self.ws_server_config {
             if let Some(cors) = self.ws_cors_domains.as_deref().map(cors::create_cors_layer) {
                 let cors = cors.map_err(|err| RpcError::Custom(err.to_string()))?;
-                let middleware = tower::ServiceBuilder::new().layer(cors);
-                let ws_server =
-                    builder.set_middleware(middleware).build(ws_socket_addr).await.map_err(
-                        |err| RpcError::from_jsonrpsee_error(err, ServerKind::WS(ws_socket_addr)),
-                    )?;
-                server.http_local_addr = ws_server.local_addr().ok();
+                let middleware = tower::ServiceBuilder::new()
+                    .option_layer(proxy_guard(&self.ws_allowlist))
+                    .layer(cors);
+                let ws_server = builder
+                    .set_middleware(middleware)
+                    .build(bind_addr(ws_socket_addr, &self.ws_allowlist))
+                    .await
+                    .map_err(|err| {
+                        RpcError::from_jsonrpsee_error(err, ServerKind::WS(ws_socket_addr))
+                    })?;
+                server.ws_local_addr = ws_server.local_addr().ok();
                 server.ws = Some(WsHttpServer::WithCors(ws_server));
             } else {
-                let ws_server = builder.build(ws_socket_addr).await.map_err(|err| {
-                    RpcError::from_jsonrpsee_error(err, ServerKind::WS(ws_socket_addr))
-                })?;
+                let middleware =
+                    tower::ServiceBuilder::new().option_layer(proxy_guard(&self.ws_allowlist));
+                let ws_server = builder
+                    .set_middleware(middleware)
+                    .build(bind_addr(ws_socket_addr, &self.ws_allowlist))
+                    .await
+                    .map_err(|err| {
+                        RpcError::from_jsonrpsee_error(err, ServerKind::WS(ws_socket_addr))
+                    })?;
                 server.ws_local_addr = ws_server.local_addr().ok();
                 server.ws = Some(WsHttpServer::Plain(ws_server));
             }
+
+            if behind_proxy(&self.ws_allowlist) {
+                if let Some(backend) = server.ws_local_addr {
+                    let proxy = RpcProxy::bind(
+                        ws_socket_addr,
+                        tls_acceptor.clone(),
+                        backend,
+                        secret.clone(),
+                        self.ws_allowlist.clone(),
+                        executor.clone(),
+                    )
+                    .await
+                    .map_err(|err| RpcError::from_io_error(err, ServerKind::WS(ws_socket_addr)))?;
+                    server.ws_local_addr = proxy.local_addr().ok();
+                    server.ws_proxy = Some(proxy);
+                }
+            }
         }
 
         if let Some(builder) = self.ipc_server_config {
@@ -1120,6 +1574,11 @@ impl TransportRpcModuleConfig {
     pub fn ipc(&self) -> Option<&RpcModuleSelection> {
         self.ipc.as_ref()
     }
+
+    /// Returns the custom [RpcModuleConfig] for the configured modules, if any
+    pub fn config(&self) -> Option<&RpcModuleConfig> {
+        self.config.as_ref()
+    }
 }
 
 /// Holds installed modules per transport type.
@@ -1154,20 +1613,39 @@ pub struct RpcServer {
     ws: Option<WsHttpServer>,
     /// ipc server
     ipc: Option<IpcServer>,
+    /// Whether the http and ws servers are only reachable via TLS
+    tls: bool,
+    /// Proxy in front of the http server
+    http_proxy: Option<RpcProxy>,
+    /// Proxy in front of the ws server
+    ws_proxy: Option<RpcProxy>,
 }
+/// The middleware of a server that's only reachable through a proxy, see
+/// [RpcServerConfig::with_tls].
+type ProxyGuardLayer = Either<AuthLayer<ProxyValidator>, Identity>;
+
 /// Http Servers Enum
 pub enum WsHttpServer {
     /// Http server
-    Plain(Server),
+    Plain(Server<Stack<ProxyGuardLayer, Identity>>),
     /// Http server with cors
-    WithCors(Server<Stack<CorsLayer, Identity>>),
+    WithCors(Server<Stack<CorsLayer, Stack<ProxyGuardLayer, Identity>>>),
 }
 
 // === impl RpcServer ===
 
 impl RpcServer {
     fn empty() -> RpcServer {
-        RpcServer { http_local_addr: None, ws_local_addr: None, http: None, ws: None, ipc: None }
+        RpcServer {
+            http_local_addr: None,
+            ws_local_addr: None,
+            http: None,
+            ws: None,
+            ipc: None,
+            tls: false,
+            http_proxy: None,
+            ws_proxy: None,
+        }
     }
 
     /// Returns the [`SocketAddr`] of the http server if started.
@@ -1202,8 +1680,19 @@ impl RpcServer {
             http: None,
             ws: None,
             ipc: None,
+            tls: self.tls,
+            proxy_shutdown: None,
         };
 
+        // Start the proxies, they stop once the handle is stopped or dropped
+        if self.http_proxy.is_some() || self.ws_proxy.is_some() {
+            let (shutdown_tx, shutdown_rx) = watch::channel(());
+            for proxy in self.http_proxy.into_iter().chain(self.ws_proxy) {
+                proxy.spawn(shutdown_rx.clone());
+            }
+            handle.proxy_shutdown = Some(Arc::new(shutdown_tx));
+        }
+
         // Start all servers
         if let Some((server, module)) =
             self.http.and_then(|server| http.map(|module| (server, module)))
@@ -1262,6 +1751,10 @@ pub struct RpcServerHandle {
     http: Option<ServerHandle>,
     ws: Option<ServerHandle>,
     ipc: Option<ServerHandle>,
+    /// Whether the http and ws servers are only reachable via TLS
+    tls: bool,
+    /// Stops the proxies when fired or dropped
+    proxy_shutdown: Option<Arc<watch::Sender<()>>>,
 }
 
 // === impl RpcServerHandle ===
@@ -1291,17 +1784,23 @@ impl RpcServerHandle {
             handle.stop()?
         }
 
+        if let Some(proxy_shutdown) = self.proxy_shutdown {
+            let _ = proxy_shutdown.send(());
+        }
+
         Ok(())
     }
 
     /// Returns the url to the http server
     pub fn http_url(&self) -> Option<String> {
-        self.http_local_addr.map(|addr| format!("http://{addr}"))
+        let scheme = if self.tls { "https" } else { "http" };
+        self.http_local_addr.map(|addr| format!("{scheme}://{addr}"))
     }
 
     /// Returns the url to the ws server
     pub fn ws_url(&self) -> Option<String> {
-        self.ws_local_addr.map(|addr| format!("ws://{addr}"))
+        let scheme = if self.tls { "wss" } else { "ws" };
+        self.ws_local_addr.map(|addr| format!("{scheme}://{addr}"))
     }
 
     /// Returns a http client connected to the server.
@@ -1330,6 +1829,7 @@ impl std::fmt::Debug for RpcServerHandle {
             .field("http", &self.http.is_some())
             .field("ws", &self.ws.is_some())
             .field("ipc", &self.ipc.is_some())
+            .field("tls", &self.tls)
             .finish()
     }
 }
@@ -1338,6 +1838,13 @@ impl std::fmt::Debug for RpcServerHandle {
 mod tests {
     use super::*;
 
+    #[test]
+    fn all_modules_exclude_personal() {
+        let all = RpcModuleSelection::All;
+        assert!(!all.iter_selection().any(|module| module == RethRpcModule::Personal));
+        assert!(all.iter_selection().any(|module| module == RethRpcModule::Eth));
+    }
+
     #[test]
     fn test_rpc_module_str() {
         macro_rules! assert_rpc_module {
@@ -1355,7 +1862,11 @@ mod tests {
                 "debug" =>  RethRpcModule::Debug,
                 "eth" =>  RethRpcModule::Eth,
                 "net" =>  RethRpcModule::Net,
+                "ots" =>  RethRpcModule::Ots,
+                "personal" =>  RethRpcModule::Personal,
+                "reth" =>  RethRpcModule::Reth,
                 "trace" =>  RethRpcModule::Trace,
+                "txpool" =>  RethRpcModule::Txpool,
                 "web3" =>  RethRpcModule::Web3,
             );
     }
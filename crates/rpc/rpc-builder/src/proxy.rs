@@ -0,0 +1,384 @@
+use crate::allowlist::IpAllowlist;
+use hyper::{
+    client::HttpConnector, header::HeaderValue, rt::Executor, server::conn::Http,
+    service::service_fn, upgrade, Body, Client, Request, Response, StatusCode, Uri,
+};
+use reth_rpc::AuthValidator;
+use reth_tasks::TaskSpawner;
+use std::{
+    convert::Infallible,
+    future::Future,
+    io,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpListener,
+    sync::watch,
+};
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, trace};
+
+/// The header the [RpcProxy] authenticates itself with at the server.
+pub(crate) const PROXY_SECRET_HEADER: &str = "x-reth-proxy-secret";
+
+/// The header the [RpcProxy] forwards the address of the client in, for information only.
+pub(crate) const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Returns a new random secret the [RpcProxy] authenticates itself with.
+pub(crate) fn proxy_secret() -> Arc<str> {
+    reth_primitives::hex::encode(rand::random::<[u8; 32]>()).into()
+}
+
+/// A bound listener in front of a server that is only reachable on localhost.
+///
+/// Connections from peers outside of the [IpAllowlist] are closed as soon as they are accepted,
+/// before any TLS or HTTP processing. The server itself can't do this, because the peer address
+/// of a connection isn't exposed to its middleware.
+///
+/// Terminates TLS if configured and forwards the requests to the server. Every forwarded request
+/// carries the proxy's secret. The server rejects requests without the secret with the
+/// [ProxyValidator], so it can't be reached on localhost without the proxy.
+///
+/// Connections to the server are pooled and all tasks are spawned on the given [TaskSpawner].
+pub(crate) struct RpcProxy {
+    listener: TcpListener,
+    acceptor: Option<TlsAcceptor>,
+    /// The address of the server the requests are forwarded to.
+    backend: SocketAddr,
+    /// The secret that's attached to all forwarded requests.
+    secret: Arc<str>,
+    /// The addresses peers are allowed to connect from.
+    allowlist: Option<IpAllowlist>,
+    /// The pooled connections to the server.
+    client: Client<HttpConnector>,
+    /// The executor the connection tasks are spawned on.
+    executor: Box<dyn TaskSpawner>,
+}
+
+// === impl RpcProxy ===
+
+impl RpcProxy {
+    /// Binds the proxy to the given address.
+    pub(crate) async fn bind(
+        addr: SocketAddr,
+        acceptor: Option<TlsAcceptor>,
+        backend: SocketAddr,
+        secret: Arc<str>,
+        allowlist: Option<IpAllowlist>,
+        executor: Box<dyn TaskSpawner>,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let client = Client::builder().executor(SpawnerExecutor(executor.clone())).build_http();
+        Ok(Self { listener, acceptor, backend, secret, allowlist, client, executor })
+    }
+
+    /// Returns the address the proxy is listening on.
+    pub(crate) fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Spawns the proxy on its executor, see [Self::run].
+    pub(crate) fn spawn(self, shutdown: watch::Receiver<()>) {
+        let executor = self.executor.clone();
+        executor.spawn(Box::pin(self.run(shutdown)));
+    }
+
+    /// Returns true if the peer is allowed to connect.
+    fn is_allowed(&self, peer: &IpAddr) -> bool {
+        self.allowlist.as_ref().map_or(true, |allowlist| allowlist.contains(peer))
+    }
+
+    /// Accepts connections until the shutdown signal fires or its sender is dropped.
+    async fn run(self, mut shutdown: watch::Receiver<()>) {
+        loop {
+            tokio::select! {
+                _ = shutdown.changed() => break,
+                conn = self.listener.accept() => {
+                    let (stream, remote_addr) = match conn {
+                        Ok(conn) => conn,
+                        Err(err) => {
+                            debug!(target: "rpc::proxy", %err, "Failed to accept connection");
+                            continue
+                        }
+                    };
+                    if !self.is_allowed(&remote_addr.ip()) {
+                        debug!(target: "rpc::proxy", %remote_addr, "Rejected connection, not in allowlist");
+                        continue
+                    }
+                    let acceptor = self.acceptor.clone();
+                    let forwarder = Forwarder {
+                        backend: self.backend,
+                        secret: self.secret.clone(),
+                        client: self.client.clone(),
+                        executor: self.executor.clone(),
+                    };
+                    self.executor.spawn(Box::pin(async move {
+                        let res = match acceptor {
+                            Some(acceptor) => match acceptor.accept(stream).await {
+                                Ok(stream) => forwarder.serve(stream, remote_addr).await,
+                                Err(err) => Err(err.into()),
+                            },
+                            None => forwarder.serve(stream, remote_addr).await,
+                        };
+                        if let Err(err) = res {
+                            trace!(target: "rpc::proxy", %remote_addr, %err, "Connection closed");
+                        }
+                    }));
+                }
+            }
+        }
+    }
+}
+
+/// Forwards the requests of client connections to the backend.
+#[derive(Clone)]
+struct Forwarder {
+    backend: SocketAddr,
+    secret: Arc<str>,
+    client: Client<HttpConnector>,
+    executor: Box<dyn TaskSpawner>,
+}
+
+// === impl Forwarder ===
+
+impl Forwarder {
+    /// Serves the HTTP requests of a client connection by forwarding them to the backend.
+    async fn serve<S>(self, stream: S, remote_addr: SocketAddr) -> Result<(), BoxError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let service = service_fn(move |req| {
+            let this = self.clone();
+            async move {
+                let res = this.forward(req, remote_addr).await.unwrap_or_else(|err| {
+                    debug!(target: "rpc::proxy", %remote_addr, %err, "Failed to forward request");
+                    status_response(StatusCode::BAD_GATEWAY)
+                });
+                Ok::<_, Infallible>(res)
+            }
+        });
+        Http::new().http1_only(true).serve_connection(stream, service).with_upgrades().await?;
+        Ok(())
+    }
+
+    /// Forwards the request to the backend and returns its response.
+    ///
+    /// If the backend accepts a websocket handshake, the upgraded connections are joined.
+    async fn forward(
+        &self,
+        mut req: Request<Body>,
+        remote_addr: SocketAddr,
+    ) -> Result<Response<Body>, BoxError> {
+        // replaces any values sent by the client
+        let headers = req.headers_mut();
+        headers.insert(PROXY_SECRET_HEADER, HeaderValue::from_str(&self.secret)?);
+        headers.insert(FORWARDED_FOR_HEADER, HeaderValue::from_str(&remote_addr.ip().to_string())?);
+        let path = req.uri().path_and_query().map_or("/", |path| path.as_str());
+        *req.uri_mut() = format!("http://{}{path}", self.backend).parse::<Uri>()?;
+        let client_upgrade = upgrade::on(&mut req);
+
+        let mut res = self.client.request(req).await?;
+        if res.status() == StatusCode::SWITCHING_PROTOCOLS {
+            let backend_upgrade = upgrade::on(&mut res);
+            self.executor.spawn(Box::pin(async move {
+                match tokio::try_join!(client_upgrade, backend_upgrade) {
+                    Ok((mut client, mut backend)) => {
+                        let _ = tokio::io::copy_bidirectional(&mut client, &mut backend).await;
+                    }
+                    Err(err) => {
+                        trace!(target: "rpc::proxy", %remote_addr, %err, "Failed to upgrade connection")
+                    }
+                }
+            }));
+        }
+        Ok(res)
+    }
+}
+
+/// Spawns the connection tasks of the [Client] on a [TaskSpawner].
+#[derive(Clone)]
+struct SpawnerExecutor(Box<dyn TaskSpawner>);
+
+impl<F> Executor<F> for SpawnerExecutor
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    fn execute(&self, fut: F) {
+        self.0.spawn(Box::pin(fut));
+    }
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+    let mut res = Response::new(Body::empty());
+    *res.status_mut() = status;
+    res
+}
+
+/// Validates that requests were forwarded by the proxy in front of the server.
+///
+/// This is used as the [AuthValidator] of an [AuthLayer](reth_rpc::AuthLayer) on servers that are
+/// only supposed to be reachable through the proxy. Rejected requests get a `403 Forbidden`.
+#[derive(Debug, Clone)]
+pub struct ProxyValidator {
+    /// The secret of the proxy.
+    secret: Arc<str>,
+}
+
+// === impl ProxyValidator ===
+
+impl ProxyValidator {
+    /// Creates a new validator that accepts requests with the given secret.
+    pub(crate) fn new(secret: Arc<str>) -> Self {
+        Self { secret }
+    }
+}
+
+impl AuthValidator for ProxyValidator {
+    type ResponseBody = Body;
+
+    fn validate(&self, headers: &hyper::HeaderMap) -> Result<(), Response<Self::ResponseBody>> {
+        let secret = headers.get(PROXY_SECRET_HEADER).map(|value| value.as_bytes());
+        if !secret.map_or(false, |secret| constant_time_eq(secret, self.secret.as_bytes())) {
+            trace!(target: "rpc::proxy", "Rejected request that bypassed the proxy");
+            return Err(status_response(StatusCode::FORBIDDEN))
+        }
+        Ok(())
+    }
+}
+
+/// Compares the given values without short-circuiting on the first mismatch, so the time it takes
+/// doesn't reveal the length of the matching prefix.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RpcTlsConfig;
+    use hyper::{body, header, Method};
+    use reth_rpc::AuthLayer;
+    use reth_tasks::TokioTaskExecutor;
+    use tokio::{io::AsyncReadExt, net::TcpStream};
+    use tokio_rustls::{
+        rustls::{Certificate, ClientConfig, RootCertStore, ServerName},
+        TlsConnector,
+    };
+
+    /// Spawns a backend that only answers requests that passed the [ProxyValidator], and responds
+    /// with the forwarded client address.
+    async fn guarded_backend(secret: Arc<str>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let service = tower::ServiceBuilder::new()
+                    .layer(AuthLayer::new(ProxyValidator::new(secret.clone())))
+                    .service_fn(|req: Request<Body>| async move {
+                        let client = req.headers()[FORWARDED_FOR_HEADER].clone();
+                        Ok::<_, Infallible>(Response::new(Body::from(client.as_bytes().to_vec())))
+                    });
+                tokio::spawn(Http::new().serve_connection(stream, service));
+            }
+        });
+        addr
+    }
+
+    async fn spawn_proxy(
+        acceptor: Option<TlsAcceptor>,
+        allowlist: Option<IpAllowlist>,
+    ) -> (SocketAddr, SocketAddr, watch::Sender<()>) {
+        let secret = proxy_secret();
+        let backend = guarded_backend(secret.clone()).await;
+        let proxy = RpcProxy::bind(
+            "127.0.0.1:0".parse().unwrap(),
+            acceptor,
+            backend,
+            secret,
+            allowlist,
+            Box::<TokioTaskExecutor>::default(),
+        )
+        .await
+        .unwrap();
+        let proxy_addr = proxy.local_addr().unwrap();
+        let (shutdown_tx, shutdown_rx) = watch::channel(());
+        proxy.spawn(shutdown_rx);
+        (proxy_addr, backend, shutdown_tx)
+    }
+
+    /// Sends a request over the given connection and returns the status and the body.
+    async fn request<S>(stream: S, secret: Option<&str>) -> (StatusCode, String)
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let (mut sender, conn) = hyper::client::conn::handshake(stream).await.unwrap();
+        tokio::spawn(conn);
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .header(header::HOST, "localhost")
+            .header(FORWARDED_FOR_HEADER, "10.0.0.1");
+        if let Some(secret) = secret {
+            req = req.header(PROXY_SECRET_HEADER, secret);
+        }
+        let res = sender.send_request(req.body(Body::empty()).unwrap()).await.unwrap();
+        let status = res.status();
+        let body = body::to_bytes(res.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn forwards_decrypted_requests() {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let (cert_path, key_path) = (dir.path().join("cert.pem"), dir.path().join("key.pem"));
+        std::fs::write(&cert_path, cert.serialize_pem().unwrap()).unwrap();
+        std::fs::write(&key_path, cert.serialize_private_key_pem()).unwrap();
+        let acceptor = RpcTlsConfig::new(&cert_path, &key_path).acceptor().unwrap();
+        let (proxy_addr, _, _shutdown) = spawn_proxy(Some(acceptor), None).await;
+
+        let mut roots = RootCertStore::empty();
+        roots.add(&Certificate(cert.serialize_der().unwrap())).unwrap();
+        let config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let stream = TcpStream::connect(proxy_addr).await.unwrap();
+        let stream = TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), stream)
+            .await
+            .unwrap();
+
+        // the server sees the actual address of the client instead of the spoofed one
+        assert_eq!(request(stream, None).await, (StatusCode::OK, "127.0.0.1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_that_bypass_the_proxy() {
+        let (_, backend, _shutdown) = spawn_proxy(None, None).await;
+
+        let stream = TcpStream::connect(backend).await.unwrap();
+        assert_eq!(request(stream, None).await.0, StatusCode::FORBIDDEN);
+
+        let stream = TcpStream::connect(backend).await.unwrap();
+        assert_eq!(request(stream, Some("guessed")).await.0, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn rejects_addresses_not_in_allowlist() {
+        let (proxy_addr, _, _shutdown) =
+            spawn_proxy(None, Some("127.0.0.1".parse().unwrap())).await;
+        let stream = TcpStream::connect(proxy_addr).await.unwrap();
+        assert_eq!(request(stream, None).await.0, StatusCode::OK);
+
+        // the connection is closed right away, the spoofed client address is never looked at
+        let (proxy_addr, _, _shutdown) =
+            spawn_proxy(None, Some("10.0.0.0/8".parse().unwrap())).await;
+        let mut stream = TcpStream::connect(proxy_addr).await.unwrap();
+        assert_eq!(stream.read(&mut [0; 1]).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::error::RpcError;
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio_rustls::{
+    rustls::{
+        server::AllowAnyAuthenticatedClient, Certificate, PrivateKey, RootCertStore, ServerConfig,
+    },
+    TlsAcceptor,
+};
+
+/// TLS configuration for the HTTP and WS servers.
+///
+/// If configured, the servers only accept TLS connections. These are terminated by a proxy that
+/// forwards the decrypted requests to the server, which is bound to localhost instead and rejects
+/// requests that weren't forwarded by the proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcTlsConfig {
+    /// Path to the PEM encoded certificate chain.
+    cert_path: PathBuf,
+    /// Path to the PEM encoded private key.
+    key_path: PathBuf,
+    /// Path to the PEM encoded CA certificates used to authenticate clients, if any.
+    client_ca_path: Option<PathBuf>,
+}
+
+// === impl RpcTlsConfig ===
+
+impl RpcTlsConfig {
+    /// Creates a new config with the given certificate chain and private key.
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self { cert_path: cert_path.into(), key_path: key_path.into(), client_ca_path: None }
+    }
+
+    /// Requires clients to authenticate with a certificate signed by one of the CA certificates
+    /// in the given file.
+    pub fn with_client_ca(mut self, client_ca_path: impl Into<PathBuf>) -> Self {
+        self.client_ca_path = Some(client_ca_path.into());
+        self
+    }
+
+    /// Loads the certificates and creates the [TlsAcceptor].
+    pub fn acceptor(&self) -> Result<TlsAcceptor, RpcError> {
+        let certs = load_certs(&self.cert_path)?;
+        let key = load_private_key(&self.key_path)?;
+
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = match &self.client_ca_path {
+            Some(client_ca_path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(client_ca_path)? {
+                    roots.add(&cert).map_err(|err| tls_error(client_ca_path, err))?;
+                }
+                builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let config =
+            builder.with_single_cert(certs, key).map_err(|err| tls_error(&self.key_path, err))?;
+
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
+fn load_certs(path: &Path) -> Result<Vec<Certificate>, RpcError> {
+    let file = File::open(path).map_err(|err| tls_error(path, err))?;
+    let certs =
+        rustls_pemfile::certs(&mut BufReader::new(file)).map_err(|err| tls_error(path, err))?;
+    if certs.is_empty() {
+        return Err(tls_error(path, "no certificates found"))
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKey, RpcError> {
+    let file = File::open(path).map_err(|err| tls_error(path, err))?;
+    let items =
+        rustls_pemfile::read_all(&mut BufReader::new(file)).map_err(|err| tls_error(path, err))?;
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::RSAKey(key) |
+            rustls_pemfile::Item::PKCS8Key(key) |
+            rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| tls_error(path, "no private key found"))
+}
+
+fn tls_error(path: &Path, err: impl std::fmt::Display) -> RpcError {
+    RpcError::Tls(format!("{}: {err}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_certificate() {
+        let config = RpcTlsConfig::new("/does/not/exist.pem", "/does/not/exist.key");
+        assert!(matches!(config.acceptor(), Err(RpcError::Tls(_))));
+    }
+}
@@ -1,11 +1,14 @@
 //! Auth server tests
 
-use crate::utils::launch_auth;
+use crate::utils::{launch_auth, launch_auth_ipc};
 use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
+use reth_ipc::client::IpcClientBuilder;
 use reth_primitives::Block;
 use reth_rpc::JwtSecret;
 use reth_rpc_api::clients::EngineApiClient;
-use reth_rpc_types::engine::{ForkchoiceState, PayloadId, TransitionConfiguration};
+use reth_rpc_types::engine::{
+    ClientCode, ClientVersionV1, ForkchoiceState, PayloadId, TransitionConfiguration,
+};
 
 #[allow(unused_must_use)]
 async fn test_basic_engine_calls<C>(client: &C)
@@ -23,6 +26,16 @@ where
     EngineApiClient::exchange_transition_configuration(client, TransitionConfiguration::default())
         .await;
     EngineApiClient::exchange_capabilities(client, vec![]).await;
+    EngineApiClient::get_client_version_v1(
+        client,
+        ClientVersionV1 {
+            code: ClientCode::LH,
+            name: "Lighthouse".to_string(),
+            version: "v4.1.0".to_string(),
+            commit: "00000000".to_string(),
+        },
+    )
+    .await;
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -30,7 +43,7 @@ async fn test_auth_endpoints_http() {
     reth_tracing::init_test_tracing();
     let secret = JwtSecret::random();
     let handle = launch_auth(secret).await;
-    let client = handle.http_client();
+    let client = handle.http_client().unwrap();
     test_basic_engine_calls(&client).await
 }
 
@@ -39,6 +52,26 @@ async fn test_auth_endpoints_ws() {
     reth_tracing::init_test_tracing();
     let secret = JwtSecret::random();
     let handle = launch_auth(secret).await;
-    let client = handle.ws_client().await;
+    let client = handle.ws_client().await.unwrap();
+    test_basic_engine_calls(&client).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auth_endpoints_ipc() {
+    reth_tracing::init_test_tracing();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("engine.ipc");
+    let handle = launch_auth_ipc(JwtSecret::random(), path.to_str().unwrap()).await;
+    assert!(handle.local_addr().is_none());
+    assert!(handle.http_client().is_none());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let client = IpcClientBuilder::default().build(handle.ipc_endpoint().unwrap()).await.unwrap();
     test_basic_engine_calls(&client).await
 }
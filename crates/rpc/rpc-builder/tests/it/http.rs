@@ -1,6 +1,6 @@
 //! Standalone http tests
 
-use crate::utils::{launch_http, launch_http_ws, launch_ws};
+use crate::utils::{launch_http, launch_http_ws, launch_ws, test_address, test_rpc_builder};
 use jsonrpsee::{
     core::{
         client::{ClientT, SubscriptionClientT},
@@ -9,15 +9,21 @@ use jsonrpsee::{
     types::error::{CallError, ErrorCode},
 };
 use reth_primitives::{
-    hex_literal::hex, Address, BlockId, BlockNumberOrTag, Bytes, NodeRecord, TxHash, H256, H64,
-    U256,
+    hex_literal::hex, Address, Block, BlockId, BlockNumberOrTag, Bytes, Header, NodeRecord,
+    PruneSegment, TxHash, H256, H64, U256, U64,
 };
+use reth_provider::test_utils::MockEthProvider;
+use reth_rpc::MAX_TRACE_CHAIN_BLOCKS;
 use reth_rpc_api::{
     clients::{AdminApiClient, EthApiClient},
-    DebugApiClient, NetApiClient, TraceApiClient, Web3ApiClient,
+    DebugApiClient, NetApiClient, OtterscanApiClient, PersonalApiClient, RethAdminApiClient,
+    RethApiClient, TraceApiClient, TxPoolApiClient, Web3ApiClient,
+};
+use reth_rpc_builder::{RethRpcModule, RpcServerConfig, TransportRpcModuleConfig};
+use reth_rpc_types::{
+    trace::{filter::TraceFilter, geth::DebugSubscriptionKind},
+    Bundle, CallRequest, Index, StateContext, TransactionRequest,
 };
-use reth_rpc_builder::RethRpcModule;
-use reth_rpc_types::{trace::filter::TraceFilter, CallRequest, Index, TransactionRequest};
 use std::collections::HashSet;
 
 fn is_unimplemented(err: Error) -> bool {
@@ -42,6 +48,25 @@ where
     AdminApiClient::add_trusted_peer(client, node).await.unwrap();
     AdminApiClient::remove_trusted_peer(client, node).await.unwrap();
     AdminApiClient::node_info(client).await.unwrap();
+    AdminApiClient::peers(client).await.unwrap();
+    AdminApiClient::banned_peers(client).await.unwrap();
+    AdminApiClient::clear_bans(client).await.unwrap();
+    AdminApiClient::recent_disconnects(client).await.unwrap();
+    RethAdminApiClient::prune(client, PruneSegment::Receipts, 0).await.unwrap();
+    RethAdminApiClient::prune(client, PruneSegment::Receipts, 1).await.unwrap_err();
+}
+
+async fn test_basic_reth_calls<C>(client: &C)
+where
+    C: ClientT + SubscriptionClientT + Sync,
+{
+    RethApiClient::prune_status(client).await.unwrap();
+    RethApiClient::balance_changes_in_block(client, BlockId::Number(BlockNumberOrTag::Latest))
+        .await
+        .unwrap();
+    RethApiClient::balance_changes_in_block(client, BlockId::Number(1u64.into()))
+        .await
+        .unwrap_err();
 }
 
 async fn test_basic_eth_calls<C>(client: &C)
@@ -82,28 +107,38 @@ where
         .await
         .unwrap_err();
     EthApiClient::transaction_by_hash(client, tx_hash).await.unwrap();
+    EthApiClient::block_receipts(client, block_number.into()).await.unwrap();
     EthApiClient::transaction_by_block_hash_and_index(client, hash, index).await.unwrap();
     EthApiClient::transaction_by_block_number_and_index(client, block_number, index).await.unwrap();
+    EthApiClient::transaction_by_sender_and_nonce(client, address, U64::from(1)).await.unwrap();
     EthApiClient::create_access_list(client, call_request.clone(), Some(block_number.into()))
         .await
         .unwrap();
-    EthApiClient::estimate_gas(client, call_request.clone(), Some(block_number.into()))
+    EthApiClient::call_many(
+        client,
+        vec![Bundle { transactions: vec![call_request.clone()], block_override: None }],
+        Some(StateContext { block_number: Some(block_number.into()), transaction_index: None }),
+        None,
+    )
+    .await
+    .unwrap();
+    EthApiClient::estimate_gas(client, call_request.clone(), Some(block_number.into()), None, None)
         .await
         .unwrap();
-    EthApiClient::call(client, call_request.clone(), Some(block_number.into()), None)
+    EthApiClient::call(client, call_request.clone(), Some(block_number.into()), None, None)
         .await
         .unwrap();
     EthApiClient::syncing(client).await.unwrap();
+    EthApiClient::fill_transaction(client, transaction_request.clone()).await.unwrap_err();
     EthApiClient::send_transaction(client, transaction_request).await.unwrap_err();
     EthApiClient::hashrate(client).await.unwrap();
     EthApiClient::submit_hashrate(client, U256::default(), H256::default()).await.unwrap();
     EthApiClient::gas_price(client).await.unwrap();
     EthApiClient::max_priority_fee_per_gas(client).await.unwrap();
+    EthApiClient::blob_base_fee(client).await.unwrap_err();
+    EthApiClient::get_proof(client, address, vec![], None).await.unwrap();
 
     // Unimplemented
-    assert!(is_unimplemented(
-        EthApiClient::get_proof(client, address, vec![], None).await.err().unwrap()
-    ));
     assert!(is_unimplemented(EthApiClient::author(client).await.err().unwrap()));
     assert!(is_unimplemented(EthApiClient::is_mining(client).await.err().unwrap()));
     assert!(is_unimplemented(EthApiClient::get_work(client).await.err().unwrap()));
@@ -140,6 +175,19 @@ where
     NetApiClient::is_listening(client).await.unwrap();
 }
 
+async fn test_basic_ots_calls<C>(client: &C)
+where
+    C: ClientT + SubscriptionClientT + Sync,
+{
+    OtterscanApiClient::get_api_level(client).await.unwrap();
+    OtterscanApiClient::has_code(client, Address::default(), None).await.unwrap();
+    OtterscanApiClient::get_block_details(client, BlockNumberOrTag::Latest).await.unwrap();
+    OtterscanApiClient::search_transactions_before(client, Address::default(), 0, 25)
+        .await
+        .unwrap();
+    OtterscanApiClient::get_internal_operations(client, H256::default()).await.unwrap_err();
+}
+
 async fn test_basic_trace_calls<C>(client: &C)
 where
     C: ClientT + SubscriptionClientT + Sync,
@@ -173,6 +221,43 @@ where
     ));
 }
 
+async fn test_basic_personal_calls<C>(client: &C)
+where
+    C: ClientT + SubscriptionClientT + Sync,
+{
+    let password = "password".to_string();
+    let address = PersonalApiClient::new_account(client, password.clone()).await.unwrap();
+    assert_eq!(PersonalApiClient::list_accounts(client).await.unwrap(), vec![address]);
+
+    assert!(PersonalApiClient::unlock_account(client, address, "wrong".to_string(), None)
+        .await
+        .is_err());
+    assert!(PersonalApiClient::unlock_account(client, address, password.clone(), Some(0))
+        .await
+        .unwrap());
+    assert!(PersonalApiClient::lock_account(client, address).await.unwrap());
+
+    let message = Bytes::from(b"hello".to_vec());
+    let signature =
+        PersonalApiClient::sign(client, message.clone(), address, password).await.unwrap();
+    assert_eq!(PersonalApiClient::ec_recover(client, message, signature).await.unwrap(), address);
+
+    let key = "4646464646464646464646464646464646464646464646464646464646464646".to_string();
+    let imported =
+        PersonalApiClient::import_raw_key(client, key, "password".to_string()).await.unwrap();
+    assert_eq!(imported, Address::from(hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f")));
+}
+
+async fn test_basic_txpool_calls<C>(client: &C)
+where
+    C: ClientT + SubscriptionClientT + Sync,
+{
+    TxPoolApiClient::txpool_status(client).await.unwrap();
+    TxPoolApiClient::txpool_inspect(client).await.unwrap();
+    TxPoolApiClient::txpool_content(client).await.unwrap();
+    TxPoolApiClient::txpool_content_from(client, Address::default()).await.unwrap();
+}
+
 async fn test_basic_web3_calls<C>(client: &C)
 where
     C: ClientT + SubscriptionClientT + Sync,
@@ -262,6 +347,67 @@ async fn test_call_debug_functions_http_and_ws() {
     test_basic_debug_calls(&client).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_trace_chain_ws() {
+    reth_tracing::init_test_tracing();
+
+    // a chain of empty blocks 0..=3
+    let provider = MockEthProvider::default();
+    let mut hashes = Vec::new();
+    let mut parent_hash = H256::zero();
+    for number in 0..=3u64 {
+        let header = Header {
+            number,
+            parent_hash,
+            gas_limit: 30_000_000,
+            timestamp: 1_700_000_000 + number * 12,
+            base_fee_per_gas: Some(7),
+            ..Default::default()
+        };
+        let hash = header.hash_slow();
+        provider.add_block(hash, Block { header, ..Default::default() });
+        hashes.push(hash);
+        parent_hash = hash;
+    }
+
+    let server = test_rpc_builder()
+        .with_client(provider)
+        .build(TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Debug]));
+    let handle = server
+        .start_server(RpcServerConfig::ws(Default::default()).with_ws_address(test_address()))
+        .await
+        .unwrap();
+    let client = handle.ws_client().await.unwrap();
+
+    // the start block is excluded, every other block is streamed in order
+    let mut sub = DebugApiClient::debug_trace_chain(
+        &client,
+        DebugSubscriptionKind::TraceChain,
+        BlockNumberOrTag::Number(0),
+        BlockNumberOrTag::Number(3),
+        None,
+    )
+    .await
+    .unwrap();
+    for number in 1..=3u64 {
+        let result = sub.next().await.unwrap().unwrap();
+        assert_eq!(result.block, U256::from(number));
+        assert_eq!(result.hash, hashes[number as usize]);
+        assert!(result.traces.is_empty());
+    }
+
+    // ranges above the cap are rejected
+    let res = DebugApiClient::debug_trace_chain(
+        &client,
+        DebugSubscriptionKind::TraceChain,
+        BlockNumberOrTag::Number(0),
+        BlockNumberOrTag::Number(MAX_TRACE_CHAIN_BLOCKS + 1),
+        None,
+    )
+    .await;
+    assert!(res.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_call_net_functions_http() {
     reth_tracing::init_test_tracing();
@@ -289,6 +435,24 @@ async fn test_call_net_functions_http_and_ws() {
     test_basic_net_calls(&client).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_ots_functions_http() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_http(vec![RethRpcModule::Ots]).await;
+    let client = handle.http_client().unwrap();
+    test_basic_ots_calls(&client).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_ots_functions_ws() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_ws(vec![RethRpcModule::Ots]).await;
+    let client = handle.ws_client().await.unwrap();
+    test_basic_ots_calls(&client).await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_call_trace_functions_http() {
     reth_tracing::init_test_tracing();
@@ -316,6 +480,42 @@ async fn test_call_trace_functions_http_and_ws() {
     test_basic_trace_calls(&client).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_personal_functions_http() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_http(vec![RethRpcModule::Personal]).await;
+    let client = handle.http_client().unwrap();
+    test_basic_personal_calls(&client).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_personal_functions_ws() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_ws(vec![RethRpcModule::Personal]).await;
+    let client = handle.ws_client().await.unwrap();
+    test_basic_personal_calls(&client).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_txpool_functions_http() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_http(vec![RethRpcModule::Txpool]).await;
+    let client = handle.http_client().unwrap();
+    test_basic_txpool_calls(&client).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_txpool_functions_ws() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_ws(vec![RethRpcModule::Txpool]).await;
+    let client = handle.ws_client().await.unwrap();
+    test_basic_txpool_calls(&client).await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_call_web3_functions_http() {
     reth_tracing::init_test_tracing();
@@ -342,3 +542,12 @@ async fn test_call_web3_functions_http_and_ws() {
     let client = handle.http_client().unwrap();
     test_basic_web3_calls(&client).await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_reth_functions_http() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_http(vec![RethRpcModule::Reth]).await;
+    let client = handle.http_client().unwrap();
+    test_basic_reth_calls(&client).await;
+}
@@ -10,8 +10,12 @@ use reth_rpc_builder::{
     TransportRpcModuleConfig,
 };
 use reth_rpc_engine_api::EngineApi;
+use reth_rpc_types::engine::{ClientCode, ClientVersionV1};
 use reth_tasks::TokioTaskExecutor;
-use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+use reth_transaction_pool::{
+    test_utils::{testing_pool, TestPool},
+    BlobStore,
+};
 use std::{
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     sync::Arc,
@@ -25,7 +29,15 @@ pub fn test_address() -> SocketAddr {
 
 /// Launches a new server for the auth module
 pub async fn launch_auth(secret: JwtSecret) -> AuthServerHandle {
-    let config = AuthServerConfig::builder(secret).socket_addr(test_address()).build();
+    start_auth(AuthServerConfig::builder(secret).socket_addr(test_address()).build()).await
+}
+
+/// Launches a new server for the auth module on the given ipc endpoint
+pub async fn launch_auth_ipc(secret: JwtSecret, path: &str) -> AuthServerHandle {
+    start_auth(AuthServerConfig::builder(secret).ipc_endpoint(path).build()).await
+}
+
+async fn start_auth(config: AuthServerConfig) -> AuthServerHandle {
     let (tx, _rx) = unbounded_channel();
     let beacon_engine_handle = BeaconConsensusEngineHandle::new(tx);
     let engine_api = EngineApi::new(
@@ -33,6 +45,13 @@ pub async fn launch_auth(secret: JwtSecret) -> AuthServerHandle {
         Arc::new(MAINNET.clone()),
         beacon_engine_handle,
         spawn_test_payload_service().into(),
+        BlobStore::default(),
+        ClientVersionV1 {
+            code: ClientCode::RH,
+            name: "Reth".to_string(),
+            version: "v0.1.0".to_string(),
+            commit: "00000000".to_string(),
+        },
     );
     let module = AuthRpcModule::new(engine_api);
     module.start_server(config).await.unwrap()
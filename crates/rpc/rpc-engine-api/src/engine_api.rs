@@ -1,19 +1,22 @@
 use crate::{EngineApiError, EngineApiMessageVersion, EngineApiResult};
 use async_trait::async_trait;
 use jsonrpsee_core::RpcResult as Result;
+use parking_lot::RwLock;
 use reth_beacon_consensus::BeaconConsensusEngineHandle;
 use reth_interfaces::consensus::ForkchoiceState;
 use reth_payload_builder::PayloadStore;
-use reth_primitives::{BlockHash, BlockId, BlockNumber, ChainSpec, Hardfork, U64};
+use reth_primitives::{BlockHash, BlockId, BlockNumber, ChainSpec, Hardfork, H256, U64};
 use reth_provider::{BlockProvider, EvmEnvProvider, HeaderProvider, StateProviderFactory};
 use reth_rpc_api::EngineApiServer;
 use reth_rpc_types::engine::{
-    ExecutionPayload, ExecutionPayloadBodies, ExecutionPayloadEnvelope, ForkchoiceUpdated,
-    PayloadAttributes, PayloadId, PayloadStatus, TransitionConfiguration, CAPABILITIES,
+    BlobAndProofV1, ClientVersionV1, ExecutionPayload, ExecutionPayloadBodies,
+    ExecutionPayloadEnvelope, ForkchoiceUpdated, PayloadAttributes, PayloadId, PayloadStatus,
+    TransitionConfiguration, CAPABILITIES,
 };
+use reth_transaction_pool::{BlobAndProof, BlobStore};
 use std::sync::Arc;
 use tokio::sync::oneshot;
-use tracing::trace;
+use tracing::{info, trace};
 
 /// The Engine API response sender.
 pub type EngineApiSender<Ok> = oneshot::Sender<EngineApiResult<Ok>>;
@@ -21,6 +24,9 @@ pub type EngineApiSender<Ok> = oneshot::Sender<EngineApiResult<Ok>>;
 /// The upper limit for payload bodies request.
 const MAX_PAYLOAD_BODIES_LIMIT: u64 = 1024;
 
+/// The upper limit for blobs requested via `engine_getBlobsV1`.
+const MAX_BLOBS_LIMIT: u64 = 128;
+
 /// The Engine API implementation that grants the Consensus layer access to data and
 /// functions in the Execution layer that are crucial for the consensus process.
 pub struct EngineApi<Client> {
@@ -32,6 +38,12 @@ pub struct EngineApi<Client> {
     beacon_consensus: BeaconConsensusEngineHandle,
     /// The type that can communicate with the payload service to retrieve payloads.
     payload_store: PayloadStore,
+    /// The store for the sidecars of blob transactions.
+    blob_store: BlobStore,
+    /// The version information of this execution client.
+    client_version: ClientVersionV1,
+    /// The version information last reported by the consensus layer client.
+    consensus_client_version: RwLock<Option<ClientVersionV1>>,
 }
 
 impl<Client> EngineApi<Client>
@@ -44,8 +56,37 @@ where
         chain_spec: Arc<ChainSpec>,
         beacon_consensus: BeaconConsensusEngineHandle,
         payload_store: PayloadStore,
+        blob_store: BlobStore,
+        client_version: ClientVersionV1,
     ) -> Self {
-        Self { client, chain_spec, beacon_consensus, payload_store }
+        Self {
+            client,
+            chain_spec,
+            beacon_consensus,
+            payload_store,
+            blob_store,
+            client_version,
+            consensus_client_version: Default::default(),
+        }
+    }
+
+    /// Returns the version information of the consensus layer client, if it was reported via
+    /// `engine_getClientVersionV1`.
+    pub fn consensus_client_version(&self) -> Option<ClientVersionV1> {
+        self.consensus_client_version.read().clone()
+    }
+
+    /// Records the version of the consensus layer client and returns the version information of
+    /// this execution client.
+    ///
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/identification.md#engine_getclientversionv1>
+    pub fn get_client_version_v1(&self, client_version: ClientVersionV1) -> Vec<ClientVersionV1> {
+        let mut consensus_client_version = self.consensus_client_version.write();
+        if consensus_client_version.as_ref() != Some(&client_version) {
+            info!(target: "rpc::engine", %client_version, "Consensus layer client identified");
+            *consensus_client_version = Some(client_version);
+        }
+        vec![self.client_version.clone()]
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/8db51dcd2f4bdfbd9ad6e4a7560aac97010ad063/src/engine/specification.md#engine_newpayloadv1>
@@ -200,6 +241,27 @@ where
         Ok(result)
     }
 
+    /// Returns the blob and proof for each of the given versioned hashes, or `None` if the blob
+    /// is not available.
+    ///
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_getblobsv1>
+    pub fn get_blobs_v1(
+        &self,
+        versioned_hashes: Vec<H256>,
+    ) -> EngineApiResult<Vec<Option<BlobAndProofV1>>> {
+        let len = versioned_hashes.len() as u64;
+        if len > MAX_BLOBS_LIMIT {
+            return Err(EngineApiError::PayloadRequestTooLarge { len })
+        }
+
+        Ok(self
+            .blob_store
+            .get_by_versioned_hashes(&versioned_hashes)
+            .into_iter()
+            .map(|blob| blob.map(|BlobAndProof { blob, proof }| BlobAndProofV1 { blob, proof }))
+            .collect())
+    }
+
     /// Called to verify network configuration parameters and ensure that Consensus and Execution
     /// layers are using the latest configuration.
     pub fn exchange_transition_configuration(
@@ -398,6 +460,26 @@ where
     async fn exchange_capabilities(&self, _capabilities: Vec<String>) -> Result<Vec<String>> {
         Ok(CAPABILITIES.into_iter().map(str::to_owned).collect())
     }
+
+    /// Handler for `engine_getClientVersionV1`
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/identification.md#engine_getclientversionv1>
+    async fn get_client_version_v1(
+        &self,
+        client_version: ClientVersionV1,
+    ) -> Result<Vec<ClientVersionV1>> {
+        trace!(target: "rpc::eth", "Serving engine_getClientVersionV1");
+        Ok(EngineApi::get_client_version_v1(self, client_version))
+    }
+
+    /// Handler for `engine_getBlobsV1`
+    /// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_getblobsv1>
+    async fn get_blobs_v1(
+        &self,
+        versioned_hashes: Vec<H256>,
+    ) -> Result<Vec<Option<BlobAndProofV1>>> {
+        trace!(target: "rpc::eth", "Serving engine_getBlobsV1");
+        Ok(EngineApi::get_blobs_v1(self, versioned_hashes)?)
+    }
 }
 
 impl<Client> std::fmt::Debug for EngineApi<Client> {
@@ -411,10 +493,17 @@ mod tests {
     use super::*;
     use assert_matches::assert_matches;
     use reth_beacon_consensus::BeaconEngineMessage;
-    use reth_interfaces::test_utils::generators::random_block;
+    use reth_interfaces::test_utils::generators::{random_block, random_signed_tx};
     use reth_payload_builder::test_utils::spawn_test_payload_service;
-    use reth_primitives::{SealedBlock, H256, MAINNET};
+    use reth_primitives::{
+        BlobTransactionSidecar, Bytes, FromRecoveredTransaction, SealedBlock, MAINNET,
+    };
     use reth_provider::test_utils::MockEthProvider;
+    use reth_rpc_types::engine::ClientCode;
+    use reth_transaction_pool::{
+        test_utils::NoopTransactionValidator, CanonicalStateUpdate, CostOrdering, Pool,
+        PooledTransaction, TransactionPool,
+    };
     use std::sync::Arc;
     use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
@@ -428,11 +517,22 @@ mod tests {
             chain_spec.clone(),
             BeaconConsensusEngineHandle::new(to_engine),
             payload_store.into(),
+            BlobStore::default(),
+            test_client_version(ClientCode::RH),
         );
         let handle = EngineApiTestHandle { chain_spec, client, from_api: engine_rx };
         (handle, api)
     }
 
+    fn test_client_version(code: ClientCode) -> ClientVersionV1 {
+        ClientVersionV1 {
+            code,
+            name: code.to_string(),
+            version: "v0.1.0".to_string(),
+            commit: "00000000".to_string(),
+        }
+    }
+
     struct EngineApiTestHandle {
         chain_spec: Arc<ChainSpec>,
         client: Arc<MockEthProvider>,
@@ -449,6 +549,65 @@ mod tests {
         assert_matches!(handle.from_api.recv().await, Some(BeaconEngineMessage::NewPayload { .. }));
     }
 
+    #[tokio::test]
+    async fn exchanges_client_version() {
+        let (_, api) = setup_engine_api();
+        assert_eq!(api.consensus_client_version(), None);
+
+        let consensus_version = test_client_version(ClientCode::LH);
+        let res = api.get_client_version_v1(consensus_version.clone());
+        assert_eq!(res, vec![test_client_version(ClientCode::RH)]);
+        assert_eq!(api.consensus_client_version(), Some(consensus_version));
+    }
+
+    #[tokio::test]
+    async fn serves_blobs_of_pooled_transactions() {
+        let (_, mut api) = setup_engine_api();
+        let pool = Pool::new(
+            NoopTransactionValidator::<PooledTransaction>::default(),
+            CostOrdering::default(),
+            Default::default(),
+        );
+        api.blob_store = pool.blob_store().clone();
+
+        let sidecar = BlobTransactionSidecar {
+            blobs: vec![Bytes::from(vec![1; 32])],
+            commitments: vec![Bytes::from(vec![2; 48])],
+            proofs: vec![Bytes::from(vec![3; 48])],
+        };
+        let versioned_hash = sidecar.versioned_hashes().next().unwrap();
+        let tx = random_signed_tx().into_ecrecovered().unwrap();
+        let tx = PooledTransaction::from_recovered_transaction(tx).with_blob_sidecar(sidecar);
+        let hash = pool.add_external_transaction(tx).await.unwrap();
+
+        let res = api.get_blobs_v1(vec![versioned_hash, H256::random()]).unwrap();
+        assert_eq!(
+            res,
+            vec![
+                Some(BlobAndProofV1 {
+                    blob: Bytes::from(vec![1; 32]),
+                    proof: Bytes::from(vec![3; 48])
+                }),
+                None
+            ]
+        );
+
+        // the default store keeps no sidecars of included transactions
+        let info = pool.block_info();
+        let number = info.last_seen_block_number + 1;
+        pool.on_canonical_state_change(CanonicalStateUpdate {
+            hash: H256::random(),
+            number,
+            pending_block_base_fee: info.pending_basefee,
+            changed_accounts: vec![],
+            mined_transactions: vec![(number, hash)],
+        });
+        assert_eq!(api.get_blobs_v1(vec![versioned_hash]).unwrap(), vec![None]);
+
+        let res = api.get_blobs_v1(vec![H256::zero(); MAX_BLOBS_LIMIT as usize + 1]);
+        assert_matches!(res, Err(EngineApiError::PayloadRequestTooLarge { .. }));
+    }
+
     // tests covering `engine_getPayloadBodiesByRange` and `engine_getPayloadBodiesByHash`
     mod get_payload_bodies {
         use super::*;
@@ -1,4 +1,4 @@
-use reth_primitives::{NodeRecord, PeerId, H256, U256};
+use reth_primitives::{ChainSpec, ForkCondition, ForkId, Hardfork, NodeRecord, PeerId, H256, U256};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
@@ -14,6 +14,9 @@ use std::{
 pub struct NodeInfo {
     /// Enode of the node in URL format.
     pub enode: NodeRecord,
+    /// The base64 encoded ENR of the node, if discv4 is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enr: Option<String>,
     /// ID of the local node.
     pub id: PeerId,
     /// IP of the local node.
@@ -27,6 +30,9 @@ pub struct NodeInfo {
     pub name: String,
     /// Networking protocols being run by the local node.
     pub protocols: Protocols,
+    /// The bootnodes the node was configured with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bootnodes: Vec<NodeRecord>,
 }
 
 impl NodeInfo {
@@ -34,12 +40,14 @@ impl NodeInfo {
     pub fn new(enr: NodeRecord, status: NetworkStatus) -> NodeInfo {
         NodeInfo {
             enode: enr,
+            enr: status.enr,
             id: enr.id,
             ip: enr.address,
             listen_addr: enr.tcp_addr(),
             ports: Ports { discovery: enr.udp_port, listener: enr.tcp_port },
             name: status.client_version,
             protocols: Protocols { eth: status.eth_protocol_info, other: Default::default() },
+            bootnodes: status.boot_nodes,
         }
     }
 }
@@ -72,6 +80,12 @@ pub struct NetworkStatus {
     pub protocol_version: u64,
     /// Information about the Ethereum Wire Protocol.
     pub eth_protocol_info: EthProtocolInfo,
+    /// The bootnodes the network was configured with.
+    #[serde(default)]
+    pub boot_nodes: Vec<NodeRecord>,
+    /// The base64 encoded ENR of the node, if discv4 is enabled.
+    #[serde(default)]
+    pub enr: Option<String>,
 }
 
 /// Information about the Ethereum Wire Protocol (ETH)
@@ -86,11 +100,272 @@ pub struct EthProtocolInfo {
     pub network: u64,
     /// Genesis block of the current chain.
     pub genesis: H256,
+    /// The fork configuration of the current chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<ChainConfig>,
+}
+
+/// The fork configuration of a chain, in the format of Geth's genesis `config`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainConfig {
+    /// The chain id.
+    pub chain_id: u64,
+    /// Homestead switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homestead_block: Option<u64>,
+    /// DAO hard-fork switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dao_fork_block: Option<u64>,
+    /// Whether the node supports the DAO hard-fork.
+    #[serde(default)]
+    pub dao_fork_support: bool,
+    /// EIP-150 (Tangerine Whistle) switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip150_block: Option<u64>,
+    /// EIP-155 (Spurious Dragon) switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip155_block: Option<u64>,
+    /// EIP-158 (Spurious Dragon) switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip158_block: Option<u64>,
+    /// Byzantium switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byzantium_block: Option<u64>,
+    /// Constantinople switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constantinople_block: Option<u64>,
+    /// Petersburg switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub petersburg_block: Option<u64>,
+    /// Istanbul switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub istanbul_block: Option<u64>,
+    /// Muir Glacier switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muir_glacier_block: Option<u64>,
+    /// Berlin switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub berlin_block: Option<u64>,
+    /// London switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub london_block: Option<u64>,
+    /// Arrow Glacier switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arrow_glacier_block: Option<u64>,
+    /// Gray Glacier switch block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gray_glacier_block: Option<u64>,
+    /// The block that marks the merge for the fork id, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_netsplit_block: Option<u64>,
+    /// Shanghai switch time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shanghai_time: Option<u64>,
+    /// The total difficulty at which the merge happens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terminal_total_difficulty: Option<U256>,
+}
+
+impl From<&ChainSpec> for ChainConfig {
+    fn from(spec: &ChainSpec) -> Self {
+        let block = |fork| match spec.fork(fork) {
+            ForkCondition::Block(block) => Some(block),
+            _ => None,
+        };
+        let (merge_netsplit_block, terminal_total_difficulty) = match spec.fork(Hardfork::Paris) {
+            ForkCondition::TTD { fork_block, total_difficulty } => {
+                (fork_block, Some(total_difficulty))
+            }
+            _ => (None, None),
+        };
+        let shanghai_time = match spec.fork(Hardfork::Shanghai) {
+            ForkCondition::Timestamp(timestamp) => Some(timestamp),
+            _ => None,
+        };
+
+        Self {
+            chain_id: spec.chain().id(),
+            homestead_block: block(Hardfork::Homestead),
+            dao_fork_block: block(Hardfork::Dao),
+            dao_fork_support: block(Hardfork::Dao).is_some(),
+            eip150_block: block(Hardfork::Tangerine),
+            eip155_block: block(Hardfork::SpuriousDragon),
+            eip158_block: block(Hardfork::SpuriousDragon),
+            byzantium_block: block(Hardfork::Byzantium),
+            constantinople_block: block(Hardfork::Constantinople),
+            petersburg_block: block(Hardfork::Petersburg),
+            istanbul_block: block(Hardfork::Istanbul),
+            muir_glacier_block: block(Hardfork::MuirGlacier),
+            berlin_block: block(Hardfork::Berlin),
+            london_block: block(Hardfork::London),
+            arrow_glacier_block: block(Hardfork::ArrowGlacier),
+            gray_glacier_block: block(Hardfork::GrayGlacier),
+            merge_netsplit_block,
+            shanghai_time,
+            terminal_total_difficulty,
+        }
+    }
+}
+
+/// Represents a connected peer of the `admin_peers` response.
+///
+/// Note: this format is not standardized. Reth follows Geth's format, but additionally returns how
+/// long the peer is connected.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectedPeer {
+    /// Enode of the peer in URL format, with the address of the connection.
+    pub enode: NodeRecord,
+    /// ID of the peer.
+    pub id: PeerId,
+    /// Client version of the peer.
+    pub name: String,
+    /// Capabilities the peer announced, e.g. `eth/68`.
+    pub caps: Vec<String>,
+    /// Info about the connection to the peer.
+    pub network: ConnectedPeerNetwork,
+    /// Info about the sub-protocols of the session.
+    pub protocols: ConnectedPeerProtocols,
+}
+
+/// Info about the connection to a peer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectedPeerNetwork {
+    /// Address of the remote end of the connection.
+    pub remote_address: SocketAddr,
+    /// Whether the peer initiated the connection.
+    pub inbound: bool,
+    /// Seconds since the session was established.
+    pub connected_secs: u64,
+    /// Bytes exchanged with the peer over the connection, including the encryption overhead.
+    #[serde(default)]
+    pub bandwidth: PeerBandwidth,
+    /// Bytes of the messages exchanged with the peer, by capability name.
+    ///
+    /// The reserved `p2p` messages, e.g. pings, are listed as `p2p`.
+    #[serde(default)]
+    pub capability_bandwidth: BTreeMap<String, PeerBandwidth>,
+}
+
+/// The number of bytes exchanged with a peer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerBandwidth {
+    /// Bytes received from the peer.
+    pub ingress: u64,
+    /// Bytes sent to the peer.
+    pub egress: u64,
+}
+
+/// Info about the sub-protocols of a session.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectedPeerProtocols {
+    /// Info about the `eth` sub-protocol.
+    pub eth: EthPeerInfo,
+}
+
+/// Info about the `eth` sub-protocol of a session, from the peer's `Status` message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthPeerInfo {
+    /// The negotiated version of the protocol.
+    pub version: u64,
+    /// The total difficulty of the peer's head.
+    pub difficulty: U256,
+    /// The block hash of the peer's head.
+    pub head: H256,
+    /// The fork id of the peer.
+    pub fork_id: ForkId,
+}
+
+/// A banned peer of the `admin_bannedPeers` response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BannedPeer {
+    /// ID of the peer.
+    pub id: PeerId,
+    /// The unix timestamp in seconds at which the ban expires, `None` if it never expires.
+    pub expires_at: Option<u64>,
+}
+
+/// A peer lifecycle event streamed by the `admin_peerEvents` subscription.
+///
+/// Note: this format is not standardized. Reth follows Geth's format, but additionally reports
+/// failed handshakes, for which the peer may not be known yet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerEvent {
+    /// The kind of the event.
+    #[serde(rename = "type")]
+    pub kind: PeerEventKind,
+    /// ID of the peer, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer: Option<PeerId>,
+    /// The reason the session was dropped or the handshake failed, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The code of the RLPx disconnect reason, if the connection was closed with a disconnect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<u8>,
+    /// The RLPx disconnect reason, if the connection was closed with a disconnect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Address of the remote end of the connection.
+    pub remote: SocketAddr,
+}
+
+/// The kind of a [PeerEvent].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PeerEventKind {
+    /// A session with the peer was established.
+    Add,
+    /// The session with the peer was closed.
+    Drop,
+    /// The handshake with the remote failed before a session was established.
+    HandshakeFailed,
+}
+
+/// A disconnect of the `admin_recentDisconnects` response.
+///
+/// Note: this is a reth specific endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerDisconnect {
+    /// ID of the peer, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer: Option<PeerId>,
+    /// Address of the remote end of the connection.
+    pub remote: SocketAddr,
+    /// The side that closed the connection.
+    pub initiator: DisconnectInitiator,
+    /// The code of the RLPx disconnect reason, if a disconnect message was sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<u8>,
+    /// The RLPx disconnect reason, if a disconnect message was sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The error that closed the connection, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The unix timestamp in seconds at which the disconnect happened.
+    pub timestamp: u64,
+}
+
+/// The side of a connection that sent a [PeerDisconnect].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DisconnectInitiator {
+    /// The local node disconnected the peer.
+    Local,
+    /// The peer disconnected the local node.
+    Remote,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_primitives::ForkHash;
 
     #[test]
     fn test_parse_node_info_roundtrip() {
@@ -101,4 +376,123 @@ mod tests {
         let de_serialized: NodeInfo = serde_json::from_str(&serialized).unwrap();
         assert_eq!(info, de_serialized)
     }
+
+    #[test]
+    fn test_mainnet_chain_config() {
+        let config = ChainConfig::from(&*reth_primitives::MAINNET);
+        assert_eq!(config.chain_id, 1);
+        assert_eq!(config.homestead_block, Some(1_150_000));
+        assert_eq!(config.dao_fork_block, Some(1_920_000));
+        assert!(config.dao_fork_support);
+        assert_eq!(config.gray_glacier_block, Some(15_050_000));
+        assert_eq!(config.shanghai_time, Some(1_681_338_455));
+        assert!(config.terminal_total_difficulty.is_some());
+
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["chainId"], 1);
+        assert_eq!(value["eip150Block"], 2_463_000);
+        assert_eq!(value["shanghaiTime"], 1_681_338_455);
+    }
+
+    #[test]
+    fn test_serialize_connected_peer() {
+        let id = PeerId::random();
+        let remote_address: SocketAddr = "10.0.0.1:30303".parse().unwrap();
+        let peer = ConnectedPeer {
+            enode: NodeRecord::new(remote_address, id),
+            id,
+            name: "Geth/v1.11.5".to_string(),
+            caps: vec!["eth/67".to_string(), "eth/68".to_string()],
+            network: ConnectedPeerNetwork {
+                remote_address,
+                inbound: true,
+                connected_secs: 12,
+                bandwidth: PeerBandwidth { ingress: 2048, egress: 1024 },
+                capability_bandwidth: BTreeMap::from([(
+                    "eth".to_string(),
+                    PeerBandwidth { ingress: 1500, egress: 800 },
+                )]),
+            },
+            protocols: ConnectedPeerProtocols {
+                eth: EthPeerInfo {
+                    version: 68,
+                    difficulty: U256::from(1u64),
+                    head: H256::zero(),
+                    fork_id: ForkId { hash: ForkHash([0xfc, 0x64, 0xec, 0x04]), next: 1150000 },
+                },
+            },
+        };
+
+        let value = serde_json::to_value(&peer).unwrap();
+        assert_eq!(value["network"]["remoteAddress"], "10.0.0.1:30303");
+        assert_eq!(value["network"]["connectedSecs"], 12);
+        assert_eq!(value["network"]["bandwidth"]["ingress"], 2048);
+        assert_eq!(value["network"]["capabilityBandwidth"]["eth"]["egress"], 800);
+        assert!(value["protocols"]["eth"]["forkId"].is_object());
+        assert_eq!(serde_json::from_value::<ConnectedPeer>(value).unwrap(), peer);
+    }
+
+    #[test]
+    fn test_serialize_peer_event() {
+        let remote: SocketAddr = "10.0.0.1:30303".parse().unwrap();
+        let event = PeerEvent {
+            kind: PeerEventKind::HandshakeFailed,
+            peer: None,
+            error: Some("timeout".to_string()),
+            code: None,
+            reason: None,
+            remote,
+        };
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], "handshakeFailed");
+        assert!(value.get("peer").is_none());
+        assert!(value.get("reason").is_none());
+        assert_eq!(serde_json::from_value::<PeerEvent>(value).unwrap(), event);
+
+        let event = PeerEvent {
+            kind: PeerEventKind::Drop,
+            peer: Some(PeerId::random()),
+            error: None,
+            code: Some(4),
+            reason: Some("Too many peers".to_string()),
+            remote,
+        };
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["code"], 4);
+        assert_eq!(value["reason"], "Too many peers");
+        assert_eq!(serde_json::from_value::<PeerEvent>(value).unwrap(), event);
+    }
+
+    #[test]
+    fn test_serialize_peer_disconnect() {
+        let disconnect = PeerDisconnect {
+            peer: Some(PeerId::random()),
+            remote: "10.0.0.1:30303".parse().unwrap(),
+            initiator: DisconnectInitiator::Remote,
+            code: Some(4),
+            reason: Some("Too many peers".to_string()),
+            error: None,
+            timestamp: 1_681_338_455,
+        };
+
+        let value = serde_json::to_value(&disconnect).unwrap();
+        assert_eq!(value["initiator"], "remote");
+        assert_eq!(value["code"], 4);
+        assert!(value.get("error").is_none());
+        assert_eq!(serde_json::from_value::<PeerDisconnect>(value).unwrap(), disconnect);
+
+        // connections closed by an error don't necessarily have a disconnect reason
+        let disconnect = PeerDisconnect {
+            initiator: DisconnectInitiator::Local,
+            code: None,
+            reason: None,
+            error: Some("timeout".to_string()),
+            ..disconnect
+        };
+        let value = serde_json::to_value(&disconnect).unwrap();
+        assert!(value.get("code").is_none());
+        assert_eq!(value["error"], "timeout");
+        assert_eq!(serde_json::from_value::<PeerDisconnect>(value).unwrap(), disconnect);
+    }
 }
@@ -181,13 +181,15 @@ impl Block {
     pub fn uncle_block_from_header(header: PrimitiveHeader) -> Self {
         let hash = header.hash_slow();
         let rpc_header = Header::from_primitive_with_hash(header.clone().seal(hash));
+        // uncles never have withdrawals, the field is only included if the header commits to them
+        let withdrawals = header.withdrawals_root.map(|_| vec![]);
         let uncle_block = PrimitiveBlock { header, ..Default::default() };
         let size = Some(U256::from(uncle_block.length()));
         Self {
             uncles: vec![],
             header: rpc_header,
             transactions: BlockTransactions::Uncle,
-            withdrawals: Some(vec![]),
+            withdrawals,
             size,
             total_difficulty: None,
         }
@@ -386,6 +388,15 @@ mod tests {
         assert_eq!(BlockTransactionsKind::Hashes, full.into());
     }
 
+    #[test]
+    fn uncle_block_without_withdrawals() {
+        let uncle = Block::uncle_block_from_header(PrimitiveHeader::default());
+        let value = serde_json::to_value(&uncle).unwrap();
+        assert!(value.get("withdrawals").is_none());
+        assert!(value.get("transactions").is_none());
+        assert_eq!(value["uncles"], serde_json::json!([]));
+    }
+
     #[test]
     fn serde_header() {
         let resp = r#"{"jsonrpc":"2.0","method":"eth_subscribe","params":{"subscription":"0x7eef37ff35d471f8825b1c8f67a5d3c0","result":{"hash":"0x7a7ada12e140961a32395059597764416499f4178daf1917193fad7bd2cc6386","parentHash":"0xdedbd831f496e705e7f2ec3c8dcb79051040a360bf1455dbd7eb8ea6ad03b751","sha3Uncles":"0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347","miner":"0x0000000000000000000000000000000000000000","stateRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionsRoot":"0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421","receiptsRoot":"0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421","number":"0x8","gasUsed":"0x0","gasLimit":"0x1c9c380","extraData":"0x","logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","timestamp":"0x642aa48f","difficulty":"0x0","mixHash":"0x0000000000000000000000000000000000000000000000000000000000000000","nonce":"0x0000000000000000"}}}"#;
@@ -0,0 +1,152 @@
+use reth_primitives::{Address, BlockNumberOrTag, Bytes, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// Bundle of signed raw transactions to simulate with `eth_callBundle`, following the Flashbots
+/// schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundle {
+    /// The signed raw transactions of the bundle, executed in order.
+    pub txs: Vec<Bytes>,
+    /// The block the bundle is valid for.
+    pub block_number: U64,
+    /// The block whose state the bundle is executed on, usually the parent of `block_number`.
+    pub state_block_number: BlockNumberOrTag,
+    /// The timestamp of the simulated block, defaults to the timestamp of the state block + 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// The coinbase of the simulated block, defaults to the coinbase of the state block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coinbase: Option<Address>,
+    /// The gas limit of the simulated block, defaults to the gas limit of the state block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<u64>,
+    /// The difficulty of the simulated block, defaults to the difficulty of the state block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<U256>,
+    /// The base fee of the simulated block, defaults to the base fee that follows the state
+    /// block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee: Option<U256>,
+}
+
+/// The result of `eth_callBundle`.
+///
+/// The amounts of ether are serialized as decimal strings, like the Flashbots API does.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundleResponse {
+    /// The hash of the bundle: the keccak256 hash of the concatenated transaction hashes.
+    pub bundle_hash: H256,
+    /// The coinbase difference per gas used by the bundle.
+    #[serde(with = "u256_numeric_string")]
+    pub bundle_gas_price: U256,
+    /// How much the balance of the coinbase changed.
+    #[serde(with = "u256_numeric_string")]
+    pub coinbase_diff: U256,
+    /// How much ether the transactions transferred to the coinbase directly.
+    #[serde(with = "u256_numeric_string")]
+    pub eth_sent_to_coinbase: U256,
+    /// The priority fees paid by the transactions.
+    #[serde(with = "u256_numeric_string")]
+    pub gas_fees: U256,
+    /// The results of the transactions, in order.
+    pub results: Vec<CallBundleTransactionResult>,
+    /// The block whose state the bundle was executed on.
+    pub state_block_number: u64,
+    /// The gas used by all transactions.
+    pub total_gas_used: u64,
+}
+
+/// The result of a single transaction of a bundle.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundleTransactionResult {
+    /// The hash of the transaction.
+    pub tx_hash: H256,
+    /// The sender of the transaction.
+    pub from_address: Address,
+    /// The recipient of the transaction, `None` for contract creations.
+    pub to_address: Option<Address>,
+    /// The gas used by the transaction.
+    pub gas_used: u64,
+    /// The coinbase difference per gas used by the transaction.
+    #[serde(with = "u256_numeric_string")]
+    pub gas_price: U256,
+    /// The priority fees paid by the transaction.
+    #[serde(with = "u256_numeric_string")]
+    pub gas_fees: U256,
+    /// How much the balance of the coinbase changed.
+    #[serde(with = "u256_numeric_string")]
+    pub coinbase_diff: U256,
+    /// How much ether the transaction transferred to the coinbase directly.
+    #[serde(with = "u256_numeric_string")]
+    pub eth_sent_to_coinbase: U256,
+    /// The data returned by the transaction, if it succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Bytes>,
+    /// Why the transaction failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The decoded revert reason, if the transaction reverted with one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert: Option<String>,
+}
+
+/// (De)serializes a [U256] as a decimal string.
+mod u256_numeric_string {
+    use reth_primitives::U256;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    pub(crate) fn serialize<S: Serializer>(value: &U256, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&value.to_string())
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<U256, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        U256::from_str(&value).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_call_bundle() {
+        let s = r#"{
+            "txs": ["0x01", "0x02"],
+            "blockNumber": "0xa",
+            "stateBlockNumber": "latest",
+            "timestamp": 1680000000
+        }"#;
+        let bundle: CallBundle = serde_json::from_str(s).unwrap();
+        assert_eq!(bundle.txs.len(), 2);
+        assert_eq!(bundle.block_number, U64::from(10));
+        assert_eq!(bundle.state_block_number, BlockNumberOrTag::Latest);
+        assert_eq!(bundle.timestamp, Some(1680000000));
+        assert!(bundle.coinbase.is_none());
+    }
+
+    #[test]
+    fn test_serialize_call_bundle_response() {
+        let response = CallBundleResponse {
+            bundle_gas_price: U256::from(476190476193u64),
+            coinbase_diff: U256::from(20000000000126000u64),
+            state_block_number: 5221585,
+            total_gas_used: 42000,
+            ..Default::default()
+        };
+        let value = serde_json::to_value(&response).unwrap();
+        assert_eq!(value["bundleGasPrice"], "476190476193");
+        assert_eq!(value["coinbaseDiff"], "20000000000126000");
+        assert_eq!(value["totalGasUsed"], 42000);
+
+        let deserialized: CallBundleResponse = serde_json::from_value(value).unwrap();
+        assert_eq!(deserialized, response);
+    }
+}
@@ -1,5 +1,6 @@
-use reth_primitives::{AccessList, Address, Bytes, U256, U64};
-use serde::{Deserialize, Serialize};
+use crate::BlockOverrides;
+use reth_primitives::{AccessList, Address, BlockId, Bytes, U256, U64};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Call request
 #[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
@@ -37,3 +38,110 @@ impl CallRequest {
         self.gas_price.or(self.max_fee_per_gas)
     }
 }
+
+/// A bundle of calls with the block overrides they are executed with, see `eth_callMany`.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    /// The calls of the bundle, executed in order.
+    pub transactions: Vec<CallRequest>,
+    /// The header fields of the block the calls are executed in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_override: Option<BlockOverrides>,
+}
+
+/// The state the bundles of `eth_callMany` are executed on top of.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateContext {
+    /// The block the bundles are executed in, the latest block if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<BlockId>,
+    /// How many transactions of the block are executed before the bundles, all of them if not
+    /// set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_index: Option<TransactionIndex>,
+}
+
+/// The number of transactions of a block executed before the bundles of `eth_callMany`.
+///
+/// `-1` selects all transactions of the block.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum TransactionIndex {
+    /// All transactions of the block.
+    #[default]
+    All,
+    /// The first `n` transactions of the block.
+    Index(usize),
+}
+
+impl Serialize for TransactionIndex {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            TransactionIndex::All => serializer.serialize_i8(-1),
+            TransactionIndex::Index(idx) => idx.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TransactionIndex {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match isize::deserialize(deserializer)? {
+            -1 => Ok(TransactionIndex::All),
+            idx if idx < -1 => Err(serde::de::Error::custom(format!(
+                "invalid transaction index, expected -1 or a non-negative integer, got {idx}"
+            ))),
+            idx => Ok(TransactionIndex::Index(idx as usize)),
+        }
+    }
+}
+
+/// The result of a call of `eth_callMany`: its return data if it succeeded, why it failed
+/// otherwise.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EthCallResponse {
+    /// The data returned by the call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Bytes>,
+    /// Why the call failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::BlockNumberOrTag;
+
+    #[test]
+    fn serde_call_many_params() {
+        let s = r#"{"transactions":[{"to":"0x0000000000000000000000000000000000000001"}],"blockOverride":{"number":"0x2"}}"#;
+        let bundle = serde_json::from_str::<Bundle>(s).unwrap();
+        assert_eq!(bundle.transactions.len(), 1);
+        assert_eq!(bundle.block_override.unwrap().number, Some(U256::from(2)));
+
+        let s = r#"{"blockNumber":"latest","transactionIndex":-1}"#;
+        let ctx = serde_json::from_str::<StateContext>(s).unwrap();
+        assert_eq!(ctx.block_number, Some(BlockId::Number(BlockNumberOrTag::Latest)));
+        assert_eq!(ctx.transaction_index, Some(TransactionIndex::All));
+        assert_eq!(serde_json::to_string(&ctx).unwrap(), s);
+
+        let ctx = serde_json::from_str::<StateContext>(r#"{"transactionIndex":3}"#).unwrap();
+        assert_eq!(ctx.transaction_index, Some(TransactionIndex::Index(3)));
+        assert!(serde_json::from_str::<StateContext>(r#"{"transactionIndex":-2}"#).is_err());
+    }
+
+    #[test]
+    fn serde_call_response() {
+        let res = EthCallResponse { value: Some(Bytes::from(vec![1])), error: None };
+        assert_eq!(serde_json::to_string(&res).unwrap(), r#"{"value":"0x01"}"#);
+        let res = EthCallResponse { value: None, error: Some("out of gas".to_string()) };
+        assert_eq!(serde_json::to_string(&res).unwrap(), r#"{"error":"out of gas"}"#);
+    }
+}
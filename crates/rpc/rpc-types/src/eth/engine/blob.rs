@@ -0,0 +1,13 @@
+use reth_primitives::Bytes;
+use serde::{Deserialize, Serialize};
+
+/// A blob and its KZG proof, returned by `engine_getBlobsV1`.
+///
+/// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#blobandproofv1>
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobAndProofV1 {
+    /// The blob data.
+    pub blob: Bytes,
+    /// The KZG proof of the blob.
+    pub proof: Bytes,
+}
@@ -0,0 +1,116 @@
+//! Client identification: <https://github.com/ethereum/execution-apis/blob/main/src/engine/identification.md>
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The two-letter code identifying a client implementation.
+///
+/// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/identification.md#clientcode>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ClientCode {
+    /// Besu
+    #[serde(rename = "BU")]
+    BU,
+    /// EthereumJS
+    #[serde(rename = "EJ")]
+    EJ,
+    /// Erigon
+    #[serde(rename = "EG")]
+    EG,
+    /// Geth, go-ethereum
+    #[serde(rename = "GE")]
+    GE,
+    /// Grandine
+    #[serde(rename = "GR")]
+    GR,
+    /// Lighthouse
+    #[serde(rename = "LH")]
+    LH,
+    /// Lodestar
+    #[serde(rename = "LS")]
+    LS,
+    /// Nethermind
+    #[serde(rename = "NM")]
+    NM,
+    /// Nimbus
+    #[serde(rename = "NB")]
+    NB,
+    /// Teku
+    #[serde(rename = "TK")]
+    TK,
+    /// Prysm
+    #[serde(rename = "PM")]
+    PM,
+    /// Reth
+    #[serde(rename = "RH")]
+    RH,
+}
+
+impl ClientCode {
+    /// Returns the two-letter client code.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ClientCode::BU => "BU",
+            ClientCode::EJ => "EJ",
+            ClientCode::EG => "EG",
+            ClientCode::GE => "GE",
+            ClientCode::GR => "GR",
+            ClientCode::LH => "LH",
+            ClientCode::LS => "LS",
+            ClientCode::NM => "NM",
+            ClientCode::NB => "NB",
+            ClientCode::TK => "TK",
+            ClientCode::PM => "PM",
+            ClientCode::RH => "RH",
+        }
+    }
+}
+
+impl fmt::Display for ClientCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Contains information which identifies a client implementation.
+///
+/// See also <https://github.com/ethereum/execution-apis/blob/main/src/engine/identification.md#clientversionv1>
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientVersionV1 {
+    /// Client code, e.g. `RH` for reth
+    pub code: ClientCode,
+    /// Human-readable name of the client, e.g. `Reth`
+    pub name: String,
+    /// The version string of the current implementation, e.g. `v0.1.0`
+    pub version: String,
+    /// The first four bytes of the latest commit hash of this build, e.g. `fa4ff922`
+    pub commit: String,
+}
+
+impl fmt::Display for ClientVersionV1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}-{}", self.code, self.name, self.version, self.commit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_version_serde_roundtrip() {
+        let s = r#"{"code":"RH","name":"Reth","version":"v0.1.0","commit":"fa4ff922"}"#;
+        let version: ClientVersionV1 = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            version,
+            ClientVersionV1 {
+                code: ClientCode::RH,
+                name: "Reth".to_string(),
+                version: "v0.1.0".to_string(),
+                commit: "fa4ff922".to_string(),
+            }
+        );
+        assert_eq!(serde_json::to_string(&version).unwrap(), s);
+    }
+}
@@ -2,15 +2,17 @@
 
 #![allow(missing_docs)]
 
+mod blob;
 mod error;
 mod forkchoice;
+mod identification;
 mod payload;
 mod transition;
 
-pub use self::{error::*, forkchoice::*, payload::*, transition::*};
+pub use self::{blob::*, error::*, forkchoice::*, identification::*, payload::*, transition::*};
 
 /// The list of supported Engine capabilities
-pub const CAPABILITIES: [&str; 9] = [
+pub const CAPABILITIES: [&str; 11] = [
     "engine_forkchoiceUpdatedV1",
     "engine_forkchoiceUpdatedV2",
     "engine_exchangeTransitionConfigurationV1",
@@ -20,4 +22,6 @@ pub const CAPABILITIES: [&str; 9] = [
     "engine_newPayloadV2",
     "engine_getPayloadBodiesByHashV1",
     "engine_getPayloadBodiesByRangeV1",
+    "engine_getClientVersionV1",
+    "engine_getBlobsV1",
 ];
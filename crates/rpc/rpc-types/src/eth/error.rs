@@ -13,6 +13,8 @@ pub enum EthRpcErrorCode {
     /// > If the block is not found, the callee SHOULD raise a JSON-RPC error (the recommended
     /// > error code is -32001: Resource not found).
     ResourceNotFound,
+    /// Thrown when a request exceeds a limit of the node <https://eips.ethereum.org/EIPS/eip-1474>
+    LimitExceeded,
 }
 
 impl EthRpcErrorCode {
@@ -23,6 +25,7 @@ impl EthRpcErrorCode {
             EthRpcErrorCode::ExecutionError => 3,
             EthRpcErrorCode::InvalidInput => -32000,
             EthRpcErrorCode::ResourceNotFound => -32001,
+            EthRpcErrorCode::LimitExceeded => -32005,
         }
     }
 }
@@ -1,6 +1,6 @@
 use crate::Log;
 use jsonrpsee_types::SubscriptionId;
-use reth_primitives::H256;
+use reth_primitives::{H256, U64};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Response of the `eth_getFilterChanges` RPC.
@@ -60,6 +60,18 @@ impl<'de> Deserialize<'de> for FilterChanges {
     }
 }
 
+/// Response of the `eth_getLogsPage` RPC.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsPage {
+    /// Matching logs of all queried blocks.
+    pub logs: Vec<Log>,
+    /// The block to continue the query from if the server limits were reached before the end of
+    /// the requested range.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_block: Option<U64>,
+}
+
 /// Owned equivalent of [SubscriptionId]
 #[derive(Debug, PartialEq, Clone, Hash, Eq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -0,0 +1,30 @@
+use reth_primitives::constants::GWEI_TO_WEI;
+use serde::{Deserialize, Serialize};
+
+/// Settings for the gas price oracle behind `eth_gasPrice` and `eth_maxPriorityFeePerGas`
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GasPriceOracleConfig {
+    /// Number of recent blocks that are sampled.
+    pub blocks: u64,
+    /// The percentile of the sampled tips that is suggested, between `0` and `100`.
+    pub percentile: u64,
+    /// The highest tip that is suggested, in wei.
+    pub max_price: u64,
+    /// Tips below this are not sampled, in wei.
+    pub ignore_price: u64,
+    /// The tip suggested until there are samples, in wei.
+    pub default_price: u64,
+}
+
+impl Default for GasPriceOracleConfig {
+    fn default() -> Self {
+        Self {
+            blocks: 20,
+            percentile: 60,
+            max_price: 500 * GWEI_TO_WEI,
+            ignore_price: 2,
+            default_price: GWEI_TO_WEI,
+        }
+    }
+}
@@ -2,11 +2,13 @@
 
 mod account;
 mod block;
+mod bundle;
 mod call;
 pub mod engine;
 pub mod error;
 mod fee;
 mod filter;
+mod gas_oracle;
 mod index;
 mod log;
 pub mod pubsub;
@@ -18,9 +20,11 @@ mod work;
 
 pub use account::*;
 pub use block::*;
-pub use call::CallRequest;
+pub use bundle::*;
+pub use call::{Bundle, CallRequest, EthCallResponse, StateContext, TransactionIndex};
 pub use fee::{FeeHistory, FeeHistoryCache, FeeHistoryCacheItem, TxGasAndReward};
 pub use filter::*;
+pub use gas_oracle::GasPriceOracleConfig;
 pub use index::Index;
 pub use log::Log;
 pub use syncing::*;
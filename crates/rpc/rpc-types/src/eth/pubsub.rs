@@ -1,6 +1,6 @@
 //! Ethereum types for pub-sub
 
-use crate::{Log, RichHeader};
+use crate::{Log, RichHeader, Transaction};
 use reth_primitives::{filter::Filter, H256};
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
@@ -12,6 +12,8 @@ pub enum SubscriptionResult {
     Header(Box<RichHeader>),
     /// Log
     Log(Box<Log>),
+    /// Full transaction
+    FullTransaction(Box<Transaction>),
     /// Transaction hash
     TransactionHash(H256),
     /// SyncStatus
@@ -48,6 +50,7 @@ impl Serialize for SubscriptionResult {
         match *self {
             SubscriptionResult::Header(ref header) => header.serialize(serializer),
             SubscriptionResult::Log(ref log) => log.serialize(serializer),
+            SubscriptionResult::FullTransaction(ref tx) => tx.serialize(serializer),
             SubscriptionResult::TransactionHash(ref hash) => hash.serialize(serializer),
             SubscriptionResult::SyncState(ref sync) => sync.serialize(serializer),
         }
@@ -80,6 +83,8 @@ pub enum SubscriptionKind {
     /// with a key that is available in the node. When a transaction that was previously part of
     /// the canonical chain isn't part of the new canonical chain after a reorganization its again
     /// emitted.
+    ///
+    /// If the `true` parameter is passed, the full transactions are returned instead.
     NewPendingTransactions,
     /// Node syncing status subscription.
     ///
@@ -97,6 +102,8 @@ pub enum Params {
     None,
     /// Log parameters.
     Logs(Box<Filter>),
+    /// Boolean parameter, whether to return full transactions for `newPendingTransactions`.
+    Bool(bool),
 }
 
 impl Serialize for Params {
@@ -107,6 +114,7 @@ impl Serialize for Params {
         match self {
             Params::None => (&[] as &[serde_json::Value]).serialize(serializer),
             Params::Logs(logs) => logs.serialize(serializer),
+            Params::Bool(full) => full.serialize(serializer),
         }
     }
 }
@@ -122,8 +130,30 @@ impl<'a> Deserialize<'a> for Params {
             return Ok(Params::None)
         }
 
+        if let Some(full) = v.as_bool() {
+            return Ok(Params::Bool(full))
+        }
+
         serde_json::from_value(v)
             .map(|f| Params::Logs(Box::new(f)))
             .map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {e}")))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_params() {
+        assert_eq!(serde_json::from_str::<Params>("null").unwrap(), Params::None);
+        assert_eq!(serde_json::from_str::<Params>("true").unwrap(), Params::Bool(true));
+        assert!(matches!(
+            serde_json::from_str::<Params>(
+                r#"{"address":"0x0000000000000000000000000000000000000001"}"#
+            )
+            .unwrap(),
+            Params::Logs(_)
+        ));
+    }
+}
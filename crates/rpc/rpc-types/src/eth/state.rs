@@ -1,5 +1,6 @@
 //! bindings for state overrides in eth_call
 
+use crate::BlockOverrides;
 use reth_primitives::{Address, Bytes, H256, U256, U64};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -30,3 +31,60 @@ pub struct AccountOverride {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub state_diff: Option<HashMap<H256, H256>>,
 }
+
+/// The overrides of the state and of the block a call is executed with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EvmOverrides {
+    /// The account overrides applied to the state before the call.
+    pub state: Option<StateOverride>,
+    /// The header fields of the block the call is executed in.
+    pub block: Option<BlockOverrides>,
+}
+
+impl EvmOverrides {
+    /// Creates a new instance with the given overrides.
+    pub fn new(state: Option<StateOverride>, block: Option<BlockOverrides>) -> Self {
+        Self { state, block }
+    }
+
+    /// Creates a new instance with only state overrides.
+    pub fn state(state: Option<StateOverride>) -> Self {
+        Self { state, block: None }
+    }
+}
+
+impl From<Option<StateOverride>> for EvmOverrides {
+    fn from(state: Option<StateOverride>) -> Self {
+        Self::state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_state_override() {
+        let s = r#"{
+            "0x0000000000000000000000000000000000000124": {
+                "balance": "0x1",
+                "nonce": "0x2",
+                "code": "0x6080",
+                "stateDiff": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002"
+                }
+            }
+        }"#;
+        let state_override: StateOverride = serde_json::from_str(s).unwrap();
+        let account_override =
+            state_override.get(&Address::from_low_u64_be(0x124)).expect("account override");
+        assert_eq!(account_override.balance, Some(U256::from(1)));
+        assert_eq!(account_override.nonce, Some(U64::from(2)));
+        assert_eq!(account_override.code, Some(Bytes::from(vec![0x60, 0x80])));
+        assert!(account_override.state.is_none());
+        assert_eq!(
+            account_override.state_diff.as_ref().unwrap().get(&H256::from_low_u64_be(1)),
+            Some(&H256::from_low_u64_be(2))
+        );
+    }
+}
@@ -16,6 +16,25 @@ pub struct SyncInfo {
     pub warp_chunks_amount: Option<U256>,
     /// Warp sync snapshot chunks processed.
     pub warp_chunks_processed: Option<U256>,
+    /// The checkpoints of the individual sync stages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stages: Option<Vec<StageProgress>>,
+    /// Number of state entries downloaded so far.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pulled_states: Option<U256>,
+    /// Number of state entries known to be pending download.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub known_states: Option<U256>,
+}
+
+/// The progress of a single sync stage.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StageProgress {
+    /// Name of the stage
+    pub name: String,
+    /// The block the stage has progressed to
+    pub block: U64,
 }
 
 /// Peers info
@@ -158,3 +177,29 @@ pub struct ChainStatus {
     /// Describes the gap in the blockchain, if there is one: (first, last)
     pub block_gap: Option<(U256, U256)>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_sync_status_with_stages() {
+        let status = SyncStatus::Info(SyncInfo {
+            starting_block: U256::from(0),
+            current_block: U256::from(100),
+            highest_block: U256::from(200),
+            stages: Some(vec![
+                StageProgress { name: "Headers".to_string(), block: U64::from(200) },
+                StageProgress { name: "Execution".to_string(), block: U64::from(100) },
+            ]),
+            ..Default::default()
+        });
+        let s = serde_json::to_string(&status).unwrap();
+        assert_eq!(
+            s,
+            r#"{"startingBlock":"0x0","currentBlock":"0x64","highestBlock":"0xc8","warpChunksAmount":null,"warpChunksProcessed":null,"stages":[{"name":"Headers","block":"0xc8"},{"name":"Execution","block":"0x64"}]}"#
+        );
+        assert_eq!(serde_json::from_str::<SyncStatus>(&s).unwrap(), status);
+        assert_eq!(serde_json::from_str::<SyncStatus>("false").unwrap(), SyncStatus::None);
+    }
+}
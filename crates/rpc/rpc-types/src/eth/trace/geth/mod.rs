@@ -8,10 +8,10 @@ use std::collections::BTreeMap;
 
 // re-exports
 pub use self::{
-    call::{CallConfig, CallFrame},
+    call::{CallConfig, CallFrame, CallLogFrame},
     four_byte::FourByteFrame,
     noop::NoopFrame,
-    pre_state::{PreStateConfig, PreStateFrame},
+    pre_state::{AccountState, DiffMode, PreStateConfig, PreStateFrame, PreStateMode},
 };
 
 mod call;
@@ -34,6 +34,14 @@ pub struct BlockTraceResult {
     pub traces: Vec<TraceResult>,
 }
 
+/// The kind of a `debug_subscribe` subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DebugSubscriptionKind {
+    /// Streams the traces of a range of blocks, see `debug_traceChain`
+    TraceChain,
+}
+
 /// Geth Default trace frame
 ///
 /// <https://github.com/ethereum/go-ethereum/blob/a9ef135e2dd53682d106c6a2aede9187026cc1de/eth/tracers/logger/logger.go#L406-L411>
@@ -95,6 +103,8 @@ pub enum GethTraceFrame {
     FourByteTracer(FourByteFrame),
     CallTracer(CallFrame),
     PreStateTracer(PreStateFrame),
+    /// The result of a javascript tracer
+    JS(serde_json::Value),
 }
 
 impl From<DefaultFrame> for GethTraceFrame {
@@ -246,6 +256,14 @@ impl GethDebugTracerConfig {
         }
     }
 
+    /// Returns the config of a javascript tracer if it is one.
+    pub fn into_js_config(self) -> Option<serde_json::Value> {
+        match self {
+            GethDebugTracerConfig::JsTracer(cfg) => Some(cfg),
+            _ => None,
+        }
+    }
+
     /// Returns true if the config matches the given tracer
     pub fn matches_tracer(&self, tracer: &GethDebugTracerType) -> bool {
         match (self, tracer) {
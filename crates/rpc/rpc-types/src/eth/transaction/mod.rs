@@ -161,6 +161,16 @@ impl Transaction {
     }
 }
 
+/// Response of `eth_fillTransaction`: the request as unsigned transaction, with all fields filled
+/// in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FillTransactionResult {
+    /// The RLP encoding of the unsigned transaction
+    pub raw: Bytes,
+    /// The unsigned transaction, its signature values are zero
+    pub tx: Transaction,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -11,6 +11,12 @@
 
 mod admin;
 mod eth;
+mod otterscan;
+mod reth;
+mod txpool;
 
 pub use admin::*;
 pub use eth::*;
+pub use otterscan::*;
+pub use reth::*;
+pub use txpool::*;
@@ -0,0 +1,164 @@
+//! Types for the `ots_` namespace of the Otterscan block explorer.
+
+use crate::{Block, Transaction, TransactionReceipt};
+use reth_primitives::{Address, Bytes, H256, U256, U64};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The kind of an [InternalOperation], serialized as its number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationType {
+    /// A transfer of ether by a call
+    Transfer = 0,
+    /// A self-destruct that sends the balance to the beneficiary
+    SelfDestruct = 1,
+    /// A contract creation with `CREATE`
+    Create = 2,
+    /// A contract creation with `CREATE2`
+    Create2 = 3,
+}
+
+impl Serialize for OperationType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+impl<'de> Deserialize<'de> for OperationType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(OperationType::Transfer),
+            1 => Ok(OperationType::SelfDestruct),
+            2 => Ok(OperationType::Create),
+            3 => Ok(OperationType::Create2),
+            other => Err(de::Error::custom(format!("unknown operation type {other}"))),
+        }
+    }
+}
+
+/// A value transfer of a transaction that isn't visible in the transaction itself, returned by
+/// `ots_getInternalOperations`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternalOperation {
+    /// The kind of the operation
+    #[serde(rename = "type")]
+    pub r#type: OperationType,
+    /// The sender of the ether
+    pub from: Address,
+    /// The recipient of the ether, the created contract for contract creations
+    pub to: Address,
+    /// The transferred ether
+    pub value: U256,
+}
+
+/// A call of a transaction's call tree, returned by `ots_traceTransaction`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// The kind of the call, like `CALL`, `DELEGATECALL` or `CREATE`
+    #[serde(rename = "type")]
+    pub r#type: String,
+    /// The depth of the call in the call tree, the transaction's call is at depth `0`
+    pub depth: u32,
+    /// The caller
+    pub from: Address,
+    /// The callee, the created contract for contract creations
+    pub to: Address,
+    /// The transferred ether, `None` for calls that can't transfer ether
+    pub value: Option<U256>,
+    /// The call data, the init code for contract creations
+    pub input: Bytes,
+}
+
+/// A block with the number of its transactions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsBlock {
+    /// The block
+    #[serde(flatten)]
+    pub block: Block,
+    /// The number of transactions in the block
+    pub transaction_count: usize,
+}
+
+/// The ether issued by a block.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockIssuance {
+    /// The reward of the block's beneficiary, including the rewards for the included ommers
+    pub block_reward: U256,
+    /// The rewards of the beneficiaries of the included ommers
+    pub uncle_reward: U256,
+    /// The ether issued by the block
+    pub issuance: U256,
+}
+
+/// Response of `ots_getBlockDetails` and `ots_getBlockDetailsByHash`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockDetails {
+    /// The block, without its transactions
+    pub block: OtsBlock,
+    /// The ether issued by the block
+    pub issuance: BlockIssuance,
+    /// The fees paid by the block's transactions
+    pub total_fees: U256,
+}
+
+/// Response of `ots_getBlockTransactions`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsBlockTransactions {
+    /// The block with the transactions of the requested page
+    pub fullblock: OtsBlock,
+    /// The receipts of the transactions of the page, without logs
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+/// A transaction receipt with the timestamp of the block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsTransactionReceipt {
+    /// The receipt
+    #[serde(flatten)]
+    pub receipt: TransactionReceipt,
+    /// The timestamp of the block
+    pub timestamp: U64,
+}
+
+/// Response of `ots_searchTransactionsBefore` and `ots_searchTransactionsAfter`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsWithReceipts {
+    /// The transactions, the most recent first
+    pub txs: Vec<Transaction>,
+    /// The receipts of the transactions
+    pub receipts: Vec<OtsTransactionReceipt>,
+    /// Whether there are no more recent transactions
+    pub first_page: bool,
+    /// Whether there are no older transactions
+    pub last_page: bool,
+}
+
+/// Response of `ots_getContractCreator`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractCreator {
+    /// The hash of the transaction that created the contract
+    pub hash: H256,
+    /// The account that created the contract, a contract for contracts created by contracts
+    pub creator: Address,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_internal_operation() {
+        let s = r#"{"type":1,"from":"0x0000000000000000000000000000000000000001","to":"0x0000000000000000000000000000000000000002","value":"0x64"}"#;
+        let op: InternalOperation = serde_json::from_str(s).unwrap();
+        assert_eq!(op.r#type, OperationType::SelfDestruct);
+        assert_eq!(op.value, U256::from(100));
+        assert_eq!(serde_json::to_string(&op).unwrap(), s);
+
+        assert!(serde_json::from_str::<OperationType>("4").is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use reth_primitives::{PruneSegment, U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// Response type for `reth_pruneStatus`.
+///
+/// Describes which historical data the node is still able to serve.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneStatus {
+    /// Status of all prunable segments.
+    pub segments: Vec<PruneSegmentStatus>,
+    /// The most recent prune run of any segment, if any.
+    pub last_run: Option<PruneRun>,
+}
+
+/// The status of a single [PruneSegment].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneSegmentStatus {
+    /// The pruned segment.
+    pub segment: PruneSegment,
+    /// The lowest block for which the segment's data is available.
+    ///
+    /// `None` if no data of this segment is stored.
+    pub lowest_available_block: Option<U64>,
+}
+
+/// Outcome of a prune run triggered via `reth_prune`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneRun {
+    /// The pruned segment.
+    pub segment: PruneSegment,
+    /// All data of the segment below this block was pruned.
+    pub to_block: U64,
+    /// Number of deleted entries.
+    pub pruned: U64,
+    /// Unix timestamp of the last progress of the run, in seconds.
+    pub timestamp: U64,
+    /// Whether all data below `to_block` was pruned.
+    ///
+    /// This is `false` if the run was interrupted, pruning to the same block again resumes it.
+    pub finished: bool,
+}
+
+/// The balance change of an account in a block, see `reth_getBalanceChangesInBlock`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceChange {
+    /// The balance before the block.
+    pub before: U256,
+    /// The balance after the block.
+    pub after: U256,
+}
+
+/// Item of the `reth_subscribeSyncEvents` subscription.
+///
+/// Describes the progress of the sync pipeline and of prune runs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SyncEvent {
+    /// A stage is about to be executed.
+    StageStarted {
+        /// The id of the stage.
+        stage: String,
+        /// The checkpoint of the stage before execution, if it was executed before.
+        checkpoint: Option<U64>,
+    },
+    /// A stage was executed once.
+    StageFinished {
+        /// The id of the stage.
+        stage: String,
+        /// The new checkpoint of the stage.
+        checkpoint: U64,
+        /// Whether the stage reached the target of the pipeline.
+        done: bool,
+    },
+    /// A stage is about to be unwound.
+    UnwindStarted {
+        /// The id of the stage.
+        stage: String,
+        /// The checkpoint of the stage before the unwind.
+        checkpoint: U64,
+        /// The block the stage is unwound to.
+        #[serde(rename = "unwindTo")]
+        unwind_to: U64,
+        /// The bad block that caused the unwind, if any.
+        #[serde(rename = "badBlock")]
+        bad_block: Option<U64>,
+    },
+    /// A stage was unwound.
+    UnwindFinished {
+        /// The id of the stage.
+        stage: String,
+        /// The checkpoint of the stage after the unwind.
+        checkpoint: U64,
+    },
+    /// A stage failed to execute or unwind.
+    StageFailed {
+        /// The id of the stage.
+        stage: String,
+    },
+    /// A stage was skipped because it had nothing to do.
+    StageSkipped {
+        /// The id of the stage.
+        stage: String,
+    },
+    /// A segment was pruned.
+    PruneFinished(PruneRun),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_prune_status() {
+        let status = PruneStatus {
+            segments: vec![PruneSegmentStatus {
+                segment: PruneSegment::Receipts,
+                lowest_available_block: Some(U64::from(100)),
+            }],
+            last_run: Some(PruneRun {
+                segment: PruneSegment::Receipts,
+                to_block: U64::from(100),
+                pruned: U64::from(42),
+                timestamp: U64::from(1681000000),
+                finished: true,
+            }),
+        };
+        let s = serde_json::to_string(&status).unwrap();
+        assert_eq!(
+            s,
+            r#"{"segments":[{"segment":"receipts","lowestAvailableBlock":"0x64"}],"lastRun":{"segment":"receipts","toBlock":"0x64","pruned":"0x2a","timestamp":"0x64320640","finished":true}}"#
+        );
+        let deserialized: PruneStatus = serde_json::from_str(&s).unwrap();
+        assert_eq!(deserialized, status);
+    }
+
+    #[test]
+    fn serde_sync_event() {
+        let event = SyncEvent::UnwindStarted {
+            stage: "Execution".to_string(),
+            checkpoint: U64::from(100),
+            unwind_to: U64::from(90),
+            bad_block: Some(U64::from(91)),
+        };
+        let s = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            s,
+            r#"{"type":"unwindStarted","stage":"Execution","checkpoint":"0x64","unwindTo":"0x5a","badBlock":"0x5b"}"#
+        );
+        assert_eq!(serde_json::from_str::<SyncEvent>(&s).unwrap(), event);
+
+        let event = SyncEvent::PruneFinished(PruneRun {
+            segment: PruneSegment::Receipts,
+            to_block: U64::from(100),
+            pruned: U64::from(42),
+            timestamp: U64::from(1681000000),
+            finished: true,
+        });
+        let s = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            s,
+            r#"{"type":"pruneFinished","segment":"receipts","toBlock":"0x64","pruned":"0x2a","timestamp":"0x64320640","finished":true}"#
+        );
+        assert_eq!(serde_json::from_str::<SyncEvent>(&s).unwrap(), event);
+    }
+}
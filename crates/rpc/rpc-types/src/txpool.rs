@@ -0,0 +1,123 @@
+//! Types for the `txpool_` namespace.
+
+use crate::Transaction;
+use reth_primitives::{Address, U256, U64};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeMap, str::FromStr};
+
+/// Response of `txpool_status`: the number of transactions in the pool.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolStatus {
+    /// The number of transactions that are ready to be included in the next block
+    pub pending: U64,
+    /// The number of transactions that can't be included in the next block yet
+    pub queued: U64,
+}
+
+/// Response of `txpool_content`: the transactions in the pool, grouped by sender and nonce.
+///
+/// The nonces are serialized as decimal strings, like geth does.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolContent {
+    /// The transactions that are ready to be included in the next block
+    pub pending: BTreeMap<Address, BTreeMap<String, Transaction>>,
+    /// The transactions that can't be included in the next block yet
+    pub queued: BTreeMap<Address, BTreeMap<String, Transaction>>,
+}
+
+/// Response of `txpool_contentFrom`: the transactions of a single sender, grouped by nonce.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolContentFrom {
+    /// The transactions that are ready to be included in the next block
+    pub pending: BTreeMap<String, Transaction>,
+    /// The transactions that can't be included in the next block yet
+    pub queued: BTreeMap<String, Transaction>,
+}
+
+/// Response of `txpool_inspect`: summaries of the transactions in the pool, grouped by sender and
+/// nonce.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolInspect {
+    /// The transactions that are ready to be included in the next block
+    pub pending: BTreeMap<Address, BTreeMap<String, TxpoolInspectSummary>>,
+    /// The transactions that can't be included in the next block yet
+    pub queued: BTreeMap<Address, BTreeMap<String, TxpoolInspectSummary>>,
+}
+
+/// The summary of a transaction returned by `txpool_inspect`.
+///
+/// Serialized as a string like geth does: `<to>: <value> wei + <gas> gas × <gas price> wei`, with
+/// `contract creation` instead of the recipient for contract creations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxpoolInspectSummary {
+    /// The recipient, `None` for contract creations
+    pub to: Option<Address>,
+    /// The transferred ether
+    pub value: U256,
+    /// The gas limit
+    pub gas: U256,
+    /// The gas price, the fee cap for EIP-1559 transactions
+    pub gas_price: U256,
+}
+
+impl Serialize for TxpoolInspectSummary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let to = match self.to {
+            Some(to) => format!("{to:?}"),
+            None => "contract creation".to_string(),
+        };
+        serializer.serialize_str(&format!(
+            "{to}: {} wei + {} gas × {} wei",
+            self.value, self.gas, self.gas_price
+        ))
+    }
+}
+
+impl<'de> Deserialize<'de> for TxpoolInspectSummary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let invalid = || de::Error::custom(format!("invalid txpool summary {s}"));
+
+        let (to, rest) = s.split_once(": ").ok_or_else(invalid)?;
+        let to = match to {
+            "contract creation" => None,
+            to => Some(Address::from_str(to).map_err(|_| invalid())?),
+        };
+
+        let parts = rest.split_whitespace().collect::<Vec<_>>();
+        let [value, "wei", "+", gas, "gas", "×", gas_price, "wei"] = parts[..] else {
+            return Err(invalid())
+        };
+        let parse = |n: &str| U256::from_str(n).map_err(|_| invalid());
+
+        Ok(Self { to, value: parse(value)?, gas: parse(gas)?, gas_price: parse(gas_price)? })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_txpool_inspect_summary() {
+        let summary = TxpoolInspectSummary {
+            to: Some(Address::from_low_u64_be(1)),
+            value: U256::from(100),
+            gas: U256::from(21000),
+            gas_price: U256::from(1_000_000_000u64),
+        };
+        let s = serde_json::to_string(&summary).unwrap();
+        assert_eq!(
+            s,
+            r#""0x0000000000000000000000000000000000000001: 100 wei + 21000 gas × 1000000000 wei""#
+        );
+        assert_eq!(serde_json::from_str::<TxpoolInspectSummary>(&s).unwrap(), summary);
+
+        let s = r#""contract creation: 0 wei + 53000 gas × 2 wei""#;
+        let summary = serde_json::from_str::<TxpoolInspectSummary>(s).unwrap();
+        assert_eq!(summary.to, None);
+        assert_eq!(summary.gas, U256::from(53000));
+
+        assert!(serde_json::from_str::<TxpoolInspectSummary>(r#""0x01: 1 wei""#).is_err());
+    }
+}
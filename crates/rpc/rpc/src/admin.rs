@@ -1,10 +1,12 @@
-use crate::result::{internal_rpc_err, ToRpcResult};
+use crate::result::ToRpcResult;
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use futures::StreamExt;
+use jsonrpsee::{core::RpcResult, types::SubscriptionResult, SubscriptionSink};
 use reth_network_api::{NetworkInfo, PeerKind, Peers};
 use reth_primitives::NodeRecord;
 use reth_rpc_api::AdminApiServer;
-use reth_rpc_types::NodeInfo;
+use reth_rpc_types::{BannedPeer, ConnectedPeer, NodeInfo, PeerDisconnect};
+use tokio_stream::wrappers::BroadcastStream;
 
 /// `admin` API implementation.
 ///
@@ -27,13 +29,17 @@ where
     N: NetworkInfo + Peers + 'static,
 {
     /// Handler for `admin_addPeer`
+    ///
+    /// Like geth, the peer is added as static peer, so it's redialed if the session drops.
     fn add_peer(&self, record: NodeRecord) -> RpcResult<bool> {
-        self.network.add_peer(record.id, record.tcp_addr());
+        self.network.add_static_peer(record.id, record.tcp_addr());
         Ok(true)
     }
 
     /// Handler for `admin_removePeer`
     fn remove_peer(&self, record: NodeRecord) -> RpcResult<bool> {
+        // static peers are kept by the peer set, so it needs to be demoted first
+        self.network.remove_peer(record.id, PeerKind::Static);
         self.network.remove_peer(record.id, PeerKind::Basic);
         Ok(true)
     }
@@ -50,12 +56,38 @@ where
         Ok(true)
     }
 
+    /// Handler for `admin_peers`
+    async fn peers(&self) -> RpcResult<Vec<ConnectedPeer>> {
+        self.network.connected_peers().await.to_rpc_result()
+    }
+
+    /// Handler for `admin_bannedPeers`
+    async fn banned_peers(&self) -> RpcResult<Vec<BannedPeer>> {
+        self.network.banned_peers().await.to_rpc_result()
+    }
+
+    /// Handler for `admin_clearBans`
+    fn clear_bans(&self) -> RpcResult<bool> {
+        self.network.clear_bans();
+        Ok(true)
+    }
+
+    /// Handler for `admin_recentDisconnects`
+    async fn recent_disconnects(&self) -> RpcResult<Vec<PeerDisconnect>> {
+        self.network.recent_disconnects().await.to_rpc_result()
+    }
+
     /// Handler for `admin_peerEvents`
-    fn subscribe_peer_events(
-        &self,
-        sink: jsonrpsee::SubscriptionSink,
-    ) -> jsonrpsee::types::SubscriptionResult {
-        sink.close(internal_rpc_err("admin_peerEvents is not implemented yet"));
+    fn subscribe_peer_events(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+        sink.accept()?;
+
+        // events missed by a lagging subscriber are skipped
+        let stream = BroadcastStream::new(self.network.peer_events())
+            .filter_map(|event| futures::future::ready(event.ok()));
+        tokio::spawn(async move {
+            sink.pipe_from_stream(stream).await;
+        });
+
         Ok(())
     }
 
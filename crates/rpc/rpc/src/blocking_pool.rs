@@ -0,0 +1,219 @@
+//! A dedicated thread pool for expensive RPC calls
+
+use futures::Future;
+use serde::{Deserialize, Serialize};
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::Arc,
+};
+use tokio::{
+    runtime::Handle,
+    sync::{oneshot, Semaphore},
+};
+
+/// What happens to new tasks if the queue of the [BlockingTaskPool] is full.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RejectionPolicy {
+    /// Reject new tasks with [BlockingTaskPoolError::QueueFull].
+    #[default]
+    Reject,
+    /// Wait until a queued task finished.
+    Wait,
+}
+
+/// Settings for the [BlockingTaskPool]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockingTaskPoolConfig {
+    /// Number of threads in the pool, `0` uses the number of available cores.
+    pub num_threads: usize,
+    /// Number of threads that execute parts of a single call in parallel, e.g. the transactions
+    /// of a traced block, `0` uses the number of available cores.
+    #[serde(default)]
+    pub num_parallel_threads: usize,
+    /// Maximum number of tasks that are queued or executing, `0` means no limit.
+    pub max_queued_tasks: usize,
+    /// What happens to new tasks if the queue is full.
+    pub rejection_policy: RejectionPolicy,
+}
+
+impl Default for BlockingTaskPoolConfig {
+    fn default() -> Self {
+        Self {
+            num_threads: 0,
+            num_parallel_threads: 0,
+            max_queued_tasks: 256,
+            rejection_policy: RejectionPolicy::Reject,
+        }
+    }
+}
+
+/// Errors returned by [BlockingTaskPool::spawn].
+#[derive(Debug, thiserror::Error)]
+pub enum BlockingTaskPoolError {
+    /// Thrown if the queue is full and the [RejectionPolicy] is [RejectionPolicy::Reject].
+    #[error("too many expensive requests in progress, try again later")]
+    QueueFull,
+    /// Thrown if the task panicked.
+    #[error("blocking task panicked")]
+    Panicked,
+}
+
+/// A bounded pool of threads for expensive calls like tracing or large log queries.
+///
+/// Running these on the async runtime would block the runtime's worker threads and starve cheap
+/// calls and the engine API. The number of tasks that are queued or executing is limited, new
+/// tasks are handled according to the configured [RejectionPolicy] once the limit is reached.
+///
+/// Calls can split their work across a separate set of threads with [BlockingTaskPool::install].
+/// These never execute spawned tasks, so a call that waits for its parallel work can't end up
+/// driving another call on the same thread.
+#[derive(Debug, Clone)]
+pub struct BlockingTaskPool {
+    pool: Arc<rayon::ThreadPool>,
+    /// Threads for the parallel work of a single call.
+    parallel: Arc<rayon::ThreadPool>,
+    /// Permits for queued and executing tasks.
+    queue: Arc<Semaphore>,
+    rejection_policy: RejectionPolicy,
+}
+
+// === impl BlockingTaskPool ===
+
+impl BlockingTaskPool {
+    /// Spawns the threads of a new pool with the given settings.
+    pub fn new(config: BlockingTaskPoolConfig) -> Result<Self, rayon::ThreadPoolBuildError> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(config.num_threads)
+            .thread_name(|idx| format!("rpc-blocking-{idx}"))
+            .build()?;
+        let parallel = rayon::ThreadPoolBuilder::new()
+            .num_threads(config.num_parallel_threads)
+            .thread_name(|idx| format!("rpc-parallel-{idx}"))
+            .build()?;
+        let permits = match config.max_queued_tasks {
+            0 => Semaphore::MAX_PERMITS,
+            max => max,
+        };
+        Ok(Self {
+            pool: Arc::new(pool),
+            parallel: Arc::new(parallel),
+            queue: Arc::new(Semaphore::new(permits)),
+            rejection_policy: config.rejection_policy,
+        })
+    }
+
+    /// Drives the future to completion on one of the pool's threads and returns its output.
+    ///
+    /// Must be called from within a tokio runtime, which the future is entered into.
+    pub async fn spawn<F, R>(&self, fut: F) -> Result<R, BlockingTaskPoolError>
+    where
+        F: Future<Output = R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let permit = match self.rejection_policy {
+            RejectionPolicy::Reject => self.queue.clone().try_acquire_owned().ok(),
+            RejectionPolicy::Wait => self.queue.clone().acquire_owned().await.ok(),
+        }
+        .ok_or(BlockingTaskPoolError::QueueFull)?;
+
+        let handle = Handle::current();
+        let (tx, rx) = oneshot::channel();
+        self.pool.spawn(move || {
+            let res = catch_unwind(AssertUnwindSafe(|| handle.block_on(fut)));
+            drop(permit);
+            // the sender is dropped if the task panicked
+            if let Ok(res) = res {
+                let _ = tx.send(res);
+            }
+        });
+
+        rx.await.map_err(|_| BlockingTaskPoolError::Panicked)
+    }
+
+    /// Returns the number of threads available for the parallel work of a single call.
+    pub fn num_parallel_threads(&self) -> usize {
+        self.parallel.current_num_threads()
+    }
+
+    /// Executes the operation on the threads for parallel work and blocks until it completes.
+    ///
+    /// Rayon's parallel iterators used within the operation are executed on these threads.
+    pub fn install<OP, R>(&self, op: OP) -> R
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        self.parallel.install(op)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(rejection_policy: RejectionPolicy) -> BlockingTaskPool {
+        BlockingTaskPool::new(BlockingTaskPoolConfig {
+            num_threads: 1,
+            num_parallel_threads: 2,
+            max_queued_tasks: 1,
+            rejection_policy,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn rejects_tasks_if_queue_is_full() {
+        let pool = pool(RejectionPolicy::Reject);
+        assert_eq!(pool.spawn(async { 1 }).await.unwrap(), 1);
+
+        let (unblock, blocked) = std::sync::mpsc::channel::<()>();
+        let task = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.spawn(async move { blocked.recv().unwrap() }).await }
+        });
+        while pool.queue.available_permits() > 0 {
+            tokio::task::yield_now().await;
+        }
+
+        assert!(matches!(pool.spawn(async {}).await, Err(BlockingTaskPoolError::QueueFull)));
+        unblock.send(()).unwrap();
+        task.await.unwrap().unwrap();
+        assert!(pool.spawn(async {}).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn waits_for_queue() {
+        let pool = pool(RejectionPolicy::Wait);
+        let tasks = (0..4).map(|i| pool.spawn(async move { i }));
+        let results = futures::future::try_join_all(tasks).await.unwrap();
+        assert_eq!(results, vec![0, 1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn panics_are_caught() {
+        let pool = pool(RejectionPolicy::Reject);
+        assert!(matches!(
+            pool.spawn(async { panic!("boom") }).await,
+            Err(BlockingTaskPoolError::Panicked)
+        ));
+        // the permit is released
+        assert!(pool.spawn(async {}).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn parallel_work_within_task() {
+        use rayon::prelude::*;
+
+        let pool = pool(RejectionPolicy::Reject);
+        let sum = pool
+            .spawn({
+                let pool = pool.clone();
+                async move { pool.install(|| (0..100u64).into_par_iter().sum::<u64>()) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(sum, 4950);
+    }
+}
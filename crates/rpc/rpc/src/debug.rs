@@ -1,14 +1,20 @@
 use crate::{
     eth::{
+        cache::EthStateCache,
         error::{EthApiError, EthResult},
-        revm_utils::inspect,
+        revm_utils::{inspect, replay_transactions_with, transact},
         EthTransactions, TransactionSource,
     },
     result::{internal_rpc_err, ToRpcResult},
-    EthApiSpec, TracingCallGuard,
+    BlockingTaskPool, EthApiSpec, TraceCache, TraceCacheKey, TraceSize, TracingCallGuard,
 };
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use futures::{Future, Stream, StreamExt};
+use jsonrpsee::{
+    core::{Error as RpcError, RpcResult},
+    types::SubscriptionResult,
+    SubscriptionSink,
+};
 use reth_primitives::{Block, BlockId, BlockNumberOrTag, Bytes, H256, U256};
 use reth_provider::{BlockProvider, HeaderProvider, StateProviderBox};
 use reth_revm::{
@@ -19,35 +25,134 @@ use reth_revm::{
 use reth_rlp::{Decodable, Encodable};
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_types::{
+    state::EvmOverrides,
     trace::geth::{
-        BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
-        GethDebugTracingCallOptions, GethDebugTracingOptions, GethTraceFrame, NoopFrame,
-        TraceResult,
+        BlockTraceResult, DebugSubscriptionKind, FourByteFrame, GethDebugBuiltInTracerType,
+        GethDebugTracerType, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTraceFrame,
+        NoopFrame, TraceResult,
     },
     BlockError, CallRequest, RichBlock,
 };
-use revm::primitives::Env;
+use reth_tasks::{TaskSpawner, TokioTaskExecutor};
+use revm::{
+    primitives::{Env, State as StateChanges},
+    DatabaseCommit,
+};
+#[cfg(feature = "js-tracer")]
+use std::time::Duration;
+use tokio::sync::{AcquireError, OwnedSemaphorePermit};
+
+/// The maximum number of blocks a single `debug_traceChain` subscription traces.
+pub const MAX_TRACE_CHAIN_BLOCKS: u64 = 1_000;
 
 /// `debug` API implementation.
 ///
 /// This type provides the functionality for handling `debug` related requests.
+#[derive(Clone)]
 #[non_exhaustive]
 pub struct DebugApi<Client, Eth> {
     /// The client that can interact with the chain.
     client: Client,
     /// The implementation of `eth` API
     eth_api: Eth,
-
+    /// The async cache frontend for eth related data
+    eth_cache: EthStateCache,
     // restrict the number of concurrent calls to `debug_traceTransaction`
     tracing_call_guard: TracingCallGuard,
+    /// Cache for the traces of recently traced blocks and transactions
+    trace_cache: Option<TraceCache>,
+    /// The pool tracing calls are executed on
+    blocking_pool: Option<BlockingTaskPool>,
+    /// The type that spawns the tasks of subscriptions
+    subscription_task_spawner: Box<dyn TaskSpawner>,
 }
 
 // === impl DebugApi ===
 
 impl<Client, Eth> DebugApi<Client, Eth> {
     /// Create a new instance of the [DebugApi]
-    pub fn new(client: Client, eth: Eth, tracing_call_guard: TracingCallGuard) -> Self {
-        Self { client, eth_api: eth, tracing_call_guard }
+    pub fn new(
+        client: Client,
+        eth: Eth,
+        eth_cache: EthStateCache,
+        tracing_call_guard: TracingCallGuard,
+    ) -> Self {
+        Self {
+            client,
+            eth_api: eth,
+            eth_cache,
+            tracing_call_guard,
+            trace_cache: None,
+            blocking_pool: None,
+            subscription_task_spawner: Box::<TokioTaskExecutor>::default(),
+        }
+    }
+
+    /// Caches the results of `debug_traceBlock*` and `debug_traceTransaction` in the given
+    /// [TraceCache].
+    pub fn with_trace_cache(mut self, trace_cache: TraceCache) -> Self {
+        self.trace_cache = Some(trace_cache);
+        self
+    }
+
+    /// Executes tracing calls on the given [BlockingTaskPool] instead of the async runtime.
+    pub fn with_blocking_pool(mut self, blocking_pool: BlockingTaskPool) -> Self {
+        self.blocking_pool = Some(blocking_pool);
+        self
+    }
+
+    /// Spawns the tasks of `debug_subscribe` subscriptions with the given [TaskSpawner] instead
+    /// of on the current tokio runtime.
+    pub fn with_subscription_task_spawner(
+        mut self,
+        subscription_task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        self.subscription_task_spawner = subscription_task_spawner;
+        self
+    }
+
+    /// Acquires a permit to execute a tracing call.
+    async fn acquire_trace_permit(
+        &self,
+    ) -> std::result::Result<OwnedSemaphorePermit, AcquireError> {
+        self.tracing_call_guard.clone().acquire_owned().await
+    }
+
+    /// Returns the cached result for the given key, if caching is enabled.
+    fn cached<T>(&self, key: Option<&TraceCacheKey>) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.trace_cache.as_ref()?.get(key?)
+    }
+
+    /// Caches the result for the given key, if caching is enabled.
+    fn cache<T>(&self, key: Option<TraceCacheKey>, value: &T)
+    where
+        T: Clone + TraceSize + Send + Sync + 'static,
+    {
+        if let (Some(cache), Some(key)) = (&self.trace_cache, key) {
+            cache.insert(key, value.clone());
+        }
+    }
+}
+
+impl<Client, Eth> DebugApi<Client, Eth>
+where
+    Client: Clone + 'static,
+    Eth: Clone + 'static,
+{
+    /// Runs the call on the [BlockingTaskPool], if configured.
+    async fn on_blocking_pool<F, Fut, R>(&self, f: F) -> EthResult<R>
+    where
+        F: FnOnce(Self) -> Fut,
+        Fut: Future<Output = EthResult<R>> + Send + 'static,
+        R: Send + 'static,
+    {
+        match &self.blocking_pool {
+            Some(pool) => pool.spawn(f(self.clone())).await?,
+            None => f(self.clone()).await,
+        }
     }
 }
 
@@ -78,6 +183,9 @@ where
     }
 
     /// Replays a block and returns the trace of each transaction.
+    ///
+    /// The transactions are traced in parallel on the [BlockingTaskPool], if configured, see
+    /// `replay_transactions_with`. A transaction whose trace fails is reported with the error.
     pub async fn debug_trace_block(
         &self,
         block_id: BlockId,
@@ -87,57 +195,127 @@ where
             .client
             .block_hash_for_id(block_id)?
             .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let key = debug_tracer(&opts).map(|tracer| TraceCacheKey::block(block_hash, tracer));
+        if let Some(results) = self.cached(key.as_ref()) {
+            return Ok(results)
+        }
 
-        let ((cfg, block_env, at), transactions) = futures::try_join!(
+        let ((cfg, block_env, _), transactions) = futures::try_join!(
             self.eth_api.evm_env_at(block_hash.into()),
             self.eth_api.transactions_by_block(block_hash),
         )?;
         let transactions = transactions.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        // the transactions are replayed on top of the state of the parent block
+        let parent = self
+            .client
+            .header(&block_hash)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?
+            .parent_hash;
 
-        // replay all transactions of the block
-        self.eth_api.with_state_at(at, move |state| {
-            let mut results = Vec::with_capacity(transactions.len());
-            let mut db = SubState::new(State::new(state));
-
-            for tx in transactions {
+        let envs = transactions
+            .into_iter()
+            .map(|tx| {
                 let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
                 let tx = tx_env_with_recovered(&tx);
-                let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
+                Ok(Env { cfg: cfg.clone(), block: block_env.clone(), tx })
+            })
+            .collect::<EthResult<Vec<_>>>()?;
+
+        // replay all transactions of the block, in parallel if a blocking pool is configured
+        let results = replay_transactions_with(
+            &self.eth_api,
+            BlockId::Hash(parent.into()),
+            envs,
+            self.blocking_pool.as_ref(),
+            |_, env, db| {
                 // TODO(mattsse): get rid of clone by extracting necessary opts fields into a struct
-                let result = trace_transaction(opts.clone(), env, &mut db)?;
-                results.push(TraceResult::Success { result });
-            }
+                match trace_transaction(opts.clone(), env.clone(), db) {
+                    Ok((result, changes)) => Ok((TraceResult::Success { result }, changes)),
+                    // like geth, a failed trace is reported for the transaction instead of failing
+                    // the whole block, which still needs its state changes for the following
+                    // transactions
+                    Err(err) => {
+                        let (res, _) = transact(db, env)?;
+                        Ok((TraceResult::Error { error: err.to_string() }, res.state))
+                    }
+                }
+            },
+        )?;
 
-            Ok(results)
-        })
+        self.cache(key, &results);
+        Ok(results)
     }
 
     /// Trace the transaction according to the provided options.
     ///
+    /// Mined transactions are traced on top of the state of the parent block, after replaying the
+    /// transactions that precede the traced one in its block.
+    ///
     /// Ref: <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
     pub async fn debug_trace_transaction(
         &self,
         tx_hash: H256,
         opts: GethDebugTracingOptions,
     ) -> EthResult<GethTraceFrame> {
+        let key = debug_tracer(&opts).map(|tracer| TraceCacheKey::transaction(tx_hash, tracer));
+        if let Some(frame) = self.cached(key.as_ref()) {
+            return Ok(frame)
+        }
+
         let (transaction, at) = match self.eth_api.transaction_by_hash_at(tx_hash).await? {
             None => return Err(EthApiError::TransactionNotFound),
             Some(res) => res,
         };
+        // transactions that are not mined yet are traced on top of the pending state
+        let key = key.filter(|_| matches!(transaction, TransactionSource::Database { .. }));
 
         let (cfg, block, at) = self.eth_api.evm_env_at(at).await?;
 
-        self.eth_api.with_state_at(at, |state| {
-            let tx = transaction.into_recovered();
+        let (tx, at, preceding) = match transaction {
+            TransactionSource::Pool(tx) => (tx, at, Vec::new()),
+            TransactionSource::Database { transaction, index, block_hash, .. } => {
+                let parent = self
+                    .client
+                    .header(&block_hash)?
+                    .ok_or_else(|| EthApiError::UnknownBlockNumber)?
+                    .parent_hash;
+                let mut transactions = self
+                    .eth_api
+                    .transactions_by_block(block_hash)
+                    .await?
+                    .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+                transactions.truncate(index as usize);
+                (transaction, BlockId::Hash(parent.into()), transactions)
+            }
+        };
+
+        let frame = self.eth_api.with_state_at(at, |state| {
+            let mut db = SubState::new(State::new(state));
+
+            // replay the preceding transactions of the block to get the state the transaction
+            // was executed on
+            for tx in preceding {
+                let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
+                let tx = tx_env_with_recovered(&tx);
+                let env = Env { cfg: cfg.clone(), block: block.clone(), tx };
+                let (res, _) = transact(&mut db, env)?;
+                db.commit(res.state);
+            }
+
             let tx = tx_env_with_recovered(&tx);
             let env = Env { cfg, block, tx };
-            let mut db = SubState::new(State::new(state));
-            trace_transaction(opts, env, &mut db)
-        })
+            trace_transaction(opts, env, &mut db).map(|(frame, _)| frame)
+        })?;
+
+        self.cache(key, &frame);
+        Ok(frame)
     }
 
     /// The debug_traceCall method lets you run an `eth_call` within the context of the given block
     /// execution using the final state of parent block as the base.
+    ///
+    /// The call is traced like [Self::debug_trace_transaction] with the configured tracer, the
+    /// state and block overrides are applied before.
     pub async fn debug_trace_call(
         &self,
         call: CallRequest,
@@ -145,32 +323,80 @@ where
         opts: GethDebugTracingCallOptions,
     ) -> EthResult<GethTraceFrame> {
         let at = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
-        // TODO(mattsse) apply block overrides
-        let GethDebugTracingCallOptions { tracing_options, state_overrides, block_overrides: _ } =
+        let GethDebugTracingCallOptions { tracing_options, state_overrides, block_overrides } =
             opts;
-        let GethDebugTracingOptions { config, .. } = tracing_options;
-        // TODO(mattsse) support non default tracers
+        let overrides = EvmOverrides::new(state_overrides, block_overrides);
 
-        // default structlog tracer
-        let inspector_config = TracingInspectorConfig::from_geth_config(&config);
-
-        let mut inspector = TracingInspector::new(inspector_config);
-
-        let (res, _) =
-            self.eth_api.inspect_call_at(call, at, state_overrides, &mut inspector).await?;
-        let gas_used = res.result.gas_used();
+        self.eth_api
+            .with_call_at(call, at, overrides, |mut db, env| {
+                trace_transaction(tracing_options, env, &mut db).map(|(frame, _)| frame)
+            })
+            .await
+    }
+}
 
-        let frame = inspector.into_geth_builder().geth_traces(U256::from(gas_used), config);
+impl<Client, Eth> DebugApi<Client, Eth>
+where
+    Client: BlockProvider + HeaderProvider + Clone + 'static,
+    Eth: EthTransactions + Clone + 'static,
+{
+    /// Returns a stream of the traces of the blocks after `start_exclusive` up to and including
+    /// `end_inclusive`, for `debug_traceChain`.
+    ///
+    /// The blocks are traced one after another when the stream is polled, so the traces of a large
+    /// range are never buffered and tracing stops once the stream is dropped. Each block is traced
+    /// with a permit of the [TracingCallGuard], like any other tracing call, and the range is
+    /// limited to [MAX_TRACE_CHAIN_BLOCKS] blocks.
+    pub fn debug_trace_chain(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: GethDebugTracingOptions,
+    ) -> EthResult<impl Stream<Item = EthResult<BlockTraceResult>> + Send + 'static> {
+        let start = self
+            .client
+            .convert_block_number(start_exclusive)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        let end = self
+            .client
+            .convert_block_number(end_inclusive)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        if start >= end {
+            return Err(EthApiError::InvalidParams(format!(
+                "start block ({start}) must be less than end block ({end})"
+            )))
+        }
+        if end - start > MAX_TRACE_CHAIN_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "range of {} blocks exceeds the limit of {MAX_TRACE_CHAIN_BLOCKS} blocks",
+                end - start
+            )))
+        }
 
-        Ok(frame.into())
+        let this = self.clone();
+        Ok(futures::stream::iter(start + 1..=end).then(move |number| {
+            let this = this.clone();
+            let opts = opts.clone();
+            async move {
+                let hash =
+                    this.client.block_hash(number)?.ok_or(EthApiError::UnknownBlockNumber)?;
+                let _permit = this.acquire_trace_permit().await;
+                let traces = this
+                    .on_blocking_pool(move |this| async move {
+                        DebugApi::debug_trace_block(&this, hash.into(), opts).await
+                    })
+                    .await?;
+                Ok(BlockTraceResult { block: U256::from(number), hash, traces })
+            }
+        }))
     }
 }
 
 #[async_trait]
 impl<Client, Eth> DebugApiServer for DebugApi<Client, Eth>
 where
-    Client: BlockProvider + HeaderProvider + 'static,
-    Eth: EthApiSpec + 'static,
+    Client: BlockProvider + HeaderProvider + Clone + 'static,
+    Eth: EthApiSpec + Clone + 'static,
 {
     /// Handler for `debug_getRawHeader`
     async fn raw_header(&self, block_id: BlockId) -> RpcResult<Bytes> {
@@ -195,7 +421,10 @@ where
 
     /// Handler for `debug_getRawBlock`
     async fn raw_block(&self, block_id: BlockId) -> RpcResult<Bytes> {
-        let block = self.client.block(block_id).to_rpc_result()?;
+        let block = match self.client.block_hash_for_id(block_id).to_rpc_result()? {
+            Some(block_hash) => self.eth_cache.get_block(block_hash).await.to_rpc_result()?,
+            None => None,
+        };
 
         let mut res = Vec::new();
         if let Some(mut block) = block {
@@ -224,7 +453,11 @@ where
 
     /// Handler for `debug_getRawReceipts`
     async fn raw_receipts(&self, block_id: BlockId) -> RpcResult<Vec<Bytes>> {
-        let receipts = self.client.receipts_by_block(block_id).to_rpc_result()?.unwrap_or_default();
+        let receipts = match self.client.block_hash_for_id(block_id).to_rpc_result()? {
+            Some(block_hash) => self.eth_cache.get_receipts(block_hash).await.to_rpc_result()?,
+            None => None,
+        }
+        .unwrap_or_default();
         let mut all_receipts = Vec::with_capacity(receipts.len());
 
         for receipt in receipts {
@@ -242,13 +475,34 @@ where
         Err(internal_rpc_err("unimplemented"))
     }
 
-    /// Handler for `debug_traceChain`
-    async fn debug_trace_chain(
+    /// Handler for `debug_subscribe`
+    fn debug_trace_chain(
         &self,
-        _start_exclusive: BlockNumberOrTag,
-        _end_inclusive: BlockNumberOrTag,
-    ) -> RpcResult<Vec<BlockTraceResult>> {
-        Err(internal_rpc_err("unimplemented"))
+        mut sink: SubscriptionSink,
+        _kind: DebugSubscriptionKind,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> SubscriptionResult {
+        let stream = match DebugApi::debug_trace_chain(
+            self,
+            start_exclusive,
+            end_inclusive,
+            opts.unwrap_or_default(),
+        ) {
+            Ok(stream) => stream,
+            Err(err) => {
+                sink.reject(RpcError::from(err))?;
+                return Ok(())
+            }
+        };
+        sink.accept()?;
+
+        self.subscription_task_spawner.spawn(Box::pin(async move {
+            sink.pipe_from_try_stream(Box::pin(stream)).await;
+        }));
+
+        Ok(())
     }
 
     /// Handler for `debug_traceBlock`
@@ -266,7 +520,11 @@ where
         block: H256,
         opts: GethDebugTracingOptions,
     ) -> RpcResult<Vec<TraceResult>> {
-        Ok(DebugApi::debug_trace_block(self, block.into(), opts).await?)
+        Ok(self
+            .on_blocking_pool(move |this| async move {
+                DebugApi::debug_trace_block(&this, block.into(), opts).await
+            })
+            .await?)
     }
 
     /// Handler for `debug_traceBlockByNumber`
@@ -275,7 +533,11 @@ where
         block: BlockNumberOrTag,
         opts: GethDebugTracingOptions,
     ) -> RpcResult<Vec<TraceResult>> {
-        Ok(DebugApi::debug_trace_block(self, block.into(), opts).await?)
+        Ok(self
+            .on_blocking_pool(move |this| async move {
+                DebugApi::debug_trace_block(&this, block.into(), opts).await
+            })
+            .await?)
     }
 
     /// Handler for `debug_traceTransaction`
@@ -284,7 +546,11 @@ where
         tx_hash: H256,
         opts: GethDebugTracingOptions,
     ) -> RpcResult<GethTraceFrame> {
-        Ok(DebugApi::debug_trace_transaction(self, tx_hash, opts).await?)
+        Ok(self
+            .on_blocking_pool(move |this| async move {
+                DebugApi::debug_trace_transaction(&this, tx_hash, opts).await
+            })
+            .await?)
     }
 
     /// Handler for `debug_traceCall`
@@ -294,7 +560,11 @@ where
         block_number: Option<BlockId>,
         opts: GethDebugTracingCallOptions,
     ) -> RpcResult<GethTraceFrame> {
-        Ok(DebugApi::debug_trace_call(self, request, block_number, opts).await?)
+        Ok(self
+            .on_blocking_pool(move |this| async move {
+                DebugApi::debug_trace_call(&this, request, block_number, opts).await
+            })
+            .await?)
     }
 }
 
@@ -304,15 +574,16 @@ impl<Client, Eth> std::fmt::Debug for DebugApi<Client, Eth> {
     }
 }
 
-/// Executes the configured transaction in the environment on the given database.
+/// Executes the configured transaction in the environment on the given database and returns the
+/// trace and the state changes of the transaction.
 ///
 /// Note: this does not apply any state overrides if they're configured in the `opts`.
 fn trace_transaction(
     opts: GethDebugTracingOptions,
     env: Env,
     db: &mut SubState<StateProviderBox<'_>>,
-) -> EthResult<GethTraceFrame> {
-    let GethDebugTracingOptions { config, tracer, tracer_config, .. } = opts;
+) -> EthResult<(GethTraceFrame, StateChanges)> {
+    let GethDebugTracingOptions { config, tracer, tracer_config, timeout, .. } = opts;
     if let Some(tracer) = tracer {
         // valid matching config
         if let Some(ref config) = tracer_config {
@@ -325,19 +596,74 @@ fn trace_transaction(
             GethDebugTracerType::BuiltInTracer(tracer) => match tracer {
                 GethDebugBuiltInTracerType::FourByteTracer => {
                     let mut inspector = FourByteInspector::default();
-                    let _ = inspect(db, env, &mut inspector)?;
-                    return Ok(FourByteFrame::from(inspector).into())
+                    let (res, _) = inspect(db, env, &mut inspector)?;
+                    return Ok((FourByteFrame::from(inspector).into(), res.state))
                 }
                 GethDebugBuiltInTracerType::CallTracer => {
-                    todo!()
+                    let call_config = tracer_config
+                        .and_then(|config| config.into_call_config())
+                        .unwrap_or_default();
+                    let gas_limit = env.tx.gas_limit;
+                    let mut inspector =
+                        TracingInspector::new(TracingInspectorConfig::default_parity());
+                    let (res, _) = inspect(&mut *db, env, &mut inspector)?;
+                    let mut frame = inspector.into_geth_builder().geth_call_traces(call_config);
+                    // like geth, the top-level frame reports the gas of the transaction
+                    frame.gas = U256::from(gas_limit);
+                    frame.gas_used = U256::from(res.result.gas_used());
+                    return Ok((frame.into(), res.state))
                 }
                 GethDebugBuiltInTracerType::PreStateTracer => {
-                    todo!()
+                    let prestate_config = tracer_config
+                        .and_then(|config| config.into_pre_state_config())
+                        .unwrap_or_default();
+                    let mut inspector =
+                        TracingInspector::new(TracingInspectorConfig::default_parity());
+                    let (res, _) = inspect(&mut *db, env, &mut inspector)?;
+                    // the changes aren't committed yet, so the database still holds the pre state
+                    let frame = inspector.into_geth_builder().geth_prestate_traces(
+                        &res.state,
+                        prestate_config,
+                        &*db,
+                    )?;
+                    return Ok((frame.into(), res.state))
+                }
+                GethDebugBuiltInTracerType::NoopTracer => {
+                    // the transaction is still executed for the state changes, which the
+                    // following transactions of a traced block are executed on
+                    let (res, _) = transact(db, env)?;
+                    Ok((NoopFrame::default().into(), res.state))
                 }
-                GethDebugBuiltInTracerType::NoopTracer => Ok(NoopFrame::default().into()),
             },
+            #[cfg(feature = "js-tracer")]
+            GethDebugTracerType::JsTracer(code) => {
+                let config =
+                    tracer_config.and_then(|config| config.into_js_config()).unwrap_or_default();
+                let timeout = match timeout {
+                    Some(timeout) => parse_go_duration(&timeout)
+                        .ok_or_else(|| {
+                            EthApiError::InvalidParams(format!("invalid timeout: {timeout}"))
+                        })?
+                        .min(MAX_JS_TRACER_TIMEOUT),
+                    None => DEFAULT_JS_TRACER_TIMEOUT,
+                };
+                let mut inspector = reth_revm::tracing::js::JsInspector::new(
+                    code,
+                    config,
+                    crate::eth::revm_utils::get_precompiles(&env.cfg.spec_id),
+                )?
+                .with_timeout(timeout);
+                let (res, env) = inspect(&mut *db, env, &mut inspector)?;
+                // the changes aren't committed yet, the tracer reads them on top of the database
+                let result = inspector.json_result(&res, &env, &*db)?;
+                Ok((GethTraceFrame::JS(result), res.state))
+            }
+            #[cfg(not(feature = "js-tracer"))]
             GethDebugTracerType::JsTracer(_) => {
-                Err(EthApiError::Unsupported("javascript tracers are unsupported."))
+                let _ = timeout;
+                Err(EthApiError::Unsupported(
+                    "javascript tracers are unsupported, enable the js-tracer feature",
+                ))
             }
         }
     }
@@ -352,5 +678,85 @@ fn trace_transaction(
 
     let frame = inspector.into_geth_builder().geth_traces(U256::from(gas_used), config);
 
-    Ok(frame.into())
+    Ok((frame.into(), res.state))
+}
+
+/// The timeout of javascript tracers if the request doesn't configure one, same as geth.
+#[cfg(feature = "js-tracer")]
+const DEFAULT_JS_TRACER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The upper bound of the timeout a request can configure for a javascript tracer, longer
+/// timeouts are capped to it.
+#[cfg(feature = "js-tracer")]
+const MAX_JS_TRACER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Parses a duration in the format of go's `time.ParseDuration`, e.g. `5s`, `300ms` or `1m30s`,
+/// which is used for the timeout of a tracer.
+///
+/// Returns `None` if the duration is invalid or negative.
+#[cfg(feature = "js-tracer")]
+fn parse_go_duration(s: &str) -> Option<Duration> {
+    let s = s.strip_prefix('+').unwrap_or(s);
+    if s == "0" {
+        return Some(Duration::ZERO)
+    }
+    if s.is_empty() {
+        return None
+    }
+
+    let mut rest = s;
+    let mut total = 0f64;
+    while !rest.is_empty() {
+        let number_len = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(number_len);
+        if number.is_empty() || number == "." {
+            return None
+        }
+        let number: f64 = number.parse().ok()?;
+
+        let unit_len = tail.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        let nanos_per_unit = match unit {
+            "ns" => 1.,
+            "us" | "µs" | "μs" => 1e3,
+            "ms" => 1e6,
+            "s" => 1e9,
+            "m" => 60e9,
+            "h" => 3600e9,
+            _ => return None,
+        };
+        total += number * nanos_per_unit;
+        rest = tail;
+    }
+
+    if !total.is_finite() || total > u64::MAX as f64 {
+        return None
+    }
+    Some(Duration::from_nanos(total as u64))
+}
+
+/// Identifies the traces created with the given options in the [TraceCache].
+fn debug_tracer(opts: &GethDebugTracingOptions) -> Option<String> {
+    serde_json::to_string(opts).ok().map(|opts| format!("debug:{opts}"))
+}
+
+#[cfg(all(test, feature = "js-tracer"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tracer_timeout() {
+        assert_eq!(parse_go_duration("0"), Some(Duration::ZERO));
+        assert_eq!(parse_go_duration("5s"), Some(Duration::from_secs(5)));
+        assert_eq!(parse_go_duration("300ms"), Some(Duration::from_millis(300)));
+        assert_eq!(parse_go_duration("1m30s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_go_duration("1.5h"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_go_duration("10us"), Some(Duration::from_micros(10)));
+
+        assert_eq!(parse_go_duration(""), None);
+        assert_eq!(parse_go_duration("5"), None);
+        assert_eq!(parse_go_duration("-5s"), None);
+        assert_eq!(parse_go_duration("5d"), None);
+        assert_eq!(parse_go_duration("s"), None);
+    }
 }
@@ -1,16 +1,22 @@
 //! Contains RPC handler implementations specific to blocks.
 
 use crate::{
-    eth::error::{EthApiError, EthResult},
+    eth::{
+        api::transactions::build_transaction_receipt_with_block_receipts,
+        error::{EthApiError, EthResult},
+    },
     EthApi,
 };
-use reth_primitives::BlockId;
+use reth_primitives::{BlockId, TransactionMeta};
 use reth_provider::{BlockProvider, EvmEnvProvider, StateProviderFactory};
-use reth_rpc_types::{Block, Index, RichBlock};
+use reth_rpc_types::{Block, Index, RichBlock, TransactionReceipt};
+use reth_transaction_pool::TransactionPool;
 
 impl<Client, Pool, Network> EthApi<Client, Pool, Network>
 where
     Client: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Pool: TransactionPool + 'static,
+    Network: Send + Sync + 'static,
 {
     /// Returns the uncle headers of the given block
     ///
@@ -20,6 +26,10 @@ where
         block_id: impl Into<BlockId>,
     ) -> EthResult<Option<Vec<reth_primitives::Header>>> {
         let block_id = block_id.into();
+        if block_id.is_pending() {
+            // the pending block is built like a post-merge block, without ommers
+            return Ok(Some(Vec::new()))
+        }
         Ok(self.client().ommers(block_id)?)
     }
 
@@ -30,13 +40,7 @@ where
     ) -> EthResult<Option<RichBlock>> {
         let block_id = block_id.into();
 
-        let uncles = if block_id.is_pending() {
-            // Pending block can be fetched directly without need for caching
-            self.client().pending_block()?.map(|block| block.ommers)
-        } else {
-            self.client().ommers(block_id)?
-        }
-        .unwrap_or_default();
+        let uncles = self.ommers(block_id)?.unwrap_or_default();
 
         let index = usize::from(index);
         let uncle = uncles
@@ -46,6 +50,45 @@ where
         Ok(uncle)
     }
 
+    /// Returns all transaction receipts in the given block.
+    ///
+    /// Returns `None` if the block or its receipts don't exist.
+    pub(crate) async fn block_receipts(
+        &self,
+        block_id: impl Into<BlockId>,
+    ) -> EthResult<Option<Vec<TransactionReceipt>>> {
+        let block = match self.block(block_id).await? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        let block_hash = block.hash;
+        let block_number = block.number;
+
+        // the receipts of the block are read at once, instead of once per transaction
+        let receipts = match self.client().receipts_by_block(block_number.into())? {
+            Some(receipts) => receipts,
+            None => return Ok(None),
+        };
+
+        block
+            .body
+            .into_iter()
+            .zip(receipts.iter())
+            .enumerate()
+            .map(|(index, (tx, receipt))| {
+                let meta = TransactionMeta {
+                    tx_hash: tx.hash,
+                    index: index as u64,
+                    block_hash,
+                    block_number,
+                };
+                build_transaction_receipt_with_block_receipts(tx, meta, receipt.clone(), &receipts)
+            })
+            .collect::<EthResult<Vec<_>>>()
+            .map(Some)
+    }
+
     /// Returns the number transactions in the given block.
     ///
     /// Returns `None` if the block does not exist
@@ -56,8 +99,7 @@ where
         let block_id = block_id.into();
 
         if block_id.is_pending() {
-            // Pending block can be fetched directly without need for caching
-            return Ok(self.client().pending_block()?.map(|block| block.body.len()))
+            return Ok(Some(self.pending_block().await?.block.body.len()))
         }
 
         let block_hash = match self.client().block_hash_for_id(block_id)? {
@@ -84,8 +126,7 @@ where
         let block_id = block_id.into();
 
         if block_id.is_pending() {
-            // Pending block can be fetched directly without need for caching
-            return Ok(self.client().pending_block()?)
+            return Ok(Some(self.pending_block().await?.block.clone()))
         }
 
         let block_hash = match self.client().block_hash_for_id(block_id)? {
@@ -105,13 +146,17 @@ where
         block_id: impl Into<BlockId>,
         full: bool,
     ) -> EthResult<Option<RichBlock>> {
+        let block_id = block_id.into();
         let block = match self.block(block_id).await? {
             Some(block) => block,
             None => return Ok(None),
         };
         let block_hash = block.hash;
+        // the pending block isn't stored, it has the total difficulty of its parent since the
+        // difficulty of post-merge blocks is zero
+        let td_hash = if block_id.is_pending() { block.parent_hash } else { block_hash };
         let total_difficulty =
-            self.client().header_td(&block_hash)?.ok_or(EthApiError::UnknownBlockNumber)?;
+            self.client().header_td(&td_hash)?.ok_or(EthApiError::UnknownBlockNumber)?;
         let block =
             Block::from_block(block.into(), total_difficulty, full.into(), Some(block_hash))?;
         Ok(Some(block.into()))
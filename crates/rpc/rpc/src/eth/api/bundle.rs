@@ -0,0 +1,162 @@
+//! Contains RPC handler implementations specific to bundles.
+
+use crate::{
+    eth::{
+        error::{
+            decode_revert_reason, EthApiError, EthResult, InvalidTransactionError, RevertError,
+        },
+        revm_utils::{apply_block_overrides, transact},
+        utils::recover_raw_transaction,
+        EthTransactions,
+    },
+    EthApi,
+};
+use reth_network_api::NetworkInfo;
+use reth_primitives::{keccak256, TransactionKind, U256, U64};
+use reth_provider::{BlockProvider, EvmEnvProvider, StateProviderFactory};
+use reth_revm::{
+    database::{State, SubState},
+    env::tx_env_with_recovered,
+};
+use reth_rpc_types::{BlockOverrides, CallBundle, CallBundleResponse, CallBundleTransactionResult};
+use reth_transaction_pool::TransactionPool;
+use revm::{
+    db::DatabaseRef,
+    primitives::{Env, ExecutionResult},
+    DatabaseCommit,
+};
+
+impl<Client, Pool, Network> EthApi<Client, Pool, Network>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Client: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+{
+    /// Simulates the transactions of the bundle in order on top of the state block, in a block
+    /// with the configured header fields.
+    ///
+    /// Like the Flashbots implementation, the header fields that aren't configured are derived
+    /// from the state block as if the bundle was included in the next block. A reverted or halted
+    /// transaction doesn't abort the simulation, it's reported in its result.
+    pub(crate) async fn call_bundle(&self, bundle: CallBundle) -> EthResult<CallBundleResponse> {
+        let CallBundle {
+            txs,
+            block_number,
+            state_block_number,
+            timestamp,
+            coinbase,
+            gas_limit,
+            difficulty,
+            base_fee,
+        } = bundle;
+        if txs.is_empty() {
+            return Err(EthApiError::InvalidParams("bundle missing txs".to_string()))
+        }
+        if block_number.is_zero() {
+            return Err(EthApiError::InvalidParams("bundle missing blockNumber".to_string()))
+        }
+
+        let transactions =
+            txs.into_iter().map(recover_raw_transaction).collect::<EthResult<Vec<_>>>()?;
+
+        let (cfg, mut block_env, at) = self.evm_env_at(state_block_number.into()).await?;
+        let parent = self.header(at).await?.ok_or(EthApiError::UnknownBlockNumber)?;
+
+        block_env.number = U256::from(block_number.as_u64());
+        block_env.timestamp = U256::from(parent.timestamp + 1);
+        block_env.basefee = U256::from(parent.next_block_base_fee().unwrap_or_default());
+        apply_block_overrides(
+            BlockOverrides {
+                time: timestamp.map(U64::from),
+                coinbase,
+                gas_limit: gas_limit.map(U64::from),
+                difficulty,
+                base_fee,
+                ..Default::default()
+            },
+            &mut block_env,
+        );
+
+        let coinbase = block_env.coinbase;
+        let base_fee = block_env.basefee.saturating_to::<u64>();
+        let env = Env { cfg, block: block_env, ..Default::default() };
+
+        self.with_state_at(at, move |state| {
+            let mut db = SubState::new(State::new(state));
+
+            let initial_coinbase_balance =
+                db.basic(coinbase)?.map(|account| account.balance).unwrap_or_default();
+            let mut coinbase_balance_before_tx = initial_coinbase_balance;
+            let mut total_gas_used = 0u64;
+            let mut total_gas_fees = U256::ZERO;
+            let mut hashes = Vec::with_capacity(transactions.len() * 32);
+            let mut results = Vec::with_capacity(transactions.len());
+
+            for tx in transactions {
+                let tx_hash = tx.hash();
+                hashes.extend_from_slice(tx_hash.as_bytes());
+                let gas_tip = tx.effective_tip_per_gas(base_fee).unwrap_or_default();
+
+                let mut env = env.clone();
+                env.tx = tx_env_with_recovered(&tx);
+                let (res, _) = transact(&mut db, env)?;
+                db.commit(res.state);
+
+                let gas_used = res.result.gas_used();
+                total_gas_used += gas_used;
+
+                let gas_fees = U256::from(gas_used) * U256::from(gas_tip);
+                total_gas_fees += gas_fees;
+
+                let coinbase_balance_after_tx =
+                    db.basic(coinbase)?.map(|account| account.balance).unwrap_or_default();
+                let coinbase_diff =
+                    coinbase_balance_after_tx.saturating_sub(coinbase_balance_before_tx);
+                coinbase_balance_before_tx = coinbase_balance_after_tx;
+
+                let mut result = CallBundleTransactionResult {
+                    tx_hash,
+                    from_address: tx.signer(),
+                    to_address: match tx.kind() {
+                        TransactionKind::Create => None,
+                        TransactionKind::Call(to) => Some(*to),
+                    },
+                    gas_used,
+                    gas_price: coinbase_diff.checked_div(U256::from(gas_used)).unwrap_or_default(),
+                    gas_fees,
+                    coinbase_diff,
+                    eth_sent_to_coinbase: coinbase_diff.saturating_sub(gas_fees),
+                    ..Default::default()
+                };
+                match res.result {
+                    ExecutionResult::Success { output, .. } => {
+                        result.value = Some(output.into_data().into());
+                    }
+                    ExecutionResult::Revert { output, .. } => {
+                        result.revert = decode_revert_reason(&output);
+                        result.error = Some(RevertError::new(output).to_string());
+                    }
+                    ExecutionResult::Halt { reason, gas_used } => {
+                        result.error =
+                            Some(InvalidTransactionError::halt(reason, gas_used).to_string());
+                    }
+                }
+                results.push(result);
+            }
+
+            let coinbase_diff = coinbase_balance_before_tx.saturating_sub(initial_coinbase_balance);
+            Ok(CallBundleResponse {
+                bundle_hash: keccak256(&hashes),
+                bundle_gas_price: coinbase_diff
+                    .checked_div(U256::from(total_gas_used))
+                    .unwrap_or_default(),
+                coinbase_diff,
+                eth_sent_to_coinbase: coinbase_diff.saturating_sub(total_gas_fees),
+                gas_fees: total_gas_fees,
+                results,
+                state_block_number: parent.number,
+                total_gas_used,
+            })
+        })
+    }
+}
@@ -4,7 +4,8 @@ use crate::{
     eth::{
         error::{EthApiError, EthResult, InvalidTransactionError, RevertError},
         revm_utils::{
-            build_call_evm_env, cap_tx_gas_limit_with_caller_allowance, get_precompiles, inspect,
+            apply_block_overrides, apply_state_overrides, build_call_evm_env,
+            cap_tx_gas_limit_with_caller_allowance, get_precompiles, inspect, prepare_call_env,
             transact,
         },
         EthTransactions,
@@ -13,23 +14,40 @@ use crate::{
 };
 use ethers_core::utils::get_contract_address;
 use reth_network_api::NetworkInfo;
-use reth_primitives::{AccessList, BlockId, BlockNumberOrTag, U256};
-use reth_provider::{BlockProvider, EvmEnvProvider, StateProvider, StateProviderFactory};
+use reth_primitives::{AccessListWithGasUsed, BlockId, BlockNumberOrTag, U256};
+use reth_provider::{
+    BlockProvider, EvmEnvProvider, HeaderProvider, StateProvider, StateProviderFactory,
+};
 use reth_revm::{
     access_list::AccessListInspector,
     database::{State, SubState},
+    env::tx_env_with_recovered,
+};
+use reth_rpc_types::{
+    state::{EvmOverrides, StateOverride},
+    BlockError, Bundle, CallRequest, EthCallResponse, StateContext, TransactionIndex,
 };
-use reth_rpc_types::CallRequest;
 use reth_transaction_pool::TransactionPool;
 use revm::{
     db::{CacheDB, DatabaseRef},
-    primitives::{BlockEnv, CfgEnv, Env, ExecutionResult, Halt, TransactTo},
+    primitives::{BlockEnv, CfgEnv, Env, ExecutionResult, Halt, ResultAndState, TransactTo},
+    DatabaseCommit,
 };
 use tracing::trace;
 
 // Gas per transaction not creating a contract.
 const MIN_TRANSACTION_GAS: u64 = 21_000u64;
 const MIN_CREATE_GAS: u64 = 53_000u64;
+/// The gas a call with value passes to the callee for free, which isn't included in the gas used.
+const CALL_STIPEND_GAS: u64 = 2_300;
+/// The accepted ratio between the estimated gas and the lowest gas limit the transaction succeeds
+/// with, like geth's.
+const ESTIMATE_GAS_ERROR_RATIO: f64 = 0.015;
+/// The maximum number of calls of all bundles of an `eth_callMany` request.
+pub(crate) const MAX_CALL_MANY_CALLS: usize = 1_000;
+/// The gas all calls of an `eth_callMany` request can use together, like geth's default RPC gas
+/// cap.
+pub(crate) const CALL_MANY_GAS_CAP: u64 = 50_000_000;
 
 impl<Client, Pool, Network> EthApi<Client, Pool, Network>
 where
@@ -38,14 +56,21 @@ where
     Network: NetworkInfo + Send + Sync + 'static,
 {
     /// Estimate gas needed for execution of the `request` at the [BlockId].
+    ///
+    /// The estimation is done with the given state and block overrides applied.
     pub(crate) async fn estimate_gas_at(
         &self,
         request: CallRequest,
         at: BlockId,
+        overrides: EvmOverrides,
     ) -> EthResult<U256> {
-        let (cfg, block_env, at) = self.evm_env_at(at).await?;
+        let (cfg, mut block_env, at) = self.evm_env_at(at).await?;
+        let EvmOverrides { state: state_overrides, block: block_overrides } = overrides;
+        if let Some(block_overrides) = block_overrides {
+            apply_block_overrides(block_overrides, &mut block_env);
+        }
         let state = self.state_at(at)?;
-        self.estimate_gas_with(cfg, block_env, request, state)
+        self.estimate_gas_with(cfg, block_env, request, state, state_overrides)
     }
 
     /// Estimates the gas usage of the `request` with the state.
@@ -57,6 +82,7 @@ where
         block: BlockEnv,
         request: CallRequest,
         state: S,
+        state_overrides: Option<StateOverride>,
     ) -> EthResult<U256>
     where
         S: StateProvider,
@@ -83,8 +109,14 @@ where
         let mut env = build_call_evm_env(cfg, block, request)?;
         let mut db = SubState::new(State::new(state));
 
+        // the code of the callee could be overridden, which the underlying state doesn't know
+        let has_state_overrides = state_overrides.is_some();
+        if let Some(state_overrides) = state_overrides {
+            apply_state_overrides(state_overrides, &mut db)?;
+        }
+
         // if the request is a simple transfer we can optimize
-        if env.tx.data.is_empty() {
+        if env.tx.data.is_empty() && !has_state_overrides {
             if let TransactTo::Call(to) = env.tx.transact_to {
                 if let Ok(code) = db.db.state().account_code(to) {
                     let no_code_callee = code.map(|code| code.is_empty()).unwrap_or(true);
@@ -161,80 +193,194 @@ where
             }
         }
 
-        // at this point we know the call succeeded but want to find the _best_ (lowest) gas the
-        // transaction succeeds with. we  find this by doing a binary search over the
-        // possible range NOTE: this is the gas the transaction used, which is less than the
-        // transaction requires to succeed
+        // at this point we know the call succeeds with the highest gas limit but want to find the
+        // _best_ (lowest) gas limit the transaction succeeds with.
+        //
+        // the transaction can't succeed with less gas than it used
         let gas_used = res.result.gas_used();
-        // the lowest value is capped by the gas it takes for a transfer
-        let mut lowest_gas_limit =
+        let min_gas_limit =
             if env.tx.transact_to.is_create() { MIN_CREATE_GAS } else { MIN_TRANSACTION_GAS };
+        let mut lowest_gas_limit = gas_used.saturating_sub(1).max(min_gas_limit - 1);
         let mut highest_gas_limit: u64 = highest_gas_limit.try_into().unwrap_or(u64::MAX);
-        // pick a point that's close to the estimated gas
-        let mut mid_gas_limit = std::cmp::min(
-            gas_used * 3,
-            ((highest_gas_limit as u128 + lowest_gas_limit as u128) / 2) as u64,
-        );
 
-        let _last_highest_gas_limit = highest_gas_limit;
+        // optimistically try the gas used plus the refund and the stipend, with the 1/64th of the
+        // gas that's withheld from calls added back. this is usually enough to succeed, so the
+        // search can usually start with a narrow range
+        let gas_refunded = match res.result {
+            ExecutionResult::Success { gas_refunded, .. } => gas_refunded,
+            _ => 0,
+        };
+        let optimistic_gas_limit = (gas_used + gas_refunded + CALL_STIPEND_GAS) * 64 / 63;
+        if optimistic_gas_limit < highest_gas_limit {
+            let mut env = env.clone();
+            env.tx.gas_limit = optimistic_gas_limit;
+            if execution_succeeds(transact(&mut db, env))? {
+                highest_gas_limit = optimistic_gas_limit;
+            } else {
+                lowest_gas_limit = optimistic_gas_limit;
+            }
+        }
+
+        trace!(target: "rpc::eth::estimate", ?env, ?highest_gas_limit, ?lowest_gas_limit, "Starting binary search for gas");
 
-        trace!(target: "rpc::eth::estimate", ?env, ?highest_gas_limit, ?lowest_gas_limit, ?mid_gas_limit, "Starting binary search for gas");
+        // binary search until the range is narrower than the accepted error ratio
+        while lowest_gas_limit + 1 < highest_gas_limit &&
+            (highest_gas_limit - lowest_gas_limit) as f64 / highest_gas_limit as f64 >
+                ESTIMATE_GAS_ERROR_RATIO
+        {
+            // most transactions succeed with a gas limit close to the lowest one, so the midpoint
+            // is capped to narrow the range faster
+            let mid_gas_limit = (((highest_gas_limit as u128 + lowest_gas_limit as u128) / 2)
+                as u64)
+                .min(lowest_gas_limit.saturating_mul(2));
 
-        // binary search
-        while (highest_gas_limit - lowest_gas_limit) > 1 {
             let mut env = env.clone();
             env.tx.gas_limit = mid_gas_limit;
-            let ethres = transact(&mut db, env);
-
-            // Exceptional case: init used too much gas, we need to increase the gas limit and try
-            // again
-            if let Err(EthApiError::InvalidTransaction(InvalidTransactionError::GasTooHigh)) =
-                ethres
-            {
-                // increase the lowest gas limit
+            if execution_succeeds(transact(&mut db, env))? {
+                highest_gas_limit = mid_gas_limit;
+            } else {
                 lowest_gas_limit = mid_gas_limit;
-
-                // new midpoint
-                mid_gas_limit = ((highest_gas_limit as u128 + lowest_gas_limit as u128) / 2) as u64;
-                continue
             }
+        }
+
+        Ok(U256::from(highest_gas_limit))
+    }
 
-            let (res, _) = ethres?;
-            match res.result {
-                ExecutionResult::Success { .. } => {
-                    // cap the highest gas limit with succeeding gas limit
-                    highest_gas_limit = mid_gas_limit;
+    /// Executes the bundles of calls in order in the block of the [StateContext], each call on top
+    /// of the state changes of the previous ones.
+    ///
+    /// The state overrides are applied before the first call and the block overrides of a bundle
+    /// replace the fields of the block its calls are executed in. A reverted or halted call
+    /// doesn't abort the simulation, it's reported in its result.
+    ///
+    /// The number of calls is limited to [MAX_CALL_MANY_CALLS] and the gas they use to
+    /// [CALL_MANY_GAS_CAP].
+    pub(crate) async fn call_many(
+        &self,
+        bundles: Vec<Bundle>,
+        state_context: StateContext,
+        state_override: Option<StateOverride>,
+    ) -> EthResult<Vec<Vec<EthCallResponse>>> {
+        let num_calls = bundles.iter().map(|bundle| bundle.transactions.len()).sum::<usize>();
+        if num_calls > MAX_CALL_MANY_CALLS {
+            return Err(EthApiError::InvalidParams(format!(
+                "too many calls: {num_calls}, the maximum is {MAX_CALL_MANY_CALLS}"
+            )))
+        }
+
+        let StateContext { block_number, transaction_index } = state_context;
+        let at = block_number.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let (cfg, block_env, at) = self.evm_env_at(at).await?;
+
+        // the calls are executed on top of the state of the parent block after replaying the
+        // selected transactions of the block, or on top of the block if all of them are selected
+        let (at, transactions) = match transaction_index.unwrap_or_default() {
+            TransactionIndex::All => (at, Vec::new()),
+            TransactionIndex::Index(index) => {
+                let BlockId::Hash(block_hash) = at else {
+                    return Err(EthApiError::Unsupported(
+                        "transaction index in the pending block is not supported",
+                    ))
+                };
+                let block_hash = block_hash.block_hash;
+                let transactions = self
+                    .transactions_by_block(block_hash)
+                    .await?
+                    .ok_or(EthApiError::UnknownBlockNumber)?;
+                if index > transactions.len() {
+                    return Err(EthApiError::UnknownBlockOrTxIndex)
                 }
-                ExecutionResult::Revert { .. } => {
-                    // increase the lowest gas limit
-                    lowest_gas_limit = mid_gas_limit;
+                let parent = self
+                    .client()
+                    .header(&block_hash)?
+                    .ok_or(EthApiError::UnknownBlockNumber)?
+                    .parent_hash;
+                let transactions = transactions
+                    .into_iter()
+                    .take(index)
+                    .map(|tx| {
+                        let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
+                        Ok(tx_env_with_recovered(&tx))
+                    })
+                    .collect::<EthResult<Vec<_>>>()?;
+                (BlockId::Hash(parent.into()), transactions)
+            }
+        };
+
+        self.with_state_at(at, move |state| {
+            let mut db = SubState::new(State::new(state));
+            for tx in transactions {
+                let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
+                let (res, _) = transact(&mut db, env)?;
+                db.commit(res.state);
+            }
+
+            let mut state_override = state_override;
+            let mut gas_budget = CALL_MANY_GAS_CAP;
+            let mut results = Vec::with_capacity(bundles.len());
+
+            for Bundle { transactions, block_override } in bundles {
+                let mut block_env = block_env.clone();
+                if let Some(block_override) = block_override {
+                    apply_block_overrides(block_override, &mut block_env);
                 }
-                ExecutionResult::Halt { reason, .. } => {
-                    match reason {
-                        Halt::OutOfGas(_) => {
-                            // increase the lowest gas limit
-                            lowest_gas_limit = mid_gas_limit;
-                        }
-                        err => {
-                            // these should be unreachable because we know the transaction succeeds,
-                            // but we consider these cases an error
-                            return Err(InvalidTransactionError::EvmHalt(err).into())
-                        }
+
+                let mut bundle_results = Vec::with_capacity(transactions.len());
+                for call in transactions {
+                    if gas_budget == 0 {
+                        return Err(EthApiError::InvalidParams(format!(
+                            "the calls exceed the gas cap of {CALL_MANY_GAS_CAP}"
+                        )))
                     }
+
+                    let mut env = prepare_call_env(
+                        cfg.clone(),
+                        block_env.clone(),
+                        call,
+                        &mut db,
+                        EvmOverrides::state(state_override.take()),
+                    )?;
+                    // all calls share the gas cap
+                    env.tx.gas_limit = env.tx.gas_limit.min(gas_budget);
+
+                    let (res, _) = transact(&mut db, env)?;
+                    db.commit(res.state);
+                    gas_budget = gas_budget.saturating_sub(res.result.gas_used());
+
+                    let response = match res.result {
+                        ExecutionResult::Success { output, .. } => {
+                            EthCallResponse { value: Some(output.into_data().into()), error: None }
+                        }
+                        ExecutionResult::Revert { output, .. } => EthCallResponse {
+                            value: None,
+                            error: Some(RevertError::new(output).to_string()),
+                        },
+                        ExecutionResult::Halt { reason, gas_used } => EthCallResponse {
+                            value: None,
+                            error: Some(
+                                InvalidTransactionError::halt(reason, gas_used).to_string(),
+                            ),
+                        },
+                    };
+                    bundle_results.push(response);
                 }
+                results.push(bundle_results);
             }
-            // new midpoint
-            mid_gas_limit = ((highest_gas_limit as u128 + lowest_gas_limit as u128) / 2) as u64;
-        }
 
-        Ok(U256::from(highest_gas_limit))
+            Ok(results)
+        })
     }
 
+    /// Creates the [AccessList](reth_primitives::AccessList) of the `request` at the [BlockId] and the gas used with it.
+    ///
+    /// Like geth, the call is executed with the produced access list until the list stops
+    /// changing, since warm accesses can change the execution. A failed execution is reported in
+    /// the result instead of failing the request.
     pub(crate) async fn create_access_list_at(
         &self,
         request: CallRequest,
         at: Option<BlockId>,
-    ) -> EthResult<AccessList> {
+    ) -> EthResult<AccessListWithGasUsed> {
         let block_id = at.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
         let (cfg, block, at) = self.evm_env_at(block_id).await?;
         let state = self.state_at(at)?;
@@ -261,27 +407,75 @@ where
         let to = if let Some(to) = request.to {
             to
         } else {
-            let nonce = db.basic(from)?.unwrap_or_default().nonce;
+            let nonce = match env.tx.nonce {
+                Some(nonce) => nonce,
+                None => db.basic(from)?.unwrap_or_default().nonce,
+            };
             get_contract_address(from, nonce).into()
         };
 
+        let precompiles = get_precompiles(&env.cfg.spec_id);
         let initial = request.access_list.clone().unwrap_or_default();
+        let mut access_list =
+            AccessListInspector::new(initial, from, to, precompiles.clone()).into_access_list();
 
-        let precompiles = get_precompiles(&env.cfg.spec_id);
-        let mut inspector = AccessListInspector::new(initial, from, to, precompiles);
-        let (result, _env) = inspect(&mut db, env, &mut inspector)?;
-
-        match result.result {
-            ExecutionResult::Halt { reason, .. } => Err(match reason {
-                Halt::NonceOverflow => InvalidTransactionError::NonceMaxValue,
-                halt => InvalidTransactionError::EvmHalt(halt),
-            }),
-            ExecutionResult::Revert { output, .. } => {
-                Err(InvalidTransactionError::Revert(RevertError::new(output)))
+        loop {
+            let mut env = env.clone();
+            env.tx.access_list = access_list.clone().flattened();
+
+            let mut inspector =
+                AccessListInspector::new(access_list.clone(), from, to, precompiles.clone());
+            let (result, _env) = inspect(&mut db, env, &mut inspector)?;
+
+            let produced = inspector.into_access_list();
+            if produced != access_list {
+                access_list = produced;
+                continue
             }
-            ExecutionResult::Success { .. } => Ok(()),
-        }?;
-        Ok(inspector.into_access_list())
+
+            let gas_used = U256::from(result.result.gas_used());
+            let error = match result.result {
+                ExecutionResult::Success { .. } => None,
+                ExecutionResult::Revert { output, .. } => {
+                    Some(RevertError::new(output).to_string())
+                }
+                ExecutionResult::Halt { reason, .. } => Some(
+                    match reason {
+                        Halt::NonceOverflow => InvalidTransactionError::NonceMaxValue,
+                        halt => InvalidTransactionError::EvmHalt(halt),
+                    }
+                    .to_string(),
+                ),
+            };
+            return Ok(AccessListWithGasUsed { access_list, gas_used, error })
+        }
+    }
+}
+
+/// Returns whether the result of executing the transaction with a candidate gas limit during gas
+/// estimation is a success.
+///
+/// Reverts and running out of gas count as failures, since the transaction could succeed with a
+/// higher gas limit. Other errors and halts are returned, because they aren't gas related and the
+/// transaction is known to succeed with the highest gas limit.
+fn execution_succeeds(res: EthResult<(ResultAndState, Env)>) -> EthResult<bool> {
+    let (res, _) = match res {
+        Ok(res) => res,
+        // the intrinsic gas exceeds the gas limit
+        Err(EthApiError::InvalidTransaction(InvalidTransactionError::GasTooHigh)) => {
+            return Ok(false)
+        }
+        Err(err) => return Err(err),
+    };
+    match res.result {
+        ExecutionResult::Success { .. } => Ok(true),
+        ExecutionResult::Revert { .. } |
+        ExecutionResult::Halt { reason: Halt::OutOfGas(_), .. } => Ok(false),
+        ExecutionResult::Halt { reason, .. } => {
+            // these should be unreachable because we know the transaction succeeds, but we
+            // consider these cases an error
+            Err(InvalidTransactionError::EvmHalt(reason).into())
+        }
     }
 }
 
@@ -315,3 +509,116 @@ where
         ExecutionResult::Halt { reason, .. } => InvalidTransactionError::EvmHalt(reason).into(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::cache::EthStateCache;
+    use reth_primitives::{hex_literal::hex, Address, Bytes, Header, H256};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types::BlockOverrides;
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+    // returns the balance of the contract
+    const SELF_BALANCE: [u8; 9] = hex!("4760005260206000f3");
+    // returns the block number
+    const NUMBER: [u8; 9] = hex!("4360005260206000f3");
+    // reverts without data
+    const REVERT: [u8; 5] = hex!("60006000fd");
+    // loops until it runs out of gas
+    const LOOP: [u8; 4] = hex!("5b600056");
+
+    fn eth_api(contracts: &[(Address, &[u8])]) -> (EthApi<MockEthProvider, TestPool, ()>, H256) {
+        let provider = MockEthProvider::default();
+        let hash = H256::random();
+        provider
+            .add_header(hash, Header { number: 1, gas_limit: 30_000_000, ..Default::default() });
+        for (address, code) in contracts {
+            provider.add_account(
+                *address,
+                ExtendedAccount::new(0, U256::ZERO).with_bytecode(Bytes::from(code.to_vec())),
+            );
+        }
+        let eth_api = EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            (),
+            EthStateCache::spawn(provider, Default::default()),
+        );
+        (eth_api, hash)
+    }
+
+    fn call(to: Address) -> CallRequest {
+        CallRequest { to: Some(to), gas: Some(U256::from(100_000)), ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn call_many_executes_bundles_on_top_of_each_other() {
+        let (balance, number, revert) = (Address::random(), Address::random(), Address::random());
+        let (eth_api, hash) =
+            eth_api(&[(balance, &SELF_BALANCE[..]), (number, &NUMBER[..]), (revert, &REVERT[..])]);
+        let caller = Address::random();
+        eth_api.client().add_account(caller, ExtendedAccount::new(0, U256::from(10)));
+
+        let bundles = vec![
+            Bundle {
+                transactions: vec![
+                    CallRequest { from: Some(caller), value: Some(U256::from(1)), ..call(balance) },
+                    call(number),
+                    call(revert),
+                ],
+                block_override: None,
+            },
+            Bundle {
+                transactions: vec![call(balance), call(number)],
+                block_override: Some(BlockOverrides {
+                    number: Some(U256::from(100)),
+                    ..Default::default()
+                }),
+            },
+        ];
+        let state_context =
+            StateContext { block_number: Some(hash.into()), transaction_index: None };
+        let results = eth_api.call_many(bundles, state_context, None).await.unwrap();
+
+        let word = |value: u64| Some(Bytes::from(H256::from_low_u64_be(value).as_bytes().to_vec()));
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0][0], EthCallResponse { value: word(1), error: None });
+        assert_eq!(results[0][1], EthCallResponse { value: word(1), error: None });
+        assert!(results[0][2].value.is_none());
+        assert!(results[0][2].error.is_some());
+        // the value sent by the first bundle is still there
+        assert_eq!(results[1][0], EthCallResponse { value: word(1), error: None });
+        // the block override only applies to its bundle
+        assert_eq!(results[1][1], EthCallResponse { value: word(100), error: None });
+    }
+
+    #[tokio::test]
+    async fn call_many_caps_calls_and_gas() {
+        let looping = Address::random();
+        let (eth_api, hash) = eth_api(&[(looping, &LOOP[..])]);
+        let state_context =
+            StateContext { block_number: Some(hash.into()), transaction_index: None };
+
+        let bundles = vec![Bundle {
+            transactions: vec![call(looping); MAX_CALL_MANY_CALLS + 1],
+            block_override: None,
+        }];
+        let err = eth_api.call_many(bundles, state_context.clone(), None).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)));
+
+        let loop_call = CallRequest { gas: Some(U256::from(30_000_000)), ..call(looping) };
+        let bundles =
+            vec![Bundle { transactions: vec![loop_call.clone(); 2], block_override: None }];
+        // the second call is capped to the gas left
+        let results = eth_api.call_many(bundles, state_context.clone(), None).await.unwrap();
+        assert!(results[0].iter().all(|res| res.error.is_some()));
+
+        let bundles = vec![
+            Bundle { transactions: vec![loop_call.clone(); 2], block_override: None },
+            Bundle { transactions: vec![loop_call], block_override: None },
+        ];
+        let err = eth_api.call_many(bundles, state_context, None).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)));
+    }
+}
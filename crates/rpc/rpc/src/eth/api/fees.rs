@@ -29,9 +29,18 @@ where
     }
 
     /// Returns a suggestion for the priority fee (the tip)
+    ///
+    /// See [GasPriceOracle](crate::eth::gas_oracle::GasPriceOracle).
     pub(crate) async fn suggested_priority_fee(&self) -> EthResult<U256> {
-        // TODO: properly implement sampling https://github.com/ethereum/pm/issues/328#issuecomment-853234014
-        Ok(U256::from(1e9 as u64))
+        self.inner.gas_oracle.suggest_tip_cap(self.client(), self.cache()).await
+    }
+
+    /// Returns the blob gas price of the next block.
+    ///
+    /// The price is derived from the excess blob gas of the latest header, which headers don't
+    /// store until Cancun is supported, so this always returns an unsupported error for now.
+    pub(crate) async fn blob_base_fee(&self) -> EthResult<U256> {
+        Err(EthApiError::Unsupported("blob base fee requires the excess blob gas of headers"))
     }
 
     /// Reports the fee history, for the given amount of blocks, up until the newest block
@@ -46,7 +55,10 @@ where
             return Ok(FeeHistory::default())
         }
 
-        let Some(previous_to_end_block) = self.inner.client.block_number_for_id(newest_block)? else { return Err(EthApiError::UnknownBlockNumber)};
+        let Some(previous_to_end_block) = self.inner.client.block_number_for_id(newest_block)?
+        else {
+            return Err(EthApiError::UnknownBlockNumber)
+        };
         let end_block = previous_to_end_block + 1;
 
         if end_block < block_count {
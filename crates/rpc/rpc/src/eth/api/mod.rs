@@ -3,24 +3,39 @@
 //! The entire implementation of the namespace is quite large, hence it is divided across several
 //! files.
 
-use crate::eth::{cache::EthStateCache, signer::EthSigner};
+use crate::{
+    eth::{
+        cache::EthStateCache,
+        error::EthResult,
+        gas_oracle::{GasPriceOracle, GasPriceOracleConfig},
+        signer::{DevSigner, EthSigner, PersonalSigner},
+    },
+    BlockingTaskPool, BlockingTaskPoolError,
+};
 use async_trait::async_trait;
-use reth_interfaces::Result;
+use parking_lot::{Mutex, RwLock};
+use reth_interfaces::{provider::ProviderError, Result};
 use reth_network_api::NetworkInfo;
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, ChainInfo, H256, U256, U64};
-use reth_provider::{BlockProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory};
-use reth_rpc_types::{FeeHistoryCache, SyncInfo, SyncStatus};
+use reth_provider::{
+    BlockProvider, EvmEnvProvider, PostStateProvider, StageCheckpointProvider, StateProviderBox,
+    StateProviderFactory,
+};
+use reth_rpc_types::{FeeHistoryCache, StageProgress, SyncInfo, SyncStatus};
 use reth_transaction_pool::TransactionPool;
 use std::{num::NonZeroUsize, sync::Arc};
 
 mod block;
+mod bundle;
 mod call;
 mod fees;
+mod pending_block;
 mod server;
 mod sign;
 mod state;
 mod transactions;
 use crate::eth::error::{EthApiError, EthResult};
+use pending_block::PendingBlock;
 pub use transactions::{EthTransactions, TransactionSource};
 
 /// Cache limit of block-level fee history for `eth_feeHistory` RPC method.
@@ -58,25 +73,59 @@ pub trait EthApiSpec: EthTransactions + Send + Sync {
 /// are implemented separately in submodules. The rpc handler implementation can then delegate to
 /// the main impls. This way [`EthApi`] is not limited to [`jsonrpsee`] and can be used standalone
 /// or in other network handlers (for example ipc).
-#[derive(Clone)]
 pub struct EthApi<Client, Pool, Network> {
     /// All nested fields bundled together.
     inner: Arc<EthApiInner<Client, Pool, Network>>,
     fee_history_cache: FeeHistoryCache,
+    /// The pool that blocking work, like building the pending block, is executed on.
+    blocking_pool: Option<BlockingTaskPool>,
 }
 
 impl<Client, Pool, Network> EthApi<Client, Pool, Network> {
     /// Creates a new, shareable instance.
     pub fn new(client: Client, pool: Pool, network: Network, eth_cache: EthStateCache) -> Self {
-        let inner = EthApiInner { client, pool, network, signers: Default::default(), eth_cache };
+        Self::with_gas_oracle_config(
+            client,
+            pool,
+            network,
+            eth_cache,
+            GasPriceOracleConfig::default(),
+        )
+    }
+
+    /// Creates a new, shareable instance whose gas price suggestions use the given settings.
+    pub fn with_gas_oracle_config(
+        client: Client,
+        pool: Pool,
+        network: Network,
+        eth_cache: EthStateCache,
+        gas_oracle_config: GasPriceOracleConfig,
+    ) -> Self {
+        let inner = EthApiInner {
+            client,
+            pool,
+            network,
+            signers: Default::default(),
+            eth_cache,
+            gas_oracle: GasPriceOracle::new(gas_oracle_config),
+            pending_block: Default::default(),
+            pending_block_build: Default::default(),
+        };
         Self {
             inner: Arc::new(inner),
             fee_history_cache: FeeHistoryCache::new(
                 NonZeroUsize::new(FEE_HISTORY_CACHE_LIMIT).unwrap(),
             ),
+            blocking_pool: None,
         }
     }
 
+    /// Executes blocking work on the given [BlockingTaskPool] instead of tokio's blocking threads.
+    pub fn with_blocking_pool(mut self, blocking_pool: BlockingTaskPool) -> Self {
+        self.blocking_pool = Some(blocking_pool);
+        self
+    }
+
     /// Returns the state cache frontend
     pub(crate) fn cache(&self) -> &EthStateCache {
         &self.inner.eth_cache
@@ -96,6 +145,33 @@ impl<Client, Pool, Network> EthApi<Client, Pool, Network> {
     pub fn pool(&self) -> &Pool {
         &self.inner.pool
     }
+
+    /// Adds a signer whose accounts are returned by `eth_accounts` and can be used with `eth_sign`
+    /// and `eth_sendTransaction`.
+    pub fn add_signer(&self, signer: DevSigner) {
+        self.inner.signers.write().push(Arc::new(signer));
+    }
+
+    /// Adds the signer of the accounts of the `personal_` namespace, they can be used with
+    /// `eth_sign` and `eth_sendTransaction` while unlocked.
+    pub fn add_personal_signer(&self, signer: PersonalSigner) {
+        self.inner.signers.write().push(Arc::new(signer));
+    }
+
+    /// Executes the blocking work on the [BlockingTaskPool] if configured, otherwise on tokio's
+    /// blocking threads.
+    pub(crate) async fn on_blocking_task<F, R>(&self, f: F) -> EthResult<R>
+    where
+        F: FnOnce() -> EthResult<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        match &self.blocking_pool {
+            Some(pool) => pool.spawn(async move { f() }).await?,
+            None => {
+                tokio::task::spawn_blocking(f).await.map_err(|_| BlockingTaskPoolError::Panicked)?
+            }
+        }
+    }
 }
 
 // === State access helpers ===
@@ -103,27 +179,45 @@ impl<Client, Pool, Network> EthApi<Client, Pool, Network> {
 impl<Client, Pool, Network> EthApi<Client, Pool, Network>
 where
     Client: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Pool: TransactionPool + 'static,
 {
     fn convert_block_number(&self, num: BlockNumberOrTag) -> Result<Option<u64>> {
         self.client().convert_block_number(num)
     }
 
     /// Returns the state at the given [BlockId] enum.
+    ///
+    /// The state of [BlockNumberOrTag::Pending] is the state after the most recently built pending
+    /// block, see [Self::pending_state].
     pub fn state_at_block_id(&self, at: BlockId) -> EthResult<StateProviderBox<'_>> {
         match at {
             BlockId::Hash(hash) => Ok(self.state_at_hash(hash.into())?),
-            BlockId::Number(num) => {
-                self.state_at_block_number(num)?.ok_or(EthApiError::UnknownBlockNumber)
-            }
+            BlockId::Number(BlockNumberOrTag::Pending) => self.pending_state(),
+            BlockId::Number(num) => match self.state_at_block_number(num) {
+                Ok(state) => state.ok_or(EthApiError::UnknownBlockNumber),
+                // the block isn't part of the canonical chain
+                Err(reth_interfaces::Error::Provider(ProviderError::CanonicalHeader {
+                    ..
+                })) => Err(EthApiError::UnknownBlockNumber),
+                Err(err) => Err(err.into()),
+            },
         }
     }
 
     /// Returns the state at the given [BlockId] enum or the latest.
-    pub fn state_at_block_id_or_latest(
+    ///
+    /// If the pending state is requested, the pending block is rebuilt first if it's outdated.
+    pub async fn state_at_block_id_or_latest(
         &self,
         block_id: Option<BlockId>,
-    ) -> EthResult<StateProviderBox<'_>> {
+    ) -> EthResult<StateProviderBox<'_>>
+    where
+        Network: Send + Sync + 'static,
+    {
         if let Some(block_id) = block_id {
+            if block_id.is_pending() {
+                self.pending_block().await?;
+            }
             self.state_at_block_id(block_id)
         } else {
             Ok(self.latest_state()?)
@@ -161,6 +255,28 @@ where
     pub fn latest_state(&self) -> Result<StateProviderBox<'_>> {
         self.client().latest()
     }
+
+    /// Returns the _pending_ state: the state with the transactions of the most recently built
+    /// pending block applied.
+    ///
+    /// This doesn't build the pending block, it must have been built before, which
+    /// [EthTransactions::evm_env_at] and [Self::state_at_block_id_or_latest] do for the pending
+    /// tag.
+    pub fn pending_state(&self) -> EthResult<StateProviderBox<'_>> {
+        let pending = self.last_pending_block().ok_or(EthApiError::UnknownBlockNumber)?;
+        let state = self.state_at_hash(pending.block.parent_hash)?;
+        Ok(Box::new(PostStateProvider::new(state, pending)))
+    }
+}
+
+impl<Client, Pool, Network> Clone for EthApi<Client, Pool, Network> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            fee_history_cache: self.fee_history_cache.clone(),
+            blocking_pool: self.blocking_pool.clone(),
+        }
+    }
 }
 
 impl<Client, Pool, Events> std::fmt::Debug for EthApi<Client, Pool, Events> {
@@ -173,7 +289,8 @@ impl<Client, Pool, Events> std::fmt::Debug for EthApi<Client, Pool, Events> {
 impl<Client, Pool, Network> EthApiSpec for EthApi<Client, Pool, Network>
 where
     Pool: TransactionPool + Clone + 'static,
-    Client: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Client:
+        BlockProvider + StageCheckpointProvider + StateProviderFactory + EvmEnvProvider + 'static,
     Network: NetworkInfo + 'static,
 {
     /// Returns the current ethereum protocol version.
@@ -195,7 +312,7 @@ where
     }
 
     fn accounts(&self) -> Vec<Address> {
-        self.inner.signers.iter().flat_map(|s| s.accounts()).collect()
+        self.inner.signers.read().iter().flat_map(|s| s.accounts()).collect()
     }
 
     fn is_syncing(&self) -> bool {
@@ -203,17 +320,30 @@ where
     }
 
     /// Returns the [SyncStatus] of the network
+    ///
+    /// While syncing, this includes the checkpoints of all pipeline stages. The highest block is
+    /// the highest canonical header, i.e. the tip the pipeline is syncing towards.
+    ///
+    /// Stage checkpoints only track block numbers, so the pulled and known states are omitted.
     fn sync_status(&self) -> Result<SyncStatus> {
         let status = if self.is_syncing() {
-            let current_block = U256::from(
-                self.client().chain_info().map(|info| info.best_number).unwrap_or_default(),
-            );
+            let current_block = self.client().best_block_number()?;
+            let highest_block = self.client().last_block_number()?.max(current_block);
+            let stages = self.client().stage_checkpoints()?;
             SyncStatus::Info(SyncInfo {
                 starting_block: U256::from(0),
-                current_block,
-                highest_block: current_block,
+                current_block: U256::from(current_block),
+                highest_block: U256::from(highest_block),
                 warp_chunks_amount: None,
                 warp_chunks_processed: None,
+                stages: Some(
+                    stages
+                        .into_iter()
+                        .map(|(name, block)| StageProgress { name, block: U64::from(block) })
+                        .collect(),
+                ),
+                pulled_states: None,
+                known_states: None,
             })
         } else {
             SyncStatus::None
@@ -231,7 +361,13 @@ struct EthApiInner<Client, Pool, Network> {
     /// An interface to interact with the network
     network: Network,
     /// All configured Signers
-    signers: Vec<Box<dyn EthSigner>>,
+    signers: RwLock<Vec<Arc<dyn EthSigner>>>,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
+    /// The oracle that suggests gas prices
+    gas_oracle: GasPriceOracle,
+    /// The last built pending block
+    pending_block: Mutex<Option<Arc<PendingBlock>>>,
+    /// Held while the pending block is built, so it's built once for concurrent requests
+    pending_block_build: tokio::sync::Mutex<()>,
 }
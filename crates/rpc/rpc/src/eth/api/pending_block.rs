@@ -0,0 +1,345 @@
+//! Support for the `pending` block tag, backed by a block built from the transaction pool.
+
+use crate::{
+    eth::error::{EthApiError, EthResult},
+    EthApi,
+};
+use reth_primitives::{
+    constants::{BEACON_NONCE, EMPTY_WITHDRAWALS, SLOT_DURATION},
+    proofs, Block, BlockNumHash, BlockNumber, Header, IntoRecoveredTransaction, Receipt,
+    SealedBlock, SealedHeader, EMPTY_OMMER_ROOT, H256, U256,
+};
+use reth_provider::{
+    BlockProvider, EvmEnvProvider, PostState, PostStateDataProvider, StateProviderFactory,
+};
+use reth_revm::{
+    database::{State, SubState},
+    env::tx_env_with_recovered,
+    executor::commit_state_changes,
+    into_reth_log,
+};
+use reth_transaction_pool::{BestTransactions, TransactionPool};
+use revm::primitives::{BlockEnv, CfgEnv, EVMError, Env, InvalidTransaction, ResultAndState};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+use tracing::trace;
+
+/// How long a pending block is reused while the chain head doesn't change.
+///
+/// Transactions that entered the pool in the meantime are only included once the block is
+/// rebuilt.
+pub(crate) const PENDING_BLOCK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A block built from the best transactions of the pool on top of the latest block.
+///
+/// It's what the `pending` block tag resolves to: its header, transactions and the state after its
+/// execution.
+///
+/// The state root of the block isn't computed, it's zero: computing it is as expensive as for a
+/// real block and it's of no use to callers of the RPC.
+#[derive(Debug)]
+pub(crate) struct PendingBlock {
+    /// The built block.
+    pub(crate) block: SealedBlock,
+    /// The state changes of the block's transactions.
+    pub(crate) post_state: PostState,
+    /// The configuration the block was executed with.
+    pub(crate) cfg: CfgEnv,
+    /// The block environment the block was executed with.
+    pub(crate) block_env: BlockEnv,
+    /// The latest block the block was built on.
+    parent: BlockNumHash,
+    /// When the block was built.
+    built_at: Instant,
+}
+
+// === impl PendingBlock ===
+
+impl PendingBlock {
+    /// Returns `true` if the block was built on the given head less than the interval ago.
+    fn is_current(&self, head: H256, interval: Duration) -> bool {
+        self.parent.hash == head && self.built_at.elapsed() < interval
+    }
+
+    /// Executes the best transactions of the pool on top of the parent and assembles the block.
+    ///
+    /// Transactions that don't fit into the block's gas limit or fail validation are skipped,
+    /// together with their descendants.
+    fn build<Client, Pool>(client: &Client, pool: &Pool, parent: SealedHeader) -> EthResult<Self>
+    where
+        Client: StateProviderFactory + EvmEnvProvider,
+        Pool: TransactionPool,
+    {
+        let (cfg, mut block_env) = client.env_with_header(&parent)?;
+
+        // the block follows the parent like the next payload would
+        let now =
+            SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs();
+        let timestamp = now.max(parent.timestamp + SLOT_DURATION.as_secs());
+        let base_fee = parent.next_block_base_fee().unwrap_or_default();
+        let block_number = parent.number + 1;
+        block_env.number = U256::from(block_number);
+        block_env.timestamp = U256::from(timestamp);
+        block_env.basefee = U256::from(base_fee);
+
+        let state = client.state_by_block_hash(parent.hash)?;
+        let mut db = SubState::new(State::new(state));
+        let mut post_state = PostState::default();
+
+        let block_gas_limit = parent.gas_limit;
+        let mut cumulative_gas_used = 0;
+        let mut executed_txs = Vec::new();
+        let mut best_txs = pool.best_transactions();
+
+        while let Some(pool_tx) = best_txs.next() {
+            if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
+                best_txs.mark_invalid(&pool_tx);
+                continue
+            }
+
+            let tx = pool_tx.to_recovered_transaction();
+            let env =
+                Env { cfg: cfg.clone(), block: block_env.clone(), tx: tx_env_with_recovered(&tx) };
+
+            let mut evm = revm::EVM::with_env(env);
+            evm.database(&mut db);
+
+            let ResultAndState { result, state } = match evm.transact() {
+                Ok(res) => res,
+                Err(EVMError::Transaction(err)) => {
+                    // a transaction with a nonce that's too low doesn't invalidate its descendants
+                    if !matches!(err, InvalidTransaction::NonceTooLow { .. }) {
+                        best_txs.mark_invalid(&pool_tx);
+                    }
+                    trace!(target: "rpc::eth", ?err, ?tx, "skipping invalid transaction");
+                    continue
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            commit_state_changes(&mut db, &mut post_state, block_number, state, true);
+
+            cumulative_gas_used += result.gas_used();
+            post_state.add_receipt(Receipt {
+                tx_type: tx.tx_type(),
+                success: result.is_success(),
+                cumulative_gas_used,
+                logs: result.logs().into_iter().map(into_reth_log).collect(),
+            });
+
+            executed_txs.push(tx.into_signed());
+        }
+
+        // withdrawals are only known to the consensus layer, the block has none
+        let (withdrawals, withdrawals_root) = if parent.withdrawals_root.is_some() {
+            (Some(Vec::new()), Some(EMPTY_WITHDRAWALS))
+        } else {
+            (None, None)
+        };
+
+        let receipts_root = post_state.receipts_root();
+        let logs_bloom = post_state.logs_bloom();
+        let transactions_root = proofs::calculate_transaction_root(&executed_txs);
+
+        let header = Header {
+            parent_hash: parent.hash,
+            ommers_hash: EMPTY_OMMER_ROOT,
+            beneficiary: block_env.coinbase,
+            state_root: H256::zero(),
+            transactions_root,
+            receipts_root,
+            withdrawals_root,
+            logs_bloom,
+            timestamp,
+            mix_hash: parent.mix_hash,
+            nonce: BEACON_NONCE,
+            base_fee_per_gas: Some(base_fee),
+            number: block_number,
+            gas_limit: block_gas_limit,
+            difficulty: U256::ZERO,
+            gas_used: cumulative_gas_used,
+            extra_data: Default::default(),
+        };
+        let block = Block { header, body: executed_txs, ommers: vec![], withdrawals }.seal_slow();
+
+        Ok(Self {
+            block,
+            post_state,
+            cfg,
+            block_env,
+            parent: BlockNumHash::new(parent.number, parent.hash),
+            built_at: Instant::now(),
+        })
+    }
+}
+
+impl PostStateDataProvider for PendingBlock {
+    fn state(&self) -> &PostState {
+        &self.post_state
+    }
+
+    fn block_hash(&self, block_number: BlockNumber) -> Option<H256> {
+        (block_number == self.parent.number).then_some(self.parent.hash)
+    }
+
+    fn canonical_fork(&self) -> BlockNumHash {
+        self.parent
+    }
+}
+
+impl<Client, Pool, Network> EthApi<Client, Pool, Network>
+where
+    Client: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Pool: TransactionPool + 'static,
+    Network: Send + Sync + 'static,
+{
+    /// Returns the block the `pending` tag resolves to.
+    ///
+    /// The block is built on top of the latest block on the blocking pool and reused until the
+    /// chain head changes or [PENDING_BLOCK_INTERVAL] passed, so it's rebuilt at most once per
+    /// interval. Concurrent requests wait for the same build.
+    pub(crate) async fn pending_block(&self) -> EthResult<Arc<PendingBlock>> {
+        let head = self.client().chain_info()?.best_hash;
+
+        let _build = self.inner.pending_block_build.lock().await;
+        if let Some(block) =
+            self.last_pending_block().filter(|block| block.is_current(head, PENDING_BLOCK_INTERVAL))
+        {
+            return Ok(block)
+        }
+
+        let parent =
+            self.client().header(&head)?.ok_or(EthApiError::UnknownBlockNumber)?.seal(head);
+        let inner = self.inner.clone();
+        let block = self
+            .on_blocking_task(move || PendingBlock::build(&inner.client, &inner.pool, parent))
+            .await?;
+
+        let block = Arc::new(block);
+        *self.inner.pending_block.lock() = Some(block.clone());
+        Ok(block)
+    }
+}
+
+impl<Client, Pool, Network> EthApi<Client, Pool, Network> {
+    /// Returns the most recently built pending block, see [Self::pending_block].
+    pub(crate) fn last_pending_block(&self) -> Option<Arc<PendingBlock>> {
+        self.inner.pending_block.lock().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::cache::EthStateCache;
+    use reth_primitives::constants::EIP1559_INITIAL_BASE_FEE;
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider},
+        StateProvider,
+    };
+    use reth_transaction_pool::{
+        test_utils::{MockOrdering, MockTransaction},
+        Pool, PoolTransaction, TransactionOrigin, TransactionValidationOutcome,
+        TransactionValidator,
+    };
+
+    /// Validates every transaction as if its sender could pay for it.
+    struct FundedValidator;
+
+    #[async_trait::async_trait]
+    impl TransactionValidator for FundedValidator {
+        type Transaction = MockTransaction;
+
+        async fn validate_transaction(
+            &self,
+            _origin: TransactionOrigin,
+            transaction: Self::Transaction,
+        ) -> TransactionValidationOutcome<Self::Transaction> {
+            TransactionValidationOutcome::Valid { balance: U256::MAX, state_nonce: 0, transaction }
+        }
+    }
+
+    fn header(number: u64) -> SealedHeader {
+        Header {
+            number,
+            gas_limit: 1_000_000,
+            base_fee_per_gas: Some(EIP1559_INITIAL_BASE_FEE),
+            timestamp: 1_700_000_000 + number * 12,
+            ..Default::default()
+        }
+        .seal_slow()
+    }
+
+    #[tokio::test]
+    async fn builds_pending_block_from_pool() {
+        let provider = MockEthProvider::default();
+        let parent = header(1);
+        provider.add_header(parent.hash, parent.header.clone());
+
+        let pool = Pool::new(FundedValidator, MockOrdering::default(), Default::default());
+        let tx = MockTransaction::legacy()
+            .with_gas_price(EIP1559_INITIAL_BASE_FEE as u128)
+            .with_gas_limit(21_000);
+        provider
+            .add_account(tx.sender(), ExtendedAccount::new(0, U256::from(10).pow(U256::from(18))));
+        // doesn't fit into the block, but into the pool's block gas limit
+        let too_large = MockTransaction::legacy()
+            .with_gas_price(EIP1559_INITIAL_BASE_FEE as u128)
+            .with_gas_limit(parent.gas_limit * 2);
+        pool.add_transaction(TransactionOrigin::External, tx.clone()).await.unwrap();
+        pool.add_transaction(TransactionOrigin::External, too_large).await.unwrap();
+
+        let eth_api = EthApi::new(
+            provider.clone(),
+            pool,
+            (),
+            EthStateCache::spawn(provider.clone(), Default::default()),
+        );
+        let pending = eth_api.pending_block().await.unwrap();
+
+        assert_eq!(pending.block.number, parent.number + 1);
+        assert_eq!(pending.block.parent_hash, parent.hash);
+        assert_eq!(pending.block.state_root, H256::zero());
+        assert_eq!(pending.block.gas_used, 21_000);
+        assert_eq!(pending.block.body.len(), 1);
+        assert_eq!(pending.block.body[0].hash(), *tx.hash());
+        assert_eq!(pending.post_state.receipts().len(), 1);
+
+        // the state after the pending block includes the transaction
+        let state = eth_api.pending_state().unwrap();
+        assert_eq!(state.account_nonce(tx.sender()).unwrap(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn reuses_pending_block_until_new_head() {
+        let provider = MockEthProvider::default();
+        let parent = header(1);
+        provider.add_header(parent.hash, parent.header.clone());
+
+        let pool = Pool::new(FundedValidator, MockOrdering::default(), Default::default());
+        let eth_api = EthApi::new(
+            provider.clone(),
+            pool,
+            (),
+            EthStateCache::spawn(provider.clone(), Default::default()),
+        );
+        assert!(eth_api.pending_state().is_err());
+
+        let first = eth_api.pending_block().await.unwrap();
+        let second = eth_api.pending_block().await.unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // a new head invalidates the block before the interval passed
+        let head = header(2);
+        provider.add_header(head.hash, head.header.clone());
+        let third = eth_api.pending_block().await.unwrap();
+        assert!(!Arc::ptr_eq(&second, &third));
+        assert_eq!(third.block.parent_hash, head.hash);
+
+        // the block is rebuilt once the interval passed
+        assert!(third.is_current(head.hash, PENDING_BLOCK_INTERVAL));
+        assert!(!third.is_current(head.hash, Duration::ZERO));
+    }
+}
@@ -15,11 +15,15 @@ use reth_primitives::{
     serde_helper::JsonStorageKey, AccessListWithGasUsed, Address, BlockId, BlockNumberOrTag, Bytes,
     H256, H64, U256, U64,
 };
-use reth_provider::{BlockProvider, EvmEnvProvider, HeaderProvider, StateProviderFactory};
+use reth_provider::{
+    AccountHistoryProvider, BlockProvider, EvmEnvProvider, HeaderProvider, StateProviderFactory,
+};
 use reth_rpc_api::EthApiServer;
 use reth_rpc_types::{
-    state::StateOverride, CallRequest, EIP1186AccountProofResponse, FeeHistory, Index, RichBlock,
-    SyncStatus, TransactionReceipt, TransactionRequest, Work,
+    state::{EvmOverrides, StateOverride},
+    BlockOverrides, Bundle, CallBundle, CallBundleResponse, CallRequest,
+    EIP1186AccountProofResponse, EthCallResponse, FeeHistory, FillTransactionResult, Index,
+    RichBlock, StateContext, SyncStatus, TransactionReceipt, TransactionRequest, Work,
 };
 use reth_transaction_pool::TransactionPool;
 use serde_json::Value;
@@ -30,7 +34,12 @@ impl<Client, Pool, Network> EthApiServer for EthApi<Client, Pool, Network>
 where
     Self: EthApiSpec + EthTransactions,
     Pool: TransactionPool + 'static,
-    Client: BlockProvider + HeaderProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Client: BlockProvider
+        + HeaderProvider
+        + AccountHistoryProvider
+        + StateProviderFactory
+        + EvmEnvProvider
+        + 'static,
     Network: NetworkInfo + Send + Sync + 'static,
 {
     /// Handler for: `eth_protocolVersion`
@@ -159,16 +168,32 @@ where
         Ok(EthApi::transaction_by_block_and_tx_index(self, number, index).await?)
     }
 
+    /// Handler for: `eth_getTransactionBySenderAndNonce`
+    async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: U64,
+    ) -> Result<Option<reth_rpc_types::Transaction>> {
+        trace!(target: "rpc::eth", ?sender, ?nonce, "Serving eth_getTransactionBySenderAndNonce");
+        Ok(EthApi::transaction_by_sender_and_nonce(self, sender, nonce.as_u64()).await?)
+    }
+
     /// Handler for: `eth_getTransactionReceipt`
     async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>> {
         trace!(target: "rpc::eth", ?hash, "Serving eth_getTransactionReceipt");
         Ok(EthTransactions::transaction_receipt(self, hash).await?)
     }
 
+    /// Handler for: `eth_getBlockReceipts`
+    async fn block_receipts(&self, block_id: BlockId) -> Result<Option<Vec<TransactionReceipt>>> {
+        trace!(target: "rpc::eth", ?block_id, "Serving eth_getBlockReceipts");
+        Ok(EthApi::block_receipts(self, block_id).await?)
+    }
+
     /// Handler for: `eth_getBalance`
     async fn balance(&self, address: Address, block_number: Option<BlockId>) -> Result<U256> {
         trace!(target: "rpc::eth", ?address, ?block_number, "Serving eth_getBalance");
-        Ok(EthApi::balance(self, address, block_number)?)
+        Ok(EthApi::balance(self, address, block_number).await?)
     }
 
     /// Handler for: `eth_getStorageAt`
@@ -179,7 +204,7 @@ where
         block_number: Option<BlockId>,
     ) -> Result<H256> {
         trace!(target: "rpc::eth", ?address, ?block_number, "Serving eth_getStorageAt");
-        Ok(EthApi::storage_at(self, address, index, block_number)?)
+        Ok(EthApi::storage_at(self, address, index, block_number).await?)
     }
 
     /// Handler for: `eth_getTransactionCount`
@@ -189,13 +214,13 @@ where
         block_number: Option<BlockId>,
     ) -> Result<U256> {
         trace!(target: "rpc::eth", ?address, ?block_number, "Serving eth_getTransactionCount");
-        Ok(EthApi::get_transaction_count(self, address, block_number)?)
+        Ok(EthApi::get_transaction_count(self, address, block_number).await?)
     }
 
     /// Handler for: `eth_getCode`
     async fn get_code(&self, address: Address, block_number: Option<BlockId>) -> Result<Bytes> {
         trace!(target: "rpc::eth", ?address, ?block_number, "Serving eth_getCode");
-        Ok(EthApi::get_code(self, address, block_number)?)
+        Ok(EthApi::get_code(self, address, block_number).await?)
     }
 
     /// Handler for: `eth_call`
@@ -204,31 +229,46 @@ where
         request: CallRequest,
         block_number: Option<BlockId>,
         state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<Bytes> {
-        trace!(target: "rpc::eth", ?request, ?block_number, ?state_overrides, "Serving eth_call");
+        trace!(target: "rpc::eth", ?request, ?block_number, ?state_overrides, ?block_overrides, "Serving eth_call");
         let (res, _env) = self
             .transact_call_at(
                 request,
                 block_number.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)),
-                state_overrides,
+                EvmOverrides::new(state_overrides, block_overrides),
             )
             .await?;
 
         Ok(ensure_success(res.result)?)
     }
 
+    /// Handler for: `eth_callMany`
+    async fn call_many(
+        &self,
+        bundles: Vec<Bundle>,
+        state_context: Option<StateContext>,
+        state_override: Option<StateOverride>,
+    ) -> Result<Vec<Vec<EthCallResponse>>> {
+        trace!(target: "rpc::eth", ?bundles, ?state_context, ?state_override, "Serving eth_callMany");
+        Ok(EthApi::call_many(self, bundles, state_context.unwrap_or_default(), state_override)
+            .await?)
+    }
+
+    /// Handler for: `eth_callBundle`
+    async fn call_bundle(&self, bundle: CallBundle) -> Result<CallBundleResponse> {
+        trace!(target: "rpc::eth", ?bundle, "Serving eth_callBundle");
+        Ok(EthApi::call_bundle(self, bundle).await?)
+    }
+
     /// Handler for: `eth_createAccessList`
     async fn create_access_list(
         &self,
-        mut request: CallRequest,
+        request: CallRequest,
         block_number: Option<BlockId>,
     ) -> Result<AccessListWithGasUsed> {
         trace!(target: "rpc::eth", ?request, ?block_number, "Serving eth_createAccessList");
-        let block_id = block_number.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
-        let access_list = self.create_access_list_at(request.clone(), block_number).await?;
-        request.access_list = Some(access_list.clone());
-        let gas_used = self.estimate_gas_at(request, block_id).await?;
-        Ok(AccessListWithGasUsed { access_list, gas_used })
+        Ok(self.create_access_list_at(request, block_number).await?)
     }
 
     /// Handler for: `eth_estimateGas`
@@ -236,12 +276,15 @@ where
         &self,
         request: CallRequest,
         block_number: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<U256> {
-        trace!(target: "rpc::eth", ?request, ?block_number, "Serving eth_estimateGas");
+        trace!(target: "rpc::eth", ?request, ?block_number, ?state_overrides, ?block_overrides, "Serving eth_estimateGas");
         Ok(EthApi::estimate_gas_at(
             self,
             request,
             block_number.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)),
+            EvmOverrides::new(state_overrides, block_overrides),
         )
         .await?)
     }
@@ -258,6 +301,12 @@ where
         return Ok(EthApi::suggested_priority_fee(self).await?)
     }
 
+    /// Handler for: `eth_blobBaseFee`
+    async fn blob_base_fee(&self) -> Result<U256> {
+        trace!(target: "rpc::eth", "Serving eth_blobBaseFee");
+        Ok(EthApi::blob_base_fee(self).await?)
+    }
+
     // FeeHistory is calculated based on lazy evaluation of fees for historical blocks, and further
     // caching of it in the LRU cache.
     // When new RPC call is executed, the cache gets locked, we check it for the historical fees
@@ -309,6 +358,12 @@ where
         Ok(EthTransactions::send_transaction(self, request).await?)
     }
 
+    /// Handler for: `eth_fillTransaction`
+    async fn fill_transaction(&self, request: TransactionRequest) -> Result<FillTransactionResult> {
+        trace!(target: "rpc::eth", ?request, "Serving eth_fillTransaction");
+        Ok(EthApi::fill_transaction(self, request).await?)
+    }
+
     /// Handler for: `eth_sendRawTransaction`
     async fn send_raw_transaction(&self, tx: Bytes) -> Result<H256> {
         trace!(target: "rpc::eth", ?tx, "Serving eth_sendRawTransaction");
@@ -335,21 +390,12 @@ where
     /// Handler for: `eth_getProof`
     async fn get_proof(
         &self,
-        _address: Address,
-        _keys: Vec<JsonStorageKey>,
-        _block_number: Option<BlockId>,
+        address: Address,
+        keys: Vec<JsonStorageKey>,
+        block_number: Option<BlockId>,
     ) -> Result<EIP1186AccountProofResponse> {
-        // TODO: uncomment when implemented
-        // trace!(target: "rpc::eth", ?address, ?keys, ?block_number, "Serving eth_getProof");
-        // let res = EthApi::get_proof(self, address, keys, block_number);
-
-        // Ok(res.map_err(|e| match e {
-        //     EthApiError::InvalidBlockRange => {
-        //         internal_rpc_err("eth_getProof is unimplemented for historical blocks")
-        //     }
-        //     _ => e.into(),
-        // })?)
-        Err(internal_rpc_err("unimplemented"))
+        trace!(target: "rpc::eth", ?address, ?keys, ?block_number, "Serving eth_getProof");
+        Ok(EthApi::get_proof(self, address, keys, block_number).await?)
     }
 }
 
@@ -6,10 +6,9 @@ use crate::{
     },
     EthApi,
 };
-use ethers_core::types::transaction::eip712::TypedData;
-use reth_primitives::{Address, Bytes};
+use reth_primitives::{eip712::TypedData, Address, Bytes};
 use serde_json::Value;
-use std::ops::Deref;
+use std::sync::Arc;
 
 impl<Client, Pool, Network> EthApi<Client, Pool, Network> {
     pub(crate) async fn sign(&self, account: Address, message: Bytes) -> EthResult<Bytes> {
@@ -27,15 +26,13 @@ impl<Client, Pool, Network> EthApi<Client, Pool, Network> {
         Ok(bytes)
     }
 
-    pub(crate) fn find_signer(
-        &self,
-        account: &Address,
-    ) -> Result<&(dyn EthSigner + 'static), SignError> {
+    pub(crate) fn find_signer(&self, account: &Address) -> Result<Arc<dyn EthSigner>, SignError> {
         self.inner
             .signers
+            .read()
             .iter()
             .find(|signer| signer.is_signer_for(account))
-            .map(|signer| signer.deref())
+            .cloned()
             .ok_or(SignError::NoAccount)
     }
 }
@@ -9,24 +9,40 @@ use reth_primitives::{
     U256,
 };
 use reth_provider::{
-    AccountProvider, BlockProvider, EvmEnvProvider, StateProvider, StateProviderFactory,
+    AccountProvider, BlockIdProvider, BlockProvider, EvmEnvProvider, StateProvider,
+    StateProviderFactory,
 };
 use reth_rpc_types::{EIP1186AccountProofResponse, StorageProof};
 use reth_transaction_pool::{PoolTransaction, TransactionPool};
 
+/// The maximum number of blocks between the head and the block proofs are served for.
+///
+/// The proofs of a historical block are computed by reverting the changes of all later blocks on
+/// top of the latest state, so the cost grows with the distance to the head.
+const MAX_PROOF_BLOCK_DISTANCE: u64 = 1_024;
+
 impl<Client, Pool, Network> EthApi<Client, Pool, Network>
 where
     Client: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
     Pool: TransactionPool + Clone + 'static,
+    Network: Send + Sync + 'static,
 {
-    pub(crate) fn get_code(&self, address: Address, block_id: Option<BlockId>) -> EthResult<Bytes> {
-        let state = self.state_at_block_id_or_latest(block_id)?;
+    pub(crate) async fn get_code(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> EthResult<Bytes> {
+        let state = self.state_at_block_id_or_latest(block_id).await?;
         let code = state.account_code(address)?.unwrap_or_default();
         Ok(code.original_bytes().into())
     }
 
-    pub(crate) fn balance(&self, address: Address, block_id: Option<BlockId>) -> EthResult<U256> {
-        let state = self.state_at_block_id_or_latest(block_id)?;
+    pub(crate) async fn balance(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> EthResult<U256> {
+        let state = self.state_at_block_id_or_latest(block_id).await?;
         let balance = state.account_balance(address)?.unwrap_or_default();
         Ok(balance)
     }
@@ -35,7 +51,7 @@ where
     ///
     /// If this is [BlockNumberOrTag::Pending] then this will look up the highest transaction in
     /// pool and return the next nonce (highest + 1).
-    pub(crate) fn get_transaction_count(
+    pub(crate) async fn get_transaction_count(
         &self,
         address: Address,
         block_id: Option<BlockId>,
@@ -66,45 +82,49 @@ where
             }
         }
 
-        let state = self.state_at_block_id_or_latest(block_id)?;
+        let state = self.state_at_block_id_or_latest(block_id).await?;
         Ok(U256::from(state.account_nonce(address)?.unwrap_or_default()))
     }
 
-    pub(crate) fn storage_at(
+    pub(crate) async fn storage_at(
         &self,
         address: Address,
         index: JsonStorageKey,
         block_id: Option<BlockId>,
     ) -> EthResult<H256> {
-        let state = self.state_at_block_id_or_latest(block_id)?;
+        let state = self.state_at_block_id_or_latest(block_id).await?;
         let value = state.storage(address, index.0)?.unwrap_or_default();
         Ok(H256(value.to_be_bytes()))
     }
 
-    pub(crate) fn get_proof(
+    pub(crate) async fn get_proof(
         &self,
         address: Address,
         keys: Vec<JsonStorageKey>,
         block_id: Option<BlockId>,
     ) -> EthResult<EIP1186AccountProofResponse> {
-        let chain_info = self.client().chain_info()?;
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
-
-        // if we are trying to create a proof for the latest block, but have a BlockId as input
-        // that is not BlockNumberOrTag::Latest, then we need to figure out whether or not the
-        // BlockId corresponds to the latest block
-        let is_blockid_latest = match block_id {
-            BlockId::Number(BlockNumberOrTag::Number(num)) => num == chain_info.best_number,
-            BlockId::Hash(hash) => hash == chain_info.best_hash.into(),
-            BlockId::Number(BlockNumberOrTag::Latest) => true,
-            _ => false,
-        };
-
-        // TODO: remove when HistoricalStateProviderRef::proof is implemented
-        if !is_blockid_latest {
-            return Err(EthApiError::InvalidBlockRange)
+        if let Some(number) = self.client().block_number_for_id(block_id)? {
+            let best_number = self.client().best_block_number()?;
+            if best_number.saturating_sub(number) > MAX_PROOF_BLOCK_DISTANCE {
+                return Err(EthApiError::ExceedsMaxProofWindow)
+            }
         }
+        if block_id.is_pending() {
+            self.pending_block().await?;
+        }
+
+        let this = self.clone();
+        self.on_blocking_task(move || this.get_proof_blocking(address, keys, block_id)).await
+    }
 
+    /// Computes the proofs of [Self::get_proof], which reads the trie tables.
+    fn get_proof_blocking(
+        &self,
+        address: Address,
+        keys: Vec<JsonStorageKey>,
+        block_id: BlockId,
+    ) -> EthResult<EIP1186AccountProofResponse> {
         let state = self.state_at_block_id(block_id)?;
 
         let hash_keys = keys.iter().map(|key| key.0).collect::<Vec<_>>();
@@ -145,7 +165,7 @@ where
 mod tests {
     use super::*;
     use crate::eth::cache::EthStateCache;
-    use reth_primitives::{StorageKey, StorageValue};
+    use reth_primitives::{Header, StorageKey, StorageValue};
     use reth_provider::test_utils::{ExtendedAccount, MockEthProvider, NoopProvider};
     use reth_transaction_pool::test_utils::testing_pool;
     use std::collections::HashMap;
@@ -162,7 +182,7 @@ mod tests {
             EthStateCache::spawn(NoopProvider::default(), Default::default()),
         );
         let address = Address::random();
-        let storage = eth_api.storage_at(address, U256::ZERO.into(), None).unwrap();
+        let storage = eth_api.storage_at(address, U256::ZERO.into(), None).await.unwrap();
         assert_eq!(storage, U256::ZERO.into());
 
         // === Mock ===
@@ -181,7 +201,24 @@ mod tests {
         );
 
         let storage_key: U256 = storage_key.into();
-        let storage = eth_api.storage_at(address, storage_key.into(), None).unwrap();
+        let storage = eth_api.storage_at(address, storage_key.into(), None).await.unwrap();
         assert_eq!(storage, storage_value.into());
     }
+
+    #[tokio::test]
+    async fn test_proof_window() {
+        let mock_provider = MockEthProvider::default();
+        mock_provider.add_header(H256::random(), Header { number: 2_000, ..Default::default() });
+
+        let eth_api = EthApi::new(
+            mock_provider.clone(),
+            testing_pool(),
+            (),
+            EthStateCache::spawn(mock_provider, Default::default()),
+        );
+
+        let block_id = BlockId::Number((2_000 - MAX_PROOF_BLOCK_DISTANCE - 1).into());
+        let err = eth_api.get_proof(Address::random(), vec![], Some(block_id)).await.unwrap_err();
+        assert!(matches!(err, EthApiError::ExceedsMaxProofWindow));
+    }
 }
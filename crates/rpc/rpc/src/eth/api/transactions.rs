@@ -3,28 +3,32 @@ use crate::{
     eth::{
         error::{EthApiError, EthResult, SignError},
         revm_utils::{inspect, prepare_call_env, transact},
+        signer::EthSigner,
         utils::recover_raw_transaction,
     },
     EthApi, EthApiSpec,
 };
 use async_trait::async_trait;
+use bytes::BytesMut;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
-    Address, BlockId, BlockNumberOrTag, Bytes, FromRecoveredTransaction, IntoRecoveredTransaction,
-    Receipt, Transaction as PrimitiveTransaction,
+    keccak256, Address, BlockId, BlockNumberOrTag, Bytes, FromRecoveredTransaction,
+    IntoRecoveredTransaction, Receipt, Transaction as PrimitiveTransaction,
     TransactionKind::{Call, Create},
     TransactionMeta, TransactionSigned, TransactionSignedEcRecovered, TxEip1559, TxEip2930,
     TxLegacy, H256, U128, U256, U64,
 };
-use reth_provider::{BlockProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory};
+use reth_provider::{
+    AccountHistoryProvider, BlockProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
+};
 use reth_revm::{
     database::{State, SubState},
     env::tx_env_with_recovered,
     tracing::{TracingInspector, TracingInspectorConfig},
 };
 use reth_rpc_types::{
-    state::StateOverride, CallRequest, Index, Log, Transaction, TransactionInfo,
-    TransactionReceipt, TransactionRequest, TypedTransactionRequest,
+    state::EvmOverrides, CallRequest, FillTransactionResult, Index, Log, Signature, Transaction,
+    TransactionInfo, TransactionReceipt, TransactionRequest, TypedTransactionRequest,
 };
 use reth_transaction_pool::{TransactionOrigin, TransactionPool};
 use revm::{
@@ -91,7 +95,7 @@ pub trait EthTransactions: Send + Sync {
         &self,
         request: CallRequest,
         at: BlockId,
-        state_overrides: Option<StateOverride>,
+        overrides: EvmOverrides,
         f: F,
     ) -> EthResult<R>
     where
@@ -102,7 +106,7 @@ pub trait EthTransactions: Send + Sync {
         &self,
         request: CallRequest,
         at: BlockId,
-        state_overrides: Option<StateOverride>,
+        overrides: EvmOverrides,
     ) -> EthResult<(ResultAndState, Env)>;
 
     /// Executes the call request at the given [BlockId]
@@ -110,7 +114,7 @@ pub trait EthTransactions: Send + Sync {
         &self,
         request: CallRequest,
         at: BlockId,
-        state_overrides: Option<StateOverride>,
+        overrides: EvmOverrides,
         inspector: I,
     ) -> EthResult<(ResultAndState, Env)>
     where
@@ -160,12 +164,10 @@ where
     }
 
     async fn evm_env_at(&self, at: BlockId) -> EthResult<(CfgEnv, BlockEnv, BlockId)> {
-        // TODO handle Pending state's env
         match at {
             BlockId::Number(BlockNumberOrTag::Pending) => {
-                // This should perhaps use the latest env settings and update block specific
-                // settings like basefee/number
-                Err(EthApiError::Unsupported("pending state not implemented yet"))
+                let pending = self.pending_block().await?;
+                Ok((pending.cfg.clone(), pending.block_env.clone(), at))
             }
             hash_or_num => {
                 let block_hash = self
@@ -265,87 +267,15 @@ where
         Ok(hash)
     }
 
-    async fn send_transaction(&self, mut request: TransactionRequest) -> EthResult<H256> {
-        let from = match request.from {
-            Some(from) => from,
-            None => return Err(SignError::NoAccount.into()),
-        };
-
-        // set nonce if not already set before
-        if request.nonce.is_none() {
-            let nonce =
-                self.get_transaction_count(from, Some(BlockId::Number(BlockNumberOrTag::Pending)))?;
-            request.nonce = Some(nonce);
-        }
-
-        let chain_id = self.chain_id();
-        // TODO: we need an oracle to fetch the gas price of the current chain
-        let gas_price = request.gas_price.unwrap_or_default();
-        let max_fee_per_gas = request.max_fee_per_gas.unwrap_or_default();
-
-        let estimated_gas = self
-            .estimate_gas_at(
-                CallRequest {
-                    from: Some(from),
-                    to: request.to,
-                    gas: request.gas,
-                    gas_price: Some(U256::from(gas_price)),
-                    max_fee_per_gas: Some(U256::from(max_fee_per_gas)),
-                    value: request.value,
-                    data: request.data.clone(),
-                    nonce: request.nonce,
-                    chain_id: Some(chain_id),
-                    access_list: request.access_list.clone(),
-                    max_priority_fee_per_gas: Some(U256::from(max_fee_per_gas)),
-                },
-                BlockId::Number(BlockNumberOrTag::Pending),
-            )
-            .await?;
-        let gas_limit = estimated_gas;
-
-        let transaction = match request.into_typed_request() {
-            Some(TypedTransactionRequest::Legacy(mut m)) => {
-                m.chain_id = Some(chain_id.as_u64());
-                m.gas_limit = gas_limit;
-                m.gas_price = gas_price;
-
-                TypedTransactionRequest::Legacy(m)
-            }
-            Some(TypedTransactionRequest::EIP2930(mut m)) => {
-                m.chain_id = chain_id.as_u64();
-                m.gas_limit = gas_limit;
-                m.gas_price = gas_price;
-
-                TypedTransactionRequest::EIP2930(m)
-            }
-            Some(TypedTransactionRequest::EIP1559(mut m)) => {
-                m.chain_id = chain_id.as_u64();
-                m.gas_limit = gas_limit;
-                m.max_fee_per_gas = max_fee_per_gas;
-
-                TypedTransactionRequest::EIP1559(m)
-            }
-            None => return Err(EthApiError::ConflictingFeeFieldsInRequest),
-        };
-
-        let signed_tx = self.sign_request(&from, transaction)?;
-
-        let recovered =
-            signed_tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
-
-        let pool_transaction = <Pool::Transaction>::from_recovered_transaction(recovered);
-
-        // submit the transaction to the pool with a `Local` origin
-        let hash = self.pool().add_transaction(TransactionOrigin::Local, pool_transaction).await?;
-
-        Ok(hash)
+    async fn send_transaction(&self, request: TransactionRequest) -> EthResult<H256> {
+        self.send_transaction_with_signer(request, None).await
     }
 
     async fn with_call_at<F, R>(
         &self,
         request: CallRequest,
         at: BlockId,
-        state_overrides: Option<StateOverride>,
+        overrides: EvmOverrides,
         f: F,
     ) -> EthResult<R>
     where
@@ -355,7 +285,7 @@ where
         let state = self.state_at(at)?;
         let mut db = SubState::new(State::new(state));
 
-        let env = prepare_call_env(cfg, block_env, request, &mut db, state_overrides)?;
+        let env = prepare_call_env(cfg, block_env, request, &mut db, overrides)?;
         f(db, env)
     }
 
@@ -363,22 +293,22 @@ where
         &self,
         request: CallRequest,
         at: BlockId,
-        state_overrides: Option<StateOverride>,
+        overrides: EvmOverrides,
     ) -> EthResult<(ResultAndState, Env)> {
-        self.with_call_at(request, at, state_overrides, |mut db, env| transact(&mut db, env)).await
+        self.with_call_at(request, at, overrides, |mut db, env| transact(&mut db, env)).await
     }
 
     async fn inspect_call_at<I>(
         &self,
         request: CallRequest,
         at: BlockId,
-        state_overrides: Option<StateOverride>,
+        overrides: EvmOverrides,
         inspector: I,
     ) -> EthResult<(ResultAndState, Env)>
     where
         I: for<'r> Inspector<CacheDB<State<StateProviderBox<'r>>>> + Send,
     {
-        self.with_call_at(request, at, state_overrides, |db, env| inspect(db, env, inspector)).await
+        self.with_call_at(request, at, overrides, |db, env| inspect(db, env, inspector)).await
     }
 
     fn trace_at<F, R>(
@@ -427,18 +357,173 @@ where
 
 // === impl EthApi ===
 
+impl<Client, Pool, Network> EthApi<Client, Pool, Network>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Client: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+{
+    /// Fills in the missing fields of the request, signs it and submits it to the pool.
+    ///
+    /// The transaction is signed with the given signer, or with the matching signer of the `eth_`
+    /// namespace if there's none.
+    pub(crate) async fn send_transaction_with_signer(
+        &self,
+        request: TransactionRequest,
+        signer: Option<&(dyn EthSigner + 'static)>,
+    ) -> EthResult<H256> {
+        let from = match request.from {
+            Some(from) => from,
+            None => return Err(SignError::NoAccount.into()),
+        };
+        let transaction = self.fill_transaction_request(from, request).await?;
+
+        let signed_tx = match signer {
+            Some(signer) => signer.sign_transaction(transaction, &from)?,
+            None => self.sign_request(&from, transaction)?,
+        };
+
+        let recovered =
+            signed_tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
+
+        let pool_transaction = <Pool::Transaction>::from_recovered_transaction(recovered);
+
+        // submit the transaction to the pool with a `Local` origin
+        let hash = self.pool().add_transaction(TransactionOrigin::Local, pool_transaction).await?;
+
+        Ok(hash)
+    }
+
+    /// Fills in the missing fields of the request like `eth_sendTransaction` and returns the
+    /// unsigned transaction, like geth does for `eth_fillTransaction`.
+    ///
+    /// If the request has no sender, the fields are filled in for the zero address.
+    pub(crate) async fn fill_transaction(
+        &self,
+        request: TransactionRequest,
+    ) -> EthResult<FillTransactionResult> {
+        let from = request.from.unwrap_or_default();
+        let transaction = self.fill_transaction_request(from, request).await?.into_transaction();
+
+        let mut raw = BytesMut::new();
+        transaction.encode_unsigned(&mut raw);
+        let raw = Bytes::from(raw.freeze());
+
+        let signed =
+            TransactionSigned::from_transaction_and_signature(transaction, Default::default());
+        let mut tx = Transaction::from_recovered(
+            TransactionSignedEcRecovered::from_signed_transaction(signed, from),
+        );
+        // like geth, the unsigned transaction's hash is the hash of its encoding and its signature
+        // is zero
+        tx.hash = keccak256(&raw);
+        tx.signature = Some(Signature { r: U256::ZERO, s: U256::ZERO, v: U256::ZERO });
+
+        Ok(FillTransactionResult { raw, tx })
+    }
+
+    /// Fills in the nonce, fees, gas limit and chain id of the request, from the state of the
+    /// pending block and the gas price oracle.
+    async fn fill_transaction_request(
+        &self,
+        from: Address,
+        mut request: TransactionRequest,
+    ) -> EthResult<TypedTransactionRequest> {
+        // set nonce if not already set before
+        if request.nonce.is_none() {
+            let nonce = self
+                .get_transaction_count(from, Some(BlockId::Number(BlockNumberOrTag::Pending)))
+                .await?;
+            request.nonce = Some(nonce);
+        }
+
+        // like geth, fill in the fees of EIP-1559 requests with the suggested tip and a fee cap
+        // that covers a doubling of the base fee
+        if request.gas_price.is_none() {
+            if request.max_priority_fee_per_gas.is_none() {
+                let tip = self.suggested_priority_fee().await?;
+                request.max_priority_fee_per_gas = Some(U128::from(tip.saturating_to::<u128>()));
+            }
+            if request.max_fee_per_gas.is_none() {
+                let base_fee = self
+                    .block(BlockNumberOrTag::Latest)
+                    .await?
+                    .and_then(|block| block.base_fee_per_gas)
+                    .unwrap_or_default();
+                let tip = request.max_priority_fee_per_gas.unwrap_or_default();
+                request.max_fee_per_gas = Some(tip + U128::from(base_fee) * U128::from(2));
+            }
+        }
+
+        let chain_id = self.chain_id();
+        let gas_price = request.gas_price.unwrap_or_default();
+        let max_fee_per_gas = request.max_fee_per_gas.unwrap_or_default();
+        let max_priority_fee_per_gas = request.max_priority_fee_per_gas.unwrap_or_default();
+
+        let estimated_gas = self
+            .estimate_gas_at(
+                CallRequest {
+                    from: Some(from),
+                    to: request.to,
+                    gas: request.gas,
+                    gas_price: Some(U256::from(gas_price)),
+                    max_fee_per_gas: Some(U256::from(max_fee_per_gas)),
+                    value: request.value,
+                    data: request.data.clone(),
+                    nonce: request.nonce,
+                    chain_id: Some(chain_id),
+                    access_list: request.access_list.clone(),
+                    max_priority_fee_per_gas: Some(U256::from(max_priority_fee_per_gas)),
+                },
+                BlockId::Number(BlockNumberOrTag::Pending),
+                EvmOverrides::default(),
+            )
+            .await?;
+        let gas_limit = estimated_gas;
+
+        let transaction = match request.into_typed_request() {
+            Some(TypedTransactionRequest::Legacy(mut m)) => {
+                m.chain_id = Some(chain_id.as_u64());
+                m.gas_limit = gas_limit;
+                m.gas_price = gas_price;
+
+                TypedTransactionRequest::Legacy(m)
+            }
+            Some(TypedTransactionRequest::EIP2930(mut m)) => {
+                m.chain_id = chain_id.as_u64();
+                m.gas_limit = gas_limit;
+                m.gas_price = gas_price;
+
+                TypedTransactionRequest::EIP2930(m)
+            }
+            Some(TypedTransactionRequest::EIP1559(mut m)) => {
+                m.chain_id = chain_id.as_u64();
+                m.gas_limit = gas_limit;
+                m.max_fee_per_gas = max_fee_per_gas;
+
+                TypedTransactionRequest::EIP1559(m)
+            }
+            None => return Err(EthApiError::ConflictingFeeFieldsInRequest),
+        };
+
+        Ok(transaction)
+    }
+}
+
+// === impl EthApi ===
+
 impl<Client, Pool, Network> EthApi<Client, Pool, Network>
 where
     Pool: TransactionPool + 'static,
     Client: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
-    Network: 'static,
+    Network: Send + Sync + 'static,
 {
     pub(crate) fn sign_request(
         &self,
         from: &Address,
         request: TypedTransactionRequest,
     ) -> EthResult<TransactionSigned> {
-        for signer in self.inner.signers.iter() {
+        for signer in self.inner.signers.read().iter() {
             if signer.is_signer_for(from) {
                 return match signer.sign_transaction(request, from) {
                     Ok(tx) => Ok(tx),
@@ -486,101 +571,188 @@ where
         meta: TransactionMeta,
         receipt: Receipt,
     ) -> EthResult<TransactionReceipt> {
-        let transaction =
-            tx.clone().into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
-
         // get all receipts for the block
         let all_receipts = match self.client().receipts_by_block((meta.block_number).into())? {
             Some(recpts) => recpts,
             None => return Err(EthApiError::UnknownBlockNumber),
         };
 
-        let mut res_receipt = TransactionReceipt {
-            transaction_hash: Some(meta.tx_hash),
-            transaction_index: Some(U256::from(meta.index)),
-            block_hash: Some(meta.block_hash),
-            block_number: Some(U256::from(meta.block_number)),
-            from: transaction.signer(),
-            to: None,
-            cumulative_gas_used: U256::from(receipt.cumulative_gas_used),
-            gas_used: None,
-            contract_address: None,
-            logs: vec![],
-            effective_gas_price: U128::from(0),
-            transaction_type: U256::from(0),
-            // TODO: set state root after the block
-            state_root: None,
-            logs_bloom: receipt.bloom_slow(),
-            status_code: if receipt.success { Some(U64::from(1)) } else { Some(U64::from(0)) },
-        };
+        build_transaction_receipt_with_block_receipts(tx, meta, receipt, &all_receipts)
+    }
+}
 
-        // get the previous transaction cumulative gas used
-        let gas_used = if meta.index == 0 {
-            receipt.cumulative_gas_used
-        } else {
-            let prev_tx_idx = (meta.index - 1) as usize;
-            all_receipts
-                .get(prev_tx_idx)
-                .map(|prev_receipt| receipt.cumulative_gas_used - prev_receipt.cumulative_gas_used)
-                .unwrap_or_default()
-        };
-        res_receipt.gas_used = Some(U256::from(gas_used));
+impl<Client, Pool, Network> EthApi<Client, Pool, Network>
+where
+    Pool: TransactionPool + 'static,
+    Client:
+        BlockProvider + AccountHistoryProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: Send + Sync + 'static,
+{
+    /// Returns the mined transaction of the sender with the given nonce.
+    ///
+    /// Returns `Ok(None)` if the nonce wasn't used by a mined transaction, e.g. if the transaction
+    /// is still pending or the sender is a contract.
+    pub(crate) async fn transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: u64,
+    ) -> EthResult<Option<Transaction>> {
+        let this = self.clone();
+        self.on_blocking_task(move || this.transaction_by_sender_and_nonce_blocking(sender, nonce))
+            .await
+    }
 
-        match tx.transaction.kind() {
-            Create => {
-                res_receipt.contract_address =
-                    Some(create_address(transaction.signer(), tx.transaction.nonce()));
-            }
-            Call(addr) => {
-                res_receipt.to = Some(*addr);
-            }
+    /// Looks up the transaction of [Self::transaction_by_sender_and_nonce].
+    ///
+    /// The transaction is in the first block after which the sender's nonce is higher than the
+    /// given nonce. Only the blocks that changed the sender's account are candidates, so they are
+    /// taken from the account history and the block is found by a binary search over the nonce
+    /// after each of them.
+    fn transaction_by_sender_and_nonce_blocking(
+        &self,
+        sender: Address,
+        nonce: u64,
+    ) -> EthResult<Option<Transaction>> {
+        if self.latest_state()?.account_nonce(sender)?.unwrap_or_default() <= nonce {
+            return Ok(None)
         }
 
-        match tx.transaction {
-            PrimitiveTransaction::Legacy(TxLegacy { gas_price, .. }) => {
-                res_receipt.transaction_type = U256::from(0);
-                res_receipt.effective_gas_price = U128::from(gas_price);
+        let best_number = self.client().chain_info()?.best_number;
+        let blocks = self.client().blocks_with_account_changes(sender, 0..=best_number)?;
+        let (mut low, mut high) = (0, blocks.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.state_at_number(blocks[mid])?.account_nonce(sender)?.unwrap_or_default() > nonce
+            {
+                high = mid;
+            } else {
+                low = mid + 1;
             }
-            PrimitiveTransaction::Eip2930(TxEip2930 { gas_price, .. }) => {
-                res_receipt.transaction_type = U256::from(1);
-                res_receipt.effective_gas_price = U128::from(gas_price);
+        }
+        let Some(&block_number) = blocks.get(low) else { return Ok(None) };
+
+        let Some(transactions) = self.client().transactions_by_block(block_number.into())? else {
+            return Ok(None)
+        };
+        for tx in transactions {
+            if tx.nonce() != nonce {
+                continue
             }
-            PrimitiveTransaction::Eip1559(TxEip1559 {
-                max_fee_per_gas,
-                max_priority_fee_per_gas,
-                ..
-            }) => {
-                res_receipt.transaction_type = U256::from(2);
-                res_receipt.effective_gas_price =
-                    U128::from(max_fee_per_gas + max_priority_fee_per_gas)
+            let tx = tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
+            if tx.signer() != sender {
+                continue
             }
+            let Some((_, meta)) = self.client().transaction_by_hash_with_meta(tx.hash())? else {
+                return Ok(None)
+            };
+            return Ok(Some(Transaction::from_recovered_with_block_context(
+                tx,
+                meta.block_hash,
+                meta.block_number,
+                U256::from(meta.index),
+            )))
         }
 
-        // get number of logs in the block
-        let mut num_logs = 0;
-        for prev_receipt in all_receipts.iter().take(meta.index as usize) {
-            num_logs += prev_receipt.logs.len();
+        Ok(None)
+    }
+}
+
+/// Builds the [TransactionReceipt] of the transaction from its receipt and all receipts of its
+/// block, which are needed for the gas used and the log indices.
+pub(crate) fn build_transaction_receipt_with_block_receipts(
+    tx: TransactionSigned,
+    meta: TransactionMeta,
+    receipt: Receipt,
+    all_receipts: &[Receipt],
+) -> EthResult<TransactionReceipt> {
+    let transaction =
+        tx.clone().into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
+
+    let mut res_receipt = TransactionReceipt {
+        transaction_hash: Some(meta.tx_hash),
+        transaction_index: Some(U256::from(meta.index)),
+        block_hash: Some(meta.block_hash),
+        block_number: Some(U256::from(meta.block_number)),
+        from: transaction.signer(),
+        to: None,
+        cumulative_gas_used: U256::from(receipt.cumulative_gas_used),
+        gas_used: None,
+        contract_address: None,
+        logs: vec![],
+        effective_gas_price: U128::from(0),
+        transaction_type: U256::from(0),
+        // TODO: set state root after the block
+        state_root: None,
+        logs_bloom: receipt.bloom_slow(),
+        status_code: if receipt.success { Some(U64::from(1)) } else { Some(U64::from(0)) },
+    };
+
+    // get the previous transaction cumulative gas used
+    let gas_used = if meta.index == 0 {
+        receipt.cumulative_gas_used
+    } else {
+        let prev_tx_idx = (meta.index - 1) as usize;
+        all_receipts
+            .get(prev_tx_idx)
+            .map(|prev_receipt| receipt.cumulative_gas_used - prev_receipt.cumulative_gas_used)
+            .unwrap_or_default()
+    };
+    res_receipt.gas_used = Some(U256::from(gas_used));
+
+    match tx.transaction.kind() {
+        Create => {
+            res_receipt.contract_address =
+                Some(create_address(transaction.signer(), tx.transaction.nonce()));
+        }
+        Call(addr) => {
+            res_receipt.to = Some(*addr);
         }
+    }
 
-        for (tx_log_idx, log) in receipt.logs.into_iter().enumerate() {
-            let rpclog = Log {
-                address: log.address,
-                topics: log.topics,
-                data: log.data,
-                block_hash: Some(meta.block_hash),
-                block_number: Some(U256::from(meta.block_number)),
-                transaction_hash: Some(meta.tx_hash),
-                transaction_index: Some(U256::from(meta.index)),
-                transaction_log_index: Some(U256::from(tx_log_idx)),
-                log_index: Some(U256::from(num_logs + tx_log_idx)),
-                removed: false,
-            };
-            res_receipt.logs.push(rpclog);
+    match tx.transaction {
+        PrimitiveTransaction::Legacy(TxLegacy { gas_price, .. }) => {
+            res_receipt.transaction_type = U256::from(0);
+            res_receipt.effective_gas_price = U128::from(gas_price);
+        }
+        PrimitiveTransaction::Eip2930(TxEip2930 { gas_price, .. }) => {
+            res_receipt.transaction_type = U256::from(1);
+            res_receipt.effective_gas_price = U128::from(gas_price);
+        }
+        PrimitiveTransaction::Eip1559(TxEip1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..
+        }) => {
+            res_receipt.transaction_type = U256::from(2);
+            res_receipt.effective_gas_price = U128::from(max_fee_per_gas + max_priority_fee_per_gas)
         }
+    }
 
-        Ok(res_receipt)
+    // get number of logs in the block
+    let mut num_logs = 0;
+    for prev_receipt in all_receipts.iter().take(meta.index as usize) {
+        num_logs += prev_receipt.logs.len();
     }
+
+    for (tx_log_idx, log) in receipt.logs.into_iter().enumerate() {
+        let rpclog = Log {
+            address: log.address,
+            topics: log.topics,
+            data: log.data,
+            block_hash: Some(meta.block_hash),
+            block_number: Some(U256::from(meta.block_number)),
+            transaction_hash: Some(meta.tx_hash),
+            transaction_index: Some(U256::from(meta.index)),
+            transaction_log_index: Some(U256::from(tx_log_idx)),
+            log_index: Some(U256::from(num_logs + tx_log_idx)),
+            removed: false,
+        };
+        res_receipt.logs.push(rpclog);
+    }
+
+    Ok(res_receipt)
 }
+
 /// Represents from where a transaction was fetched.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TransactionSource {
@@ -667,10 +839,79 @@ impl From<TransactionSource> for Transaction {
 mod tests {
     use super::*;
     use crate::{eth::cache::EthStateCache, EthApi};
+    use reth_db::{
+        database::Database,
+        mdbx::test_utils::create_test_rw_db,
+        models::{AccountBeforeTx, ShardedKey},
+        tables,
+        transaction::{DbTx, DbTxMut},
+        BlockNumberList,
+    };
+    use reth_interfaces::blockchain_tree::BlockchainTreeViewer;
     use reth_network_api::test_utils::NoopNetwork;
-    use reth_primitives::{hex_literal::hex, Bytes};
-    use reth_provider::test_utils::NoopProvider;
+    use reth_primitives::{
+        hex_literal::hex, sign_message, stage, Account, Block, BlockHash, BlockNumHash,
+        BlockNumber, Bytes, Header, SealedBlock, TransactionKind, MAINNET,
+    };
+    use reth_provider::{
+        insert_canonical_block, test_utils::NoopProvider, BlockchainProvider,
+        BlockchainTreePendingStateProvider, PostStateDataProvider, ShareableDatabase,
+    };
     use reth_transaction_pool::{test_utils::testing_pool, TransactionPool};
+    use std::collections::{BTreeMap, HashSet};
+
+    /// A blockchain tree without any blocks, so that everything is served from the database.
+    #[derive(Clone)]
+    struct EmptyTree;
+
+    impl BlockchainTreeViewer for EmptyTree {
+        fn blocks(&self) -> BTreeMap<BlockNumber, HashSet<BlockHash>> {
+            Default::default()
+        }
+
+        fn block_by_hash(&self, _hash: BlockHash) -> Option<SealedBlock> {
+            None
+        }
+
+        fn canonical_blocks(&self) -> BTreeMap<BlockNumber, BlockHash> {
+            Default::default()
+        }
+
+        fn find_canonical_ancestor(&self, _hash: BlockHash) -> Option<BlockHash> {
+            None
+        }
+
+        fn canonical_tip(&self) -> BlockNumHash {
+            Default::default()
+        }
+
+        fn pending_blocks(&self) -> (BlockNumber, Vec<BlockHash>) {
+            Default::default()
+        }
+
+        fn pending_block_num_hash(&self) -> Option<BlockNumHash> {
+            None
+        }
+    }
+
+    impl BlockchainTreePendingStateProvider for EmptyTree {
+        fn find_pending_state_provider(
+            &self,
+            _block_hash: BlockHash,
+        ) -> Option<Box<dyn PostStateDataProvider>> {
+            None
+        }
+    }
+
+    fn signed_tx(secret: u64, nonce: u64) -> TransactionSigned {
+        let tx = PrimitiveTransaction::Legacy(TxLegacy {
+            nonce,
+            to: TransactionKind::Create,
+            ..Default::default()
+        });
+        let signature = sign_message(H256::from_low_u64_be(secret), tx.signature_hash()).unwrap();
+        TransactionSigned::from_transaction_and_signature(tx, signature)
+    }
 
     #[tokio::test]
     async fn send_raw_transaction() {
@@ -711,4 +952,66 @@ mod tests {
         assert!(pool.get(&tx_1_result).is_some(), "tx1 not found in the pool");
         assert!(pool.get(&tx_2_result).is_some(), "tx2 not found in the pool");
     }
+
+    #[tokio::test]
+    async fn transaction_by_sender_and_nonce() {
+        let first = signed_tx(1, 0);
+        let other = signed_tx(2, 0);
+        let second = signed_tx(1, 1);
+        let sender = first.recover_signer().unwrap();
+        let bodies = [vec![], vec![first], vec![], vec![other, second]];
+
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+        let mut parent_hash = H256::zero();
+        for (number, body) in bodies.into_iter().enumerate() {
+            let header = Header { number: number as u64, parent_hash, ..Default::default() };
+            let block = Block { header, body, ommers: vec![], withdrawals: None }.seal_slow();
+            parent_hash = block.hash();
+            insert_canonical_block(&tx, block, None).unwrap();
+        }
+        // the sender's nonce changed in blocks 1 and 3
+        tx.put::<tables::AccountChangeSet>(1, AccountBeforeTx { address: sender, info: None })
+            .unwrap();
+        tx.put::<tables::AccountChangeSet>(
+            3,
+            AccountBeforeTx {
+                address: sender,
+                info: Some(Account { nonce: 1, ..Default::default() }),
+            },
+        )
+        .unwrap();
+        tx.put::<tables::PlainAccountState>(sender, Account { nonce: 2, ..Default::default() })
+            .unwrap();
+        tx.put::<tables::AccountHistory>(
+            ShardedKey::new(sender, u64::MAX),
+            BlockNumberList::new([1, 3]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::SyncStage>(stage::INDEX_ACCOUNT_HISTORY.to_string(), 3).unwrap();
+        tx.put::<tables::SyncStage>(stage::FINISH.to_string(), 3).unwrap();
+        tx.commit().unwrap();
+
+        let client =
+            BlockchainProvider::new(ShareableDatabase::new(db, MAINNET.clone()), EmptyTree);
+        let eth_api = EthApi::new(
+            client.clone(),
+            testing_pool(),
+            NoopNetwork::default(),
+            EthStateCache::spawn(client, Default::default()),
+        );
+
+        let found = eth_api.transaction_by_sender_and_nonce(sender, 0).await.unwrap().unwrap();
+        assert_eq!(found.block_number, Some(U256::from(1)));
+        assert_eq!(found.transaction_index, Some(U256::ZERO));
+
+        // preceded by another sender's transaction in the same block
+        let found = eth_api.transaction_by_sender_and_nonce(sender, 1).await.unwrap().unwrap();
+        assert_eq!(found.block_number, Some(U256::from(3)));
+        assert_eq!(found.transaction_index, Some(U256::from(1)));
+        assert_eq!(found.from, sender);
+
+        // the sender hasn't sent a transaction with that nonce yet
+        assert!(eth_api.transaction_by_sender_and_nonce(sender, 2).await.unwrap().is_none());
+    }
 }
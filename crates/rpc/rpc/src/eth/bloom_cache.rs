@@ -0,0 +1,153 @@
+//! In-memory cache for the logs blooms of recently queried blocks
+
+use parking_lot::Mutex;
+use reth_primitives::{BlockHash, Bloom};
+use reth_provider::{CanonStateNotification, CanonStateNotifications};
+use schnellru::{ByLength, LruMap};
+use serde::{Deserialize, Serialize};
+use std::{fmt, sync::Arc};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::debug;
+
+/// Settings for the [BloomCache]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BloomCacheConfig {
+    /// Max number of blocks whose logs bloom is kept in memory.
+    ///
+    /// A bloom takes 256 bytes, so the default of `100_000` blocks takes about 30MB.
+    pub max_blocks: u32,
+}
+
+impl Default for BloomCacheConfig {
+    fn default() -> Self {
+        Self { max_blocks: 100_000 }
+    }
+}
+
+/// Caches the logs bloom of blocks by block hash.
+///
+/// Repeated `eth_getLogs` queries over the same range only need to touch storage for the blocks
+/// whose bloom matches the filter.
+///
+/// Since the cache is keyed by hash, an entry can't become stale on reorgs. Blooms of newly
+/// committed blocks can be cached ahead of queries, see [BloomCache::update_on_canonical_state].
+#[derive(Clone)]
+pub struct BloomCache {
+    inner: Arc<Mutex<LruMap<BlockHash, Bloom, ByLength>>>,
+}
+
+// === impl BloomCache ===
+
+impl BloomCache {
+    /// Creates a new, empty cache with the given settings.
+    pub fn new(config: BloomCacheConfig) -> Self {
+        Self { inner: Arc::new(Mutex::new(LruMap::new(ByLength::new(config.max_blocks)))) }
+    }
+
+    /// Returns the cached logs bloom of the block with the given hash.
+    pub fn get(&self, hash: BlockHash) -> Option<Bloom> {
+        self.inner.lock().get(&hash).copied()
+    }
+
+    /// Caches the logs bloom of the block with the given hash.
+    pub fn insert(&self, hash: BlockHash, bloom: Bloom) {
+        self.inner.lock().insert(hash, bloom);
+    }
+
+    /// Returns the number of cached blooms.
+    pub fn len(&self) -> usize {
+        self.inner.lock().len()
+    }
+
+    /// Returns true if no blooms are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the blooms of reverted blocks and caches the blooms of newly committed blocks.
+    pub fn on_canonical_state(&self, notification: &CanonStateNotification) {
+        let mut inner = self.inner.lock();
+        if let Some(reverted) = notification.reverted() {
+            for block in reverted.blocks().values() {
+                inner.remove(&block.hash());
+            }
+        }
+        if let Some(committed) = notification.committed() {
+            for block in committed.blocks().values() {
+                inner.insert(block.hash(), block.header.logs_bloom);
+            }
+        }
+    }
+
+    /// Keeps the cache in sync with the canonical chain until the channel is closed.
+    ///
+    /// Missed notifications only mean that the blooms of some blocks are not cached ahead of
+    /// queries.
+    pub async fn update_on_canonical_state(self, mut notifications: CanonStateNotifications) {
+        loop {
+            match notifications.recv().await {
+                Ok(notification) => self.on_canonical_state(&notification),
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!(target: "rpc::eth", skipped, "Missed canonical state notifications");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+impl fmt::Debug for BloomCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BloomCache").field("len", &self.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{BlockNumber, SealedBlockWithSenders, H256};
+    use reth_provider::{Chain, PostState};
+
+    fn chain(blocks: impl IntoIterator<Item = (BlockNumber, Bloom)>) -> Arc<Chain> {
+        let blocks = blocks.into_iter().map(|(number, bloom)| {
+            let mut block = SealedBlockWithSenders::default();
+            block.block.header.header.number = number;
+            block.block.header.header.logs_bloom = bloom;
+            block.block.header.hash = hash(number, bloom);
+            block
+        });
+        Arc::new(Chain::new(blocks.map(|block| (block, PostState::default())).collect()))
+    }
+
+    fn hash(number: BlockNumber, bloom: Bloom) -> H256 {
+        H256::from_low_u64_be(number) ^ H256::from_slice(&bloom.as_bytes()[..32])
+    }
+
+    #[test]
+    fn follows_canonical_chain() {
+        let cache = BloomCache::new(BloomCacheConfig { max_blocks: 2 });
+        cache.insert(hash(1, Bloom::repeat_byte(1)), Bloom::repeat_byte(1));
+
+        let new = chain([(2, Bloom::repeat_byte(2)), (3, Bloom::repeat_byte(3))]);
+        cache.on_canonical_state(&CanonStateNotification::Commit { new: new.clone() });
+        // the oldest entry is evicted
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(hash(1, Bloom::repeat_byte(1))), None);
+        assert_eq!(cache.get(hash(3, Bloom::repeat_byte(3))), Some(Bloom::repeat_byte(3)));
+
+        cache.on_canonical_state(&CanonStateNotification::Reorg {
+            old: chain([(3, Bloom::repeat_byte(3))]),
+            new: chain([(3, Bloom::repeat_byte(4))]),
+        });
+        assert_eq!(cache.get(hash(3, Bloom::repeat_byte(3))), None);
+        assert_eq!(cache.get(hash(3, Bloom::repeat_byte(4))), Some(Bloom::repeat_byte(4)));
+
+        // a lookup that raced the reorg can't resurrect the bloom of the reorged block
+        cache.insert(hash(3, Bloom::repeat_byte(3)), Bloom::repeat_byte(3));
+        assert_eq!(cache.get(hash(3, Bloom::repeat_byte(4))), Some(Bloom::repeat_byte(4)));
+
+        cache.on_canonical_state(&CanonStateNotification::Revert { old: new });
+        assert_eq!(cache.get(hash(2, Bloom::repeat_byte(2))), None);
+    }
+}
@@ -3,23 +3,28 @@
 use futures::{future::Either, StreamExt};
 use reth_interfaces::{provider::ProviderError, Result};
 use reth_primitives::{Block, Receipt, TransactionSigned, H256};
-use reth_provider::{BlockProvider, EvmEnvProvider, StateProviderFactory};
+use reth_provider::{
+    BlockProvider, CanonStateNotification, CanonStateNotifications, EvmEnvProvider,
+    StateProviderFactory,
+};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use revm::primitives::{BlockEnv, CfgEnv};
 use schnellru::{ByMemoryUsage, Limiter, LruMap};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     future::Future,
     hash::Hash,
     pin::Pin,
     task::{ready, Context, Poll},
 };
 use tokio::sync::{
+    broadcast::error::RecvError,
     mpsc::{unbounded_channel, UnboundedSender},
     oneshot,
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::debug;
 
 /// The type that can send the response to a requested [Block]
 type BlockResponseSender = oneshot::Sender<Result<Option<Block>>>;
@@ -179,6 +184,31 @@ impl EthStateCache {
         let _ = self.to_service.send(CacheAction::GetEnv { block_hash, response_tx });
         rx.await.map_err(|_| ProviderError::CacheServiceUnavailable)?
     }
+
+    /// Evicts the cached data of the blocks that were reverted by the notification.
+    pub fn on_canonical_state(&self, notification: &CanonStateNotification) {
+        if let Some(reverted) = notification.reverted() {
+            let block_hashes = reverted.blocks().values().map(|block| block.hash()).collect();
+            let _ = self.to_service.send(CacheAction::RemoveBlocks { block_hashes });
+        }
+    }
+
+    /// Keeps the cache in sync with the canonical chain until the channel is closed, so the data
+    /// of blocks that were reorged out isn't served anymore.
+    ///
+    /// If notifications were missed, the entire cache is cleared.
+    pub async fn update_on_canonical_state(self, mut notifications: CanonStateNotifications) {
+        loop {
+            match notifications.recv().await {
+                Ok(notification) => self.on_canonical_state(&notification),
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!(target: "rpc::eth", skipped, "Missed canonical state notifications, clearing eth state cache");
+                    let _ = self.to_service.send(CacheAction::Clear);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
 }
 
 /// A task than manages caches for data required by the `eth` rpc implementation.
@@ -325,7 +355,9 @@ where
                             }
                         }
                         CacheAction::BlockResult { block_hash, res } => {
-                            if let Some(queued) = this.full_block_cache.queued.remove(&block_hash) {
+                            let (queued, cacheable) =
+                                this.full_block_cache.take_queued(&block_hash);
+                            if let Some(queued) = queued {
                                 // send the response to queued senders
                                 for tx in queued {
                                     match tx {
@@ -342,12 +374,13 @@ where
                             }
 
                             // cache good block
-                            if let Ok(Some(block)) = res {
+                            if let (true, Ok(Some(block))) = (cacheable, res) {
                                 this.full_block_cache.cache.insert(block_hash, block);
                             }
                         }
                         CacheAction::ReceiptsResult { block_hash, res } => {
-                            if let Some(queued) = this.receipts_cache.queued.remove(&block_hash) {
+                            let (queued, cacheable) = this.receipts_cache.take_queued(&block_hash);
+                            if let Some(queued) = queued {
                                 // send the response to queued senders
                                 for tx in queued {
                                     let _ = tx.send(res.clone());
@@ -355,13 +388,14 @@ where
                             }
 
                             // cache good receipts
-                            if let Ok(Some(receipts)) = res {
+                            if let (true, Ok(Some(receipts))) = (cacheable, res) {
                                 this.receipts_cache.cache.insert(block_hash, receipts);
                             }
                         }
                         CacheAction::EnvResult { block_hash, res } => {
                             let res = *res;
-                            if let Some(queued) = this.evm_env_cache.queued.remove(&block_hash) {
+                            let (queued, cacheable) = this.evm_env_cache.take_queued(&block_hash);
+                            if let Some(queued) = queued {
                                 // send the response to queued senders
                                 for tx in queued {
                                     let _ = tx.send(res.clone());
@@ -369,10 +403,22 @@ where
                             }
 
                             // cache good env data
-                            if let Ok(data) = res {
+                            if let (true, Ok(data)) = (cacheable, res) {
                                 this.evm_env_cache.cache.insert(block_hash, data);
                             }
                         }
+                        CacheAction::RemoveBlocks { block_hashes } => {
+                            for block_hash in block_hashes {
+                                this.full_block_cache.remove(&block_hash);
+                                this.receipts_cache.remove(&block_hash);
+                                this.evm_env_cache.remove(&block_hash);
+                            }
+                        }
+                        CacheAction::Clear => {
+                            this.full_block_cache.clear();
+                            this.receipts_cache.clear();
+                            this.evm_env_cache.clear();
+                        }
                     }
                 }
             }
@@ -389,6 +435,8 @@ where
     cache: LruMap<K, V, L>,
     /// All queued consumers
     queued: HashMap<K, Vec<S>>,
+    /// Queued keys that were removed while their value was being fetched
+    removed: HashSet<K>,
 }
 
 impl<K, V, L, S> MultiConsumerLruCache<K, V, L, S>
@@ -411,6 +459,34 @@ where
             }
         }
     }
+
+    /// Removes and returns the queued senders for the given key, once its value was fetched.
+    ///
+    /// Also returns whether the fetched value can be cached, which is not the case if the key was
+    /// removed while the value was being fetched: the value may predate the removal.
+    fn take_queued(&mut self, key: &K) -> (Option<Vec<S>>, bool) {
+        (self.queued.remove(key), !self.removed.remove(key))
+    }
+
+    /// Removes the cached value for the given key.
+    fn remove(&mut self, key: &K)
+    where
+        K: Clone,
+    {
+        self.cache.remove(key);
+        if self.queued.contains_key(key) {
+            self.removed.insert(key.clone());
+        }
+    }
+
+    /// Removes all cached values.
+    fn clear(&mut self)
+    where
+        K: Clone,
+    {
+        self.cache.clear();
+        self.removed.extend(self.queued.keys().cloned());
+    }
 }
 
 impl<K, V, S> MultiConsumerLruCache<K, V, ByMemoryUsage, S>
@@ -421,7 +497,11 @@ where
     ///
     /// See also [LruMap::with_memory_budget]
     fn with_memory_budget(memory_budget: usize) -> Self {
-        Self { cache: LruMap::with_memory_budget(memory_budget), queued: Default::default() }
+        Self {
+            cache: LruMap::with_memory_budget(memory_budget),
+            queued: Default::default(),
+            removed: Default::default(),
+        }
     }
 }
 
@@ -434,4 +514,32 @@ enum CacheAction {
     BlockResult { block_hash: H256, res: Result<Option<Block>> },
     ReceiptsResult { block_hash: H256, res: Result<Option<Vec<Receipt>>> },
     EnvResult { block_hash: H256, res: Box<Result<(CfgEnv, BlockEnv)>> },
+    RemoveBlocks { block_hashes: Vec<H256> },
+    Clear,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removed_while_fetching_is_not_cached() {
+        let mut cache = MultiConsumerLruCache::<u64, u64, _, ()>::with_memory_budget(1024);
+        cache.cache.insert(1, 1);
+        assert!(cache.queue(2, ()));
+        assert!(!cache.queue(2, ()));
+
+        cache.remove(&1);
+        cache.remove(&2);
+        assert!(cache.cache.get(&1).is_none());
+
+        // the value of the removed key was fetched before the removal
+        let (queued, cacheable) = cache.take_queued(&2);
+        assert_eq!(queued.map(|queued| queued.len()), Some(2));
+        assert!(!cacheable);
+
+        // later fetches are cached again
+        assert!(cache.queue(2, ()));
+        assert_eq!(cache.take_queued(&2), (Some(vec![()]), true));
+    }
 }
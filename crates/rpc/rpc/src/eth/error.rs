@@ -1,11 +1,17 @@
 //! Implementation specific Errors for the `eth_` namespace.
 
-use crate::result::{internal_rpc_err, invalid_params_rpc_err, rpc_err, rpc_error_with_code};
+use crate::{
+    result::{internal_rpc_err, invalid_params_rpc_err, rpc_err, rpc_error_with_code},
+    BlockingTaskPoolError,
+};
 use jsonrpsee::core::Error as RpcError;
 use reth_primitives::{constants::SELECTOR_LEN, Address, Bytes, U256};
+#[cfg(feature = "js-tracer")]
+use reth_revm::tracing::js::JsInspectorError;
 use reth_rpc_types::{error::EthRpcErrorCode, BlockError};
 use reth_transaction_pool::error::{InvalidPoolTransactionError, PoolError};
 use revm::primitives::{EVMError, ExecutionResult, Halt, OutOfGasError};
+use std::path::PathBuf;
 
 /// Result alias
 pub type EthResult<T> = Result<T, EthApiError>;
@@ -60,9 +66,21 @@ pub enum EthApiError {
     /// When tracer config does not match the tracer
     #[error("invalid tracer config")]
     InvalidTracerConfig,
+    /// Thrown when a javascript tracer fails
+    #[error("{0}")]
+    JsTracer(String),
     /// Percentile array is invalid
     #[error("invalid reward percentile")]
     InvalidRewardPercentile(f64),
+    /// Error while executing the call on the blocking task pool
+    #[error(transparent)]
+    BlockingTaskPool(#[from] BlockingTaskPoolError),
+    /// Thrown when the request parameters are invalid
+    #[error("{0}")]
+    InvalidParams(String),
+    /// Thrown when proofs are requested for a block too far behind the head
+    #[error("distance to target block exceeds maximum proof window")]
+    ExceedsMaxProofWindow,
 }
 
 impl From<EthApiError> for RpcError {
@@ -75,18 +93,27 @@ impl From<EthApiError> for RpcError {
             EthApiError::ConflictingFeeFieldsInRequest |
             EthApiError::Signing(_) |
             EthApiError::BothStateAndStateDiffInOverride(_) |
-            EthApiError::InvalidTracerConfig => invalid_params_rpc_err(error.to_string()),
+            EthApiError::InvalidTracerConfig |
+            EthApiError::InvalidParams(_) |
+            EthApiError::ExceedsMaxProofWindow => invalid_params_rpc_err(error.to_string()),
             EthApiError::InvalidTransaction(err) => err.into(),
             EthApiError::PoolError(_) |
             EthApiError::PrevrandaoNotSet |
             EthApiError::InvalidBlockData(_) |
             EthApiError::Internal(_) |
-            EthApiError::TransactionNotFound => internal_rpc_err(error.to_string()),
+            EthApiError::TransactionNotFound |
+            EthApiError::JsTracer(_) => internal_rpc_err(error.to_string()),
             EthApiError::UnknownBlockNumber | EthApiError::UnknownBlockOrTxIndex => {
                 rpc_error_with_code(EthRpcErrorCode::ResourceNotFound.code(), error.to_string())
             }
             EthApiError::Unsupported(msg) => internal_rpc_err(msg),
             EthApiError::InvalidRewardPercentile(msg) => internal_rpc_err(msg.to_string()),
+            EthApiError::BlockingTaskPool(BlockingTaskPoolError::QueueFull) => {
+                rpc_error_with_code(EthRpcErrorCode::LimitExceeded.code(), error.to_string())
+            }
+            EthApiError::BlockingTaskPool(BlockingTaskPoolError::Panicked) => {
+                internal_rpc_err(error.to_string())
+            }
         }
     }
 }
@@ -355,6 +382,13 @@ impl From<PoolError> for RpcPoolError {
     }
 }
 
+#[cfg(feature = "js-tracer")]
+impl From<JsInspectorError> for EthApiError {
+    fn from(err: JsInspectorError) -> Self {
+        EthApiError::JsTracer(err.to_string())
+    }
+}
+
 impl From<PoolError> for EthApiError {
     fn from(err: PoolError) -> Self {
         EthApiError::PoolError(RpcPoolError::from(err))
@@ -376,6 +410,26 @@ pub enum SignError {
     /// No chainid
     #[error("No chainid")]
     NoChainId,
+    /// The account is locked and no password was given.
+    #[error("authentication needed: password or unlock")]
+    AccountLocked,
+    /// The password doesn't match the account's password.
+    #[error("could not decrypt key with given password")]
+    IncorrectPassword,
+}
+
+/// Errors returned when unlocking the accounts of a keystore directory.
+#[derive(Debug, thiserror::Error)]
+pub enum KeystoreError {
+    /// The keystore directory couldn't be read.
+    #[error("failed to read keystore directory {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    /// A keystore file couldn't be decrypted, for example because of a wrong password.
+    #[error("failed to decrypt keystore file {0}: {1}")]
+    Decrypt(PathBuf, eth_keystore::KeystoreError),
+    /// A keystore file holds an invalid secret key.
+    #[error("invalid secret key in keystore file {0}")]
+    InvalidKey(PathBuf),
 }
 
 /// Converts the evm [ExecutionResult] into a result where `Ok` variant is the output bytes if it is
@@ -403,3 +457,30 @@ pub(crate) fn decode_revert_reason(out: impl AsRef<[u8]>) -> Option<String> {
     }
     String::decode(&out[SELECTOR_LEN..]).ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::types::error::CallError;
+
+    #[test]
+    fn revert_error_includes_output() {
+        // a custom error `Unauthorized(address)`, which isn't a revert reason string
+        let output =
+            hex::decode("8e4a23d60000000000000000000000000000000000000000000000000000000000000001")
+                .unwrap();
+        let err: RpcError = EthApiError::InvalidTransaction(InvalidTransactionError::Revert(
+            RevertError::new(output.into()),
+        ))
+        .into();
+        let RpcError::Call(CallError::Custom(err)) = err else {
+            panic!("expected custom call error")
+        };
+        assert_eq!(err.code(), EthRpcErrorCode::ExecutionError.code());
+        assert_eq!(err.message(), "execution reverted");
+        assert_eq!(
+            err.data().unwrap().get(),
+            r#""0x8e4a23d60000000000000000000000000000000000000000000000000000000000000001""#
+        );
+    }
+}
@@ -1,42 +1,107 @@
-use super::cache::EthStateCache;
+use super::{bloom_cache::BloomCache, cache::EthStateCache};
 use crate::{
     eth::{error::EthApiError, logs_utils},
-    result::{internal_rpc_err, rpc_error_with_code, ToRpcResult},
-    EthSubscriptionIdProvider,
+    result::{rpc_error_with_code, ToRpcResult},
+    BlockingTaskPool, EthSubscriptionIdProvider,
 };
 use async_trait::async_trait;
-use jsonrpsee::{core::RpcResult, server::IdProvider};
-use reth_primitives::filter::{Filter, FilterBlockOption, FilteredParams};
-use reth_provider::{BlockProvider, EvmEnvProvider};
+use futures::Future;
+use jsonrpsee::{
+    core::RpcResult,
+    server::IdProvider,
+    types::error::{CallError, ErrorObject},
+};
+use reth_primitives::{
+    filter::{Filter, FilterBlockOption, FilteredParams, ValueOrArray},
+    Bloom, TxHash, H256, U64,
+};
+use reth_provider::{BlockProvider, EvmEnvProvider, LogIndexProvider};
 use reth_rpc_api::EthFilterApiServer;
-use reth_rpc_types::{FilterChanges, FilterId, Log};
+use reth_rpc_types::{error::EthRpcErrorCode, FilterChanges, FilterId, Log, LogsPage};
 use reth_transaction_pool::TransactionPool;
-use std::{collections::HashMap, sync::Arc, time::Instant};
-use tokio::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::{mpsc::Receiver, Mutex};
 use tracing::trace;
 
 /// The default maximum of logs in a single response.
 const DEFAULT_MAX_LOGS_IN_RESPONSE: usize = 2_000;
 
+/// The default time after which a filter that isn't polled is removed, like geth.
+const DEFAULT_STALE_FILTER_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The minimum interval at which [EthFilter::clear_stale_filters] checks for stale filters.
+const MIN_STALE_FILTERS_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Settings for the [EthFilter]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EthFilterConfig {
+    /// Maximum number of logs that can be returned in a single response, `0` means no limit.
+    pub max_logs_per_response: usize,
+    /// Maximum number of blocks a single log query can span, `0` means no limit.
+    pub max_blocks_per_filter: u64,
+    /// How long an installed filter is kept without being polled.
+    pub stale_filter_ttl: Duration,
+}
+
+impl Default for EthFilterConfig {
+    fn default() -> Self {
+        Self {
+            max_logs_per_response: DEFAULT_MAX_LOGS_IN_RESPONSE,
+            max_blocks_per_filter: 0,
+            stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
+        }
+    }
+}
+
 /// `Eth` filter RPC implementation.
 #[derive(Debug, Clone)]
 pub struct EthFilter<Client, Pool> {
     /// All nested fields bundled together.
     inner: Arc<EthFilterInner<Client, Pool>>,
+    /// The pool log queries are executed on
+    blocking_pool: Option<BlockingTaskPool>,
 }
 
 impl<Client, Pool> EthFilter<Client, Pool> {
     /// Creates a new, shareable instance.
     pub fn new(client: Client, pool: Pool, eth_cache: EthStateCache) -> Self {
+        Self::with_config(client, pool, eth_cache, EthFilterConfig::default(), None)
+    }
+
+    /// Creates a new, shareable instance with the given limits.
+    ///
+    /// If a [BloomCache] is provided, the logs blooms of blocks are looked up there before reading
+    /// them from storage. The cache can be filled with the blooms of new canonical blocks, see
+    /// [BloomCache::update_on_canonical_state].
+    pub fn with_config(
+        client: Client,
+        pool: Pool,
+        eth_cache: EthStateCache,
+        config: EthFilterConfig,
+        bloom_cache: Option<BloomCache>,
+    ) -> Self {
         let inner = EthFilterInner {
             client,
             active_filters: Default::default(),
             pool,
             id_provider: Arc::new(EthSubscriptionIdProvider::default()),
-            max_logs_in_response: DEFAULT_MAX_LOGS_IN_RESPONSE,
+            config,
             eth_cache,
+            bloom_cache,
         };
-        Self { inner: Arc::new(inner) }
+        Self { inner: Arc::new(inner), blocking_pool: None }
+    }
+
+    /// Executes log queries on the given [BlockingTaskPool] instead of the async runtime.
+    pub fn with_blocking_pool(mut self, blocking_pool: BlockingTaskPool) -> Self {
+        self.blocking_pool = Some(blocking_pool);
+        self
     }
 
     /// Returns all currently active filters
@@ -45,10 +110,45 @@ impl<Client, Pool> EthFilter<Client, Pool> {
     }
 }
 
+impl<Client, Pool> EthFilter<Client, Pool>
+where
+    Client: BlockProvider + EvmEnvProvider + LogIndexProvider + 'static,
+    Pool: TransactionPool + 'static,
+{
+    /// Runs the log query on the [BlockingTaskPool], if configured.
+    async fn on_blocking_pool<F, Fut, R>(&self, f: F) -> RpcResult<R>
+    where
+        F: FnOnce(Arc<EthFilterInner<Client, Pool>>) -> Fut,
+        Fut: Future<Output = RpcResult<R>> + Send + 'static,
+        R: Send + 'static,
+    {
+        let inner = self.inner.clone();
+        match &self.blocking_pool {
+            Some(pool) => pool.spawn(f(inner)).await.map_err(EthApiError::from)?,
+            None => f(inner).await,
+        }
+    }
+
+    /// Endless future that periodically removes the filters that weren't polled within the
+    /// configured ttl, like geth's timeout loop.
+    ///
+    /// Without it, stale filters are only removed when another filter is installed or polled.
+    /// This should be spawned as a task.
+    pub async fn clear_stale_filters(self) {
+        let ttl = self.inner.config.stale_filter_ttl;
+        let mut interval = tokio::time::interval(ttl.max(MIN_STALE_FILTERS_CHECK_INTERVAL));
+        loop {
+            interval.tick().await;
+            let mut filters = self.inner.active_filters.inner.lock().await;
+            remove_stale_filters(&mut filters, ttl);
+        }
+    }
+}
+
 #[async_trait]
 impl<Client, Pool> EthFilterApiServer for EthFilter<Client, Pool>
 where
-    Client: BlockProvider + EvmEnvProvider + 'static,
+    Client: BlockProvider + EvmEnvProvider + LogIndexProvider + 'static,
     Pool: TransactionPool + 'static,
 {
     /// Handler for `eth_newFilter`
@@ -66,7 +166,9 @@ where
     /// Handler for `eth_newPendingTransactionFilter`
     async fn new_pending_transaction_filter(&self) -> RpcResult<FilterId> {
         trace!(target: "rpc::eth", "Serving eth_newPendingTransactionFilter");
-        self.inner.install_filter(FilterKind::PendingTransaction).await
+        let receiver =
+            PendingTransactionsReceiver::new(self.inner.pool.pending_transactions_listener());
+        self.inner.install_filter(FilterKind::PendingTransaction(receiver)).await
     }
 
     /// Handler for `eth_getFilterChanges`
@@ -77,6 +179,7 @@ where
 
         let (start_block, kind) = {
             let mut filters = self.inner.active_filters.inner.lock().await;
+            remove_stale_filters(&mut filters, self.inner.config.stale_filter_ttl);
             let filter = filters.get_mut(&id).ok_or(FilterError::FilterNotFound(id))?;
 
             // update filter
@@ -90,12 +193,12 @@ where
         };
 
         match kind {
-            FilterKind::PendingTransaction => {
-                return Err(internal_rpc_err("method not implemented"))
+            FilterKind::PendingTransaction(receiver) => {
+                Ok(FilterChanges::Hashes(receiver.drain().await))
             }
             FilterKind::Block => {
                 let mut block_hashes = Vec::new();
-                for block_num in start_block..best_number {
+                for block_num in start_block..=best_number {
                     let block_hash = self
                         .inner
                         .client
@@ -109,7 +212,14 @@ where
             FilterKind::Log(filter) => {
                 let (from_block_number, to_block_number) = match filter.block_option {
                     FilterBlockOption::Range { from_block, to_block } => {
-                        logs_utils::get_filter_block_range(from_block, to_block, start_block, info)
+                        // only the blocks since the last poll that are within the filter's range
+                        let from_block_number = from_block
+                            .and_then(|num| info.convert_block_number(num))
+                            .map_or(start_block, |num| num.max(start_block));
+                        let to_block_number = to_block
+                            .and_then(|num| info.convert_block_number(num))
+                            .map_or(best_number, |num| num.min(best_number));
+                        (from_block_number, to_block_number)
                     }
                     FilterBlockOption::AtBlockHash(_) => {
                         // blockHash is equivalent to fromBlock = toBlock = the block number with
@@ -117,10 +227,16 @@ where
                         (start_block, best_number)
                     }
                 };
+                if from_block_number > to_block_number {
+                    return Ok(FilterChanges::Logs(Vec::new()))
+                }
 
-                self.inner
-                    .get_logs_in_block_range(&filter, from_block_number, to_block_number)
-                    .map(FilterChanges::Logs)
+                self.on_blocking_pool(move |inner| async move {
+                    inner
+                        .get_logs_in_block_range(&filter, from_block_number, to_block_number)
+                        .map(FilterChanges::Logs)
+                })
+                .await
             }
         }
     }
@@ -133,7 +249,8 @@ where
     async fn filter_logs(&self, id: FilterId) -> RpcResult<Vec<Log>> {
         trace!(target: "rpc::eth", "Serving eth_getFilterLogs");
         let filter = {
-            let filters = self.inner.active_filters.inner.lock().await;
+            let mut filters = self.inner.active_filters.inner.lock().await;
+            remove_stale_filters(&mut filters, self.inner.config.stale_filter_ttl);
             if let FilterKind::Log(ref filter) =
                 filters.get(&id).ok_or_else(|| FilterError::FilterNotFound(id.clone()))?.kind
             {
@@ -144,7 +261,7 @@ where
             }
         };
 
-        self.inner.logs_for_filter(filter).await
+        self.on_blocking_pool(|inner| async move { inner.logs_for_filter(filter).await }).await
     }
 
     /// Handler for `eth_uninstallFilter`
@@ -164,7 +281,13 @@ where
     /// Handler for `eth_getLogs`
     async fn logs(&self, filter: Filter) -> RpcResult<Vec<Log>> {
         trace!(target: "rpc::eth", "Serving eth_getLogs");
-        self.inner.logs_for_filter(filter).await
+        self.on_blocking_pool(|inner| async move { inner.logs_for_filter(filter).await }).await
+    }
+
+    /// Handler for `eth_getLogsPage`
+    async fn logs_page(&self, filter: Filter) -> RpcResult<LogsPage> {
+        trace!(target: "rpc::eth", "Serving eth_getLogsPage");
+        self.on_blocking_pool(|inner| async move { inner.logs_page_for_filter(filter).await }).await
     }
 }
 
@@ -179,39 +302,34 @@ struct EthFilterInner<Client, Pool> {
     active_filters: ActiveFilters,
     /// Provides ids to identify filters
     id_provider: Arc<dyn IdProvider>,
-    /// Maximum number of logs that can be returned in a response
-    max_logs_in_response: usize,
+    /// Limits for log queries
+    config: EthFilterConfig,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
+    /// The logs blooms of recently queried blocks
+    bloom_cache: Option<BloomCache>,
 }
 
 impl<Client, Pool> EthFilterInner<Client, Pool>
 where
-    Client: BlockProvider + EvmEnvProvider + 'static,
+    Client: BlockProvider + EvmEnvProvider + LogIndexProvider + 'static,
     Pool: TransactionPool + 'static,
 {
     /// Returns logs matching given filter object.
     async fn logs_for_filter(&self, filter: Filter) -> RpcResult<Vec<Log>> {
         match filter.block_option {
             FilterBlockOption::AtBlockHash(block_hash) => {
-                let mut all_logs = Vec::new();
-                // all matching logs in the block, if it exists
-                if let Some(block) = self.eth_cache.get_block(block_hash).await.to_rpc_result()? {
-                    // get receipts for the block
-                    if let Some(receipts) =
-                        self.eth_cache.get_receipts(block_hash).await.to_rpc_result()?
-                    {
-                        let filter = FilteredParams::new(Some(filter));
-                        logs_utils::append_matching_block_logs(
-                            &mut all_logs,
-                            &filter,
-                            (block_hash, block.number).into(),
-                            block.body.into_iter().map(|tx| tx.hash()).zip(receipts),
-                            false,
-                        );
+                let logs = self.logs_at_block_hash(filter, block_hash).await?;
+                let max_logs_per_response = self.config.max_logs_per_response;
+                if max_logs_per_response > 0 && logs.len() > max_logs_per_response {
+                    // the query spans a single block, so there's no narrower range to suggest
+                    return Err(FilterError::QueryExceedsMaxResults {
+                        max: max_logs_per_response,
+                        suggested: None,
                     }
+                    .into())
                 }
-                Ok(all_logs)
+                Ok(logs)
             }
             FilterBlockOption::Range { from_block, to_block } => {
                 // compute the range
@@ -226,15 +344,61 @@ where
         }
     }
 
+    /// Returns logs matching given filter object, up to the configured limits.
+    ///
+    /// Instead of failing if the limits are exceeded, this returns the logs up to a block boundary
+    /// and the block to continue from.
+    async fn logs_page_for_filter(&self, filter: Filter) -> RpcResult<LogsPage> {
+        match filter.block_option {
+            FilterBlockOption::AtBlockHash(block_hash) => {
+                // like the first block of a range, the block is included regardless of the limit
+                let logs = self.logs_at_block_hash(filter, block_hash).await?;
+                Ok(LogsPage { logs, next_block: None })
+            }
+            FilterBlockOption::Range { from_block, to_block } => {
+                let info = self.client.chain_info().to_rpc_result()?;
+                let start_block = info.best_number;
+                let (from_block_number, to_block_number) =
+                    logs_utils::get_filter_block_range(from_block, to_block, start_block, info);
+                let (logs, next_block) =
+                    self.logs_page_in_block_range(&filter, from_block_number, to_block_number)?;
+                Ok(LogsPage { logs, next_block: next_block.map(U64::from) })
+            }
+        }
+    }
+
+    /// Returns all logs of the block with the given hash that match the filter, if the block
+    /// exists.
+    async fn logs_at_block_hash(&self, filter: Filter, block_hash: H256) -> RpcResult<Vec<Log>> {
+        let mut all_logs = Vec::new();
+        if let Some(block) = self.eth_cache.get_block(block_hash).await.to_rpc_result()? {
+            // get receipts for the block
+            if let Some(receipts) = self.eth_cache.get_receipts(block_hash).await.to_rpc_result()? {
+                let filter = FilteredParams::new(Some(filter));
+                logs_utils::append_matching_block_logs(
+                    &mut all_logs,
+                    &filter,
+                    (block_hash, block.number).into(),
+                    block.body.into_iter().map(|tx| tx.hash()).zip(receipts),
+                    false,
+                );
+            }
+        }
+        Ok(all_logs)
+    }
+
     /// Installs a new filter and returns the new identifier.
+    ///
+    /// The filter reports the changes after the current best block.
     async fn install_filter(&self, kind: FilterKind) -> RpcResult<FilterId> {
-        let last_poll_block_number = self.client.best_block_number().to_rpc_result()?;
+        let best_block_number = self.client.best_block_number().to_rpc_result()?;
         let id = FilterId::from(self.id_provider.next_id());
         let mut filters = self.active_filters.inner.lock().await;
+        remove_stale_filters(&mut filters, self.config.stale_filter_ttl);
         filters.insert(
             id.clone(),
             ActiveFilter {
-                block: last_poll_block_number,
+                block: best_block_number + 1,
                 last_poll_timestamp: Instant::now(),
                 kind,
             },
@@ -242,10 +406,33 @@ where
         Ok(id)
     }
 
+    /// Returns the logs bloom of the canonical block with the given number, if it exists.
+    ///
+    /// Only the header is read from storage, unless the bloom is cached.
+    fn logs_bloom(&self, block_number: u64) -> RpcResult<Option<Bloom>> {
+        let Some(cache) = &self.bloom_cache else {
+            let header = self.client.header_by_number(block_number).to_rpc_result()?;
+            return Ok(header.map(|header| header.logs_bloom))
+        };
+
+        // the cache is keyed by hash, so a lookup racing a reorg can't cache a stale bloom for the
+        // block number
+        let Some(hash) = self.client.block_hash(block_number).to_rpc_result()? else {
+            return Ok(None)
+        };
+        if let Some(bloom) = cache.get(hash) {
+            return Ok(Some(bloom))
+        }
+        let Some(header) = self.client.header(&hash).to_rpc_result()? else { return Ok(None) };
+        cache.insert(hash, header.logs_bloom);
+        Ok(Some(header.logs_bloom))
+    }
+
     /// Returns all logs in the given _inclusive_ range that match the filter
     ///
     /// Returns an error if:
     ///  - underlying database error
+    ///  - the range spans more blocks than the configured limit
     ///  - amount of matches exceeds configured limit
     fn get_logs_in_block_range(
         &self,
@@ -253,6 +440,59 @@ where
         from_block: u64,
         to_block: u64,
     ) -> RpcResult<Vec<Log>> {
+        let EthFilterConfig { max_logs_per_response, max_blocks_per_filter, .. } = self.config;
+        if max_blocks_per_filter > 0 && to_block.saturating_sub(from_block) >= max_blocks_per_filter
+        {
+            let suggested =
+                SuggestedBlockRange::new(from_block, from_block + max_blocks_per_filter - 1);
+            return Err(
+                FilterError::QueryExceedsMaxBlocks { max: max_blocks_per_filter, suggested }.into()
+            )
+        }
+
+        let (logs, next_block) = self.logs_page_in_block_range(filter, from_block, to_block)?;
+        if max_logs_per_response > 0 && logs.len() > max_logs_per_response {
+            // a single block exceeds the limit, so there's no narrower range to suggest
+            return Err(FilterError::QueryExceedsMaxResults {
+                max: max_logs_per_response,
+                suggested: None,
+            }
+            .into())
+        }
+        if let Some(next_block) = next_block {
+            let suggested = SuggestedBlockRange::new(from_block, next_block - 1);
+            return Err(FilterError::QueryExceedsMaxResults {
+                max: max_logs_per_response,
+                suggested: Some(suggested),
+            }
+            .into())
+        }
+
+        Ok(logs)
+    }
+
+    /// Returns the logs in the given _inclusive_ range that match the filter, up to the
+    /// configured limits.
+    ///
+    /// If a limit is reached, this stops at the last block boundary within the limits and also
+    /// returns the block to continue from. The first block is always included, even if it has more
+    /// matching logs than allowed.
+    fn logs_page_in_block_range(
+        &self,
+        filter: &Filter,
+        from_block: u64,
+        to_block: u64,
+    ) -> RpcResult<(Vec<Log>, Option<u64>)> {
+        let EthFilterConfig { max_logs_per_response, max_blocks_per_filter, .. } = self.config;
+        let (to_block, mut next_block) = if max_blocks_per_filter > 0 &&
+            to_block.saturating_sub(from_block) >= max_blocks_per_filter
+        {
+            let to_block = from_block + max_blocks_per_filter - 1;
+            (to_block, Some(to_block + 1))
+        } else {
+            (to_block, None)
+        };
+
         let mut all_logs = Vec::new();
         let filter_params = FilteredParams::new(Some(filter.clone()));
 
@@ -262,41 +502,136 @@ where
         let address_filter = FilteredParams::address_filter(&filter.address);
         let topics_filter = FilteredParams::topics_filter(&topics);
 
+        // the blocks covered by the log index are only visited if they have candidate logs, the
+        // others are checked against their blooms
+        let (indexed_blocks, first_unindexed_block) =
+            match self.indexed_blocks(filter, from_block, to_block)? {
+                Some((blocks, last_indexed_block)) => (blocks, last_indexed_block + 1),
+                None => (Vec::new(), from_block),
+            };
+        let blocks = indexed_blocks
+            .into_iter()
+            .map(|block_number| (block_number, true))
+            .chain((first_unindexed_block..=to_block).map(|block_number| (block_number, false)));
+
         // loop over the range of new blocks and check logs if the filter matches the log's bloom
         // filter
-        for block_number in from_block..=to_block {
-            if let Some(block) = self.client.block_by_number(block_number).to_rpc_result()? {
+        for (block_number, is_indexed) in blocks {
+            if !is_indexed {
+                let Some(logs_bloom) = self.logs_bloom(block_number)? else { continue };
+
                 // only if filter matches
-                if FilteredParams::matches_address(block.header.logs_bloom, &address_filter) &&
-                    FilteredParams::matches_topics(block.header.logs_bloom, &topics_filter)
+                if !FilteredParams::matches_address(logs_bloom, &address_filter) ||
+                    !FilteredParams::matches_topics(logs_bloom, &topics_filter)
                 {
-                    // get receipts for the block
-                    if let Some(receipts) =
-                        self.client.receipts_by_block(block.number.into()).to_rpc_result()?
-                    {
-                        let block_hash = block.hash_slow();
-
-                        logs_utils::append_matching_block_logs(
-                            &mut all_logs,
-                            &filter_params,
-                            (block_number, block_hash).into(),
-                            block.body.into_iter().map(|tx| tx.hash()).zip(receipts),
-                            false,
-                        );
-
-                        // size check
-                        if all_logs.len() > self.max_logs_in_response {
-                            return Err(FilterError::QueryExceedsMaxResults(
-                                self.max_logs_in_response,
-                            )
-                            .into())
-                        }
+                    continue
+                }
+            }
+
+            let Some(block) = self.client.block_by_number(block_number).to_rpc_result()? else {
+                continue
+            };
+            // get receipts for the block
+            if let Some(receipts) =
+                self.client.receipts_by_block(block.number.into()).to_rpc_result()?
+            {
+                let block_hash = block.hash_slow();
+
+                let mut block_logs = Vec::new();
+                logs_utils::append_matching_block_logs(
+                    &mut block_logs,
+                    &filter_params,
+                    (block_number, block_hash).into(),
+                    block.body.into_iter().map(|tx| tx.hash()).zip(receipts),
+                    false,
+                );
+
+                // size check
+                if max_logs_per_response > 0 &&
+                    all_logs.len() + block_logs.len() > max_logs_per_response
+                {
+                    if block_number > from_block {
+                        return Ok((all_logs, Some(block_number)))
                     }
+                    all_logs.extend(block_logs);
+                    if block_number < to_block {
+                        next_block = Some(block_number + 1);
+                    }
+                    return Ok((all_logs, next_block))
                 }
+                all_logs.extend(block_logs);
             }
         }
 
-        Ok(all_logs)
+        Ok((all_logs, next_block))
+    }
+
+    /// Returns the blocks in the given _inclusive_ range that have candidate logs for the filter
+    /// according to the log index, and the last block of the range that is indexed.
+    ///
+    /// Returns `None` if the logs aren't indexed, or if the filter has no address or topic to look
+    /// up.
+    fn indexed_blocks(
+        &self,
+        filter: &Filter,
+        from_block: u64,
+        to_block: u64,
+    ) -> RpcResult<Option<(Vec<u64>, u64)>> {
+        let Some(checkpoint) = self.client.log_index_checkpoint().to_rpc_result()? else {
+            return Ok(None)
+        };
+        let to_block = to_block.min(checkpoint);
+        if from_block > to_block {
+            return Ok(None)
+        }
+        let range = from_block..=to_block;
+
+        // a block is a candidate if it matches each of the filter's criteria: one of the addresses
+        // and one of the topics of each position
+        let mut candidates: Option<BTreeSet<u64>> = None;
+        let mut intersect = |blocks: BTreeSet<u64>| {
+            candidates = Some(match candidates.take() {
+                Some(candidates) => candidates.intersection(&blocks).copied().collect(),
+                None => blocks,
+            });
+        };
+
+        let addresses = match &filter.address {
+            Some(ValueOrArray::Value(address)) => vec![*address],
+            Some(ValueOrArray::Array(addresses)) => addresses.clone(),
+            None => Vec::new(),
+        };
+        if !addresses.is_empty() {
+            let mut blocks = BTreeSet::new();
+            for address in addresses {
+                blocks.extend(
+                    self.client.blocks_with_log_address(address, range.clone()).to_rpc_result()?,
+                );
+            }
+            intersect(blocks);
+        }
+
+        for topic in filter.topics() {
+            // a wildcard at the position matches any topic
+            let topics = match topic {
+                ValueOrArray::Value(Some(topic)) => vec![*topic],
+                ValueOrArray::Array(topics)
+                    if !topics.is_empty() && topics.iter().all(Option::is_some) =>
+                {
+                    topics.iter().flatten().copied().collect()
+                }
+                _ => continue,
+            };
+            let mut blocks = BTreeSet::new();
+            for topic in topics {
+                blocks.extend(
+                    self.client.blocks_with_log_topic(topic, range.clone()).to_rpc_result()?,
+                );
+            }
+            intersect(blocks);
+        }
+
+        Ok(candidates.map(|blocks| (blocks.into_iter().collect(), to_block)))
     }
 }
 
@@ -306,10 +641,22 @@ pub struct ActiveFilters {
     inner: Arc<Mutex<HashMap<FilterId, ActiveFilter>>>,
 }
 
+/// Removes the filters that weren't polled within the `ttl`.
+fn remove_stale_filters(filters: &mut HashMap<FilterId, ActiveFilter>, ttl: Duration) {
+    let now = Instant::now();
+    filters.retain(|id, filter| {
+        let is_stale = now.duration_since(filter.last_poll_timestamp) > ttl;
+        if is_stale {
+            trace!(target: "rpc::eth::filter", ?id, "removed stale filter");
+        }
+        !is_stale
+    });
+}
+
 /// An installed filter
 #[derive(Debug)]
 struct ActiveFilter {
-    /// At which block the filter was polled last.
+    /// The first block the next poll reports changes for.
     block: u64,
     /// Last time this filter was polled.
     last_poll_timestamp: Instant,
@@ -321,7 +668,45 @@ struct ActiveFilter {
 enum FilterKind {
     Log(Box<Filter>),
     Block,
-    PendingTransaction,
+    PendingTransaction(PendingTransactionsReceiver),
+}
+
+/// Receives the hashes of the transactions that became pending since the filter was installed.
+#[derive(Clone, Debug)]
+struct PendingTransactionsReceiver {
+    txs_receiver: Arc<Mutex<Receiver<TxHash>>>,
+}
+
+impl PendingTransactionsReceiver {
+    fn new(receiver: Receiver<TxHash>) -> Self {
+        Self { txs_receiver: Arc::new(Mutex::new(receiver)) }
+    }
+
+    /// Returns all hashes received since the last poll.
+    async fn drain(&self) -> Vec<TxHash> {
+        let mut receiver = self.txs_receiver.lock().await;
+        let mut pending_txs = Vec::new();
+        while let Ok(tx_hash) = receiver.try_recv() {
+            pending_txs.push(tx_hash);
+        }
+        pending_txs
+    }
+}
+
+/// An _inclusive_ range of blocks that is within the limits of a log query that exceeded them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedBlockRange {
+    /// The first block of the range.
+    pub from_block: U64,
+    /// The last block of the range.
+    pub to_block: U64,
+}
+
+impl SuggestedBlockRange {
+    fn new(from_block: u64, to_block: u64) -> Self {
+        Self { from_block: from_block.into(), to_block: to_block.into() }
+    }
 }
 
 /// Errors that can occur in the handler implementation
@@ -329,8 +714,10 @@ enum FilterKind {
 pub enum FilterError {
     #[error("filter not found")]
     FilterNotFound(FilterId),
-    #[error("Query exceeds max results {0}")]
-    QueryExceedsMaxResults(usize),
+    #[error("Query exceeds max block range {max}")]
+    QueryExceedsMaxBlocks { max: u64, suggested: SuggestedBlockRange },
+    #[error("Query exceeds max results {max}")]
+    QueryExceedsMaxResults { max: usize, suggested: Option<SuggestedBlockRange> },
 }
 
 // convert the error
@@ -341,9 +728,72 @@ impl From<FilterError> for jsonrpsee::core::Error {
                 jsonrpsee::types::error::INVALID_PARAMS_CODE,
                 "filter not found",
             ),
-            err @ FilterError::QueryExceedsMaxResults(_) => {
-                rpc_error_with_code(jsonrpsee::types::error::INVALID_PARAMS_CODE, err.to_string())
+            // the data contains a narrower range that is within the limits, if any
+            FilterError::QueryExceedsMaxBlocks { suggested, .. } => {
+                limit_exceeded_rpc_err(err.to_string(), Some(suggested))
+            }
+            FilterError::QueryExceedsMaxResults { suggested, .. } => {
+                limit_exceeded_rpc_err(err.to_string(), suggested)
             }
         }
     }
 }
+
+fn limit_exceeded_rpc_err(
+    msg: String,
+    suggested: Option<SuggestedBlockRange>,
+) -> jsonrpsee::core::Error {
+    jsonrpsee::core::Error::Call(CallError::Custom(ErrorObject::owned(
+        EthRpcErrorCode::LimitExceeded.code(),
+        msg,
+        suggested,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_exceeded_error_suggests_range() {
+        let err: jsonrpsee::core::Error = FilterError::QueryExceedsMaxBlocks {
+            max: 100,
+            suggested: SuggestedBlockRange::new(1000, 1099),
+        }
+        .into();
+        let jsonrpsee::core::Error::Call(CallError::Custom(err)) = err else {
+            panic!("expected custom call error")
+        };
+        assert_eq!(err.code(), EthRpcErrorCode::LimitExceeded.code());
+        assert_eq!(err.data().unwrap().get(), r#"{"fromBlock":"0x3e8","toBlock":"0x44b"}"#);
+    }
+
+    #[test]
+    fn limit_exceeded_error_without_narrower_range() {
+        let err: jsonrpsee::core::Error =
+            FilterError::QueryExceedsMaxResults { max: 10, suggested: None }.into();
+        let jsonrpsee::core::Error::Call(CallError::Custom(err)) = err else {
+            panic!("expected custom call error")
+        };
+        assert_eq!(err.code(), EthRpcErrorCode::LimitExceeded.code());
+        assert_eq!(err.message(), "Query exceeds max results 10");
+        assert!(err.data().is_none());
+    }
+
+    #[test]
+    fn removes_stale_filters() {
+        let ttl = Duration::from_secs(60);
+        let now = Instant::now();
+        let mut filters = HashMap::new();
+        for (id, idle) in [("0x1", Duration::ZERO), ("0x2", ttl * 2)] {
+            filters.insert(
+                FilterId::Str(id.to_string()),
+                ActiveFilter { block: 0, last_poll_timestamp: now - idle, kind: FilterKind::Block },
+            );
+        }
+
+        remove_stale_filters(&mut filters, ttl);
+        assert_eq!(filters.len(), 1);
+        assert!(filters.contains_key(&FilterId::Str("0x1".to_string())));
+    }
+}
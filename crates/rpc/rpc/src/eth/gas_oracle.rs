@@ -0,0 +1,155 @@
+//! An implementation of the gas price oracle behind `eth_gasPrice` and `eth_maxPriorityFeePerGas`
+//! that samples the tips paid in recent blocks, like geth's.
+
+use crate::eth::{cache::EthStateCache, error::EthResult};
+use reth_primitives::{Block, H256, U256};
+use reth_provider::BlockProvider;
+pub use reth_rpc_types::GasPriceOracleConfig;
+use tokio::sync::Mutex;
+
+/// The number of transactions sampled per block, the ones with the lowest tips are sampled.
+const SAMPLE_NUMBER: usize = 3;
+
+/// Suggests the tip for new transactions from the tips paid in recent blocks.
+///
+/// The lowest tips of each of the recent blocks are sampled, and the configured percentile of the
+/// samples is suggested. Blocks without tips to sample, like empty blocks, contribute the last
+/// suggestion instead.
+///
+/// The suggestion is cached until the chain head changes.
+#[derive(Debug)]
+pub struct GasPriceOracle {
+    /// The settings of the oracle.
+    config: GasPriceOracleConfig,
+    /// The last suggestion and the head it was made for.
+    last_price: Mutex<LastPrice>,
+}
+
+// === impl GasPriceOracle ===
+
+impl GasPriceOracle {
+    /// Creates a new oracle with the given settings.
+    pub fn new(config: GasPriceOracleConfig) -> Self {
+        let last_price =
+            LastPrice { block_hash: H256::zero(), price: U256::from(config.default_price) };
+        Self { config, last_price: Mutex::new(last_price) }
+    }
+
+    /// Returns the settings of the oracle.
+    pub fn config(&self) -> &GasPriceOracleConfig {
+        &self.config
+    }
+
+    /// Suggests the tip for a new transaction, based on the blocks up to the current head.
+    pub(crate) async fn suggest_tip_cap<Client>(
+        &self,
+        client: &Client,
+        cache: &EthStateCache,
+    ) -> EthResult<U256>
+    where
+        Client: BlockProvider,
+    {
+        let head = client.chain_info()?.best_hash;
+
+        // concurrent requests wait for the first one to sample the blocks
+        let mut last_price = self.last_price.lock().await;
+        if last_price.block_hash == head {
+            return Ok(last_price.price)
+        }
+
+        let mut samples = Vec::new();
+        let mut block_hash = head;
+        for _ in 0..self.config.blocks {
+            let Some(block) = cache.get_block(block_hash).await? else { break };
+
+            let tips = self.sample_block(&block);
+            if tips.is_empty() {
+                samples.push(last_price.price);
+            } else {
+                samples.extend(tips);
+            }
+
+            if block.number == 0 {
+                break
+            }
+            block_hash = block.parent_hash;
+        }
+
+        let mut price = last_price.price;
+        if !samples.is_empty() {
+            samples.sort_unstable();
+            let percentile = self.config.percentile.min(100) as usize;
+            price = samples[(samples.len() - 1) * percentile / 100];
+        }
+        price = price.min(U256::from(self.config.max_price));
+
+        *last_price = LastPrice { block_hash: head, price };
+        Ok(price)
+    }
+
+    /// Returns the lowest tips paid in the block, in ascending order.
+    ///
+    /// Tips below the ignore price and the transactions of the block's beneficiary are skipped.
+    fn sample_block(&self, block: &Block) -> Vec<U256> {
+        let base_fee = block.base_fee_per_gas.unwrap_or_default();
+        let mut txs = block
+            .body
+            .iter()
+            .filter_map(|tx| Some((tx.effective_tip_per_gas(base_fee)?, tx)))
+            .filter(|(tip, _)| *tip >= self.config.ignore_price as u128)
+            .collect::<Vec<_>>();
+        txs.sort_unstable_by_key(|(tip, _)| *tip);
+
+        txs.into_iter()
+            .filter(|(_, tx)| tx.recover_signer() != Some(block.beneficiary))
+            .take(SAMPLE_NUMBER)
+            .map(|(tip, _)| U256::from(tip))
+            .collect()
+    }
+}
+
+impl Default for GasPriceOracle {
+    fn default() -> Self {
+        Self::new(GasPriceOracleConfig::default())
+    }
+}
+
+/// The last suggestion of the [GasPriceOracle].
+#[derive(Debug)]
+struct LastPrice {
+    /// The head the suggestion was made for.
+    block_hash: H256,
+    /// The suggested tip.
+    price: U256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        sign_message, Header, Transaction, TransactionKind, TransactionSigned, TxEip1559,
+    };
+
+    fn tx_with_tip(tip: u128) -> TransactionSigned {
+        let tx = Transaction::Eip1559(TxEip1559 {
+            max_fee_per_gas: 100 + tip,
+            max_priority_fee_per_gas: tip,
+            to: TransactionKind::Create,
+            ..Default::default()
+        });
+        let signature = sign_message(H256::from_low_u64_be(1), tx.signature_hash()).unwrap();
+        TransactionSigned::from_transaction_and_signature(tx, signature)
+    }
+
+    #[test]
+    fn samples_lowest_tips() {
+        let oracle = GasPriceOracle::default();
+        let block = Block {
+            header: Header { base_fee_per_gas: Some(100), ..Default::default() },
+            body: [5, 1, 4, 3, 2].into_iter().map(tx_with_tip).collect(),
+            ..Default::default()
+        };
+        // the tip of 1 wei is below the ignore price
+        assert_eq!(oracle.sample_block(&block), vec![U256::from(2), U256::from(3), U256::from(4)]);
+    }
+}
@@ -1,17 +1,20 @@
 //! `eth` namespace handler implementation.
 
 mod api;
+pub mod bloom_cache;
 pub mod cache;
 pub mod error;
 mod filter;
+pub mod gas_oracle;
 mod id_provider;
 mod logs_utils;
 mod pubsub;
 pub(crate) mod revm_utils;
-mod signer;
+pub(crate) mod signer;
 pub(crate) mod utils;
 
 pub use api::{EthApi, EthApiSpec, EthTransactions, TransactionSource};
-pub use filter::EthFilter;
+pub use filter::{EthFilter, EthFilterConfig};
 pub use id_provider::EthSubscriptionIdProvider;
 pub use pubsub::EthPubSub;
+pub use signer::{DevSigner, PersonalSigner};
@@ -1,24 +1,35 @@
 //! `eth_` PubSub RPC handler implementation
 use crate::eth::{cache::EthStateCache, logs_utils};
-use futures::StreamExt;
-use jsonrpsee::{types::SubscriptionResult, SubscriptionSink};
+use futures::{StreamExt, TryStreamExt};
+use jsonrpsee::{
+    types::{
+        error::{ErrorObject, INTERNAL_ERROR_CODE},
+        SubscriptionResult,
+    },
+    SubscriptionSink,
+};
 use reth_network_api::NetworkInfo;
 use reth_primitives::{filter::FilteredParams, TxHash};
-use reth_provider::{BlockProvider, CanonStateSubscriptions, EvmEnvProvider};
+use reth_provider::{
+    BlockProvider, CanonStateNotification, CanonStateSubscriptions, EvmEnvProvider,
+};
 use reth_rpc_api::EthPubSubApiServer;
 use reth_rpc_types::{
     pubsub::{
         Params, PubSubSyncStatus, SubscriptionKind, SubscriptionResult as EthSubscriptionResult,
         SyncStatusMetadata,
     },
-    Header, Log,
+    Header, Log, SyncEvent, Transaction,
 };
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{PoolTransaction, TransactionPool};
+use serde::Serialize;
+use tokio::sync::broadcast;
 use tokio_stream::{
-    wrappers::{BroadcastStream, ReceiverStream},
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream, ReceiverStream},
     Stream,
 };
+use tracing::debug;
 
 /// `Eth` pubsub RPC implementation.
 ///
@@ -63,9 +74,19 @@ impl<Client, Pool, Events, Network> EthPubSub<Client, Pool, Events, Network> {
         eth_cache: EthStateCache,
         subscription_task_spawner: Box<dyn TaskSpawner>,
     ) -> Self {
-        let inner = EthPubSubInner { client, pool, chain_events, network, eth_cache };
+        let inner =
+            EthPubSubInner { client, pool, chain_events, network, eth_cache, sync_events: None };
         Self { inner, subscription_task_spawner }
     }
+
+    /// Configures the channel the events of the sync pipeline are sent on.
+    ///
+    /// `syncing` subscribers are notified when the node starts or stops syncing. Without the
+    /// pipeline events, this is only checked when the canonical chain changes.
+    pub fn with_sync_events(mut self, sync_events: broadcast::Sender<SyncEvent>) -> Self {
+        self.inner.sync_events = Some(sync_events);
+        self
+    }
 }
 
 impl<Client, Pool, Events, Network> EthPubSubApiServer for EthPubSub<Client, Pool, Events, Network>
@@ -109,8 +130,8 @@ async fn handle_accepted<Client, Pool, Events, Network>(
         SubscriptionKind::NewHeads => {
             let stream = pubsub
                 .into_new_headers_stream()
-                .map(|block| EthSubscriptionResult::Header(Box::new(block.into())));
-            accepted_sink.pipe_from_stream(stream).await;
+                .map_ok(|block| EthSubscriptionResult::Header(Box::new(block.into())));
+            pipe_until_lagged(accepted_sink, stream).await;
         }
         SubscriptionKind::Logs => {
             // if no params are provided, used default filter params
@@ -118,20 +139,37 @@ async fn handle_accepted<Client, Pool, Events, Network>(
                 Some(Params::Logs(filter)) => FilteredParams::new(Some(*filter)),
                 _ => FilteredParams::default(),
             };
-            let stream =
-                pubsub.into_log_stream(filter).map(|log| EthSubscriptionResult::Log(Box::new(log)));
-            accepted_sink.pipe_from_stream(stream).await;
+            let stream = pubsub
+                .into_log_stream(filter)
+                .map_ok(|log| EthSubscriptionResult::Log(Box::new(log)));
+            pipe_until_lagged(accepted_sink, stream).await;
         }
         SubscriptionKind::NewPendingTransactions => {
+            if let Some(Params::Bool(true)) = params {
+                let stream = pubsub
+                    .into_full_pending_transaction_stream()
+                    .map(|tx| EthSubscriptionResult::FullTransaction(Box::new(tx)));
+                accepted_sink.pipe_from_stream(stream).await;
+                return
+            }
+
             let stream = pubsub
                 .into_pending_transaction_stream()
                 .map(EthSubscriptionResult::TransactionHash);
             accepted_sink.pipe_from_stream(stream).await;
         }
         SubscriptionKind::Syncing => {
-            // get new block subscription
-            let mut canon_state =
-                BroadcastStream::new(pubsub.chain_events.subscribe_to_canonical_state());
+            // the sync status can change when the pipeline progresses or a new block is added
+            let pipeline_progress = match pubsub.sync_events.as_ref() {
+                Some(sync_events) => {
+                    BroadcastStream::new(sync_events.subscribe()).map(|_| ()).left_stream()
+                }
+                None => futures::stream::pending().right_stream(),
+            };
+            let mut sync_updates = futures::stream::select(
+                canonical_state_stream(&pubsub.chain_events).map(|_| ()),
+                pipeline_progress,
+            );
             // get current sync status
             let mut initial_sync_status = pubsub.network.is_syncing();
             let current_sub_res = pubsub.sync_status(initial_sync_status).await;
@@ -139,7 +177,7 @@ async fn handle_accepted<Client, Pool, Events, Network>(
             // send the current status immediately
             let _ = accepted_sink.send(&current_sub_res);
 
-            while (canon_state.next().await).is_some() {
+            while (sync_updates.next().await).is_some() {
                 let current_syncing = pubsub.network.is_syncing();
                 // Only send a new response if the sync status has changed
                 if current_syncing != initial_sync_status {
@@ -161,6 +199,53 @@ impl<Client, Pool, Events, Network> std::fmt::Debug for EthPubSub<Client, Pool,
     }
 }
 
+/// Returns a stream of the canonical state notifications.
+///
+/// A slow subscriber can lag behind the notification channel, which yields a
+/// [SubscriptionLagged] error for the missed notifications.
+fn canonical_state_stream<Events>(
+    chain_events: &Events,
+) -> impl Stream<Item = Result<CanonStateNotification, SubscriptionLagged>>
+where
+    Events: CanonStateSubscriptions,
+{
+    BroadcastStream::new(chain_events.subscribe_to_canonical_state()).map(|notification| {
+        notification.map_err(|BroadcastStreamRecvError::Lagged(skipped)| {
+            debug!(target: "rpc::eth", skipped, "Subscription missed canonical state notifications");
+            SubscriptionLagged(skipped)
+        })
+    })
+}
+
+/// Sends the items of the stream to the subscriber until the stream or the subscription ends.
+///
+/// If the stream fails, the subscription is closed with the error.
+async fn pipe_until_lagged<S, T>(mut sink: SubscriptionSink, stream: S)
+where
+    S: Stream<Item = Result<T, SubscriptionLagged>>,
+    T: Serialize,
+{
+    futures::pin_mut!(stream);
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(item) => {
+                if !matches!(sink.send(&item), Ok(true)) {
+                    return
+                }
+            }
+            Err(err) => {
+                sink.close(ErrorObject::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>));
+                return
+            }
+        }
+    }
+}
+
+/// The subscriber fell behind the canonical state notifications and missed some of them.
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("subscription lagged behind and missed {0} canonical state notifications")]
+struct SubscriptionLagged(u64);
+
 /// Container type `EthPubSub`
 #[derive(Clone)]
 struct EthPubSubInner<Client, Pool, Events, Network> {
@@ -174,6 +259,8 @@ struct EthPubSubInner<Client, Pool, Events, Network> {
     network: Network,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
+    /// The events of the sync pipeline, if configured.
+    sync_events: Option<broadcast::Sender<SyncEvent>>,
 }
 
 // == impl EthPubSubInner ===
@@ -183,15 +270,18 @@ where
     Client: BlockProvider + 'static,
 {
     /// Returns the current sync status for the `syncing` subscription
+    ///
+    /// Like `eth_syncing`, the highest block is the tip the pipeline is syncing towards.
     async fn sync_status(&self, is_syncing: bool) -> EthSubscriptionResult {
         if is_syncing {
-            let current_block =
-                self.client.chain_info().map(|info| info.best_number).unwrap_or_default();
+            let current_block = self.client.best_block_number().unwrap_or_default();
+            let highest_block =
+                self.client.last_block_number().unwrap_or_default().max(current_block);
             EthSubscriptionResult::SyncState(PubSubSyncStatus::Detailed(SyncStatusMetadata {
                 syncing: true,
                 starting_block: 0,
                 current_block,
-                highest_block: Some(current_block),
+                highest_block: Some(highest_block),
             }))
         } else {
             EthSubscriptionResult::SyncState(PubSubSyncStatus::Simple(false))
@@ -207,6 +297,22 @@ where
     fn into_pending_transaction_stream(self) -> impl Stream<Item = TxHash> {
         ReceiverStream::new(self.pool.pending_transactions_listener())
     }
+
+    /// Returns a stream that yields the full transactions emitted by the txpool.
+    ///
+    /// Only the hashes are buffered until the subscriber catches up, the transactions are looked
+    /// up when they're sent. The pool skips the notifications for a subscriber whose buffer is
+    /// full, so a slow subscriber misses transactions instead of growing the buffer. Transactions
+    /// that left the pool in the meantime are skipped.
+    fn into_full_pending_transaction_stream(self) -> impl Stream<Item = Transaction> {
+        let pool = self.pool;
+        ReceiverStream::new(pool.pending_transactions_listener()).filter_map(move |hash| {
+            let tx = pool
+                .get(&hash)
+                .map(|tx| Transaction::from_recovered(tx.transaction.to_recovered_transaction()));
+            futures::future::ready(tx)
+        })
+    }
 }
 
 impl<Client, Pool, Events, Network> EthPubSubInner<Client, Pool, Events, Network>
@@ -217,11 +323,13 @@ where
     Pool: 'static,
 {
     /// Returns a stream that yields all new RPC blocks.
-    fn into_new_headers_stream(self) -> impl Stream<Item = Header> {
-        BroadcastStream::new(self.chain_events.subscribe_to_canonical_state())
-            .map(|new_block| {
-                let new_chain = new_block.expect("new block subscription never ends; qed");
-                new_chain
+    ///
+    /// On reorgs, the headers of the new canonical blocks are emitted. The stream fails if it
+    /// lagged behind the canonical state notifications.
+    fn into_new_headers_stream(self) -> impl Stream<Item = Result<Header, SubscriptionLagged>> {
+        canonical_state_stream(&self.chain_events)
+            .map_ok(|new_chain| {
+                let headers = new_chain
                     .committed()
                     .map(|c| {
                         c.blocks()
@@ -231,26 +339,102 @@ where
                             })
                             .collect::<Vec<_>>()
                     })
-                    .unwrap_or_default()
+                    .unwrap_or_default();
+                futures::stream::iter(headers).map(Ok)
             })
-            .flat_map(futures::stream::iter)
+            .try_flatten()
     }
 
     /// Returns a stream that yields all logs that match the given filter.
-    fn into_log_stream(self, filter: FilteredParams) -> impl Stream<Item = Log> {
-        BroadcastStream::new(self.chain_events.subscribe_to_canonical_state())
-            .map(move |canon_state| {
-                canon_state.expect("new block subscription never ends; qed").block_receipts()
-            })
-            .flat_map(futures::stream::iter)
-            .flat_map(move |(block_receipts, removed)| {
-                let all_logs = logs_utils::matching_block_logs(
-                    &filter,
-                    block_receipts.block,
-                    block_receipts.tx_receipts.into_iter(),
-                    removed,
-                );
-                futures::stream::iter(all_logs)
+    ///
+    /// On reorgs, the logs of the reverted blocks are emitted with `removed: true` before the logs
+    /// of the new canonical blocks. The stream fails if it lagged behind the canonical state
+    /// notifications.
+    fn into_log_stream(
+        self,
+        filter: FilteredParams,
+    ) -> impl Stream<Item = Result<Log, SubscriptionLagged>> {
+        canonical_state_stream(&self.chain_events)
+            .map_ok(move |canon_state| {
+                let all_logs = canon_state
+                    .block_receipts()
+                    .into_iter()
+                    .flat_map(|(block_receipts, removed)| {
+                        logs_utils::matching_block_logs(
+                            &filter,
+                            block_receipts.block,
+                            block_receipts.tx_receipts.into_iter(),
+                            removed,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                futures::stream::iter(all_logs).map(Ok)
             })
+            .try_flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_network_api::test_utils::NoopNetwork;
+    use reth_provider::{
+        test_utils::{blocks::BlockChainTestData, NoopProvider, TestCanonStateSubscriptions},
+        Chain,
+    };
+    use reth_transaction_pool::test_utils::testing_pool;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn reorg_emits_removed_logs() {
+        let mut chain_events = TestCanonStateSubscriptions::default();
+        let pubsub = EthPubSubInner {
+            pool: testing_pool(),
+            client: NoopProvider::default(),
+            chain_events: chain_events.clone(),
+            network: NoopNetwork::default(),
+            eth_cache: EthStateCache::spawn(NoopProvider::default(), Default::default()),
+            sync_events: None,
+        };
+        let mut logs = Box::pin(pubsub.into_log_stream(FilteredParams::default()));
+
+        // replace block 2 with a block that emits a different log
+        let old_block = BlockChainTestData::default().blocks.pop().unwrap();
+        let new_block = BlockChainTestData::default_with_numbers(2, 3).blocks.remove(0);
+        let (old_hash, new_hash) = (old_block.0.hash, new_block.0.hash);
+        chain_events.add_next_reorg(
+            Arc::new(Chain::new(vec![old_block])),
+            Arc::new(Chain::new(vec![new_block])),
+        );
+
+        let removed = logs.next().await.unwrap().unwrap();
+        assert!(removed.removed);
+        assert_eq!(removed.block_hash, Some(old_hash));
+
+        let added = logs.next().await.unwrap().unwrap();
+        assert!(!added.removed);
+        assert_eq!(added.block_hash, Some(new_hash));
+    }
+
+    #[tokio::test]
+    async fn lagging_subscription_fails() {
+        let mut chain_events = TestCanonStateSubscriptions::default();
+        let pubsub = EthPubSubInner {
+            pool: testing_pool(),
+            client: NoopProvider::default(),
+            chain_events: chain_events.clone(),
+            network: NoopNetwork::default(),
+            eth_cache: EthStateCache::spawn(NoopProvider::default(), Default::default()),
+            sync_events: None,
+        };
+        let mut headers = Box::pin(pubsub.into_new_headers_stream());
+
+        // overflow the notification channel of the subscription
+        let block = BlockChainTestData::default().blocks.pop().unwrap();
+        for _ in 0..101 {
+            chain_events.add_next_commit(Arc::new(Chain::new(vec![block.clone()])));
+        }
+
+        assert!(matches!(headers.next().await, Some(Err(SubscriptionLagged(1)))));
     }
 }
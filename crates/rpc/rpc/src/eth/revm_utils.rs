@@ -1,16 +1,27 @@
 //! utilities for working with revm
 
-use crate::eth::error::{EthApiError, EthResult, InvalidTransactionError};
-use reth_primitives::{AccessList, Address, U256};
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult, InvalidTransactionError},
+        EthTransactions,
+    },
+    BlockingTaskPool,
+};
+use rayon::prelude::*;
+use reth_primitives::{AccessList, Address, BlockId, U256};
+use reth_provider::StateProviderBox;
+use reth_revm::database::{State, SubState};
 use reth_rpc_types::{
-    state::{AccountOverride, StateOverride},
-    CallRequest,
+    state::{AccountOverride, EvmOverrides, StateOverride},
+    BlockOverrides, CallRequest,
 };
 use revm::{
     db::CacheDB,
     precompile::{Precompiles, SpecId as PrecompilesSpecId},
-    primitives::{BlockEnv, CfgEnv, Env, ResultAndState, SpecId, TransactTo, TxEnv},
-    Database, Inspector,
+    primitives::{
+        BlockEnv, CfgEnv, Env, ResultAndState, SpecId, State as StateChanges, TransactTo, TxEnv,
+    },
+    Database, DatabaseCommit, Inspector,
 };
 use revm_primitives::{db::DatabaseRef, Bytecode};
 use tracing::trace;
@@ -63,15 +74,85 @@ where
     Ok((res, evm.env))
 }
 
+/// Replays the transactions on top of the state at the given block and applies `f` to each of
+/// them, in order.
+///
+/// `f` executes the transaction and returns its state changes, which are committed before the
+/// next transaction is executed.
+///
+/// If a [BlockingTaskPool] is provided, the transactions are split into one chunk per parallel
+/// thread of the pool and the chunks are processed in parallel. The state each chunk starts from
+/// is obtained by executing the preceding transactions once without an inspector, which is much
+/// cheaper than tracing them.
+pub(crate) fn replay_transactions_with<Eth, F, R>(
+    eth_api: &Eth,
+    at: BlockId,
+    envs: Vec<Env>,
+    blocking_pool: Option<&BlockingTaskPool>,
+    f: F,
+) -> EthResult<Vec<R>>
+where
+    Eth: EthTransactions,
+    F: Fn(usize, Env, &mut SubState<StateProviderBox<'_>>) -> EthResult<(R, StateChanges)> + Sync,
+    R: Send,
+{
+    let num_chunks = blocking_pool.map(|pool| pool.num_parallel_threads()).unwrap_or(1).max(1);
+    let chunk_size = ((envs.len() + num_chunks - 1) / num_chunks).max(1);
+
+    let replay_chunk =
+        |start: usize, chunk: &[Env], pre_state: &[StateChanges]| -> EthResult<Vec<R>> {
+            let mut db = SubState::new(State::new(eth_api.state_at(at)?));
+            for changes in pre_state {
+                db.commit(changes.clone());
+            }
+
+            let mut results = Vec::with_capacity(chunk.len());
+            for (offset, env) in chunk.iter().enumerate() {
+                let (res, changes) = f(start + offset, env.clone(), &mut db)?;
+                db.commit(changes);
+                results.push(res);
+            }
+            Ok(results)
+        };
+
+    match blocking_pool {
+        Some(pool) if envs.len() > chunk_size => {
+            // the changes of all transactions before the last chunk
+            let pre_state_len = (envs.len() - 1) / chunk_size * chunk_size;
+            let mut db = SubState::new(State::new(eth_api.state_at(at)?));
+            let mut pre_state = Vec::with_capacity(pre_state_len);
+            for env in &envs[..pre_state_len] {
+                let (res, _) = transact(&mut db, env.clone())?;
+                db.commit(res.state.clone());
+                pre_state.push(res.state);
+            }
+
+            let chunks = pool.install(|| {
+                envs.par_chunks(chunk_size)
+                    .enumerate()
+                    .map(|(idx, chunk)| {
+                        let start = idx * chunk_size;
+                        replay_chunk(start, chunk, &pre_state[..start])
+                    })
+                    .collect::<EthResult<Vec<_>>>()
+            })?;
+            Ok(chunks.into_iter().flatten().collect())
+        }
+        _ => replay_chunk(0, &envs, &[]),
+    }
+}
+
 /// Prepares the [Env] for execution.
 ///
+/// The state overrides are applied to the database, the block overrides to the block environment.
+///
 /// Does not commit any changes to the underlying database.
 pub(crate) fn prepare_call_env<DB>(
     mut cfg: CfgEnv,
-    block: BlockEnv,
+    mut block: BlockEnv,
     request: CallRequest,
     db: &mut CacheDB<DB>,
-    state_overrides: Option<StateOverride>,
+    overrides: EvmOverrides,
 ) -> EthResult<Env>
 where
     DB: DatabaseRef,
@@ -90,6 +171,11 @@ where
     // <https://github.com/ethereum/go-ethereum/blob/ee8e83fa5f6cb261dad2ed0a7bbcde4930c41e6c/internal/ethapi/api.go#L985>
     cfg.disable_base_fee = true;
 
+    let EvmOverrides { state: state_overrides, block: block_overrides } = overrides;
+    if let Some(block_overrides) = block_overrides {
+        apply_block_overrides(block_overrides, &mut block);
+    }
+
     let request_gas = request.gas;
 
     let mut env = build_call_evm_env(cfg, block, request)?;
@@ -249,8 +335,39 @@ impl CallFees {
     }
 }
 
+/// Applies the given [BlockOverrides] to the [BlockEnv].
+pub(crate) fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
+    let BlockOverrides { number, difficulty, time, gas_limit, coinbase, random, base_fee } =
+        overrides;
+
+    if let Some(number) = number {
+        env.number = number;
+    }
+    if let Some(difficulty) = difficulty {
+        env.difficulty = difficulty;
+    }
+    if let Some(time) = time {
+        env.timestamp = U256::from(time.as_u64());
+    }
+    if let Some(gas_limit) = gas_limit {
+        env.gas_limit = U256::from(gas_limit.as_u64());
+    }
+    if let Some(coinbase) = coinbase {
+        env.coinbase = coinbase;
+    }
+    if let Some(random) = random {
+        env.prevrandao = Some(random);
+    }
+    if let Some(base_fee) = base_fee {
+        env.basefee = base_fee;
+    }
+}
+
 /// Applies the given state overrides (a set of [AccountOverride]) to the [CacheDB].
-fn apply_state_overrides<DB>(overrides: StateOverride, db: &mut CacheDB<DB>) -> EthResult<()>
+pub(crate) fn apply_state_overrides<DB>(
+    overrides: StateOverride,
+    db: &mut CacheDB<DB>,
+) -> EthResult<()>
 where
     DB: DatabaseRef,
     EthApiError: From<<DB as DatabaseRef>::Error>,
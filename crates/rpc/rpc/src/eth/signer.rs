@@ -1,15 +1,21 @@
 //! An abstraction over ethereum signers.
 
-use crate::eth::error::SignError;
-use ethers_core::{
-    types::transaction::eip712::{Eip712, TypedData},
-    utils::hash_message,
+use crate::eth::error::{KeystoreError, SignError};
+use ethers_core::utils::hash_message;
+use reth_primitives::{
+    eip712::TypedData, keccak256, sign_message, Address, Signature, TransactionSigned, H256,
 };
-use reth_primitives::{sign_message, Address, Signature, TransactionSigned, H256};
 use reth_rpc_types::TypedTransactionRequest;
 
-use secp256k1::SecretKey;
-use std::collections::HashMap;
+use parking_lot::Mutex;
+use secp256k1::{SecretKey, SECP256K1};
+use std::{
+    collections::HashMap,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 type Result<T> = std::result::Result<T, SignError>;
 
@@ -34,17 +40,82 @@ pub(crate) trait EthSigner: Send + Sync {
         address: &Address,
     ) -> Result<TransactionSigned>;
 
-    /// Encodes and signs the typed data according EIP-712. Payload must implement Eip712 trait.
+    /// Encodes and signs the typed data according to EIP-712.
     fn sign_typed_data(&self, address: Address, payload: &TypedData) -> Result<Signature>;
 }
 
+/// The secret keys of the prefunded accounts of the [`DEV`](reth_primitives::DEV) chain, derived
+/// from the `test test test test test test test test test test test junk` mnemonic.
+const DEV_ACCOUNT_KEYS: [&str; 10] = [
+    "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+    "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+    "5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a",
+    "7c852118294e51e653712a81e05800f419141751be58f605c371e15141b007a6",
+    "47e179ec197488593b187f80a00eb0da91f1b9d0b13f8733639f19c30a34926a",
+    "8b3a350cf5c34c9194ca85829a2df0ec3153be0318b5e2d3348e872092edffba",
+    "92db14e403b83dfe3df233f83dfa3a0d7096f21ca9b0d6d6b8d88b2b4ec1564e",
+    "4bbbf85ce3377467afe5d46f804f221813b2bb87f24d81f60f1fcdbf7cbf4356",
+    "dbda1821b80551c9d65939329250298aa3472ba22feea921c0cf5d620ea67b97",
+    "2a871d0798f97d79848a013d4936a73bf4cc922c825d33c1cf7073dff6d409c6",
+];
+
 /// Holds developer keys
-pub(crate) struct DevSigner {
+///
+/// Signs `eth_sign` and `eth_sendTransaction` requests with unlocked keys, like the prefunded
+/// accounts of the dev chain or the accounts of a keystore directory. The keys are held in
+/// memory, so this is only meant for local development.
+#[derive(Clone)]
+pub struct DevSigner {
     addresses: Vec<Address>,
     accounts: HashMap<Address, SecretKey>,
 }
 
 impl DevSigner {
+    /// Creates a signer for the accounts of the given keys.
+    pub fn new(keys: impl IntoIterator<Item = SecretKey>) -> Self {
+        let mut addresses = Vec::new();
+        let mut accounts = HashMap::new();
+        for key in keys {
+            let address = key_to_address(&key);
+            if accounts.insert(address, key).is_none() {
+                addresses.push(address);
+            }
+        }
+        Self { addresses, accounts }
+    }
+
+    /// Creates a signer for the prefunded accounts of the [`DEV`](reth_primitives::DEV) chain.
+    pub fn dev_accounts() -> Self {
+        Self::new(
+            DEV_ACCOUNT_KEYS.iter().map(|key| SecretKey::from_str(key).expect("valid secret key")),
+        )
+    }
+
+    /// Creates a signer for the accounts of the keystore files in the given directory, which are
+    /// decrypted with the given password.
+    ///
+    /// The accounts are ordered by file name, which is the creation time for keystore files
+    /// created by geth or clef.
+    pub fn from_keystore_dir(dir: &Path, password: &str) -> Result<Self, KeystoreError> {
+        let io_err = |err| KeystoreError::Io(dir.to_path_buf(), err);
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(io_err)? {
+            let path = entry.map_err(io_err)?.path();
+            if path.is_file() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut keys = Vec::with_capacity(paths.len());
+        for path in paths {
+            let key = eth_keystore::decrypt_key(&path, password)
+                .map_err(|err| KeystoreError::Decrypt(path.clone(), err))?;
+            keys.push(SecretKey::from_slice(&key).map_err(|_| KeystoreError::InvalidKey(path))?);
+        }
+        Ok(Self::new(keys))
+    }
+
     fn get_key(&self, account: Address) -> Result<&SecretKey> {
         self.accounts.get(&account).ok_or(SignError::NoAccount)
     }
@@ -54,6 +125,13 @@ impl DevSigner {
         signature.map_err(|_| SignError::CouldNotSign)
     }
 }
+
+impl std::fmt::Debug for DevSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DevSigner").field("addresses", &self.addresses).finish_non_exhaustive()
+    }
+}
+
 #[async_trait::async_trait]
 impl EthSigner for DevSigner {
     fn accounts(&self) -> Vec<Address> {
@@ -85,10 +163,153 @@ impl EthSigner for DevSigner {
     }
 
     fn sign_typed_data(&self, address: Address, payload: &TypedData) -> Result<Signature> {
-        let encoded: H256 = payload.encode_eip712().map_err(|_| SignError::TypedData)?.into();
+        let encoded = payload.signing_hash().map_err(|_| SignError::TypedData)?;
         self.sign_hash(encoded, address)
     }
 }
+
+/// Holds the accounts created or imported over the `personal_` namespace.
+///
+/// The keys are held in memory and protected by a password: they sign `eth_` requests only while
+/// unlocked, and `personal_` requests with the right password. Like the [DevSigner], this is only
+/// meant for local development.
+///
+/// Clones share the accounts.
+#[derive(Clone, Default)]
+pub struct PersonalSigner {
+    inner: Arc<Mutex<PersonalAccounts>>,
+}
+
+#[derive(Default)]
+struct PersonalAccounts {
+    /// The accounts, in the order they were added.
+    addresses: Vec<Address>,
+    /// The keys of the accounts and the hashes of their passwords.
+    keys: HashMap<Address, (SecretKey, H256)>,
+    /// The unlocked accounts and when they're locked again, `None` if they stay unlocked.
+    unlocked: HashMap<Address, Option<Instant>>,
+}
+
+impl PersonalSigner {
+    /// Creates a new account with a random key, protected by the given password.
+    pub fn new_account(&self, password: &str) -> Address {
+        self.import_key(SecretKey::new(&mut rand::thread_rng()), password)
+    }
+
+    /// Adds the account of the key, protected by the given password.
+    ///
+    /// If the account already exists, its password is replaced and it's locked.
+    pub fn import_key(&self, key: SecretKey, password: &str) -> Address {
+        let address = key_to_address(&key);
+        let mut accounts = self.inner.lock();
+        if accounts.keys.insert(address, (key, keccak256(password))).is_none() {
+            accounts.addresses.push(address);
+        }
+        accounts.unlocked.remove(&address);
+        address
+    }
+
+    /// Unlocks the account for the given duration, or until it's locked if there's none.
+    pub fn unlock(
+        &self,
+        address: Address,
+        password: &str,
+        duration: Option<Duration>,
+    ) -> Result<()> {
+        let mut accounts = self.inner.lock();
+        accounts.key(address, password)?;
+        let locked_at = duration.map(|duration| Instant::now() + duration);
+        accounts.unlocked.insert(address, locked_at);
+        Ok(())
+    }
+
+    /// Locks the account, returns `false` if it doesn't exist.
+    pub fn lock(&self, address: Address) -> bool {
+        let mut accounts = self.inner.lock();
+        accounts.unlocked.remove(&address);
+        accounts.keys.contains_key(&address)
+    }
+
+    /// Returns a signer for the account if the password is right, whether it's unlocked or not.
+    pub(crate) fn signer_with_password(
+        &self,
+        address: Address,
+        password: &str,
+    ) -> Result<DevSigner> {
+        let key = self.inner.lock().key(address, password)?;
+        Ok(DevSigner::new([key]))
+    }
+
+    /// Returns a signer for the account if it's unlocked.
+    fn unlocked_signer(&self, address: Address) -> Result<DevSigner> {
+        let mut accounts = self.inner.lock();
+        let (key, _) = *accounts.keys.get(&address).ok_or(SignError::NoAccount)?;
+        match accounts.unlocked.get(&address) {
+            Some(None) => {}
+            Some(Some(locked_at)) if Instant::now() < *locked_at => {}
+            Some(Some(_)) => {
+                accounts.unlocked.remove(&address);
+                return Err(SignError::AccountLocked)
+            }
+            None => return Err(SignError::AccountLocked),
+        }
+        Ok(DevSigner::new([key]))
+    }
+}
+
+impl PersonalAccounts {
+    /// Returns the key of the account if the password is right.
+    fn key(&self, address: Address, password: &str) -> Result<SecretKey> {
+        let (key, password_hash) = self.keys.get(&address).ok_or(SignError::NoAccount)?;
+        if keccak256(password) != *password_hash {
+            return Err(SignError::IncorrectPassword)
+        }
+        Ok(*key)
+    }
+}
+
+impl std::fmt::Debug for PersonalSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersonalSigner")
+            .field("addresses", &self.inner.lock().addresses)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl EthSigner for PersonalSigner {
+    fn accounts(&self) -> Vec<Address> {
+        self.inner.lock().addresses.clone()
+    }
+
+    fn is_signer_for(&self, addr: &Address) -> bool {
+        self.inner.lock().keys.contains_key(addr)
+    }
+
+    async fn sign(&self, address: Address, message: &[u8]) -> Result<Signature> {
+        let signer = self.unlocked_signer(address)?;
+        signer.sign(address, message).await
+    }
+
+    fn sign_transaction(
+        &self,
+        request: TypedTransactionRequest,
+        address: &Address,
+    ) -> Result<TransactionSigned> {
+        self.unlocked_signer(*address)?.sign_transaction(request, address)
+    }
+
+    fn sign_typed_data(&self, address: Address, payload: &TypedData) -> Result<Signature> {
+        self.unlocked_signer(address)?.sign_typed_data(address, payload)
+    }
+}
+
+/// Returns the address of the account of the secret key.
+fn key_to_address(key: &SecretKey) -> Address {
+    let public = key.public_key(SECP256K1).serialize_uncompressed();
+    Address::from_slice(&keccak256(&public[1..])[12..])
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -190,6 +411,50 @@ mod test {
         assert_eq!(sig, expected)
     }
 
+    #[test]
+    fn dev_accounts_are_prefunded() {
+        let signer = DevSigner::dev_accounts();
+        assert_eq!(signer.accounts().len(), 10);
+        for address in signer.accounts() {
+            assert!(reth_primitives::DEV.genesis.alloc.contains_key(&address));
+        }
+    }
+
+    #[test]
+    fn keystore_accounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = SecretKey::new(&mut rand::thread_rng());
+        let mut rng = rand::thread_rng();
+        eth_keystore::encrypt_key(dir.path(), &mut rng, key.secret_bytes(), "password", None)
+            .unwrap();
+
+        let signer = DevSigner::from_keystore_dir(dir.path(), "password").unwrap();
+        assert_eq!(signer.accounts(), vec![key_to_address(&key)]);
+
+        let err = DevSigner::from_keystore_dir(dir.path(), "wrong").unwrap_err();
+        assert!(matches!(err, KeystoreError::Decrypt(..)));
+    }
+
+    #[tokio::test]
+    async fn personal_accounts_sign_while_unlocked() {
+        let signer = PersonalSigner::default();
+        let address = signer.new_account("password");
+        assert_eq!(signer.accounts(), vec![address]);
+        assert!(matches!(signer.sign(address, b"").await, Err(SignError::AccountLocked)));
+
+        assert!(matches!(signer.unlock(address, "wrong", None), Err(SignError::IncorrectPassword)));
+        signer.unlock(address, "password", None).unwrap();
+        assert!(signer.sign(address, b"").await.is_ok());
+
+        assert!(signer.lock(address));
+        assert!(matches!(signer.sign(address, b"").await, Err(SignError::AccountLocked)));
+        assert!(signer.signer_with_password(address, "password").is_ok());
+
+        // the account locks itself again once the duration passed
+        signer.unlock(address, "password", Some(Duration::ZERO)).unwrap();
+        assert!(matches!(signer.sign(address, b"").await, Err(SignError::AccountLocked)));
+    }
+
     #[tokio::test]
     async fn test_signer() {
         let message = b"Test message";
@@ -12,23 +12,37 @@
 //! Provides the implementation of all RPC interfaces.
 
 mod admin;
+mod blocking_pool;
 mod call_guard;
 mod debug;
 mod engine;
 pub mod eth;
 mod layers;
 mod net;
+mod otterscan;
+mod personal;
+mod reth;
 mod trace;
+mod trace_cache;
+mod txpool;
 mod web3;
 
 pub use admin::AdminApi;
+pub use blocking_pool::{
+    BlockingTaskPool, BlockingTaskPoolConfig, BlockingTaskPoolError, RejectionPolicy,
+};
 pub use call_guard::TracingCallGuard;
-pub use debug::DebugApi;
+pub use debug::{DebugApi, MAX_TRACE_CHAIN_BLOCKS};
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthApiSpec, EthFilter, EthPubSub, EthSubscriptionIdProvider};
 pub use layers::{AuthLayer, AuthValidator, Claims, JwtAuthValidator, JwtError, JwtSecret};
 pub use net::NetApi;
+pub use otterscan::OtterscanApi;
+pub use personal::PersonalApi;
+pub use reth::{RethApi, PRUNE_BATCH_SIZE, SYNC_EVENTS_CHANNEL_CAPACITY};
 pub use trace::TraceApi;
+pub use trace_cache::{TraceCache, TraceCacheConfig, TraceCacheKey, TraceSize};
+pub use txpool::TxPoolApi;
 pub use web3::Web3Api;
 
 pub(crate) mod result;
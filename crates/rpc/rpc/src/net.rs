@@ -1,6 +1,6 @@
 use crate::eth::EthApiSpec;
 use jsonrpsee::core::RpcResult as Result;
-use reth_network_api::PeersInfo;
+use reth_network_api::{NetworkInfo, PeersInfo};
 use reth_rpc_api::NetApiServer;
 use reth_rpc_types::PeerCount;
 
@@ -26,7 +26,7 @@ impl<Net, Eth> NetApi<Net, Eth> {
 /// Net rpc implementation
 impl<Net, Eth> NetApiServer for NetApi<Net, Eth>
 where
-    Net: PeersInfo + 'static,
+    Net: NetworkInfo + PeersInfo + 'static,
     Eth: EthApiSpec + 'static,
 {
     /// Handler for `net_version`
@@ -41,7 +41,7 @@ where
 
     /// Handler for `net_listening`
     fn is_listening(&self) -> Result<bool> {
-        Ok(true)
+        Ok(self.network.is_listening())
     }
 }
 
@@ -0,0 +1,715 @@
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult},
+        EthTransactions,
+    },
+    result::ToRpcResult,
+    TraceApi,
+};
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult as Result;
+use reth_consensus_common::calc::{block_reward, ommer_reward};
+use reth_primitives::{
+    constants::ETH_TO_WEI, Address, BlockId, BlockNumber, BlockNumberOrTag, Bytes, Chain, Header,
+    H256, U256, U64,
+};
+use reth_provider::{
+    AccountHistoryProvider, BlockProvider, EvmEnvProvider, LogIndexProvider, StateProvider,
+    StateProviderFactory,
+};
+use reth_revm::tracing::TracingInspectorConfig;
+use reth_rpc_api::{EthApiServer, OtterscanApiServer};
+use reth_rpc_types::{
+    trace::parity::{Action, CallType, TraceOutput, TraceResult, TransactionTrace},
+    Block, BlockDetails, BlockIssuance, BlockTransactions, ContractCreator, InternalOperation,
+    OperationType, OtsBlock, OtsBlockTransactions, OtsTransactionReceipt, TraceEntry, Transaction,
+    TransactionsWithReceipts,
+};
+use revm::primitives::{CfgEnv, SpecId};
+use revm_primitives::{ExecutionResult, ResultAndState};
+use std::{collections::HashSet, ops::RangeInclusive};
+
+/// The version of the Otterscan API that is implemented.
+const API_LEVEL: u64 = 8;
+
+/// The number of blocks that are looked up in the account history and the log index at once when
+/// searching the transactions of an address.
+const SEARCH_WINDOW: u64 = 100_000;
+
+/// The maximum number of transactions that can be requested per page.
+const MAX_PAGE_SIZE: usize = 100;
+
+/// The maximum number of blocks that are traced when searching a page of the transactions of an
+/// address.
+///
+/// Blocks that changed the account without a transaction involving the address, like the blocks
+/// paying the fees to it, are traced without adding to the page.
+const MAX_TRACED_BLOCKS: usize = 1_000;
+
+/// `ots` API implementation.
+///
+/// This type provides the functionality for handling `ots` related requests.
+///
+/// The transactions of an address are found in the blocks that changed the account or its storage
+/// and, if the logs are indexed, the blocks with logs emitted by the address. There is no index of
+/// the calls themselves, so transactions that only called the address without any of these
+/// effects aren't found, e.g. failed calls, calls to view functions or calls to contracts that
+/// only forward the call. Transactions sent by the address are always found, since they change its
+/// nonce.
+#[derive(Clone)]
+pub struct OtterscanApi<Client, Eth> {
+    /// The client that can interact with the chain.
+    client: Client,
+    /// Access to commonly used code of the `eth` namespace
+    eth_api: Eth,
+    /// The `trace` handler the transactions are traced with
+    trace_api: TraceApi<Client, Eth>,
+}
+
+// === impl OtterscanApi ===
+
+impl<Client, Eth> OtterscanApi<Client, Eth> {
+    /// Create a new instance of the [OtterscanApi]
+    pub fn new(client: Client, eth_api: Eth, trace_api: TraceApi<Client, Eth>) -> Self {
+        Self { client, eth_api, trace_api }
+    }
+}
+
+impl<Client, Eth> OtterscanApi<Client, Eth>
+where
+    Client: BlockProvider
+        + AccountHistoryProvider
+        + LogIndexProvider
+        + StateProviderFactory
+        + EvmEnvProvider
+        + 'static,
+    Eth: EthApiServer + EthTransactions + 'static,
+{
+    /// Returns the block without its transactions, with the ether it issued and the fees its
+    /// transactions paid.
+    async fn block_details(&self, mut block: Block) -> Result<BlockDetails> {
+        let block_hash = block.header.hash.ok_or(EthApiError::UnknownBlockNumber)?;
+        let block_number = block.header.number.unwrap_or_default().saturating_to::<u64>();
+
+        let receipts = EthApiServer::block_receipts(&self.eth_api, block_hash.into())
+            .await?
+            .unwrap_or_default();
+        let total_fees = receipts.iter().fold(U256::ZERO, |total, receipt| {
+            total +
+                receipt.gas_used.unwrap_or_default() *
+                    U256::from(receipt.effective_gas_price.to::<u128>())
+        });
+
+        let (cfg, _, _) = self.eth_api.evm_env_at(block_hash.into()).await?;
+        let ommers = self.client.ommers(block_hash.into()).to_rpc_result()?.unwrap_or_default();
+        let issuance = block_issuance(&cfg, block_number, &ommers);
+
+        let transactions =
+            std::mem::replace(&mut block.transactions, BlockTransactions::Hashes(Vec::new()));
+        let transaction_count = match transactions {
+            BlockTransactions::Hashes(hashes) => hashes.len(),
+            BlockTransactions::Full(txs) => txs.len(),
+            BlockTransactions::Uncle => 0,
+        };
+
+        Ok(BlockDetails { block: OtsBlock { block, transaction_count }, issuance, total_fees })
+    }
+
+    /// Returns the blocks in the range that changed the account or its storage or have logs
+    /// emitted by the address, in ascending order.
+    fn blocks_with_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> EthResult<Vec<BlockNumber>> {
+        let mut blocks = self.client.blocks_with_account_changes(address, range.clone())?;
+        blocks.extend(self.client.blocks_with_storage_changes(address, range.clone())?);
+
+        if let Some(checkpoint) = self.client.log_index_checkpoint()? {
+            if *range.start() <= checkpoint {
+                let indexed = *range.start()..=checkpoint.min(*range.end());
+                blocks.extend(self.client.blocks_with_log_address(address, indexed)?);
+            }
+        }
+
+        blocks.sort_unstable();
+        blocks.dedup();
+        Ok(blocks)
+    }
+
+    /// Returns the transactions of the block that involve the address, with their receipts, in
+    /// the order of the block.
+    ///
+    /// A transaction involves the address if any of its calls, contract creations or
+    /// self-destructs was made by or to the address.
+    async fn block_transactions_with_address(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+    ) -> Result<Vec<(Transaction, OtsTransactionReceipt)>> {
+        let Some(block_hash) = self.client.block_hash(block_number).to_rpc_result()? else {
+            return Ok(Vec::new())
+        };
+        let Some(traces) = self.trace_api.trace_block(block_hash.into()).await? else {
+            return Ok(Vec::new())
+        };
+
+        let hashes = traces
+            .into_iter()
+            .filter(|trace| involves_address(&trace.trace, address))
+            .filter_map(|trace| trace.transaction_hash)
+            .collect::<HashSet<_>>();
+        if hashes.is_empty() {
+            return Ok(Vec::new())
+        }
+
+        let Some(block) = EthApiServer::block_by_hash(&self.eth_api, block_hash, true).await?
+        else {
+            return Ok(Vec::new())
+        };
+        let receipts = EthApiServer::block_receipts(&self.eth_api, block_hash.into())
+            .await?
+            .unwrap_or_default();
+        let timestamp = U64::from(block.header.timestamp.saturating_to::<u64>());
+
+        let BlockTransactions::Full(transactions) = block.inner.transactions else {
+            return Ok(Vec::new())
+        };
+        Ok(transactions
+            .into_iter()
+            .zip(receipts)
+            .filter(|(tx, _)| hashes.contains(&tx.hash))
+            .map(|(tx, receipt)| (tx, OtsTransactionReceipt { receipt, timestamp }))
+            .collect())
+    }
+
+    /// Walks the blocks of the range in the given direction and collects the transactions that
+    /// involve the address, until there are at least `page_size` transactions.
+    ///
+    /// Returns an error if the page size exceeds [MAX_PAGE_SIZE] or more than [MAX_TRACED_BLOCKS]
+    /// blocks would have to be traced for the page.
+    ///
+    /// The transactions are returned in the order they were found, together with whether there
+    /// are more transactions in the rest of the range.
+    async fn search_transactions(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+        descending: bool,
+        page_size: usize,
+    ) -> Result<(Vec<(Transaction, OtsTransactionReceipt)>, bool)> {
+        if page_size > MAX_PAGE_SIZE {
+            return Err(EthApiError::InvalidParams(format!(
+                "page size {page_size} exceeds the maximum of {MAX_PAGE_SIZE}"
+            ))
+            .into())
+        }
+
+        let mut found = Vec::new();
+        let mut traced = 0;
+        if range.is_empty() {
+            return Ok((found, false))
+        }
+
+        let (first, last) = range.into_inner();
+        let mut next = Some(if descending { last } else { first });
+        while let Some(from) = next {
+            let window = if descending {
+                let start = from.saturating_sub(SEARCH_WINDOW - 1).max(first);
+                next = (start > first).then(|| start - 1);
+                start..=from
+            } else {
+                let end = from.saturating_add(SEARCH_WINDOW - 1).min(last);
+                next = (end < last).then(|| end + 1);
+                from..=end
+            };
+
+            let mut blocks = self.blocks_with_address(address, window)?;
+            if descending {
+                blocks.reverse();
+            }
+            for block_number in blocks {
+                if found.len() >= page_size {
+                    return Ok((found, true))
+                }
+                if traced >= MAX_TRACED_BLOCKS {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "search exceeds the maximum of {MAX_TRACED_BLOCKS} traced blocks"
+                    ))
+                    .into())
+                }
+                traced += 1;
+                let mut txs = self.block_transactions_with_address(address, block_number).await?;
+                if descending {
+                    txs.reverse();
+                }
+                found.extend(txs);
+            }
+        }
+
+        Ok((found, false))
+    }
+
+    /// Returns whether the address has code after the execution of the given block.
+    fn has_code_at(&self, address: Address, block_number: BlockNumber) -> EthResult<bool> {
+        let state = self.eth_api.state_at(block_number.into())?;
+        Ok(state.account_code(address)?.is_some())
+    }
+}
+
+#[async_trait]
+impl<Client, Eth> OtterscanApiServer for OtterscanApi<Client, Eth>
+where
+    Client: BlockProvider
+        + AccountHistoryProvider
+        + LogIndexProvider
+        + StateProviderFactory
+        + EvmEnvProvider
+        + 'static,
+    Eth: EthApiServer + EthTransactions + 'static,
+{
+    /// Handler for `ots_hasCode`
+    async fn has_code(&self, address: Address, block_id: Option<BlockId>) -> Result<bool> {
+        let code = EthApiServer::get_code(&self.eth_api, address, block_id).await?;
+        Ok(!code.is_empty())
+    }
+
+    /// Handler for `ots_getApiLevel`
+    async fn get_api_level(&self) -> Result<u64> {
+        Ok(API_LEVEL)
+    }
+
+    /// Handler for `ots_getInternalOperations`
+    async fn get_internal_operations(&self, tx_hash: H256) -> Result<Vec<InternalOperation>> {
+        let traces = self
+            .trace_api
+            .trace_transaction(tx_hash)
+            .await?
+            .ok_or(EthApiError::TransactionNotFound)?;
+        Ok(traces.into_iter().filter_map(|trace| internal_operation(trace.trace)).collect())
+    }
+
+    /// Handler for `ots_getTransactionError`
+    async fn get_transaction_error(&self, tx_hash: H256) -> Result<Bytes> {
+        let output = self
+            .eth_api
+            .trace_transaction(
+                tx_hash,
+                TracingInspectorConfig::default_parity(),
+                |_, _, ResultAndState { result, .. }| match result {
+                    ExecutionResult::Revert { output, .. } => Ok(output.into()),
+                    _ => Ok(Bytes::default()),
+                },
+            )
+            .await?
+            .ok_or(EthApiError::TransactionNotFound)?;
+        Ok(output)
+    }
+
+    /// Handler for `ots_traceTransaction`
+    async fn trace_transaction(&self, tx_hash: H256) -> Result<Vec<TraceEntry>> {
+        let traces = self
+            .trace_api
+            .trace_transaction(tx_hash)
+            .await?
+            .ok_or(EthApiError::TransactionNotFound)?;
+        Ok(traces.into_iter().map(|trace| trace_entry(trace.trace)).collect())
+    }
+
+    /// Handler for `ots_getBlockDetails`
+    async fn get_block_details(
+        &self,
+        block_number: BlockNumberOrTag,
+    ) -> Result<Option<BlockDetails>> {
+        let Some(block) = EthApiServer::block_by_number(&self.eth_api, block_number, false).await?
+        else {
+            return Ok(None)
+        };
+        Ok(Some(self.block_details(block.inner).await?))
+    }
+
+    /// Handler for `ots_getBlockDetailsByHash`
+    async fn get_block_details_by_hash(&self, block_hash: H256) -> Result<Option<BlockDetails>> {
+        let Some(block) = EthApiServer::block_by_hash(&self.eth_api, block_hash, false).await?
+        else {
+            return Ok(None)
+        };
+        Ok(Some(self.block_details(block.inner).await?))
+    }
+
+    /// Handler for `ots_getBlockTransactions`
+    ///
+    /// Like erigon, pages are counted from the end of the block and the input of the
+    /// transactions is cut to the function selector.
+    async fn get_block_transactions(
+        &self,
+        block_number: BlockNumberOrTag,
+        page_number: usize,
+        page_size: usize,
+    ) -> Result<OtsBlockTransactions> {
+        if page_size > MAX_PAGE_SIZE {
+            return Err(EthApiError::InvalidParams(format!(
+                "page size {page_size} exceeds the maximum of {MAX_PAGE_SIZE}"
+            ))
+            .into())
+        }
+
+        let mut block = EthApiServer::block_by_number(&self.eth_api, block_number, true)
+            .await?
+            .ok_or(EthApiError::UnknownBlockNumber)?
+            .inner;
+        let block_hash = block.header.hash.ok_or(EthApiError::UnknownBlockNumber)?;
+        let mut receipts = EthApiServer::block_receipts(&self.eth_api, block_hash.into())
+            .await?
+            .unwrap_or_default();
+
+        let BlockTransactions::Full(transactions) = &mut block.transactions else {
+            return Err(EthApiError::UnknownBlockNumber.into())
+        };
+        let transaction_count = transactions.len();
+        let page_end = transaction_count.saturating_sub(page_number.saturating_mul(page_size));
+        let page_start = page_end.saturating_sub(page_size);
+
+        transactions.truncate(page_end);
+        transactions.drain(..page_start);
+        for tx in transactions.iter_mut() {
+            tx.input.0.truncate(4);
+        }
+        receipts.truncate(page_end);
+        receipts.drain(..page_start.min(receipts.len()));
+        for receipt in receipts.iter_mut() {
+            receipt.logs.clear();
+        }
+
+        Ok(OtsBlockTransactions { fullblock: OtsBlock { block, transaction_count }, receipts })
+    }
+
+    /// Handler for `ots_searchTransactionsBefore`
+    async fn search_transactions_before(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> Result<TransactionsWithReceipts> {
+        let best_number = self.client.best_block_number().to_rpc_result()?;
+        let last =
+            if block_number == 0 { best_number } else { (block_number - 1).min(best_number) };
+
+        let (found, has_more) =
+            self.search_transactions(address, 0..=last, true, page_size).await?;
+        let (txs, receipts) = found.into_iter().unzip();
+
+        Ok(TransactionsWithReceipts {
+            txs,
+            receipts,
+            first_page: block_number == 0,
+            last_page: !has_more,
+        })
+    }
+
+    /// Handler for `ots_searchTransactionsAfter`
+    async fn search_transactions_after(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> Result<TransactionsWithReceipts> {
+        let best_number = self.client.best_block_number().to_rpc_result()?;
+        let first = if block_number == 0 { 0 } else { block_number.saturating_add(1) };
+
+        let (mut found, has_more) =
+            self.search_transactions(address, first..=best_number, false, page_size).await?;
+        // the most recent transactions come first
+        found.reverse();
+        let (txs, receipts) = found.into_iter().unzip();
+
+        Ok(TransactionsWithReceipts {
+            txs,
+            receipts,
+            first_page: !has_more,
+            last_page: block_number == 0,
+        })
+    }
+
+    /// Handler for `ots_getTransactionBySenderAndNonce`
+    async fn get_transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: U64,
+    ) -> Result<Option<H256>> {
+        let tx =
+            EthApiServer::transaction_by_sender_and_nonce(&self.eth_api, sender, nonce).await?;
+        Ok(tx.map(|tx| tx.hash))
+    }
+
+    /// Handler for `ots_getContractCreator`
+    ///
+    /// The contract was created in the first block after which the address has code, which is
+    /// found by a binary search over the account history. The block is traced to find the
+    /// creation.
+    async fn get_contract_creator(&self, address: Address) -> Result<Option<ContractCreator>> {
+        let best_number = self.client.best_block_number().to_rpc_result()?;
+        if !self.has_code_at(address, best_number)? {
+            return Ok(None)
+        }
+
+        let mut low = 0;
+        let mut high = best_number;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.has_code_at(address, mid)? {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        let Some(block_hash) = self.client.block_hash(low).to_rpc_result()? else {
+            return Ok(None)
+        };
+        let Some(traces) = self.trace_api.trace_block(block_hash.into()).await? else {
+            return Ok(None)
+        };
+        let creator = traces.into_iter().find_map(|trace| {
+            let Action::Create(create) = &trace.trace.action else { return None };
+            match &trace.trace.result {
+                Some(TraceResult::Success { result: TraceOutput::Create(output) })
+                    if output.address == address =>
+                {
+                    Some(ContractCreator { hash: trace.transaction_hash?, creator: create.from })
+                }
+                _ => None,
+            }
+        });
+
+        Ok(creator)
+    }
+}
+
+impl<Client, Eth> std::fmt::Debug for OtterscanApi<Client, Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OtterscanApi").finish_non_exhaustive()
+    }
+}
+
+/// Returns the ether issued by a block executed with the given settings.
+///
+/// Goerli and post-merge blocks don't issue ether.
+fn block_issuance(cfg: &CfgEnv, block_number: BlockNumber, ommers: &[Header]) -> BlockIssuance {
+    let base_reward =
+        if cfg.chain_id == U256::from(Chain::goerli().id()) || cfg.spec_id >= SpecId::MERGE {
+            return BlockIssuance::default()
+        } else if cfg.spec_id >= SpecId::PETERSBURG {
+            ETH_TO_WEI * 2
+        } else if cfg.spec_id >= SpecId::BYZANTIUM {
+            ETH_TO_WEI * 3
+        } else {
+            ETH_TO_WEI * 5
+        };
+
+    let block_reward = block_reward(base_reward, ommers.len());
+    let uncle_reward = ommers.iter().fold(U256::ZERO, |total, ommer| {
+        total + ommer_reward(base_reward, block_number, ommer.number)
+    });
+    BlockIssuance { block_reward, uncle_reward, issuance: block_reward + uncle_reward }
+}
+
+/// Returns whether the call, contract creation or self-destruct of the trace was made by or to
+/// the address.
+fn involves_address(trace: &TransactionTrace, address: Address) -> bool {
+    match &trace.action {
+        Action::Call(call) => call.from == address || call.to == address,
+        Action::Create(create) => {
+            create.from == address ||
+                matches!(
+                    &trace.result,
+                    Some(TraceResult::Success { result: TraceOutput::Create(output) })
+                        if output.address == address
+                )
+        }
+        Action::Selfdestruct(selfdestruct) => {
+            selfdestruct.address == address || selfdestruct.refund_address == address
+        }
+        Action::Reward(reward) => reward.author == address,
+    }
+}
+
+/// Returns the transfer of ether of the trace, if it isn't the transaction's own call and didn't
+/// fail.
+///
+/// Contract creations with `CREATE2` can't be told apart from the traces and are reported as
+/// [OperationType::Create].
+fn internal_operation(trace: TransactionTrace) -> Option<InternalOperation> {
+    if trace.trace_address.is_empty() || matches!(trace.result, Some(TraceResult::Error { .. })) {
+        return None
+    }
+
+    match trace.action {
+        Action::Call(call) if call.call_type == CallType::Call && call.value > U256::ZERO => {
+            Some(InternalOperation {
+                r#type: OperationType::Transfer,
+                from: call.from,
+                to: call.to,
+                value: call.value,
+            })
+        }
+        Action::Create(create) => {
+            let Some(TraceResult::Success { result: TraceOutput::Create(output) }) = trace.result
+            else {
+                return None
+            };
+            Some(InternalOperation {
+                r#type: OperationType::Create,
+                from: create.from,
+                to: output.address,
+                value: create.value,
+            })
+        }
+        Action::Selfdestruct(selfdestruct) => Some(InternalOperation {
+            r#type: OperationType::SelfDestruct,
+            from: selfdestruct.address,
+            to: selfdestruct.refund_address,
+            value: selfdestruct.balance,
+        }),
+        _ => None,
+    }
+}
+
+/// Converts the trace into an entry of the call tree returned by `ots_traceTransaction`.
+fn trace_entry(trace: TransactionTrace) -> TraceEntry {
+    let depth = trace.trace_address.len() as u32;
+    match trace.action {
+        Action::Call(call) => {
+            let (r#type, value) = match call.call_type {
+                CallType::DelegateCall => ("DELEGATECALL", None),
+                CallType::StaticCall => ("STATICCALL", None),
+                CallType::CallCode => ("CALLCODE", Some(call.value)),
+                CallType::Call | CallType::None => ("CALL", Some(call.value)),
+            };
+            TraceEntry {
+                r#type: r#type.to_string(),
+                depth,
+                from: call.from,
+                to: call.to,
+                value,
+                input: call.input,
+            }
+        }
+        Action::Create(create) => {
+            let to = match trace.result {
+                Some(TraceResult::Success { result: TraceOutput::Create(output) }) => {
+                    output.address
+                }
+                _ => Address::zero(),
+            };
+            TraceEntry {
+                r#type: "CREATE".to_string(),
+                depth,
+                from: create.from,
+                to,
+                value: Some(create.value),
+                input: create.init,
+            }
+        }
+        Action::Selfdestruct(selfdestruct) => TraceEntry {
+            r#type: "SELFDESTRUCT".to_string(),
+            depth,
+            from: selfdestruct.address,
+            to: selfdestruct.refund_address,
+            value: Some(selfdestruct.balance),
+            input: Bytes::default(),
+        },
+        Action::Reward(reward) => TraceEntry {
+            r#type: "REWARD".to_string(),
+            depth,
+            from: Address::zero(),
+            to: reward.author,
+            value: Some(reward.value),
+            input: Bytes::default(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_rpc_types::trace::parity::{CallAction, CreateAction, CreateOutput};
+
+    fn call(trace_address: Vec<usize>, call_type: CallType, value: u64) -> TransactionTrace {
+        TransactionTrace {
+            trace_address,
+            subtraces: 0,
+            action: Action::Call(CallAction {
+                from: Address::from_low_u64_be(1),
+                to: Address::from_low_u64_be(2),
+                value: U256::from(value),
+                gas: Default::default(),
+                input: Default::default(),
+                call_type,
+            }),
+            result: None,
+        }
+    }
+
+    #[test]
+    fn internal_operations_of_traces() {
+        // the transaction's own call is visible in the transaction
+        assert_eq!(internal_operation(call(vec![], CallType::Call, 1)), None);
+        assert_eq!(internal_operation(call(vec![0], CallType::Call, 0)), None);
+        assert_eq!(internal_operation(call(vec![0], CallType::DelegateCall, 1)), None);
+        assert_eq!(
+            internal_operation(call(vec![0], CallType::Call, 1)),
+            Some(InternalOperation {
+                r#type: OperationType::Transfer,
+                from: Address::from_low_u64_be(1),
+                to: Address::from_low_u64_be(2),
+                value: U256::from(1),
+            })
+        );
+
+        let mut create = TransactionTrace {
+            trace_address: vec![1],
+            subtraces: 0,
+            action: Action::Create(CreateAction {
+                from: Address::from_low_u64_be(1),
+                value: U256::from(5),
+                gas: Default::default(),
+                init: Default::default(),
+            }),
+            result: Some(TraceResult::Error { error: "Reverted".to_string() }),
+        };
+        assert_eq!(internal_operation(create.clone()), None);
+
+        create.result = Some(TraceResult::Success {
+            result: TraceOutput::Create(CreateOutput {
+                gas_used: Default::default(),
+                code: Default::default(),
+                address: Address::from_low_u64_be(3),
+            }),
+        });
+        assert_eq!(
+            internal_operation(create),
+            Some(InternalOperation {
+                r#type: OperationType::Create,
+                from: Address::from_low_u64_be(1),
+                to: Address::from_low_u64_be(3),
+                value: U256::from(5),
+            })
+        );
+    }
+
+    #[test]
+    fn issuance_before_and_after_the_merge() {
+        let mut cfg =
+            CfgEnv { chain_id: U256::from(1), spec_id: SpecId::BYZANTIUM, ..Default::default() };
+        let ommer = Header { number: 9, ..Default::default() };
+        let issuance = block_issuance(&cfg, 10, &[ommer]);
+        // 3 ETH and 1/32 of it for the ommer, the ommer gets 7/8 of 3 ETH
+        assert_eq!(issuance.block_reward, U256::from(ETH_TO_WEI * 3 + ((ETH_TO_WEI * 3) >> 5)));
+        assert_eq!(issuance.uncle_reward, U256::from(ETH_TO_WEI * 3 * 7 / 8));
+        assert_eq!(issuance.issuance, issuance.block_reward + issuance.uncle_reward);
+
+        cfg.spec_id = SpecId::MERGE;
+        assert_eq!(block_issuance(&cfg, 10, &[]), BlockIssuance::default());
+    }
+}
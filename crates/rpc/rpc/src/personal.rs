@@ -0,0 +1,150 @@
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult, SignError},
+        signer::EthSigner,
+        PersonalSigner,
+    },
+    EthApi, EthApiSpec,
+};
+use async_trait::async_trait;
+use ethers_core::utils::hash_message;
+use jsonrpsee::core::RpcResult as Result;
+use reth_network_api::NetworkInfo;
+use reth_primitives::{Address, Bytes, Signature, H256, U256};
+use reth_provider::{BlockProvider, EvmEnvProvider, StageCheckpointProvider, StateProviderFactory};
+use reth_rpc_api::PersonalApiServer;
+use reth_rpc_types::TransactionRequest;
+use reth_transaction_pool::TransactionPool;
+use secp256k1::SecretKey;
+use std::{sync::Arc, time::Duration};
+
+/// The duration accounts are unlocked for if `personal_unlockAccount` doesn't specify one.
+const DEFAULT_UNLOCK_DURATION: Duration = Duration::from_secs(300);
+
+/// `personal` API implementation.
+///
+/// This type provides the functionality for handling `personal` related requests, it manages the
+/// accounts of a [PersonalSigner] that's shared with the `eth_` namespace.
+///
+/// The accounts of the other signers of the `eth_` namespace, like the dev accounts, are always
+/// unlocked and sign without a password.
+pub struct PersonalApi<Client, Pool, Network> {
+    /// The `eth_` handler transactions are sent with
+    eth: EthApi<Client, Pool, Network>,
+    /// The accounts managed by this namespace
+    signer: PersonalSigner,
+}
+
+// === impl PersonalApi ===
+
+impl<Client, Pool, Network> PersonalApi<Client, Pool, Network> {
+    /// Creates a new instance of `PersonalApi`.
+    ///
+    /// The signer should be registered with the [EthApi], see [EthApi::add_personal_signer].
+    pub fn new(eth: EthApi<Client, Pool, Network>, signer: PersonalSigner) -> Self {
+        Self { eth, signer }
+    }
+
+    /// Returns the signer for the account, using the password if it's one of the accounts of this
+    /// namespace.
+    fn signer(&self, address: Address, password: &str) -> EthResult<Arc<dyn EthSigner>> {
+        if self.signer.is_signer_for(&address) {
+            return Ok(Arc::new(self.signer.signer_with_password(address, password)?))
+        }
+        Ok(self.eth.find_signer(&address)?)
+    }
+}
+
+#[async_trait]
+impl<Client, Pool, Network> PersonalApiServer for PersonalApi<Client, Pool, Network>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Client:
+        BlockProvider + StageCheckpointProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+{
+    /// Handler for `personal_listAccounts`
+    async fn list_accounts(&self) -> Result<Vec<Address>> {
+        Ok(self.eth.accounts())
+    }
+
+    /// Handler for `personal_newAccount`
+    async fn new_account(&self, password: String) -> Result<Address> {
+        Ok(self.signer.new_account(&password))
+    }
+
+    /// Handler for `personal_importRawKey`
+    async fn import_raw_key(&self, key: String, password: String) -> Result<Address> {
+        let key = hex::decode(key.trim_start_matches("0x"))
+            .ok()
+            .and_then(|key| SecretKey::from_slice(&key).ok())
+            .ok_or_else(|| EthApiError::InvalidParams("invalid secret key".to_string()))?;
+        Ok(self.signer.import_key(key, &password))
+    }
+
+    /// Handler for `personal_unlockAccount`
+    async fn unlock_account(
+        &self,
+        address: Address,
+        password: String,
+        duration: Option<u64>,
+    ) -> Result<bool> {
+        if !self.signer.is_signer_for(&address) {
+            // the accounts of the other signers are always unlocked
+            self.eth.find_signer(&address).map_err(EthApiError::from)?;
+            return Ok(true)
+        }
+        let duration = match duration {
+            None => Some(DEFAULT_UNLOCK_DURATION),
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+        };
+        self.signer.unlock(address, &password, duration).map_err(EthApiError::from)?;
+        Ok(true)
+    }
+
+    /// Handler for `personal_lockAccount`
+    async fn lock_account(&self, address: Address) -> Result<bool> {
+        Ok(self.signer.lock(address))
+    }
+
+    /// Handler for `personal_sendTransaction`
+    async fn send_transaction(
+        &self,
+        request: TransactionRequest,
+        password: String,
+    ) -> Result<H256> {
+        let from = request.from.ok_or(EthApiError::from(SignError::NoAccount))?;
+        let signer = self.signer(from, &password)?;
+        Ok(self.eth.send_transaction_with_signer(request, Some(&*signer)).await?)
+    }
+
+    /// Handler for `personal_sign`
+    async fn sign(&self, message: Bytes, address: Address, password: String) -> Result<Bytes> {
+        let signer = self.signer(address, &password)?;
+        let signature = signer.sign(address, &message).await.map_err(EthApiError::from)?;
+        Ok(signature.to_bytes().to_vec().into())
+    }
+
+    /// Handler for `personal_ecRecover`
+    async fn ec_recover(&self, message: Bytes, signature: Bytes) -> Result<Address> {
+        let invalid_signature = || EthApiError::InvalidParams("invalid signature".to_string());
+        // like geth, only the `v` values of `personal_sign` signatures are accepted
+        if signature.len() != 65 || !matches!(signature[64], 27 | 28) {
+            return Err(invalid_signature().into())
+        }
+        let signature = Signature {
+            r: U256::try_from_be_slice(&signature[..32]).ok_or_else(invalid_signature)?,
+            s: U256::try_from_be_slice(&signature[32..64]).ok_or_else(invalid_signature)?,
+            odd_y_parity: signature[64] == 28,
+        };
+        let hash = H256::from(hash_message(&message[..]).0);
+        Ok(signature.recover_signer(hash).ok_or_else(invalid_signature)?)
+    }
+}
+
+impl<Client, Pool, Network> std::fmt::Debug for PersonalApi<Client, Pool, Network> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersonalApi").field("signer", &self.signer).finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{
+    eth::error::EthResult,
+    result::{invalid_params_rpc_err, ToRpcResult},
+    BlockingTaskPoolError,
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use jsonrpsee::{core::RpcResult, types::SubscriptionResult, SubscriptionSink};
+use reth_primitives::{Address, BlockId, BlockNumber, PruneCheckpoint, PruneSegment, U64};
+use reth_provider::{
+    AccountHistoryProvider, BlockProvider, PruneProvider, StateProvider, StateProviderFactory,
+};
+use reth_rpc_api::{RethAdminApiServer, RethApiServer};
+use reth_rpc_types::{BalanceChange, PruneRun, PruneSegmentStatus, PruneStatus, SyncEvent};
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::{broadcast, Mutex};
+use tokio_stream::wrappers::BroadcastStream;
+
+/// The capacity of the channel [SyncEvent]s are broadcast on.
+pub const SYNC_EVENTS_CHANNEL_CAPACITY: usize = 1024;
+
+/// The maximum number of entries `reth_prune` deletes per database transaction.
+pub const PRUNE_BATCH_SIZE: usize = 10_000;
+
+/// `reth` API implementation.
+///
+/// This type provides the functionality for handling `reth` related requests.
+pub struct RethApi<Client> {
+    inner: Arc<RethApiInner<Client>>,
+}
+
+impl<Client> RethApi<Client> {
+    /// Creates a new instance of `RethApi`.
+    ///
+    /// Only prune runs are reported to `reth_subscribeSyncEvents` subscribers.
+    pub fn new(client: Client) -> Self {
+        let (sync_events, _) = broadcast::channel(SYNC_EVENTS_CHANNEL_CAPACITY);
+        Self::with_sync_events(client, sync_events)
+    }
+
+    /// Creates a new instance of `RethApi` that streams the [SyncEvent]s sent on the given channel
+    /// to `reth_subscribeSyncEvents` subscribers.
+    pub fn with_sync_events(client: Client, sync_events: broadcast::Sender<SyncEvent>) -> Self {
+        let inner = RethApiInner { client, prune_lock: Default::default(), sync_events };
+        Self { inner: Arc::new(inner) }
+    }
+}
+
+impl<Client> RethApi<Client>
+where
+    Client: BlockProvider + PruneProvider + 'static,
+{
+    /// Returns the lowest available block of all prunable segments and the last prune run.
+    pub fn prune_status(&self) -> reth_interfaces::Result<PruneStatus> {
+        let mut segments = Vec::with_capacity(PruneSegment::ALL.len());
+        let mut last_run: Option<PruneRun> = None;
+        for segment in PruneSegment::ALL {
+            let lowest_available_block =
+                self.inner.client.lowest_available_block(segment)?.map(U64::from);
+            segments.push(PruneSegmentStatus { segment, lowest_available_block });
+
+            if let Some(checkpoint) = self.inner.client.prune_checkpoint(segment)? {
+                let run = prune_run(segment, checkpoint);
+                if last_run.as_ref().map_or(true, |last| last.timestamp <= run.timestamp) {
+                    last_run = Some(run);
+                }
+            }
+        }
+        Ok(PruneStatus { segments, last_run })
+    }
+
+    /// Prunes all data of the segment below `to_block` on a blocking task.
+    ///
+    /// The data is deleted in batches of [PRUNE_BATCH_SIZE] entries and the progress of the run
+    /// is persisted with every batch.
+    pub async fn prune(&self, segment: PruneSegment, to_block: BlockNumber) -> EthResult<PruneRun> {
+        // hold the lock for the entire run so concurrent prune requests are serialized
+        let _lock = self.inner.prune_lock.lock().await;
+        let inner = Arc::clone(&self.inner);
+        let checkpoint = tokio::task::spawn_blocking(move || {
+            inner.client.prune(segment, to_block, PRUNE_BATCH_SIZE)
+        })
+        .await
+        .map_err(|_| BlockingTaskPoolError::Panicked)??;
+        let run = prune_run(segment, checkpoint);
+        // there may be no subscribers
+        let _ = self.inner.sync_events.send(SyncEvent::PruneFinished(run.clone()));
+        Ok(run)
+    }
+}
+
+impl<Client> RethApi<Client>
+where
+    Client: BlockProvider + AccountHistoryProvider + StateProviderFactory + 'static,
+{
+    /// Returns the balance changes of all accounts whose balance was changed by the block.
+    ///
+    /// The balances before the block are taken from the block's account changeset, so this is
+    /// much cheaper than tracing the transactions of the block.
+    pub fn balance_changes_in_block(
+        &self,
+        block_number: BlockNumber,
+    ) -> reth_interfaces::Result<BTreeMap<Address, BalanceChange>> {
+        let changeset = self.inner.client.account_block_changeset(block_number)?;
+        if changeset.is_empty() {
+            return Ok(BTreeMap::new())
+        }
+
+        let state = self.inner.client.history_by_block_number(block_number)?;
+        let mut changes = BTreeMap::new();
+        for (address, info) in changeset {
+            let before = info.map(|account| account.balance).unwrap_or_default();
+            let after = state.account_balance(address)?.unwrap_or_default();
+            // accounts that were only touched, e.g. by a nonce increment, are skipped
+            if before != after {
+                changes.insert(address, BalanceChange { before, after });
+            }
+        }
+        Ok(changes)
+    }
+}
+
+#[async_trait]
+impl<Client> RethApiServer for RethApi<Client>
+where
+    Client: BlockProvider + PruneProvider + AccountHistoryProvider + StateProviderFactory + 'static,
+{
+    /// Handler for `reth_pruneStatus`
+    async fn prune_status(&self) -> RpcResult<PruneStatus> {
+        RethApi::prune_status(self).to_rpc_result()
+    }
+
+    /// Handler for `reth_getBalanceChangesInBlock`
+    async fn balance_changes_in_block(
+        &self,
+        block_id: BlockId,
+    ) -> RpcResult<BTreeMap<Address, BalanceChange>> {
+        let client = &self.inner.client;
+        let tip = client.best_block_number().to_rpc_result()?;
+        let block_number = client
+            .block_number_for_id(block_id)
+            .to_rpc_result()?
+            .filter(|block_number| *block_number <= tip)
+            .ok_or_else(|| invalid_params_rpc_err("unknown block"))?;
+        RethApi::balance_changes_in_block(self, block_number).to_rpc_result()
+    }
+
+    /// Handler for `reth_subscribeSyncEvents`
+    fn subscribe_sync_events(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+        sink.accept()?;
+
+        // events missed by a lagging subscriber are skipped
+        let stream = BroadcastStream::new(self.inner.sync_events.subscribe())
+            .filter_map(|event| futures::future::ready(event.ok()));
+        tokio::spawn(async move {
+            sink.pipe_from_stream(stream).await;
+        });
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Client> RethAdminApiServer for RethApi<Client>
+where
+    Client: BlockProvider + PruneProvider + 'static,
+{
+    /// Handler for `reth_prune`
+    async fn prune(&self, segment: PruneSegment, to_block: BlockNumber) -> RpcResult<PruneRun> {
+        // never prune the data required to serve the tip
+        let tip = self.inner.client.best_block_number().to_rpc_result()?;
+        if to_block > tip {
+            return Err(invalid_params_rpc_err(format!("cannot prune beyond the current tip {tip}")))
+        }
+        Ok(RethApi::prune(self, segment, to_block).await?)
+    }
+}
+
+impl<Client> Clone for RethApi<Client> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<Client> std::fmt::Debug for RethApi<Client> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RethApi").finish_non_exhaustive()
+    }
+}
+
+/// Converts the checkpoint of a prune run of the segment to its rpc type.
+fn prune_run(segment: PruneSegment, checkpoint: PruneCheckpoint) -> PruneRun {
+    PruneRun {
+        segment,
+        to_block: U64::from(checkpoint.to_block),
+        pruned: U64::from(checkpoint.pruned),
+        timestamp: U64::from(checkpoint.timestamp),
+        finished: checkpoint.finished,
+    }
+}
+
+/// Container type for `RethApi`
+struct RethApiInner<Client> {
+    /// The client that can interact with the database
+    client: Client,
+    /// Serializes the runs triggered via `reth_prune`
+    prune_lock: Mutex<()>,
+    /// The channel pipeline and prune events are broadcast on
+    sync_events: broadcast::Sender<SyncEvent>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{
+        database::Database, mdbx::test_utils::create_test_rw_db, models::StoredBlockBodyIndices,
+        tables, transaction::DbTxMut,
+    };
+    use reth_primitives::{ChainSpecBuilder, Receipt};
+    use reth_provider::{test_utils::NoopProvider, ShareableDatabase};
+
+    #[tokio::test]
+    async fn reports_persisted_prune_run() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().build());
+        let db = create_test_rw_db();
+        db.update(|tx| {
+            for tx_num in 0..3 {
+                tx.put::<tables::Receipts>(tx_num, Receipt::default()).unwrap();
+            }
+            tx.put::<tables::TransactionBlock>(1, 0).unwrap();
+            tx.put::<tables::TransactionBlock>(2, 1).unwrap();
+            tx.put::<tables::BlockBodyIndices>(
+                1,
+                StoredBlockBodyIndices { first_tx_num: 2, tx_count: 1 },
+            )
+            .unwrap();
+        })
+        .unwrap();
+
+        let api = RethApi::new(ShareableDatabase::new(db.clone(), chain_spec.clone()));
+        assert_eq!(api.prune_status().unwrap().last_run, None);
+
+        let run = api.prune(PruneSegment::Receipts, 1).await.unwrap();
+        assert_eq!((run.pruned, run.finished), (U64::from(2), true));
+
+        // the run is read back from the database
+        let api = RethApi::new(ShareableDatabase::new(db, chain_spec));
+        let status = api.prune_status().unwrap();
+        assert_eq!(status.segments.len(), PruneSegment::ALL.len());
+        assert_eq!(status.last_run, Some(run));
+    }
+
+    #[test]
+    fn no_balance_changes_without_changeset() {
+        let api = RethApi::new(NoopProvider::default());
+        assert!(api.balance_changes_in_block(0).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn broadcasts_prune_runs() {
+        let (sync_events, mut rx) = broadcast::channel(SYNC_EVENTS_CHANNEL_CAPACITY);
+        let api = RethApi::with_sync_events(NoopProvider::default(), sync_events);
+
+        let run = api.prune(PruneSegment::Receipts, 0).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), SyncEvent::PruneFinished(run));
+    }
+}
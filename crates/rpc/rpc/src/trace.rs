@@ -2,14 +2,15 @@ use crate::{
     eth::{
         cache::EthStateCache,
         error::{EthApiError, EthResult},
-        revm_utils::{inspect, prepare_call_env},
+        revm_utils::{inspect, prepare_call_env, replay_transactions_with},
         utils::recover_raw_transaction,
         EthTransactions,
     },
     result::internal_rpc_err,
-    TracingCallGuard,
+    BlockingTaskPool, TraceCache, TraceCacheKey, TracingCallGuard,
 };
 use async_trait::async_trait;
+use futures::Future;
 use jsonrpsee::core::RpcResult as Result;
 use reth_primitives::{BlockId, BlockNumberOrTag, Bytes, H256};
 use reth_provider::{BlockProvider, EvmEnvProvider, StateProviderFactory};
@@ -24,7 +25,7 @@ use reth_rpc_types::{
     BlockError, CallRequest, Index, TransactionInfo,
 };
 use revm::primitives::Env;
-use revm_primitives::ResultAndState;
+use revm_primitives::{ExecutionResult, ResultAndState};
 use std::collections::HashSet;
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
@@ -41,6 +42,10 @@ pub struct TraceApi<Client, Eth> {
     eth_cache: EthStateCache,
     // restrict the number of concurrent calls to `trace_*`
     tracing_call_guard: TracingCallGuard,
+    /// Cache for the traces of recently traced blocks and transactions
+    trace_cache: Option<TraceCache>,
+    /// The pool tracing calls are executed on
+    blocking_pool: Option<BlockingTaskPool>,
 }
 
 // === impl TraceApi ===
@@ -53,7 +58,26 @@ impl<Client, Eth> TraceApi<Client, Eth> {
         eth_cache: EthStateCache,
         tracing_call_guard: TracingCallGuard,
     ) -> Self {
-        Self { client, eth_api, eth_cache, tracing_call_guard }
+        Self {
+            client,
+            eth_api,
+            eth_cache,
+            tracing_call_guard,
+            trace_cache: None,
+            blocking_pool: None,
+        }
+    }
+
+    /// Caches the results of `trace_block` and `trace_transaction` in the given [TraceCache].
+    pub fn with_trace_cache(mut self, trace_cache: TraceCache) -> Self {
+        self.trace_cache = Some(trace_cache);
+        self
+    }
+
+    /// Executes tracing calls on the given [BlockingTaskPool] instead of the async runtime.
+    pub fn with_blocking_pool(mut self, blocking_pool: BlockingTaskPool) -> Self {
+        self.blocking_pool = Some(blocking_pool);
+        self
     }
 
     /// Acquires a permit to execute a tracing call.
@@ -66,6 +90,25 @@ impl<Client, Eth> TraceApi<Client, Eth> {
 
 // === impl TraceApi ===
 
+impl<Client, Eth> TraceApi<Client, Eth>
+where
+    Client: Clone + 'static,
+    Eth: Clone + 'static,
+{
+    /// Runs the call on the [BlockingTaskPool], if configured.
+    async fn on_blocking_pool<F, Fut, R>(&self, f: F) -> EthResult<R>
+    where
+        F: FnOnce(Self) -> Fut,
+        Fut: Future<Output = EthResult<R>> + Send + 'static,
+        R: Send + 'static,
+    {
+        match &self.blocking_pool {
+            Some(pool) => pool.spawn(f(self.clone())).await?,
+            None => f(self.clone()).await,
+        }
+    }
+}
+
 impl<Client, Eth> TraceApi<Client, Eth>
 where
     Client: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
@@ -83,7 +126,8 @@ where
         let config = tracing_config(&trace_types);
         let mut inspector = TracingInspector::new(config);
 
-        let (res, _) = self.eth_api.inspect_call_at(call, at, None, &mut inspector).await?;
+        let (res, _) =
+            self.eth_api.inspect_call_at(call, at, Default::default(), &mut inspector).await?;
 
         let trace_res =
             inspector.into_parity_builder().into_trace_results(res.result, &trace_types);
@@ -134,7 +178,13 @@ where
             let mut db = SubState::new(State::new(state));
 
             for (call, trace_types) in calls {
-                let env = prepare_call_env(cfg.clone(), block_env.clone(), call, &mut db, None)?;
+                let env = prepare_call_env(
+                    cfg.clone(),
+                    block_env.clone(),
+                    call,
+                    &mut db,
+                    Default::default(),
+                )?;
                 let config = tracing_config(&trace_types);
                 let mut inspector = TracingInspector::new(config);
                 let (res, _) = inspect(&mut db, env, &mut inspector)?;
@@ -188,9 +238,15 @@ where
         &self,
         hash: H256,
     ) -> EthResult<Option<Vec<LocalizedTransactionTrace>>> {
+        let key = TraceCacheKey::transaction(hash, PARITY_TRACER);
+        if let Some(traces) = self.trace_cache.as_ref().and_then(|cache| cache.get(&key)) {
+            return Ok(Some(traces))
+        }
+
         let _permit = self.acquire_trace_permit().await;
 
-        self.eth_api
+        let traces = self
+            .eth_api
             .trace_transaction(
                 hash,
                 TracingInspectorConfig::default_parity(),
@@ -200,10 +256,20 @@ where
                     Ok(traces)
                 },
             )
-            .await
+            .await?;
+
+        if let (Some(cache), Some(traces)) = (&self.trace_cache, &traces) {
+            // transactions that are not mined yet are traced on top of the pending state
+            if traces.iter().all(|trace| trace.block_hash.is_some()) {
+                cache.insert(key, traces.clone());
+            }
+        }
+        Ok(traces)
     }
 
     /// Executes all transactions of a block and returns a list of callback results.
+    ///
+    /// If a [BlockingTaskPool] is configured, the transactions are traced in parallel.
     async fn trace_block_with<F, R>(
         &self,
         block_id: BlockId,
@@ -211,7 +277,8 @@ where
         f: F,
     ) -> EthResult<Option<Vec<R>>>
     where
-        F: Fn(TransactionInfo, TracingInspector, ResultAndState) -> EthResult<R> + Send,
+        F: Fn(TransactionInfo, TracingInspector, ExecutionResult) -> EthResult<R> + Send + Sync,
+        R: Send,
     {
         let block_hash = match self.client.block_hash_for_id(block_id)? {
             Some(hash) => hash,
@@ -224,32 +291,34 @@ where
         )?;
         let transactions = transactions.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
 
+        let block_number = block_env.number.try_into().unwrap_or(u64::MAX);
+        let mut tx_infos = Vec::with_capacity(transactions.len());
+        let mut envs = Vec::with_capacity(transactions.len());
+        for (idx, tx) in transactions.into_iter().enumerate() {
+            let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
+            tx_infos.push(TransactionInfo {
+                hash: Some(tx.hash()),
+                index: Some(idx as u64),
+                block_hash: Some(block_hash),
+                block_number: Some(block_number),
+            });
+            let tx = tx_env_with_recovered(&tx);
+            envs.push(Env { cfg: cfg.clone(), block: block_env.clone(), tx });
+        }
+
         // replay all transactions of the block
-        self.eth_api
-            .with_state_at(at, move |state| {
-                let mut results = Vec::with_capacity(transactions.len());
-                let mut db = SubState::new(State::new(state));
-
-                for (idx, tx) in transactions.into_iter().enumerate() {
-                    let tx = tx.into_ecrecovered().ok_or(BlockError::InvalidSignature)?;
-                    let tx_info = TransactionInfo {
-                        hash: Some(tx.hash()),
-                        index: Some(idx as u64),
-                        block_hash: Some(block_hash),
-                        block_number: Some(block_env.number.try_into().unwrap_or(u64::MAX)),
-                    };
-
-                    let tx = tx_env_with_recovered(&tx);
-                    let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
-
-                    let mut inspector = TracingInspector::new(config);
-                    let (res, _) = inspect(&mut db, env, &mut inspector)?;
-                    results.push(f(tx_info, inspector, res)?);
-                }
-
-                Ok(results)
-            })
-            .map(Some)
+        replay_transactions_with(
+            &self.eth_api,
+            at,
+            envs,
+            self.blocking_pool.as_ref(),
+            |idx, env, db| {
+                let mut inspector = TracingInspector::new(config);
+                let (ResultAndState { result, state }, _) = inspect(db, env, &mut inspector)?;
+                Ok((f(tx_infos[idx], inspector, result)?, state))
+            },
+        )
+        .map(Some)
     }
 
     /// Returns traces created at given block.
@@ -257,9 +326,18 @@ where
         &self,
         block_id: BlockId,
     ) -> EthResult<Option<Vec<LocalizedTransactionTrace>>> {
-        let traces = self
+        let block_hash = match self.client.block_hash_for_id(block_id)? {
+            Some(hash) => hash,
+            None => return Ok(None),
+        };
+        let key = TraceCacheKey::block(block_hash, PARITY_TRACER);
+        if let Some(traces) = self.trace_cache.as_ref().and_then(|cache| cache.get(&key)) {
+            return Ok(Some(traces))
+        }
+
+        let traces: Option<Vec<_>> = self
             .trace_block_with(
-                block_id,
+                block_hash.into(),
                 TracingInspectorConfig::default_parity(),
                 |tx_info, inspector, _| {
                     let traces =
@@ -269,6 +347,10 @@ where
             )
             .await?
             .map(|traces| traces.into_iter().flatten().collect());
+
+        if let (Some(cache), Some(traces)) = (&self.trace_cache, &traces) {
+            cache.insert(key, traces.clone());
+        }
         Ok(traces)
     }
 
@@ -279,8 +361,7 @@ where
         trace_types: HashSet<TraceType>,
     ) -> EthResult<Option<Vec<TraceResultsWithTransactionHash>>> {
         self.trace_block_with(block_id, tracing_config(&trace_types), |tx_info, inspector, res| {
-            let full_trace =
-                inspector.into_parity_builder().into_trace_results(res.result, &trace_types);
+            let full_trace = inspector.into_parity_builder().into_trace_results(res, &trace_types);
             let trace = TraceResultsWithTransactionHash {
                 transaction_hash: tx_info.hash.expect("tx hash is set"),
                 full_trace,
@@ -294,8 +375,8 @@ where
 #[async_trait]
 impl<Client, Eth> TraceApiServer for TraceApi<Client, Eth>
 where
-    Client: BlockProvider + StateProviderFactory + EvmEnvProvider + 'static,
-    Eth: EthTransactions + 'static,
+    Client: BlockProvider + StateProviderFactory + EvmEnvProvider + Clone + 'static,
+    Eth: EthTransactions + Clone + 'static,
 {
     /// Executes the given call and returns a number of possible traces for it.
     ///
@@ -306,7 +387,11 @@ where
         trace_types: HashSet<TraceType>,
         block_id: Option<BlockId>,
     ) -> Result<TraceResults> {
-        Ok(TraceApi::trace_call(self, call, trace_types, block_id).await?)
+        Ok(self
+            .on_blocking_pool(move |this| async move {
+                TraceApi::trace_call(&this, call, trace_types, block_id).await
+            })
+            .await?)
     }
 
     /// Handler for `trace_callMany`
@@ -315,7 +400,11 @@ where
         calls: Vec<(CallRequest, HashSet<TraceType>)>,
         block_id: Option<BlockId>,
     ) -> Result<Vec<TraceResults>> {
-        Ok(TraceApi::trace_call_many(self, calls, block_id).await?)
+        Ok(self
+            .on_blocking_pool(move |this| async move {
+                TraceApi::trace_call_many(&this, calls, block_id).await
+            })
+            .await?)
     }
 
     /// Handler for `trace_rawTransaction`
@@ -325,7 +414,11 @@ where
         trace_types: HashSet<TraceType>,
         block_id: Option<BlockId>,
     ) -> Result<TraceResults> {
-        Ok(TraceApi::trace_raw_transaction(self, data, trace_types, block_id).await?)
+        Ok(self
+            .on_blocking_pool(move |this| async move {
+                TraceApi::trace_raw_transaction(&this, data, trace_types, block_id).await
+            })
+            .await?)
     }
 
     /// Handler for `trace_replayBlockTransactions`
@@ -334,7 +427,11 @@ where
         block_id: BlockId,
         trace_types: HashSet<TraceType>,
     ) -> Result<Option<Vec<TraceResultsWithTransactionHash>>> {
-        Ok(TraceApi::replay_block_transactions(self, block_id, trace_types).await?)
+        Ok(self
+            .on_blocking_pool(move |this| async move {
+                TraceApi::replay_block_transactions(&this, block_id, trace_types).await
+            })
+            .await?)
     }
 
     /// Handler for `trace_replayTransaction`
@@ -343,7 +440,11 @@ where
         transaction: H256,
         trace_types: HashSet<TraceType>,
     ) -> Result<TraceResults> {
-        Ok(TraceApi::replay_transaction(self, transaction, trace_types).await?)
+        Ok(self
+            .on_blocking_pool(move |this| async move {
+                TraceApi::replay_transaction(&this, transaction, trace_types).await
+            })
+            .await?)
     }
 
     /// Handler for `trace_block`
@@ -351,7 +452,11 @@ where
         &self,
         block_id: BlockId,
     ) -> Result<Option<Vec<LocalizedTransactionTrace>>> {
-        Ok(TraceApi::trace_block(self, block_id).await?)
+        Ok(self
+            .on_blocking_pool(
+                move |this| async move { TraceApi::trace_block(&this, block_id).await },
+            )
+            .await?)
     }
 
     /// Handler for `trace_filter`
@@ -366,7 +471,12 @@ where
         hash: H256,
         indices: Vec<Index>,
     ) -> Result<Option<LocalizedTransactionTrace>> {
-        Ok(TraceApi::trace_get(self, hash, indices.into_iter().map(Into::into).collect()).await?)
+        let indices: Vec<usize> = indices.into_iter().map(Into::into).collect();
+        Ok(self
+            .on_blocking_pool(
+                move |this| async move { TraceApi::trace_get(&this, hash, indices).await },
+            )
+            .await?)
     }
 
     /// Handler for `trace_transaction`
@@ -374,7 +484,11 @@ where
         &self,
         hash: H256,
     ) -> Result<Option<Vec<LocalizedTransactionTrace>>> {
-        Ok(TraceApi::trace_transaction(self, hash).await?)
+        Ok(self
+            .on_blocking_pool(
+                move |this| async move { TraceApi::trace_transaction(&this, hash).await },
+            )
+            .await?)
     }
 }
 
@@ -384,6 +498,10 @@ impl<Client, Eth> std::fmt::Debug for TraceApi<Client, Eth> {
     }
 }
 
+/// Identifies the parity traces created with [TracingInspectorConfig::default_parity] in the
+/// [TraceCache].
+const PARITY_TRACER: &str = "trace";
+
 /// Returns the [TracingInspectorConfig] depending on the enabled [TraceType]s
 fn tracing_config(trace_types: &HashSet<TraceType>) -> TracingInspectorConfig {
     TracingInspectorConfig::default_parity()
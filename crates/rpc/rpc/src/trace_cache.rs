@@ -0,0 +1,484 @@
+//! In-memory cache for the results of tracing calls
+
+use parking_lot::Mutex;
+use reth_primitives::{Address, Bytes, JsonU256, H256, U256, U64};
+use reth_provider::{CanonStateNotification, CanonStateNotifications};
+use reth_rpc_types::trace::{
+    common::TraceResult,
+    geth::{
+        AccountState, CallFrame, CallLogFrame, DefaultFrame, DiffMode, FourByteFrame,
+        GethTraceFrame, PreStateFrame, StructLog,
+    },
+    parity::{Action, LocalizedTransactionTrace, TraceOutput, TransactionTrace},
+};
+use schnellru::{LruMap, Unlimited};
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashSet},
+    fmt,
+    mem::size_of,
+    sync::Arc,
+};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::debug;
+
+/// Settings for the [TraceCache]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceCacheConfig {
+    /// Max number of bytes the cached traces may take, as estimated by [TraceSize].
+    ///
+    /// `0` disables the cache.
+    pub max_memory: usize,
+}
+
+impl Default for TraceCacheConfig {
+    fn default() -> Self {
+        // 128MB
+        Self { max_memory: 128 * 1024 * 1024 }
+    }
+}
+
+/// Identifies a cached tracing result.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TraceCacheKey {
+    /// The traces of all transactions of the block with the given hash.
+    Block {
+        /// Hash of the traced block.
+        block_hash: H256,
+        /// The tracer and its options that produced the traces.
+        tracer: String,
+    },
+    /// The traces of the transaction with the given hash.
+    Transaction {
+        /// Hash of the traced transaction.
+        tx_hash: H256,
+        /// The tracer and its options that produced the traces.
+        tracer: String,
+    },
+}
+
+impl TraceCacheKey {
+    /// Creates the key for the traces of a block.
+    pub fn block(block_hash: H256, tracer: impl Into<String>) -> Self {
+        Self::Block { block_hash, tracer: tracer.into() }
+    }
+
+    /// Creates the key for the traces of a transaction.
+    pub fn transaction(tx_hash: H256, tracer: impl Into<String>) -> Self {
+        Self::Transaction { tx_hash, tracer: tracer.into() }
+    }
+}
+
+/// A cached result and its estimated size.
+struct CachedTrace {
+    value: Arc<dyn Any + Send + Sync>,
+    size: usize,
+}
+
+struct TraceCacheInner {
+    entries: LruMap<TraceCacheKey, CachedTrace, Unlimited>,
+    /// Sum of the sizes of all entries.
+    memory_usage: usize,
+    max_memory: usize,
+}
+
+impl TraceCacheInner {
+    fn remove(&mut self, key: &TraceCacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.memory_usage -= entry.size;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.memory_usage = 0;
+    }
+}
+
+/// Caches the results of tracing calls of recently traced blocks and transactions.
+///
+/// Explorers typically trace the same block several times in a row, the cache makes sure the
+/// block is only re-executed once. The cache is bounded by the estimated size of the results.
+///
+/// Block traces are keyed by block hash and are never stale, but transactions are keyed by hash
+/// and the results depend on the block that includes them, so the cache must be kept in sync with
+/// the canonical chain, see [TraceCache::update_on_canonical_state].
+#[derive(Clone)]
+pub struct TraceCache {
+    inner: Arc<Mutex<TraceCacheInner>>,
+}
+
+// === impl TraceCache ===
+
+impl TraceCache {
+    /// Creates a new, empty cache with the given settings.
+    pub fn new(config: TraceCacheConfig) -> Self {
+        let inner = TraceCacheInner {
+            entries: LruMap::new(Unlimited),
+            memory_usage: 0,
+            max_memory: config.max_memory,
+        };
+        Self { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Returns the cached result for the given key.
+    ///
+    /// Returns `None` if there is no entry or the entry is not of type `T`.
+    pub fn get<T>(&self, key: &TraceCacheKey) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let mut inner = self.inner.lock();
+        inner.entries.get(key).and_then(|entry| entry.value.downcast_ref::<T>().cloned())
+    }
+
+    /// Caches the result for the given key, evicting the least recently used entries if the
+    /// memory limit is exceeded.
+    ///
+    /// Results that exceed the limit on their own are not cached.
+    pub fn insert<T>(&self, key: TraceCacheKey, value: T)
+    where
+        T: TraceSize + Send + Sync + 'static,
+    {
+        let size = value.size();
+
+        let mut inner = self.inner.lock();
+        if size > inner.max_memory {
+            return
+        }
+        inner.remove(&key);
+        inner.entries.insert(key, CachedTrace { value: Arc::new(value), size });
+        inner.memory_usage += size;
+
+        while inner.memory_usage > inner.max_memory {
+            match inner.entries.pop_oldest() {
+                Some((_, entry)) => inner.memory_usage -= entry.size,
+                None => break,
+            }
+        }
+    }
+
+    /// Returns the number of cached results.
+    pub fn len(&self) -> usize {
+        self.inner.lock().entries.len()
+    }
+
+    /// Returns true if no results are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the estimated number of bytes the cached results take.
+    pub fn memory_usage(&self) -> usize {
+        self.inner.lock().memory_usage
+    }
+
+    /// Removes the traces of reverted blocks and their transactions.
+    pub fn on_canonical_state(&self, notification: &CanonStateNotification) {
+        let Some(reverted) = notification.reverted() else { return };
+
+        let mut blocks = HashSet::new();
+        let mut transactions = HashSet::new();
+        for block in reverted.blocks().values() {
+            blocks.insert(block.hash());
+            transactions.extend(block.body.iter().map(|tx| tx.hash()));
+        }
+
+        let mut inner = self.inner.lock();
+        let stale = inner
+            .entries
+            .iter()
+            .filter(|(key, _)| match key {
+                TraceCacheKey::Block { block_hash, .. } => blocks.contains(block_hash),
+                TraceCacheKey::Transaction { tx_hash, .. } => transactions.contains(tx_hash),
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in stale {
+            inner.remove(&key);
+        }
+    }
+
+    /// Keeps the cache in sync with the canonical chain until the channel is closed.
+    ///
+    /// If notifications were missed, the entire cache is cleared.
+    pub async fn update_on_canonical_state(self, mut notifications: CanonStateNotifications) {
+        loop {
+            match notifications.recv().await {
+                Ok(notification) => self.on_canonical_state(&notification),
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!(target: "rpc::trace", skipped, "Missed canonical state notifications, clearing trace cache");
+                    self.inner.lock().clear();
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+/// Estimates the memory a cached result takes from its structure, without serializing it.
+pub trait TraceSize {
+    /// Returns the number of bytes the value owns on the heap.
+    fn heap_size(&self) -> usize;
+
+    /// Returns the number of bytes of the value, including the bytes it owns on the heap.
+    fn size(&self) -> usize
+    where
+        Self: Sized,
+    {
+        size_of::<Self>() + self.heap_size()
+    }
+}
+
+macro_rules! impl_inline_trace_size {
+    ($($ty:ty),*) => {
+        $(
+            impl TraceSize for $ty {
+                fn heap_size(&self) -> usize {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+impl_inline_trace_size!(bool, u64, usize, Address, H256, U256, U64, JsonU256);
+
+impl TraceSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl TraceSize for Bytes {
+    fn heap_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T: TraceSize> TraceSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, TraceSize::heap_size)
+    }
+}
+
+impl<T: TraceSize> TraceSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(TraceSize::heap_size).sum::<usize>()
+    }
+}
+
+impl<K: TraceSize, V: TraceSize> TraceSize for BTreeMap<K, V> {
+    fn heap_size(&self) -> usize {
+        self.iter()
+            .map(|(key, value)| {
+                size_of::<K>() + key.heap_size() + size_of::<V>() + value.heap_size()
+            })
+            .sum()
+    }
+}
+
+impl<Ok: TraceSize, Err: TraceSize> TraceSize for TraceResult<Ok, Err> {
+    fn heap_size(&self) -> usize {
+        match self {
+            TraceResult::Success { result } => result.heap_size(),
+            TraceResult::Error { error } => error.heap_size(),
+        }
+    }
+}
+
+impl TraceSize for serde_json::Value {
+    fn heap_size(&self) -> usize {
+        match self {
+            serde_json::Value::Null | serde_json::Value::Bool(_) | serde_json::Value::Number(_) => {
+                0
+            }
+            serde_json::Value::String(s) => s.heap_size(),
+            serde_json::Value::Array(values) => values.heap_size(),
+            serde_json::Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| size_of::<String>() + key.heap_size() + value.size())
+                .sum(),
+        }
+    }
+}
+
+impl TraceSize for LocalizedTransactionTrace {
+    fn heap_size(&self) -> usize {
+        self.trace.heap_size()
+    }
+}
+
+impl TraceSize for TransactionTrace {
+    fn heap_size(&self) -> usize {
+        self.trace_address.heap_size() + self.action.heap_size() + self.result.heap_size()
+    }
+}
+
+impl TraceSize for Action {
+    fn heap_size(&self) -> usize {
+        match self {
+            Action::Call(call) => call.input.heap_size(),
+            Action::Create(create) => create.init.heap_size(),
+            Action::Selfdestruct(_) | Action::Reward(_) => 0,
+        }
+    }
+}
+
+impl TraceSize for TraceOutput {
+    fn heap_size(&self) -> usize {
+        match self {
+            TraceOutput::Call(call) => call.output.heap_size(),
+            TraceOutput::Create(create) => create.code.heap_size(),
+        }
+    }
+}
+
+impl TraceSize for GethTraceFrame {
+    fn heap_size(&self) -> usize {
+        match self {
+            GethTraceFrame::Default(frame) => frame.heap_size(),
+            GethTraceFrame::NoopTracer(_) => 0,
+            GethTraceFrame::FourByteTracer(FourByteFrame(selectors)) => selectors.heap_size(),
+            GethTraceFrame::CallTracer(frame) => frame.heap_size(),
+            GethTraceFrame::PreStateTracer(PreStateFrame::Default(mode)) => mode.0.heap_size(),
+            GethTraceFrame::PreStateTracer(PreStateFrame::Diff(DiffMode { pre, post })) => {
+                pre.heap_size() + post.heap_size()
+            }
+            GethTraceFrame::JS(value) => value.heap_size(),
+        }
+    }
+}
+
+impl TraceSize for DefaultFrame {
+    fn heap_size(&self) -> usize {
+        self.return_value.heap_size() + self.struct_logs.heap_size()
+    }
+}
+
+impl TraceSize for StructLog {
+    fn heap_size(&self) -> usize {
+        self.op.heap_size() +
+            self.memory.heap_size() +
+            self.stack.heap_size() +
+            self.return_data.heap_size() +
+            self.storage.heap_size() +
+            self.error.heap_size()
+    }
+}
+
+impl TraceSize for CallFrame {
+    fn heap_size(&self) -> usize {
+        self.typ.heap_size() +
+            self.input.heap_size() +
+            self.output.heap_size() +
+            self.error.heap_size() +
+            self.calls.heap_size() +
+            self.logs.heap_size()
+    }
+}
+
+impl TraceSize for CallLogFrame {
+    fn heap_size(&self) -> usize {
+        self.topics.heap_size() + self.data.heap_size()
+    }
+}
+
+impl TraceSize for AccountState {
+    fn heap_size(&self) -> usize {
+        self.code.heap_size() + self.storage.heap_size()
+    }
+}
+
+impl fmt::Debug for TraceCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.lock();
+        f.debug_struct("TraceCache")
+            .field("len", &inner.entries.len())
+            .field("memory_usage", &inner.memory_usage)
+            .field("max_memory", &inner.max_memory)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{SealedBlockWithSenders, TransactionSigned};
+    use reth_provider::{Chain, PostState};
+
+    #[test]
+    fn evicts_by_memory_usage() {
+        let value = vec![1u64; 10];
+        let size = value.size();
+        let cache = TraceCache::new(TraceCacheConfig { max_memory: size * 2 });
+
+        let key = |n| TraceCacheKey::block(H256::from_low_u64_be(n), "trace");
+        cache.insert(key(1), value.clone());
+        cache.insert(key(2), value.clone());
+        assert_eq!(cache.get::<Vec<u64>>(&key(1)), Some(value.clone()));
+        // wrong type
+        assert_eq!(cache.get::<Vec<u32>>(&key(1)), None);
+
+        // the least recently used entry is evicted
+        cache.insert(key(3), value.clone());
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.memory_usage(), size * 2);
+        assert_eq!(cache.get::<Vec<u64>>(&key(2)), None);
+
+        // too large to be cached
+        cache.insert(key(4), vec![1u64; 100]);
+        assert_eq!(cache.get::<Vec<u64>>(&key(4)), None);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn estimates_trace_size() {
+        let frame = CallFrame {
+            input: Bytes::from(vec![0u8; 100]),
+            calls: Some(vec![CallFrame {
+                output: Some(Bytes::from(vec![0u8; 50])),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        let size = frame.size();
+        assert_eq!(size, 2 * size_of::<CallFrame>() + 150);
+
+        let trace = TraceResult::<GethTraceFrame, String>::Success {
+            result: GethTraceFrame::CallTracer(frame),
+        };
+        assert!(trace.size() >= size);
+
+        let js = GethTraceFrame::JS(serde_json::json!({ "a": "b".repeat(64) }));
+        assert!(js.heap_size() >= 64);
+    }
+
+    #[test]
+    fn removes_reverted_traces() {
+        let tx = TransactionSigned::default();
+        let mut block = SealedBlockWithSenders::default();
+        block.block.header.hash = H256::from_low_u64_be(1);
+        block.block.body.push(tx.clone());
+        let chain = Arc::new(Chain::new(vec![(block, PostState::default())]));
+
+        let cache = TraceCache::new(TraceCacheConfig::default());
+        let block_key = TraceCacheKey::block(H256::from_low_u64_be(1), "trace");
+        let tx_key = TraceCacheKey::transaction(tx.hash(), "trace");
+        let other_key = TraceCacheKey::block(H256::from_low_u64_be(2), "trace");
+        cache.insert(block_key.clone(), 1u64);
+        cache.insert(tx_key.clone(), 1u64);
+        cache.insert(other_key.clone(), 1u64);
+
+        cache.on_canonical_state(&CanonStateNotification::Commit { new: chain.clone() });
+        assert_eq!(cache.len(), 3);
+
+        cache.on_canonical_state(&CanonStateNotification::Revert { old: chain });
+        assert_eq!(cache.get::<u64>(&block_key), None);
+        assert_eq!(cache.get::<u64>(&tx_key), None);
+        assert_eq!(cache.get::<u64>(&other_key), Some(1));
+        assert_eq!(cache.memory_usage(), size_of::<u64>());
+    }
+}
@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult as Result;
+use reth_primitives::{Address, IntoRecoveredTransaction, TransactionKind, U256, U64};
+use reth_rpc_api::TxPoolApiServer;
+use reth_rpc_types::{
+    Transaction, TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary,
+    TxpoolStatus,
+};
+use reth_transaction_pool::{PoolTransaction, TransactionPool, ValidPoolTransaction};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// `txpool` API implementation.
+///
+/// This type provides the functionality for handling `txpool` related requests.
+///
+/// Like geth, the transactions that are ready to be included in the next block are reported as
+/// `pending`, the transactions of the basefee and queued sub-pools as `queued`.
+#[derive(Clone)]
+pub struct TxPoolApi<Pool> {
+    /// An interface to interact with the pool
+    pool: Pool,
+}
+
+// === impl TxPoolApi ===
+
+impl<Pool> TxPoolApi<Pool> {
+    /// Creates a new instance of `TxPoolApi`.
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl<Pool> TxPoolApiServer for TxPoolApi<Pool>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Handler for `txpool_status`
+    async fn txpool_status(&self) -> Result<TxpoolStatus> {
+        let size = self.pool.pool_size();
+        Ok(TxpoolStatus {
+            pending: U64::from(size.pending),
+            queued: U64::from(size.basefee + size.queued),
+        })
+    }
+
+    /// Handler for `txpool_inspect`
+    async fn txpool_inspect(&self) -> Result<TxpoolInspect> {
+        Ok(TxpoolInspect {
+            pending: group_by_sender(self.pool.pending_transactions(), summary),
+            queued: group_by_sender(self.pool.queued_transactions(), summary),
+        })
+    }
+
+    /// Handler for `txpool_content`
+    async fn txpool_content(&self) -> Result<TxpoolContent> {
+        Ok(TxpoolContent {
+            pending: group_by_sender(self.pool.pending_transactions(), to_rpc_transaction),
+            queued: group_by_sender(self.pool.queued_transactions(), to_rpc_transaction),
+        })
+    }
+
+    /// Handler for `txpool_contentFrom`
+    async fn txpool_content_from(&self, from: Address) -> Result<TxpoolContentFrom> {
+        let from_sender = |txs: Vec<Arc<ValidPoolTransaction<Pool::Transaction>>>| {
+            txs.into_iter()
+                .filter(|tx| tx.sender() == from)
+                .map(|tx| (tx.nonce().to_string(), to_rpc_transaction(&tx)))
+                .collect::<BTreeMap<_, _>>()
+        };
+        Ok(TxpoolContentFrom {
+            pending: from_sender(self.pool.pending_transactions()),
+            queued: from_sender(self.pool.queued_transactions()),
+        })
+    }
+}
+
+impl<Pool> std::fmt::Debug for TxPoolApi<Pool> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TxPoolApi").finish_non_exhaustive()
+    }
+}
+
+/// Groups the transactions by sender and nonce, converted with the given function.
+fn group_by_sender<T, R>(
+    txs: Vec<Arc<ValidPoolTransaction<T>>>,
+    f: impl Fn(&ValidPoolTransaction<T>) -> R,
+) -> BTreeMap<Address, BTreeMap<String, R>>
+where
+    T: PoolTransaction,
+{
+    let mut grouped = BTreeMap::<_, BTreeMap<_, _>>::new();
+    for tx in txs {
+        grouped.entry(tx.sender()).or_default().insert(tx.nonce().to_string(), f(&tx));
+    }
+    grouped
+}
+
+/// Converts the pooled transaction into its rpc representation.
+fn to_rpc_transaction<T>(tx: &ValidPoolTransaction<T>) -> Transaction
+where
+    T: PoolTransaction,
+{
+    Transaction::from_recovered(tx.to_recovered_transaction())
+}
+
+/// Returns the summary of the pooled transaction reported by `txpool_inspect`.
+fn summary<T>(tx: &ValidPoolTransaction<T>) -> TxpoolInspectSummary
+where
+    T: PoolTransaction,
+{
+    let recovered = tx.to_recovered_transaction();
+    let to = match recovered.kind() {
+        TransactionKind::Call(to) => Some(*to),
+        TransactionKind::Create => None,
+    };
+    TxpoolInspectSummary {
+        to,
+        value: U256::from(*recovered.value()),
+        gas: U256::from(tx.gas_limit()),
+        gas_price: U256::from(tx.max_fee_per_gas()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        TransactionOrigin,
+    };
+
+    #[tokio::test]
+    async fn content_from_sender() {
+        let pool = testing_pool();
+        let api = TxPoolApi::new(pool.clone());
+
+        // the senders have no balance to pay for the gas, so the transactions are queued
+        let tx = MockTransaction::eip1559().with_gas_limit(21_000);
+        let sender = tx.get_sender();
+        pool.add_transaction(TransactionOrigin::External, tx).await.unwrap();
+        let other = MockTransaction::eip1559().with_gas_limit(21_000);
+        pool.add_transaction(TransactionOrigin::External, other).await.unwrap();
+
+        let status = api.txpool_status().await.unwrap();
+        assert_eq!(status, TxpoolStatus { pending: U64::zero(), queued: U64::from(2) });
+
+        let content = api.txpool_content_from(sender).await.unwrap();
+        assert!(content.pending.is_empty());
+        assert_eq!(content.queued.keys().collect::<Vec<_>>(), vec!["0"]);
+        assert_eq!(content.queued["0"].from, sender);
+
+        let inspect = api.txpool_inspect().await.unwrap();
+        assert_eq!(inspect.queued.len(), 2);
+        assert_eq!(inspect.queued[&sender]["0"].gas, U256::from(21_000));
+    }
+}
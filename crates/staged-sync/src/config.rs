@@ -5,9 +5,10 @@ use reth_downloaders::{
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
 use reth_network::{NetworkConfigBuilder, PeersConfig, SessionsConfig};
+use reth_rpc_types::GasPriceOracleConfig;
 use secp256k1::SecretKey;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 /// Configuration for the reth node.
 #[derive(Debug, Clone, Default, Deserialize, PartialEq, Serialize)]
@@ -20,28 +21,28 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for the gas price oracle behind `eth_gasPrice`.
+    pub gpo: GasPriceOracleConfig,
 }
 
 impl Config {
     /// Initializes network config from read data
+    ///
+    /// Returns an error if the peers file exists but can't be read or parsed.
     pub fn network_config(
         &self,
         nat_resolution_method: reth_net_nat::NatResolver,
         peers_file: Option<PathBuf>,
         secret_key: SecretKey,
-    ) -> NetworkConfigBuilder {
-        let peer_config = self
-            .peers
-            .clone()
-            .with_basic_nodes_from_file(peers_file)
-            .unwrap_or_else(|_| self.peers.clone());
+    ) -> Result<NetworkConfigBuilder, std::io::Error> {
+        let peer_config = self.peers.clone().with_basic_nodes_from_file(peers_file)?;
 
         let discv4 =
             Discv4Config::builder().external_ip_resolver(Some(nat_resolution_method)).clone();
-        NetworkConfigBuilder::new(secret_key)
+        Ok(NetworkConfigBuilder::new(secret_key)
             .sessions_config(self.sessions.clone())
             .peer_config(peer_config)
-            .discovery(discv4)
+            .discovery(discv4))
     }
 }
 
@@ -58,20 +59,41 @@ pub struct StageConfig {
     pub sender_recovery: SenderRecoveryConfig,
     /// Execution stage configuration.
     pub execution: ExecutionConfig,
+    /// Log index stage configuration.
+    pub index_logs: IndexLogsConfig,
 }
 
 /// Header stage configuration.
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
 pub struct HeadersConfig {
     /// The maximum number of headers to download before committing progress to the database.
     pub commit_threshold: u64,
     /// The maximum number of headers to request from a peer at a time.
     pub downloader_batch_size: u64,
+    /// Maximum amount of received header responses to buffer internally.
+    pub downloader_max_buffered_responses: usize,
+    /// The minimum number of requests to send concurrently.
+    pub downloader_min_concurrent_requests: usize,
+    /// The maximum number of requests to send concurrently.
+    pub downloader_max_concurrent_requests: usize,
+    /// The timeout after which a pending request is retried.
+    pub downloader_request_timeout: Duration,
+    /// The number of times a failed request is retried.
+    pub downloader_max_retries: usize,
 }
 
 impl Default for HeadersConfig {
     fn default() -> Self {
-        Self { commit_threshold: 10_000, downloader_batch_size: 1000 }
+        Self {
+            commit_threshold: 10_000,
+            downloader_batch_size: 1000,
+            downloader_max_buffered_responses: 750,
+            downloader_min_concurrent_requests: 5,
+            downloader_max_concurrent_requests: 150,
+            downloader_request_timeout: Duration::from_secs(30),
+            downloader_max_retries: 10,
+        }
     }
 }
 
@@ -80,6 +102,11 @@ impl From<HeadersConfig> for ReverseHeadersDownloaderBuilder {
         ReverseHeadersDownloaderBuilder::default()
             .request_limit(config.downloader_batch_size)
             .stream_batch_size(config.commit_threshold as usize)
+            .max_buffered_responses(config.downloader_max_buffered_responses)
+            .min_concurrent_requests(config.downloader_min_concurrent_requests)
+            .max_concurrent_requests(config.downloader_max_concurrent_requests)
+            .request_timeout(config.downloader_request_timeout)
+            .max_retries(config.downloader_max_retries)
     }
 }
 
@@ -99,6 +126,7 @@ impl Default for TotalDifficultyConfig {
 
 /// Body stage configuration.
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
 pub struct BodiesConfig {
     /// The batch size of non-empty blocks per one request
     pub downloader_request_limit: u64,
@@ -111,6 +139,11 @@ pub struct BodiesConfig {
     pub downloader_min_concurrent_requests: usize,
     /// The maximum number of requests to send concurrently.
     pub downloader_max_concurrent_requests: usize,
+    /// The timeout after which a pending request is retried.
+    pub downloader_request_timeout: Duration,
+    /// The number of consecutive times a failed request is retried before it is restarted with
+    /// high priority. Requests are retried until they succeed.
+    pub downloader_max_retries: usize,
 }
 
 impl Default for BodiesConfig {
@@ -121,6 +154,8 @@ impl Default for BodiesConfig {
             downloader_max_buffered_responses: 1000,
             downloader_min_concurrent_requests: 5,
             downloader_max_concurrent_requests: 100,
+            downloader_request_timeout: Duration::from_secs(30),
+            downloader_max_retries: 10,
         }
     }
 }
@@ -135,6 +170,8 @@ impl From<BodiesConfig> for BodiesDownloaderBuilder {
                 config.downloader_min_concurrent_requests..=
                     config.downloader_max_concurrent_requests,
             )
+            .with_request_timeout(config.downloader_request_timeout)
+            .with_max_retries(config.downloader_max_retries)
     }
 }
 
@@ -164,6 +201,26 @@ impl Default for ExecutionConfig {
     }
 }
 
+/// Log index stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct IndexLogsConfig {
+    /// Whether the addresses and topics of logs are indexed, to speed up log queries over large
+    /// block ranges.
+    ///
+    /// The index is dropped when the node is started with indexing disabled, since it isn't kept
+    /// up to date with reorgs anymore.
+    pub enabled: bool,
+    /// The maximum number of blocks to index before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexLogsConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 100_000 }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Config;
@@ -5,10 +5,10 @@ use reth_db::{
     tables,
     transaction::{DbTx, DbTxMut},
 };
-use reth_primitives::{keccak256, Account, Bytecode, ChainSpec, StorageEntry, H256};
+use reth_primitives::{Account, Bytecode, ChainSpec, StorageEntry, H256};
 use reth_provider::{Transaction, TransactionError};
 use reth_stages::StageKind;
-use std::{path::Path, sync::Arc};
+use std::{collections::BTreeMap, path::Path, sync::Arc};
 use tracing::debug;
 
 /// Opens up an existing database or creates a new one at the specified path.
@@ -32,6 +32,15 @@ pub enum InitDatabaseError {
         actual: H256,
     },
 
+    /// The genesis block of the chain spec doesn't match the known genesis hash of the chain
+    #[error("Invalid genesis: expected hash {expected}, computed {computed}")]
+    InvalidGenesis {
+        /// Known genesis hash of the chain.
+        expected: H256,
+        /// Hash of the genesis block computed from the chain spec.
+        computed: H256,
+    },
+
     /// Higher level error encountered when using a Transaction.
     #[error(transparent)]
     TransactionError(#[from] TransactionError),
@@ -51,6 +60,11 @@ pub fn init_genesis<DB: Database>(
 
     let header = chain.genesis_header();
     let hash = header.hash_slow();
+    if let Some(expected) = chain.genesis_hash {
+        if expected != hash {
+            return Err(InitDatabaseError::InvalidGenesis { expected, computed: hash })
+        }
+    }
 
     let tx = db.tx()?;
     if let Some((_, db_hash)) = tx.cursor_read::<tables::CanonicalHeaders>()?.first()? {
@@ -99,12 +113,10 @@ pub fn insert_genesis_state<DB: Database>(
 
     // Insert account state
     for (address, account) in &genesis.alloc {
-        let mut bytecode_hash = None;
-        // insert bytecode hash
-        if let Some(code) = &account.code {
-            let hash = keccak256(code.as_ref());
+        // insert bytecode, accounts with empty code are treated as accounts without code
+        let bytecode_hash = account.code_hash();
+        if let Some((code, hash)) = account.code.as_ref().zip(bytecode_hash) {
             bytecode_cursor.upsert(hash, Bytecode::new_raw_with_hash(code.0.clone(), hash))?;
-            bytecode_hash = Some(hash);
         }
         // insert plain account.
         account_cursor.upsert(
@@ -115,12 +127,11 @@ pub fn insert_genesis_state<DB: Database>(
                 bytecode_hash,
             },
         )?;
-        // insert plain storages
+        // insert plain storages in key order, skipping empty slots
         if let Some(storage) = &account.storage {
+            let storage =
+                storage.iter().filter(|(_, value)| !value.is_zero()).collect::<BTreeMap<_, _>>();
             for (&key, &value) in storage {
-                if value.is_zero() {
-                    continue
-                }
                 storage_cursor.upsert(*address, StorageEntry { key, value: value.into() })?
             }
         }
@@ -150,11 +161,20 @@ pub fn insert_genesis_hashes<DB: Database>(
 #[cfg(test)]
 mod tests {
     use super::{init_genesis, InitDatabaseError};
-    use reth_db::mdbx::test_utils::create_test_rw_db;
+    use reth_db::{
+        cursor::{DbCursorRO, DbDupCursorRO},
+        database::Database,
+        mdbx::test_utils::create_test_rw_db,
+        tables,
+        transaction::DbTx,
+    };
     use reth_primitives::{
-        GOERLI, GOERLI_GENESIS, MAINNET, MAINNET_GENESIS, SEPOLIA, SEPOLIA_GENESIS,
+        hex_literal::hex, keccak256, Address, Bytes, ChainSpecBuilder, Genesis, GenesisAccount,
+        StorageEntry, GOERLI, GOERLI_GENESIS, H256, HOLESKY, HOLESKY_GENESIS, MAINNET,
+        MAINNET_GENESIS, SEPOLIA, SEPOLIA_GENESIS, U256,
     };
-    use std::sync::Arc;
+    use reth_trie::StateRoot;
+    use std::{collections::HashMap, sync::Arc};
 
     #[test]
     fn success_init_genesis_mainnet() {
@@ -183,6 +203,17 @@ mod tests {
         assert_eq!(genesis_hash, SEPOLIA_GENESIS);
     }
 
+    #[test]
+    fn fail_init_genesis_holesky_without_alloc() {
+        let db = create_test_rw_db();
+        let err = init_genesis(db.clone(), Arc::new(HOLESKY.clone())).unwrap_err();
+
+        assert!(matches!(err, InitDatabaseError::InvalidGenesis { expected: HOLESKY_GENESIS, .. }));
+        // nothing was written
+        let tx = db.tx().unwrap();
+        assert!(tx.cursor_read::<tables::CanonicalHeaders>().unwrap().first().unwrap().is_none());
+    }
+
     #[test]
     fn fail_init_inconsistent_db() {
         let db = create_test_rw_db();
@@ -199,4 +230,53 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn init_genesis_with_code_and_storage() {
+        let contract = Address::from_low_u64_be(0x4200);
+        let code = Bytes::from(hex!("6080604052").as_slice());
+        let storage = HashMap::from([
+            (H256::from_low_u64_be(1), H256::from_low_u64_be(0xff)),
+            (H256::from_low_u64_be(2), H256::zero()),
+        ]);
+        let genesis = Genesis::default().extend_accounts([
+            (
+                contract,
+                GenesisAccount::default().with_code(Some(code.clone())).with_storage(Some(storage)),
+            ),
+            (
+                Address::from_low_u64_be(1),
+                GenesisAccount::default()
+                    .with_balance(U256::from(1))
+                    .with_code(Some(Bytes::default())),
+            ),
+        ]);
+        let chain = Arc::new(ChainSpecBuilder::mainnet().genesis(genesis).build());
+
+        let db = create_test_rw_db();
+        init_genesis(db.clone(), chain.clone()).unwrap();
+
+        let tx = db.tx().unwrap();
+        let account = tx.get::<tables::PlainAccountState>(contract).unwrap().unwrap();
+        assert_eq!(account.bytecode_hash, Some(keccak256(&code)));
+        assert_eq!(tx.entries::<tables::Bytecodes>().unwrap(), 1);
+
+        // the empty slot is not written
+        let mut storage_cursor = tx.cursor_dup_read::<tables::PlainStorageState>().unwrap();
+        let slots = storage_cursor
+            .walk_dup(Some(contract), None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            slots,
+            vec![(
+                contract,
+                StorageEntry { key: H256::from_low_u64_be(1), value: U256::from(0xff) }
+            )]
+        );
+
+        // the root of the hashed state matches the root of the genesis header
+        assert_eq!(StateRoot::new(&tx).root().unwrap(), chain.genesis_header().state_root);
+    }
 }
@@ -1,5 +1,5 @@
 use crate::stages::{
-    ACCOUNT_HASHING, BODIES, EXECUTION, FINISH, HEADERS, INDEX_ACCOUNT_HISTORY,
+    ACCOUNT_HASHING, BODIES, EXECUTION, FINISH, HEADERS, INDEX_ACCOUNT_HISTORY, INDEX_LOGS,
     INDEX_STORAGE_HISTORY, MERKLE_EXECUTION, MERKLE_UNWIND, SENDER_RECOVERY, TOTAL_DIFFICULTY,
     TRANSACTION_LOOKUP,
 };
@@ -23,6 +23,7 @@ pub enum StageKind {
     StorageHashing,
     IndexAccountHistory,
     IndexStorageHistory,
+    IndexLogs,
     MerkleExecution,
     MerkleUnwind,
     Execution,
@@ -32,7 +33,7 @@ pub enum StageKind {
 
 impl StageKind {
     /// All supported Stages
-    pub const ALL: [StageKind; 13] = [
+    pub const ALL: [StageKind; 14] = [
         StageKind::Headers,
         StageKind::Bodies,
         StageKind::SenderRecovery,
@@ -41,6 +42,7 @@ impl StageKind {
         StageKind::StorageHashing,
         StageKind::IndexAccountHistory,
         StageKind::IndexStorageHistory,
+        StageKind::IndexLogs,
         StageKind::MerkleExecution,
         StageKind::MerkleUnwind,
         StageKind::Execution,
@@ -59,6 +61,7 @@ impl StageKind {
             StageKind::StorageHashing => ACCOUNT_HASHING,
             StageKind::IndexAccountHistory => INDEX_ACCOUNT_HISTORY,
             StageKind::IndexStorageHistory => INDEX_STORAGE_HISTORY,
+            StageKind::IndexLogs => INDEX_LOGS,
             StageKind::MerkleExecution => MERKLE_EXECUTION,
             StageKind::MerkleUnwind => MERKLE_UNWIND,
             StageKind::Execution => EXECUTION,
@@ -22,7 +22,7 @@ mod set;
 mod sync_metrics;
 
 pub use builder::*;
-use ctrl::*;
+pub use ctrl::*;
 pub use event::*;
 use progress::*;
 pub use set::*;
@@ -135,6 +135,11 @@ where
         &self.progress.minimum_progress
     }
 
+    /// Returns the updater that is notified about the sync state of the pipeline, if any.
+    pub fn sync_state_updater(&self) -> Option<&U> {
+        self.sync_state_updater.as_ref()
+    }
+
     /// Set tip for reverse sync.
     #[track_caller]
     pub fn set_tip(&self, tip: H256) {
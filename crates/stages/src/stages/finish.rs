@@ -5,7 +5,7 @@ use reth_primitives::{BlockNumber, Head};
 use reth_provider::Transaction;
 
 /// The [`StageId`] of the finish stage.
-pub const FINISH: StageId = StageId("Finish");
+pub const FINISH: StageId = StageId(reth_primitives::stage::FINISH);
 
 /// The finish stage.
 ///
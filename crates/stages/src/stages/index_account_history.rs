@@ -5,7 +5,7 @@ use std::fmt::Debug;
 use tracing::*;
 
 /// The [`StageId`] of the account history indexing stage.
-pub const INDEX_ACCOUNT_HISTORY: StageId = StageId("IndexAccountHistory");
+pub const INDEX_ACCOUNT_HISTORY: StageId = StageId(reth_primitives::stage::INDEX_ACCOUNT_HISTORY);
 
 /// Stage is indexing history the account changesets generated in
 /// [`ExecutionStage`][crate::stages::ExecutionStage]. For more information
@@ -0,0 +1,156 @@
+use crate::{ExecInput, ExecOutput, Stage, StageError, StageId, UnwindInput, UnwindOutput};
+use reth_db::database::Database;
+use reth_provider::Transaction;
+use std::fmt::Debug;
+use tracing::*;
+
+/// The [`StageId`] of the log indexing stage.
+pub const INDEX_LOGS: StageId = StageId(reth_primitives::stage::INDEX_LOGS);
+
+/// Stage is indexing the addresses and topics of the logs in the receipts generated in
+/// [`ExecutionStage`][crate::stages::ExecutionStage], so log queries only need to read the
+/// receipts of the blocks with matching logs.
+///
+/// The index is optional and the stage is not part of the default stage sets. For more
+/// information on index sharding take a look at [`reth_db::tables::LogAddressIndex`].
+#[derive(Debug)]
+pub struct IndexLogsStage {
+    /// Number of blocks after which the control
+    /// flow will be returned to the pipeline for commit.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexLogsStage {
+    fn default() -> Self {
+        Self { commit_threshold: 100_000 }
+    }
+}
+
+#[async_trait::async_trait]
+impl<DB: Database> Stage<DB> for IndexLogsStage {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        INDEX_LOGS
+    }
+
+    /// Execute the stage.
+    async fn execute(
+        &mut self,
+        tx: &mut Transaction<'_, DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        let (range, is_final_range) = input.next_block_range_with_threshold(self.commit_threshold);
+
+        if range.is_empty() {
+            return Ok(ExecOutput::done(*range.end()))
+        }
+
+        let (addresses, topics) = tx.get_log_indices_from_receipts(range.clone())?;
+        // Insert log addresses and topics to the index
+        tx.insert_log_index(addresses, topics)?;
+
+        info!(target: "sync::stages::index_logs", "Stage finished");
+        Ok(ExecOutput { stage_progress: *range.end(), done: is_final_range })
+    }
+
+    /// Unwind the stage.
+    async fn unwind(
+        &mut self,
+        tx: &mut Transaction<'_, DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        info!(target: "sync::stages::index_logs", to_block = input.unwind_to, "Unwinding");
+        let range = input.unwind_block_range();
+
+        tx.unwind_log_indices(range)?;
+
+        Ok(UnwindOutput { stage_progress: input.unwind_to })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::test_utils::{TestTransaction, PREV_STAGE_ID};
+    use reth_db::{
+        models::{ShardedKey, StoredBlockBodyIndices},
+        tables,
+        transaction::DbTxMut,
+        BlockNumberList,
+    };
+    use reth_primitives::{hex_literal::hex, Log, Receipt, H160, H256};
+
+    const ADDRESS: H160 = H160(hex!("0000000000000000000000000000000000000001"));
+    const TOPIC: H256 =
+        H256(hex!("0000000000000000000000000000000000000000000000000000000000000001"));
+
+    fn receipt(logs: Vec<Log>) -> Receipt {
+        Receipt { logs, ..Default::default() }
+    }
+
+    fn log(topics: Vec<H256>) -> Log {
+        Log { address: ADDRESS, topics, data: Default::default() }
+    }
+
+    fn cast<K: Ord>(table: Vec<(ShardedKey<K>, BlockNumberList)>) -> BTreeMap<K, Vec<usize>> {
+        table.into_iter().map(|(k, v)| (k.key, v.iter(0).collect())).collect()
+    }
+
+    /// Blocks 1 to 5 with one transaction each, the transactions of blocks 2 and 4 emit logs.
+    fn setup(tx: &TestTransaction) {
+        tx.commit(|tx| {
+            for block in 1..=5u64 {
+                let indices = StoredBlockBodyIndices { first_tx_num: block, tx_count: 1 };
+                tx.put::<tables::BlockBodyIndices>(block, indices)?;
+                let logs = match block {
+                    2 => vec![log(vec![TOPIC]), log(vec![])],
+                    4 => vec![log(vec![TOPIC, H256::zero()])],
+                    _ => vec![],
+                };
+                tx.put::<tables::Receipts>(block, receipt(logs))?;
+            }
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn index_logs_and_unwind() {
+        let tx = TestTransaction::default();
+        setup(&tx);
+
+        let input = ExecInput { previous_stage: Some((PREV_STAGE_ID, 5)), ..Default::default() };
+        let mut stage = IndexLogsStage::default();
+        let mut inner = tx.inner();
+        let out = stage.execute(&mut inner, input).await.unwrap();
+        assert_eq!(out, ExecOutput { stage_progress: 5, done: true });
+        inner.commit().unwrap();
+
+        // a block is listed once, no matter how many of its logs match
+        assert_eq!(
+            cast(tx.table::<tables::LogAddressIndex>().unwrap()),
+            BTreeMap::from([(ADDRESS, vec![2, 4])])
+        );
+        assert_eq!(
+            cast(tx.table::<tables::LogTopicIndex>().unwrap()),
+            BTreeMap::from([(H256::zero(), vec![4]), (TOPIC, vec![2, 4])])
+        );
+
+        let input = UnwindInput { stage_progress: 5, unwind_to: 3, ..Default::default() };
+        let mut inner = tx.inner();
+        let out = stage.unwind(&mut inner, input).await.unwrap();
+        assert_eq!(out, UnwindOutput { stage_progress: 3 });
+        inner.commit().unwrap();
+
+        assert_eq!(
+            cast(tx.table::<tables::LogAddressIndex>().unwrap()),
+            BTreeMap::from([(ADDRESS, vec![2])])
+        );
+        assert_eq!(
+            cast(tx.table::<tables::LogTopicIndex>().unwrap()),
+            BTreeMap::from([(TOPIC, vec![2])])
+        );
+    }
+}
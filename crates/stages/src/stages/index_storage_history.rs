@@ -5,7 +5,7 @@ use std::fmt::Debug;
 use tracing::*;
 
 /// The [`StageId`] of the storage history indexing stage.
-pub const INDEX_STORAGE_HISTORY: StageId = StageId("IndexStorageHistory");
+pub const INDEX_STORAGE_HISTORY: StageId = StageId(reth_primitives::stage::INDEX_STORAGE_HISTORY);
 
 /// Stage is indexing history the account changesets generated in
 /// [`ExecutionStage`][crate::stages::ExecutionStage]. For more information
@@ -5,8 +5,14 @@ use reth_db::{
     tables,
     transaction::{DbTx, DbTxMut},
 };
-use reth_interfaces::{consensus::Consensus, provider::ProviderError};
-use reth_primitives::U256;
+use reth_interfaces::{
+    consensus::{Consensus, ConsensusError},
+    provider::ProviderError,
+};
+use reth_primitives::{
+    accumulator::{HeaderAccumulator, HeaderRecord, EPOCH_SIZE},
+    BlockNumber, U256,
+};
 use reth_provider::Transaction;
 use std::sync::Arc;
 use tracing::*;
@@ -19,18 +25,24 @@ pub const TOTAL_DIFFICULTY: StageId = StageId("TotalDifficulty");
 /// This stage walks over inserted headers and computes total difficulty
 /// at each block. The entries are inserted into [`HeaderTD`][reth_db::tables::HeaderTD]
 /// table.
+///
+/// If a [HeaderAccumulator] is configured, every completed epoch of pre-merge headers is verified
+/// against the accumulator. Ranges are then aligned to epoch boundaries, so a mismatching epoch is
+/// unwound entirely.
 #[derive(Debug, Clone)]
 pub struct TotalDifficultyStage {
     /// Consensus client implementation
     consensus: Arc<dyn Consensus>,
     /// The number of table entries to commit at once
     commit_threshold: u64,
+    /// The accumulator pre-merge headers are verified against.
+    header_accumulator: Option<Arc<HeaderAccumulator>>,
 }
 
 impl TotalDifficultyStage {
     /// Create a new total difficulty stage
     pub fn new(consensus: Arc<dyn Consensus>) -> Self {
-        Self { consensus, commit_threshold: 100_000 }
+        Self { consensus, commit_threshold: 100_000, header_accumulator: None }
     }
 
     /// Set a commit threshold on total difficulty stage
@@ -38,6 +50,40 @@ impl TotalDifficultyStage {
         self.commit_threshold = commit_threshold;
         self
     }
+
+    /// Verify pre-merge headers against the given accumulator
+    pub fn with_header_accumulator(mut self, header_accumulator: Arc<HeaderAccumulator>) -> Self {
+        self.header_accumulator = Some(header_accumulator);
+        self
+    }
+}
+
+/// Verifies the epoch that ends with the given block against the accumulator.
+fn verify_epoch<DB: Database>(
+    tx: &Transaction<'_, DB>,
+    accumulator: &HeaderAccumulator,
+    last_block: BlockNumber,
+) -> Result<(), StageError> {
+    let epoch = HeaderAccumulator::epoch_of(last_block);
+    let first_block = epoch * EPOCH_SIZE;
+
+    let hashes =
+        tx.cursor_read::<tables::CanonicalHeaders>()?.walk_range(first_block..=last_block)?;
+    let tds = tx.cursor_read::<tables::HeaderTD>()?.walk_range(first_block..=last_block)?;
+    let mut records = Vec::with_capacity((last_block - first_block + 1) as usize);
+    for (hash, td) in hashes.zip(tds) {
+        let ((_, block_hash), (_, td)) = (hash?, td?);
+        records.push(HeaderRecord { block_hash, total_difficulty: td.into() });
+    }
+
+    if !accumulator.verify_epoch(epoch, &records) {
+        return Err(StageError::Validation {
+            block: first_block,
+            error: ConsensusError::HeaderAccumulatorMismatch { epoch },
+        })
+    }
+    debug!(target: "sync::stages::total_difficulty", epoch, "Verified epoch against header accumulator");
+    Ok(())
 }
 
 #[async_trait::async_trait]
@@ -53,8 +99,19 @@ impl<DB: Database> Stage<DB> for TotalDifficultyStage {
         tx: &mut Transaction<'_, DB>,
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
-        let (range, is_final_range) = input.next_block_range_with_threshold(self.commit_threshold);
-        let (start_block, end_block) = range.clone().into_inner();
+        let (range, mut is_final_range) =
+            input.next_block_range_with_threshold(self.commit_threshold);
+        let (start_block, mut end_block) = range.into_inner();
+
+        // end the range with a complete epoch, so epochs are never split across ranges
+        if let Some(accumulator) = &self.header_accumulator {
+            let epoch_end = (end_block + 1) / EPOCH_SIZE * EPOCH_SIZE;
+            if accumulator.covers(end_block) && epoch_end > start_block && epoch_end <= end_block {
+                end_block = epoch_end - 1;
+                is_final_range = false;
+            }
+        }
+        let range = start_block..=end_block;
 
         debug!(target: "sync::stages::total_difficulty", start_block, end_block, "Commencing sync");
 
@@ -71,7 +128,14 @@ impl<DB: Database> Stage<DB> for TotalDifficultyStage {
         let mut td: U256 = last_entry.1.into();
         debug!(target: "sync::stages::total_difficulty", ?td, block_number = last_header_number, "Last total difficulty entry");
 
+        // whether the previous header is a pre-merge header
+        let mut parent_is_pow = cursor_headers
+            .seek_exact(last_header_number)?
+            .map(|(_, header)| header.difficulty != U256::ZERO)
+            .unwrap_or_default();
+
         // Walk over newly inserted headers, update & insert td
+        let mut completed_epochs = Vec::new();
         for entry in cursor_headers.walk_range(range)? {
             let (block_number, header) = entry?;
             td += header.difficulty;
@@ -80,6 +144,24 @@ impl<DB: Database> Stage<DB> for TotalDifficultyStage {
                 .validate_header_with_total_difficulty(&header, td)
                 .map_err(|error| StageError::Validation { block: header.number, error })?;
             cursor_td.append(block_number, td.into())?;
+
+            // an epoch ends with every `EPOCH_SIZE`th pre-merge header and the last pre-merge
+            // header
+            let is_pow = header.difficulty != U256::ZERO;
+            if is_pow && (block_number + 1) % EPOCH_SIZE == 0 {
+                completed_epochs.push(block_number);
+            } else if parent_is_pow && !is_pow && block_number % EPOCH_SIZE != 0 {
+                completed_epochs.push(block_number - 1);
+            }
+            parent_is_pow = is_pow;
+        }
+
+        if let Some(accumulator) = &self.header_accumulator {
+            for last_block in completed_epochs {
+                if accumulator.covers(last_block) {
+                    verify_epoch(tx, accumulator, last_block)?;
+                }
+            }
         }
         info!(target: "sync::stages::total_difficulty", stage_progress = end_block, is_final_range, "Sync iteration finished");
         Ok(ExecOutput { stage_progress: end_block, done: is_final_range })
@@ -104,7 +186,7 @@ mod tests {
         generators::{random_header, random_header_range},
         TestConsensus,
     };
-    use reth_primitives::{BlockNumber, SealedHeader};
+    use reth_primitives::{Header, SealedHeader, H256};
 
     use super::*;
     use crate::test_utils::{
@@ -154,6 +236,58 @@ mod tests {
         assert!(runner.validate_execution(first_input, result.ok()).is_ok(), "validation failed");
     }
 
+    #[tokio::test]
+    async fn verify_header_accumulator() {
+        // 100 pre-merge headers followed by post-merge headers
+        let mut headers = Vec::<SealedHeader>::new();
+        for number in 0..110u64 {
+            let header = Header {
+                number,
+                parent_hash: headers.last().map(|parent| parent.hash()).unwrap_or_default(),
+                difficulty: if number < 100 { U256::from(number + 1) } else { U256::ZERO },
+                ..Default::default()
+            };
+            headers.push(header.seal_slow());
+        }
+        let mut td = U256::ZERO;
+        let records = headers[..100]
+            .iter()
+            .map(|header| {
+                td += header.difficulty;
+                HeaderRecord { block_hash: header.hash(), total_difficulty: td }
+            })
+            .collect::<Vec<_>>();
+        let input =
+            ExecInput { previous_stage: Some((PREV_STAGE_ID, 109)), stage_progress: Some(0) };
+
+        let execute = |epoch_root: H256| {
+            let tx = TestTransaction::default();
+            tx.insert_headers(headers.iter()).unwrap();
+            tx.commit(|tx| tx.put::<tables::HeaderTD>(0, headers[0].difficulty.into())).unwrap();
+
+            let accumulator = HeaderAccumulator::new(vec![epoch_root]).unwrap();
+            let mut stage = TotalDifficultyStage::new(Arc::new(TestConsensus::default()))
+                .with_header_accumulator(Arc::new(accumulator));
+            async move {
+                let mut tx = tx.inner();
+                stage.execute(&mut tx, input).await
+            }
+        };
+
+        let output = execute(HeaderAccumulator::compute_epoch_root(&records)).await.unwrap();
+        assert_eq!(output, ExecOutput { stage_progress: 109, done: true });
+
+        let mut tampered = records;
+        tampered[50].total_difficulty += U256::from(1);
+        assert!(matches!(
+            execute(HeaderAccumulator::compute_epoch_root(&tampered)).await,
+            Err(StageError::Validation {
+                block: 0,
+                error: ConsensusError::HeaderAccumulatorMismatch { epoch: 0 }
+            })
+        ));
+    }
+
     struct TotalDifficultyTestRunner {
         tx: TestTransaction,
         consensus: Arc<TestConsensus>,
@@ -181,6 +315,7 @@ mod tests {
             TotalDifficultyStage {
                 consensus: self.consensus.clone(),
                 commit_threshold: self.commit_threshold,
+                header_accumulator: None,
             }
         }
     }
@@ -49,6 +49,7 @@ impl_compression_for_compact!(
 );
 impl_compression_for_compact!(AccountBeforeTx, TransactionSignedNoHash);
 impl_compression_for_compact!(CompactU256);
+impl_compression_for_compact!(PruneCheckpoint);
 
 macro_rules! impl_compression_fixed_compact {
     ($($name:tt),+) => {
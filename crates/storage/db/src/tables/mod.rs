@@ -34,8 +34,8 @@ use crate::{
 };
 use reth_primitives::{
     trie::{BranchNodeCompact, StorageTrieEntry, StoredNibbles, StoredNibblesSubKey},
-    Account, Address, BlockHash, BlockNumber, Bytecode, Header, IntegerList, Receipt, StorageEntry,
-    TransactionSignedNoHash, TxHash, TxNumber, H256,
+    Account, Address, BlockHash, BlockNumber, Bytecode, Header, IntegerList, PruneCheckpoint,
+    PruneSegment, Receipt, StorageEntry, TransactionSignedNoHash, TxHash, TxNumber, H256,
 };
 
 /// Enum for the types of tables present in libmdbx.
@@ -48,7 +48,7 @@ pub enum TableType {
 }
 
 /// Number of tables that should be present inside database.
-pub const NUM_TABLES: usize = 25;
+pub const NUM_TABLES: usize = 28;
 
 /// Default tables that should be present inside database.
 pub const TABLES: [(TableType, &str); NUM_TABLES] = [
@@ -77,6 +77,9 @@ pub const TABLES: [(TableType, &str); NUM_TABLES] = [
     (TableType::Table, TxSenders::const_name()),
     (TableType::Table, SyncStage::const_name()),
     (TableType::Table, SyncStageProgress::const_name()),
+    (TableType::Table, LogAddressIndex::const_name()),
+    (TableType::Table, LogTopicIndex::const_name()),
+    (TableType::Table, PruneCheckpoints::const_name()),
 ];
 
 #[macro_export]
@@ -308,6 +311,31 @@ table!(
     ( SyncStageProgress ) StageId | Vec<u8>
 );
 
+table!(
+    /// Stores pointers to the blocks with logs emitted by each address.
+    ///
+    /// The block numbers are sharded like the ones of [`AccountHistory`], the last shard of an
+    /// address has the `u64::MAX` key.
+    ///
+    /// The table is only filled if the optional log index is enabled.
+    ( LogAddressIndex ) ShardedKey<Address> | BlockNumberList
+);
+
+table!(
+    /// Stores pointers to the blocks with logs that have each topic, at any position.
+    ///
+    /// The block numbers are sharded like the ones of [`AccountHistory`], the last shard of a
+    /// topic has the `u64::MAX` key.
+    ///
+    /// The table is only filled if the optional log index is enabled.
+    ( LogTopicIndex ) ShardedKey<H256> | BlockNumberList
+);
+
+table!(
+    /// Stores the progress of the most recent prune run of each segment.
+    ( PruneCheckpoints ) PruneSegment | PruneCheckpoint
+);
+
 /// Alias Types
 
 /// List with transaction numbers.
@@ -6,7 +6,7 @@ use crate::{
 use reth_codecs::Compact;
 use reth_primitives::{
     trie::{StoredNibbles, StoredNibblesSubKey},
-    Address, H256,
+    Address, PruneSegment, H256,
 };
 
 pub mod accounts;
@@ -99,6 +99,26 @@ impl Decode for String {
     }
 }
 
+impl Encode for PruneSegment {
+    type Encoded = [u8; 1];
+    fn encode(self) -> Self::Encoded {
+        match self {
+            PruneSegment::Receipts => [0],
+            PruneSegment::History => [1],
+        }
+    }
+}
+
+impl Decode for PruneSegment {
+    fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, Error> {
+        match value.as_ref() {
+            [0] => Ok(PruneSegment::Receipts),
+            [1] => Ok(PruneSegment::History),
+            _ => Err(Error::DecodeError),
+        }
+    }
+}
+
 impl Encode for StoredNibbles {
     type Encoded = Vec<u8>;
 
@@ -11,18 +11,19 @@
 /// Various provider traits.
 mod traits;
 pub use traits::{
-    AccountProvider, BlockExecutor, BlockHashProvider, BlockIdProvider, BlockProvider, BlockSource,
-    BlockchainTreePendingStateProvider, CanonStateNotification, CanonStateNotificationSender,
-    CanonStateNotifications, CanonStateSubscriptions, EvmEnvProvider, ExecutorFactory,
-    HeaderProvider, PostStateDataProvider, ReceiptProvider, StateProvider, StateProviderBox,
+    AccountHistoryProvider, AccountProvider, BlockExecutor, BlockHashProvider, BlockIdProvider,
+    BlockProvider, BlockSource, BlockchainTreePendingStateProvider, CanonStateNotification,
+    CanonStateNotificationSender, CanonStateNotifications, CanonStateSubscriptions, EvmEnvProvider,
+    ExecutorFactory, HeaderProvider, LogIndexProvider, PostStateDataProvider, PruneProvider,
+    ReceiptProvider, StageCheckpointProvider, StateProvider, StateProviderBox,
     StateProviderFactory, StateRootProvider, TransactionsProvider, WithdrawalsProvider,
 };
 
 /// Provider trait implementations.
 pub mod providers;
 pub use providers::{
-    HistoricalStateProvider, HistoricalStateProviderRef, LatestStateProvider,
-    LatestStateProviderRef, ShareableDatabase,
+    HistoricalStateProvider, HistoricalStateProviderRef, HistoryIndexCheckpoints,
+    LatestStateProvider, LatestStateProviderRef, ShareableDatabase,
 };
 
 /// Execution result
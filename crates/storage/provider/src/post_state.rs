@@ -1399,5 +1399,25 @@ mod tests {
                     .map(|(address, (account, storage))| (address, (account, storage.into_iter())))
             )
         );
+
+        // clear slot 3 in account 2, the cleared slot is removed from the storage trie
+        let slot_3 = U256::from(3);
+        let slot_3_key = H256(slot_3.to_be_bytes());
+        let address_2_slot_3_old_value =
+            state.get_mut(&address_2).unwrap().1.remove(&slot_3_key).unwrap();
+        post_state.change_storage(
+            block_number,
+            address_2,
+            BTreeMap::from([(slot_3, (address_2_slot_3_old_value, U256::ZERO))]),
+        );
+        assert_eq!(
+            post_state.state_root_slow(&tx).unwrap(),
+            state_root(
+                state
+                    .clone()
+                    .into_iter()
+                    .map(|(address, (account, storage))| (address, (account, storage.into_iter())))
+            )
+        );
     }
 }
@@ -1,21 +1,38 @@
 use crate::{
-    providers::state::{historical::HistoricalStateProvider, latest::LatestStateProvider},
+    providers::state::{
+        historical::{ensure_history_index_lag, HistoricalStateProvider, HistoryIndexCheckpoints},
+        latest::LatestStateProvider,
+    },
     traits::{BlockSource, ReceiptProvider},
-    BlockHashProvider, BlockIdProvider, BlockProvider, EvmEnvProvider, HeaderProvider,
-    ProviderError, StateProviderBox, TransactionsProvider, WithdrawalsProvider,
+    AccountHistoryProvider, BlockHashProvider, BlockIdProvider, BlockProvider, EvmEnvProvider,
+    HeaderProvider, LogIndexProvider, ProviderError, PruneProvider, StageCheckpointProvider,
+    StateProviderBox, TransactionsProvider, WithdrawalsProvider,
+};
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+    database::Database,
+    models::{BlockNumberAddress, ShardedKey},
+    table::Table,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    BlockNumberList,
 };
-use reth_db::{cursor::DbCursorRO, database::Database, tables, transaction::DbTx};
 use reth_interfaces::Result;
 use reth_primitives::{
-    Block, BlockHash, BlockId, BlockNumber, ChainInfo, ChainSpec, Hardfork, Head, Header, Receipt,
-    SealedBlock, TransactionMeta, TransactionSigned, TxHash, TxNumber, Withdrawal, H256, U256,
+    stage, Account, Address, Block, BlockHash, BlockId, BlockNumber, ChainInfo, ChainSpec,
+    Hardfork, Head, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock, TransactionMeta,
+    TransactionSigned, TxHash, TxNumber, Withdrawal, H256, U256,
 };
 use reth_revm_primitives::{
     config::revm_spec,
     env::{fill_block_env, fill_cfg_and_block_env, fill_cfg_env},
     primitives::{BlockEnv, CfgEnv, SpecId},
 };
-use std::{ops::RangeBounds, sync::Arc};
+use std::{
+    ops::{RangeBounds, RangeInclusive},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use tracing::trace;
 
 /// A common provider that fetches data from a database.
@@ -60,11 +77,21 @@ impl<DB: Database> ShareableDatabase<DB> {
             return Ok(Box::new(LatestStateProvider::new(tx)))
         }
 
+        // the state of non-canonical blocks is unknown
+        if tx.get::<tables::CanonicalHeaders>(block_number)?.is_none() {
+            return Err(ProviderError::CanonicalHeader { block_number }.into())
+        }
+        ensure_state_history_available(&tx, block_number)?;
+        let checkpoints = history_index_checkpoints(&tx)?;
+
         // +1 as the changeset that we want is the one that was applied after this block.
         block_number += 1;
 
         trace!(target: "providers::db", ?block_number, "Returning historical state provider for block number");
-        Ok(Box::new(HistoricalStateProvider::new(tx, block_number)))
+        Ok(Box::new(
+            HistoricalStateProvider::new(tx, block_number)
+                .with_history_index_checkpoints(checkpoints),
+        ))
     }
 
     /// Storage provider for state at that given block hash
@@ -78,13 +105,18 @@ impl<DB: Database> ShareableDatabase<DB> {
         if is_latest_block_number(&tx, block_number)? {
             return Ok(Box::new(LatestStateProvider::new(tx)))
         }
+        ensure_state_history_available(&tx, block_number)?;
+        let checkpoints = history_index_checkpoints(&tx)?;
 
         // +1 as the changeset that we want is the one that was applied after this block.
         // as the  changeset contains old values.
         block_number += 1;
 
         trace!(target: "providers::db", ?block_hash, "Returning historical state provider for block hash");
-        Ok(Box::new(HistoricalStateProvider::new(tx, block_number)))
+        Ok(Box::new(
+            HistoricalStateProvider::new(tx, block_number)
+                .with_history_index_checkpoints(checkpoints),
+        ))
     }
 }
 
@@ -160,6 +192,14 @@ impl<DB: Database> BlockIdProvider for ShareableDatabase<DB> {
         Ok(self.db.view(|tx| best_block_number(tx))??.unwrap_or_default())
     }
 
+    fn last_block_number(&self) -> Result<BlockNumber> {
+        Ok(self
+            .db
+            .view(|tx| tx.cursor_read::<tables::CanonicalHeaders>()?.last())??
+            .map(|(number, _)| number)
+            .unwrap_or_default())
+    }
+
     fn block_number(&self, hash: H256) -> Result<Option<BlockNumber>> {
         self.db.view(|tx| tx.get::<tables::HeaderNumbers>(hash))?.map_err(Into::into)
     }
@@ -205,9 +245,16 @@ impl<DB: Database> BlockProvider for ShareableDatabase<DB> {
     fn ommers(&self, id: BlockId) -> Result<Option<Vec<Header>>> {
         if let Some(number) = self.block_number_for_id(id)? {
             let tx = self.db.tx()?;
-            // TODO: this can be optimized to return empty Vec post-merge
+            if tx.get::<tables::CanonicalHeaders>(number)?.is_none() {
+                return Ok(None)
+            }
+            // there are no ommers after the merge
+            if self.chain_spec.fork(Hardfork::Paris).active_at_block(number) {
+                return Ok(Some(Vec::new()))
+            }
+            // ommers are only stored for blocks that have any
             let ommers = tx.get::<tables::BlockOmmers>(number)?.map(|o| o.ommers);
-            return Ok(ommers)
+            return Ok(Some(ommers.unwrap_or_default()))
         }
 
         Ok(None)
@@ -405,6 +452,205 @@ impl<DB: Database> WithdrawalsProvider for ShareableDatabase<DB> {
     }
 }
 
+impl<DB: Database> StageCheckpointProvider for ShareableDatabase<DB> {
+    fn stage_checkpoint(&self, id: &str) -> Result<Option<BlockNumber>> {
+        self.db.view(|tx| tx.get::<tables::SyncStage>(id.to_string()))?.map_err(Into::into)
+    }
+
+    fn stage_checkpoints(&self) -> Result<Vec<(String, BlockNumber)>> {
+        self.db
+            .view(|tx| {
+                let mut cursor = tx.cursor_read::<tables::SyncStage>()?;
+                cursor
+                    .walk(None)?
+                    .map(|result| result.map_err(Into::into))
+                    .collect::<Result<Vec<_>>>()
+            })?
+            .map_err(Into::into)
+    }
+}
+
+impl<DB: Database> LogIndexProvider for ShareableDatabase<DB> {
+    fn log_index_checkpoint(&self) -> Result<Option<BlockNumber>> {
+        self.db
+            .view(|tx| tx.get::<tables::SyncStage>(stage::INDEX_LOGS.to_string()))?
+            .map_err(Into::into)
+    }
+
+    fn blocks_with_log_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        self.db
+            .view(|tx| {
+                blocks_in_sharded_index::<tables::LogAddressIndex, _, _>(tx, address, range)
+            })?
+            .map_err(Into::into)
+    }
+
+    fn blocks_with_log_topic(
+        &self,
+        topic: H256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        self.db
+            .view(|tx| blocks_in_sharded_index::<tables::LogTopicIndex, _, _>(tx, topic, range))?
+            .map_err(Into::into)
+    }
+}
+
+impl<DB: Database> AccountHistoryProvider for ShareableDatabase<DB> {
+    fn blocks_with_account_changes(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        self.db.view(|tx| -> Result<_> {
+            let checkpoint = history_index_checkpoints(tx)?.account_history.unwrap_or_default();
+            let mut blocks = if *range.start() <= checkpoint {
+                let indexed = *range.start()..=checkpoint.min(*range.end());
+                blocks_in_sharded_index::<tables::AccountHistory, _, _>(tx, address, indexed)?
+            } else {
+                Vec::new()
+            };
+
+            // the changesets of the blocks after the checkpoint aren't indexed yet
+            let mut cursor = tx.cursor_dup_read::<tables::AccountChangeSet>()?;
+            let Some((last_block, _)) = cursor.last()? else { return Ok(blocks) };
+            let unindexed = (*range.start()).max(checkpoint + 1)..=(*range.end()).min(last_block);
+            if unindexed.is_empty() {
+                return Ok(blocks)
+            }
+            ensure_history_index_lag(checkpoint, *unindexed.start(), *unindexed.end())?;
+            for block in unindexed {
+                if cursor
+                    .seek_by_key_subkey(block, address)?
+                    .filter(|entry| entry.address == address)
+                    .is_some()
+                {
+                    blocks.push(block);
+                }
+            }
+            Ok(blocks)
+        })?
+    }
+
+    fn blocks_with_storage_changes(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        self.db.view(|tx| -> Result<_> {
+            let mut cursor = tx.cursor_dup_read::<tables::StorageChangeSet>()?;
+            let Some((last, _)) = cursor.last()? else { return Ok(Vec::new()) };
+            let mut blocks = Vec::new();
+            for block in *range.start()..=(*range.end()).min(last.block_number()) {
+                if cursor.seek_exact(BlockNumberAddress((block, address)))?.is_some() {
+                    blocks.push(block);
+                }
+            }
+            Ok(blocks)
+        })?
+    }
+
+    fn account_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Vec<(Address, Option<Account>)>> {
+        self.db
+            .view(|tx| {
+                tx.cursor_dup_read::<tables::AccountChangeSet>()?
+                    .walk_dup(Some(block_number), None)?
+                    .map(|entry| entry.map(|(_, before)| (before.address, before.info)))
+                    .collect::<std::result::Result<Vec<_>, _>>()
+            })?
+            .map_err(Into::into)
+    }
+}
+
+impl<DB: Database> PruneProvider for ShareableDatabase<DB> {
+    fn lowest_available_block(&self, segment: PruneSegment) -> Result<Option<BlockNumber>> {
+        self.db.view(|tx| {
+            match segment {
+                PruneSegment::Receipts => {
+                    let first_tx_num =
+                        tx.cursor_read::<tables::Receipts>()?.first()?.map(|(tx_num, _)| tx_num);
+                    match first_tx_num {
+                        // the block of a transaction is keyed by the last transaction of the block
+                        Some(tx_num) => Ok(tx
+                            .cursor_read::<tables::TransactionBlock>()?
+                            .seek(tx_num)?
+                            .map(|(_, block)| block)),
+                        None => Ok(None),
+                    }
+                }
+                PruneSegment::History => {
+                    // blocks without changes have no changeset, so the checkpoint is the lower
+                    // bound even if the first changeset is above it
+                    let first_block = tx
+                        .cursor_read::<tables::AccountChangeSet>()?
+                        .first()?
+                        .map(|(block, _)| block);
+                    let pruned = tx.get::<tables::PruneCheckpoints>(segment)?.map(|c| c.to_block);
+                    Ok(first_block.max(pruned))
+                }
+            }
+        })?
+    }
+
+    fn prune_checkpoint(&self, segment: PruneSegment) -> Result<Option<PruneCheckpoint>> {
+        Ok(self.db.view(|tx| tx.get::<tables::PruneCheckpoints>(segment))??)
+    }
+
+    fn prune(
+        &self,
+        segment: PruneSegment,
+        to_block: BlockNumber,
+        batch_size: usize,
+    ) -> Result<PruneCheckpoint> {
+        let to_tx_num = self
+            .db
+            .view(|tx| tx.get::<tables::BlockBodyIndices>(to_block))??
+            .map(|body| body.first_tx_num());
+
+        // continue an interrupted run to the same block
+        let mut checkpoint = match self.prune_checkpoint(segment)? {
+            Some(checkpoint) if checkpoint.to_block == to_block && !checkpoint.finished => {
+                checkpoint
+            }
+            _ => PruneCheckpoint { to_block, ..Default::default() },
+        };
+
+        loop {
+            let tx = self.db.tx_mut()?;
+            let (deleted, finished) = match segment {
+                PruneSegment::Receipts => match to_tx_num {
+                    Some(to_tx_num) => {
+                        prune_while::<tables::Receipts, _>(&tx, batch_size, |tx_num| {
+                            *tx_num < to_tx_num
+                        })?
+                    }
+                    None => (0, true),
+                },
+                PruneSegment::History => prune_history(&tx, to_block, batch_size)?,
+            };
+            checkpoint.pruned += deleted as u64;
+            checkpoint.timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            checkpoint.finished = finished;
+            tx.put::<tables::PruneCheckpoints>(segment, checkpoint)?;
+            tx.commit()?;
+
+            if finished {
+                return Ok(checkpoint)
+            }
+        }
+    }
+}
+
 impl<DB: Database> EvmEnvProvider for ShareableDatabase<DB> {
     fn fill_env_at(&self, cfg: &mut CfgEnv, block_env: &mut BlockEnv, at: BlockId) -> Result<()> {
         let hash = self.block_hash_for_id(at)?.ok_or(ProviderError::HeaderNotFound)?;
@@ -488,7 +734,133 @@ fn best_block_number<'a, TX>(
 where
     TX: DbTx<'a> + Send + Sync,
 {
-    tx.get::<tables::SyncStage>("Finish".to_string())
+    tx.get::<tables::SyncStage>(stage::FINISH.to_string())
+}
+
+/// Fetches the blocks covered by the history indices from the checkpoints of the stages that
+/// build them.
+///
+/// The indices lag behind the changesets until these stages caught up with the last executed
+/// block.
+#[inline]
+fn history_index_checkpoints<'a, TX>(
+    tx: &TX,
+) -> std::result::Result<HistoryIndexCheckpoints, reth_interfaces::db::Error>
+where
+    TX: DbTx<'a> + Send + Sync,
+{
+    Ok(HistoryIndexCheckpoints {
+        account_history: Some(
+            tx.get::<tables::SyncStage>(stage::INDEX_ACCOUNT_HISTORY.to_string())?
+                .unwrap_or_default(),
+        ),
+        storage_history: Some(
+            tx.get::<tables::SyncStage>(stage::INDEX_STORAGE_HISTORY.to_string())?
+                .unwrap_or_default(),
+        ),
+    })
+}
+
+/// Returns the blocks in the range that are listed in the shards of the key, in ascending order.
+fn blocks_in_sharded_index<'a, T, K, TX>(
+    tx: &TX,
+    key: K,
+    range: RangeInclusive<BlockNumber>,
+) -> std::result::Result<Vec<BlockNumber>, reth_interfaces::db::Error>
+where
+    T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+    K: PartialEq + Clone,
+    TX: DbTx<'a>,
+{
+    let mut blocks = Vec::new();
+    let mut cursor = tx.cursor_read::<T>()?;
+    // the first shard whose highest block is in the range
+    let mut shard = cursor.seek(ShardedKey::new(key.clone(), *range.start()))?;
+    while let Some((sharded_key, list)) = shard {
+        if sharded_key.key != key {
+            break
+        }
+        for block in list.iter(0).map(|block| block as BlockNumber) {
+            if block > *range.end() {
+                return Ok(blocks)
+            }
+            if block >= *range.start() {
+                blocks.push(block);
+            }
+        }
+        shard = cursor.next()?;
+    }
+    Ok(blocks)
+}
+
+/// Deletes at most `limit` entries from the start of a table as long as their key is prunable.
+///
+/// Returns the number of deleted entries and whether all prunable entries are deleted.
+fn prune_while<'a, T, TX>(
+    tx: &TX,
+    limit: usize,
+    is_prunable: impl Fn(&T::Key) -> bool,
+) -> std::result::Result<(usize, bool), reth_interfaces::db::Error>
+where
+    T: Table,
+    TX: DbTxMut<'a> + DbTx<'a>,
+{
+    let mut cursor = tx.cursor_write::<T>()?;
+    let mut walker = cursor.walk(None)?;
+    let mut deleted = 0;
+    while let Some((key, _)) = walker.next().transpose()? {
+        if !is_prunable(&key) {
+            return Ok((deleted, true))
+        }
+        if deleted == limit.max(1) {
+            return Ok((deleted, false))
+        }
+        walker.delete_current()?;
+        deleted += 1;
+    }
+    Ok((deleted, true))
+}
+
+/// Deletes at most `limit` account and storage changesets of the blocks below `to_block`, the
+/// account changesets first.
+///
+/// The history indices still list the pruned blocks, the historical state providers refuse the
+/// blocks below the [PruneSegment::History] checkpoint before the indices are read.
+fn prune_history<'a, TX>(
+    tx: &TX,
+    to_block: BlockNumber,
+    limit: usize,
+) -> std::result::Result<(usize, bool), reth_interfaces::db::Error>
+where
+    TX: DbTxMut<'a> + DbTx<'a>,
+{
+    let (deleted, finished) =
+        prune_while::<tables::AccountChangeSet, _>(tx, limit, |block| *block < to_block)?;
+    if deleted > 0 || !finished {
+        return Ok((deleted, false))
+    }
+    prune_while::<tables::StorageChangeSet, _>(tx, limit, |key| key.block_number() < to_block)
+}
+
+/// Returns an error if the changesets that restore the state of the block were pruned or never
+/// imported.
+///
+/// The checkpoint is set when a prune run starts, so the blocks of an unfinished run are refused
+/// too.
+fn ensure_state_history_available<'a, TX>(tx: &TX, block_number: BlockNumber) -> Result<()>
+where
+    TX: DbTx<'a> + Send + Sync,
+{
+    if let Some(checkpoint) = tx.get::<tables::PruneCheckpoints>(PruneSegment::History)? {
+        // the state of a block is restored from the changesets of the blocks after it
+        let lowest_available_block = checkpoint.to_block.saturating_sub(1);
+        if block_number < lowest_available_block {
+            return Err(
+                ProviderError::StateHistoryPruned { block_number, lowest_available_block }.into()
+            )
+        }
+    }
+    Ok(())
 }
 
 /// Fetches the last canonical header from the database.
@@ -505,9 +877,25 @@ where
 #[cfg(test)]
 mod tests {
     use super::ShareableDatabase;
-    use crate::BlockIdProvider;
-    use reth_db::mdbx::{test_utils::create_test_db, EnvKind, WriteMap};
-    use reth_primitives::{ChainSpecBuilder, H256};
+    use crate::{
+        providers::state::historical::MAX_UNINDEXED_HISTORY_BLOCKS, AccountHistoryProvider,
+        BlockIdProvider, BlockProvider, LogIndexProvider, ProviderError, PruneProvider,
+    };
+    use reth_db::{
+        database::Database,
+        mdbx::{test_utils::create_test_db, EnvKind, WriteMap},
+        models::{
+            AccountBeforeTx, BlockNumberAddress, ShardedKey, StoredBlockBodyIndices,
+            StoredBlockOmmers,
+        },
+        tables,
+        transaction::DbTxMut,
+        BlockNumberList,
+    };
+    use reth_primitives::{
+        stage, Address, BlockId, ChainSpecBuilder, Header, PruneCheckpoint, PruneSegment, Receipt,
+        StorageEntry, H256, U256,
+    };
     use std::sync::Arc;
 
     #[test]
@@ -518,6 +906,168 @@ mod tests {
         let _ = provider.latest();
     }
 
+    #[test]
+    fn history_provider_rejects_unknown_block() {
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        db.update(|tx| {
+            for block in 0..=2u64 {
+                tx.put::<tables::CanonicalHeaders>(block, H256::from_low_u64_be(block)).unwrap();
+            }
+            tx.put::<tables::SyncStage>(stage::FINISH.to_string(), 2).unwrap();
+        })
+        .unwrap();
+        let provider = ShareableDatabase::new(db, Arc::new(chain_spec));
+
+        assert!(provider.history_by_block_number(1).is_ok());
+        assert_eq!(
+            provider.history_by_block_number(3).err(),
+            Some(ProviderError::CanonicalHeader { block_number: 3 }.into())
+        );
+    }
+
+    #[test]
+    fn ommers_of_stored_blocks() {
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let ommer = Header { number: 1, ..Default::default() };
+        db.update(|tx| {
+            for block in 0..=2u64 {
+                tx.put::<tables::CanonicalHeaders>(block, H256::from_low_u64_be(block)).unwrap();
+            }
+            tx.put::<tables::BlockOmmers>(2, StoredBlockOmmers { ommers: vec![ommer.clone()] })
+                .unwrap();
+        })
+        .unwrap();
+        let provider = ShareableDatabase::new(db, Arc::new(chain_spec));
+
+        assert_eq!(provider.ommers(BlockId::Number(1.into())).unwrap(), Some(vec![]));
+        assert_eq!(provider.ommers(BlockId::Number(2.into())).unwrap(), Some(vec![ommer]));
+        assert_eq!(provider.ommers(BlockId::Number(3.into())).unwrap(), None);
+    }
+
+    #[test]
+    fn log_index_lookup() {
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let address = Address::from_low_u64_be(1);
+        db.update(|tx| {
+            for (address, highest_block_number, blocks) in [
+                (address, 5, vec![1, 3, 5]),
+                (address, u64::MAX, vec![7, 9]),
+                (Address::from_low_u64_be(2), u64::MAX, vec![4]),
+            ] {
+                tx.put::<tables::LogAddressIndex>(
+                    ShardedKey::new(address, highest_block_number),
+                    BlockNumberList::new(blocks).unwrap(),
+                )
+                .unwrap();
+            }
+        })
+        .unwrap();
+        let provider = ShareableDatabase::new(db, Arc::new(chain_spec));
+
+        // the logs aren't indexed until the stage ran
+        assert_eq!(provider.log_index_checkpoint().unwrap(), None);
+
+        assert_eq!(provider.blocks_with_log_address(address, 2..=7).unwrap(), vec![3, 5, 7]);
+        assert_eq!(provider.blocks_with_log_address(address, 6..=6).unwrap(), vec![]);
+        assert_eq!(provider.blocks_with_log_address(address, 8..=100).unwrap(), vec![9]);
+        assert_eq!(
+            provider.blocks_with_log_address(Address::from_low_u64_be(3), 0..=100).unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn account_history_lookup() {
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let address = Address::from_low_u64_be(1);
+        db.update(|tx| {
+            // the history is indexed up to block 5
+            tx.put::<tables::SyncStage>(stage::INDEX_ACCOUNT_HISTORY.to_string(), 5).unwrap();
+            tx.put::<tables::AccountHistory>(
+                ShardedKey::new(address, u64::MAX),
+                BlockNumberList::new([2, 4]).unwrap(),
+            )
+            .unwrap();
+            for (block, address) in
+                [(2, address), (4, address), (7, address), (8, Address::from_low_u64_be(2))]
+            {
+                tx.put::<tables::AccountChangeSet>(block, AccountBeforeTx { address, info: None })
+                    .unwrap();
+            }
+        })
+        .unwrap();
+        let provider = ShareableDatabase::new(db, Arc::new(chain_spec));
+
+        assert_eq!(provider.blocks_with_account_changes(address, 0..=100).unwrap(), vec![2, 4, 7]);
+        assert_eq!(provider.blocks_with_account_changes(address, 3..=6).unwrap(), vec![4]);
+        assert_eq!(provider.blocks_with_account_changes(address, 6..=8).unwrap(), vec![7]);
+
+        assert_eq!(provider.account_block_changeset(7).unwrap(), vec![(address, None)]);
+        assert_eq!(provider.account_block_changeset(5).unwrap(), vec![]);
+
+        // too many blocks after the checkpoint would have to be looked up in the changesets
+        let last_block_number = 5 + MAX_UNINDEXED_HISTORY_BLOCKS + 1;
+        provider
+            .db
+            .update(|tx| {
+                tx.put::<tables::AccountChangeSet>(
+                    last_block_number,
+                    AccountBeforeTx { address, info: None },
+                )
+            })
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            provider.blocks_with_account_changes(address, 0..=u64::MAX).err(),
+            Some(
+                ProviderError::HistoryIndexLag {
+                    checkpoint: 5,
+                    last_block_number,
+                    max_unindexed: MAX_UNINDEXED_HISTORY_BLOCKS
+                }
+                .into()
+            )
+        );
+        assert_eq!(provider.blocks_with_account_changes(address, 0..=8).unwrap(), vec![2, 4, 7]);
+    }
+
+    #[test]
+    fn storage_history_lookup() {
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let address = Address::from_low_u64_be(1);
+        db.update(|tx| {
+            for (block, address, key) in [
+                (2, address, H256::from_low_u64_be(1)),
+                (2, address, H256::from_low_u64_be(2)),
+                (5, Address::from_low_u64_be(2), H256::from_low_u64_be(1)),
+                (6, address, H256::from_low_u64_be(3)),
+            ] {
+                tx.put::<tables::StorageChangeSet>(
+                    BlockNumberAddress((block, address)),
+                    StorageEntry { key, value: U256::from(1) },
+                )
+                .unwrap();
+            }
+        })
+        .unwrap();
+        let provider = ShareableDatabase::new(db, Arc::new(chain_spec));
+
+        assert_eq!(
+            provider.blocks_with_storage_changes(address, 0..=u64::MAX).unwrap(),
+            vec![2, 6]
+        );
+        assert_eq!(provider.blocks_with_storage_changes(address, 3..=5).unwrap(), vec![]);
+        assert_eq!(
+            provider.blocks_with_storage_changes(Address::from_low_u64_be(2), 0..=10).unwrap(),
+            vec![5]
+        );
+    }
+
     #[test]
     fn default_chain_info() {
         let chain_spec = ChainSpecBuilder::mainnet().build();
@@ -530,4 +1080,109 @@ mod tests {
         assert_eq!(chain_info.last_finalized, None);
         assert_eq!(chain_info.safe_finalized, None);
     }
+
+    #[test]
+    fn prune_receipts() {
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+
+        // 4 blocks with 2 transactions each
+        db.update(|tx| {
+            for block in 0..4u64 {
+                let indices = StoredBlockBodyIndices { first_tx_num: block * 2, tx_count: 2 };
+                for tx_num in indices.tx_num_range() {
+                    tx.put::<tables::Receipts>(tx_num, Receipt::default()).unwrap();
+                }
+                tx.put::<tables::TransactionBlock>(indices.last_tx_num(), block).unwrap();
+                tx.put::<tables::BlockBodyIndices>(block, indices).unwrap();
+            }
+        })
+        .unwrap();
+
+        let provider = ShareableDatabase::new(db, Arc::new(chain_spec));
+        assert_eq!(provider.lowest_available_block(PruneSegment::Receipts).unwrap(), Some(0));
+        assert_eq!(provider.prune_checkpoint(PruneSegment::Receipts).unwrap(), None);
+
+        // deleted in batches of 3
+        let checkpoint = provider.prune(PruneSegment::Receipts, 2, 3).unwrap();
+        assert_eq!((checkpoint.to_block, checkpoint.pruned, checkpoint.finished), (2, 4, true));
+        assert_eq!(provider.lowest_available_block(PruneSegment::Receipts).unwrap(), Some(2));
+        assert_eq!(provider.prune_checkpoint(PruneSegment::Receipts).unwrap(), Some(checkpoint));
+
+        // pruning is idempotent
+        assert_eq!(provider.prune(PruneSegment::Receipts, 2, 3).unwrap().pruned, 0);
+    }
+
+    #[test]
+    fn prune_history() {
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+        let address = Address::random();
+
+        // an account and a storage change in every block but the genesis block
+        db.update(|tx| {
+            for block in 0..5u64 {
+                tx.put::<tables::CanonicalHeaders>(block, H256::random()).unwrap();
+            }
+            for block in 1..5u64 {
+                tx.put::<tables::AccountChangeSet>(block, AccountBeforeTx { address, info: None })
+                    .unwrap();
+                tx.put::<tables::StorageChangeSet>(
+                    BlockNumberAddress((block, address)),
+                    StorageEntry::default(),
+                )
+                .unwrap();
+            }
+        })
+        .unwrap();
+
+        let provider = ShareableDatabase::new(db, Arc::new(chain_spec));
+        assert_eq!(provider.lowest_available_block(PruneSegment::History).unwrap(), Some(1));
+        assert!(provider.history_by_block_number(0).is_ok());
+
+        let checkpoint = provider.prune(PruneSegment::History, 3, 1).unwrap();
+        assert_eq!((checkpoint.to_block, checkpoint.pruned, checkpoint.finished), (3, 4, true));
+        assert_eq!(provider.lowest_available_block(PruneSegment::History).unwrap(), Some(3));
+
+        // the state of block 2 is restored from the changesets of block 3 and later
+        assert!(provider.history_by_block_number(2).is_ok());
+        assert_eq!(
+            provider.history_by_block_number(1).err(),
+            Some(
+                ProviderError::StateHistoryPruned { block_number: 1, lowest_available_block: 2 }
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn resume_interrupted_prune() {
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        let db = create_test_db::<WriteMap>(EnvKind::RW);
+
+        db.update(|tx| {
+            for tx_num in 0..4 {
+                tx.put::<tables::Receipts>(tx_num, Receipt::default()).unwrap();
+            }
+            tx.put::<tables::TransactionBlock>(3, 0).unwrap();
+            tx.put::<tables::BlockBodyIndices>(
+                1,
+                StoredBlockBodyIndices { first_tx_num: 4, tx_count: 0 },
+            )
+            .unwrap();
+            // a run that deleted the first receipt and was interrupted
+            tx.delete::<tables::Receipts>(0, None).unwrap();
+            tx.put::<tables::PruneCheckpoints>(
+                PruneSegment::Receipts,
+                PruneCheckpoint { to_block: 1, pruned: 1, timestamp: 0, finished: false },
+            )
+            .unwrap();
+        })
+        .unwrap();
+
+        let provider = ShareableDatabase::new(db, Arc::new(chain_spec));
+        let checkpoint = provider.prune(PruneSegment::Receipts, 1, 1).unwrap();
+        assert_eq!((checkpoint.pruned, checkpoint.finished), (4, true));
+        assert_eq!(provider.lowest_available_block(PruneSegment::Receipts).unwrap(), None);
+    }
 }
@@ -1,7 +1,8 @@
 use crate::{
-    BlockHashProvider, BlockIdProvider, BlockProvider, BlockchainTreePendingStateProvider,
-    CanonStateNotifications, CanonStateSubscriptions, EvmEnvProvider, HeaderProvider,
-    PostStateDataProvider, ReceiptProvider, StateProviderBox, StateProviderFactory,
+    AccountHistoryProvider, BlockHashProvider, BlockIdProvider, BlockProvider,
+    BlockchainTreePendingStateProvider, CanonStateNotifications, CanonStateSubscriptions,
+    EvmEnvProvider, HeaderProvider, LogIndexProvider, PostStateDataProvider, PruneProvider,
+    ReceiptProvider, StageCheckpointProvider, StateProviderBox, StateProviderFactory,
     TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::database::Database;
@@ -10,18 +11,18 @@ use reth_interfaces::{
     Result,
 };
 use reth_primitives::{
-    Block, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag, ChainInfo, Header,
-    Receipt, SealedBlock, SealedBlockWithSenders, TransactionMeta, TransactionSigned, TxHash,
-    TxNumber, Withdrawal, H256, U256,
+    Account, Address, Block, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag,
+    ChainInfo, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders,
+    TransactionMeta, TransactionSigned, TxHash, TxNumber, Withdrawal, H256, U256,
 };
 use reth_revm_primitives::primitives::{BlockEnv, CfgEnv};
 pub use state::{
-    historical::{HistoricalStateProvider, HistoricalStateProviderRef},
+    historical::{HistoricalStateProvider, HistoricalStateProviderRef, HistoryIndexCheckpoints},
     latest::{LatestStateProvider, LatestStateProviderRef},
 };
 use std::{
     collections::{BTreeMap, HashSet},
-    ops::RangeBounds,
+    ops::{RangeBounds, RangeInclusive},
 };
 use tracing::trace;
 
@@ -105,6 +106,10 @@ where
         self.database.best_block_number()
     }
 
+    fn last_block_number(&self) -> Result<BlockNumber> {
+        self.database.last_block_number()
+    }
+
     fn convert_block_number(&self, num: BlockNumberOrTag) -> Result<Option<BlockNumber>> {
         let num = match num {
             BlockNumberOrTag::Latest => self.chain_info()?.best_number,
@@ -251,6 +256,98 @@ where
     }
 }
 
+impl<DB, Tree> StageCheckpointProvider for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn stage_checkpoint(&self, id: &str) -> Result<Option<BlockNumber>> {
+        self.database.stage_checkpoint(id)
+    }
+
+    fn stage_checkpoints(&self) -> Result<Vec<(String, BlockNumber)>> {
+        self.database.stage_checkpoints()
+    }
+}
+
+impl<DB, Tree> AccountHistoryProvider for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn blocks_with_account_changes(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        self.database.blocks_with_account_changes(address, range)
+    }
+
+    fn blocks_with_storage_changes(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        self.database.blocks_with_storage_changes(address, range)
+    }
+
+    fn account_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Vec<(Address, Option<Account>)>> {
+        self.database.account_block_changeset(block_number)
+    }
+}
+
+impl<DB, Tree> LogIndexProvider for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn log_index_checkpoint(&self) -> Result<Option<BlockNumber>> {
+        self.database.log_index_checkpoint()
+    }
+
+    fn blocks_with_log_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        self.database.blocks_with_log_address(address, range)
+    }
+
+    fn blocks_with_log_topic(
+        &self,
+        topic: H256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        self.database.blocks_with_log_topic(topic, range)
+    }
+}
+
+impl<DB, Tree> PruneProvider for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn lowest_available_block(&self, segment: PruneSegment) -> Result<Option<BlockNumber>> {
+        self.database.lowest_available_block(segment)
+    }
+
+    fn prune_checkpoint(&self, segment: PruneSegment) -> Result<Option<PruneCheckpoint>> {
+        self.database.prune_checkpoint(segment)
+    }
+
+    fn prune(
+        &self,
+        segment: PruneSegment,
+        to_block: BlockNumber,
+        batch_size: usize,
+    ) -> Result<PruneCheckpoint> {
+        self.database.prune(segment, to_block, batch_size)
+    }
+}
+
 impl<DB, Tree> EvmEnvProvider for BlockchainProvider<DB, Tree>
 where
     DB: Database,
@@ -12,8 +12,33 @@ use reth_interfaces::Result;
 use reth_primitives::{
     Account, Address, BlockNumber, Bytecode, Bytes, StorageKey, StorageValue, H256,
 };
+use reth_trie::{
+    hashed_cursor::{HashedPostState, HashedPostStateCursorFactory},
+    Proof,
+};
 use std::marker::PhantomData;
 
+/// The maximum number of blocks whose changes are looked up in the changesets because the history
+/// indices don't cover them yet.
+///
+/// Every read of a value that didn't change in the indexed blocks seeks the changeset of each of
+/// these blocks, so reads fail while the indices lag further behind.
+pub const MAX_UNINDEXED_HISTORY_BLOCKS: u64 = 1_024;
+
+/// The highest blocks whose changes are indexed by the history tables.
+///
+/// The history indices are built from the changesets by separate stages after execution, so while
+/// they lag behind, the changes of the later blocks can only be found in the changesets.
+///
+/// `None` means that the changes of all blocks are indexed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HistoryIndexCheckpoints {
+    /// The highest block indexed by [tables::AccountHistory].
+    pub account_history: Option<BlockNumber>,
+    /// The highest block indexed by [tables::StorageHistory].
+    pub storage_history: Option<BlockNumber>,
+}
+
 /// State provider for a given transition id which takes a tx reference.
 ///
 /// Historical state provider reads the following tables:
@@ -22,11 +47,18 @@ use std::marker::PhantomData;
 /// [tables::StorageHistory]
 /// [tables::AccountChangeSet]
 /// [tables::StorageChangeSet]
+///
+/// The value of an account or a storage slot at the block is the value before its first change at
+/// or after the block, which is recorded in the changeset of that change. The first change is
+/// looked up in the history index, and for the blocks the index doesn't cover yet, in the
+/// changesets directly. Without a later change, the value is the one of the plain state.
 pub struct HistoricalStateProviderRef<'a, 'b, TX: DbTx<'a>> {
     /// Transaction
     tx: &'b TX,
     /// Block number is main index for the history state of accounts and storages.
     block_number: BlockNumber,
+    /// The blocks covered by the history indices.
+    history_index_checkpoints: HistoryIndexCheckpoints,
     /// Phantom lifetime `'a`
     _phantom: PhantomData<&'a TX>,
 }
@@ -34,12 +66,24 @@ pub struct HistoricalStateProviderRef<'a, 'b, TX: DbTx<'a>> {
 impl<'a, 'b, TX: DbTx<'a>> HistoricalStateProviderRef<'a, 'b, TX> {
     /// Create new StateProvider from history transaction number
     pub fn new(tx: &'b TX, block_number: BlockNumber) -> Self {
-        Self { tx, block_number, _phantom: PhantomData {} }
+        Self {
+            tx,
+            block_number,
+            history_index_checkpoints: Default::default(),
+            _phantom: PhantomData {},
+        }
     }
-}
-impl<'a, 'b, TX: DbTx<'a>> AccountProvider for HistoricalStateProviderRef<'a, 'b, TX> {
-    /// Get basic account information.
-    fn basic_account(&self, address: Address) -> Result<Option<Account>> {
+
+    /// Set the blocks covered by the history indices.
+    ///
+    /// The changes of later blocks are looked up in the changesets.
+    pub fn with_history_index_checkpoints(mut self, checkpoints: HistoryIndexCheckpoints) -> Self {
+        self.history_index_checkpoints = checkpoints;
+        self
+    }
+
+    /// Returns the first block at or after the provider's block that changed the account.
+    fn account_changeset_block_number(&self, address: Address) -> Result<Option<BlockNumber>> {
         // history key to search IntegerList of block number changesets.
         let history_key = ShardedKey::new(address, self.block_number);
 
@@ -48,12 +92,98 @@ impl<'a, 'b, TX: DbTx<'a>> AccountProvider for HistoricalStateProviderRef<'a, 'b
             .cursor_read::<tables::AccountHistory>()?
             .seek(history_key)?
             .filter(|(key, _)| key.key == address)
-            .map(|(_, list)| {
+            .and_then(|(_, list)| {
                 list.0.enable_rank().successor(self.block_number as usize).map(|i| i as u64)
             });
+        if changeset_block_number.is_some() {
+            return Ok(changeset_block_number)
+        }
+
+        // the changes of the blocks after the checkpoint aren't indexed yet
+        let Some(checkpoint) = self.history_index_checkpoints.account_history else {
+            return Ok(None)
+        };
+        let mut cursor = self.tx.cursor_dup_read::<tables::AccountChangeSet>()?;
+        let Some((last_block_number, _)) = cursor.last()? else { return Ok(None) };
+        let first_block_number = self.block_number.max(checkpoint + 1);
+        ensure_history_index_lag(checkpoint, first_block_number, last_block_number)?;
+        for block_number in first_block_number..=last_block_number {
+            if cursor
+                .seek_by_key_subkey(block_number, address)?
+                .filter(|acc| acc.address == address)
+                .is_some()
+            {
+                return Ok(Some(block_number))
+            }
+        }
+        Ok(None)
+    }
 
+    /// Returns the first block at or after the provider's block that changed the storage slot.
+    fn storage_changeset_block_number(
+        &self,
+        address: Address,
+        storage_key: StorageKey,
+    ) -> Result<Option<BlockNumber>> {
+        // history key to search IntegerList of block changesets.
+        let history_key = StorageShardedKey::new(address, storage_key, self.block_number);
+
+        let changeset_block_number = self
+            .tx
+            .cursor_read::<tables::StorageHistory>()?
+            .seek(history_key)?
+            .filter(|(key, _)| key.address == address && key.sharded_key.key == storage_key)
+            .and_then(|(_, list)| {
+                list.0.enable_rank().successor(self.block_number as usize).map(|i| i as u64)
+            });
+        if changeset_block_number.is_some() {
+            return Ok(changeset_block_number)
+        }
+
+        // the changes of the blocks after the checkpoint aren't indexed yet
+        let Some(checkpoint) = self.history_index_checkpoints.storage_history else {
+            return Ok(None)
+        };
+        let mut cursor = self.tx.cursor_dup_read::<tables::StorageChangeSet>()?;
+        let Some((last_key, _)) = cursor.last()? else { return Ok(None) };
+        let first_block_number = self.block_number.max(checkpoint + 1);
+        ensure_history_index_lag(checkpoint, first_block_number, last_key.block_number())?;
+        for block_number in first_block_number..=last_key.block_number() {
+            if cursor
+                .seek_by_key_subkey((block_number, address).into(), storage_key)?
+                .filter(|entry| entry.key == storage_key)
+                .is_some()
+            {
+                return Ok(Some(block_number))
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Returns an error if more than [MAX_UNINDEXED_HISTORY_BLOCKS] blocks of the range would have to
+/// be looked up in the changesets.
+pub(crate) fn ensure_history_index_lag(
+    checkpoint: BlockNumber,
+    first_block_number: BlockNumber,
+    last_block_number: BlockNumber,
+) -> Result<()> {
+    if last_block_number.saturating_sub(first_block_number) >= MAX_UNINDEXED_HISTORY_BLOCKS {
+        return Err(ProviderError::HistoryIndexLag {
+            checkpoint,
+            last_block_number,
+            max_unindexed: MAX_UNINDEXED_HISTORY_BLOCKS,
+        }
+        .into())
+    }
+    Ok(())
+}
+
+impl<'a, 'b, TX: DbTx<'a>> AccountProvider for HistoricalStateProviderRef<'a, 'b, TX> {
+    /// Get basic account information.
+    fn basic_account(&self, address: Address) -> Result<Option<Account>> {
         // if changeset of the block is present we are getting value from that changeset
-        if let Some(Some(changeset_block_number)) = changeset_block_number {
+        if let Some(changeset_block_number) = self.account_changeset_block_number(address)? {
             let account = self
                 .tx
                 .cursor_dup_read::<tables::AccountChangeSet>()?
@@ -101,20 +231,10 @@ impl<'a, 'b, TX: DbTx<'a>> StateRootProvider for HistoricalStateProviderRef<'a,
 impl<'a, 'b, TX: DbTx<'a>> StateProvider for HistoricalStateProviderRef<'a, 'b, TX> {
     /// Get storage.
     fn storage(&self, address: Address, storage_key: StorageKey) -> Result<Option<StorageValue>> {
-        // history key to search IntegerList of block changesets.
-        let history_key = StorageShardedKey::new(address, storage_key, self.block_number);
-
-        let changeset_block_number = self
-            .tx
-            .cursor_read::<tables::StorageHistory>()?
-            .seek(history_key)?
-            .filter(|(key, _)| key.address == address && key.sharded_key.key == storage_key)
-            .map(|(_, list)| {
-                list.0.enable_rank().successor(self.block_number as usize).map(|i| i as u64)
-            });
-
         // if changeset transition id is present we are getting value from changeset
-        if let Some(Some(changeset_block_number)) = changeset_block_number {
+        if let Some(changeset_block_number) =
+            self.storage_changeset_block_number(address, storage_key)?
+        {
             let storage_entry = self
                 .tx
                 .cursor_dup_read::<tables::StorageChangeSet>()?
@@ -144,12 +264,31 @@ impl<'a, 'b, TX: DbTx<'a>> StateProvider for HistoricalStateProviderRef<'a, 'b,
     }
 
     /// Get account and storage proofs.
+    ///
+    /// The changes of all blocks since the requested one are reverted on top of the hashed state,
+    /// so the cost grows with the distance to the tip.
     fn proof(
         &self,
-        _address: Address,
-        _keys: &[H256],
+        address: Address,
+        keys: &[H256],
     ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
-        Err(ProviderError::StateRootNotAvailableForHistoricalBlock.into())
+        let tip = self
+            .tx
+            .cursor_read::<tables::CanonicalHeaders>()?
+            .last()?
+            .ok_or(ProviderError::CanonicalHeader { block_number: self.block_number })?
+            .0;
+
+        let post_state = HashedPostState::from_reverts(self.tx, self.block_number..=tip)?;
+        let (account_prefix_set, storage_prefix_set) = post_state.construct_prefix_sets();
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(self.tx, &post_state);
+        let proof = Proof::new(self.tx)
+            .with_hashed_cursor_factory(&hashed_cursor_factory)
+            .with_changed_account_prefixes(account_prefix_set)
+            .with_changed_storage_prefixes(storage_prefix_set)
+            .account_proof(address, keys)
+            .map_err(|err| reth_interfaces::Error::Database(err.into()))?;
+        Ok((proof.proof, proof.storage_root, proof.storage_proofs))
     }
 }
 
@@ -159,6 +298,8 @@ pub struct HistoricalStateProvider<'a, TX: DbTx<'a>> {
     tx: TX,
     /// State at the block number is the main indexer of the state.
     block_number: BlockNumber,
+    /// The blocks covered by the history indices.
+    history_index_checkpoints: HistoryIndexCheckpoints,
     /// Phantom lifetime `'a`
     _phantom: PhantomData<&'a TX>,
 }
@@ -166,13 +307,27 @@ pub struct HistoricalStateProvider<'a, TX: DbTx<'a>> {
 impl<'a, TX: DbTx<'a>> HistoricalStateProvider<'a, TX> {
     /// Create new StateProvider from history transaction number
     pub fn new(tx: TX, block_number: BlockNumber) -> Self {
-        Self { tx, block_number, _phantom: PhantomData {} }
+        Self {
+            tx,
+            block_number,
+            history_index_checkpoints: Default::default(),
+            _phantom: PhantomData {},
+        }
+    }
+
+    /// Set the blocks covered by the history indices.
+    ///
+    /// See [HistoricalStateProviderRef::with_history_index_checkpoints].
+    pub fn with_history_index_checkpoints(mut self, checkpoints: HistoryIndexCheckpoints) -> Self {
+        self.history_index_checkpoints = checkpoints;
+        self
     }
 
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref<'b>(&'b self) -> HistoricalStateProviderRef<'a, 'b, TX> {
         HistoricalStateProviderRef::new(&self.tx, self.block_number)
+            .with_history_index_checkpoints(self.history_index_checkpoints)
     }
 }
 
@@ -181,8 +336,10 @@ delegate_provider_impls!(HistoricalStateProvider<'a, TX> where [TX: DbTx<'a>]);
 
 #[cfg(test)]
 mod tests {
+    use super::MAX_UNINDEXED_HISTORY_BLOCKS;
     use crate::{
-        AccountProvider, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
+        AccountProvider, HistoricalStateProvider, HistoricalStateProviderRef,
+        HistoryIndexCheckpoints, ProviderError, StateProvider,
     };
     use reth_db::{
         database::Database,
@@ -363,4 +520,116 @@ mod tests {
             Ok(Some(entry_plain.value))
         );
     }
+
+    #[test]
+    fn history_provider_reads_unindexed_changesets() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+
+        // only the changes up to block 7 are indexed
+        tx.put::<tables::AccountHistory>(
+            ShardedKey { key: ADDRESS, highest_block_number: u64::MAX },
+            BlockNumberList::new([3]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StorageHistory>(
+            StorageShardedKey {
+                address: ADDRESS,
+                sharded_key: ShardedKey { key: STORAGE, highest_block_number: u64::MAX },
+            },
+            BlockNumberList::new([3]).unwrap(),
+        )
+        .unwrap();
+        let checkpoints =
+            HistoryIndexCheckpoints { account_history: Some(7), storage_history: Some(7) };
+
+        let acc_plain = Account { nonce: 100, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at10 = Account { nonce: 10, balance: U256::ZERO, bytecode_hash: None };
+        let acc_at3 = Account { nonce: 3, balance: U256::ZERO, bytecode_hash: None };
+        let entry_plain = StorageEntry { key: STORAGE, value: U256::from(100) };
+        let entry_at10 = StorageEntry { key: STORAGE, value: U256::from(10) };
+        let entry_at3 = StorageEntry { key: STORAGE, value: U256::from(3) };
+
+        tx.put::<tables::AccountChangeSet>(
+            3,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at3) },
+        )
+        .unwrap();
+        tx.put::<tables::AccountChangeSet>(
+            10,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at10) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSet>((3, ADDRESS).into(), entry_at3).unwrap();
+        tx.put::<tables::StorageChangeSet>((10, ADDRESS).into(), entry_at10).unwrap();
+        tx.put::<tables::PlainAccountState>(ADDRESS, acc_plain).unwrap();
+        tx.put::<tables::PlainStorageState>(ADDRESS, entry_plain).unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+
+        // the indexed change is found in the history
+        let provider =
+            HistoricalStateProviderRef::new(&tx, 2).with_history_index_checkpoints(checkpoints);
+        assert_eq!(provider.basic_account(ADDRESS), Ok(Some(acc_at3)));
+        assert_eq!(provider.storage(ADDRESS, STORAGE), Ok(Some(entry_at3.value)));
+
+        // the unindexed change is found in the changesets
+        for block_number in [4, 8, 10] {
+            let provider = HistoricalStateProviderRef::new(&tx, block_number)
+                .with_history_index_checkpoints(checkpoints);
+            assert_eq!(provider.basic_account(ADDRESS), Ok(Some(acc_at10)));
+            assert_eq!(provider.storage(ADDRESS, STORAGE), Ok(Some(entry_at10.value)));
+        }
+
+        // without the checkpoints, the unindexed change is missed
+        let provider = HistoricalStateProviderRef::new(&tx, 4);
+        assert_eq!(provider.basic_account(ADDRESS), Ok(Some(acc_plain)));
+
+        let provider =
+            HistoricalStateProviderRef::new(&tx, 11).with_history_index_checkpoints(checkpoints);
+        assert_eq!(provider.basic_account(ADDRESS), Ok(Some(acc_plain)));
+        assert_eq!(provider.storage(ADDRESS, STORAGE), Ok(Some(entry_plain.value)));
+    }
+
+    #[test]
+    fn history_provider_bounds_unindexed_changesets() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+
+        // the history indices never ran
+        let last_block_number = MAX_UNINDEXED_HISTORY_BLOCKS + 1;
+        let account = Account { nonce: 1, balance: U256::ZERO, bytecode_hash: None };
+        tx.put::<tables::AccountChangeSet>(
+            last_block_number,
+            AccountBeforeTx { address: ADDRESS, info: Some(account) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSet>(
+            (last_block_number, ADDRESS).into(),
+            StorageEntry { key: STORAGE, value: U256::from(1) },
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        let checkpoints =
+            HistoryIndexCheckpoints { account_history: Some(0), storage_history: Some(0) };
+        let err = ProviderError::HistoryIndexLag {
+            checkpoint: 0,
+            last_block_number,
+            max_unindexed: MAX_UNINDEXED_HISTORY_BLOCKS,
+        };
+
+        let provider =
+            HistoricalStateProviderRef::new(&tx, 1).with_history_index_checkpoints(checkpoints);
+        assert_eq!(provider.basic_account(ADDRESS), Err(err.clone().into()));
+        assert_eq!(provider.storage(ADDRESS, STORAGE), Err(err.into()));
+
+        // the blocks close to the last changeset are looked up
+        let provider =
+            HistoricalStateProviderRef::new(&tx, 2).with_history_index_checkpoints(checkpoints);
+        assert_eq!(provider.basic_account(ADDRESS), Ok(Some(account)));
+        assert_eq!(provider.storage(ADDRESS, STORAGE), Ok(Some(U256::from(1))));
+    }
 }
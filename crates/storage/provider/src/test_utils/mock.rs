@@ -1,20 +1,25 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
-    AccountProvider, BlockHashProvider, BlockIdProvider, BlockProvider, EvmEnvProvider,
-    HeaderProvider, PostState, PostStateDataProvider, StateProvider, StateProviderBox,
-    StateProviderFactory, StateRootProvider, TransactionsProvider,
+    AccountHistoryProvider, AccountProvider, BlockHashProvider, BlockIdProvider, BlockProvider,
+    EvmEnvProvider, HeaderProvider, LogIndexProvider, PostState, PostStateDataProvider,
+    PruneProvider, StageCheckpointProvider, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRootProvider, TransactionsProvider,
 };
 use parking_lot::Mutex;
-use reth_interfaces::Result;
+use reth_interfaces::{provider::ProviderError, Result};
 use reth_primitives::{
     keccak256, Account, Address, Block, BlockHash, BlockId, BlockNumber, BlockNumberOrTag,
-    Bytecode, Bytes, ChainInfo, Header, Receipt, SealedBlock, StorageKey, StorageValue,
-    TransactionMeta, TransactionSigned, TxHash, TxNumber, H256, U256,
+    Bytecode, Bytes, ChainInfo, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock,
+    StorageKey, StorageValue, TransactionMeta, TransactionSigned, TxHash, TxNumber, H256, MAINNET,
+    U256,
+};
+use reth_revm_primitives::{
+    env::{fill_block_env, fill_cfg_and_block_env, fill_cfg_env},
+    primitives::{BlockEnv, CfgEnv},
 };
-use reth_revm_primitives::primitives::{BlockEnv, CfgEnv};
 use std::{
     collections::{BTreeMap, HashMap},
-    ops::RangeBounds,
+    ops::{RangeBounds, RangeInclusive},
     sync::Arc,
 };
 
@@ -257,6 +262,10 @@ impl BlockIdProvider for MockEthProvider {
             .expect("provider is empty"))
     }
 
+    fn last_block_number(&self) -> Result<BlockNumber> {
+        self.best_block_number()
+    }
+
     fn block_number(&self, hash: H256) -> Result<Option<reth_primitives::BlockNumber>> {
         let lock = self.blocks.lock();
         let num = lock.iter().find_map(|(h, b)| (*h == hash).then_some(b.number));
@@ -264,6 +273,82 @@ impl BlockIdProvider for MockEthProvider {
     }
 }
 
+impl PruneProvider for MockEthProvider {
+    fn lowest_available_block(&self, _segment: PruneSegment) -> Result<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn prune_checkpoint(&self, _segment: PruneSegment) -> Result<Option<PruneCheckpoint>> {
+        Ok(None)
+    }
+
+    fn prune(
+        &self,
+        _segment: PruneSegment,
+        to_block: BlockNumber,
+        _batch_size: usize,
+    ) -> Result<PruneCheckpoint> {
+        Ok(PruneCheckpoint { to_block, finished: true, ..Default::default() })
+    }
+}
+
+impl AccountHistoryProvider for MockEthProvider {
+    fn blocks_with_account_changes(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        Ok(vec![])
+    }
+
+    fn blocks_with_storage_changes(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        Ok(vec![])
+    }
+
+    fn account_block_changeset(
+        &self,
+        _block_number: BlockNumber,
+    ) -> Result<Vec<(Address, Option<Account>)>> {
+        Ok(vec![])
+    }
+}
+
+impl LogIndexProvider for MockEthProvider {
+    fn log_index_checkpoint(&self) -> Result<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn blocks_with_log_address(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        Ok(vec![])
+    }
+
+    fn blocks_with_log_topic(
+        &self,
+        _topic: H256,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        Ok(vec![])
+    }
+}
+
+impl StageCheckpointProvider for MockEthProvider {
+    fn stage_checkpoint(&self, _id: &str) -> Result<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn stage_checkpoints(&self) -> Result<Vec<(String, BlockNumber)>> {
+        Ok(vec![])
+    }
+}
+
 impl BlockProvider for MockEthProvider {
     fn find_block_by_hash(&self, hash: H256, _source: BlockSource) -> Result<Option<Block>> {
         self.block(hash.into())
@@ -331,42 +416,39 @@ impl StateProvider for MockEthProvider {
 }
 
 impl EvmEnvProvider for MockEthProvider {
-    fn fill_env_at(
-        &self,
-        _cfg: &mut CfgEnv,
-        _block_env: &mut BlockEnv,
-        _at: BlockId,
-    ) -> Result<()> {
-        unimplemented!()
+    fn fill_env_at(&self, cfg: &mut CfgEnv, block_env: &mut BlockEnv, at: BlockId) -> Result<()> {
+        let hash = self.block_hash_for_id(at)?.ok_or(ProviderError::HeaderNotFound)?;
+        let header = self.header(&hash)?.ok_or(ProviderError::HeaderNotFound)?;
+        self.fill_env_with_header(cfg, block_env, &header)
     }
 
     fn fill_env_with_header(
         &self,
-        _cfg: &mut CfgEnv,
-        _block_env: &mut BlockEnv,
-        _header: &Header,
+        cfg: &mut CfgEnv,
+        block_env: &mut BlockEnv,
+        header: &Header,
     ) -> Result<()> {
-        unimplemented!()
+        // the mock chain is a post-merge mainnet chain
+        fill_cfg_and_block_env(cfg, block_env, &MAINNET, header, U256::MAX);
+        Ok(())
     }
 
     fn fill_block_env_at(&self, _block_env: &mut BlockEnv, _at: BlockId) -> Result<()> {
         unimplemented!()
     }
 
-    fn fill_block_env_with_header(
-        &self,
-        _block_env: &mut BlockEnv,
-        _header: &Header,
-    ) -> Result<()> {
-        unimplemented!()
+    fn fill_block_env_with_header(&self, block_env: &mut BlockEnv, header: &Header) -> Result<()> {
+        fill_block_env(block_env, &MAINNET, header, true);
+        Ok(())
     }
 
     fn fill_cfg_env_at(&self, _cfg: &mut CfgEnv, _at: BlockId) -> Result<()> {
         unimplemented!()
     }
 
-    fn fill_cfg_env_with_header(&self, _cfg: &mut CfgEnv, _header: &Header) -> Result<()> {
-        unimplemented!()
+    fn fill_cfg_env_with_header(&self, cfg: &mut CfgEnv, header: &Header) -> Result<()> {
+        fill_cfg_env(cfg, &MAINNET, header, U256::MAX);
+        Ok(())
     }
 }
 
@@ -379,12 +461,13 @@ impl StateProviderFactory for MockEthProvider {
         todo!()
     }
 
+    // the mock has a single state, which is the state of every block
     fn history_by_block_hash(&self, _block: BlockHash) -> Result<StateProviderBox<'_>> {
-        todo!()
+        Ok(Box::new(self.clone()))
     }
 
     fn state_by_block_hash(&self, _block: BlockHash) -> Result<StateProviderBox<'_>> {
-        todo!()
+        Ok(Box::new(self.clone()))
     }
 
     fn pending(&self) -> Result<StateProviderBox<'_>> {
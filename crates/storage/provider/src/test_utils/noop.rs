@@ -1,17 +1,18 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
-    AccountProvider, BlockHashProvider, BlockIdProvider, BlockProvider, EvmEnvProvider,
-    HeaderProvider, PostState, StateProvider, StateProviderBox, StateProviderFactory,
+    AccountHistoryProvider, AccountProvider, BlockHashProvider, BlockIdProvider, BlockProvider,
+    EvmEnvProvider, HeaderProvider, LogIndexProvider, PostState, PruneProvider,
+    StageCheckpointProvider, StateProvider, StateProviderBox, StateProviderFactory,
     StateRootProvider, TransactionsProvider,
 };
 use reth_interfaces::Result;
 use reth_primitives::{
     Account, Address, Block, BlockHash, BlockId, BlockNumber, Bytecode, Bytes, ChainInfo, Header,
-    Receipt, SealedBlock, StorageKey, StorageValue, TransactionMeta, TransactionSigned, TxHash,
-    TxNumber, H256, KECCAK_EMPTY, U256,
+    PruneCheckpoint, PruneSegment, Receipt, SealedBlock, StorageKey, StorageValue, TransactionMeta,
+    TransactionSigned, TxHash, TxNumber, H256, KECCAK_EMPTY, U256,
 };
 use reth_revm_primitives::primitives::{BlockEnv, CfgEnv};
-use std::ops::RangeBounds;
+use std::ops::{RangeBounds, RangeInclusive};
 
 /// Supports various api interfaces for testing purposes.
 #[derive(Debug, Clone, Default, Copy)]
@@ -38,11 +39,91 @@ impl BlockIdProvider for NoopProvider {
         Ok(0)
     }
 
+    fn last_block_number(&self) -> Result<BlockNumber> {
+        Ok(0)
+    }
+
     fn block_number(&self, _hash: H256) -> Result<Option<BlockNumber>> {
         Ok(None)
     }
 }
 
+impl PruneProvider for NoopProvider {
+    fn lowest_available_block(&self, _segment: PruneSegment) -> Result<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn prune_checkpoint(&self, _segment: PruneSegment) -> Result<Option<PruneCheckpoint>> {
+        Ok(None)
+    }
+
+    fn prune(
+        &self,
+        _segment: PruneSegment,
+        to_block: BlockNumber,
+        _batch_size: usize,
+    ) -> Result<PruneCheckpoint> {
+        Ok(PruneCheckpoint { to_block, finished: true, ..Default::default() })
+    }
+}
+
+impl AccountHistoryProvider for NoopProvider {
+    fn blocks_with_account_changes(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        Ok(vec![])
+    }
+
+    fn blocks_with_storage_changes(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        Ok(vec![])
+    }
+
+    fn account_block_changeset(
+        &self,
+        _block_number: BlockNumber,
+    ) -> Result<Vec<(Address, Option<Account>)>> {
+        Ok(vec![])
+    }
+}
+
+impl LogIndexProvider for NoopProvider {
+    fn log_index_checkpoint(&self) -> Result<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn blocks_with_log_address(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        Ok(vec![])
+    }
+
+    fn blocks_with_log_topic(
+        &self,
+        _topic: H256,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>> {
+        Ok(vec![])
+    }
+}
+
+impl StageCheckpointProvider for NoopProvider {
+    fn stage_checkpoint(&self, _id: &str) -> Result<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn stage_checkpoints(&self) -> Result<Vec<(String, BlockNumber)>> {
+        Ok(vec![])
+    }
+}
+
 impl BlockProvider for NoopProvider {
     fn find_block_by_hash(&self, hash: H256, _source: BlockSource) -> Result<Option<Block>> {
         self.block(hash.into())
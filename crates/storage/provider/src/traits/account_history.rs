@@ -0,0 +1,35 @@
+use reth_interfaces::Result;
+use reth_primitives::{Account, Address, BlockNumber};
+use std::ops::RangeInclusive;
+
+/// Client trait for looking up the changes of accounts in the account history.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait AccountHistoryProvider: Send + Sync {
+    /// Returns the blocks in the range that changed the account, in ascending order.
+    ///
+    /// Returns an error if the changes of too many blocks of the range aren't indexed yet.
+    fn blocks_with_account_changes(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>>;
+
+    /// Returns the blocks in the range that changed the storage of the account, in ascending
+    /// order.
+    ///
+    /// The storage history is indexed per slot, so the changesets of every block in the range are
+    /// looked up instead.
+    fn blocks_with_storage_changes(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>>;
+
+    /// Returns the accounts changed by the block together with their state before the block.
+    ///
+    /// `None` if the account didn't exist before the block.
+    fn account_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Vec<(Address, Option<Account>)>>;
+}
@@ -11,6 +11,12 @@ pub trait BlockIdProvider: BlockHashProvider + Send + Sync {
     /// Returns the best block number in the chain.
     fn best_block_number(&self) -> Result<BlockNumber>;
 
+    /// Returns the number of the highest known canonical header.
+    ///
+    /// While the pipeline syncs, this is the tip it downloads headers towards, which can be ahead
+    /// of the [best block number](BlockIdProvider::best_block_number).
+    fn last_block_number(&self) -> Result<BlockNumber>;
+
     /// Converts the `BlockNumberOrTag` variants.
     fn convert_block_number(
         &self,
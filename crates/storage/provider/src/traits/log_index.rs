@@ -0,0 +1,28 @@
+use reth_interfaces::Result;
+use reth_primitives::{Address, BlockNumber, H256};
+use std::ops::RangeInclusive;
+
+/// Client trait for looking up the blocks with logs of certain addresses or topics in the
+/// optional log index.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait LogIndexProvider: Send + Sync {
+    /// Returns the highest block whose logs are indexed.
+    ///
+    /// Returns `None` if the logs aren't indexed.
+    fn log_index_checkpoint(&self) -> Result<Option<BlockNumber>>;
+
+    /// Returns the blocks in the range with logs emitted by the address, in ascending order.
+    fn blocks_with_log_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>>;
+
+    /// Returns the blocks in the range with logs that have the topic at any position, in
+    /// ascending order.
+    fn blocks_with_log_topic(
+        &self,
+        topic: H256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<BlockNumber>>;
+}
@@ -3,6 +3,9 @@
 mod account;
 pub use account::AccountProvider;
 
+mod account_history;
+pub use account_history::AccountHistoryProvider;
+
 mod block;
 pub use block::{BlockProvider, BlockSource};
 
@@ -21,6 +24,9 @@ pub use header::HeaderProvider;
 mod receipts;
 pub use receipts::ReceiptProvider;
 
+mod log_index;
+pub use log_index::LogIndexProvider;
+
 mod state;
 pub use state::{
     BlockchainTreePendingStateProvider, PostStateDataProvider, StateProvider, StateProviderBox,
@@ -30,6 +36,12 @@ pub use state::{
 mod transactions;
 pub use transactions::TransactionsProvider;
 
+mod prune;
+pub use prune::PruneProvider;
+
+mod stage_checkpoint;
+pub use stage_checkpoint::StageCheckpointProvider;
+
 mod withdrawals;
 pub use withdrawals::WithdrawalsProvider;
 
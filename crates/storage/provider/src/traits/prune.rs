@@ -0,0 +1,28 @@
+use reth_interfaces::Result;
+use reth_primitives::{BlockNumber, PruneCheckpoint, PruneSegment};
+
+/// Client trait for inspecting and pruning historical data.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait PruneProvider: Send + Sync {
+    /// Returns the lowest block for which the data of the given segment is available.
+    ///
+    /// Returns `None` if there is no data for the segment.
+    fn lowest_available_block(&self, segment: PruneSegment) -> Result<Option<BlockNumber>>;
+
+    /// Returns the checkpoint of the most recent prune run of the given segment.
+    fn prune_checkpoint(&self, segment: PruneSegment) -> Result<Option<PruneCheckpoint>>;
+
+    /// Removes the data of the given segment for all blocks below `to_block`.
+    ///
+    /// At most `batch_size` entries are deleted per database transaction and the progress is
+    /// saved with every batch, so an interrupted run continues where it stopped when pruning to
+    /// the same block again.
+    ///
+    /// Returns the checkpoint of the finished run.
+    fn prune(
+        &self,
+        segment: PruneSegment,
+        to_block: BlockNumber,
+        batch_size: usize,
+    ) -> Result<PruneCheckpoint>;
+}
@@ -0,0 +1,12 @@
+use reth_interfaces::Result;
+use reth_primitives::BlockNumber;
+
+/// Client trait for fetching the progress of the sync pipeline stages.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait StageCheckpointProvider: Send + Sync {
+    /// Returns the block number the stage with the given id has progressed to, if any.
+    fn stage_checkpoint(&self, id: &str) -> Result<Option<BlockNumber>>;
+
+    /// Returns the checkpoints of all stages, ordered by stage id.
+    fn stage_checkpoints(&self) -> Result<Vec<(String, BlockNumber)>>;
+}
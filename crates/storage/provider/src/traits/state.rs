@@ -177,7 +177,7 @@ pub trait BlockchainTreePendingStateProvider: Send + Sync {
 /// * [`PostState`] contains all changed of accounts and storage of pending chain
 /// * block hashes of pending chain and canonical blocks.
 /// * canonical fork, the block on what pending chain was forked from.
-#[auto_impl[Box,&]]
+#[auto_impl[Box,&, Arc]]
 pub trait PostStateDataProvider: Send + Sync {
     /// Return post state
     fn state(&self) -> &PostState;
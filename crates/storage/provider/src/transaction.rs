@@ -483,6 +483,45 @@ where
         Ok(())
     }
 
+    /// Unwind and clear log indices
+    pub fn unwind_log_indices(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<(), TransactionError> {
+        let (addresses, topics) = self.get_log_indices_from_receipts(range)?;
+        self.unwind_sharded_index::<tables::LogAddressIndex, _>(addresses)?;
+        self.unwind_sharded_index::<tables::LogTopicIndex, _>(topics)
+    }
+
+    /// Removes the given blocks from the shards of a log index.
+    ///
+    /// The blocks of a key are expected to be sorted, everything from the lowest one is removed.
+    fn unwind_sharded_index<T, K>(
+        &self,
+        indices: BTreeMap<K, Vec<u64>>,
+    ) -> Result<(), TransactionError>
+    where
+        T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+        K: PartialEq + Clone,
+    {
+        let mut cursor = self.cursor_write::<T>()?;
+        for (key, blocks) in indices {
+            let Some(rem_index) = blocks.first() else { continue };
+            let shard_part =
+                unwind_sharded_index_shards::<DB, T, K>(&mut cursor, key.clone(), *rem_index)?;
+
+            // check last shard_part, if present, items needs to be reinserted.
+            if !shard_part.is_empty() {
+                self.put::<T>(
+                    ShardedKey::new(key, u64::MAX),
+                    BlockNumberList::new(shard_part)
+                        .expect("There is at least one element in list and it is sorted."),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     /// Append blocks and insert its post state.
     /// This will insert block data to all related tables and will update pipeline progress.
     pub fn append_blocks_with_post_state(
@@ -1305,6 +1344,101 @@ where
         Ok(())
     }
 
+    /// Get the blocks with logs of each address and topic.
+    ///
+    /// NOTE: Get inclusive range of blocks.
+    #[allow(clippy::type_complexity)]
+    pub fn get_log_indices_from_receipts(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<(BTreeMap<Address, Vec<u64>>, BTreeMap<H256, Vec<u64>>), TransactionError> {
+        let mut addresses: BTreeMap<Address, Vec<u64>> = BTreeMap::new();
+        let mut topics: BTreeMap<H256, Vec<u64>> = BTreeMap::new();
+
+        // the blocks are walked in order, so the last block of a list is the current one if the
+        // key was already seen in the block
+        let push_block = |blocks: &mut Vec<u64>, block_number| {
+            if blocks.last() != Some(&block_number) {
+                blocks.push(block_number);
+            }
+        };
+
+        let mut receipts = self.cursor_read::<tables::Receipts>()?;
+        for entry in self.cursor_read::<tables::BlockBodyIndices>()?.walk_range(range)? {
+            let (block_number, body) = entry?;
+            for entry in receipts.walk_range(body.tx_num_range())? {
+                let (_, receipt) = entry?;
+                for log in receipt.logs {
+                    push_block(addresses.entry(log.address).or_default(), block_number);
+                    for topic in log.topics {
+                        push_block(topics.entry(topic).or_default(), block_number);
+                    }
+                }
+            }
+        }
+
+        Ok((addresses, topics))
+    }
+
+    /// Insert log index to database. Used inside IndexLogs stage
+    pub fn insert_log_index(
+        &self,
+        addresses: BTreeMap<Address, Vec<u64>>,
+        topics: BTreeMap<H256, Vec<u64>>,
+    ) -> Result<(), TransactionError> {
+        self.insert_sharded_index::<tables::LogAddressIndex, _>(addresses)?;
+        self.insert_sharded_index::<tables::LogTopicIndex, _>(topics)
+    }
+
+    /// Appends the blocks of each key to the shards of a log index.
+    fn insert_sharded_index<T, K>(
+        &self,
+        indices: BTreeMap<K, Vec<u64>>,
+    ) -> Result<(), TransactionError>
+    where
+        T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+        K: Clone,
+    {
+        for (key, mut indices) in indices {
+            // take the last shard, it's replaced by the new ones
+            let mut last_shard = Vec::new();
+            if let Some((shard_key, list)) =
+                self.cursor_read::<T>()?.seek_exact(ShardedKey::new(key.clone(), u64::MAX))?
+            {
+                self.delete::<T>(shard_key, None)?;
+                last_shard = list.iter(0).map(|i| i as u64).collect();
+            }
+            last_shard.append(&mut indices);
+
+            // chunk indices and insert them in shards of N size.
+            let mut chunks = last_shard
+                .iter()
+                .chunks(sharded_key::NUM_OF_INDICES_IN_SHARD)
+                .into_iter()
+                .map(|chunks| chunks.map(|i| *i as usize).collect::<Vec<usize>>())
+                .collect::<Vec<_>>();
+            let last_chunk = chunks.pop();
+
+            chunks.into_iter().try_for_each(|list| {
+                self.put::<T>(
+                    ShardedKey::new(
+                        key.clone(),
+                        *list.last().expect("Chuck does not return empty list") as BlockNumber,
+                    ),
+                    BlockNumberList::new(list).expect("Indices are presorted and not empty"),
+                )
+            })?;
+            // Insert last list with u64::MAX
+            if let Some(last_list) = last_chunk {
+                self.put::<T>(
+                    ShardedKey::new(key, u64::MAX),
+                    BlockNumberList::new(last_list).expect("Indices are presorted and not empty"),
+                )?
+            }
+        }
+        Ok(())
+    }
+
     /// Return full table as Vec
     pub fn table<T: Table>(&self) -> Result<Vec<KeyValue<T>>, DbError>
     where
@@ -1350,6 +1484,39 @@ fn unwind_account_history_shards<DB: Database>(
     Ok(Vec::new())
 }
 
+/// Unwind all shards of a log index key. For boundary shard, remove it from database and
+/// return last part of shard with still valid items. If all full shard were removed, return list
+/// would be empty.
+fn unwind_sharded_index_shards<DB: Database, T, K>(
+    cursor: &mut <<DB as DatabaseGAT<'_>>::TXMut as DbTxMutGAT<'_>>::CursorMut<T>,
+    key: K,
+    block_number: BlockNumber,
+) -> Result<Vec<usize>, TransactionError>
+where
+    T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+    K: PartialEq + Clone,
+{
+    let mut item = cursor.seek_exact(ShardedKey::new(key.clone(), u64::MAX))?;
+
+    while let Some((sharded_key, list)) = item {
+        // there is no more shard for the key
+        if sharded_key.key != key {
+            break
+        }
+        cursor.delete_current()?;
+        let first = list.iter(0).next().expect("List can't empty");
+        if first >= block_number as usize {
+            item = cursor.prev()?;
+            continue
+        } else if block_number <= sharded_key.highest_block_number {
+            return Ok(list.iter(0).take_while(|i| *i < block_number as usize).collect::<Vec<_>>())
+        } else {
+            return Ok(list.iter(0).collect::<Vec<_>>())
+        }
+    }
+    Ok(Vec::new())
+}
+
 /// Unwind all history shards. For boundary shard, remove it from database and
 /// return last part of shard with still valid items. If all full shard were removed, return list
 /// would be empty but this does not mean that there is none shard left but that there is no
@@ -0,0 +1,550 @@
+//! Storage for the sidecars of blob transactions.
+
+use crate::metrics::BlobStoreMetrics;
+use parking_lot::RwLock;
+use reth_primitives::{BlobTransactionSidecar, BlockNumber, Bytes, TxHash, H256};
+use reth_rlp::{Decodable, Encodable, RlpDecodable, RlpEncodable};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::debug;
+
+/// The number of blocks the sidecars of mined transactions are kept for by default.
+///
+/// This is `MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS` of the consensus layer, 4096 epochs of 32
+/// slots.
+pub const DEFAULT_BLOB_RETENTION_BLOCKS: u64 = 4096 * 32;
+
+/// A shareable store for the [BlobTransactionSidecar]s of blob transactions.
+///
+/// The pool inserts the sidecar of a blob transaction once the transaction was added and evicts
+/// it if the transaction is discarded or replaced. Once the transaction is mined, the sidecar is
+/// moved to the sidecars of its block, which are persisted in the directory of the store and
+/// pruned when the block falls out of the retention window.
+///
+/// A store created with [Default] has no directory: it keeps only the sidecars of pooled
+/// transactions, in memory, and evicts the sidecar of a mined transaction right away.
+#[derive(Debug, Clone, Default)]
+pub struct BlobStore {
+    inner: Arc<RwLock<BlobStoreInner>>,
+    metrics: Arc<BlobStoreMetrics>,
+}
+
+// === impl BlobStore ===
+
+impl BlobStore {
+    /// Opens the store that persists the sidecars of mined transactions in the given directory
+    /// for `retention_blocks` blocks.
+    ///
+    /// The directory is created if it doesn't exist, sidecars persisted by a previous run are
+    /// served again.
+    pub fn open(dir: impl Into<PathBuf>, retention_blocks: u64) -> io::Result<Self> {
+        let mined = MinedSidecars::open(dir.into(), retention_blocks)?;
+        let mut inner = BlobStoreInner::default();
+        for entry in mined.blocks.values().flatten() {
+            inner.index(entry.tx, entry.versioned_hashes.iter().copied());
+        }
+        inner.mined = Some(mined);
+
+        let store = Self { inner: Arc::new(RwLock::new(inner)), metrics: Default::default() };
+        store.update_metrics(&store.inner.read());
+        Ok(store)
+    }
+
+    /// Inserts the sidecar of the given pooled transaction.
+    pub fn insert(&self, tx: TxHash, sidecar: BlobTransactionSidecar) {
+        let mut inner = self.inner.write();
+        inner.insert(tx, sidecar);
+        self.update_metrics(&inner);
+    }
+
+    /// Returns the sidecar of the given transaction, pooled or mined.
+    pub fn get(&self, tx: &TxHash) -> Option<BlobTransactionSidecar> {
+        self.inner.read().sidecar(tx)
+    }
+
+    /// Returns the blob and its proof for each of the given versioned hashes.
+    ///
+    /// The returned list has the same length as the given hashes, with `None` for blobs that are
+    /// not stored.
+    pub fn get_by_versioned_hashes(&self, versioned_hashes: &[H256]) -> Vec<Option<BlobAndProof>> {
+        let inner = self.inner.read();
+        // the blobs of a transaction are usually requested together
+        let mut sidecars = HashMap::new();
+        versioned_hashes.iter().map(|hash| inner.blob_and_proof(hash, &mut sidecars)).collect()
+    }
+
+    /// Removes the sidecars of the given pooled transactions.
+    ///
+    /// Returns the number of removed sidecars.
+    pub fn remove(&self, transactions: impl IntoIterator<Item = TxHash>) -> usize {
+        let mut inner = self.inner.write();
+        let removed = transactions.into_iter().filter(|tx| inner.remove(tx)).count();
+        self.on_evicted(&inner, removed);
+        removed
+    }
+
+    /// Retains only the sidecars of the pooled transactions for which the predicate returns
+    /// `true`.
+    ///
+    /// Returns the number of removed sidecars.
+    pub fn retain(&self, mut f: impl FnMut(&TxHash) -> bool) -> usize {
+        let mut inner = self.inner.write();
+        if inner.sidecars.is_empty() {
+            return 0
+        }
+        let evicted = inner.sidecars.keys().filter(|tx| !f(tx)).copied().collect::<Vec<_>>();
+        for tx in &evicted {
+            inner.remove(tx);
+        }
+        self.on_evicted(&inner, evicted.len());
+        evicted.len()
+    }
+
+    /// Moves the sidecars of the mined transactions to the sidecars of their blocks and prunes
+    /// the blocks that fell out of the retention window of the new tip.
+    ///
+    /// Without a directory, the sidecars of the mined transactions are evicted.
+    pub fn on_mined(
+        &self,
+        tip: BlockNumber,
+        transactions: impl IntoIterator<Item = (BlockNumber, TxHash)>,
+    ) -> io::Result<()> {
+        let mut inner = self.inner.write();
+        let mut blocks = BTreeMap::<_, Vec<_>>::new();
+        let mut evicted = 0;
+        for (block, tx) in transactions {
+            let Some(sidecar) = inner.take(&tx) else { continue };
+            if inner.mined.is_some() {
+                blocks.entry(block).or_default().push((tx, sidecar));
+            } else {
+                inner.unindex(tx, sidecar.versioned_hashes());
+                evicted += 1;
+            }
+        }
+        self.on_evicted(&inner, evicted);
+
+        let mut result = Ok(());
+        if let Some(mut mined) = inner.mined.take() {
+            for (block, sidecars) in blocks {
+                if let Err(err) = mined.insert(block, sidecars) {
+                    result = Err(err);
+                }
+            }
+            let pruned = mined.prune(tip);
+            inner.mined = Some(mined);
+            for entry in &pruned {
+                // the transaction may have been re-injected into the pool after a reorg
+                if !inner.sidecars.contains_key(&entry.tx) {
+                    inner.unindex(entry.tx, entry.versioned_hashes.iter().copied());
+                }
+            }
+            self.metrics.pruned_sidecars.increment(pruned.len() as u64);
+        }
+        self.update_metrics(&inner);
+        result
+    }
+
+    /// Number of stored sidecars of pooled transactions.
+    pub fn len(&self) -> usize {
+        self.inner.read().sidecars.len()
+    }
+
+    /// Whether no sidecars of pooled transactions are stored.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().sidecars.is_empty()
+    }
+
+    /// The total size of the stored sidecars of pooled transactions in bytes.
+    pub fn data_size(&self) -> usize {
+        self.inner.read().data_size
+    }
+
+    fn on_evicted(&self, inner: &BlobStoreInner, evicted: usize) {
+        if evicted > 0 {
+            self.metrics.evicted_sidecars.increment(evicted as u64);
+            self.update_metrics(inner);
+        }
+    }
+
+    fn update_metrics(&self, inner: &BlobStoreInner) {
+        self.metrics.sidecars.set(inner.sidecars.len() as f64);
+        self.metrics.blobs.set(inner.versioned_hashes.len() as f64);
+        self.metrics.data_size.set(inner.data_size as f64);
+        if let Some(mined) = &inner.mined {
+            self.metrics.mined_sidecars.set(mined.transactions.len() as f64);
+            self.metrics.mined_data_size.set(mined.data_size as f64);
+        }
+    }
+}
+
+/// A blob and its KZG proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobAndProof {
+    /// The blob data.
+    pub blob: Bytes,
+    /// The KZG proof of the blob.
+    pub proof: Bytes,
+}
+
+#[derive(Debug, Default)]
+struct BlobStoreInner {
+    /// The sidecars of pooled transactions by transaction hash.
+    sidecars: HashMap<TxHash, BlobTransactionSidecar>,
+    /// The persisted sidecars of mined transactions, if the store has a directory.
+    mined: Option<MinedSidecars>,
+    /// Maps the versioned hash of a blob to the transactions that carry it and the index of the
+    /// blob within their sidecars.
+    versioned_hashes: HashMap<H256, HashSet<(TxHash, usize)>>,
+    /// The total size of the sidecars of pooled transactions.
+    data_size: usize,
+}
+
+impl BlobStoreInner {
+    fn insert(&mut self, tx: TxHash, sidecar: BlobTransactionSidecar) {
+        self.remove(&tx);
+        self.index(tx, sidecar.versioned_hashes());
+        self.data_size += sidecar.size();
+        self.sidecars.insert(tx, sidecar);
+    }
+
+    /// Removes the sidecar of the pooled transaction, returns `true` if it was stored.
+    fn remove(&mut self, tx: &TxHash) -> bool {
+        let Some(sidecar) = self.take(tx) else { return false };
+        if !self.is_mined(tx) {
+            self.unindex(*tx, sidecar.versioned_hashes());
+        }
+        true
+    }
+
+    /// Takes the sidecar of the pooled transaction without touching the index.
+    fn take(&mut self, tx: &TxHash) -> Option<BlobTransactionSidecar> {
+        let sidecar = self.sidecars.remove(tx)?;
+        self.data_size -= sidecar.size();
+        Some(sidecar)
+    }
+
+    fn is_mined(&self, tx: &TxHash) -> bool {
+        self.mined.as_ref().map_or(false, |mined| mined.transactions.contains_key(tx))
+    }
+
+    fn index(&mut self, tx: TxHash, versioned_hashes: impl Iterator<Item = H256>) {
+        for (idx, hash) in versioned_hashes.enumerate() {
+            self.versioned_hashes.entry(hash).or_default().insert((tx, idx));
+        }
+    }
+
+    fn unindex(&mut self, tx: TxHash, versioned_hashes: impl Iterator<Item = H256>) {
+        for (idx, hash) in versioned_hashes.enumerate() {
+            if let Some(txs) = self.versioned_hashes.get_mut(&hash) {
+                txs.remove(&(tx, idx));
+                if txs.is_empty() {
+                    self.versioned_hashes.remove(&hash);
+                }
+            }
+        }
+    }
+
+    /// Returns the sidecar of the pooled or mined transaction.
+    fn sidecar(&self, tx: &TxHash) -> Option<BlobTransactionSidecar> {
+        if let Some(sidecar) = self.sidecars.get(tx) {
+            return Some(sidecar.clone())
+        }
+        match self.mined.as_ref()?.read(tx) {
+            Ok(sidecar) => sidecar,
+            Err(err) => {
+                debug!(target: "txpool", ?tx, ?err, "Failed to read persisted sidecar");
+                None
+            }
+        }
+    }
+
+    fn blob_and_proof(
+        &self,
+        versioned_hash: &H256,
+        sidecars: &mut HashMap<TxHash, Option<BlobTransactionSidecar>>,
+    ) -> Option<BlobAndProof> {
+        self.versioned_hashes.get(versioned_hash)?.iter().find_map(|(tx, idx)| {
+            let sidecar = sidecars.entry(*tx).or_insert_with(|| self.sidecar(tx)).as_ref()?;
+            Some(BlobAndProof {
+                blob: sidecar.blobs.get(*idx)?.clone(),
+                proof: sidecar.proofs.get(*idx)?.clone(),
+            })
+        })
+    }
+}
+
+/// The versioned hashes of the sidecar of a mined transaction.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+struct IndexEntry {
+    tx: TxHash,
+    versioned_hashes: Vec<H256>,
+}
+
+/// The persisted sidecar of a mined transaction.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+struct StoredSidecar {
+    tx: TxHash,
+    sidecar: BlobTransactionSidecar,
+}
+
+/// The sidecars of mined transactions, persisted by block.
+///
+/// The sidecars of a block are stored in `<block>.sidecars` and their versioned hashes in
+/// `<block>.index`, which is loaded on startup. The index is written after the sidecars, so a
+/// block without an index was not written completely and is removed.
+#[derive(Debug)]
+struct MinedSidecars {
+    dir: PathBuf,
+    /// The number of blocks below the tip whose sidecars are kept.
+    retention_blocks: u64,
+    /// The index of the sidecars of each block.
+    blocks: BTreeMap<BlockNumber, Vec<IndexEntry>>,
+    /// The block of each mined transaction.
+    transactions: HashMap<TxHash, BlockNumber>,
+    /// The total size of the sidecar files.
+    data_size: u64,
+}
+
+impl MinedSidecars {
+    const SIDECARS: &'static str = "sidecars";
+    const INDEX: &'static str = "index";
+
+    fn open(dir: PathBuf, retention_blocks: u64) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let mut store = Self {
+            dir,
+            retention_blocks,
+            blocks: Default::default(),
+            transactions: Default::default(),
+            data_size: 0,
+        };
+
+        let mut sidecar_files = HashSet::new();
+        let mut index_files = Vec::new();
+        for entry in fs::read_dir(&store.dir)? {
+            let path = entry?.path();
+            let Some(block) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<BlockNumber>().ok())
+            else {
+                continue
+            };
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some(Self::SIDECARS) => {
+                    sidecar_files.insert(block);
+                }
+                Some(Self::INDEX) => index_files.push(block),
+                _ => {}
+            }
+        }
+
+        for block in index_files {
+            if !sidecar_files.remove(&block) {
+                fs::remove_file(store.path(block, Self::INDEX))?;
+                continue
+            }
+            let index =
+                Vec::<IndexEntry>::decode(&mut &fs::read(store.path(block, Self::INDEX))?[..])
+                    .map_err(invalid_data)?;
+            store.data_size += fs::metadata(store.path(block, Self::SIDECARS))?.len();
+            for entry in &index {
+                store.transactions.insert(entry.tx, block);
+            }
+            store.blocks.insert(block, index);
+        }
+        // sidecars of interrupted writes
+        for block in sidecar_files {
+            fs::remove_file(store.path(block, Self::SIDECARS))?;
+        }
+        Ok(store)
+    }
+
+    fn path(&self, block: BlockNumber, extension: &str) -> PathBuf {
+        self.dir.join(format!("{block}.{extension}"))
+    }
+
+    /// Adds the sidecars to the sidecars of the block.
+    fn insert(
+        &mut self,
+        block: BlockNumber,
+        sidecars: Vec<(TxHash, BlobTransactionSidecar)>,
+    ) -> io::Result<()> {
+        let mut stored =
+            if self.blocks.contains_key(&block) { self.read_block(block)? } else { Vec::new() };
+        for (tx, sidecar) in sidecars {
+            if !stored.iter().any(|stored| stored.tx == tx) {
+                stored.push(StoredSidecar { tx, sidecar });
+            }
+        }
+        let index = stored
+            .iter()
+            .map(|stored| IndexEntry {
+                tx: stored.tx,
+                versioned_hashes: stored.sidecar.versioned_hashes().collect(),
+            })
+            .collect::<Vec<_>>();
+
+        let previous_size = self.file_size(block);
+        write_atomic(&self.path(block, Self::SIDECARS), &stored)?;
+        write_atomic(&self.path(block, Self::INDEX), &index)?;
+
+        self.data_size = self.data_size - previous_size + self.file_size(block);
+        for entry in &index {
+            self.transactions.insert(entry.tx, block);
+        }
+        self.blocks.insert(block, index);
+        Ok(())
+    }
+
+    /// Reads the sidecar of the mined transaction.
+    fn read(&self, tx: &TxHash) -> io::Result<Option<BlobTransactionSidecar>> {
+        let Some(block) = self.transactions.get(tx) else { return Ok(None) };
+        Ok(self
+            .read_block(*block)?
+            .into_iter()
+            .find(|stored| stored.tx == *tx)
+            .map(|stored| stored.sidecar))
+    }
+
+    fn read_block(&self, block: BlockNumber) -> io::Result<Vec<StoredSidecar>> {
+        let data = fs::read(self.path(block, Self::SIDECARS))?;
+        Vec::<StoredSidecar>::decode(&mut &data[..]).map_err(invalid_data)
+    }
+
+    fn file_size(&self, block: BlockNumber) -> u64 {
+        fs::metadata(self.path(block, Self::SIDECARS)).map(|meta| meta.len()).unwrap_or_default()
+    }
+
+    /// Removes the sidecars of all blocks outside the retention window of the tip.
+    ///
+    /// Returns the index entries of the removed sidecars.
+    fn prune(&mut self, tip: BlockNumber) -> Vec<IndexEntry> {
+        let first_retained = tip.saturating_sub(self.retention_blocks) + 1;
+        let retained = self.blocks.split_off(&first_retained);
+        let pruned = std::mem::replace(&mut self.blocks, retained);
+
+        let mut entries = Vec::new();
+        for (block, index) in pruned {
+            self.data_size -= self.file_size(block);
+            // remove the index first, so a partially removed block is cleaned up on startup
+            for extension in [Self::INDEX, Self::SIDECARS] {
+                if let Err(err) = fs::remove_file(self.path(block, extension)) {
+                    debug!(target: "txpool", block, ?err, "Failed to remove pruned sidecars");
+                }
+            }
+            for entry in &index {
+                self.transactions.remove(&entry.tx);
+            }
+            entries.extend(index);
+        }
+        entries
+    }
+}
+
+/// Writes the RLP encoded items to a temporary file and moves it to the path.
+fn write_atomic<T: Encodable>(path: &Path, items: &[T]) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(reth_rlp::list_length::<T, T>(items));
+    reth_rlp::encode_list::<T, T>(items, &mut buf);
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, buf)?;
+    fs::rename(tmp, path)
+}
+
+fn invalid_data(err: reth_rlp::DecodeError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{err:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::kzg_to_versioned_hash;
+
+    fn sidecar(seed: u8) -> BlobTransactionSidecar {
+        BlobTransactionSidecar {
+            blobs: vec![Bytes::from(vec![seed; 32])],
+            commitments: vec![Bytes::from(vec![seed; 48])],
+            proofs: vec![Bytes::from(vec![seed.wrapping_add(1); 48])],
+        }
+    }
+
+    fn blob_and_proof(seed: u8) -> Option<BlobAndProof> {
+        Some(BlobAndProof {
+            blob: Bytes::from(vec![seed; 32]),
+            proof: Bytes::from(vec![seed.wrapping_add(1); 48]),
+        })
+    }
+
+    #[test]
+    fn evicts_sidecars() {
+        let store = BlobStore::default();
+        let (mined, pending) = (H256::random(), H256::random());
+        store.insert(mined, sidecar(1));
+        store.insert(pending, sidecar(2));
+        assert_eq!(store.data_size(), 2 * (32 + 48 + 48));
+
+        let hash = kzg_to_versioned_hash(&[1; 48]);
+        assert_eq!(
+            store.get_by_versioned_hashes(&[hash, H256::zero()]),
+            vec![blob_and_proof(1), None]
+        );
+
+        // without a directory the sidecars of mined transactions are not kept
+        store.on_mined(1, [(1, mined)]).unwrap();
+        assert_eq!(store.get_by_versioned_hashes(&[hash]), vec![None]);
+        assert_eq!(store.data_size(), 32 + 48 + 48);
+
+        assert_eq!(store.retain(|tx| *tx != pending), 1);
+        assert!(store.is_empty());
+        assert_eq!(store.data_size(), 0);
+    }
+
+    #[test]
+    fn shared_blobs() {
+        let store = BlobStore::default();
+        let (first, second) = (H256::random(), H256::random());
+        store.insert(first, sidecar(1));
+        store.insert(second, sidecar(1));
+
+        // the blob is served as long as one of the transactions carries it
+        let hash = kzg_to_versioned_hash(&[1; 48]);
+        assert_eq!(store.remove([first]), 1);
+        assert_eq!(store.get_by_versioned_hashes(&[hash]), vec![blob_and_proof(1)]);
+        assert_eq!(store.remove([second]), 1);
+        assert_eq!(store.get_by_versioned_hashes(&[hash]), vec![None]);
+    }
+
+    #[test]
+    fn persists_mined_sidecars() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BlobStore::open(dir.path(), 2).unwrap();
+        let (first, second, pending) = (H256::random(), H256::random(), H256::random());
+        store.insert(first, sidecar(1));
+        store.insert(second, sidecar(2));
+        store.insert(pending, sidecar(3));
+
+        store.on_mined(2, [(1, first), (2, second)]).unwrap();
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.get(&first), Some(sidecar(1)));
+
+        // mined sidecars are served after a restart, pooled sidecars are not
+        drop(store);
+        let store = BlobStore::open(dir.path(), 2).unwrap();
+        let hashes = [1, 2, 3].map(|seed| kzg_to_versioned_hash(&[seed; 48]));
+        assert_eq!(
+            store.get_by_versioned_hashes(&hashes),
+            vec![blob_and_proof(1), blob_and_proof(2), None]
+        );
+
+        // block 1 falls out of the retention window of block 3
+        store.on_mined(3, []).unwrap();
+        assert_eq!(store.get(&first), None);
+        assert_eq!(store.get_by_versioned_hashes(&hashes[..2]), vec![None, blob_and_proof(2)]);
+        assert!(!dir.path().join("1.sidecars").exists());
+        assert!(dir.path().join("2.sidecars").exists());
+    }
+}
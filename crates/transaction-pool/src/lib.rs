@@ -81,6 +81,7 @@
 //! that provides the `TransactionPool` interface.
 
 pub use crate::{
+    blobstore::{BlobAndProof, BlobStore, DEFAULT_BLOB_RETENTION_BLOCKS},
     config::PoolConfig,
     ordering::{CostOrdering, TransactionOrdering},
     traits::{
@@ -105,6 +106,7 @@ use std::{collections::HashMap, sync::Arc};
 use tokio::sync::mpsc::Receiver;
 use tracing::{instrument, trace};
 
+pub mod blobstore;
 mod config;
 pub mod error;
 mod identifier;
@@ -153,7 +155,18 @@ where
 {
     /// Create a new transaction pool instance.
     pub fn new(validator: V, ordering: T, config: PoolConfig) -> Self {
-        Self { pool: Arc::new(PoolInner::new(validator, ordering, config)) }
+        Self::with_blob_store(validator, ordering, config, Default::default())
+    }
+
+    /// Create a new transaction pool instance that keeps the sidecars of blob transactions in the
+    /// given store.
+    pub fn with_blob_store(
+        validator: V,
+        ordering: T,
+        config: PoolConfig,
+        blob_store: BlobStore,
+    ) -> Self {
+        Self { pool: Arc::new(PoolInner::new(validator, ordering, config, blob_store)) }
     }
 
     /// Returns the wrapped pool.
@@ -161,6 +174,11 @@ where
         &self.pool
     }
 
+    /// Returns the store for the sidecars of blob transactions.
+    pub fn blob_store(&self) -> &BlobStore {
+        self.inner().blob_store()
+    }
+
     /// Get the config the pool was configured with.
     pub fn config(&self) -> &PoolConfig {
         self.inner().config()
@@ -226,6 +244,16 @@ where
     ) -> Self {
         Self::new(validator, CostOrdering::default(), config)
     }
+
+    /// Returns a new [Pool] like [Pool::eth_pool] that keeps the sidecars of blob transactions
+    /// in the given store.
+    pub fn eth_pool_with_blob_store(
+        validator: EthTransactionValidator<Client, PooledTransaction>,
+        config: PoolConfig,
+        blob_store: BlobStore,
+    ) -> Self {
+        Self::with_blob_store(validator, CostOrdering::default(), config, blob_store)
+    }
 }
 
 /// implements the `TransactionPool` interface for various transaction pool API consumers.
@@ -307,6 +335,14 @@ where
         self.pooled_transactions().into_iter().take(max).collect()
     }
 
+    fn pending_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.pending_transactions()
+    }
+
+    fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.queued_transactions()
+    }
+
     fn best_transactions(
         &self,
     ) -> Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<Self::Transaction>>>> {
@@ -5,8 +5,12 @@ use crate::{
     BlockInfo, Pool, TransactionOrdering, TransactionPool, TransactionValidator,
 };
 use futures_util::{Stream, StreamExt};
-use reth_primitives::{Address, BlockHash, BlockNumberOrTag, FromRecoveredTransaction};
-use reth_provider::{BlockProvider, CanonStateNotification, PostState, StateProviderFactory};
+use reth_primitives::{
+    Address, BlockHash, BlockNumber, BlockNumberOrTag, FromRecoveredTransaction, TxHash,
+};
+use reth_provider::{
+    chain::ChainBlocks, BlockProvider, CanonStateNotification, PostState, StateProviderFactory,
+};
 use std::{
     borrow::Borrow,
     collections::HashSet,
@@ -115,7 +119,7 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
                     pending_block_base_fee,
                     changed_accounts,
                     // all transactions mined in the new chain need to be removed from the pool
-                    mined_transactions: new_mined_transactions.into_iter().collect(),
+                    mined_transactions: mined_transactions(&new_blocks),
                 };
                 pool.on_canonical_state_change(update);
 
@@ -177,7 +181,7 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
                 // check if the range of the commit is canonical
                 if first_block.parent_hash == pool_info.last_seen_block_hash {
                     let changed_accounts = changed_accounts_iter(state).collect();
-                    let mined_transactions = blocks.transactions().map(|tx| tx.hash).collect();
+                    let mined_transactions = mined_transactions(&blocks);
                     // Canonical update
                     let update = CanonicalStateUpdate {
                         hash: tip.hash,
@@ -196,6 +200,14 @@ pub async fn maintain_transaction_pool<Client, V, T, St>(
     }
 }
 
+/// Returns the hashes of all transactions of the blocks with the number of their block.
+fn mined_transactions(blocks: &ChainBlocks<'_>) -> Vec<(BlockNumber, TxHash)> {
+    blocks
+        .iter()
+        .flat_map(|(number, block)| block.body.iter().map(|tx| (*number, tx.hash)))
+        .collect()
+}
+
 /// A unique ChangedAccount identified by its address that can be used for deduplication
 #[derive(Eq)]
 struct ChangedAccountEntry(ChangedAccount);
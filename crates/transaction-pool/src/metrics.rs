@@ -1,6 +1,6 @@
 //! Transaction pool metrics.
 
-use metrics::Counter;
+use metrics::{Counter, Gauge};
 use reth_metrics_derive::Metrics;
 
 /// Transaction pool metrics
@@ -14,3 +14,23 @@ pub struct TxPoolMetrics {
     /// Number of removed transactions from the pool
     pub(crate) removed_transactions: Counter,
 }
+
+/// Blob store metrics
+#[derive(Metrics)]
+#[metrics(scope = "transaction_pool.blobstore")]
+pub struct BlobStoreMetrics {
+    /// Number of stored blob sidecars
+    pub(crate) sidecars: Gauge,
+    /// Number of stored blobs
+    pub(crate) blobs: Gauge,
+    /// Total size of all stored blob sidecars in bytes
+    pub(crate) data_size: Gauge,
+    /// Number of blob sidecars evicted because their transaction left the pool
+    pub(crate) evicted_sidecars: Counter,
+    /// Number of persisted blob sidecars of mined transactions
+    pub(crate) mined_sidecars: Gauge,
+    /// Total size of the persisted blob sidecars in bytes
+    pub(crate) mined_data_size: Gauge,
+    /// Number of persisted blob sidecars pruned after the retention window
+    pub(crate) pruned_sidecars: Counter,
+}
@@ -78,7 +78,8 @@ use crate::{
         TransactionOrigin,
     },
     validate::{TransactionValidationOutcome, ValidPoolTransaction},
-    CanonicalStateUpdate, ChangedAccount, PoolConfig, TransactionOrdering, TransactionValidator,
+    BlobStore, CanonicalStateUpdate, ChangedAccount, PoolConfig, TransactionOrdering,
+    TransactionValidator,
 };
 use best::BestTransactions;
 pub use events::TransactionEvent;
@@ -119,6 +120,8 @@ pub struct PoolInner<V: TransactionValidator, T: TransactionOrdering> {
     pending_transaction_listener: Mutex<Vec<mpsc::Sender<TxHash>>>,
     /// Listeners for new transactions added to the pool.
     transaction_listener: Mutex<Vec<mpsc::Sender<NewTransactionEvent<T::Transaction>>>>,
+    /// Sidecars of blob transactions.
+    blob_store: BlobStore,
 }
 
 // === impl PoolInner ===
@@ -129,7 +132,12 @@ where
     T: TransactionOrdering<Transaction = <V as TransactionValidator>::Transaction>,
 {
     /// Create a new transaction pool instance.
-    pub(crate) fn new(validator: V, ordering: T, config: PoolConfig) -> Self {
+    pub(crate) fn new(
+        validator: V,
+        ordering: T,
+        config: PoolConfig,
+        blob_store: BlobStore,
+    ) -> Self {
         Self {
             identifiers: Default::default(),
             validator,
@@ -137,10 +145,16 @@ where
             pool: RwLock::new(TxPool::new(ordering, config.clone())),
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
+            blob_store,
             config,
         }
     }
 
+    /// Returns the store for the sidecars of blob transactions.
+    pub(crate) fn blob_store(&self) -> &BlobStore {
+        &self.blob_store
+    }
+
     /// Returns stats about the size of the pool.
     pub(crate) fn size(&self) -> PoolSize {
         self.pool.read().size()
@@ -232,9 +246,16 @@ where
         };
         let outcome = self.pool.write().on_canonical_state_change(
             block_info,
-            mined_transactions,
+            mined_transactions.iter().map(|(_, hash)| *hash).collect(),
             changed_senders,
         );
+
+        // sidecars of mined transactions are kept for the retention window
+        if let Err(err) = self.blob_store.on_mined(number, mined_transactions) {
+            warn!(target: "txpool", ?err, "Failed to persist the blob sidecars of mined transactions");
+        }
+        self.evict_blob_sidecars();
+
         self.notify_on_new_state(outcome);
     }
 
@@ -252,6 +273,7 @@ where
                 let sender_id = self.get_sender_id(transaction.sender());
                 let transaction_id = TransactionId::new(sender_id, transaction.nonce());
                 let encoded_length = transaction.encoded_length();
+                let blob_sidecar = transaction.blob_sidecar().cloned();
 
                 let tx = ValidPoolTransaction {
                     cost: transaction.cost(),
@@ -266,6 +288,10 @@ where
                 let added = self.pool.write().add_transaction(tx, balance, state_nonce)?;
                 let hash = *added.hash();
 
+                if let Some(sidecar) = blob_sidecar {
+                    self.blob_store.insert(hash, sidecar);
+                }
+
                 // Notify about new pending transactions
                 if let Some(pending_hash) = added.as_pending() {
                     self.on_new_pending_transaction(pending_hash);
@@ -305,6 +331,9 @@ where
         let discarded =
             if added.iter().any(Result::is_ok) { self.discard_worst() } else { Default::default() };
 
+        // drop the sidecars of replaced or discarded transactions
+        self.evict_blob_sidecars();
+
         if discarded.is_empty() {
             return added
         }
@@ -391,6 +420,16 @@ where
         }
     }
 
+    /// Returns all transactions of the pending sub-pool.
+    pub(crate) fn pending_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.pool.read().pending_transactions()
+    }
+
+    /// Returns all transactions of the basefee and queued sub-pools.
+    pub(crate) fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.pool.read().queued_transactions()
+    }
+
     /// Returns an iterator that yields transactions that are ready to be included in the block.
     pub(crate) fn best_transactions(&self) -> BestTransactions<T> {
         self.pool.read().best_transactions()
@@ -406,10 +445,25 @@ where
         let mut listener = self.event_listener.write();
 
         removed.iter().for_each(|tx| listener.discarded(tx.hash()));
+        drop(listener);
+
+        self.blob_store.remove(removed.iter().map(|tx| *tx.hash()));
 
         removed
     }
 
+    /// Evicts the sidecars of all blob transactions that are no longer in the pool.
+    ///
+    /// Transactions can leave the pool without an event, e.g. if they were replaced, so this
+    /// compares the store against the pool's content.
+    fn evict_blob_sidecars(&self) {
+        if self.blob_store.is_empty() {
+            return
+        }
+        let pool = self.pool.read();
+        self.blob_store.retain(|tx| pool.contains(tx));
+    }
+
     /// Removes all transactions that are present in the pool.
     pub(crate) fn retain_unknown(&self, hashes: &mut Vec<TxHash>) {
         let pool = self.pool.read();
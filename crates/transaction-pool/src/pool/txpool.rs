@@ -181,6 +181,16 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.pending_pool.best()
     }
 
+    /// Returns all transactions of the pending sub-pool, sorted by sender and nonce.
+    pub(crate) fn pending_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.all_transactions.subpool_transactions_iter(|subpool| subpool.is_pending()).collect()
+    }
+
+    /// Returns all transactions of the basefee and queued sub-pools, sorted by sender and nonce.
+    pub(crate) fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.all_transactions.subpool_transactions_iter(|subpool| !subpool.is_pending()).collect()
+    }
+
     /// Returns `true` if the transaction with the given hash is already included in this pool.
     pub(crate) fn contains(&self, tx_hash: &TxHash) -> bool {
         self.all_transactions.contains(tx_hash)
@@ -619,6 +629,18 @@ impl<T: PoolTransaction> AllTransactions<T> {
         self.by_hash.contains_key(tx_hash)
     }
 
+    /// Returns an iterator over the transactions in the sub-pools that match the predicate,
+    /// sorted by sender and nonce.
+    pub(crate) fn subpool_transactions_iter(
+        &self,
+        predicate: impl Fn(SubPool) -> bool + 'static,
+    ) -> impl Iterator<Item = Arc<ValidPoolTransaction<T>>> + '_ {
+        self.txs
+            .values()
+            .filter(move |tx| predicate(tx.subpool))
+            .map(|tx| Arc::clone(&tx.transaction))
+    }
+
     /// Returns the internal transaction with additional metadata
     #[cfg(test)]
     pub(crate) fn get(&self, id: &TransactionId) -> Option<&PoolInternalTransaction<T>> {
@@ -1360,6 +1382,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pending_and_queued_transactions() {
+        let on_chain_balance = U256::MAX;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let tx = MockTransaction::eip1559().inc_price().inc_limit();
+        let pending = f.validated(tx.clone());
+        // the nonce after the next one leaves a gap
+        let queued = f.validated(tx.next().next());
+        pool.add_transaction(pending.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        pool.add_transaction(queued.clone(), on_chain_balance, on_chain_nonce).unwrap();
+
+        let pending_hashes =
+            pool.pending_transactions().iter().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(pending_hashes, vec![*pending.hash()]);
+        let queued_hashes =
+            pool.queued_transactions().iter().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(queued_hashes, vec![*queued.hash()]);
+    }
+
     #[test]
     fn insert_replace() {
         let on_chain_balance = U256::ZERO;
@@ -12,9 +12,10 @@ use rand::{
     prelude::Distribution,
 };
 use reth_primitives::{
-    constants::MIN_PROTOCOL_BASE_FEE, hex, Address, FromRecoveredTransaction,
-    IntoRecoveredTransaction, Signature, Transaction, TransactionKind, TransactionSigned,
-    TransactionSignedEcRecovered, TxEip1559, TxHash, TxLegacy, TxType, H256, U128, U256,
+    constants::MIN_PROTOCOL_BASE_FEE, hex, Address, BlobTransactionSidecar,
+    FromRecoveredTransaction, IntoRecoveredTransaction, Signature, Transaction, TransactionKind,
+    TransactionSigned, TransactionSignedEcRecovered, TxEip1559, TxHash, TxLegacy, TxType, H256,
+    U128, U256,
 };
 use std::{ops::Range, sync::Arc, time::Instant};
 
@@ -378,6 +379,10 @@ impl PoolTransaction for MockTransaction {
     fn chain_id(&self) -> Option<u64> {
         Some(1)
     }
+
+    fn blob_sidecar(&self) -> Option<&BlobTransactionSidecar> {
+        None
+    }
 }
 
 impl FromRecoveredTransaction for MockTransaction {
@@ -1,7 +1,8 @@
 use crate::{error::PoolResult, pool::state::SubPool, validate::ValidPoolTransaction};
 use reth_primitives::{
-    Address, FromRecoveredTransaction, IntoRecoveredTransaction, PeerId, Transaction,
-    TransactionKind, TransactionSignedEcRecovered, TxHash, EIP1559_TX_TYPE_ID, H256, U256,
+    Address, BlobTransactionSidecar, BlockNumber, FromRecoveredTransaction,
+    IntoRecoveredTransaction, PeerId, Transaction, TransactionKind, TransactionSignedEcRecovered,
+    TxHash, EIP1559_TX_TYPE_ID, H256, U256,
 };
 use reth_rlp::Encodable;
 use std::{collections::HashMap, fmt, sync::Arc};
@@ -115,6 +116,18 @@ pub trait TransactionPool: Send + Sync + Clone {
         max: usize,
     ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns all transactions that are ready to be included in the next block, the transactions
+    /// of the pending sub-pool.
+    ///
+    /// Consumer: RPC
+    fn pending_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
+    /// Returns all transactions that can't be included in the next block yet, because of a nonce
+    /// gap, insufficient funds or a fee cap below the base fee.
+    ///
+    /// Consumer: RPC
+    fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
     /// Returns an iterator that yields transactions that are ready for block production.
     ///
     /// Consumer: Block production
@@ -265,8 +278,8 @@ pub struct CanonicalStateUpdate {
     pub pending_block_base_fee: u128,
     /// A set of changed accounts across a range of blocks.
     pub changed_accounts: Vec<ChangedAccount>,
-    /// All mined transactions in the block range.
-    pub mined_transactions: Vec<H256>,
+    /// All mined transactions in the block range, with the number of their block.
+    pub mined_transactions: Vec<(BlockNumber, TxHash)>,
 }
 
 /// Represents a changed account
@@ -365,6 +378,12 @@ pub trait PoolTransaction:
 
     /// Returns chain_id
     fn chain_id(&self) -> Option<u64>;
+
+    /// Returns the sidecar of a blob transaction.
+    ///
+    /// The pool keeps the sidecar in its [BlobStore](crate::BlobStore) while the transaction is
+    /// in the pool and, if the store has a directory, for the retention window once it was mined.
+    fn blob_sidecar(&self) -> Option<&BlobTransactionSidecar>;
 }
 
 /// The default [PoolTransaction] for the [Pool](crate::Pool).
@@ -381,6 +400,9 @@ pub struct PooledTransaction {
 
     /// This is `priority + basefee`for EIP-1559 and `gasPrice` for legacy transactions.
     pub(crate) effective_gas_price: u128,
+
+    /// The blobs, commitments and proofs of a blob transaction.
+    pub(crate) blob_sidecar: Option<BlobTransactionSidecar>,
 }
 
 impl PooledTransaction {
@@ -388,6 +410,12 @@ impl PooledTransaction {
     pub fn transaction(&self) -> &TransactionSignedEcRecovered {
         &self.transaction
     }
+
+    /// Attaches the sidecar of a blob transaction.
+    pub fn with_blob_sidecar(mut self, sidecar: BlobTransactionSidecar) -> Self {
+        self.blob_sidecar = Some(sidecar);
+        self
+    }
 }
 
 impl PoolTransaction for PooledTransaction {
@@ -457,7 +485,8 @@ impl PoolTransaction for PooledTransaction {
 
     /// Returns a measurement of the heap usage of this type and all its internals.
     fn size(&self) -> usize {
-        self.transaction.transaction.input().len()
+        self.transaction.transaction.input().len() +
+            self.blob_sidecar.as_ref().map_or(0, BlobTransactionSidecar::size)
     }
 
     /// Returns the transaction type
@@ -474,6 +503,11 @@ impl PoolTransaction for PooledTransaction {
     fn chain_id(&self) -> Option<u64> {
         self.transaction.chain_id()
     }
+
+    /// Returns the sidecar of a blob transaction
+    fn blob_sidecar(&self) -> Option<&BlobTransactionSidecar> {
+        self.blob_sidecar.as_ref()
+    }
 }
 
 impl FromRecoveredTransaction for PooledTransaction {
@@ -497,7 +531,7 @@ impl FromRecoveredTransaction for PooledTransaction {
             }
         };
 
-        PooledTransaction { transaction: tx, cost, effective_gas_price }
+        PooledTransaction { transaction: tx, cost, effective_gas_price, blob_sidecar: None }
     }
 }
 
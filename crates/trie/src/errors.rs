@@ -27,3 +27,23 @@ pub enum StorageRootError {
     #[error(transparent)]
     DB(#[from] reth_db::Error),
 }
+
+/// Proof error.
+#[derive(Error, PartialEq, Eq, Clone, Debug)]
+pub enum ProofError {
+    /// Internal database error.
+    #[error(transparent)]
+    DB(#[from] reth_db::Error),
+    /// Storage root error.
+    #[error(transparent)]
+    StorageRootError(#[from] StorageRootError),
+}
+
+impl From<ProofError> for reth_db::Error {
+    fn from(err: ProofError) -> Self {
+        match err {
+            ProofError::DB(err) => err,
+            ProofError::StorageRootError(StorageRootError::DB(err)) => err,
+        }
+    }
+}
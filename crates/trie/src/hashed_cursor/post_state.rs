@@ -2,11 +2,15 @@ use super::{HashedAccountCursor, HashedCursorFactory, HashedStorageCursor};
 use crate::prefix_set::PrefixSet;
 use reth_db::{
     cursor::{DbCursorRO, DbDupCursorRO},
+    models::{AccountBeforeTx, BlockNumberAddress},
     tables,
     transaction::{DbTx, DbTxGAT},
 };
-use reth_primitives::{trie::Nibbles, Account, StorageEntry, H256, U256};
-use std::collections::{BTreeMap, HashMap};
+use reth_primitives::{keccak256, trie::Nibbles, Account, BlockNumber, StorageEntry, H256, U256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeInclusive,
+};
 
 /// The post state account storage with hashed slots.
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
@@ -27,6 +31,39 @@ pub struct HashedPostState {
 }
 
 impl HashedPostState {
+    /// Load the state that reverts the changes of the given block range.
+    ///
+    /// Applied on top of the latest state, it yields the state before the first block of the
+    /// range. Cleared slots have zero values.
+    pub fn from_reverts<'a, TX: DbTx<'a>>(
+        tx: &TX,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, reth_db::Error> {
+        let mut post_state = Self::default();
+
+        // The first change of an account within the range holds its value before the range.
+        let mut account_cursor = tx.cursor_read::<tables::AccountChangeSet>()?;
+        for account_entry in account_cursor.walk_range(range.clone())? {
+            let (_, AccountBeforeTx { address, info }) = account_entry?;
+            post_state.accounts.entry(keccak256(address)).or_insert(info);
+        }
+
+        // The same applies to the storage slots.
+        let mut storage_cursor = tx.cursor_dup_read::<tables::StorageChangeSet>()?;
+        for storage_entry in storage_cursor.walk_range(BlockNumberAddress::range(range))? {
+            let (BlockNumberAddress((_, address)), StorageEntry { key, value }) = storage_entry?;
+            post_state
+                .storages
+                .entry(keccak256(address))
+                .or_default()
+                .storage
+                .entry(keccak256(key))
+                .or_insert(value);
+        }
+
+        Ok(post_state)
+    }
+
     /// Construct prefix sets from hashed post state.
     pub fn construct_prefix_sets(&self) -> (PrefixSet, HashMap<H256, PrefixSet>) {
         // Initialize prefix sets.
@@ -328,6 +365,7 @@ mod tests {
     use super::*;
     use proptest::prelude::*;
     use reth_db::{database::Database, mdbx::test_utils::create_test_rw_db, transaction::DbTxMut};
+    use reth_primitives::Address;
 
     fn assert_account_cursor_order<'a, 'b>(
         factory: &'a impl HashedCursorFactory<'b>,
@@ -371,6 +409,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reverts_keep_first_change() {
+        let address = Address::random();
+        let slot = H256::from_low_u64_be(1);
+        let account = |nonce| Account { nonce, ..Default::default() };
+
+        let db = create_test_rw_db();
+        db.update(|tx| {
+            for block in 1..=3 {
+                let info = if block == 1 { None } else { Some(account(block)) };
+                tx.put::<tables::AccountChangeSet>(block, AccountBeforeTx { address, info })
+                    .unwrap();
+                tx.put::<tables::StorageChangeSet>(
+                    (block, address).into(),
+                    StorageEntry { key: slot, value: U256::from(block) },
+                )
+                .unwrap();
+            }
+        })
+        .unwrap();
+
+        let tx = db.tx().unwrap();
+        let hashed_address = keccak256(address);
+
+        let post_state = HashedPostState::from_reverts(&tx, 2..=3).unwrap();
+        assert_eq!(post_state.accounts, BTreeMap::from([(hashed_address, Some(account(2)))]));
+        assert_eq!(
+            post_state.storages[&hashed_address].storage,
+            BTreeMap::from([(keccak256(slot), U256::from(2))])
+        );
+
+        // the account didn't exist before the first block
+        let post_state = HashedPostState::from_reverts(&tx, 1..=3).unwrap();
+        assert_eq!(post_state.accounts, BTreeMap::from([(hashed_address, None)]));
+    }
+
     #[test]
     fn post_state_only_accounts() {
         let accounts =
@@ -26,12 +26,16 @@ pub mod hashed_cursor;
 pub mod walker;
 
 mod errors;
-pub use errors::{StateRootError, StorageRootError};
+pub use errors::{ProofError, StateRootError, StorageRootError};
 
 /// The implementation of the Merkle Patricia Trie.
 mod trie;
 pub use trie::{StateRoot, StorageRoot};
 
+/// The implementation of the merkle proof generation.
+mod proof;
+pub use proof::{AccountProof, Proof};
+
 /// Buffer for trie updates.
 pub mod updates;
 
@@ -0,0 +1,327 @@
+use crate::{
+    account::EthAccount,
+    hashed_cursor::{HashedAccountCursor, HashedCursorFactory, HashedStorageCursor},
+    prefix_set::PrefixSet,
+    trie_cursor::{AccountTrieCursor, StorageTrieCursor},
+    walker::TrieWalker,
+    ProofError, StorageRoot,
+};
+use reth_db::{tables, transaction::DbTx};
+use reth_primitives::{
+    keccak256,
+    proofs::EMPTY_ROOT,
+    trie::{HashBuilder, Nibbles},
+    Address, Bytes, StorageEntry, H256, U256,
+};
+use reth_rlp::Encodable;
+use std::collections::{BTreeMap, HashMap};
+
+/// The merkle proofs of an account and a set of its storage slots.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccountProof {
+    /// The account trie nodes on the path from the root to the account.
+    pub proof: Vec<Bytes>,
+    /// The storage root of the account, [EMPTY_ROOT] if the account doesn't exist.
+    pub storage_root: H256,
+    /// The storage trie nodes on the paths to the requested slots, in the order of the request.
+    pub storage_proofs: Vec<Vec<Bytes>>,
+}
+
+/// Proof is used to generate the merkle proofs of an account and its storage slots.
+///
+/// It follows the same algorithm as [StateRoot](crate::StateRoot), but the target keys are added
+/// to the prefix sets, so the walker descends along their paths instead of skipping the nodes
+/// with the stored hashes, and the hash builder retains the nodes on these paths.
+pub struct Proof<'a, 'b, TX, H> {
+    /// A reference to the database transaction.
+    pub tx: &'a TX,
+    /// The factory for hashed cursors.
+    pub hashed_cursor_factory: &'b H,
+    /// A set of account prefixes that have changed.
+    pub changed_account_prefixes: PrefixSet,
+    /// A map containing storage changes with the hashed address as key and a set of storage key
+    /// prefixes as the value.
+    pub changed_storage_prefixes: HashMap<H256, PrefixSet>,
+}
+
+impl<'a, 'tx, TX> Proof<'a, 'a, TX, TX>
+where
+    TX: DbTx<'tx> + HashedCursorFactory<'a>,
+{
+    /// Create a new [Proof] instance.
+    pub fn new(tx: &'a TX) -> Self {
+        Self {
+            tx,
+            hashed_cursor_factory: tx,
+            changed_account_prefixes: PrefixSet::default(),
+            changed_storage_prefixes: HashMap::default(),
+        }
+    }
+}
+
+impl<'a, 'b, TX, H> Proof<'a, 'b, TX, H> {
+    /// Set the changed account prefixes.
+    ///
+    /// Required if the hashed cursor factory overlays state that isn't reflected by the trie
+    /// tables.
+    pub fn with_changed_account_prefixes(mut self, prefixes: PrefixSet) -> Self {
+        self.changed_account_prefixes = prefixes;
+        self
+    }
+
+    /// Set the changed storage prefixes.
+    pub fn with_changed_storage_prefixes(mut self, prefixes: HashMap<H256, PrefixSet>) -> Self {
+        self.changed_storage_prefixes = prefixes;
+        self
+    }
+
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<'c, HF>(
+        self,
+        hashed_cursor_factory: &'c HF,
+    ) -> Proof<'a, 'c, TX, HF> {
+        Proof {
+            tx: self.tx,
+            hashed_cursor_factory,
+            changed_account_prefixes: self.changed_account_prefixes,
+            changed_storage_prefixes: self.changed_storage_prefixes,
+        }
+    }
+}
+
+impl<'a, 'b, 'tx, TX, H> Proof<'a, 'b, TX, H>
+where
+    TX: DbTx<'tx>,
+    H: HashedCursorFactory<'b>,
+{
+    /// Generates the merkle proofs of the account and the given storage slots.
+    ///
+    /// The proofs of accounts and slots that don't exist prove their absence.
+    pub fn account_proof(
+        &self,
+        address: Address,
+        slots: &[H256],
+    ) -> Result<AccountProof, ProofError> {
+        let target_hashed_address = keccak256(address);
+        let target_nibbles = Nibbles::unpack(target_hashed_address);
+        let mut account_proof = AccountProof {
+            storage_root: EMPTY_ROOT,
+            storage_proofs: vec![Vec::new(); slots.len()],
+            ..Default::default()
+        };
+
+        let mut hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let mut trie_cursor =
+            AccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
+
+        let mut prefix_set = self.changed_account_prefixes.clone();
+        prefix_set.insert(target_nibbles.clone());
+        let mut walker = TrieWalker::new(&mut trie_cursor, prefix_set);
+
+        let mut hash_builder =
+            HashBuilder::default().with_proof_retainer(vec![target_nibbles.clone()]);
+
+        let mut account_rlp = Vec::with_capacity(128);
+        while let Some(key) = walker.key() {
+            if walker.can_skip_current_node {
+                let value = walker.hash().unwrap();
+                let is_in_db_trie = walker.children_are_in_trie();
+                hash_builder.add_branch(key.clone(), value, is_in_db_trie);
+            }
+
+            let seek_key = match walker.next_unprocessed_key() {
+                Some(key) => key,
+                None => break, // no more keys
+            };
+
+            let next_key = walker.advance()?;
+            let mut next_account_entry = hashed_account_cursor.seek(seek_key)?;
+            while let Some((hashed_address, account)) = next_account_entry {
+                let account_nibbles = Nibbles::unpack(hashed_address);
+
+                if let Some(ref key) = next_key {
+                    if key < &account_nibbles {
+                        break
+                    }
+                }
+
+                let storage_root = if hashed_address == target_hashed_address {
+                    let (storage_root, storage_proofs) =
+                        self.storage_root_with_proofs(hashed_address, slots)?;
+                    account_proof.storage_root = storage_root;
+                    account_proof.storage_proofs = storage_proofs;
+                    storage_root
+                } else {
+                    self.storage_root(hashed_address)?
+                };
+
+                let account = EthAccount::from(account).with_storage_root(storage_root);
+
+                account_rlp.clear();
+                account.encode(&mut &mut account_rlp);
+
+                hash_builder.add_leaf(account_nibbles, &account_rlp);
+
+                next_account_entry = hashed_account_cursor.next()?;
+            }
+        }
+
+        let _ = hash_builder.root();
+
+        account_proof.proof = hash_builder.take_proofs().into_values().collect();
+        Ok(account_proof)
+    }
+
+    /// Computes the storage root of the account.
+    fn storage_root(&self, hashed_address: H256) -> Result<H256, ProofError> {
+        let root = StorageRoot::new_hashed_with_factory(
+            self.tx,
+            self.hashed_cursor_factory,
+            hashed_address,
+        )
+        .with_changed_prefixes(
+            self.changed_storage_prefixes.get(&hashed_address).cloned().unwrap_or_default(),
+        )
+        .root()?;
+        Ok(root)
+    }
+
+    /// Computes the storage root of the account and the proofs of the given slots.
+    fn storage_root_with_proofs(
+        &self,
+        hashed_address: H256,
+        slots: &[H256],
+    ) -> Result<(H256, Vec<Vec<Bytes>>), ProofError> {
+        let mut hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
+
+        // the proofs of all slots are empty if there's no storage
+        if hashed_storage_cursor.is_empty(hashed_address)? {
+            return Ok((EMPTY_ROOT, vec![Vec::new(); slots.len()]))
+        }
+
+        let target_nibbles =
+            slots.iter().map(|slot| Nibbles::unpack(keccak256(slot))).collect::<Vec<_>>();
+
+        let mut trie_cursor = StorageTrieCursor::new(
+            self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
+            hashed_address,
+        );
+
+        let mut prefix_set =
+            self.changed_storage_prefixes.get(&hashed_address).cloned().unwrap_or_default();
+        for target in &target_nibbles {
+            prefix_set.insert(target.clone());
+        }
+        let mut walker = TrieWalker::new(&mut trie_cursor, prefix_set);
+
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(target_nibbles.clone());
+
+        while let Some(key) = walker.key() {
+            if walker.can_skip_current_node {
+                hash_builder.add_branch(key, walker.hash().unwrap(), walker.children_are_in_trie());
+            }
+
+            let seek_key = match walker.next_unprocessed_key() {
+                Some(key) => key,
+                None => break, // no more keys
+            };
+
+            let next_key = walker.advance()?;
+            let mut storage = hashed_storage_cursor.seek(hashed_address, seek_key)?;
+            while let Some(StorageEntry { key: hashed_key, value }) = storage {
+                let storage_key_nibbles = Nibbles::unpack(hashed_key);
+                if let Some(ref key) = next_key {
+                    if key < &storage_key_nibbles {
+                        break
+                    }
+                }
+                // zero slots are not part of the trie, the post state marks cleared slots with them
+                if value != U256::ZERO {
+                    hash_builder.add_leaf(
+                        storage_key_nibbles,
+                        reth_rlp::encode_fixed_size(&value).as_ref(),
+                    );
+                }
+                storage = hashed_storage_cursor.next()?;
+            }
+        }
+
+        let root = hash_builder.root();
+
+        let proofs = hash_builder.take_proofs();
+        let storage_proofs =
+            target_nibbles.iter().map(|target| proof_of(&proofs, target)).collect();
+
+        Ok((root, storage_proofs))
+    }
+}
+
+/// Returns the retained nodes on the path to the target, ordered from the root.
+fn proof_of(proofs: &BTreeMap<Nibbles, Bytes>, target: &Nibbles) -> Vec<Bytes> {
+    proofs
+        .iter()
+        .filter(|(path, _)| target.has_prefix(path))
+        .map(|(_, node)| node.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StateRoot;
+    use reth_db::{mdbx::test_utils::create_test_rw_db, transaction::DbTxMut};
+    use reth_primitives::Account;
+    use reth_provider::Transaction;
+    use std::ops::{Deref, DerefMut};
+
+    /// Asserts that the proof starts at the root and each node is referenced by its parent.
+    fn assert_proof(root: H256, proof: &[Bytes]) {
+        assert_eq!(keccak256(&proof[0]), root);
+        for pair in proof.windows(2) {
+            let child_hash = keccak256(&pair[1]);
+            assert!(pair[0].windows(32).any(|window| window == child_hash.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn account_and_storage_proofs() {
+        let db = create_test_rw_db();
+        let mut tx = Transaction::new(db.as_ref()).unwrap();
+
+        let address = Address::from_low_u64_be(7);
+        let slots = (1..=50).map(H256::from_low_u64_be).collect::<Vec<_>>();
+        for i in 1..=100 {
+            let account = Account { nonce: i, ..Default::default() };
+            tx.put::<tables::HashedAccount>(keccak256(Address::from_low_u64_be(i)), account)
+                .unwrap();
+        }
+        for (i, slot) in slots.iter().enumerate() {
+            tx.put::<tables::HashedStorage>(
+                keccak256(address),
+                StorageEntry { key: keccak256(slot), value: U256::from(i + 1) },
+            )
+            .unwrap();
+        }
+
+        let (root, updates) = StateRoot::new(tx.deref_mut()).root_with_updates().unwrap();
+        updates.flush(tx.deref_mut()).unwrap();
+
+        let missing_slot = H256::from_low_u64_be(100);
+        let proof = Proof::new(tx.deref())
+            .account_proof(address, &[slots[0], slots[49], missing_slot])
+            .unwrap();
+        assert_proof(root, &proof.proof);
+
+        let storage_root = StorageRoot::new(tx.deref(), address).root().unwrap();
+        assert_eq!(proof.storage_root, storage_root);
+        assert_eq!(proof.storage_proofs.len(), 3);
+        for storage_proof in &proof.storage_proofs {
+            assert_proof(storage_root, storage_proof);
+        }
+
+        // the proof of a missing account ends at the node where its path diverges
+        let proof = Proof::new(tx.deref()).account_proof(Address::random(), &[]).unwrap();
+        assert_proof(root, &proof.proof);
+        assert_eq!(proof.storage_root, EMPTY_ROOT);
+    }
+}
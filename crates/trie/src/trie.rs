@@ -13,7 +13,7 @@ use reth_primitives::{
     keccak256,
     proofs::EMPTY_ROOT,
     trie::{HashBuilder, Nibbles},
-    Address, BlockNumber, StorageEntry, H256,
+    Address, BlockNumber, StorageEntry, H256, U256,
 };
 use reth_rlp::Encodable;
 use std::{collections::HashMap, ops::RangeInclusive};
@@ -470,8 +470,14 @@ where
                         break
                     }
                 }
-                hash_builder
-                    .add_leaf(storage_key_nibbles, reth_rlp::encode_fixed_size(&value).as_ref());
+                // Slots set to zero are deleted from the trie. The database never contains them,
+                // but the post state of executed blocks marks cleared slots with zero values.
+                if value != U256::ZERO {
+                    hash_builder.add_leaf(
+                        storage_key_nibbles,
+                        reth_rlp::encode_fixed_size(&value).as_ref(),
+                    );
+                }
                 storage = hashed_storage_cursor.next()?;
             }
         }